@@ -1,42 +1,96 @@
+use crate::decoder::Decoder;
 use crate::error::ClientError;
-use crate::extensions::reqwest::{to_header_map, ResponseExt};
-use crate::Headers;
+use crate::extensions::http::{to_header_map, RequestBuilderExt, ResponseExt};
+use crate::meta::{LastResponseMeta, ResponseMeta};
+use crate::retry::Retry;
+use crate::{ClientConfig, Headers, HttpClient, RedactedHeaders};
 use algonaut_core::{Address, Round};
 use algonaut_model::algod::v2::{
-    Account, ApiCompiledTeal, Application, Asset, Block, Catchup, DryrunRequest, DryrunResponse,
-    GenesisBlock, KeyRegistration, NodeStatus, PendingTransaction, PendingTransactions, Supply,
-    TransactionParams, TransactionResponse, Version,
+    Account, ApiCompiledTeal, ApiSpec, Application, Asset, Block, BlockHashResponse, Catchup,
+    DryrunRequest, DryrunResponse, GenesisBlock, KeyRegistration, NodeStatus, PendingTransaction,
+    PendingTransactions, Supply, TransactionParams, TransactionResponse, Version,
 };
-use reqwest::header::HeaderMap;
-use reqwest::Url;
+use http::header::HeaderMap;
+use serde::de::DeserializeOwned;
+use std::fmt;
+use url::Url;
+
+mod msgpack;
 
-#[derive(Debug)]
 /// Client for interacting with the Algorand protocol daemon
+#[derive(Clone)]
 pub struct Client {
     url: String,
     headers: HeaderMap,
-    http_client: reqwest::Client,
+    http_client: HttpClient,
+    last_response_meta: LastResponseMeta,
+    retry: Retry,
+    debug_body_limit: Option<usize>,
+    decoder: Decoder,
+}
+
+/// The values of the sensitive headers (e.g. the API token) are redacted.
+impl fmt::Debug for Client {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Client")
+            .field("url", &self.url)
+            .field("headers", &RedactedHeaders::of(&self.headers))
+            .field("http_client", &self.http_client)
+            .field("last_response_meta", &self.last_response_meta)
+            .field("retry", &self.retry)
+            .field("debug_body_limit", &self.debug_body_limit)
+            .field("decoder", &self.decoder)
+            .finish()
+    }
 }
 
 impl Client {
     pub fn new(url: &str, headers: Headers) -> Result<Client, ClientError> {
+        Self::with_config(url, headers, &ClientConfig::default())
+    }
+
+    /// Builds a client with custom settings of the HTTP client (e.g. the connection pool).
+    pub fn with_config(
+        url: &str,
+        headers: Headers,
+        config: &ClientConfig,
+    ) -> Result<Client, ClientError> {
         Ok(Client {
             url: Url::parse(url)?.as_ref().into(),
             headers: to_header_map(headers)?,
-            http_client: reqwest::Client::new(),
+            http_client: config.build_http_client()?,
+            last_response_meta: LastResponseMeta::default(),
+            retry: config.retry(),
+            debug_body_limit: config.debug_body_limit,
+            decoder: config.decoder.clone(),
         })
     }
 
+    /// Status and rate limit headers of the latest response received by this client or its clones.
+    pub fn last_response_meta(&self) -> Option<ResponseMeta> {
+        self.last_response_meta.get()
+    }
+
+    /// A clone of this client recording the meta of its responses apart from this client and its
+    /// other clones, e.g. to read the meta of the responses of a single call.
+    pub fn with_own_meta(&self) -> Client {
+        Client {
+            last_response_meta: LastResponseMeta::default(),
+            ..self.clone()
+        }
+    }
+
     pub async fn genesis(&self) -> Result<GenesisBlock, ClientError> {
         let response = self
             .http_client
             .get(&format!("{}genesis", self.url))
             .headers(self.headers.clone())
-            .send()
+            .send_retrying(&self.retry, "algod.genesis")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
 
         Ok(response)
@@ -47,8 +101,9 @@ impl Client {
             .http_client
             .get(&format!("{}health", self.url))
             .headers(self.headers.clone())
-            .send()
+            .send_retrying(&self.retry, "algod.health")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?;
 
@@ -60,11 +115,12 @@ impl Client {
             .http_client
             .get(&format!("{}metrics", self.url))
             .headers(self.headers.clone())
-            .send()
+            .send_retrying(&self.retry, "algod.metrics")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?
-            .text()
+            .limited_text()
             .await?;
 
         Ok(response)
@@ -75,21 +131,24 @@ impl Client {
             .http_client
             .get(&format!("{}v2/accounts/{}", self.url, address))
             .headers(self.headers.clone())
-            .send()
+            .send_retrying(&self.retry, "algod.account_information")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
 
         Ok(response)
     }
 
-    pub async fn pending_transactions_for(
+    /// Requests the pending transactions in MessagePack, the JSON encoding of the signed
+    /// transactions not being decodable into `T`.
+    pub async fn pending_transactions_for<T: DeserializeOwned>(
         &self,
         address: &str,
         max: u64,
-    ) -> Result<PendingTransactions, ClientError> {
+    ) -> Result<PendingTransactions<T>, ClientError> {
         let response = self
             .http_client
             .get(&format!(
@@ -97,12 +156,13 @@ impl Client {
                 self.url, address,
             ))
             .headers(self.headers.clone())
-            .query(&[("max", max.to_string())])
-            .send()
+            .query(&[("format", "msgpack".to_owned()), ("max", max.to_string())])
+            .send_retrying(&self.retry, "algod.pending_transactions_for")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?
-            .json()
+            .msgpack_or_json(&self.decoder, self.debug_body_limit)
             .await?;
         Ok(response)
     }
@@ -112,11 +172,12 @@ impl Client {
             .http_client
             .get(&format!("{}v2/applications/{}", self.url, id))
             .headers(self.headers.clone())
-            .send()
+            .send_retrying(&self.retry, "algod.application_information")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
 
         Ok(response)
@@ -127,26 +188,47 @@ impl Client {
             .http_client
             .get(&format!("{}v2/assets/{}", self.url, id))
             .headers(self.headers.clone())
-            .send()
+            .send_retrying(&self.retry, "algod.asset_information")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
 
         Ok(response)
     }
 
+    /// Get the block for the given round, requested as MessagePack. The certificate of a
+    /// MessagePack block isn't decoded, and a JSON response is decoded as JSON.
     pub async fn block(&self, round: Round) -> Result<Block, ClientError> {
         let response = self
             .http_client
             .get(&format!("{}v2/blocks/{}", self.url, round))
             .headers(self.headers.clone())
-            .send()
+            .query(&[("format", "msgpack")])
+            .send_retrying(&self.retry, "algod.block")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?
-            .json()
+            .msgpack_with_or_json(msgpack::decode_block, &self.decoder, self.debug_body_limit)
+            .await?;
+
+        Ok(response)
+    }
+
+    pub async fn block_hash(&self, round: Round) -> Result<BlockHashResponse, ClientError> {
+        let response = self
+            .http_client
+            .get(&format!("{}v2/blocks/{}/hash", self.url, round))
+            .headers(self.headers.clone())
+            .send_retrying(&self.retry, "algod.block_hash")
+            .await?
+            .record_meta(&self.last_response_meta)
+            .http_error_for_status()
+            .await?
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
 
         Ok(response)
@@ -155,13 +237,19 @@ impl Client {
     pub async fn start_catchup(&self, catchpoint: &str) -> Result<Catchup, ClientError> {
         let response = self
             .http_client
-            .post(&format!("{}v2/catchup/{}", self.url, catchpoint))
+            .post(&format!(
+                "{}v2/catchup/{}",
+                self.url,
+                // the label has a `#` separator, which would otherwise start the url fragment
+                catchpoint.replace('#', "%23")
+            ))
             .headers(self.headers.clone())
-            .send()
+            .send_retrying(&self.retry, "algod.start_catchup")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
 
         Ok(response)
@@ -170,13 +258,19 @@ impl Client {
     pub async fn abort_catchup(&self, catchpoint: &str) -> Result<Catchup, ClientError> {
         let response = self
             .http_client
-            .delete(&format!("{}v2/catchup/{}", self.url, catchpoint))
+            .delete(&format!(
+                "{}v2/catchup/{}",
+                self.url,
+                // the label has a `#` separator, which would otherwise start the url fragment
+                catchpoint.replace('#', "%23")
+            ))
             .headers(self.headers.clone())
-            .send()
+            .send_retrying(&self.retry, "algod.abort_catchup")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
 
         Ok(response)
@@ -187,11 +281,12 @@ impl Client {
             .http_client
             .get(&format!("{}v2/ledger/supply", self.url))
             .headers(self.headers.clone())
-            .send()
+            .send_retrying(&self.retry, "algod.ledger_supply")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
 
         Ok(response)
@@ -210,11 +305,12 @@ impl Client {
             ))
             .headers(self.headers.clone())
             .query(&params)
-            .send()
+            .send_retrying(&self.retry, "algod.register_participation_keys")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
 
         Ok(response)
@@ -225,11 +321,12 @@ impl Client {
             .post(&format!("{}v2/shutdown", self.url))
             .headers(self.headers.clone())
             .query(&[("timeout", timeout.to_string())])
-            .send()
+            .send_retrying(&self.retry, "algod.shutdown")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json::<()>(&self.decoder, self.debug_body_limit)
             .await?;
 
         Ok(())
@@ -240,11 +337,12 @@ impl Client {
             .http_client
             .get(&format!("{}v2/status", self.url))
             .headers(self.headers.clone())
-            .send()
+            .send_retrying(&self.retry, "algod.status")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
 
         Ok(response)
@@ -258,11 +356,12 @@ impl Client {
                 self.url, round.0
             ))
             .headers(self.headers.clone())
-            .send()
+            .send_retrying(&self.retry, "algod.status_after_round")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
 
         Ok(response)
@@ -275,11 +374,12 @@ impl Client {
             .headers(self.headers.clone())
             .header("Content-Type", "application/x-binary")
             .body(teal)
-            .send()
+            .send_retrying(&self.retry, "algod.compile_teal")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
 
         Ok(response)
@@ -292,11 +392,12 @@ impl Client {
             .headers(self.headers.clone())
             .header("Content-Type", "application/json")
             .json(req)
-            .send()
+            .send_retrying(&self.retry, "algod.dryrun_teal")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
 
         Ok(response)
@@ -312,11 +413,12 @@ impl Client {
             .headers(self.headers.clone())
             .header("Content-Type", "application/x-binary")
             .body(rawtxn.to_vec())
-            .send()
+            .send_retrying(&self.retry, "algod.broadcast_raw_transaction")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
 
         Ok(response)
@@ -327,27 +429,34 @@ impl Client {
             .http_client
             .get(&format!("{}v2/transactions/params", self.url))
             .headers(self.headers.clone())
-            .send()
+            .send_retrying(&self.retry, "algod.transaction_params")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
 
         Ok(response)
     }
 
-    pub async fn pending_transactions(&self, max: u64) -> Result<PendingTransactions, ClientError> {
+    /// Requests the pending transactions in MessagePack, as
+    /// [pending_transactions_for](Self::pending_transactions_for).
+    pub async fn pending_transactions<T: DeserializeOwned>(
+        &self,
+        max: u64,
+    ) -> Result<PendingTransactions<T>, ClientError> {
         let response = self
             .http_client
             .get(&format!("{}v2/transactions/pending", self.url))
             .headers(self.headers.clone())
-            .query(&[("max", max.to_string())])
-            .send()
+            .query(&[("format", "msgpack".to_owned()), ("max", max.to_string())])
+            .send_retrying(&self.retry, "algod.pending_transactions")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?
-            .json()
+            .msgpack_or_json(&self.decoder, self.debug_body_limit)
             .await?;
 
         Ok(response)
@@ -361,11 +470,28 @@ impl Client {
             .http_client
             .get(&format!("{}v2/transactions/pending/{}", self.url, txid))
             .headers(self.headers.clone())
-            .send()
+            .send_retrying(&self.retry, "algod.pending_transaction_with_id")
+            .await?
+            .record_meta(&self.last_response_meta)
+            .http_error_for_status()
+            .await?
+            .decode_json(&self.decoder, self.debug_body_limit)
+            .await?;
+
+        Ok(response)
+    }
+
+    pub async fn api_spec(&self) -> Result<ApiSpec, ClientError> {
+        let response = self
+            .http_client
+            .get(&format!("{}swagger.json", self.url))
+            .headers(self.headers.clone())
+            .send_retrying(&self.retry, "algod.api_spec")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
 
         Ok(response)
@@ -376,11 +502,12 @@ impl Client {
             .http_client
             .get(&format!("{}versions", self.url))
             .headers(self.headers.clone())
-            .send()
+            .send_retrying(&self.retry, "algod.versions")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
 
         Ok(response)
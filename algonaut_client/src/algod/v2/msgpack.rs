@@ -0,0 +1,154 @@
+//! Decoding of the blocks of algod encoded as MessagePack, which use raw bytes for the hashes and
+//! the addresses, instead of the base32 / base64 strings of the JSON.
+
+use algonaut_core::Address;
+use algonaut_model::algod::v2::{Block, BlockHeader};
+use data_encoding::{BASE32_NOPAD, BASE64};
+use rmpv::Value;
+use serde::Deserialize;
+use std::convert::TryInto;
+
+/// Decodes the response of the block endpoint encoded as MessagePack, with the block under `block`.
+///
+/// The header is decoded into the strings of the JSON encoding: `blk-` and the base32 hash for the
+/// previous block, the addresses for the fee sink and the rewards pool, and base64 for the other
+/// bytes. The certificate isn't decoded.
+pub(super) fn decode_block(body: &[u8]) -> Result<Block, String> {
+    let value = rmpv::decode::read_value(&mut &body[..]).map_err(|e| e.to_string())?;
+    let block = match value {
+        Value::Map(entries) => entries
+            .into_iter()
+            .find(|(key, _)| key.as_str() == Some("block"))
+            .map(|(_, block)| block)
+            .ok_or("the response has no block")?,
+        _ => return Err("the response isn't a map".to_owned()),
+    };
+    let header: CodecBlockHeader = rmpv::ext::from_value(block).map_err(|e| e.to_string())?;
+    Ok(Block {
+        block: header.into_header()?,
+        cert: None,
+    })
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct CodecBlockHeader {
+    earn: Option<u64>,
+    #[serde(with = "serde_bytes")]
+    fees: Vec<u8>,
+    frac: u64,
+    gen: String,
+    #[serde(with = "serde_bytes")]
+    gh: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    prev: Vec<u8>,
+    proto: String,
+    rate: u64,
+    rnd: u64,
+    rwcalr: u64,
+    #[serde(with = "serde_bytes")]
+    rwd: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    seed: Vec<u8>,
+    ts: u64,
+    #[serde(with = "serde_bytes")]
+    txn: Vec<u8>,
+}
+
+impl CodecBlockHeader {
+    fn into_header(self) -> Result<BlockHeader, String> {
+        Ok(BlockHeader {
+            earn: self.earn,
+            fees: address(&self.fees, "fees")?,
+            frac: self.frac,
+            gen: self.gen,
+            gh: BASE64.encode(&self.gh),
+            prev: match self.prev.is_empty() {
+                true => String::new(),
+                false => format!("blk-{}", BASE32_NOPAD.encode(&self.prev)),
+            },
+            proto: self.proto,
+            rate: self.rate,
+            rnd: self.rnd,
+            rwcalr: self.rwcalr,
+            rwd: address(&self.rwd, "rwd")?,
+            seed: BASE64.encode(&self.seed),
+            ts: self.ts,
+            txn: Some(&self.txn)
+                .filter(|txn| !txn.is_empty())
+                .map(|txn| BASE64.encode(txn)),
+        })
+    }
+}
+
+/// The address of these bytes, empty if there are none.
+fn address(bytes: &[u8], field: &str) -> Result<String, String> {
+    if bytes.is_empty() {
+        return Ok(String::new());
+    }
+    bytes
+        .try_into()
+        .map(|bytes| Address(bytes).to_string())
+        .map_err(|_| format!("`{}` isn't an address: {} bytes", field, bytes.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bytes(byte: u8) -> Value {
+        Value::Binary(vec![byte; 32])
+    }
+
+    #[test]
+    fn test_decode_block() {
+        let block = Value::Map(vec![
+            ("earn".into(), 27521.into()),
+            ("fees".into(), bytes(1)),
+            ("gen".into(), "testnet-v1.0".into()),
+            ("gh".into(), bytes(2)),
+            ("prev".into(), bytes(3)),
+            ("proto".into(), "future".into()),
+            ("rnd".into(), 1000.into()),
+            ("rwd".into(), bytes(4)),
+            ("seed".into(), bytes(5)),
+            ("ts".into(), 1660000000.into()),
+        ]);
+        let response = Value::Map(vec![
+            ("block".into(), block),
+            ("cert".into(), Value::Map(vec![("rnd".into(), 1000.into())])),
+        ]);
+        let mut body = vec![];
+        rmpv::encode::write_value(&mut body, &response).unwrap();
+
+        let header = decode_block(&body).unwrap().block;
+
+        assert_eq!(Some(27521), header.earn);
+        assert_eq!(Address([1; 32]).to_string(), header.fees);
+        assert_eq!("testnet-v1.0", header.gen);
+        assert_eq!(BASE64.encode(&[2; 32]), header.gh);
+        assert_eq!(
+            format!("blk-{}", BASE32_NOPAD.encode(&[3; 32])),
+            header.prev
+        );
+        assert_eq!([3; 32], header.previous_hash().unwrap().unwrap().0);
+        assert_eq!(1000, header.rnd);
+        assert_eq!(0, header.rate);
+        assert_eq!(Address([4; 32]).to_string(), header.rwd);
+        assert_eq!(BASE64.encode(&[5; 32]), header.seed);
+        assert_eq!(1660000000, header.ts);
+        assert_eq!(None, header.txn);
+    }
+
+    #[test]
+    fn test_decode_block_with_invalid_address() {
+        let block = Value::Map(vec![("fees".into(), Value::Binary(vec![1; 3]))]);
+        let mut body = vec![];
+        rmpv::encode::write_value(&mut body, &Value::Map(vec![("block".into(), block)])).unwrap();
+
+        assert_eq!(
+            Err("`fees` isn't an address: 3 bytes".to_owned()),
+            decode_block(&body).map(|_| ())
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use crate::decoder::Decoder;
+use crate::error::ClientError;
+use crate::retry::{Retry, DEFAULT_MAX_RETRY_DELAY, DEFAULT_RETRY_BUDGET_RATIO};
+use crate::transport::HttpClient;
+use std::time::Duration;
+
+/// Settings of the HTTP client of the algod, indexer and kmd clients.
+///
+/// Unset (`None`) settings keep the defaults of the HTTP backend. The connection pool, timeout and
+/// retry settings are ignored with the WASM target, where the browser manages the connections.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClientConfig {
+    /// Maximum number of idle connections kept open per host.
+    pub pool_max_idle_per_host: Option<usize>,
+    /// How long idle connections are kept open.
+    pub pool_idle_timeout: Option<Duration>,
+    /// Timeout of each request, from connecting until the end of the response body (none by
+    /// default). A request timing out fails with a timeout error, and is retried as a transient
+    /// failure if retries are enabled.
+    pub timeout: Option<Duration>,
+    /// Maximum number of retries of a `GET` request failing with a transient error (a connection
+    /// error, a timeout, or a 429, 502, 503 or 504 status). Retries are disabled by default (0).
+    pub max_retries: u32,
+    /// Retries allowed per successful request, shared by all the requests of a client and its
+    /// clones (0.1 by default, i.e. the retries stay below 10% of the successful requests), on top
+    /// of the 10 retries a client starts with. When the budget is exhausted, failing requests
+    /// aren't retried, to not worsen an outage with a retry storm.
+    pub retry_budget_ratio: Option<f64>,
+    /// Maximum delay before a retry (30 seconds by default): longer `Retry-After` delays of the
+    /// responses, and backoffs, are shortened to it.
+    pub max_retry_delay: Option<Duration>,
+    /// Format requested from the endpoints that can return MessagePack instead of JSON (the
+    /// indexer's block), JSON by default.
+    pub response_format: ResponseFormat,
+    /// Bytes of the response bodies kept for debugging, e.g. to see the JSON a model failed to
+    /// decode. If set, the decode errors carry the body truncated to this size and, with the
+    /// `tracing` feature, the decoded bodies are traced (truncated) at trace level. Unset by
+    /// default.
+    pub debug_body_limit: Option<usize>,
+    /// Maximum size of the response bodies, e.g. to not buffer the unbounded response of a
+    /// misbehaving server. A larger response fails with a "response too large" error as soon as
+    /// the limit is exceeded, without reading the rest of the body. Unset (unlimited) by default.
+    pub max_response_bytes: Option<usize>,
+    /// Decoder of the JSON responses, e.g. `Decoder::new(MyDecoder)` to use a faster JSON parser,
+    /// `serde_json` by default. The MessagePack responses aren't decoded with it.
+    pub decoder: Decoder,
+}
+
+/// Encoding of the responses of the endpoints that support more than JSON.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResponseFormat {
+    #[default]
+    Json,
+    /// MessagePack, smaller and faster to parse than JSON for big payloads, decoded into the same
+    /// types. It's requested with the `format=msgpack` query parameter: the response is decoded
+    /// as JSON if the server doesn't honor it (i.e. its content type isn't MessagePack).
+    MessagePack,
+}
+
+impl ClientConfig {
+    pub(crate) fn build_http_client(&self) -> Result<HttpClient, ClientError> {
+        HttpClient::new(self)
+    }
+
+    pub(crate) fn retry(&self) -> Retry {
+        Retry::new(
+            self.max_retries,
+            self.retry_budget_ratio
+                .unwrap_or(DEFAULT_RETRY_BUDGET_RATIO),
+            self.max_retry_delay.unwrap_or(DEFAULT_MAX_RETRY_DELAY),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_http_client() {
+        assert!(ClientConfig::default().build_http_client().is_ok());
+        assert!(ClientConfig {
+            pool_max_idle_per_host: Some(0),
+            pool_idle_timeout: Some(Duration::from_secs(10)),
+            timeout: Some(Duration::from_secs(30)),
+            ..ClientConfig::default()
+        }
+        .build_http_client()
+        .is_ok());
+    }
+}
@@ -0,0 +1,245 @@
+use serde::de::DeserializeOwned;
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+/// Decodes the JSON responses into the models, e.g. with a faster JSON parser, or to validate the
+/// responses before decoding them.
+///
+/// A decoder isn't generic over the models, so it can be set at runtime in the
+/// [ClientConfig](crate::ClientConfig): it's given the raw body and the [DecodeTarget] of the
+/// model, which it decodes from the JSON text or with any serde deserializer, type-erased (e.g. the
+/// one of another JSON parser).
+pub trait ResponseDecoder: Debug + Send + Sync {
+    /// Decodes `body` into `target`.
+    fn decode(&self, body: &[u8], target: &mut dyn DecodeTarget) -> Result<(), DecodeError>;
+}
+
+/// The model a response is decoded into.
+pub trait DecodeTarget {
+    /// Name of the type of the model, e.g. "algonaut_model::algod::v2::NodeStatus".
+    fn type_name(&self) -> &'static str;
+
+    /// Deserializes the model from the JSON text.
+    fn decode_json(&mut self, json: &[u8]) -> Result<(), DecodeError>;
+
+    /// Deserializes the model with `deserializer`, e.g. the one of another JSON parser or a value
+    /// it parsed, type-erased with `<dyn erased_serde::Deserializer>::erase`.
+    fn deserialize(
+        &mut self,
+        deserializer: &mut dyn erased_serde::Deserializer<'_>,
+    ) -> Result<(), DecodeError>;
+}
+
+/// Why a response couldn't be decoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeError {
+    pub description: String,
+    /// Path of the value at fault (e.g. `transactions[3].fee`), if known and not the root value.
+    pub path: Option<String>,
+}
+
+impl DecodeError {
+    pub fn new(description: impl Into<String>) -> DecodeError {
+        DecodeError {
+            description: description.into(),
+            path: None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for DecodeError {
+    fn from(error: serde_json::Error) -> Self {
+        DecodeError::new(error.to_string())
+    }
+}
+
+impl From<erased_serde::Error> for DecodeError {
+    fn from(error: erased_serde::Error) -> Self {
+        DecodeError::new(error.to_string())
+    }
+}
+
+/// The error of a deserialization tracking the path of the value at fault.
+impl<E: std::fmt::Display> From<serde_path_to_error::Error<E>> for DecodeError {
+    fn from(error: serde_path_to_error::Error<E>) -> Self {
+        // the root value has the path "."
+        let path = error.path().to_string();
+        DecodeError {
+            description: error.inner().to_string(),
+            path: Some(path).filter(|path| path != "."),
+        }
+    }
+}
+
+/// The default decoder: deserializes the body with `serde_json`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SerdeJsonDecoder;
+
+impl ResponseDecoder for SerdeJsonDecoder {
+    fn decode(&self, body: &[u8], target: &mut dyn DecodeTarget) -> Result<(), DecodeError> {
+        target.decode_json(body)
+    }
+}
+
+/// The [ResponseDecoder] of a client, shared by its clones, [SerdeJsonDecoder] by default.
+#[derive(Clone, Default)]
+pub struct Decoder(Option<Arc<dyn ResponseDecoder>>);
+
+impl Decoder {
+    pub fn new(decoder: impl ResponseDecoder + 'static) -> Decoder {
+        Decoder(Some(Arc::new(decoder)))
+    }
+
+    /// Decodes `body` into a `T`.
+    pub(crate) fn decode<T: DeserializeOwned>(&self, body: &[u8]) -> Result<T, DecodeError> {
+        let mut target = Target(None);
+        match &self.0 {
+            Some(decoder) => decoder.decode(body, &mut target)?,
+            None => target.decode_json(body)?,
+        }
+        target
+            .0
+            .ok_or_else(|| DecodeError::new("The decoder didn't decode the response"))
+    }
+}
+
+impl Debug for Decoder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Some(decoder) => decoder.fmt(f),
+            None => SerdeJsonDecoder.fmt(f),
+        }
+    }
+}
+
+/// The decoders are the same if they're both the default one, or the same instance.
+impl PartialEq for Decoder {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (None, None) => true,
+            (Some(decoder), Some(other)) => Arc::ptr_eq(decoder, other),
+            _ => false,
+        }
+    }
+}
+
+/// The [DecodeTarget] of a `T`, holding it once decoded.
+struct Target<T>(Option<T>);
+
+impl<T: DeserializeOwned> DecodeTarget for Target<T> {
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<T>()
+    }
+
+    fn decode_json(&mut self, json: &[u8]) -> Result<(), DecodeError> {
+        let mut deserializer = serde_json::Deserializer::from_slice(json);
+        let decoded = serde_path_to_error::deserialize(&mut deserializer)?;
+        // like serde_json::from_slice, fails on trailing characters
+        deserializer.end()?;
+        self.0 = Some(decoded);
+        Ok(())
+    }
+
+    fn deserialize(
+        &mut self,
+        deserializer: &mut dyn erased_serde::Deserializer<'_>,
+    ) -> Result<(), DecodeError> {
+        self.0 = Some(serde_path_to_error::deserialize(deserializer)?);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use algonaut_model::algod::v2::AccountParticipation;
+    use std::sync::Mutex;
+
+    const PARTICIPATION: &[u8] = br#"{
+        "selection-participation-key": "ISIjJCUmJygpKissLS4vMDEyMzQ1Njc4OTo7PD0+P0A=",
+        "vote-first-valid": 100,
+        "vote-key-dilution": 10000,
+        "vote-last-valid": 3000100,
+        "vote-participation-key": "AQIDBAUGBwgJCgsMDQ4PEBESExQVFhcYGRobHB0eHyA="
+    }"#;
+
+    /// Parses the body into a JSON value first, like a decoder with another parser, recording the
+    /// types decoded.
+    #[derive(Debug, Default)]
+    struct ValueDecoder(Mutex<Vec<&'static str>>);
+
+    impl ResponseDecoder for ValueDecoder {
+        fn decode(&self, body: &[u8], target: &mut dyn DecodeTarget) -> Result<(), DecodeError> {
+            self.0.lock().unwrap().push(target.type_name());
+            let value: serde_json::Value = serde_json::from_slice(body)?;
+            target.deserialize(&mut <dyn erased_serde::Deserializer>::erase(value))
+        }
+    }
+
+    #[test]
+    fn test_default_decoder() {
+        let participation: AccountParticipation = Decoder::default().decode(PARTICIPATION).unwrap();
+        assert_eq!(3000100, participation.vote_last_valid);
+        assert_eq!(32, participation.vote_participation_key.len());
+
+        let error = Decoder::default()
+            .decode::<AccountParticipation>(b"{\n\"vote-first-valid\": \"100\"}")
+            .unwrap_err();
+        assert_eq!(Some("vote-first-valid".to_owned()), error.path);
+    }
+
+    #[test]
+    fn test_custom_decoder() {
+        let value_decoder = Arc::new(ValueDecoder::default());
+        let decoder = Decoder(Some(value_decoder.clone()));
+
+        let participation: AccountParticipation = decoder.decode(PARTICIPATION).unwrap();
+
+        let expected: AccountParticipation = Decoder::default().decode(PARTICIPATION).unwrap();
+        assert_eq!(
+            expected.vote_participation_key,
+            participation.vote_participation_key
+        );
+        assert_eq!(
+            expected.selection_participation_key,
+            participation.selection_participation_key
+        );
+        assert_eq!(
+            vec!["algonaut_model::algod::v2::AccountParticipation"],
+            *value_decoder.0.lock().unwrap()
+        );
+
+        let error = decoder
+            .decode::<AccountParticipation>(br#"{"vote-first-valid": "100"}"#)
+            .unwrap_err();
+        assert_eq!(Some("vote-first-valid".to_owned()), error.path);
+    }
+
+    #[test]
+    fn test_decoder_without_output() {
+        #[derive(Debug)]
+        struct NoopDecoder;
+
+        impl ResponseDecoder for NoopDecoder {
+            fn decode(&self, _: &[u8], _: &mut dyn DecodeTarget) -> Result<(), DecodeError> {
+                Ok(())
+            }
+        }
+
+        assert_eq!(
+            Some(DecodeError::new("The decoder didn't decode the response")),
+            Decoder::new(NoopDecoder)
+                .decode::<AccountParticipation>(PARTICIPATION)
+                .err()
+        );
+    }
+
+    #[test]
+    fn test_decoder_equality() {
+        assert_eq!(Decoder::default(), Decoder::default());
+        let decoder = Decoder::new(SerdeJsonDecoder);
+        assert_eq!(decoder, decoder.clone());
+        assert_ne!(Decoder::default(), decoder);
+        assert_ne!(Decoder::new(SerdeJsonDecoder), decoder);
+    }
+}
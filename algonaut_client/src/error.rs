@@ -1,4 +1,5 @@
-use reqwest::header::{InvalidHeaderName, InvalidHeaderValue};
+use crate::meta::ResponseMeta;
+use http::header::{InvalidHeaderName, InvalidHeaderValue};
 use std::fmt::Debug;
 use thiserror::Error;
 
@@ -24,11 +25,24 @@ pub enum ClientError {
 pub struct RequestError {
     pub url: Option<String>,
     pub details: RequestErrorDetails,
+    /// Status and rate limit headers of the response, if the server responded.
+    pub meta: Option<Box<ResponseMeta>>,
 }
 
 impl RequestError {
     pub fn new(url: Option<String>, details: RequestErrorDetails) -> RequestError {
-        RequestError { url, details }
+        RequestError {
+            url,
+            details,
+            meta: None,
+        }
+    }
+
+    pub fn with_meta(self, meta: ResponseMeta) -> RequestError {
+        RequestError {
+            meta: Some(Box::new(meta)),
+            ..self
+        }
     }
 }
 
@@ -40,9 +54,25 @@ pub enum RequestErrorDetails {
     /// Timeout
     #[error("Timeout connecting to the server.")]
     Timeout,
-    /// Client generated errors (while e.g. building request or decoding response)
+    /// Client generated errors (e.g. while building the request)
     #[error("Client error: {}", description)]
     Client { description: String },
+    /// The connection was closed before the whole response body was received.
+    #[error("Incomplete response body.")]
+    IncompleteResponse,
+    /// The response body is larger than
+    /// [max_response_bytes](crate::ClientConfig::max_response_bytes): it was not read further.
+    #[error("Response body larger than {} bytes.", limit)]
+    ResponseTooLarge { limit: usize },
+    /// The response body was received but couldn't be decoded: `path` is the path of the value at
+    /// fault in the JSON body (e.g. `transactions[3].fee`), if known, and `body` the body,
+    /// truncated, if [debug_body_limit](crate::ClientConfig::debug_body_limit) is set.
+    #[error("Decode error: {}", description)]
+    Decode {
+        description: String,
+        path: Option<String>,
+        body: Option<String>,
+    },
 }
 
 impl From<url::ParseError> for ClientError {
@@ -51,31 +81,6 @@ impl From<url::ParseError> for ClientError {
     }
 }
 
-impl From<reqwest::Error> for ClientError {
-    fn from(error: reqwest::Error) -> Self {
-        let url_str = error.url().map(|u| u.to_string());
-        let request_error = if let Some(status) = error.status() {
-            RequestError::new(
-                url_str,
-                RequestErrorDetails::Http {
-                    status: status.as_u16(),
-                    message: "".to_owned(),
-                },
-            )
-        } else if error.is_timeout() {
-            RequestError::new(url_str, RequestErrorDetails::Timeout)
-        } else {
-            RequestError::new(
-                url_str,
-                RequestErrorDetails::Client {
-                    description: error.to_string(),
-                },
-            )
-        };
-        ClientError::Request(request_error)
-    }
-}
-
 impl From<InvalidHeaderName> for ClientError {
     fn from(error: InvalidHeaderName) -> Self {
         ClientError::BadHeader(error.to_string())
@@ -87,3 +92,92 @@ impl From<InvalidHeaderValue> for ClientError {
         ClientError::BadHeader(error.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extensions::http::ResponseExt;
+    use crate::test_server::{TestResponse, TestServer};
+    use crate::{ClientConfig, Decoder, HttpClient};
+    use serde_json::Value;
+    use std::time::Duration;
+
+    async fn get_json(url: &str) -> Result<Value, ClientError> {
+        HttpClient::new(&ClientConfig::default())?
+            .get(url)
+            .send()
+            .await?
+            .decode_json(&Decoder::default(), None)
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_incomplete_response() {
+        let server = TestServer::serve(vec![TestResponse::raw(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: 100\r\n\r\n{\"round\": 1",
+        )]);
+
+        match get_json(server.url()).await {
+            Err(ClientError::Request(e)) => {
+                assert!(matches!(e.details, RequestErrorDetails::IncompleteResponse))
+            }
+            r => panic!("unexpected result: {:?}", r),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_decode_error() {
+        let server = TestServer::serve(vec![TestResponse::json(r#"{"round": 1"#)]);
+
+        match get_json(server.url()).await {
+            Err(ClientError::Request(e)) => {
+                assert!(matches!(e.details, RequestErrorDetails::Decode { .. }))
+            }
+            r => panic!("unexpected result: {:?}", r),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_response_meta() {
+        let server = TestServer::serve(vec![TestResponse::json(r#"{"message":"rate limited"}"#)
+            .status("429 Too Many Requests")
+            .header("retry-after", "30")
+            .header("x-ratelimit-remaining", "0")
+            .header("x-request-id", "req-1")]);
+        let client = crate::indexer::v2::Client::new(server.url(), vec![]).unwrap();
+
+        match client.health_check().await {
+            Err(ClientError::Request(e)) => {
+                assert!(matches!(
+                    e.details,
+                    RequestErrorDetails::Http { status: 429, .. }
+                ));
+                let meta = e.meta.unwrap();
+                assert_eq!(Some(Duration::from_secs(30)), meta.retry_after);
+                assert_eq!(Some(0), meta.rate_limit_remaining);
+                assert_eq!(Some("req-1".to_owned()), meta.request_id);
+            }
+            r => panic!("unexpected result: {:?}", r),
+        }
+        assert_eq!(Some(429), client.last_response_meta().map(|m| m.status));
+    }
+
+    #[tokio::test]
+    async fn test_successful_response_meta() {
+        let server = TestServer::serve(vec![TestResponse::json("")
+            .header("x-ratelimit-remaining", "99")
+            .header("x-ratelimit-reset", "60")]);
+        let client = crate::indexer::v2::Client::new(server.url(), vec![]).unwrap();
+        assert!(client.last_response_meta().is_none());
+
+        client.health().await.unwrap();
+
+        let meta = client.last_response_meta().unwrap();
+        assert_eq!(200, meta.status);
+        assert_eq!(Some(99), meta.rate_limit_remaining);
+        assert_eq!(Some(60), meta.rate_limit_reset);
+        assert_eq!(None, meta.retry_after);
+        // shared with the clones of the client
+        assert_eq!(Some(meta), client.clone().last_response_meta());
+    }
+}
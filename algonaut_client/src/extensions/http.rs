@@ -0,0 +1,544 @@
+use std::str::FromStr;
+
+use crate::{
+    decoder::Decoder,
+    error::{ClientError, RequestError, RequestErrorDetails},
+    meta::{LastResponseMeta, ResponseMeta},
+    retry::Retry,
+    transport::{RequestBuilder, Response, TransportError},
+    Headers,
+};
+use async_trait::async_trait;
+use http::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
+use serde::{de::DeserializeOwned, Deserialize};
+
+// The responses of the reqwest backend have thread unsafe contents with the WASM target,
+// so it's required to implement Send, which is not possible.
+// Since WASM is single threaded, this can be skipped, using ?Send
+// https://docs.rs/async-trait/0.1.50/async_trait/#non-threadsafe-futures
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+// The responses are thread safe with non WASM targets
+// async_trait doesn't need additional parameters.
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub(crate) trait ResponseExt {
+    /// Maps error to custom error, with a possible message returned by API.
+    async fn http_error_for_status(self) -> Result<Response, RequestError>;
+
+    /// Records the status and rate limit headers of the response as the latest of the client.
+    fn record_meta(self, last_response_meta: &LastResponseMeta) -> Response;
+
+    /// Reads the body, failing with [RequestErrorDetails::ResponseTooLarge] as soon as it exceeds
+    /// the maximum size of the responses of the client, if set: before reading it if its content
+    /// length is too large, while reading it otherwise.
+    async fn limited_bytes(self) -> Result<Vec<u8>, ClientError>;
+
+    /// Reads the body as text (lossily decoded as UTF-8) with [limited_bytes](Self::limited_bytes).
+    async fn limited_text(self) -> Result<String, ClientError>;
+
+    /// Decodes the JSON body with `decoder`, read with [limited_bytes](Self::limited_bytes).
+    ///
+    /// If the body doesn't match `T`, the decode error carries the path of the value at fault and,
+    /// if `debug_body_limit` is set, the body truncated to that many bytes. With the `tracing`
+    /// feature and `debug_body_limit` set, the decoded body is traced (truncated) at trace level.
+    async fn decode_json<T: DeserializeOwned>(
+        self,
+        decoder: &Decoder,
+        debug_body_limit: Option<usize>,
+    ) -> Result<T, ClientError>;
+
+    /// Decodes the body as MessagePack if the content type of the response is MessagePack, as
+    /// JSON otherwise (with [decode_json](Self::decode_json)).
+    async fn msgpack_or_json<T: DeserializeOwned>(
+        self,
+        decoder: &Decoder,
+        debug_body_limit: Option<usize>,
+    ) -> Result<T, ClientError>;
+
+    /// Like [msgpack_or_json](Self::msgpack_or_json), decoding the MessagePack body with
+    /// `decode_msgpack`, failing with the description of the error.
+    async fn msgpack_with_or_json<T, F>(
+        self,
+        decode_msgpack: F,
+        decoder: &Decoder,
+        debug_body_limit: Option<usize>,
+    ) -> Result<T, ClientError>
+    where
+        T: DeserializeOwned,
+        F: FnOnce(&[u8]) -> Result<T, String> + Send;
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl ResponseExt for Response {
+    async fn http_error_for_status(self) -> Result<Response, RequestError> {
+        let status = self.status();
+        match status.is_client_error() || status.is_server_error() {
+            // The response is not an error
+            false => Ok(self),
+            // The response is an error
+            true => {
+                let meta = ResponseMeta::from_headers(self.status().as_u16(), self.headers());
+                Err(RequestError::new(
+                    Some(self.url().to_string()),
+                    RequestErrorDetails::Http {
+                        status: self.status().as_u16(),
+                        message: parse_error_message_or_empty_string(self).await,
+                    },
+                )
+                .with_meta(meta))
+            }
+        }
+    }
+
+    fn record_meta(self, last_response_meta: &LastResponseMeta) -> Response {
+        last_response_meta.set(ResponseMeta::from_headers(
+            self.status().as_u16(),
+            self.headers(),
+        ));
+        self
+    }
+
+    async fn limited_bytes(self) -> Result<Vec<u8>, ClientError> {
+        let limit = match self.max_bytes() {
+            Some(limit) => limit,
+            None => return Ok(self.bytes().await?),
+        };
+        let too_large = ClientError::Request(RequestError::new(
+            Some(self.url().to_string()),
+            RequestErrorDetails::ResponseTooLarge { limit },
+        ));
+        if self
+            .content_length()
+            .is_some_and(|length| length > limit as u64)
+        {
+            return Err(too_large);
+        }
+        // the body is streamed (but with the WASM target) to not buffer more than the limit
+        let mut response = self;
+        let mut body = vec![];
+        while let Some(chunk) = response.chunk().await? {
+            if body.len() + chunk.len() > limit {
+                return Err(too_large);
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body)
+    }
+
+    async fn limited_text(self) -> Result<String, ClientError> {
+        let body = self.limited_bytes().await?;
+        Ok(String::from_utf8_lossy(&body).into_owned())
+    }
+
+    async fn decode_json<T: DeserializeOwned>(
+        self,
+        decoder: &Decoder,
+        debug_body_limit: Option<usize>,
+    ) -> Result<T, ClientError> {
+        let url = self.url().to_string();
+        let body = self.limited_bytes().await?;
+        match decoder.decode(&body) {
+            Ok(decoded) => {
+                #[cfg(feature = "tracing")]
+                if let Some(limit) = debug_body_limit {
+                    tracing::trace!(url = %url, body = %truncated(&body, limit), "algonaut_response");
+                }
+                Ok(decoded)
+            }
+            Err(e) => Err(ClientError::Request(RequestError::new(
+                Some(url),
+                RequestErrorDetails::Decode {
+                    path: e.path,
+                    description: e.description,
+                    body: debug_body_limit.map(|limit| truncated(&body, limit)),
+                },
+            ))),
+        }
+    }
+
+    async fn msgpack_or_json<T: DeserializeOwned>(
+        self,
+        decoder: &Decoder,
+        debug_body_limit: Option<usize>,
+    ) -> Result<T, ClientError> {
+        self.msgpack_with_or_json(
+            |body| rmp_serde::from_slice(body).map_err(|e| e.to_string()),
+            decoder,
+            debug_body_limit,
+        )
+        .await
+    }
+
+    async fn msgpack_with_or_json<T, F>(
+        self,
+        decode_msgpack: F,
+        decoder: &Decoder,
+        debug_body_limit: Option<usize>,
+    ) -> Result<T, ClientError>
+    where
+        T: DeserializeOwned,
+        F: FnOnce(&[u8]) -> Result<T, String> + Send,
+    {
+        let is_msgpack = self
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .is_some_and(|content_type| content_type.contains("msgpack"));
+        if !is_msgpack {
+            return self.decode_json(decoder, debug_body_limit).await;
+        }
+        let url = self.url().to_string();
+        let body = self.limited_bytes().await?;
+        decode_msgpack(&body).map_err(|description| {
+            ClientError::Request(RequestError::new(
+                Some(url),
+                RequestErrorDetails::Decode {
+                    description,
+                    path: None,
+                    body: debug_body_limit.map(|limit| truncated(&body, limit)),
+                },
+            ))
+        })
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub(crate) trait RequestBuilderExt {
+    /// Sends the request.
+    ///
+    /// With the `tracing` feature, the request is sent in a debug span named "algonaut_request",
+    /// with the `endpoint` (e.g. "indexer.accounts"), `method`, `path`, query `params` (tokens
+    /// redacted), and, when the response is received, its `status`, `response_size` (content length,
+    /// if known) and `elapsed_ms` (until the response headers were received).
+    async fn send_traced(self, endpoint: &'static str) -> Result<Response, TransportError>;
+
+    /// Sends the request with [send_traced](Self::send_traced), retrying `GET` requests failing
+    /// with a transient error (a connection error, a timeout, or a 429, 502, 503 or 504 status)
+    /// while `retry` allows it.
+    ///
+    /// The requests aren't retried with the WASM target.
+    async fn send_retrying(
+        self,
+        retry: &Retry,
+        endpoint: &'static str,
+    ) -> Result<Response, TransportError>;
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl RequestBuilderExt for RequestBuilder {
+    #[cfg(not(feature = "tracing"))]
+    async fn send_traced(self, _endpoint: &'static str) -> Result<Response, TransportError> {
+        self.send().await
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    async fn send_retrying(
+        self,
+        _retry: &Retry,
+        endpoint: &'static str,
+    ) -> Result<Response, TransportError> {
+        self.send_traced(endpoint).await
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn send_retrying(
+        self,
+        retry: &Retry,
+        endpoint: &'static str,
+    ) -> Result<Response, TransportError> {
+        use crate::retry::{is_retryable_error, is_retryable_status};
+
+        let (client, request) = self.build_split();
+        let request = request?;
+        if request.method == http::Method::GET {
+            let mut attempt = 0;
+            loop {
+                let result = RequestBuilder::from_parts(client.clone(), request.clone())
+                    .send_traced(endpoint)
+                    .await;
+                let meta = match &result {
+                    Ok(response) if !is_retryable_status(response.status().as_u16()) => {
+                        retry.record_success();
+                        return result;
+                    }
+                    Ok(response) => Some(ResponseMeta::from_headers(
+                        response.status().as_u16(),
+                        response.headers(),
+                    )),
+                    Err(error) if is_retryable_error(error) => None,
+                    Err(_) => return result,
+                };
+                if !retry.try_retry(attempt) {
+                    return result;
+                }
+                tokio::time::sleep(retry.delay(attempt, meta.as_ref())).await;
+                attempt += 1;
+            }
+        }
+        RequestBuilder::from_parts(client, request)
+            .send_traced(endpoint)
+            .await
+    }
+
+    #[cfg(feature = "tracing")]
+    async fn send_traced(self, endpoint: &'static str) -> Result<Response, TransportError> {
+        use tracing::{field::Empty, Instrument};
+
+        let (client, request) = self.build_split();
+        let request = request?;
+        let span = tracing::debug_span!(
+            "algonaut_request",
+            endpoint,
+            method = %request.method,
+            path = request.url.path(),
+            params = %sanitized_query(&request.url),
+            status = Empty,
+            response_size = Empty,
+            elapsed_ms = Empty,
+        );
+        let start = std::time::Instant::now();
+        let response = client.execute(request).instrument(span.clone()).await;
+        span.record("elapsed_ms", start.elapsed().as_millis() as u64);
+        if let Ok(response) = &response {
+            span.record("status", response.status().as_u16());
+            if let Some(size) = response.content_length() {
+                span.record("response_size", size);
+            }
+        }
+        response
+    }
+}
+
+/// The query of `url`, with the values of the parameters whose name contains "token" redacted.
+#[cfg(feature = "tracing")]
+fn sanitized_query(url: &url::Url) -> String {
+    url.query_pairs()
+        .map(|(key, value)| {
+            if key.to_lowercase().contains("token") {
+                format!("{}=<redacted>", key)
+            } else {
+                format!("{}={}", key, value)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// The first `limit` bytes of `body`, lossily decoded as UTF-8, followed by "..." if cut.
+fn truncated(body: &[u8], limit: usize) -> String {
+    let text = String::from_utf8_lossy(&body[..body.len().min(limit)]).into_owned();
+    if body.len() > limit {
+        text + "..."
+    } else {
+        text
+    }
+}
+
+/// Try to retrieve error message from JSON, read with [limited_bytes](ResponseExt::limited_bytes).
+/// If there's no message, return an empty string.
+async fn parse_error_message_or_empty_string(response: Response) -> String {
+    response
+        .limited_bytes()
+        .await
+        .ok()
+        .and_then(|body| serde_json::from_slice::<HttpErrorPayload>(&body).ok())
+        .map(|p| p.message)
+        .unwrap_or_else(|| "".to_owned())
+}
+
+#[derive(Deserialize)]
+struct HttpErrorPayload {
+    message: String,
+}
+
+pub fn to_header_map(headers: Headers) -> Result<HeaderMap, ClientError> {
+    let mut map = HeaderMap::new();
+    for h in &headers {
+        map.insert(HeaderName::from_str(h.0)?, HeaderValue::from_str(h.1)?);
+    }
+    Ok(map)
+}
+
+#[cfg(test)]
+mod decode_tests {
+    use super::*;
+
+    /// The path of the value at fault when decoding `json` into `T`.
+    fn error_path<T: DeserializeOwned>(json: &str) -> Option<String> {
+        Decoder::default()
+            .decode::<T>(json.as_bytes())
+            .err()
+            .unwrap()
+            .path
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Txn {
+        fee: u64,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Page {
+        #[serde(rename = "current-round")]
+        current_round: u64,
+        transactions: Vec<Txn>,
+    }
+
+    #[test]
+    fn test_decode_error_path() {
+        assert_eq!(
+            Some("transactions[2].fee".to_owned()),
+            error_path::<Page>(
+                r#"{"current-round":1,"transactions":[{"fee":1},{"fee":2},{"fee":"3"}]}"#
+            )
+        );
+        assert_eq!(
+            Some("current-round".to_owned()),
+            error_path::<Page>("{\n  \"transactions\": [],\n  \"current-round\": \"1\"\n}")
+        );
+        // strings with brackets, commas and escaped quotes
+        assert_eq!(
+            Some("transactions[1].fee".to_owned()),
+            error_path::<Page>(
+                r#"{"x":"[,\"{","current-round":1,"transactions":[{"fee":1},{"fee":-1}]}"#
+            )
+        );
+        // a missing field is reported on its object
+        assert_eq!(
+            Some("transactions[0]".to_owned()),
+            error_path::<Page>(r#"{"current-round":1,"transactions":[{}]}"#)
+        );
+        assert_eq!(None, error_path::<Page>("1"));
+    }
+
+    #[test]
+    fn test_truncated() {
+        assert_eq!("{\"a\":1}", truncated(b"{\"a\":1}", 7));
+        assert_eq!("{\"a\"...", truncated(b"{\"a\":1}", 4));
+    }
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use super::*;
+    use crate::test_server::{TestResponse, TestServer};
+    use algonaut_model::indexer::v2::QueryAccount;
+    use std::{
+        collections::BTreeMap,
+        fmt::Debug,
+        sync::{Arc, Mutex},
+    };
+    use tracing::{
+        field::{Field, Visit},
+        span::{Attributes, Id, Record},
+        Event, Metadata, Subscriber,
+    };
+
+    type SpanFields = BTreeMap<String, String>;
+
+    /// Records the fields of the spans.
+    #[derive(Clone, Default)]
+    struct SpanRecorder(Arc<Mutex<Vec<SpanFields>>>);
+
+    struct FieldVisitor<'a>(&'a mut SpanFields);
+
+    impl Visit for FieldVisitor<'_> {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0.insert(field.name().to_owned(), value.to_owned());
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            self.0
+                .insert(field.name().to_owned(), format!("{:?}", value));
+        }
+    }
+
+    impl Subscriber for SpanRecorder {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, attributes: &Attributes<'_>) -> Id {
+            let mut fields = SpanFields::new();
+            fields.insert("name".to_owned(), attributes.metadata().name().to_owned());
+            attributes.record(&mut FieldVisitor(&mut fields));
+            let mut spans = self.0.lock().unwrap();
+            spans.push(fields);
+            Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, span: &Id, values: &Record<'_>) {
+            let mut spans = self.0.lock().unwrap();
+            values.record(&mut FieldVisitor(&mut spans[span.into_u64() as usize - 1]));
+        }
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, _: &Event<'_>) {}
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    impl SpanRecorder {
+        fn request_spans(&self) -> Vec<SpanFields> {
+            self.0
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|fields| fields["name"] == "algonaut_request")
+                .cloned()
+                .collect()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_indexer_and_algod_request_spans() {
+        let recorder = SpanRecorder::default();
+        let _guard = tracing::subscriber::set_default(recorder.clone());
+
+        let server = TestServer::serve(vec![TestResponse::json("{}"); 2]);
+        let indexer = crate::indexer::v2::Client::new(server.url(), vec![]).unwrap();
+        let query = QueryAccount {
+            limit: Some(2),
+            ..QueryAccount::default()
+        };
+        // the response is not a valid account list: only the span matters
+        let _ = indexer.accounts(&query).await;
+
+        let algod = crate::algod::v2::Client::new(server.url(), vec![]).unwrap();
+        let _ = algod.status().await;
+
+        let spans = recorder.request_spans();
+        assert_eq!(2, spans.len());
+
+        assert_eq!("indexer.accounts", spans[0]["endpoint"]);
+        assert_eq!("GET", spans[0]["method"]);
+        assert_eq!("/v2/accounts", spans[0]["path"]);
+        assert_eq!("limit=2", spans[0]["params"]);
+        assert_eq!("200", spans[0]["status"]);
+        assert_eq!("2", spans[0]["response_size"]);
+        assert!(spans[0].contains_key("elapsed_ms"));
+
+        assert_eq!("algod.status", spans[1]["endpoint"]);
+        assert_eq!("/v2/status", spans[1]["path"]);
+        assert_eq!("", spans[1]["params"]);
+        assert_eq!("200", spans[1]["status"]);
+        assert_eq!("2", spans[1]["response_size"]);
+    }
+
+    #[test]
+    fn test_sanitized_query() {
+        let url =
+            url::Url::parse("http://localhost/v1/key?limit=2&wallet_handle_token=abc").unwrap();
+        assert_eq!(
+            "limit=2&wallet_handle_token=<redacted>",
+            sanitized_query(&url)
+        );
+    }
+}
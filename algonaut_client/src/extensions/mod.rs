@@ -1 +1 @@
-pub(super) mod reqwest;
+pub(super) mod http;
@@ -1,47 +1,126 @@
+use crate::decoder::Decoder;
 use crate::error::ClientError;
-use crate::extensions::reqwest::{to_header_map, ResponseExt};
-use crate::Headers;
+use crate::extensions::http::{to_header_map, RequestBuilderExt, ResponseExt};
+use crate::meta::{LastResponseMeta, ResponseMeta};
+use crate::retry::Retry;
+use crate::{ClientConfig, Headers, HttpClient, RedactedHeaders, ResponseFormat};
 use algonaut_core::{Address, Round};
 use algonaut_model::indexer::v2::{
     AccountInfoResponse, AccountResponse, AccountTransactionResponse, ApplicationInfoResponse,
     ApplicationResponse, AssetResponse, AssetTransactionResponse, AssetsInfoResponse,
-    BalancesResponse, Block, QueryAccount, QueryAccountInfo, QueryAccountTransaction,
-    QueryApplicationInfo, QueryApplications, QueryAssetTransaction, QueryAssets, QueryAssetsInfo,
-    QueryBalances, QueryTransaction, TransactionInfoResponse, TransactionResponse,
+    BalancesResponse, Block, HealthCheck, MiniApplicationInfoResponse, QueryAccount,
+    QueryAccountInfo, QueryAccountTransaction, QueryApplicationInfo, QueryApplications,
+    QueryAssetTransaction, QueryAssets, QueryAssetsInfo, QueryBalances, QueryTransaction,
+    TransactionInfoResponse, TransactionResponse,
 };
-use reqwest::header::HeaderMap;
-use reqwest::Url;
+use http::header::HeaderMap;
+use std::fmt;
+use url::Url;
+
+mod msgpack;
 
 /// Client interacting with the Algorand's indexer
-#[derive(Debug)]
+#[derive(Clone)]
 pub struct Client {
     pub(super) url: String,
     pub(super) headers: HeaderMap,
-    pub(super) http_client: reqwest::Client,
+    pub(super) http_client: HttpClient,
+    pub(super) last_response_meta: LastResponseMeta,
+    pub(super) retry: Retry,
+    pub(super) response_format: ResponseFormat,
+    pub(super) debug_body_limit: Option<usize>,
+    pub(super) decoder: Decoder,
+}
+
+/// The values of the sensitive headers (e.g. the API token) are redacted.
+impl fmt::Debug for Client {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Client")
+            .field("url", &self.url)
+            .field("headers", &RedactedHeaders::of(&self.headers))
+            .field("http_client", &self.http_client)
+            .field("last_response_meta", &self.last_response_meta)
+            .field("retry", &self.retry)
+            .field("response_format", &self.response_format)
+            .field("debug_body_limit", &self.debug_body_limit)
+            .field("decoder", &self.decoder)
+            .finish()
+    }
 }
 
 impl Client {
     pub fn new(url: &str, headers: Headers) -> Result<Client, ClientError> {
+        Self::with_config(url, headers, &ClientConfig::default())
+    }
+
+    /// Builds a client with custom settings of the HTTP client (e.g. the connection pool).
+    pub fn with_config(
+        url: &str,
+        headers: Headers,
+        config: &ClientConfig,
+    ) -> Result<Client, ClientError> {
         Ok(Client {
             url: Url::parse(url)?.as_ref().into(),
             headers: to_header_map(headers)?,
-            http_client: reqwest::Client::new(),
+            http_client: config.build_http_client()?,
+            last_response_meta: LastResponseMeta::default(),
+            retry: config.retry(),
+            response_format: config.response_format,
+            debug_body_limit: config.debug_body_limit,
+            decoder: config.decoder.clone(),
         })
     }
 
+    /// The base url of the indexer, as parsed (e.g. with a trailing slash if it has no path).
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Status and rate limit headers of the latest response received by this client or its clones.
+    pub fn last_response_meta(&self) -> Option<ResponseMeta> {
+        self.last_response_meta.get()
+    }
+
+    /// A clone of this client recording the meta of its responses apart from this client and its
+    /// other clones, e.g. to read the meta of the responses of a single call.
+    pub fn with_own_meta(&self) -> Client {
+        Client {
+            last_response_meta: LastResponseMeta::default(),
+            ..self.clone()
+        }
+    }
+
     /// Returns Ok if healthy
     pub async fn health(&self) -> Result<(), ClientError> {
         let _ = self
             .http_client
             .get(&format!("{}health", self.url))
             .headers(self.headers.clone())
-            .send()
+            .send_retrying(&self.retry, "indexer.health")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?;
         Ok(())
     }
 
+    /// Returns the health status of the indexer, including the last round it processed.
+    pub async fn health_check(&self) -> Result<HealthCheck<serde_json::Value>, ClientError> {
+        let response = self
+            .http_client
+            .get(&format!("{}health", self.url))
+            .headers(self.headers.clone())
+            .send_retrying(&self.retry, "indexer.health_check")
+            .await?
+            .record_meta(&self.last_response_meta)
+            .http_error_for_status()
+            .await?
+            .decode_json(&self.decoder, self.debug_body_limit)
+            .await?;
+
+        Ok(response)
+    }
+
     /// Search for accounts.
     pub async fn accounts(&self, query: &QueryAccount) -> Result<AccountResponse, ClientError> {
         let response = self
@@ -49,11 +128,12 @@ impl Client {
             .get(&format!("{}v2/accounts", self.url))
             .headers(self.headers.clone())
             .query(query)
-            .send()
+            .send_retrying(&self.retry, "indexer.accounts")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
 
         Ok(response)
@@ -70,11 +150,12 @@ impl Client {
             .get(&format!("{}v2/accounts/{}", self.url, address))
             .headers(self.headers.clone())
             .query(query)
-            .send()
+            .send_retrying(&self.retry, "indexer.account_info")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
 
         Ok(response)
@@ -91,11 +172,12 @@ impl Client {
             .get(&format!("{}v2/accounts/{}/transactions", self.url, address))
             .headers(self.headers.clone())
             .query(query)
-            .send()
+            .send_retrying(&self.retry, "indexer.account_transactions")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
 
         Ok(response)
@@ -111,11 +193,12 @@ impl Client {
             .get(&format!("{}v2/applications", self.url))
             .headers(self.headers.clone())
             .query(query)
-            .send()
+            .send_retrying(&self.retry, "indexer.applications")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
 
         Ok(response)
@@ -132,11 +215,34 @@ impl Client {
             .get(&format!("{}v2/applications/{}", self.url, id))
             .headers(self.headers.clone())
             .query(query)
-            .send()
+            .send_retrying(&self.retry, "indexer.application_info")
+            .await?
+            .record_meta(&self.last_response_meta)
+            .http_error_for_status()
+            .await?
+            .decode_json(&self.decoder, self.debug_body_limit)
+            .await?;
+
+        Ok(response)
+    }
+
+    /// Lookup application, without decoding its programs and global state.
+    pub async fn application_params_only(
+        &self,
+        id: u64,
+        query: &QueryApplicationInfo,
+    ) -> Result<MiniApplicationInfoResponse, ClientError> {
+        let response = self
+            .http_client
+            .get(&format!("{}v2/applications/{}", self.url, id))
+            .headers(self.headers.clone())
+            .query(query)
+            .send_retrying(&self.retry, "indexer.application_params_only")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
 
         Ok(response)
@@ -149,11 +255,12 @@ impl Client {
             .get(&format!("{}v2/assets", self.url))
             .headers(self.headers.clone())
             .query(query)
-            .send()
+            .send_retrying(&self.retry, "indexer.assets")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
 
         Ok(response)
@@ -170,11 +277,12 @@ impl Client {
             .get(&format!("{}v2/assets/{}", self.url, id))
             .headers(self.headers.clone())
             .query(query)
-            .send()
+            .send_retrying(&self.retry, "indexer.assets_info")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
 
         Ok(response)
@@ -191,11 +299,12 @@ impl Client {
             .get(&format!("{}v2/assets/{}/balances", self.url, id))
             .headers(self.headers.clone())
             .query(query)
-            .send()
+            .send_retrying(&self.retry, "indexer.asset_balances")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
 
         Ok(response)
@@ -212,11 +321,12 @@ impl Client {
             .get(&format!("{}v2/assets/{}/transactions", self.url, id))
             .headers(self.headers.clone())
             .query(query)
-            .send()
+            .send_retrying(&self.retry, "indexer.asset_transactions")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
 
         Ok(response)
@@ -224,15 +334,52 @@ impl Client {
 
     /// Lookup block.
     pub async fn block(&self, round: Round) -> Result<Block, ClientError> {
+        self.block_with_format(round, self.response_format).await
+    }
+
+    /// Lookup a block, requesting the response in this format.
+    ///
+    /// A MessagePack response is decoded from the field names of the node's codec (e.g. `rnd`,
+    /// `txns`), computing the ids of the transactions, which the encoding doesn't contain. Its
+    /// inner transactions have no id, and its state proof and heartbeat fields aren't decoded. A
+    /// JSON response, from a server ignoring the format, is decoded as JSON.
+    pub async fn block_with_format(
+        &self,
+        round: Round,
+        format: ResponseFormat,
+    ) -> Result<Block, ClientError> {
+        let mut request = self
+            .http_client
+            .get(&format!("{}v2/blocks/{}", self.url, round))
+            .headers(self.headers.clone());
+        if format == ResponseFormat::MessagePack {
+            request = request.query(&[("format", "msgpack")]);
+        }
+        let response = request
+            .send_retrying(&self.retry, "indexer.block")
+            .await?
+            .record_meta(&self.last_response_meta)
+            .http_error_for_status()
+            .await?
+            .msgpack_with_or_json(msgpack::decode_block, &self.decoder, self.debug_body_limit)
+            .await?;
+
+        Ok(response)
+    }
+
+    /// Lookup the header of a block: the block without its transactions.
+    pub async fn block_header(&self, round: Round) -> Result<Block, ClientError> {
         let response = self
             .http_client
             .get(&format!("{}v2/blocks/{}", self.url, round))
             .headers(self.headers.clone())
-            .send()
+            .query(&[("header-only", "true")])
+            .send_retrying(&self.retry, "indexer.block_header")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
 
         Ok(response)
@@ -248,11 +395,30 @@ impl Client {
             .get(&format!("{}v2/transactions", self.url))
             .headers(self.headers.clone())
             .query(query)
-            .send()
+            .send_retrying(&self.retry, "indexer.transactions")
+            .await?
+            .record_meta(&self.last_response_meta)
+            .http_error_for_status()
+            .await?
+            .decode_json(&self.decoder, self.debug_body_limit)
+            .await?;
+
+        Ok(response)
+    }
+
+    /// Search for transactions, returning the JSON body of the response.
+    pub async fn transactions_raw(&self, query: &QueryTransaction) -> Result<String, ClientError> {
+        let response = self
+            .http_client
+            .get(&format!("{}v2/transactions", self.url))
+            .headers(self.headers.clone())
+            .query(query)
+            .send_retrying(&self.retry, "indexer.transactions")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?
-            .json()
+            .limited_text()
             .await?;
 
         Ok(response)
@@ -264,13 +430,456 @@ impl Client {
             .http_client
             .get(&format!("{}v2/transactions/{}", self.url, id))
             .headers(self.headers.clone())
-            .send()
+            .send_retrying(&self.retry, "indexer.transaction_info")
             .await?
+            .record_meta(&self.last_response_meta)
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
 
         Ok(response)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{RequestError, RequestErrorDetails};
+    use crate::test_server::{TestResponse, TestServer};
+    use crate::{DecodeError, DecodeTarget, ResponseDecoder};
+    use algonaut_core::MicroAlgos;
+    use algonaut_model::indexer::v2::{
+        OnCompletion, ToQueryParams, Transaction, TransactionSignature,
+    };
+    use data_encoding::{BASE64, HEXLOWER};
+
+    /// A block of the indexer, encoded as JSON.
+    const BLOCK_JSON: &str = r#"{"genesis-hash":"SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=","genesis-id":"testnet-v1.0","previous-block-hash":"qbh2v2ZuMHK7aEtCMPzpNGCc0MnEEKE8cjl7PJ6qETs=","round":1000,"seed":"0aWDKwgg2ITU/2ZJzoFH/cr2OfwKBxLEAVhp9QpsLpU=","timestamp":1660000000,"transactions":[{"confirmed-round":1000,"fee":1000,"first-valid":990,"genesis-hash":"SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=","id":"TXID","intra-round-offset":0,"last-valid":1990,"payment-transaction":{"amount":5000,"receiver":"2UEQTE5QDNXPI7M3TU44G6SYKLFWLPQO7EBZM7K7MHMQQMFI4QJPLHQFHM"},"round-time":1660000000,"sender":"47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU","tx-type":"pay"}],"transactions-root":"","txn-counter":42}"#;
+
+    /// The response of the block endpoint encoded as MessagePack, with the field names of the
+    /// node's codec: a payment, and an application call with a log, state deltas and an inner
+    /// payment. It was encoded for the test, not captured from a node, as none is available to the
+    /// tests.
+    const BLOCK_MSGPACK_HEX: &[&str] = &[
+        "81a5626c6f636b8fa46561726ecd6b81a466656573c42007dacb4b6d9ed141b17576bd459ae6421d486da3d4",
+        "ef2247c409a396b82ea221a466726163ce001fece6a367656eac746573746e65742d76312e30a26768c42048",
+        "63b518a4b3c84ec810f22d4f1081cb0f71f059a7ac20dec62f7f70e5093a22a470726576c420a9b876bf666e",
+        "3072bb684b4230fce934609cd0c9c410a13c72397b3c9eaa113ba570726f746fd95968747470733a2f2f6769",
+        "746875622e636f6d2f616c676f72616e64666f756e646174696f6e2f73706563732f747265652f6162633534",
+        "6637396639616436373964326432326630666239393039666230303563313666386131a3726e64cd03e8a672",
+        "7763616c72ce0007a120a3727764c420ffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+        "ffffffffa473656564c420d1a5832b0820d884d4ff6649ce8147fdcaf639fc0a0712c4015869f50a6c2e95a2",
+        "74632aa27473ce62f19700a374786ec420070707070707070707070707070707070707070707070707070707",
+        "0707070707a474786e739283a3686769c3a3736967c440010101010101010101010101010101010101010101",
+        "01010101010101010101010101010101010101010101010101010101010101010101010101010101010101a3",
+        "74786e87a3616d74cd1388a3666565cd03e8a26676cd03dea26c76cd07c6a3726376c420d5090993b01b6ef4",
+        "7d9b9d39c37a5852cb65be0ef903967d5f61d90830a8e412a3736e64c420e7f0f84d06811df9f31c8d878b11",
+        "55f4671d51a185c200908667f449587068a1a474797065a370617984a2647484a2676481a16b82a2617402a2",
+        "756907a36974789181a374786e86a3616d7464a26676cd03dea26c76cd07c6a3726376c420e7f0f84d06811d",
+        "f9f31c8d878b1155f4671d51a185c200908667f449587068a1a3736e64c420d5090993b01b6ef47d9b9d39c3",
+        "7a5852cb65be0ef903967d5f61d90830a8e412a474797065a3706179a26c64810181a17882a2617401a26273",
+        "a176a26c6791a36c6f67a3686769c3a3736967c4400202020202020202020202020202020202020202020202",
+        "0202020202020202020202020202020202020202020202020202020202020202020202020202020202a37478",
+        "6e8aa46170616191c403616263a46170617491c420d5090993b01b6ef47d9b9d39c37a5852cb65be0ef90396",
+        "7d5f61d90830a8e412a461706964cd04d2a3666565cd07d0a26676cd03dea3677270c4200001020304050607",
+        "08090a0b0c0d0e0f101112131415161718191a1b1c1d1e1fa26c76cd07c6a46e6f7465c4026869a3736e64c4",
+        "20e7f0f84d06811df9f31c8d878b1155f4671d51a185c200908667f449587068a1a474797065a46170706c",
+    ];
+
+    /// Answers a request with a chunked body of 2 MB, larger than the limits of the tests, without
+    /// content length nor end.
+    fn serve_large_chunked() -> TestServer {
+        let mut response =
+            b"HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ntransfer-encoding: chunked\r\n\r\n"
+                .to_vec();
+        let chunk = format!("400\r\n{}\r\n", " ".repeat(0x400));
+        response.extend(chunk.repeat(2048).into_bytes());
+        TestServer::serve(vec![TestResponse::raw(response)])
+    }
+
+    fn block_msgpack() -> Vec<u8> {
+        HEXLOWER
+            .decode(BLOCK_MSGPACK_HEX.concat().as_bytes())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_assets_query() {
+        let body = br#"{"assets":[],"current-round":1}"#.to_vec();
+        let server = TestServer::serve(vec![TestResponse::new("application/json", body)]);
+        let indexer = Client::new(server.url(), vec![]).unwrap();
+        let query = QueryAssets::default()
+            .created_by(
+                "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU"
+                    .parse()
+                    .unwrap(),
+            )
+            .with_name("Test USD")
+            .with_unit("TUSD")
+            .including_all()
+            .with_limit(10);
+
+        indexer.assets(&query).await.unwrap();
+
+        assert_eq!(
+            "GET /v2/assets?creator=47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU&include-all=true&limit=10&name=Test+USD&unit=TUSD HTTP/1.1",
+            server.request_line()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_query_string_matches_request() {
+        let body = br#"{"current-round":1,"transactions":[]}"#.to_vec();
+        let server = TestServer::serve(vec![TestResponse::new("application/json", body)]);
+        let indexer = Client::new(server.url(), vec![]).unwrap();
+        let query = QueryTransaction {
+            address: Some("47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU".to_owned()),
+            limit: Some(10),
+            min_round: Some(Round(5)),
+            note_prefix: Some("a+b/c=".to_owned()),
+            ..QueryTransaction::default()
+        };
+
+        indexer.transactions(&query).await.unwrap();
+
+        assert_eq!(
+            format!("GET /v2/transactions?{} HTTP/1.1", query.to_query_string()),
+            server.request_line()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_assets_query_by_id() {
+        let body = br#"{"assets":[],"current-round":1}"#.to_vec();
+        let server = TestServer::serve(vec![TestResponse::new("application/json", body)]);
+        let indexer = Client::new(server.url(), vec![]).unwrap();
+
+        indexer.assets(&QueryAssets::by_id(1026)).await.unwrap();
+
+        assert_eq!(
+            "GET /v2/assets?asset-id=1026 HTTP/1.1",
+            server.request_line()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_block_msgpack() {
+        let server = TestServer::serve(vec![TestResponse::new(
+            "application/msgpack",
+            block_msgpack(),
+        )]);
+        let indexer = Client::new(server.url(), vec![]).unwrap();
+
+        let block = indexer
+            .block_with_format(Round(1000), ResponseFormat::MessagePack)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            "GET /v2/blocks/1000?format=msgpack HTTP/1.1",
+            server.request_line()
+        );
+        let json = serde_json::from_str::<Block>(BLOCK_JSON).unwrap();
+        assert_eq!(json.genesis_hash, block.genesis_hash);
+        assert_eq!(json.genesis_id, block.genesis_id);
+        assert_eq!(json.previous_block_hash, block.previous_block_hash);
+        assert_eq!(json.seed, block.seed);
+        assert_eq!(Round(1000), block.round);
+        assert_eq!(1660000000, block.timestamp);
+        assert_eq!(Some(42), block.txn_counter);
+        let rewards = block.rewards.unwrap();
+        assert_eq!(
+            "A7NMWS3NT3IUDMLVO26ULGXGIIOUQ3ND2TXSER6EBGRZNOBOUIQXHIBGDE",
+            rewards.fee_sink
+        );
+        assert_eq!(MicroAlgos(27521), rewards.rewards_level);
+        assert_eq!(
+            "https://github.com/algorandfoundation/specs/tree/abc54f79f9ad679d2d22f0fb9909fb005c16f8a1",
+            block.upgrade_state.unwrap().current_protocol
+        );
+
+        let payment = &block.transactions[0];
+        assert_eq!(
+            Transaction {
+                id: "TY7I4BE7F2MRL4ZQQRFAD7YTI5GI7SZUCWQ3YHCKQOSB5JX3BOQQ".to_owned(),
+                genesis_id: Some("testnet-v1.0".to_owned()),
+                signature: Some(TransactionSignature {
+                    logicsig: None,
+                    multisig: None,
+                    sig: Some(BASE64.encode(&[1; 64])),
+                }),
+                ..json.transactions[0].clone()
+            },
+            *payment
+        );
+
+        let call = &block.transactions[1];
+        assert_eq!(
+            "4ILLK6VHSNWDH4PS75U6G2KZ4APM37AVNJKWNAREX26SL7IE23JA",
+            call.id
+        );
+        assert_eq!(Some(1), call.intra_round_offset);
+        assert_eq!(Some("aGk=".to_owned()), call.note);
+        let application = call.application_transaction.as_ref().unwrap();
+        assert_eq!(1234, application.application_id);
+        assert_eq!(OnCompletion::Noop, application.on_completion);
+        assert_eq!(vec!["YWJj".to_owned()], application.application_args);
+        assert_eq!(vec![b"log".to_vec()], call.logs);
+        let global = &call.global_state_delta.as_ref().unwrap()[0];
+        assert_eq!("aw==", global.key);
+        assert_eq!(Some(7), global.value.uint);
+        let local = &call.local_state_delta[0];
+        assert_eq!(
+            "2UEQTE5QDNXPI7M3TU44G6SYKLFWLPQO7EBZM7K7MHMQQMFI4QJPLHQFHM",
+            local.address
+        );
+        assert_eq!(Some("dg==".to_owned()), local.delta[0].value.bytes);
+        let inner = &call.inner_txns[0];
+        assert_eq!("", inner.id);
+        assert_eq!(None, inner.genesis_id);
+        assert_eq!(
+            MicroAlgos(100),
+            inner.payment_transaction.as_ref().unwrap().amount
+        );
+    }
+
+    #[test]
+    fn test_decode_msgpack_block_without_response() {
+        let body = block_msgpack();
+        // Skips the map of the response and its `block` key
+        let block = msgpack::decode_block(&body[7..]).unwrap();
+
+        assert_eq!(Round(1000), block.round);
+        assert_eq!(2, block.transactions.len());
+    }
+
+    #[tokio::test]
+    async fn test_block_msgpack_from_config() {
+        let server = TestServer::serve(vec![TestResponse::new(
+            "application/msgpack",
+            block_msgpack(),
+        )]);
+        let config = ClientConfig {
+            response_format: ResponseFormat::MessagePack,
+            ..ClientConfig::default()
+        };
+        let indexer = Client::with_config(server.url(), vec![], &config).unwrap();
+
+        let block = indexer.block(Round(1000)).await.unwrap();
+
+        assert_eq!(
+            "GET /v2/blocks/1000?format=msgpack HTTP/1.1",
+            server.request_line()
+        );
+        assert_eq!(Round(1000), block.round);
+        assert_eq!(2, block.transactions.len());
+    }
+
+    #[tokio::test]
+    async fn test_block_msgpack_falls_back_to_json() {
+        let server = TestServer::serve(vec![TestResponse::new(
+            "application/json",
+            BLOCK_JSON.as_bytes().to_vec(),
+        )]);
+        let indexer = Client::new(server.url(), vec![]).unwrap();
+
+        let block = indexer
+            .block_with_format(Round(1000), ResponseFormat::MessagePack)
+            .await
+            .unwrap();
+
+        assert_eq!(serde_json::from_str::<Block>(BLOCK_JSON).unwrap(), block);
+    }
+
+    #[tokio::test]
+    async fn test_block_json_by_default() {
+        let server = TestServer::serve(vec![TestResponse::new(
+            "application/json",
+            BLOCK_JSON.as_bytes().to_vec(),
+        )]);
+        let indexer = Client::new(server.url(), vec![]).unwrap();
+
+        indexer.block(Round(1000)).await.unwrap();
+
+        assert_eq!("GET /v2/blocks/1000 HTTP/1.1", server.request_line());
+    }
+
+    #[tokio::test]
+    async fn test_decode_error_path_and_body() {
+        let body = BLOCK_JSON.replace(r#""fee":1000"#, r#""fee":"1000""#);
+        let server = TestServer::serve(vec![TestResponse::new(
+            "application/json",
+            body.as_bytes().to_vec(),
+        )]);
+        let config = ClientConfig {
+            debug_body_limit: Some(32),
+            ..ClientConfig::default()
+        };
+        let indexer = Client::with_config(server.url(), vec![], &config).unwrap();
+
+        match indexer.block(Round(1000)).await {
+            Err(ClientError::Request(RequestError {
+                details: RequestErrorDetails::Decode { path, body, .. },
+                ..
+            })) => {
+                assert_eq!(Some("transactions[0].fee".to_owned()), path);
+                assert_eq!(
+                    Some(r#"{"genesis-hash":"SGO1GKSzyE7IEPI..."#.to_owned()),
+                    body
+                );
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_decode_error_without_body_by_default() {
+        let body = BLOCK_JSON.replace(r#""round":1000"#, r#""round":-1"#);
+        let server = TestServer::serve(vec![TestResponse::new(
+            "application/json",
+            body.as_bytes().to_vec(),
+        )]);
+        let indexer = Client::new(server.url(), vec![]).unwrap();
+
+        match indexer.block(Round(1000)).await {
+            Err(ClientError::Request(RequestError {
+                details: RequestErrorDetails::Decode { path, body, .. },
+                ..
+            })) => {
+                assert_eq!(Some("round".to_owned()), path);
+                assert_eq!(None, body);
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_custom_decoder() {
+        /// Rejects the blocks without transactions.
+        #[derive(Debug)]
+        struct NonEmptyBlockDecoder;
+
+        impl ResponseDecoder for NonEmptyBlockDecoder {
+            fn decode(
+                &self,
+                body: &[u8],
+                target: &mut dyn DecodeTarget,
+            ) -> Result<(), DecodeError> {
+                let block: serde_json::Value = serde_json::from_slice(body)?;
+                if block["transactions"].as_array().is_none_or(Vec::is_empty) {
+                    return Err(DecodeError::new("empty block"));
+                }
+                target.deserialize(&mut <dyn erased_serde::Deserializer>::erase(block))
+            }
+        }
+
+        let config = ClientConfig {
+            decoder: Decoder::new(NonEmptyBlockDecoder),
+            ..ClientConfig::default()
+        };
+        let server = TestServer::serve(vec![TestResponse::new(
+            "application/json",
+            BLOCK_JSON.as_bytes().to_vec(),
+        )]);
+        let indexer = Client::with_config(server.url(), vec![], &config).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Block>(BLOCK_JSON).unwrap(),
+            indexer.block(Round(1000)).await.unwrap()
+        );
+
+        let body = BLOCK_JSON.replace(r#""transactions":[{"#, r#""transactions":[],"x":[{"#);
+        let server = TestServer::serve(vec![TestResponse::new(
+            "application/json",
+            body.into_bytes(),
+        )]);
+        let indexer = Client::with_config(server.url(), vec![], &config).unwrap();
+        match indexer.block(Round(1000)).await {
+            Err(ClientError::Request(RequestError {
+                details:
+                    RequestErrorDetails::Decode {
+                        description, path, ..
+                    },
+                ..
+            })) => {
+                assert_eq!("empty block", description);
+                assert_eq!(None, path);
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_max_response_bytes() {
+        let config = ClientConfig {
+            max_response_bytes: Some(BLOCK_JSON.len()),
+            ..ClientConfig::default()
+        };
+        let server = TestServer::serve(vec![TestResponse::new(
+            "application/json",
+            BLOCK_JSON.as_bytes().to_vec(),
+        )]);
+        let indexer = Client::with_config(server.url(), vec![], &config).unwrap();
+        assert!(indexer.block(Round(1000)).await.is_ok());
+
+        let config = ClientConfig {
+            max_response_bytes: Some(BLOCK_JSON.len() - 1),
+            ..ClientConfig::default()
+        };
+        let server = TestServer::serve(vec![TestResponse::new(
+            "application/json",
+            BLOCK_JSON.as_bytes().to_vec(),
+        )]);
+        let indexer = Client::with_config(server.url(), vec![], &config).unwrap();
+        match indexer.block(Round(1000)).await {
+            Err(ClientError::Request(RequestError {
+                details: RequestErrorDetails::ResponseTooLarge { limit },
+                ..
+            })) => assert_eq!(BLOCK_JSON.len() - 1, limit),
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_max_response_bytes_without_content_length() {
+        let config = ClientConfig {
+            max_response_bytes: Some(1_000_000),
+            ..ClientConfig::default()
+        };
+        let server = serve_large_chunked();
+        let indexer = Client::with_config(server.url(), vec![], &config).unwrap();
+
+        // the body is never completed: it would fail as incomplete if it was buffered
+        match indexer.block(Round(1000)).await {
+            Err(ClientError::Request(RequestError {
+                details: RequestErrorDetails::ResponseTooLarge { limit },
+                ..
+            })) => assert_eq!(1_000_000, limit),
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_applications_creator_query() {
+        let server = TestServer::serve(vec![TestResponse::json(
+            r#"{"applications":[],"current-round":1}"#,
+        )]);
+
+        let indexer = Client::new(server.url(), vec![]).unwrap();
+        let query = QueryApplications {
+            application_id: Some(5),
+            creator: Some(
+                "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU"
+                    .parse()
+                    .unwrap(),
+            ),
+            ..QueryApplications::default()
+        };
+        indexer.applications(&query).await.unwrap();
+
+        assert_eq!(
+            "GET /v2/applications?application-id=5&creator=47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU HTTP/1.1",
+            server.request_line()
+        );
+    }
+}
@@ -0,0 +1,596 @@
+//! Decoding of the blocks encoded as MessagePack, which use the field names of the node's codec
+//! (e.g. `rnd`, `txns`, `snd`) and raw bytes, instead of the field names and the base32 / base64
+//! strings of the JSON.
+
+use algonaut_core::{Address, MicroAlgos, Round};
+use algonaut_crypto::HashDigest;
+use algonaut_model::indexer::v2::{
+    AccountStateDelta, AssetParams, Block, BlockRewards, BlockUpgradeState, BlockUpgradeVote,
+    EvalDelta, EvalDeltaKeyValue, OnCompletion, StateDelta, StateSchema, Transaction,
+    TransactionApplication, TransactionAssetConfig, TransactionAssetFreeze,
+    TransactionAssetTransfer, TransactionKeyreg, TransactionPayment, TransactionSignature,
+    TransactionSignatureLogicsig, TransactionSignatureMultisig,
+    TransactionSignatureMultisigSubsignature,
+};
+use data_encoding::{BASE32_NOPAD, BASE64};
+use rmpv::Value;
+use serde::Deserialize;
+use serde_bytes::ByteBuf;
+use sha2::{Digest, Sha512_256};
+use std::collections::BTreeMap;
+use std::convert::TryInto;
+
+/// Decodes a block encoded as MessagePack: either the response of the block endpoint of the node,
+/// with the block under `block` (and its certificate under `cert`), or the block itself.
+///
+/// The ids of the transactions are computed from their encodings, with the genesis hash (and the
+/// genesis id, if the transaction had it) restored. The inner transactions have no id, and the
+/// state proof and heartbeat fields aren't decoded.
+pub(super) fn decode_block(body: &[u8]) -> Result<Block, String> {
+    let value = rmpv::decode::read_value(&mut &body[..]).map_err(|e| e.to_string())?;
+    let value = match value {
+        Value::Map(entries) => match entries
+            .iter()
+            .position(|(key, _)| key.as_str() == Some("block"))
+        {
+            Some(index) => entries
+                .into_iter()
+                .nth(index)
+                .map(|(_, block)| block)
+                .unwrap(),
+            None => Value::Map(entries),
+        },
+        _ => return Err("the block isn't a map".to_owned()),
+    };
+    let block: CodecBlock = rmpv::ext::from_value(value).map_err(|e| e.to_string())?;
+    block.into_block()
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct CodecBlock {
+    earn: u64,
+    #[serde(with = "serde_bytes")]
+    fees: Vec<u8>,
+    frac: u64,
+    gen: String,
+    #[serde(with = "serde_bytes")]
+    gh: Vec<u8>,
+    nextbefore: Option<u64>,
+    nextproto: Option<String>,
+    nextswitch: Option<u64>,
+    nextyes: Option<u64>,
+    #[serde(with = "serde_bytes")]
+    prev: Vec<u8>,
+    proto: String,
+    rate: u64,
+    rnd: u64,
+    rwcalr: u64,
+    #[serde(with = "serde_bytes")]
+    rwd: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    seed: Vec<u8>,
+    tc: Option<u64>,
+    ts: u64,
+    #[serde(with = "serde_bytes")]
+    txn: Vec<u8>,
+    txns: Vec<CodecSignedTxn>,
+    upgradedelay: Option<u64>,
+    upgradeprop: Option<String>,
+    upgradeyes: bool,
+}
+
+/// A signed transaction with the changes it applied: `SignedTxnInBlock`, or `SignedTxnWithAD` for
+/// the inner transactions.
+#[derive(Deserialize)]
+struct CodecSignedTxn {
+    txn: Value,
+    #[serde(default, with = "serde_bytes")]
+    sig: Vec<u8>,
+    msig: Option<CodecMultisig>,
+    lsig: Option<CodecLogicSig>,
+    #[serde(default, with = "serde_bytes")]
+    sgnr: Vec<u8>,
+    #[serde(default)]
+    hgi: bool,
+    ca: Option<u64>,
+    aca: Option<u64>,
+    rs: Option<u64>,
+    rr: Option<u64>,
+    rc: Option<u64>,
+    caid: Option<u64>,
+    apid: Option<u64>,
+    #[serde(default)]
+    dt: CodecEvalDelta,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct CodecMultisig {
+    subsig: Vec<CodecSubsig>,
+    thr: Option<u64>,
+    v: Option<u64>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct CodecSubsig {
+    #[serde(with = "serde_bytes")]
+    pk: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    s: Vec<u8>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct CodecLogicSig {
+    #[serde(with = "serde_bytes")]
+    l: Vec<u8>,
+    arg: Vec<ByteBuf>,
+    #[serde(with = "serde_bytes")]
+    sig: Vec<u8>,
+    msig: Option<CodecMultisig>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct CodecEvalDelta {
+    gd: BTreeMap<ByteBuf, CodecValueDelta>,
+    ld: BTreeMap<u64, BTreeMap<ByteBuf, CodecValueDelta>>,
+    lg: Vec<ByteBuf>,
+    itx: Vec<CodecSignedTxn>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct CodecValueDelta {
+    at: u64,
+    #[serde(with = "serde_bytes")]
+    bs: Vec<u8>,
+    ui: u64,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct CodecTxn {
+    #[serde(rename = "type")]
+    txn_type: String,
+    #[serde(with = "serde_bytes")]
+    snd: Vec<u8>,
+    fee: u64,
+    fv: u64,
+    lv: u64,
+    #[serde(with = "serde_bytes")]
+    note: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    grp: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    lx: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    rekey: Vec<u8>,
+    // Payment
+    #[serde(with = "serde_bytes")]
+    rcv: Vec<u8>,
+    amt: u64,
+    #[serde(with = "serde_bytes")]
+    close: Vec<u8>,
+    // Key registration
+    #[serde(with = "serde_bytes")]
+    votekey: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    selkey: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    sprfkey: Vec<u8>,
+    votefst: Option<u64>,
+    votelst: Option<u64>,
+    votekd: Option<u64>,
+    nonpart: bool,
+    // Asset configuration
+    caid: u64,
+    apar: Option<CodecAssetParams>,
+    // Asset transfer
+    xaid: u64,
+    aamt: u64,
+    #[serde(with = "serde_bytes")]
+    asnd: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    arcv: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    aclose: Vec<u8>,
+    // Asset freeze
+    #[serde(with = "serde_bytes")]
+    fadd: Vec<u8>,
+    faid: u64,
+    afrz: bool,
+    // Application call
+    apid: u64,
+    apan: u64,
+    apat: Vec<ByteBuf>,
+    apaa: Vec<ByteBuf>,
+    #[serde(with = "serde_bytes")]
+    apap: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    apsu: Vec<u8>,
+    apfa: Vec<u64>,
+    apas: Vec<u64>,
+    apgs: Option<CodecStateSchema>,
+    apls: Option<CodecStateSchema>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct CodecAssetParams {
+    t: u64,
+    dc: u64,
+    df: bool,
+    un: String,
+    an: String,
+    au: String,
+    #[serde(with = "serde_bytes")]
+    am: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    m: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    r: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    f: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    c: Vec<u8>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct CodecStateSchema {
+    nui: u64,
+    nbs: u64,
+}
+
+/// The fields of the block needed to decode its transactions.
+struct BlockContext<'a> {
+    genesis_id: &'a str,
+    genesis_hash: &'a [u8],
+    round: u64,
+    timestamp: u64,
+}
+
+impl CodecBlock {
+    fn into_block(self) -> Result<Block, String> {
+        let context = BlockContext {
+            genesis_id: &self.gen,
+            genesis_hash: &self.gh,
+            round: self.rnd,
+            timestamp: self.ts,
+        };
+        let transactions = self
+            .txns
+            .into_iter()
+            .enumerate()
+            .map(|(offset, txn)| txn.into_transaction(&context, offset as u64, true))
+            .collect::<Result<_, _>>()?;
+        Ok(Block {
+            genesis_hash: hash(&self.gh, "gh")?,
+            genesis_id: self.gen.clone(),
+            previous_block_hash: BASE64.encode(&self.prev),
+            rewards: Some(BlockRewards {
+                fee_sink: address(&self.fees, "fees")?,
+                rewards_calculation_round: MicroAlgos(self.rwcalr),
+                rewards_level: MicroAlgos(self.earn),
+                rewards_pool: address(&self.rwd, "rwd")?,
+                rewards_rate: MicroAlgos(self.rate),
+                rewards_residue: MicroAlgos(self.frac),
+            }),
+            round: Round(self.rnd),
+            seed: BASE64.encode(&self.seed),
+            timestamp: self.ts,
+            transactions,
+            transactions_root: BASE64.encode(&self.txn),
+            txn_counter: self.tc,
+            upgrade_state: Some(BlockUpgradeState {
+                current_protocol: self.proto,
+                next_protocol: self.nextproto,
+                next_protocol_approvals: self.nextyes,
+                next_protocol_switch_on: self.nextswitch.map(Round),
+                next_protocol_vote_before: self.nextbefore.map(Round),
+            }),
+            upgrade_vote: Some(BlockUpgradeVote {
+                upgrade_approve: Some(self.upgradeyes),
+                upgrade_delay: self.upgradedelay,
+                upgrade_propose: self.upgradeprop,
+            }),
+        })
+    }
+}
+
+impl CodecSignedTxn {
+    /// Decodes the transaction at this offset of the block. Only the transactions of the block
+    /// itself (`top_level`) get an id.
+    fn into_transaction(
+        self,
+        context: &BlockContext,
+        offset: u64,
+        top_level: bool,
+    ) -> Result<Transaction, String> {
+        let genesis_id = (top_level && self.hgi).then_some(context.genesis_id);
+        let id = match top_level {
+            true => transaction_id(&self.txn, genesis_id, context.genesis_hash)?,
+            false => String::new(),
+        };
+        let txn: CodecTxn = rmpv::ext::from_value(self.txn).map_err(|e| e.to_string())?;
+        let sender = address(&txn.snd, "snd")?;
+        let accounts = txn
+            .apat
+            .iter()
+            .map(|account| address_bytes(account, "apat"))
+            .collect::<Result<Vec<_>, _>>()?;
+        let local_state_delta = self
+            .dt
+            .ld
+            .into_iter()
+            .map(|(index, delta)| {
+                let address = match index {
+                    0 => sender.clone(),
+                    _ => accounts
+                        .get(index as usize - 1)
+                        .ok_or_else(|| format!("no account {} for the local state delta", index))?
+                        .to_string(),
+                };
+                Ok(AccountStateDelta {
+                    address,
+                    delta: state_delta(delta),
+                })
+            })
+            .collect::<Result<_, String>>()?;
+        let inner_txns = self
+            .dt
+            .itx
+            .into_iter()
+            .map(|inner| inner.into_transaction(context, offset, false))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Transaction {
+            application_transaction: match txn.txn_type.as_str() {
+                "appl" => Some(TransactionApplication {
+                    accounts,
+                    application_args: txn.apaa.iter().map(|arg| BASE64.encode(arg)).collect(),
+                    application_id: txn.apid,
+                    approval_program: non_empty(&txn.apap).map(|p| BASE64.encode(p)),
+                    clear_state_program: non_empty(&txn.apsu).map(|p| BASE64.encode(p)),
+                    foreign_apps: txn.apfa,
+                    foreign_assets: txn.apas,
+                    global_state_schema: txn.apgs.map(state_schema),
+                    local_state_schema: txn.apls.map(state_schema),
+                    on_completion: on_completion(txn.apan),
+                }),
+                _ => None,
+            },
+            asset_config_transaction: match txn.txn_type.as_str() {
+                "acfg" => Some(TransactionAssetConfig {
+                    asset_id: Some(txn.caid),
+                    params: txn.apar.map(asset_params).transpose()?,
+                }),
+                _ => None,
+            },
+            asset_freeze_transaction: match txn.txn_type.as_str() {
+                "afrz" => Some(TransactionAssetFreeze {
+                    address: address(&txn.fadd, "fadd")?,
+                    asset_id: txn.faid,
+                    new_freeze_status: txn.afrz,
+                }),
+                _ => None,
+            },
+            asset_transfer_transaction: match txn.txn_type.as_str() {
+                "axfer" => Some(TransactionAssetTransfer {
+                    amount: txn.aamt,
+                    asset_id: txn.xaid,
+                    close_amount: non_empty(&txn.aclose).and(self.aca),
+                    close_to: optional_address(&txn.aclose, "aclose")?,
+                    receiver: address(&txn.arcv, "arcv")?,
+                    sender: optional_address(&txn.asnd, "asnd")?,
+                }),
+                _ => None,
+            },
+            auth_addr: optional_address(&self.sgnr, "sgnr")?,
+            close_rewards: self.rc.map(MicroAlgos),
+            closing_amount: self.ca.map(MicroAlgos),
+            confirmed_round: Some(Round(context.round)),
+            created_application_index: self.apid,
+            created_asset_index: self.caid,
+            fee: txn.fee,
+            first_valid: txn.fv,
+            genesis_hash: hash(context.genesis_hash, "gh")?,
+            genesis_id: genesis_id.map(str::to_owned),
+            global_state_delta: match self.dt.gd.is_empty() {
+                true => None,
+                false => Some(state_delta(self.dt.gd)),
+            },
+            group: non_empty(&txn.grp).map(|group| BASE64.encode(group)),
+            heartbeat_transaction: None,
+            id,
+            inner_txns,
+            intra_round_offset: Some(offset),
+            keyreg_transaction: match txn.txn_type.as_str() {
+                "keyreg" => Some(TransactionKeyreg {
+                    non_participation: Some(txn.nonpart),
+                    selection_participation_key: non_empty(&txn.selkey).map(|k| BASE64.encode(k)),
+                    vote_first_valid: txn.votefst,
+                    vote_key_dilution: txn.votekd,
+                    vote_last_valid: txn.votelst,
+                    vote_participation_key: non_empty(&txn.votekey).map(|k| BASE64.encode(k)),
+                    state_proof_key: non_empty(&txn.sprfkey).map(|k| BASE64.encode(k)),
+                }),
+                _ => None,
+            },
+            last_valid: Round(txn.lv),
+            lease: non_empty(&txn.lx)
+                .map(|lease| hash(lease, "lx"))
+                .transpose()?,
+            local_state_delta,
+            logs: self.dt.lg.into_iter().map(ByteBuf::into_vec).collect(),
+            note: non_empty(&txn.note).map(|note| BASE64.encode(note)),
+            payment_transaction: match txn.txn_type.as_str() {
+                "pay" => Some(TransactionPayment {
+                    amount: MicroAlgos(txn.amt),
+                    close_amount: non_empty(&txn.close).and(self.ca).map(MicroAlgos),
+                    close_remainder_to: optional_address(&txn.close, "close")?,
+                    receiver: address(&txn.rcv, "rcv")?,
+                }),
+                _ => None,
+            },
+            receiver_rewards: self.rr.map(MicroAlgos),
+            rekey_to: optional_address(&txn.rekey, "rekey")?,
+            round_time: Some(context.timestamp),
+            sender,
+            sender_rewards: self.rs,
+            signature: signature(self.sig, self.msig, self.lsig),
+            state_proof_transaction: None,
+            tx_type: txn.txn_type.into(),
+        })
+    }
+}
+
+/// The id of the transaction: the hash of its canonical encoding, with the fields removed from
+/// the transactions of a block restored.
+fn transaction_id(
+    txn: &Value,
+    genesis_id: Option<&str>,
+    genesis_hash: &[u8],
+) -> Result<String, String> {
+    let mut entries = match txn {
+        Value::Map(entries) => entries.clone(),
+        _ => return Err("the transaction `txn` isn't a map".to_owned()),
+    };
+    let restored = [
+        ("gen", genesis_id.map(Value::from)),
+        ("gh", Some(Value::Binary(genesis_hash.to_vec()))),
+    ];
+    for (key, value) in restored {
+        if let Some(value) = value {
+            if !entries.iter().any(|(k, _)| k.as_str() == Some(key)) {
+                entries.push((Value::from(key), value));
+            }
+        }
+    }
+    // The canonical encoding has its keys sorted.
+    entries.sort_by(|(a, _), (b, _)| a.as_str().cmp(&b.as_str()));
+    let mut encoded = b"TX".to_vec();
+    rmpv::encode::write_value(&mut encoded, &Value::Map(entries)).map_err(|e| e.to_string())?;
+    Ok(BASE32_NOPAD.encode(&Sha512_256::digest(&encoded)))
+}
+
+fn signature(
+    sig: Vec<u8>,
+    msig: Option<CodecMultisig>,
+    lsig: Option<CodecLogicSig>,
+) -> Option<TransactionSignature> {
+    if sig.is_empty() && msig.is_none() && lsig.is_none() {
+        return None;
+    }
+    Some(TransactionSignature {
+        logicsig: lsig.map(|lsig| TransactionSignatureLogicsig {
+            args: lsig.arg.iter().map(|arg| BASE64.encode(arg)).collect(),
+            logic: BASE64.encode(&lsig.l),
+            multisig_signature: lsig.msig.map(multisig),
+            signature: non_empty(&lsig.sig).map(|sig| BASE64.encode(sig)),
+        }),
+        multisig: msig.map(multisig),
+        sig: non_empty(&sig).map(|sig| BASE64.encode(sig)),
+    })
+}
+
+fn multisig(msig: CodecMultisig) -> TransactionSignatureMultisig {
+    TransactionSignatureMultisig {
+        subsignature: msig
+            .subsig
+            .iter()
+            .map(|subsig| TransactionSignatureMultisigSubsignature {
+                public_key: non_empty(&subsig.pk).map(|pk| BASE64.encode(pk)),
+                signature: non_empty(&subsig.s).map(|s| BASE64.encode(s)),
+            })
+            .collect(),
+        threshold: msig.thr,
+        version: msig.v,
+    }
+}
+
+fn asset_params(params: CodecAssetParams) -> Result<AssetParams, String> {
+    Ok(AssetParams {
+        clawback: optional_address_bytes(&params.c, "c")?,
+        creator: Address([0; 32]),
+        decimals: params.dc,
+        default_frozen: Some(params.df),
+        freeze: optional_address_bytes(&params.f, "f")?,
+        manager: optional_address_bytes(&params.m, "m")?,
+        metadata_hash: params.am,
+        name: Some(params.an).filter(|name| !name.is_empty()),
+        reserve: optional_address(&params.r, "r")?,
+        total: params.t,
+        unit_name: Some(params.un).filter(|unit| !unit.is_empty()),
+        url: Some(params.au).filter(|url| !url.is_empty()),
+    })
+}
+
+fn state_schema(schema: CodecStateSchema) -> StateSchema {
+    StateSchema {
+        num_byte_slice: schema.nbs,
+        num_uint: schema.nui,
+    }
+}
+
+fn state_delta(delta: BTreeMap<ByteBuf, CodecValueDelta>) -> StateDelta {
+    delta
+        .into_iter()
+        .map(|(key, value)| EvalDeltaKeyValue {
+            key: BASE64.encode(&key),
+            value: EvalDelta {
+                action: value.at,
+                // 1 sets bytes, 2 sets an uint, 3 deletes the key
+                bytes: (value.at == 1).then(|| BASE64.encode(&value.bs)),
+                uint: (value.at == 2).then_some(value.ui),
+            },
+        })
+        .collect()
+}
+
+fn on_completion(apan: u64) -> OnCompletion {
+    match apan {
+        0 => OnCompletion::Noop,
+        1 => OnCompletion::Optin,
+        2 => OnCompletion::Closeout,
+        3 => OnCompletion::Clear,
+        4 => OnCompletion::Update,
+        5 => OnCompletion::Delete,
+        _ => OnCompletion::Unknown(apan.to_string()),
+    }
+}
+
+fn non_empty(bytes: &[u8]) -> Option<&[u8]> {
+    Some(bytes).filter(|bytes| !bytes.is_empty())
+}
+
+fn address_bytes(bytes: &[u8], field: &str) -> Result<Address, String> {
+    bytes
+        .try_into()
+        .map(Address)
+        .map_err(|_| format!("`{}` isn't an address: {} bytes", field, bytes.len()))
+}
+
+fn optional_address_bytes(bytes: &[u8], field: &str) -> Result<Option<Address>, String> {
+    non_empty(bytes)
+        .map(|bytes| address_bytes(bytes, field))
+        .transpose()
+}
+
+fn address(bytes: &[u8], field: &str) -> Result<String, String> {
+    address_bytes(bytes, field).map(|address| address.to_string())
+}
+
+fn optional_address(bytes: &[u8], field: &str) -> Result<Option<String>, String> {
+    Ok(optional_address_bytes(bytes, field)?.map(|address| address.to_string()))
+}
+
+fn hash(bytes: &[u8], field: &str) -> Result<HashDigest, String> {
+    bytes
+        .try_into()
+        .map(HashDigest)
+        .map_err(|_| format!("`{}` isn't a hash: {} bytes", field, bytes.len()))
+}
@@ -1,6 +1,8 @@
-use crate::extensions::reqwest::ResponseExt;
-use crate::Headers;
-use crate::{error::ClientError, extensions::reqwest::to_header_map};
+use crate::decoder::Decoder;
+use crate::extensions::http::{RequestBuilderExt, ResponseExt};
+use crate::retry::Retry;
+use crate::{error::ClientError, extensions::http::to_header_map};
+use crate::{ClientConfig, Headers, HttpClient, RedactedHeaders};
 use algonaut_core::{Address, MultisigSignature};
 use algonaut_crypto::{Ed25519PublicKey, MasterDerivationKey};
 use algonaut_model::kmd::v1::{
@@ -16,23 +18,52 @@ use algonaut_model::kmd::v1::{
     SignMultisigTransactionRequest, SignMultisigTransactionResponse, SignTransactionRequest,
     SignTransactionResponse, VersionsResponse,
 };
-use reqwest::header::HeaderMap;
-use reqwest::Url;
+use http::header::HeaderMap;
+use std::fmt;
+use url::Url;
 
-#[derive(Debug)]
 /// Client for interacting with the key management daemon
 pub struct Client {
     pub(super) address: String,
-    pub(super) http_client: reqwest::Client,
+    pub(super) http_client: HttpClient,
     pub(super) headers: HeaderMap,
+    pub(super) retry: Retry,
+    pub(super) debug_body_limit: Option<usize>,
+    pub(super) decoder: Decoder,
+}
+
+/// The values of the sensitive headers (e.g. the API token) are redacted.
+impl fmt::Debug for Client {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Client")
+            .field("address", &self.address)
+            .field("http_client", &self.http_client)
+            .field("headers", &RedactedHeaders::of(&self.headers))
+            .field("retry", &self.retry)
+            .field("debug_body_limit", &self.debug_body_limit)
+            .field("decoder", &self.decoder)
+            .finish()
+    }
 }
 
 impl Client {
     pub fn new(address: &str, headers: Headers) -> Result<Client, ClientError> {
+        Self::with_config(address, headers, &ClientConfig::default())
+    }
+
+    /// Builds a client with custom settings of the HTTP client (e.g. the connection pool).
+    pub fn with_config(
+        address: &str,
+        headers: Headers,
+        config: &ClientConfig,
+    ) -> Result<Client, ClientError> {
         Ok(Client {
             address: Url::parse(address)?.as_ref().into(),
-            http_client: reqwest::Client::new(),
+            http_client: config.build_http_client()?,
             headers: to_header_map(headers)?,
+            retry: config.retry(),
+            debug_body_limit: config.debug_body_limit,
+            decoder: config.decoder.clone(),
         })
     }
 
@@ -42,11 +73,11 @@ impl Client {
             .get(&format!("{}versions", self.address))
             .header("Accept", "application/json")
             .headers(self.headers.clone())
-            .send()
+            .send_retrying(&self.retry, "kmd.versions")
             .await?
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
         Ok(response)
     }
@@ -57,11 +88,11 @@ impl Client {
             .get(&format!("{}v1/wallets", self.address))
             .header("Accept", "application/json")
             .headers(self.headers.clone())
-            .send()
+            .send_retrying(&self.retry, "kmd.list_wallets")
             .await?
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
         Ok(response)
     }
@@ -86,11 +117,11 @@ impl Client {
             .header("Accept", "application/json")
             .headers(self.headers.clone())
             .json(&req)
-            .send()
+            .send_retrying(&self.retry, "kmd.create_wallet")
             .await?
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
         Ok(response)
     }
@@ -110,11 +141,11 @@ impl Client {
             .header("Accept", "application/json")
             .headers(self.headers.clone())
             .json(&req)
-            .send()
+            .send_retrying(&self.retry, "kmd.init_wallet_handle")
             .await?
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
         Ok(response)
     }
@@ -132,11 +163,11 @@ impl Client {
             .header("Accept", "application/json")
             .headers(self.headers.clone())
             .json(&req)
-            .send()
+            .send_retrying(&self.retry, "kmd.release_wallet_handle")
             .await?
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
         Ok(response)
     }
@@ -154,11 +185,11 @@ impl Client {
             .header("Accept", "application/json")
             .headers(self.headers.clone())
             .json(&req)
-            .send()
+            .send_retrying(&self.retry, "kmd.renew_wallet_handle")
             .await?
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
         Ok(response)
     }
@@ -180,11 +211,11 @@ impl Client {
             .header("Accept", "application/json")
             .headers(self.headers.clone())
             .json(&req)
-            .send()
+            .send_retrying(&self.retry, "kmd.rename_wallet")
             .await?
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
         Ok(response)
     }
@@ -202,11 +233,11 @@ impl Client {
             .header("Accept", "application/json")
             .headers(self.headers.clone())
             .json(&req)
-            .send()
+            .send_retrying(&self.retry, "kmd.get_wallet_info")
             .await?
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
         Ok(response)
     }
@@ -226,11 +257,11 @@ impl Client {
             .header("Accept", "application/json")
             .headers(self.headers.clone())
             .json(&req)
-            .send()
+            .send_retrying(&self.retry, "kmd.export_master_derivation_key")
             .await?
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
         Ok(response)
     }
@@ -250,11 +281,11 @@ impl Client {
             .header("Accept", "application/json")
             .headers(self.headers.clone())
             .json(&req)
-            .send()
+            .send_retrying(&self.retry, "kmd.import_key")
             .await?
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
         Ok(response)
     }
@@ -276,11 +307,11 @@ impl Client {
             .header("Accept", "application/json")
             .headers(self.headers.clone())
             .json(&req)
-            .send()
+            .send_retrying(&self.retry, "kmd.export_key")
             .await?
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
         Ok(response)
     }
@@ -299,11 +330,11 @@ impl Client {
             .header("Accept", "application/json")
             .headers(self.headers.clone())
             .json(&req)
-            .send()
+            .send_retrying(&self.retry, "kmd.generate_key")
             .await?
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
         Ok(response)
     }
@@ -325,11 +356,11 @@ impl Client {
             .header("Accept", "application/json")
             .headers(self.headers.clone())
             .json(&req)
-            .send()
+            .send_retrying(&self.retry, "kmd.delete_key")
             .await?
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
         Ok(response)
     }
@@ -344,11 +375,11 @@ impl Client {
             .header("Accept", "application/json")
             .headers(self.headers.clone())
             .json(&req)
-            .send()
+            .send_retrying(&self.retry, "kmd.list_keys")
             .await?
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
         Ok(response)
     }
@@ -370,11 +401,11 @@ impl Client {
             .header("Accept", "application/json")
             .headers(self.headers.clone())
             .json(&req)
-            .send()
+            .send_retrying(&self.retry, "kmd.sign_transaction")
             .await?
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
         Ok(response)
     }
@@ -392,11 +423,11 @@ impl Client {
             .header("Accept", "application/json")
             .headers(self.headers.clone())
             .json(&req)
-            .send()
+            .send_retrying(&self.retry, "kmd.list_multisig")
             .await?
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
         Ok(response)
     }
@@ -420,11 +451,11 @@ impl Client {
             .header("Accept", "application/json")
             .headers(self.headers.clone())
             .json(&req)
-            .send()
+            .send_retrying(&self.retry, "kmd.import_multisig")
             .await?
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
         Ok(response)
     }
@@ -444,11 +475,11 @@ impl Client {
             .header("Accept", "application/json")
             .headers(self.headers.clone())
             .json(&req)
-            .send()
+            .send_retrying(&self.retry, "kmd.export_multisig")
             .await?
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
         Ok(response)
     }
@@ -470,11 +501,11 @@ impl Client {
             .header("Accept", "application/json")
             .headers(self.headers.clone())
             .json(&req)
-            .send()
+            .send_retrying(&self.retry, "kmd.delete_multisig")
             .await?
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
         Ok(response)
     }
@@ -500,11 +531,11 @@ impl Client {
             .header("Accept", "application/json")
             .headers(self.headers.clone())
             .json(&req)
-            .send()
+            .send_retrying(&self.retry, "kmd.sign_multisig_transaction")
             .await?
             .http_error_for_status()
             .await?
-            .json()
+            .decode_json(&self.decoder, self.debug_body_limit)
             .await?;
         Ok(response)
     }
@@ -1,5 +1,9 @@
 /// Algorand protocol daemon
 pub mod algod;
+/// Settings of the HTTP client
+mod config;
+/// Pluggable decoding of the responses
+mod decoder;
 ///
 pub mod error;
 ///
@@ -8,7 +12,25 @@ mod extensions;
 pub mod indexer;
 /// Key management daemon
 pub mod kmd;
+/// Metadata of the responses (e.g. rate limits)
+pub mod meta;
+/// Redaction of the secrets (e.g. the API tokens) from the debug output
+mod redact;
+/// Retries of the requests failing with transient errors
+mod retry;
+#[cfg(any(test, feature = "test-server"))]
+#[doc(hidden)]
+pub mod test_server;
 /// Api token management utils
 pub mod token;
+/// HTTP transport, with the backend selected by the features
+mod transport;
+
+pub use config::{ClientConfig, ResponseFormat};
+pub use decoder::{DecodeError, DecodeTarget, Decoder, ResponseDecoder, SerdeJsonDecoder};
+/// The type-erased serde of [DecodeTarget::deserialize]
+pub use erased_serde;
+pub use redact::{is_sensitive_header, RedactedHeaders};
+pub use transport::HttpClient;
 
 pub type Headers<'a> = Vec<(&'a str, &'a str)>;
@@ -0,0 +1,143 @@
+use http::header::HeaderMap;
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+
+/// Information about a response found in its status and well-known headers, e.g. to throttle
+/// requests to providers with rate limits.
+///
+/// The headers are optional: fields of headers not sent by the server, or not valid, are `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResponseMeta {
+    /// HTTP status code.
+    pub status: u16,
+    /// Requests left in the current rate limit window (`X-RateLimit-Remaining`).
+    pub rate_limit_remaining: Option<u64>,
+    /// When the rate limit window resets (`X-RateLimit-Reset`), as sent by the provider: usually
+    /// seconds until the reset, or its unix timestamp.
+    pub rate_limit_reset: Option<u64>,
+    /// How long to wait before retrying (`Retry-After`), from when the response was received.
+    pub retry_after: Option<Duration>,
+    /// Identifier of the request assigned by the provider (`X-Request-Id`), e.g. for support requests.
+    pub request_id: Option<String>,
+}
+
+impl ResponseMeta {
+    pub(crate) fn from_headers(status: u16, headers: &HeaderMap) -> ResponseMeta {
+        Self::from_headers_at(status, headers, SystemTime::now())
+    }
+
+    /// Parses the headers of a response received at `now`.
+    fn from_headers_at(status: u16, headers: &HeaderMap, now: SystemTime) -> ResponseMeta {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.trim())
+        };
+        ResponseMeta {
+            status,
+            rate_limit_remaining: header("x-ratelimit-remaining").and_then(|v| v.parse().ok()),
+            rate_limit_reset: header("x-ratelimit-reset").and_then(|v| v.parse().ok()),
+            retry_after: header("retry-after").and_then(|v| parse_retry_after(v, now)),
+            request_id: header("x-request-id").map(|v| v.to_owned()),
+        }
+    }
+}
+
+/// Parses a `Retry-After` value, either a number of seconds or an HTTP date.
+/// Dates in the past are a zero delay.
+fn parse_retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+    if let Ok(seconds) = value.parse() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(date.duration_since(now).unwrap_or_default())
+}
+
+/// The meta of the latest response received by a client, shared with its clones.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LastResponseMeta(Arc<Mutex<Option<ResponseMeta>>>);
+
+impl LastResponseMeta {
+    pub(crate) fn set(&self, meta: ResponseMeta) {
+        if let Ok(mut last) = self.0.lock() {
+            *last = Some(meta);
+        }
+    }
+
+    pub(crate) fn get(&self) -> Option<ResponseMeta> {
+        self.0.lock().ok().and_then(|last| last.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::header::HeaderValue;
+
+    fn headers(headers: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in headers {
+            map.insert(*name, HeaderValue::from_static(value));
+        }
+        map
+    }
+
+    #[test]
+    fn test_parse_headers() {
+        let meta = ResponseMeta::from_headers(
+            200,
+            &headers(&[
+                ("X-RateLimit-Remaining", "42"),
+                ("X-RateLimit-Reset", "1700000000"),
+                ("X-Request-Id", "abc-123"),
+            ]),
+        );
+        assert_eq!(
+            ResponseMeta {
+                status: 200,
+                rate_limit_remaining: Some(42),
+                rate_limit_reset: Some(1700000000),
+                retry_after: None,
+                request_id: Some("abc-123".to_owned()),
+            },
+            meta
+        );
+    }
+
+    #[test]
+    fn test_parse_retry_after_seconds() {
+        let meta = ResponseMeta::from_headers(429, &headers(&[("Retry-After", "120")]));
+        assert_eq!(Some(Duration::from_secs(120)), meta.retry_after);
+    }
+
+    #[test]
+    fn test_parse_retry_after_http_date() {
+        let now = httpdate::parse_http_date("Wed, 21 Oct 2015 07:28:00 GMT").unwrap();
+        let meta = ResponseMeta::from_headers_at(
+            429,
+            &headers(&[("Retry-After", "Wed, 21 Oct 2015 07:29:30 GMT")]),
+            now,
+        );
+        assert_eq!(Some(Duration::from_secs(90)), meta.retry_after);
+
+        let meta = ResponseMeta::from_headers_at(
+            429,
+            &headers(&[("Retry-After", "Wed, 21 Oct 2015 07:27:00 GMT")]),
+            now,
+        );
+        assert_eq!(Some(Duration::ZERO), meta.retry_after);
+    }
+
+    #[test]
+    fn test_parse_invalid_headers() {
+        let meta = ResponseMeta::from_headers(
+            429,
+            &headers(&[("X-RateLimit-Remaining", "-1"), ("Retry-After", "soon")]),
+        );
+        assert_eq!(None, meta.rate_limit_remaining);
+        assert_eq!(None, meta.retry_after);
+    }
+}
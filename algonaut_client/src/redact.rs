@@ -0,0 +1,126 @@
+use http::HeaderMap;
+use std::fmt::{self, Debug, Formatter};
+
+/// The value shown in place of the value of a sensitive header.
+const REDACTED: &str = "***";
+
+/// Whether the value of the header is a secret, redacted from the debug output of the clients:
+/// the API tokens (e.g. `X-Algo-API-Token`, `X-Indexer-API-Token`), the API keys (e.g.
+/// `X-API-Key`) and the credentials (`Authorization`, `Proxy-Authorization`, `Cookie`).
+///
+/// The name is compared case-insensitively.
+pub fn is_sensitive_header(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    matches!(
+        name.as_str(),
+        "authorization" | "proxy-authorization" | "cookie"
+    ) || ["token", "key", "secret", "password"]
+        .iter()
+        .any(|part| name.contains(part))
+}
+
+/// Headers formatted with [Debug] as a map, with the values of the
+/// [sensitive](is_sensitive_header) headers replaced by `***`, e.g. to log the headers of a
+/// client.
+pub struct RedactedHeaders<'a>(Vec<(&'a str, &'a str)>);
+
+impl<'a> RedactedHeaders<'a> {
+    pub fn new(headers: impl IntoIterator<Item = (&'a str, &'a str)>) -> RedactedHeaders<'a> {
+        RedactedHeaders(headers.into_iter().collect())
+    }
+
+    /// The headers of a request, the values that aren't visible ASCII shown as "<binary>".
+    pub(crate) fn of(headers: &'a HeaderMap) -> RedactedHeaders<'a> {
+        RedactedHeaders::new(
+            headers
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.to_str().unwrap_or("<binary>"))),
+        )
+    }
+}
+
+impl Debug for RedactedHeaders<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.0.iter().map(|(name, value)| {
+                let value = if is_sensitive_header(name) {
+                    REDACTED
+                } else {
+                    value
+                };
+                (name, value)
+            }))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sensitive_headers() {
+        for name in [
+            "X-Algo-API-Token",
+            "X-Indexer-API-Token",
+            "X-KMD-API-Token",
+            "X-API-Key",
+            "x-api-key",
+            "Authorization",
+            "Proxy-Authorization",
+            "Cookie",
+        ]
+        .iter()
+        {
+            assert!(is_sensitive_header(name), "{}", name);
+        }
+        for name in ["Accept", "Content-Type", "User-Agent", "X-Request-Id"].iter() {
+            assert!(!is_sensitive_header(name), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_redacted_headers() {
+        let headers = RedactedHeaders::new(vec![
+            ("X-API-Key", "secret-key"),
+            ("User-Agent", "algonaut"),
+        ]);
+
+        assert_eq!(
+            r#"{"X-API-Key": "***", "User-Agent": "algonaut"}"#,
+            format!("{:?}", headers)
+        );
+    }
+
+    #[test]
+    fn test_clients_debug_without_the_token() {
+        let token = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+        let url = "http://localhost:4001";
+        let debug = vec![
+            format!(
+                "{:?}",
+                crate::algod::v2::Client::new(url, vec![("X-Algo-API-Token", token)]).unwrap()
+            ),
+            format!(
+                "{:?}",
+                crate::indexer::v2::Client::new(
+                    url,
+                    vec![
+                        ("X-Indexer-API-Token", token),
+                        ("Accept", "application/json")
+                    ]
+                )
+                .unwrap()
+            ),
+            format!(
+                "{:?}",
+                crate::kmd::v1::Client::new(url, vec![("X-KMD-API-Token", token)]).unwrap()
+            ),
+        ];
+
+        for debug in debug {
+            assert!(!debug.contains(token), "{}", debug);
+            assert!(debug.contains(r#"-api-token": "***""#), "{}", debug);
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use crate::meta::ResponseMeta;
+use crate::transport::TransportError;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Retries allowed per successful request when the ratio isn't configured.
+pub(crate) const DEFAULT_RETRY_BUDGET_RATIO: f64 = 0.1;
+
+/// Maximum number of retries the budget can accumulate, so a long healthy period doesn't
+/// allow a burst of retries when the provider starts failing. The budget starts full, so the
+/// first failures of a client can be retried.
+const MAX_RETRY_BUDGET: f64 = 10.0;
+
+/// Delay before the first retry, doubled for each following retry, unless the response
+/// sets `Retry-After`.
+const MIN_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// Maximum delay before a retry when it isn't configured.
+pub(crate) const DEFAULT_MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// A token bucket shared by the requests of a client and its clones: each successful request
+/// deposits `ratio` tokens and each retry withdraws one, so the retries stay below `ratio` times
+/// the successful requests, after the [MAX_RETRY_BUDGET] retries it starts with.
+#[derive(Debug)]
+struct RetryBudget {
+    ratio: f64,
+    tokens: Mutex<f64>,
+}
+
+impl RetryBudget {
+    fn new(ratio: f64) -> Self {
+        RetryBudget {
+            ratio,
+            tokens: Mutex::new(MAX_RETRY_BUDGET),
+        }
+    }
+
+    fn deposit(&self) {
+        if let Ok(mut tokens) = self.tokens.lock() {
+            *tokens = (*tokens + self.ratio).min(MAX_RETRY_BUDGET);
+        }
+    }
+
+    fn try_withdraw(&self) -> bool {
+        match self.tokens.lock() {
+            Ok(mut tokens) if *tokens >= 1.0 => {
+                *tokens -= 1.0;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The retry settings of a client, with the budget shared with its clones.
+#[derive(Debug, Clone)]
+pub(crate) struct Retry {
+    max_retries: u32,
+    max_delay: Duration,
+    budget: Arc<RetryBudget>,
+}
+
+impl Retry {
+    pub(crate) fn new(max_retries: u32, budget_ratio: f64, max_delay: Duration) -> Self {
+        Retry {
+            max_retries,
+            max_delay,
+            budget: Arc::new(RetryBudget::new(budget_ratio)),
+        }
+    }
+
+    /// Records a request which didn't fail with a retryable error.
+    pub(crate) fn record_success(&self) {
+        self.budget.deposit();
+    }
+
+    /// Whether the failed `attempt` (starting at 0) can be retried, taking a retry from the budget.
+    pub(crate) fn try_retry(&self, attempt: u32) -> bool {
+        attempt < self.max_retries && self.budget.try_withdraw()
+    }
+
+    /// The delay before retrying the failed `attempt`: the `Retry-After` of the response if set,
+    /// or an exponential backoff, at most the maximum delay.
+    pub(crate) fn delay(&self, attempt: u32, meta: Option<&ResponseMeta>) -> Duration {
+        meta.and_then(|meta| meta.retry_after)
+            .unwrap_or_else(|| MIN_RETRY_DELAY * 2u32.saturating_pow(attempt.min(16)))
+            .min(self.max_delay)
+    }
+}
+
+/// Whether a response with this status is a transient failure, worth retrying.
+pub(crate) fn is_retryable_status(status: u16) -> bool {
+    matches!(status, 429 | 502 | 503 | 504)
+}
+
+/// Whether this error is a transient failure, worth retrying.
+pub(crate) fn is_retryable_error(error: &TransportError) -> bool {
+    error.is_connect() || error.is_timeout()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server::{TestResponse, TestServer};
+    use crate::ClientConfig;
+
+    /// Retry settings with an empty budget.
+    fn exhausted(max_retries: u32, budget_ratio: f64) -> Retry {
+        let retry = Retry::new(max_retries, budget_ratio, DEFAULT_MAX_RETRY_DELAY);
+        while retry.try_retry(0) {}
+        retry
+    }
+
+    #[test]
+    fn test_budget_starts_full() {
+        let retry = Retry::new(3, 0.1, DEFAULT_MAX_RETRY_DELAY);
+
+        let retries = (0..100).filter(|_| retry.try_retry(0)).count();
+        assert_eq!(MAX_RETRY_BUDGET as usize, retries);
+    }
+
+    #[test]
+    fn test_budget_allows_retries_in_proportion_to_successes() {
+        let retry = exhausted(3, 0.5);
+        assert!(!retry.try_retry(0));
+
+        retry.record_success();
+        retry.record_success();
+        assert!(retry.try_retry(0));
+        assert!(!retry.try_retry(0));
+    }
+
+    #[test]
+    fn test_budget_is_shared_with_clones() {
+        let retry = exhausted(3, 1.0);
+        let clone = retry.clone();
+
+        clone.record_success();
+        assert!(retry.try_retry(0));
+        assert!(!clone.try_retry(0));
+    }
+
+    #[test]
+    fn test_budget_is_capped() {
+        let retry = exhausted(3, 1.0);
+        for _ in 0..100 {
+            retry.record_success();
+        }
+
+        let retries = (0..100).filter(|_| retry.try_retry(0)).count();
+        assert_eq!(MAX_RETRY_BUDGET as usize, retries);
+    }
+
+    #[test]
+    fn test_max_retries() {
+        let retry = exhausted(1, 1.0);
+        retry.record_success();
+        retry.record_success();
+
+        assert!(!retry.try_retry(1));
+        assert!(retry.try_retry(0));
+    }
+
+    #[test]
+    fn test_delay() {
+        let retry = Retry::new(3, 1.0, Duration::from_secs(5));
+        assert_eq!(Duration::from_millis(100), retry.delay(0, None));
+        assert_eq!(Duration::from_millis(400), retry.delay(2, None));
+
+        let meta = ResponseMeta {
+            status: 429,
+            rate_limit_remaining: None,
+            rate_limit_reset: None,
+            retry_after: Some(Duration::from_secs(3)),
+            request_id: None,
+        };
+        assert_eq!(Duration::from_secs(3), retry.delay(0, Some(&meta)));
+
+        // clamped to the maximum delay
+        assert_eq!(Duration::from_secs(5), retry.delay(10, None));
+        let meta = ResponseMeta {
+            retry_after: Some(Duration::from_secs(3600)),
+            ..meta
+        };
+        assert_eq!(Duration::from_secs(5), retry.delay(0, Some(&meta)));
+    }
+
+    /// Answers the requests with empty responses with these statuses, in order.
+    fn serve(statuses: Vec<&'static str>) -> TestServer {
+        TestServer::serve(
+            statuses
+                .into_iter()
+                .map(|status| {
+                    TestResponse::json("")
+                        .status(status)
+                        .header("retry-after", "0")
+                })
+                .collect(),
+        )
+    }
+
+    fn algod(url: &str, max_retries: u32) -> crate::algod::v2::Client {
+        let config = ClientConfig {
+            max_retries,
+            retry_budget_ratio: Some(1.0),
+            ..ClientConfig::default()
+        };
+        crate::algod::v2::Client::with_config(url, vec![], &config).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_retry_transient_failure() {
+        // the first failure of the client is retried too
+        let server = serve(vec!["503 Service Unavailable", "200 OK"]);
+        let algod = algod(server.url(), 1);
+
+        assert!(algod.health().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_no_retry_when_budget_exhausted() {
+        let mut statuses = vec!["503 Service Unavailable"; MAX_RETRY_BUDGET as usize + 2];
+        statuses.push("200 OK");
+        let server = serve(statuses);
+        let algod = algod(server.url(), MAX_RETRY_BUDGET as u32);
+
+        // exhausts the budget
+        assert!(algod.health().await.is_err());
+        assert!(algod.health().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_no_retry_by_default() {
+        let server = serve(vec!["200 OK", "503 Service Unavailable", "200 OK"]);
+        let algod = algod(server.url(), 0);
+
+        algod.health().await.unwrap();
+        assert!(algod.health().await.is_err());
+    }
+}
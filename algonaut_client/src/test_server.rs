@@ -0,0 +1,270 @@
+//! An HTTP server for the tests of the clients, answering the requests with canned responses.
+//!
+//! It's compiled for the tests of this crate, and with the `test-server` feature for the tests of
+//! the crates depending on it. It isn't part of the API.
+
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A url nothing listens on: the connections are refused.
+pub const UNREACHABLE_URL: &str = "http://127.0.0.1:1/";
+
+/// How long [TestServer::request] waits for a request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A response of a [TestServer].
+#[derive(Debug, Clone)]
+pub struct TestResponse {
+    status: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+    delay: Duration,
+    raw: Option<Vec<u8>>,
+}
+
+impl TestResponse {
+    /// A 200 response with this body and content type.
+    pub fn new(content_type: &str, body: impl Into<Vec<u8>>) -> TestResponse {
+        TestResponse {
+            status: "200 OK".to_owned(),
+            headers: vec![("content-type".to_owned(), content_type.to_owned())],
+            body: body.into(),
+            delay: Duration::ZERO,
+            raw: None,
+        }
+    }
+
+    /// A 200 response with this JSON body.
+    pub fn json(body: impl Into<Vec<u8>>) -> TestResponse {
+        TestResponse::new("application/json", body)
+    }
+
+    /// A 200 response with this MessagePack body.
+    pub fn msgpack(body: impl Into<Vec<u8>>) -> TestResponse {
+        TestResponse::new("application/msgpack", body)
+    }
+
+    /// These bytes, written as they are before closing the connection, e.g. an invalid or
+    /// incomplete response.
+    pub fn raw(response: impl Into<Vec<u8>>) -> TestResponse {
+        TestResponse {
+            raw: Some(response.into()),
+            ..TestResponse::new("", "")
+        }
+    }
+
+    /// Sets the status line, e.g. "404 Not Found".
+    pub fn status(mut self, status: &str) -> TestResponse {
+        self.status = status.to_owned();
+        self
+    }
+
+    /// Adds a header.
+    pub fn header(mut self, name: &str, value: &str) -> TestResponse {
+        self.headers.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Waits this long after the request before answering.
+    pub fn delay(mut self, delay: Duration) -> TestResponse {
+        self.delay = delay;
+        self
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        if let Some(raw) = &self.raw {
+            return raw.clone();
+        }
+        let mut response = format!("HTTP/1.1 {}\r\n", self.status);
+        for (name, value) in &self.headers {
+            response.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        response.push_str(&format!("content-length: {}\r\n\r\n", self.body.len()));
+        let mut response = response.into_bytes();
+        response.extend_from_slice(&self.body);
+        response
+    }
+}
+
+/// A request received by a [TestServer].
+#[derive(Debug, Clone)]
+pub struct TestRequest {
+    /// The request line, e.g. "GET /v2/status HTTP/1.1".
+    pub line: String,
+    /// The headers, with lowercase names.
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    /// When the request was read by the server.
+    pub received_at: Instant,
+    /// The number of the connection of the request, counting the connections accepted from 0,
+    /// e.g. to check that a connection is reused.
+    pub connection: usize,
+}
+
+impl TestRequest {
+    /// The value of the header with this (lowercase) name.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// How a [TestServer] answers the requests.
+#[derive(Clone)]
+enum Responder {
+    /// With these responses, in order.
+    Queue(Arc<Mutex<VecDeque<TestResponse>>>),
+    /// With the response of this function to each request.
+    Handler(Arc<dyn Fn(&TestRequest) -> TestResponse + Send + Sync>),
+    /// Never.
+    Silent,
+}
+
+/// A server answering the requests with canned responses, in order, whatever the requests and
+/// their connections (a connection is closed once all the responses were sent), or with the
+/// responses of a handler.
+pub struct TestServer {
+    url: String,
+    requests: Receiver<TestRequest>,
+}
+
+impl TestServer {
+    /// Answers the requests with these responses, in order.
+    pub fn serve(responses: Vec<TestResponse>) -> TestServer {
+        TestServer::start(Responder::Queue(Arc::new(Mutex::new(VecDeque::from(
+            responses,
+        )))))
+    }
+
+    /// Answers each request with the response of `handler`, e.g. to route the concurrent requests
+    /// by path.
+    pub fn handle(
+        handler: impl Fn(&TestRequest) -> TestResponse + Send + Sync + 'static,
+    ) -> TestServer {
+        TestServer::start(Responder::Handler(Arc::new(handler)))
+    }
+
+    /// Accepts the connections and reads the requests, but never answers.
+    pub fn silent() -> TestServer {
+        TestServer::start(Responder::Silent)
+    }
+
+    fn start(responder: Responder) -> TestServer {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let (sender, requests) = mpsc::channel();
+        thread::spawn(move || {
+            for (connection, socket) in listener.incoming().enumerate() {
+                let (Ok(socket), sender, responder) = (socket, sender.clone(), responder.clone())
+                else {
+                    continue;
+                };
+                thread::spawn(move || serve_connection(socket, connection, sender, responder));
+            }
+        });
+        TestServer { url, requests }
+    }
+
+    /// The url of the server, ending with a slash.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// The next request received, waiting for it.
+    ///
+    /// Panics if no request is received within 10 seconds.
+    pub fn request(&self) -> TestRequest {
+        self.requests
+            .recv_timeout(REQUEST_TIMEOUT)
+            .expect("no request received")
+    }
+
+    /// The request line of the next request received, waiting for it, e.g. "GET /v2/status HTTP/1.1".
+    pub fn request_line(&self) -> String {
+        self.request().line
+    }
+
+    /// The requests received and not returned yet, without waiting.
+    pub fn received(&self) -> Vec<TestRequest> {
+        self.requests.try_iter().collect()
+    }
+}
+
+fn serve_connection(
+    socket: TcpStream,
+    connection: usize,
+    sender: Sender<TestRequest>,
+    responder: Responder,
+) {
+    let mut writer = socket.try_clone().unwrap();
+    let mut reader = BufReader::new(socket);
+    // holds the connection of a silent server open
+    let mut open = vec![];
+    while let Some(request) = read_request(&mut reader, connection) {
+        let response = match &responder {
+            Responder::Queue(responses) => responses.lock().unwrap().pop_front(),
+            Responder::Handler(handler) => Some(handler(&request)),
+            Responder::Silent => None,
+        };
+        let _ = sender.send(request);
+        let response = match response {
+            Some(response) => response,
+            None if matches!(responder, Responder::Silent) => {
+                open.push(writer.try_clone().unwrap());
+                continue;
+            }
+            None => return,
+        };
+        thread::sleep(response.delay);
+        if writer.write_all(&response.to_bytes()).is_err() || response.raw.is_some() {
+            return;
+        }
+    }
+    // a silent server never closes
+    if !open.is_empty() {
+        thread::park();
+    }
+}
+
+/// Reads a request, `None` once the connection is closed.
+fn read_request(reader: &mut BufReader<TcpStream>, connection: usize) -> Option<TestRequest> {
+    let mut line = String::new();
+    if reader.read_line(&mut line).ok()? == 0 {
+        return None;
+    }
+    let mut headers = vec![];
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).ok()? == 0 {
+            return None;
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            headers.push((name.trim().to_lowercase(), value.trim().to_owned()));
+        }
+    }
+    let mut request = TestRequest {
+        line: line.trim_end().to_owned(),
+        headers,
+        body: vec![],
+        received_at: Instant::now(),
+        connection,
+    };
+    let length = request
+        .header("content-length")
+        .and_then(|length| length.parse().ok())
+        .unwrap_or(0);
+    request.body = vec![0; length];
+    reader.read_exact(&mut request.body).ok()?;
+    Some(request)
+}
@@ -0,0 +1,108 @@
+//! The hyper backend, with the `hyper-client` feature: HTTP/1 with rustls and the webpki roots.
+
+use super::{Request, Response, TransportError, TransportErrorKind};
+use crate::ClientConfig;
+use bytes::Bytes;
+use hyper::body::HttpBody;
+use hyper::client::HttpConnector;
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+use std::future::Future;
+use std::time::Duration;
+use tokio::time::Instant;
+
+#[derive(Debug, Clone)]
+pub(super) struct Backend {
+    client: hyper::Client<HttpsConnector<HttpConnector>>,
+    timeout: Option<Duration>,
+}
+
+impl Backend {
+    pub(super) fn new(config: &ClientConfig) -> Result<Backend, TransportError> {
+        let connector = HttpsConnectorBuilder::new()
+            .with_webpki_roots()
+            .https_or_http()
+            .enable_http1()
+            .build();
+        let mut builder = hyper::Client::builder();
+        if let Some(max_idle) = config.pool_max_idle_per_host {
+            builder.pool_max_idle_per_host(max_idle);
+        }
+        if let Some(timeout) = config.pool_idle_timeout {
+            builder.pool_idle_timeout(timeout);
+        }
+        Ok(Backend {
+            client: builder.build(connector),
+            timeout: config.timeout,
+        })
+    }
+
+    pub(super) async fn execute(&self, request: Request) -> Result<Response, TransportError> {
+        let url = request.url.to_string();
+        let mut builder = hyper::Request::builder()
+            .method(request.method)
+            .uri(url.as_str());
+        if let Some(headers) = builder.headers_mut() {
+            *headers = request.headers;
+        }
+        let hyper_request = builder
+            .body(request.body.map(hyper::Body::from).unwrap_or_default())
+            .map_err(|e| TransportError::new(TransportErrorKind::Request, Some(url.clone()), e))?;
+
+        // the timeout runs from connecting until the end of the body, as with reqwest
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        let response = until(deadline, &url, self.client.request(hyper_request))
+            .await?
+            .map_err(|e| {
+                let kind = if e.is_connect() {
+                    TransportErrorKind::Connect
+                } else {
+                    TransportErrorKind::Other
+                };
+                TransportError::new(kind, Some(url.clone()), e)
+            })?;
+        let (parts, body) = response.into_parts();
+        Ok(Response {
+            status: parts.status,
+            headers: parts.headers,
+            url: request.url,
+            body: Body {
+                body,
+                deadline,
+                url,
+            },
+            max_bytes: None,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub(super) struct Body {
+    body: hyper::Body,
+    deadline: Option<Instant>,
+    url: String,
+}
+
+impl Body {
+    pub(super) async fn chunk(&mut self) -> Result<Option<Bytes>, TransportError> {
+        match until(self.deadline, &self.url, self.body.data()).await? {
+            Some(chunk) => chunk.map(Some).map_err(|e| {
+                TransportError::new(TransportErrorKind::Body, Some(self.url.clone()), e)
+            }),
+            None => Ok(None),
+        }
+    }
+}
+
+/// The output of `future`, failing with a timeout error after `deadline`, if set.
+async fn until<T>(
+    deadline: Option<Instant>,
+    url: &str,
+    future: impl Future<Output = T>,
+) -> Result<T, TransportError> {
+    match deadline {
+        Some(deadline) => tokio::time::timeout_at(deadline, future)
+            .await
+            .map_err(|e| TransportError::new(TransportErrorKind::Timeout, Some(url.to_owned()), e)),
+        None => Ok(future.await),
+    }
+}
@@ -0,0 +1,493 @@
+//! The HTTP transport of the clients, sending the requests with the backend selected by the
+//! features: reqwest with `native-tls` (default) or `rustls-tls`, or hyper with `hyper-client`.
+//!
+//! One backend is compiled: hyper if `hyper-client` is enabled, since it's never a default,
+//! except on WASM, which only supports reqwest; reqwest otherwise. None of the types of the
+//! backends are public: the clients only use the requests and responses of this module, and the
+//! errors of the backends are mapped to [ClientError].
+
+#[cfg(not(any(feature = "reqwest", feature = "hyper-client")))]
+compile_error!(
+    "an HTTP backend is required: enable the `native-tls`, `rustls-tls` or `hyper-client` feature"
+);
+
+#[cfg(all(
+    feature = "hyper-client",
+    not(feature = "reqwest"),
+    target_arch = "wasm32"
+))]
+compile_error!("the `hyper-client` feature isn't supported with the WASM target: enable the `native-tls` or `rustls-tls` feature");
+
+#[cfg(all(feature = "hyper-client", not(target_arch = "wasm32")))]
+mod hyper;
+#[cfg(all(
+    feature = "reqwest",
+    any(not(feature = "hyper-client"), target_arch = "wasm32")
+))]
+mod reqwest;
+
+#[cfg(all(feature = "hyper-client", not(target_arch = "wasm32")))]
+use self::hyper as backend;
+#[cfg(all(
+    feature = "reqwest",
+    any(not(feature = "hyper-client"), target_arch = "wasm32")
+))]
+use self::reqwest as backend;
+
+use crate::error::{ClientError, RequestError, RequestErrorDetails};
+use crate::extensions::http::ResponseExt;
+use crate::{ClientConfig, RedactedHeaders};
+use bytes::Bytes;
+use http::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
+use http::{Method, StatusCode};
+use serde::Serialize;
+use std::convert::TryFrom;
+use std::fmt;
+use url::Url;
+
+/// The HTTP client of the algod, indexer and kmd clients, built from a [ClientConfig] with the
+/// backend selected by the features.
+#[derive(Debug, Clone)]
+pub struct HttpClient {
+    backend: backend::Backend,
+    /// The [max_response_bytes](ClientConfig::max_response_bytes) of the bodies of the responses.
+    max_response_bytes: Option<usize>,
+}
+
+impl HttpClient {
+    /// Builds a client with the settings of `config` (e.g. the connection pool, or the maximum size
+    /// of the responses).
+    pub fn new(config: &ClientConfig) -> Result<HttpClient, ClientError> {
+        Ok(HttpClient {
+            backend: backend::Backend::new(config)?,
+            max_response_bytes: config.max_response_bytes,
+        })
+    }
+
+    /// The body of the response to a `GET` request of `url`, failing if its status isn't a
+    /// success, e.g. to download the off-chain metadata of an asset. The body is limited to the
+    /// [max_response_bytes](ClientConfig::max_response_bytes) of the config.
+    pub async fn get_bytes(&self, url: &str) -> Result<Vec<u8>, ClientError> {
+        let response = self.get(url).send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(ClientError::Request(RequestError::new(
+                Some(response.url().to_string()),
+                RequestErrorDetails::Http {
+                    status: status.as_u16(),
+                    message: "".to_owned(),
+                },
+            )));
+        }
+        response.limited_bytes().await
+    }
+
+    pub(crate) fn get(&self, url: &str) -> RequestBuilder {
+        self.request(Method::GET, url)
+    }
+
+    pub(crate) fn post(&self, url: &str) -> RequestBuilder {
+        self.request(Method::POST, url)
+    }
+
+    pub(crate) fn delete(&self, url: &str) -> RequestBuilder {
+        self.request(Method::DELETE, url)
+    }
+
+    fn request(&self, method: Method, url: &str) -> RequestBuilder {
+        let request = Url::parse(url)
+            .map(|url| Request {
+                method,
+                url,
+                headers: HeaderMap::new(),
+                body: None,
+            })
+            .map_err(|e| TransportError::new(TransportErrorKind::Request, None, e));
+        RequestBuilder {
+            client: self.clone(),
+            request,
+        }
+    }
+
+    pub(crate) async fn execute(&self, request: Request) -> Result<Response, TransportError> {
+        let mut response = self.backend.execute(request).await?;
+        response.max_bytes = self.max_response_bytes;
+        Ok(response)
+    }
+}
+
+/// A request, sent with [HttpClient::execute].
+#[derive(Clone)]
+pub(crate) struct Request {
+    pub(crate) method: Method,
+    pub(crate) url: Url,
+    pub(crate) headers: HeaderMap,
+    pub(crate) body: Option<Vec<u8>>,
+}
+
+impl fmt::Debug for Request {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Request")
+            .field("method", &self.method)
+            .field("url", &self.url)
+            .field("headers", &RedactedHeaders::of(&self.headers))
+            .field("body", &self.body)
+            .finish()
+    }
+}
+
+/// Builds a request: the first error (e.g. an invalid header) fails it when it's sent.
+#[derive(Debug)]
+pub(crate) struct RequestBuilder {
+    client: HttpClient,
+    request: Result<Request, TransportError>,
+}
+
+impl RequestBuilder {
+    pub(crate) fn from_parts(client: HttpClient, request: Request) -> RequestBuilder {
+        RequestBuilder {
+            client,
+            request: Ok(request),
+        }
+    }
+
+    pub(crate) fn build_split(self) -> (HttpClient, Result<Request, TransportError>) {
+        (self.client, self.request)
+    }
+
+    /// Adds a header, replacing the one with the same name.
+    pub(crate) fn header(self, name: &str, value: &str) -> RequestBuilder {
+        self.and_then(|mut request| {
+            let name = HeaderName::try_from(name).map_err(|e| request.error(e))?;
+            let value = HeaderValue::try_from(value).map_err(|e| request.error(e))?;
+            request.headers.insert(name, value);
+            Ok(request)
+        })
+    }
+
+    /// Adds the headers, replacing the ones with the same names.
+    pub(crate) fn headers(self, headers: HeaderMap) -> RequestBuilder {
+        self.and_then(|mut request| {
+            for (name, value) in headers {
+                if let Some(name) = name {
+                    request.headers.insert(name, value);
+                }
+            }
+            Ok(request)
+        })
+    }
+
+    /// Appends the URL-encoded `query` (e.g. a slice of pairs or a struct) to the query of the url.
+    pub(crate) fn query<T: Serialize + ?Sized>(self, query: &T) -> RequestBuilder {
+        self.and_then(|mut request| {
+            let mut url = request.url.clone();
+            let mut pairs = url.query_pairs_mut();
+            let result = query
+                .serialize(serde_urlencoded::Serializer::new(&mut pairs))
+                .map(|_| ());
+            drop(pairs);
+            result.map_err(|e| request.error(e))?;
+            // an empty query doesn't leave a trailing "?"
+            if url.query() == Some("") {
+                url.set_query(None);
+            }
+            request.url = url;
+            Ok(request)
+        })
+    }
+
+    /// Sets the body to `json`, with the JSON content type if none is set.
+    pub(crate) fn json<T: Serialize + ?Sized>(self, json: &T) -> RequestBuilder {
+        self.and_then(|mut request| {
+            let body = serde_json::to_vec(json).map_err(|e| request.error(e))?;
+            request
+                .headers
+                .entry(CONTENT_TYPE)
+                .or_insert_with(|| HeaderValue::from_static("application/json"));
+            request.body = Some(body);
+            Ok(request)
+        })
+    }
+
+    pub(crate) fn body(self, body: impl Into<Vec<u8>>) -> RequestBuilder {
+        self.and_then(|mut request| {
+            request.body = Some(body.into());
+            Ok(request)
+        })
+    }
+
+    pub(crate) async fn send(self) -> Result<Response, TransportError> {
+        self.client.execute(self.request?).await
+    }
+
+    fn and_then(
+        self,
+        f: impl FnOnce(Request) -> Result<Request, TransportError>,
+    ) -> RequestBuilder {
+        RequestBuilder {
+            client: self.client,
+            request: self.request.and_then(f),
+        }
+    }
+}
+
+impl Request {
+    /// An error building this request.
+    fn error(&self, error: impl ToString) -> TransportError {
+        TransportError::new(
+            TransportErrorKind::Request,
+            Some(self.url.to_string()),
+            error,
+        )
+    }
+}
+
+/// A response, whose body is read as it's received.
+#[derive(Debug)]
+pub(crate) struct Response {
+    status: StatusCode,
+    headers: HeaderMap,
+    url: Url,
+    body: backend::Body,
+    /// The maximum size of the body, if limited.
+    max_bytes: Option<usize>,
+}
+
+impl Response {
+    pub(crate) fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub(crate) fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    pub(crate) fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// The length of the body, from the `content-length` header, if set.
+    pub(crate) fn content_length(&self) -> Option<u64> {
+        self.headers
+            .get(http::header::CONTENT_LENGTH)
+            .and_then(|length| length.to_str().ok())
+            .and_then(|length| length.parse().ok())
+    }
+
+    /// The maximum size of the body, if limited: see
+    /// [limited_bytes](crate::extensions::http::ResponseExt::limited_bytes).
+    pub(crate) fn max_bytes(&self) -> Option<usize> {
+        self.max_bytes
+    }
+
+    /// The next chunk of the body, `None` at its end.
+    pub(crate) async fn chunk(&mut self) -> Result<Option<Bytes>, TransportError> {
+        self.body.chunk().await
+    }
+
+    pub(crate) async fn bytes(mut self) -> Result<Vec<u8>, TransportError> {
+        let mut body = vec![];
+        while let Some(chunk) = self.chunk().await? {
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body)
+    }
+}
+
+/// An error of the transport, independent of the backend.
+#[derive(Debug, Clone)]
+pub(crate) struct TransportError {
+    kind: TransportErrorKind,
+    url: Option<String>,
+    description: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TransportErrorKind {
+    /// The request couldn't be built, e.g. with an invalid header.
+    Request,
+    /// The connection to the server failed.
+    Connect,
+    /// The request timed out.
+    Timeout,
+    /// The body of the response couldn't be read, e.g. the connection was closed.
+    Body,
+    /// The body of the response couldn't be decoded. Only reported by reqwest.
+    #[cfg_attr(
+        all(feature = "hyper-client", not(target_arch = "wasm32")),
+        allow(dead_code)
+    )]
+    Decode,
+    /// Any other error of the backend.
+    Other,
+}
+
+impl TransportError {
+    pub(crate) fn new(
+        kind: TransportErrorKind,
+        url: Option<String>,
+        error: impl ToString,
+    ) -> TransportError {
+        TransportError {
+            kind,
+            url,
+            description: error.to_string(),
+        }
+    }
+
+    pub(crate) fn is_connect(&self) -> bool {
+        self.kind == TransportErrorKind::Connect
+    }
+
+    pub(crate) fn is_timeout(&self) -> bool {
+        self.kind == TransportErrorKind::Timeout
+    }
+}
+
+impl From<TransportError> for ClientError {
+    fn from(error: TransportError) -> Self {
+        let details = match error.kind {
+            TransportErrorKind::Timeout => RequestErrorDetails::Timeout,
+            TransportErrorKind::Body => RequestErrorDetails::IncompleteResponse,
+            TransportErrorKind::Decode => RequestErrorDetails::Decode {
+                description: error.description,
+                path: None,
+                body: None,
+            },
+            TransportErrorKind::Request
+            | TransportErrorKind::Connect
+            | TransportErrorKind::Other => RequestErrorDetails::Client {
+                description: error.description,
+            },
+        };
+        ClientError::Request(RequestError::new(error.url, details))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server::{TestResponse, TestServer, UNREACHABLE_URL};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_request() {
+        let server = TestServer::serve(vec![TestResponse::json(r#"{"round":1}"#)
+            .status("201 Created")
+            .header("x-request-id", "req-1")]);
+        let client = HttpClient::new(&ClientConfig::default()).unwrap();
+
+        let response = client
+            .post(&format!("{}v1/wallet", server.url()))
+            .header("X-API-Key", "token")
+            .query(&[("limit", 2)])
+            .json(&serde_json::json!({"name": "w"}))
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(201, response.status().as_u16());
+        assert_eq!("req-1", response.headers()["x-request-id"]);
+        assert_eq!(Some(11), response.content_length());
+        assert_eq!(
+            serde_json::json!({"round": 1}),
+            serde_json::from_slice::<serde_json::Value>(&response.bytes().await.unwrap()).unwrap()
+        );
+        let request = server.request();
+        assert_eq!("POST /v1/wallet?limit=2 HTTP/1.1", request.line);
+        assert_eq!(Some("token"), request.header("x-api-key"));
+        assert_eq!(Some("application/json"), request.header("content-type"));
+        assert_eq!(br#"{"name":"w"}"#.to_vec(), request.body);
+    }
+
+    #[tokio::test]
+    async fn test_get_bytes() {
+        let server = TestServer::serve(vec![
+            TestResponse::new("application/octet-stream", "abc"),
+            TestResponse::new("application/octet-stream", "").status("404 Not Found"),
+            TestResponse::new("application/octet-stream", "abc"),
+        ]);
+        let client = HttpClient::new(&ClientConfig::default()).unwrap();
+        assert_eq!(
+            b"abc".to_vec(),
+            client.get_bytes(server.url()).await.unwrap()
+        );
+
+        match client.get_bytes(server.url()).await {
+            Err(ClientError::Request(RequestError {
+                details: RequestErrorDetails::Http { status: 404, .. },
+                ..
+            })) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        let limited = HttpClient::new(&ClientConfig {
+            max_response_bytes: Some(2),
+            ..ClientConfig::default()
+        })
+        .unwrap();
+        match limited.get_bytes(server.url()).await {
+            Err(ClientError::Request(RequestError {
+                details: RequestErrorDetails::ResponseTooLarge { limit: 2 },
+                ..
+            })) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_error_message_is_limited() {
+        let bad_request =
+            || TestResponse::json(r#"{"message":"bad request"}"#).status("400 Bad Request");
+        let client = |max_response_bytes| {
+            HttpClient::new(&ClientConfig {
+                max_response_bytes,
+                ..ClientConfig::default()
+            })
+            .unwrap()
+        };
+        let message = |error: RequestError| match error.details {
+            RequestErrorDetails::Http { message, .. } => message,
+            details => panic!("unexpected details: {:?}", details),
+        };
+
+        let server = TestServer::serve(vec![bad_request(), bad_request()]);
+        let response = client(Some(25)).get(server.url()).send().await.unwrap();
+        let error = response.http_error_for_status().await.unwrap_err();
+        assert_eq!("bad request", message(error));
+
+        // the message isn't read past the limit
+        let response = client(Some(24)).get(server.url()).send().await.unwrap();
+        let error = response.http_error_for_status().await.unwrap_err();
+        assert_eq!("", message(error));
+    }
+
+    #[tokio::test]
+    async fn test_errors() {
+        let client = HttpClient::new(&ClientConfig {
+            timeout: Some(Duration::from_millis(200)),
+            ..ClientConfig::default()
+        })
+        .unwrap();
+
+        let error = client.get("not a url").send().await.unwrap_err();
+        assert_eq!(TransportErrorKind::Request, error.kind);
+
+        let error = client
+            .get("http://localhost/")
+            .header("x-invalid\n", "")
+            .send()
+            .await
+            .unwrap_err();
+        assert_eq!(TransportErrorKind::Request, error.kind);
+
+        // a server accepting the connection but never answering
+        let server = TestServer::silent();
+        let error = client.get(server.url()).send().await.unwrap_err();
+        assert!(error.is_timeout());
+
+        // a closed port
+        let error = client.get(UNREACHABLE_URL).send().await.unwrap_err();
+        assert!(error.is_connect());
+    }
+}
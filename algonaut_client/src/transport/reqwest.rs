@@ -0,0 +1,96 @@
+//! The reqwest backend, with the `native-tls` or `rustls-tls` feature.
+
+use super::{Request, Response, TransportError, TransportErrorKind};
+use crate::ClientConfig;
+use bytes::Bytes;
+
+#[derive(Debug, Clone)]
+pub(super) struct Backend {
+    client: reqwest::Client,
+}
+
+impl Backend {
+    pub(super) fn new(config: &ClientConfig) -> Result<Backend, TransportError> {
+        #[allow(unused_mut)]
+        let mut builder = reqwest::Client::builder();
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if let Some(max_idle) = config.pool_max_idle_per_host {
+                builder = builder.pool_max_idle_per_host(max_idle);
+            }
+            if let Some(timeout) = config.pool_idle_timeout {
+                builder = builder.pool_idle_timeout(timeout);
+            }
+            if let Some(timeout) = config.timeout {
+                builder = builder.timeout(timeout);
+            }
+        }
+        #[cfg(target_arch = "wasm32")]
+        let _ = config;
+        Ok(Backend {
+            client: builder.build().map_err(transport_error)?,
+        })
+    }
+
+    pub(super) async fn execute(&self, request: Request) -> Result<Response, TransportError> {
+        let mut builder = self
+            .client
+            .request(request.method, request.url)
+            .headers(request.headers);
+        if let Some(body) = request.body {
+            builder = builder.body(body);
+        }
+        let response = builder.send().await.map_err(transport_error)?;
+        Ok(Response {
+            status: response.status(),
+            headers: response.headers().clone(),
+            url: response.url().clone(),
+            body: Body(Some(response)),
+            max_bytes: None,
+        })
+    }
+}
+
+/// The body of a response, `None` once read.
+#[derive(Debug)]
+pub(super) struct Body(Option<reqwest::Response>);
+
+impl Body {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(super) async fn chunk(&mut self) -> Result<Option<Bytes>, TransportError> {
+        match &mut self.0 {
+            Some(response) => response.chunk().await.map_err(transport_error),
+            None => Ok(None),
+        }
+    }
+
+    /// The body is read at once with the WASM target, where it's not streamed.
+    #[cfg(target_arch = "wasm32")]
+    pub(super) async fn chunk(&mut self) -> Result<Option<Bytes>, TransportError> {
+        match self.0.take() {
+            Some(response) => response.bytes().await.map(Some).map_err(transport_error),
+            None => Ok(None),
+        }
+    }
+}
+
+fn transport_error(error: reqwest::Error) -> TransportError {
+    #[cfg(not(target_arch = "wasm32"))]
+    let is_connect = error.is_connect();
+    #[cfg(target_arch = "wasm32")]
+    let is_connect = false;
+    let kind = if error.is_timeout() {
+        TransportErrorKind::Timeout
+    } else if is_connect {
+        TransportErrorKind::Connect
+    } else if error.is_body() {
+        TransportErrorKind::Body
+    } else if error.is_decode() {
+        TransportErrorKind::Decode
+    } else if error.is_builder() {
+        TransportErrorKind::Request
+    } else {
+        TransportErrorKind::Other
+    };
+    TransportError::new(kind, error.url().map(|url| url.to_string()), error)
+}
@@ -4,13 +4,17 @@ use algonaut_encoding::U8_32Visitor;
 use data_encoding::BASE32_NOPAD;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use sha2::Digest;
+use std::convert::TryFrom;
 use std::fmt::{self, Debug, Display, Formatter};
 use std::str::FromStr;
+use thiserror::Error;
 
 type ChecksumAlg = sha2::Sha512_256;
 
 const CHECKSUM_LEN: usize = 4;
 const HASH_LEN: usize = 32;
+/// Length of the base32 encoding of the address and its checksum.
+const ADDRESS_LEN: usize = 58;
 
 /// Public key address
 #[derive(Copy, Clone, Eq, PartialEq)]
@@ -22,14 +26,31 @@ impl Address {
     }
 
     /// Decode from base32 string with checksum
-    fn decode_from_string(string: &str) -> Result<Address, String> {
-        let checksum_address = match BASE32_NOPAD.decode(string.as_bytes()) {
-            Ok(decoded) => decoded,
-            Err(err) => return Err(format!("Error decoding base32: {:?}", err)),
-        };
-        if checksum_address.len() != (HASH_LEN + CHECKSUM_LEN) {
-            return Err("Input string is an invalid address. Wrong length".to_string());
+    fn decode_from_string(string: &str) -> Result<Address, AddressParseError> {
+        // the positions and lengths are in characters, not bytes
+        let length = string.chars().count();
+        if length != ADDRESS_LEN {
+            return Err(AddressParseError::WrongLength { length });
         }
+        if let Some((position, character)) = string
+            .chars()
+            .enumerate()
+            .find(|(_, c)| !matches!(c, 'A'..='Z' | '2'..='7'))
+        {
+            return Err(AddressParseError::InvalidCharacter {
+                character,
+                position,
+            });
+        }
+        // the string is ASCII: the positions of the decode errors (e.g. non-zero trailing bits)
+        // are also character positions
+        let checksum_address = BASE32_NOPAD.decode(string.as_bytes()).map_err(|e| {
+            let position = e.position.min(ADDRESS_LEN - 1);
+            AddressParseError::InvalidCharacter {
+                character: string.as_bytes()[position] as char,
+                position,
+            }
+        })?;
         let (address, checksum) = checksum_address.split_at(HASH_LEN);
         let hashed = ChecksumAlg::digest(address);
         if &hashed[(HASH_LEN - CHECKSUM_LEN)..] == checksum {
@@ -37,7 +58,7 @@ impl Address {
             bytes.copy_from_slice(address);
             Ok(Address::new(bytes))
         } else {
-            Err("Input checksum did not validate".to_string())
+            Err(AddressParseError::ChecksumMismatch)
         }
     }
 
@@ -61,12 +82,31 @@ impl Address {
 }
 
 impl FromStr for Address {
-    type Err = String;
+    type Err = AddressParseError;
     fn from_str(string: &str) -> Result<Self, Self::Err> {
         Address::decode_from_string(string)
     }
 }
 
+impl TryFrom<&str> for Address {
+    type Error = AddressParseError;
+    fn try_from(string: &str) -> Result<Self, Self::Error> {
+        Address::decode_from_string(string)
+    }
+}
+
+/// Error parsing an [Address] from its string representation.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum AddressParseError {
+    #[error("Invalid address length: {length} characters, expected 58.")]
+    WrongLength { length: usize },
+    /// `position` is the index of the character in the address, counted in characters.
+    #[error("Invalid character '{character}' at position {position} of the address: addresses contain only A-Z and 2-7.")]
+    InvalidCharacter { character: char, position: usize },
+    #[error("Address checksum mismatch: the address probably contains a typo.")]
+    ChecksumMismatch,
+}
+
 impl Debug for Address {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.encode_as_string())
@@ -176,6 +216,72 @@ mod tests {
         assert!(invalid_csum.parse::<Address>().is_err());
     }
 
+    #[test]
+    fn decode_wrong_length() {
+        for (s, length) in [
+            ("", 0),
+            (
+                "737777777777777777777777777777777777777777777777777UFEJ2C",
+                57,
+            ),
+            (
+                "737777777777777777777777777777777777777777777777777UFEJ2CIA",
+                59,
+            ),
+        ] {
+            assert_eq!(
+                Err(AddressParseError::WrongLength { length }),
+                s.parse::<Address>()
+            );
+        }
+    }
+
+    #[test]
+    fn decode_invalid_character() {
+        // lowercase
+        assert_eq!(
+            Err(AddressParseError::InvalidCharacter {
+                character: 'u',
+                position: 51
+            }),
+            "737777777777777777777777777777777777777777777777777uFEJ2CI".parse::<Address>()
+        );
+        // 0, 1, 8 and 9 are not part of the base32 alphabet
+        assert_eq!(
+            Err(AddressParseError::InvalidCharacter {
+                character: '0',
+                position: 0
+            }),
+            Address::try_from("037777777777777777777777777777777777777777777777777UFEJ2CI")
+        );
+        // the position and the length are counted in characters, not in bytes
+        assert_eq!(
+            Err(AddressParseError::InvalidCharacter {
+                character: 'é',
+                position: 51
+            }),
+            "737777777777777777777777777777777777777777777777777éFEJ2CI".parse::<Address>()
+        );
+        assert_eq!(
+            Err(AddressParseError::WrongLength { length: 57 }),
+            "737777777777777777777777777777777777777777777777777éFEJ2C".parse::<Address>()
+        );
+    }
+
+    #[test]
+    fn decode_checksum_mismatch() {
+        // one character changed
+        let err = "737777777777777777777777777777777777777777777777777UFEJ2CA"
+            .parse::<Address>()
+            .unwrap_err();
+        assert_eq!(AddressParseError::ChecksumMismatch, err);
+        assert!(err.to_string().contains("checksum mismatch"));
+        assert_eq!(
+            Err(AddressParseError::ChecksumMismatch),
+            "737777777777777777777777777777777777777777777777777UFEJ3CI".parse::<Address>()
+        );
+    }
+
     #[test]
     fn encode() {
         let expected = "7777777777777777777777777777777777777777777777777774MSJUVU";
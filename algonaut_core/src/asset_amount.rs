@@ -0,0 +1,276 @@
+use std::fmt::{self, Display, Formatter};
+use thiserror::Error;
+
+/// Maximum number of decimals of an asset.
+pub const MAX_ASSET_DECIMALS: u32 = 19;
+
+/// An amount of an asset: `raw` base units of an asset with `decimals` decimals, i.e. the amount
+/// `raw / 10^decimals` in the unit of the asset.
+///
+/// The conversions from and to the decimal notation are exact: no floating point is involved.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AssetAmount {
+    pub raw: u64,
+    pub decimals: u32,
+}
+
+impl AssetAmount {
+    pub fn new(raw: u64, decimals: u32) -> AssetAmount {
+        AssetAmount { raw, decimals }
+    }
+
+    /// Formats the amount in the unit of the asset, with exactly `decimals` fraction digits
+    /// (e.g. "12.340" with 3 decimals), and no fraction with 0 decimals.
+    pub fn format(&self) -> String {
+        let decimals = self.decimals as usize;
+        let digits = format!("{:0>width$}", self.raw, width = decimals + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - decimals);
+        if fraction.is_empty() {
+            whole.to_owned()
+        } else {
+            format!("{}.{}", whole, fraction)
+        }
+    }
+
+    /// Parses an amount in the unit of an asset with `decimals` decimals, in decimal notation
+    /// (e.g. "12.34").
+    ///
+    /// At most `decimals` fraction digits are accepted. Returns an error if the string is
+    /// malformed, or if the amount in base units doesn't fit in a u64.
+    pub fn parse(amount: &str, decimals: u32) -> Result<AssetAmount, AssetAmountParseError> {
+        let malformed = || AssetAmountParseError::Malformed {
+            amount: amount.to_owned(),
+        };
+        let (whole, fraction) = match amount.split_once('.') {
+            Some((whole, fraction)) if !fraction.is_empty() => (whole, fraction),
+            Some(_) => return Err(malformed()),
+            None => (amount, ""),
+        };
+        let is_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+        if whole.is_empty() || !is_digits(whole) || !is_digits(fraction) {
+            return Err(malformed());
+        }
+        if fraction.len() > decimals as usize {
+            return Err(AssetAmountParseError::TooManyFractionDigits {
+                digits: fraction.len(),
+                decimals,
+            });
+        }
+        let overflow = || AssetAmountParseError::Overflow {
+            amount: amount.to_owned(),
+        };
+        let fraction_exponent = decimals - fraction.len() as u32;
+        let raw = scaled(whole, decimals)
+            .zip(scaled(fraction, fraction_exponent))
+            .and_then(|(whole, fraction)| whole.checked_add(fraction))
+            .ok_or_else(overflow)?;
+        Ok(AssetAmount { raw, decimals })
+    }
+
+    /// Adds an amount of the same asset: `None` if the decimals differ or on overflow.
+    pub fn checked_add(self, rhs: AssetAmount) -> Option<AssetAmount> {
+        if self.decimals != rhs.decimals {
+            return None;
+        }
+        self.raw.checked_add(rhs.raw).map(|raw| self.with_raw(raw))
+    }
+
+    /// Subtracts an amount of the same asset: `None` if the decimals differ or on underflow.
+    pub fn checked_sub(self, rhs: AssetAmount) -> Option<AssetAmount> {
+        if self.decimals != rhs.decimals {
+            return None;
+        }
+        self.raw.checked_sub(rhs.raw).map(|raw| self.with_raw(raw))
+    }
+
+    pub fn checked_mul(self, rhs: u64) -> Option<AssetAmount> {
+        self.raw.checked_mul(rhs).map(|raw| self.with_raw(raw))
+    }
+
+    fn with_raw(self, raw: u64) -> AssetAmount {
+        AssetAmount { raw, ..self }
+    }
+}
+
+/// The decimal `digits` times `10^exponent`, `None` on overflow. Zero whatever the exponent.
+fn scaled(digits: &str, exponent: u32) -> Option<u64> {
+    let digits = digits.trim_start_matches('0');
+    if digits.is_empty() {
+        return Some(0);
+    }
+    10u64
+        .checked_pow(exponent)?
+        .checked_mul(digits.parse().ok()?)
+}
+
+impl Display for AssetAmount {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.format())
+    }
+}
+
+/// Error parsing an [AssetAmount] from its decimal notation.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum AssetAmountParseError {
+    #[error("Invalid asset amount: {amount:?}")]
+    Malformed { amount: String },
+    #[error("Too many fraction digits: {digits}, the asset has {decimals} decimals.")]
+    TooManyFractionDigits { digits: usize, decimals: u32 },
+    #[error("Asset amount too large: {amount:?}")]
+    Overflow { amount: String },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::OsRng, Rng};
+
+    #[test]
+    fn test_format() {
+        assert_eq!("0", AssetAmount::new(0, 0).format());
+        assert_eq!("123", AssetAmount::new(123, 0).format());
+        assert_eq!("0.00", AssetAmount::new(0, 2).format());
+        assert_eq!("0.01", AssetAmount::new(1, 2).format());
+        assert_eq!("12.340", AssetAmount::new(12_340, 3).format());
+        assert_eq!(
+            "1.8446744073709551615",
+            AssetAmount::new(u64::MAX, 19).format()
+        );
+        assert_eq!(
+            "0.000000000000000000001",
+            AssetAmount::new(1, 21).to_string()
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Ok(AssetAmount::new(12, 0)), AssetAmount::parse("12", 0));
+        assert_eq!(Ok(AssetAmount::new(12_000, 3)), AssetAmount::parse("12", 3));
+        assert_eq!(
+            Ok(AssetAmount::new(12_300, 3)),
+            AssetAmount::parse("12.3", 3)
+        );
+        // 0.1 + 0.2 style floating point errors must not happen
+        assert_eq!(Ok(AssetAmount::new(3, 1)), AssetAmount::parse("0.3", 1));
+        assert_eq!(
+            Ok(AssetAmount::new(u64::MAX, 19)),
+            AssetAmount::parse("1.8446744073709551615", 19)
+        );
+        // more decimals than a u64 has digits
+        assert_eq!(Ok(AssetAmount::new(0, 20)), AssetAmount::parse("0", 20));
+        assert_eq!(
+            Ok(AssetAmount::new(1, 20)),
+            AssetAmount::parse("0.00000000000000000001", 20)
+        );
+        assert_eq!(Ok(AssetAmount::new(5, 0)), AssetAmount::parse("0005", 0));
+        assert_eq!(
+            Ok(AssetAmount::new(0, u32::MAX)),
+            AssetAmount::parse("0.0", u32::MAX)
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for invalid in ["", ".", "1.", ".5", "-1", "+1", "1,5", "1.2.3", " 1", "1e3"] {
+            assert_eq!(
+                Err(AssetAmountParseError::Malformed {
+                    amount: invalid.to_owned()
+                }),
+                AssetAmount::parse(invalid, 6),
+            );
+        }
+        assert_eq!(
+            Err(AssetAmountParseError::TooManyFractionDigits {
+                digits: 4,
+                decimals: 3
+            }),
+            AssetAmount::parse("1.2345", 3)
+        );
+        assert_eq!(
+            Err(AssetAmountParseError::TooManyFractionDigits {
+                digits: 1,
+                decimals: 0
+            }),
+            AssetAmount::parse("1.0", 0)
+        );
+        for (too_large, decimals) in [
+            ("1.8446744073709551616", 19),
+            ("2", 19),
+            ("18446744073709551616", 0),
+            ("1", 20),
+        ] {
+            assert_eq!(
+                Err(AssetAmountParseError::Overflow {
+                    amount: too_large.to_owned()
+                }),
+                AssetAmount::parse(too_large, decimals)
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_parse_roundtrip() {
+        for decimals in 0..=MAX_ASSET_DECIMALS {
+            for raw in [0, 1, 9, 10, u64::MAX - 1, u64::MAX] {
+                let amount = AssetAmount::new(raw, decimals);
+                assert_eq!(Ok(amount), AssetAmount::parse(&amount.format(), decimals));
+            }
+            for _ in 0..200 {
+                // amounts of all magnitudes
+                let raw = OsRng.gen::<u64>() >> OsRng.gen_range(0..64);
+                let amount = AssetAmount::new(raw, decimals);
+                let formatted = amount.format();
+                assert_eq!(Ok(amount), AssetAmount::parse(&formatted, decimals));
+                assert_eq!(
+                    decimals as usize,
+                    formatted.split_once('.').map_or(0, |(_, f)| f.len())
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_format_roundtrip_of_short_fractions() {
+        for decimals in 1..=MAX_ASSET_DECIMALS {
+            for _ in 0..200 {
+                let digits = OsRng.gen_range(1..=decimals as usize);
+                let fraction: String = (0..digits)
+                    .map(|_| char::from(b'0' + OsRng.gen_range(0..10)))
+                    .collect();
+                // below the largest whole amount, so that any fraction fits
+                let whole = OsRng.gen_range(0..u64::MAX / 10u64.pow(decimals));
+                let input = format!("{}.{}", whole, fraction);
+                let amount = AssetAmount::parse(&input, decimals).unwrap();
+                let formatted = amount.format();
+                // the formatting pads the fraction with zeros
+                assert_eq!(
+                    format!(
+                        "{:0<width$}",
+                        input,
+                        width = input.len() - digits + decimals as usize
+                    ),
+                    formatted
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_checked_arithmetic() {
+        let amount = AssetAmount::new(10, 2);
+        assert_eq!(
+            Some(AssetAmount::new(15, 2)),
+            amount.checked_add(AssetAmount::new(5, 2))
+        );
+        assert_eq!(None, amount.checked_add(AssetAmount::new(5, 3)));
+        assert_eq!(None, AssetAmount::new(u64::MAX, 2).checked_add(amount));
+        assert_eq!(
+            Some(AssetAmount::new(5, 2)),
+            amount.checked_sub(AssetAmount::new(5, 2))
+        );
+        assert_eq!(None, amount.checked_sub(AssetAmount::new(11, 2)));
+        assert_eq!(None, amount.checked_sub(AssetAmount::new(5, 0)));
+        assert_eq!(Some(AssetAmount::new(30, 2)), amount.checked_mul(3));
+        assert_eq!(None, amount.checked_mul(u64::MAX));
+    }
+}
@@ -1,6 +1,6 @@
 use algonaut_crypto::HashDigest;
 use algonaut_crypto::Signature;
-use algonaut_encoding::U8_32Visitor;
+use algonaut_encoding::{SignatureVisitor, U8_32Visitor};
 use data_encoding::BASE64;
 use derive_more::{Add, Display, Sub};
 use error::CoreError;
@@ -9,22 +9,38 @@ use sha2::Digest;
 use static_assertions::_core::ops::{Add, Sub};
 use std::convert::TryInto;
 use std::fmt::{self, Debug, Formatter};
+use std::iter::Sum;
 use std::ops::Mul;
 
 pub use address::Address;
+pub use address::AddressParseError;
 pub use address::MultisigAddress;
+pub use asset_amount::AssetAmount;
+pub use asset_amount::AssetAmountParseError;
+pub use asset_amount::MAX_ASSET_DECIMALS;
 pub use multisig::MultisigSignature;
 pub use multisig::MultisigSubsig;
+pub use network::GenesisHash;
+pub use network::Network;
+pub use txid::TxId;
+pub use txid::TxIdParseError;
 
 mod address;
+mod asset_amount;
 mod error;
 mod multisig;
+mod network;
+mod txid;
 
 pub const MICRO_ALGO_CONVERSION_FACTOR: f64 = 1e6;
 
+/// Number of decimals of an Algo, i.e. MicroAlgos per Algo is 10^MICRO_ALGO_DECIMALS.
+const MICRO_ALGO_DECIMALS: usize = 6;
+const MICRO_ALGOS_PER_ALGO: u64 = 1_000_000;
+
 /// MicroAlgos are the base unit of currency in Algorand
 #[derive(
-    Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Serialize, Deserialize, Display, Add, Sub,
+    Copy, Clone, Debug, Default, Ord, PartialOrd, Eq, PartialEq, Serialize, Deserialize, Add, Sub,
 )]
 pub struct MicroAlgos(pub u64);
 
@@ -36,6 +52,112 @@ impl MicroAlgos {
     pub fn from_algos(algos: f64) -> MicroAlgos {
         MicroAlgos((algos * MICRO_ALGO_CONVERSION_FACTOR) as u64)
     }
+
+    /// Parses an Algo amount in decimal notation (e.g. "12.345678"), without floating point rounding.
+    ///
+    /// At most 6 decimals are accepted. Returns an error if the string is malformed or the amount
+    /// doesn't fit in a u64.
+    pub fn from_algos_str(algos: &str) -> Result<MicroAlgos, CoreError> {
+        let invalid = || CoreError::General(format!("Invalid algos amount: {:?}", algos));
+        let (whole, fraction) = match algos.split_once('.') {
+            Some((whole, fraction)) if !fraction.is_empty() => (whole, fraction),
+            Some(_) => return Err(invalid()),
+            None => (algos, ""),
+        };
+        let is_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+        if whole.is_empty()
+            || !is_digits(whole)
+            || !is_digits(fraction)
+            || fraction.len() > MICRO_ALGO_DECIMALS
+        {
+            return Err(invalid());
+        }
+        let whole = whole.parse::<u64>().map_err(|_| invalid())?;
+        let fraction = format!("{:0<width$}", fraction, width = MICRO_ALGO_DECIMALS)
+            .parse::<u64>()
+            .map_err(|_| invalid())?;
+        whole
+            .checked_mul(MICRO_ALGOS_PER_ALGO)
+            .and_then(|micro_algos| micro_algos.checked_add(fraction))
+            .map(MicroAlgos)
+            .ok_or_else(invalid)
+    }
+
+    /// Formats the amount in Algos with exactly 6 decimals (e.g. "12.345678").
+    pub fn to_algos_string(self) -> String {
+        format!(
+            "{}.{:0width$}",
+            self.0 / MICRO_ALGOS_PER_ALGO,
+            self.0 % MICRO_ALGOS_PER_ALGO,
+            width = MICRO_ALGO_DECIMALS
+        )
+    }
+
+    pub fn checked_add(self, rhs: MicroAlgos) -> Option<MicroAlgos> {
+        self.0.checked_add(rhs.0).map(MicroAlgos)
+    }
+
+    pub fn checked_sub(self, rhs: MicroAlgos) -> Option<MicroAlgos> {
+        self.0.checked_sub(rhs.0).map(MicroAlgos)
+    }
+
+    pub fn checked_mul(self, rhs: u64) -> Option<MicroAlgos> {
+        self.0.checked_mul(rhs).map(MicroAlgos)
+    }
+
+    pub fn saturating_add(self, rhs: MicroAlgos) -> MicroAlgos {
+        MicroAlgos(self.0.saturating_add(rhs.0))
+    }
+
+    pub fn saturating_sub(self, rhs: MicroAlgos) -> MicroAlgos {
+        MicroAlgos(self.0.saturating_sub(rhs.0))
+    }
+
+    pub fn saturating_mul(self, rhs: u64) -> MicroAlgos {
+        MicroAlgos(self.0.saturating_mul(rhs))
+    }
+}
+
+/// Formats the amount with its unit, e.g. "1000 microAlgos".
+impl fmt::Display for MicroAlgos {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} microAlgos", self.0)
+    }
+}
+
+impl From<MicroAlgos> for u64 {
+    fn from(amount: MicroAlgos) -> Self {
+        amount.0
+    }
+}
+
+/// Panics if the sum overflows, like the other operators in debug builds but also in release
+/// builds: sum into an `Option<MicroAlgos>` to get `None` instead.
+impl Sum for MicroAlgos {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.sum::<Option<MicroAlgos>>()
+            .expect("overflow summing MicroAlgos")
+    }
+}
+
+/// The sum, or `None` if it overflows.
+impl Sum<MicroAlgos> for Option<MicroAlgos> {
+    fn sum<I: Iterator<Item = MicroAlgos>>(mut iter: I) -> Self {
+        iter.try_fold(MicroAlgos(0), MicroAlgos::checked_add)
+    }
+}
+
+/// The sum, or `None` if it overflows.
+impl<'a> Sum<&'a MicroAlgos> for Option<MicroAlgos> {
+    fn sum<I: Iterator<Item = &'a MicroAlgos>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+impl<'a> Sum<&'a MicroAlgos> for MicroAlgos {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
 }
 
 impl Add<u64> for MicroAlgos {
@@ -66,9 +188,54 @@ impl Mul<u64> for MicroAlgos {
 }
 
 /// Round of the Algorand consensus protocol
-#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize, Deserialize, Display, Add, Sub)]
+#[derive(
+    Copy,
+    Clone,
+    Default,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Hash,
+    Debug,
+    Serialize,
+    Deserialize,
+    Display,
+    Add,
+    Sub,
+)]
 pub struct Round(pub u64);
 
+impl Round {
+    pub fn checked_add(self, rhs: u64) -> Option<Round> {
+        self.0.checked_add(rhs).map(Round)
+    }
+
+    pub fn checked_sub(self, rhs: u64) -> Option<Round> {
+        self.0.checked_sub(rhs).map(Round)
+    }
+
+    pub fn saturating_add(self, rhs: u64) -> Round {
+        Round(self.0.saturating_add(rhs))
+    }
+
+    pub fn saturating_sub(self, rhs: u64) -> Round {
+        Round(self.0.saturating_sub(rhs))
+    }
+}
+
+impl From<u64> for Round {
+    fn from(round: u64) -> Self {
+        Round(round)
+    }
+}
+
+impl From<Round> for u64 {
+    fn from(round: Round) -> Self {
+        round.0
+    }
+}
+
 impl Add<u64> for Round {
     type Output = Self;
 
@@ -96,6 +263,77 @@ impl Mul<u64> for Round {
     }
 }
 
+/// Inclusive range of rounds, e.g. a transaction's validity window or the rounds to query in the indexer.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Serialize, Deserialize)]
+pub struct RoundRange {
+    pub min: Round,
+    pub max: Round,
+}
+
+impl RoundRange {
+    /// The rounds from `min` to `max`, both included.
+    pub fn new(min: impl Into<Round>, max: impl Into<Round>) -> RoundRange {
+        RoundRange {
+            min: min.into(),
+            max: max.into(),
+        }
+    }
+
+    /// The `count` rounds starting at `first` (included), e.g. `RoundRange::starting_at(Round(10), 3)`
+    /// contains rounds 10, 11 and 12.
+    ///
+    /// Saturates at `u64::MAX`. If `count` is 0 the range is empty, still starting at `first`: it
+    /// ends at the round before, e.g. `RoundRange::starting_at(Round(10), 0)` is `10..=9`. Round 0
+    /// having no round before, the empty range starting at it is `1..=0`.
+    pub fn starting_at(first: impl Into<Round>, count: u64) -> RoundRange {
+        let first = first.into();
+        match (count, first.checked_sub(1)) {
+            (0, Some(before)) => RoundRange {
+                min: first,
+                max: before,
+            },
+            (0, None) => RoundRange {
+                min: Round(1),
+                max: Round(0),
+            },
+            (count, _) => RoundRange {
+                min: first,
+                max: first.saturating_add(count - 1),
+            },
+        }
+    }
+
+    /// The `count` rounds after `round` (excluded), e.g. the next 1000 rounds after the current one.
+    pub fn next(round: impl Into<Round>, count: u64) -> RoundRange {
+        Self::starting_at(round.into().saturating_add(1), count)
+    }
+
+    /// Returns whether `round` is in the range.
+    pub fn contains(&self, round: Round) -> bool {
+        self.min <= round && round <= self.max
+    }
+
+    /// Returns whether the range contains no rounds, i.e. `min` is greater than `max`.
+    pub fn is_empty(&self) -> bool {
+        self.min > self.max
+    }
+
+    /// Number of rounds in the range.
+    pub fn len(&self) -> u64 {
+        if self.is_empty() {
+            0
+        } else {
+            (self.max.0 - self.min.0).saturating_add(1)
+        }
+    }
+}
+
+impl From<(Round, Round)> for RoundRange {
+    fn from((min, max): (Round, Round)) -> Self {
+        RoundRange { min, max }
+    }
+}
+
 /// Participation public key used in key registration transactions
 #[derive(Copy, Clone, Eq, PartialEq)]
 pub struct VotePk(pub [u8; 32]);
@@ -172,6 +410,46 @@ impl VrfPk {
     }
 }
 
+/// State proof public key used in key registration transactions
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub struct StateProofPk(pub [u8; 64]);
+
+impl Serialize for StateProofPk {
+    fn serialize<S>(&self, serializer: S) -> Result<<S as Serializer>::Ok, <S as Serializer>::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(&self.0[..])
+    }
+}
+
+impl<'de> Deserialize<'de> for StateProofPk {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(StateProofPk(
+            deserializer.deserialize_bytes(SignatureVisitor)?,
+        ))
+    }
+}
+
+impl Debug for StateProofPk {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_base64_str())
+    }
+}
+
+impl StateProofPk {
+    pub fn from_base64_str(base64_str: &str) -> Result<StateProofPk, CoreError> {
+        Ok(StateProofPk(base64_str_to_u8_array(base64_str)?))
+    }
+
+    pub fn to_base64_str(self) -> String {
+        BASE64.encode(&self.0)
+    }
+}
+
 #[derive(Eq, PartialEq, Clone)]
 pub struct SignedLogic {
     pub logic: CompiledTeal,
@@ -253,20 +531,403 @@ fn base64_str_to_u8_array<const N: usize>(base64_str: &str) -> Result<[u8; N], C
         .map_err(|v| CoreError::General(format!("Couldn't convert vec: {:?} into u8 array", v)))
 }
 
+/// Parameters needed to build a transaction.
+///
+/// The serialized representation is meant to be human-friendly and stable, so the params can be
+/// cached (e.g. as JSON) and reused: the genesis hash is base64 encoded, fees and rounds are integers.
+///
+/// The params are usually read from a node (algod's `/v2/transactions/params`), with all the fields
+/// of its response. For air-gapped signers, [offline](Self::offline) builds them from values known
+/// beforehand.
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct SuggestedTransactionParams {
+    /// The id of the network, e.g. "mainnet-v1.0". Empty if unknown: the protocol only requires
+    /// the genesis hash.
     pub genesis_id: String,
+    #[serde(
+        serialize_with = "serialize_hash_base64",
+        deserialize_with = "deserialize_hash_base64"
+    )]
     pub genesis_hash: HashDigest,
+    /// The consensus protocol as of the last round. Empty if unknown (e.g. for offline params).
     pub consensus_version: String,
     pub fee_per_byte: MicroAlgos,
     pub min_fee: MicroAlgos,
+    /// The first round transactions are valid in: for the params of a node, the last round it
+    /// saw.
     pub first_valid: Round,
     pub last_valid: Round,
 }
 
+impl SuggestedTransactionParams {
+    /// Params known without a node, to build transactions offline: transactions are valid from
+    /// `first_valid` to [MAX_VALIDITY_WINDOW] rounds after it (the maximum of the protocol), and
+    /// pay `min_fee`.
+    ///
+    /// Transactions built with these params are identical to the ones built with the params of a
+    /// node reporting the same genesis, minimum fee and round, and no congestion fee.
+    pub fn offline(
+        genesis_id: Option<&str>,
+        genesis_hash: HashDigest,
+        min_fee: MicroAlgos,
+        first_valid: Round,
+    ) -> SuggestedTransactionParams {
+        SuggestedTransactionParams {
+            genesis_id: genesis_id.unwrap_or_default().to_owned(),
+            genesis_hash,
+            consensus_version: String::new(),
+            fee_per_byte: MicroAlgos(0),
+            min_fee,
+            first_valid,
+            last_valid: first_valid
+                .checked_add(MAX_VALIDITY_WINDOW)
+                .unwrap_or(Round(u64::MAX)),
+        }
+    }
+
+    /// The id of the network, if known.
+    pub fn genesis_id(&self) -> Option<&str> {
+        Some(self.genesis_id.as_str()).filter(|id| !id.is_empty())
+    }
+
+    /// The consensus protocol as of the last round, if known.
+    pub fn consensus_version(&self) -> Option<&str> {
+        Some(self.consensus_version.as_str()).filter(|version| !version.is_empty())
+    }
+
+    /// Returns whether transactions built with these params can still be confirmed at the given round.
+    pub fn is_valid_at(&self, round: Round) -> bool {
+        self.first_valid.0 <= round.0 && round.0 <= self.last_valid.0
+    }
+
+    /// The rounds in which transactions built with these params can be confirmed.
+    pub fn validity(&self) -> RoundRange {
+        RoundRange::new(self.first_valid, self.last_valid)
+    }
+
+    /// Sets the validity range to `first` and the `window` rounds after it, from `first` to
+    /// `first + window`, like the validity window of the transaction builder.
+    ///
+    /// Returns an error if `window` exceeds [MAX_VALIDITY_WINDOW], the limit of the protocol, or if
+    /// the last valid round doesn't fit in a u64.
+    pub fn with_validity_window(mut self, first: Round, window: u64) -> Result<Self, CoreError> {
+        let invalid = || {
+            CoreError::General(format!(
+                "Invalid validity window of {} rounds after round {}",
+                window, first.0
+            ))
+        };
+        if window > MAX_VALIDITY_WINDOW {
+            return Err(invalid());
+        }
+        self.last_valid = first.checked_add(window).ok_or_else(invalid)?;
+        self.first_valid = first;
+        Ok(self)
+    }
+}
+
+/// The maximum number of rounds between the first and last valid rounds of a transaction,
+/// allowed by the protocol (`MaxTxnLife`).
+pub const MAX_VALIDITY_WINDOW: u64 = 1000;
+
+fn serialize_hash_base64<S>(hash: &HashDigest, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&BASE64.encode(&hash.0))
+}
+
+fn deserialize_hash_base64<'de, D>(deserializer: D) -> Result<HashDigest, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    use serde::de::Error;
+    let string = String::deserialize(deserializer)?;
+    Ok(HashDigest(
+        base64_str_to_u8_array(&string).map_err(D::Error::custom)?,
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::{rngs::OsRng, Rng};
+
+    fn suggested_params() -> SuggestedTransactionParams {
+        SuggestedTransactionParams {
+            genesis_id: "testnet-v1.0".to_owned(),
+            genesis_hash: HashDigest(
+                BASE64
+                    .decode(b"SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=")
+                    .unwrap()
+                    .try_into()
+                    .unwrap(),
+            ),
+            consensus_version: "https://github.com/algorandfoundation/specs/tree/abc54f79f9ad679d2d22f0fb9909fb005c16f8a1".to_owned(),
+            fee_per_byte: MicroAlgos(0),
+            min_fee: MicroAlgos(1000),
+            first_valid: Round(1000),
+            last_valid: Round(2000),
+        }
+    }
+
+    #[test]
+    fn test_offline_suggested_params() {
+        let params = SuggestedTransactionParams::offline(
+            Some("testnet-v1.0"),
+            suggested_params().genesis_hash,
+            MicroAlgos(1000),
+            Round(1000),
+        );
+
+        assert_eq!(
+            SuggestedTransactionParams {
+                consensus_version: String::new(),
+                ..suggested_params()
+            },
+            params
+        );
+        assert_eq!(Some("testnet-v1.0"), params.genesis_id());
+        assert_eq!(None, params.consensus_version());
+
+        let params = SuggestedTransactionParams::offline(
+            None,
+            suggested_params().genesis_hash,
+            MicroAlgos(1000),
+            Round(1000),
+        );
+        assert_eq!(None, params.genesis_id());
+    }
+
+    #[test]
+    fn micro_algos_algos_string_roundtrip() {
+        for _ in 0..1_000 {
+            let micro_algos = MicroAlgos(OsRng.gen());
+            let algos = micro_algos.to_algos_string();
+            assert_eq!(micro_algos, MicroAlgos::from_algos_str(&algos).unwrap());
+        }
+        for micro_algos in [0, 1, 999_999, 1_000_000, u64::MAX - 1, u64::MAX] {
+            let micro_algos = MicroAlgos(micro_algos);
+            let algos = micro_algos.to_algos_string();
+            assert_eq!(micro_algos, MicroAlgos::from_algos_str(&algos).unwrap());
+        }
+    }
+
+    #[test]
+    fn micro_algos_to_algos_string() {
+        assert_eq!("0.000000", MicroAlgos(0).to_algos_string());
+        assert_eq!("0.000001", MicroAlgos(1).to_algos_string());
+        assert_eq!("12.345678", MicroAlgos(12_345_678).to_algos_string());
+        assert_eq!(
+            "18446744073709.551615",
+            MicroAlgos(u64::MAX).to_algos_string()
+        );
+    }
+
+    #[test]
+    fn micro_algos_from_algos_str() {
+        assert_eq!(
+            MicroAlgos(12_000_000),
+            MicroAlgos::from_algos_str("12").unwrap()
+        );
+        assert_eq!(
+            MicroAlgos(12_300_000),
+            MicroAlgos::from_algos_str("12.3").unwrap()
+        );
+        assert_eq!(
+            MicroAlgos(1),
+            MicroAlgos::from_algos_str("0.000001").unwrap()
+        );
+        // 0.1 + 0.2 style floating point errors must not happen
+        assert_eq!(
+            MicroAlgos(300_000),
+            MicroAlgos::from_algos_str("0.3").unwrap()
+        );
+
+        for invalid in [
+            "",
+            ".",
+            "1.",
+            ".5",
+            "-1",
+            "+1",
+            "1.0000001",
+            "1,5",
+            "1.2.3",
+            " 1",
+            "18446744073709.551616",
+            "18446744073710",
+        ] {
+            assert!(
+                MicroAlgos::from_algos_str(invalid).is_err(),
+                "{:?} should not parse",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn micro_algos_checked_and_saturating_arithmetic() {
+        let max = MicroAlgos(u64::MAX);
+        assert_eq!(None, max.checked_add(MicroAlgos(1)));
+        assert_eq!(
+            Some(max),
+            MicroAlgos(u64::MAX - 1).checked_add(MicroAlgos(1))
+        );
+        assert_eq!(None, MicroAlgos(0).checked_sub(MicroAlgos(1)));
+        assert_eq!(
+            Some(MicroAlgos(0)),
+            MicroAlgos(1).checked_sub(MicroAlgos(1))
+        );
+        assert_eq!(None, max.checked_mul(2));
+        assert_eq!(Some(MicroAlgos(6)), MicroAlgos(3).checked_mul(2));
+
+        assert_eq!(max, max.saturating_add(MicroAlgos(1)));
+        assert_eq!(MicroAlgos(0), MicroAlgos(0).saturating_sub(MicroAlgos(1)));
+        assert_eq!(max, max.saturating_mul(2));
+        assert_eq!(MicroAlgos(6), MicroAlgos(3).saturating_mul(2));
+    }
+
+    #[test]
+    fn micro_algos_sum() {
+        let amounts = vec![MicroAlgos(1), MicroAlgos(2), MicroAlgos(3)];
+        assert_eq!(MicroAlgos(6), amounts.iter().sum());
+        assert_eq!(MicroAlgos(6), amounts.into_iter().sum());
+        assert_eq!(MicroAlgos(0), Vec::<MicroAlgos>::new().into_iter().sum());
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow summing MicroAlgos")]
+    fn micro_algos_sum_overflow() {
+        let _: MicroAlgos = [MicroAlgos(u64::MAX), MicroAlgos(1)].iter().sum();
+    }
+
+    #[test]
+    fn micro_algos_checked_sum() {
+        let amounts = [MicroAlgos(1), MicroAlgos(2)];
+        assert_eq!(Some(MicroAlgos(3)), amounts.iter().sum());
+        assert_eq!(
+            None,
+            vec![MicroAlgos(u64::MAX), MicroAlgos(1)]
+                .into_iter()
+                .sum::<Option<MicroAlgos>>()
+        );
+    }
+
+    #[test]
+    fn micro_algos_display() {
+        assert_eq!("1000 microAlgos", MicroAlgos(1000).to_string());
+    }
+
+    #[test]
+    fn round_arithmetic() {
+        assert_eq!(Round(11), Round(10) + 1);
+        assert_eq!(Round(9), Round(10) - 1);
+        assert_eq!(None, Round(u64::MAX).checked_add(1));
+        assert_eq!(Some(Round(11)), Round(10).checked_add(1));
+        assert_eq!(None, Round(0).checked_sub(1));
+        assert_eq!(Some(Round(9)), Round(10).checked_sub(1));
+        assert_eq!(Round(u64::MAX), Round(u64::MAX).saturating_add(1));
+        assert_eq!(Round(0), Round(0).saturating_sub(1));
+        assert!(Round(1) < Round(2));
+        assert_eq!(Round(5), 5u64.into());
+        assert_eq!(5u64, u64::from(Round(5)));
+        assert_eq!("5", Round(5).to_string());
+    }
+
+    #[test]
+    fn round_range() {
+        let range = RoundRange::starting_at(Round(10), 3);
+        assert_eq!(RoundRange::new(Round(10), Round(12)), range);
+        assert_eq!(3, range.len());
+        assert!(!range.contains(Round(9)));
+        assert!(range.contains(Round(10)));
+        assert!(range.contains(Round(12)));
+        assert!(!range.contains(Round(13)));
+
+        assert_eq!(
+            RoundRange::new(Round(11), Round(1010)),
+            RoundRange::next(Round(10), 1000)
+        );
+
+        let empty = RoundRange::starting_at(Round(10), 0);
+        assert!(empty.is_empty());
+        assert_eq!(0, empty.len());
+        assert!(!empty.contains(Round(10)));
+        assert_eq!(RoundRange::new(Round(10), Round(9)), empty);
+        assert_eq!(
+            RoundRange::new(Round(11), Round(10)),
+            RoundRange::next(Round(10), 0)
+        );
+        assert!(RoundRange::starting_at(Round(0), 0).is_empty());
+
+        assert_eq!(
+            RoundRange::new(Round(u64::MAX - 1), Round(u64::MAX)),
+            RoundRange::starting_at(Round(u64::MAX - 1), 5)
+        );
+    }
+
+    #[test]
+    fn suggested_params_json_roundtrip() {
+        let params = suggested_params();
+        let json = serde_json::to_string(&params).unwrap();
+        let deserialized: SuggestedTransactionParams = serde_json::from_str(&json).unwrap();
+        assert_eq!(params, deserialized);
+    }
+
+    #[test]
+    fn suggested_params_json_is_human_friendly() {
+        let json = serde_json::to_value(suggested_params()).unwrap();
+        assert_eq!(
+            "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=",
+            json["genesis_hash"]
+        );
+        assert_eq!(1000, json["min_fee"]);
+        assert_eq!(1000, json["first_valid"]);
+        assert_eq!(2000, json["last_valid"]);
+    }
+
+    #[test]
+    fn suggested_params_invalid_genesis_hash() {
+        let mut json = serde_json::to_value(suggested_params()).unwrap();
+        json["genesis_hash"] = "AAAA".into();
+        assert!(serde_json::from_value::<SuggestedTransactionParams>(json).is_err());
+    }
+
+    #[test]
+    fn suggested_params_validity() {
+        let params = suggested_params();
+        assert!(!params.is_valid_at(Round(999)));
+        assert!(params.is_valid_at(Round(1000)));
+        assert!(params.is_valid_at(Round(2000)));
+        assert!(!params.is_valid_at(Round(2001)));
+
+        assert_eq!(RoundRange::new(Round(1000), Round(2000)), params.validity());
+
+        let params = params.with_validity_window(Round(3000), 10).unwrap();
+        assert_eq!(Round(3000), params.first_valid);
+        assert_eq!(Round(3010), params.last_valid);
+        assert!(params.is_valid_at(Round(3010)));
+        assert!(!params.is_valid_at(Round(3011)));
+        assert!(!params.is_valid_at(Round(2000)));
+
+        let params = params
+            .with_validity_window(Round(3000), MAX_VALIDITY_WINDOW)
+            .unwrap();
+        assert_eq!(Round(4000), params.last_valid);
+    }
+
+    #[test]
+    fn suggested_params_invalid_validity_window() {
+        let params = suggested_params();
+        assert!(params
+            .clone()
+            .with_validity_window(Round(3000), MAX_VALIDITY_WINDOW + 1)
+            .is_err());
+        assert!(params
+            .with_validity_window(Round(u64::MAX - 5), 10)
+            .is_err());
+    }
 
     #[test]
     fn computes_program_address_correctly() {
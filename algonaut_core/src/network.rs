@@ -0,0 +1,134 @@
+use algonaut_crypto::HashDigest;
+use algonaut_encoding::U8_32Visitor;
+use data_encoding::BASE64;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::convert::TryInto;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::str::FromStr;
+
+use crate::error::CoreError;
+
+const MAINNET_GENESIS_HASH: &str = "wGHE2Pwdvd7S12BL5FaOP20EGYesN73ktiC1qzkkit8=";
+const TESTNET_GENESIS_HASH: &str = "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=";
+const BETANET_GENESIS_HASH: &str = "mFgazF+2uRS1tMiL9dsj01hJGySEmPN28B/TjjvpVW0=";
+
+/// Hash of the genesis block, identifying the network a transaction or block belongs to.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct GenesisHash(pub [u8; 32]);
+
+impl FromStr for GenesisHash {
+    type Err = CoreError;
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        let bytes = BASE64.decode(string.as_bytes())?;
+        Ok(GenesisHash(bytes.try_into().map_err(|v: Vec<u8>| {
+            CoreError::General(format!("Invalid genesis hash length: {}", v.len()))
+        })?))
+    }
+}
+
+impl Display for GenesisHash {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", BASE64.encode(&self.0))
+    }
+}
+
+impl Debug for GenesisHash {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", BASE64.encode(&self.0))
+    }
+}
+
+impl Serialize for GenesisHash {
+    fn serialize<S>(&self, serializer: S) -> Result<<S as Serializer>::Ok, <S as Serializer>::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(&self.0[..])
+    }
+}
+
+impl<'de> Deserialize<'de> for GenesisHash {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(GenesisHash(deserializer.deserialize_bytes(U8_32Visitor)?))
+    }
+}
+
+impl From<HashDigest> for GenesisHash {
+    fn from(digest: HashDigest) -> Self {
+        GenesisHash(digest.0)
+    }
+}
+
+impl From<GenesisHash> for HashDigest {
+    fn from(hash: GenesisHash) -> Self {
+        HashDigest(hash.0)
+    }
+}
+
+/// Algorand network, as identified by its genesis hash.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Network {
+    MainNet,
+    TestNet,
+    BetaNet,
+    /// Any other network (e.g. a private network or sandbox), with its base64 encoded genesis hash.
+    Other(String),
+}
+
+impl Network {
+    /// Determines the network from its genesis hash.
+    pub fn from_genesis_hash(hash: &GenesisHash) -> Network {
+        match hash.to_string().as_str() {
+            MAINNET_GENESIS_HASH => Network::MainNet,
+            TESTNET_GENESIS_HASH => Network::TestNet,
+            BETANET_GENESIS_HASH => Network::BetaNet,
+            other => Network::Other(other.to_owned()),
+        }
+    }
+}
+
+impl From<GenesisHash> for Network {
+    fn from(hash: GenesisHash) -> Self {
+        Network::from_genesis_hash(&hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn genesis_hash_string_roundtrip() {
+        let hash = MAINNET_GENESIS_HASH.parse::<GenesisHash>().unwrap();
+        assert_eq!(MAINNET_GENESIS_HASH, hash.to_string());
+    }
+
+    #[test]
+    fn genesis_hash_invalid_length() {
+        assert!("AAAA".parse::<GenesisHash>().is_err());
+    }
+
+    #[test]
+    fn detects_known_networks() {
+        for (hash, network) in [
+            (MAINNET_GENESIS_HASH, Network::MainNet),
+            (TESTNET_GENESIS_HASH, Network::TestNet),
+            (BETANET_GENESIS_HASH, Network::BetaNet),
+        ] {
+            let hash = hash.parse::<GenesisHash>().unwrap();
+            assert_eq!(network, Network::from_genesis_hash(&hash));
+        }
+    }
+
+    #[test]
+    fn detects_other_network() {
+        let hash = GenesisHash([1; 32]);
+        assert_eq!(
+            Network::Other(hash.to_string()),
+            Network::from_genesis_hash(&hash)
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use data_encoding::{DecodeKind, BASE32_NOPAD};
+use std::convert::TryFrom;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::str::FromStr;
+use thiserror::Error;
+
+const HASH_LEN: usize = 32;
+/// Length of the base32 encoding of a transaction id.
+const TXID_LEN: usize = 52;
+
+/// Transaction id: the hash of a transaction, displayed in base32 (without padding).
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct TxId(pub [u8; HASH_LEN]);
+
+impl TxId {
+    /// Parses a transaction id, after normalizing it: the surrounding whitespace is ignored, and
+    /// lowercase letters are read as uppercase.
+    fn decode_from_string(string: &str) -> Result<TxId, TxIdParseError> {
+        let normalized = string.trim().to_ascii_uppercase();
+        if normalized.len() != TXID_LEN {
+            return Err(TxIdParseError::WrongLength {
+                length: normalized.chars().count(),
+            });
+        }
+        let bytes = BASE32_NOPAD.decode(normalized.as_bytes()).map_err(|e| {
+            match normalized.chars().nth(e.position) {
+                Some(character) if !matches!(e.kind, DecodeKind::Length) => {
+                    TxIdParseError::InvalidCharacter {
+                        character,
+                        position: e.position,
+                    }
+                }
+                _ => TxIdParseError::WrongLength {
+                    length: normalized.chars().count(),
+                },
+            }
+        })?;
+        let mut hash = [0; HASH_LEN];
+        hash.copy_from_slice(&bytes);
+        Ok(TxId(hash))
+    }
+}
+
+impl FromStr for TxId {
+    type Err = TxIdParseError;
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        TxId::decode_from_string(string)
+    }
+}
+
+impl TryFrom<&str> for TxId {
+    type Error = TxIdParseError;
+    fn try_from(string: &str) -> Result<Self, Self::Error> {
+        TxId::decode_from_string(string)
+    }
+}
+
+/// Error parsing a [TxId] from its string representation.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum TxIdParseError {
+    #[error("Invalid transaction id length: {length} characters, expected 52.")]
+    WrongLength { length: usize },
+    #[error("Invalid character '{character}' at position {position} of the transaction id: transaction ids contain only A-Z and 2-7.")]
+    InvalidCharacter { character: char, position: usize },
+}
+
+impl Debug for TxId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", BASE32_NOPAD.encode(&self.0))
+    }
+}
+
+impl Display for TxId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", BASE32_NOPAD.encode(&self.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TXID: &str = "TDIO6RJWJIVDDJZELMSX5CPJW7MUNM3QR4YAHYAKHF3W2CFRTI7A";
+
+    #[test]
+    fn decode() {
+        let txid = TXID.parse::<TxId>().unwrap();
+        assert_eq!(TXID, txid.to_string());
+    }
+
+    #[test]
+    fn decode_normalizes() {
+        let txid = format!(" {}\n", TXID.to_ascii_lowercase())
+            .parse::<TxId>()
+            .unwrap();
+        assert_eq!(TXID, txid.to_string());
+    }
+
+    #[test]
+    fn decode_wrong_length() {
+        assert_eq!(
+            Err(TxIdParseError::WrongLength { length: 51 }),
+            TXID[1..].parse::<TxId>()
+        );
+        assert_eq!(
+            Err(TxIdParseError::WrongLength { length: 0 }),
+            TxId::try_from(" ")
+        );
+    }
+
+    #[test]
+    fn decode_invalid_character() {
+        assert_eq!(
+            Err(TxIdParseError::InvalidCharacter {
+                character: '0',
+                position: 1
+            }),
+            format!("T0{}", &TXID[2..]).parse::<TxId>()
+        );
+    }
+}
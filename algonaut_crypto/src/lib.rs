@@ -29,6 +29,12 @@ impl FromStr for HashDigest {
     }
 }
 
+impl From<[u8; 32]> for HashDigest {
+    fn from(bytes: [u8; 32]) -> Self {
+        HashDigest(bytes)
+    }
+}
+
 impl Display for HashDigest {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "{}", BASE64.encode(&self.0))
@@ -74,8 +74,25 @@ pub fn deserialize_bytes<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let s = <&str>::deserialize(deserializer)?;
-    Ok(BASE64.decode(s.as_bytes()).unwrap())
+    deserializer.deserialize_str(Base64Visitor)
+}
+
+/// Decodes a base64 string, borrowed or not (e.g. from a `serde_json::Value`).
+struct Base64Visitor;
+
+impl<'de> Visitor<'de> for Base64Visitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a base64 string")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        BASE64.decode(v.as_bytes()).map_err(E::custom)
+    }
 }
 
 pub fn serialize_bytes<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
@@ -85,6 +102,26 @@ where
     serializer.serialize_str(&BASE64.encode(bytes))
 }
 
+/// Deserializes a list of base64 encoded byte arrays.
+pub fn deserialize_vec_bytes<'de, D>(deserializer: D) -> Result<Vec<Vec<u8>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    use serde::de::Error;
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|s| BASE64.decode(s.as_bytes()).map_err(D::Error::custom))
+        .collect()
+}
+
+/// Serializes a list of byte arrays as base64 strings.
+pub fn serialize_vec_bytes<S>(bytes_vec: &[Vec<u8>], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.collect_seq(bytes_vec.iter().map(|bytes| BASE64.encode(bytes)))
+}
+
 pub fn decode_base64(bytes: &[u8]) -> Result<Vec<u8>, String> {
     BASE64.decode(bytes).map_err(|e| e.to_string())
 }
@@ -1,8 +1,14 @@
-use algonaut_core::{Address, MicroAlgos, Round};
+use crate::{FieldDecodeError, ParticipationStatus};
+use algonaut_core::{
+    Address, AssetAmount, MicroAlgos, Round, StateProofPk, SuggestedTransactionParams, VotePk,
+    VrfPk, MAX_VALIDITY_WINDOW,
+};
 use algonaut_crypto::{deserialize_hash, HashDigest};
-use algonaut_encoding::deserialize_bytes;
+use algonaut_encoding::{deserialize_bytes, deserialize_vec_bytes, serialize_vec_bytes};
 use serde::{Deserialize, Serialize};
-use serde_with::{serde_as, DisplayFromStr};
+use serde_with::{serde_as, DisplayFromStr, DurationNanoSeconds};
+use std::collections::BTreeMap;
+use std::time::Duration;
 
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -32,6 +38,13 @@ pub struct Account {
     #[serde(rename = "apps-total-schema")]
     pub apps_total_schema: Option<ApplicationStateSchema>,
 
+    /// `teap` the sum of the extra program pages of the applications created by the account.
+    #[serde(
+        rename = "apps-total-extra-pages",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub apps_total_extra_pages: Option<u64>,
+
     /// `asset` assets held by this account.
     /// Note the raw object uses map(int) -> AssetHolding for this type.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
@@ -64,6 +77,36 @@ pub struct Account {
     )]
     pub created_assets: Vec<Asset>,
 
+    /// Whether the account is eligible for the incentives, i.e. the payouts of the blocks it
+    /// proposes (set by the registration of its participation keys with the incentive fee).
+    #[serde(
+        default,
+        rename = "incentive-eligible",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub incentive_eligible: Option<bool>,
+
+    /// The round of the last heartbeat of the account, i.e. when it went online or renewed its
+    /// online status.
+    #[serde(
+        default,
+        rename = "last-heartbeat",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub last_heartbeat: Option<Round>,
+
+    /// The round of the last block proposed by the account.
+    #[serde(
+        default,
+        rename = "last-proposed",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub last_proposed: Option<Round>,
+
+    /// The minimum balance of the account, required by its assets, applications and boxes.
+    #[serde(rename = "min-balance", skip_serializing_if = "Option::is_none")]
+    pub min_balance: Option<MicroAlgos>,
+
     ///
     pub participation: Option<AccountParticipation>,
 
@@ -94,17 +137,87 @@ pub struct Account {
     /// * Online - indicates that the associated account used as part of the delegation pool.
     /// * NotParticipating - indicates that the associated account is neither a delegator nor a delegate.
     pub status: String,
+    /// The count of all the applications opted in by the account (`apps_local_state` may be
+    /// excluded from the response).
+    #[serde(
+        rename = "total-apps-opted-in",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub total_apps_opted_in: Option<u64>,
+
+    /// The count of all the assets held by the account, including the ones it created.
+    #[serde(
+        rename = "total-assets-opted-in",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub total_assets_opted_in: Option<u64>,
+
+    /// The total size in bytes (names and values) of the boxes of the account, if it's the
+    /// account of an application.
+    #[serde(rename = "total-box-bytes", skip_serializing_if = "Option::is_none")]
+    pub total_box_bytes: Option<u64>,
+
+    /// The count of the boxes of the account, if it's the account of an application.
+    #[serde(rename = "total-boxes", skip_serializing_if = "Option::is_none")]
+    pub total_boxes: Option<u64>,
+
+    /// The count of all the applications created by the account.
+    #[serde(rename = "total-created-apps", skip_serializing_if = "Option::is_none")]
+    pub total_created_apps: Option<u64>,
+
+    /// The count of all the assets created by the account.
+    #[serde(
+        rename = "total-created-assets",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub total_created_assets: Option<u64>,
+}
+
+impl Account {
+    /// The participation of the account in consensus at `current_round`, derived from its
+    /// status and the validity of its participation keys at the round.
+    pub fn participation_status(&self, current_round: Round) -> ParticipationStatus {
+        let keys_valid = self
+            .participation
+            .as_ref()
+            .is_some_and(|participation| participation.is_valid_at(current_round));
+        crate::participation_status(&self.status, keys_valid)
+    }
 }
 
 /// Signature types.
+///
+/// Types introduced by future protocol versions are deserialized as [SignatureType::Unknown].
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(from = "String", into = "String")]
 pub enum SignatureType {
-    #[serde(rename = "sig")]
     Sig,
-    #[serde(rename = "msig")]
     MultiSig,
-    #[serde(rename = "lsig")]
     LSig,
+    /// A type not known by this version of the SDK.
+    Unknown(String),
+}
+
+impl From<String> for SignatureType {
+    fn from(value: String) -> Self {
+        match value.as_ref() {
+            "sig" => SignatureType::Sig,
+            "msig" => SignatureType::MultiSig,
+            "lsig" => SignatureType::LSig,
+            _ => SignatureType::Unknown(value),
+        }
+    }
+}
+
+impl From<SignatureType> for String {
+    fn from(value: SignatureType) -> Self {
+        match value {
+            SignatureType::Sig => "sig".to_owned(),
+            SignatureType::MultiSig => "msig".to_owned(),
+            SignatureType::LSig => "lsig".to_owned(),
+            SignatureType::Unknown(value) => value,
+        }
+    }
 }
 
 /// AccountParticipation describes the parameters used by this account in consensus protocol.
@@ -120,6 +233,15 @@ pub struct AccountParticipation {
     )]
     pub selection_participation_key: Vec<u8>,
 
+    /// `stprf` root of the state proof key (if any).
+    #[serde(
+        rename = "state-proof-key",
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "deserialize_bytes"
+    )]
+    pub state_proof_key: Vec<u8>,
+
     /// `voteFst` First round for which this participation is valid.
     #[serde(rename = "vote-first-valid")]
     pub vote_first_valid: u64,
@@ -143,6 +265,30 @@ pub struct AccountParticipation {
     pub vote_participation_key: Vec<u8>,
 }
 
+impl AccountParticipation {
+    /// The vote key, if registered.
+    pub fn vote_pk(&self) -> Option<VotePk> {
+        crate::typed_key(&self.vote_participation_key, VotePk)
+    }
+
+    /// The selection key, if registered.
+    pub fn selection_pk(&self) -> Option<VrfPk> {
+        crate::typed_key(&self.selection_participation_key, VrfPk)
+    }
+
+    /// The state proof key, if registered.
+    pub fn state_proof_pk(&self) -> Option<StateProofPk> {
+        crate::typed_key(&self.state_proof_key, StateProofPk)
+    }
+
+    /// Whether the participation keys are registered and valid at `round`.
+    pub fn is_valid_at(&self, round: Round) -> bool {
+        self.vote_pk().is_some()
+            && Round(self.vote_first_valid) <= round
+            && round <= Round(self.vote_last_valid)
+    }
+}
+
 /// Application state delta.
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -210,6 +356,10 @@ pub struct ApplicationParams {
     #[serde_as(as = "DisplayFromStr")]
     pub creator: Address,
 
+    /// `epp` the amount of extra program pages available to this app.
+    #[serde(rename = "extra-program-pages")]
+    pub extra_program_pages: Option<u64>,
+
     /// `gs` global schema
     #[serde(
         default,
@@ -249,6 +399,19 @@ pub struct Asset {
     pub params: AssetParams,
 }
 
+impl Asset {
+    /// An amount of the asset, from its base units, with the decimals of the asset.
+    pub fn amount(&self, raw: u64) -> AssetAmount {
+        crate::asset_amount(raw, self.params.decimals)
+    }
+
+    /// Formats an amount of the asset, from its base units, in the unit of the asset (e.g. "12.340"
+    /// with 3 decimals).
+    pub fn format_amount(&self, raw: u64) -> String {
+        self.amount(raw).format()
+    }
+}
+
 /// Describes an asset held by an account.
 /// Definition: data/basics/userBalance.go : AssetHolding
 #[serde_as]
@@ -487,6 +650,13 @@ pub struct EvalDeltaKeyValue {
     pub value: EvalDelta,
 }
 
+impl EvalDeltaKeyValue {
+    /// The key, decoded from base64.
+    pub fn decoded_key(&self) -> Result<Vec<u8>, FieldDecodeError> {
+        crate::decode_base64_field(&self.key, "key")
+    }
+}
+
 /// Represents a key-value pair in an application store.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
 pub struct TealKeyValue {
@@ -494,6 +664,13 @@ pub struct TealKeyValue {
     pub value: TealValue,
 }
 
+impl TealKeyValue {
+    /// The key, decoded from base64.
+    pub fn decoded_key(&self) -> Result<Vec<u8>, FieldDecodeError> {
+        crate::decode_base64_field(&self.key, "key")
+    }
+}
+
 /// Represents a TEAL value.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
 pub struct TealValue {
@@ -528,6 +705,22 @@ pub struct Version {
     pub versions: Vec<String>,
 }
 
+/// The OpenAPI specification of the endpoints served by the node (`/swagger.json`).
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ApiSpec {
+    /// The specification of each path, e.g. `/v2/applications/{application-id}/boxes`.
+    #[serde(default)]
+    pub paths: BTreeMap<String, serde_json::Value>,
+}
+
+impl ApiSpec {
+    /// Whether the node serves this path, as written in the specification (e.g.
+    /// `/v2/stateproofs/{round}`).
+    pub fn has_path(&self, path: &str) -> bool {
+        self.paths.contains_key(path)
+    }
+}
+
 /// Version contains the current algod version.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct GenesisBlock {
@@ -541,17 +734,29 @@ pub struct Transaction {}
 /// A potentially truncated list of transactions currently in the node's transaction pool.
 /// You can compute whether or not the list is truncated if the number of elements in the
 /// top-transactions array is fewer than total-transactions.
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct PendingTransactions {
+///
+/// `T` is the type of the signed transactions, decoded from the MessagePack encoding of the node
+/// (e.g. `algonaut_transaction::SignedTransaction`).
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PendingTransactions<T> {
     /// An array of signed transaction objects.
-    #[serde(rename = "top-transactions")]
-    pub top_transactions: Vec<Transaction>,
+    #[serde(rename = "top-transactions", default = "Vec::new")]
+    pub top_transactions: Vec<T>,
 
     /// Total number of transactions in the pool.
-    #[serde(rename = "total-transactions")]
+    #[serde(rename = "total-transactions", default)]
     pub total_transactions: u64,
 }
 
+impl<T> PendingTransactions<T> {
+    /// Whether the pool holds more transactions than listed, i.e. the list was cut at the `max`
+    /// of the request: a full list of `max` transactions doesn't tell on its own whether the pool
+    /// has exactly `max` transactions or more.
+    pub fn is_truncated(&self) -> bool {
+        (self.top_transactions.len() as u64) < self.total_transactions
+    }
+}
+
 /// A specific pending transaction.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PendingTransaction {
@@ -584,6 +789,10 @@ pub struct PendingTransaction {
     )]
     pub global_state_delta: Vec<EvalDeltaKeyValue>,
 
+    /// Inner transactions produced by application execution.
+    #[serde(default, rename = "inner-txns", skip_serializing_if = "Vec::is_empty")]
+    pub inner_txns: Vec<PendingTransaction>,
+
     /// `ld` Local state key/value changes for the application being executed by this
     /// transaction.
     #[serde(
@@ -593,9 +802,19 @@ pub struct PendingTransaction {
     )]
     pub local_state_delta: Vec<AccountStateDelta>,
 
+    /// Logs emitted by the application being executed by this transaction.
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "deserialize_vec_bytes",
+        serialize_with = "serialize_vec_bytes"
+    )]
+    pub logs: Vec<Vec<u8>>,
+
     /// Indicates that the transaction was kicked out of this node's transaction pool
     /// (and specifies why that happened). An empty string indicates the transaction
     /// wasn't kicked out of this node's txpool due to an error.
+    /// `algonaut::algod::v2::AlgodSemanticError::parse` parses the common reasons.
     #[serde(rename = "pool-error")]
     pub pool_error: String,
 
@@ -611,7 +830,40 @@ pub struct PendingTransaction {
     pub txn: Transaction,
 }
 
+impl PendingTransaction {
+    /// The ids of the asset and of the application created by this transaction, if any.
+    pub fn created_ids(&self) -> (Option<u64>, Option<u64>) {
+        (self.asset_index, self.application_index)
+    }
+
+    /// The logs of this transaction, decoded as UTF-8 text.
+    ///
+    /// Invalid UTF-8 sequences are replaced with `U+FFFD`: use [logs](Self::logs) for binary logs.
+    pub fn decoded_logs(&self) -> Vec<String> {
+        self.logs
+            .iter()
+            .map(|log| String::from_utf8_lossy(log).into_owned())
+            .collect()
+    }
+
+    /// The logs of this transaction followed by the logs of its inner transactions, depth first.
+    pub fn all_logs(&self) -> Vec<&[u8]> {
+        let mut logs: Vec<&[u8]> = self.logs.iter().map(Vec::as_slice).collect();
+        for inner_txn in &self.inner_txns {
+            logs.extend(inner_txn.all_logs());
+        }
+        logs
+    }
+
+    /// The return value of an ARC-4 method call: the last log of this transaction, without the
+    /// prefix, if it's prefixed with [ARC4_RETURN_PREFIX](crate::ARC4_RETURN_PREFIX).
+    pub fn return_value_log(&self) -> Option<&[u8]> {
+        crate::arc4_return_value(&self.logs)
+    }
+}
+
 /// Information about the status of a node
+#[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct NodeStatus {
     /// The current catchpoint that is being caught up to
@@ -639,9 +891,24 @@ pub struct NodeStatus {
     #[serde(rename = "catchpoint-verified-accounts")]
     pub catchpoint_verified_accounts: Option<u64>,
 
-    /// CatchupTime in nanoseconds
+    /// The number of key-values (KVs) from the current catchpoint that have been processed so far
+    /// as part of the catchup
+    #[serde(rename = "catchpoint-processed-kvs")]
+    pub catchpoint_processed_kvs: Option<u64>,
+
+    /// The total number of key-values (KVs) included in the current catchpoint
+    #[serde(rename = "catchpoint-total-kvs")]
+    pub catchpoint_total_kvs: Option<u64>,
+
+    /// The number of key-values (KVs) from the current catchpoint that have been verified so far
+    /// as part of the catchup
+    #[serde(rename = "catchpoint-verified-kvs")]
+    pub catchpoint_verified_kvs: Option<u64>,
+
+    /// CatchupTime, the time spent catching up (sent in nanoseconds).
+    #[serde_as(as = "DurationNanoSeconds<u64>")]
     #[serde(rename = "catchup-time")]
-    pub catchup_time: u64,
+    pub catchup_time: Duration,
 
     /// The last catchpoint seen by the node
     #[serde(rename = "last-catchpoint")]
@@ -672,9 +939,94 @@ pub struct NodeStatus {
     #[serde(rename = "stopped-at-unsupported-round")]
     pub stopped_at_unsupported_round: bool,
 
-    /// TimeSinceLastRound in nanoseconds.
+    /// TimeSinceLastRound (sent in nanoseconds).
+    #[serde_as(as = "DurationNanoSeconds<u64>")]
     #[serde(rename = "time-since-last-round")]
-    pub time_since_last_round: u64,
+    pub time_since_last_round: Duration,
+
+    /// Rounds between the approval of a consensus upgrade and its application.
+    #[serde(rename = "upgrade-delay", skip_serializing_if = "Option::is_none")]
+    pub upgrade_delay: Option<u64>,
+
+    /// The round before which the vote on the proposed consensus upgrade ends.
+    #[serde(
+        rename = "upgrade-next-protocol-vote-before",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub upgrade_next_protocol_vote_before: Option<Round>,
+
+    /// Votes against the proposed consensus upgrade so far.
+    #[serde(rename = "upgrade-no-votes", skip_serializing_if = "Option::is_none")]
+    pub upgrade_no_votes: Option<u64>,
+
+    /// The vote of this node on the proposed consensus upgrade.
+    #[serde(rename = "upgrade-node-vote", skip_serializing_if = "Option::is_none")]
+    pub upgrade_node_vote: Option<bool>,
+
+    /// Rounds of the vote on the proposed consensus upgrade.
+    #[serde(
+        rename = "upgrade-vote-rounds",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub upgrade_vote_rounds: Option<u64>,
+
+    /// Votes on the proposed consensus upgrade so far.
+    #[serde(rename = "upgrade-votes", skip_serializing_if = "Option::is_none")]
+    pub upgrade_votes: Option<u64>,
+
+    /// Votes for the proposed consensus upgrade required to approve it.
+    #[serde(
+        rename = "upgrade-votes-required",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub upgrade_votes_required: Option<u64>,
+
+    /// Votes for the proposed consensus upgrade so far.
+    #[serde(rename = "upgrade-yes-votes", skip_serializing_if = "Option::is_none")]
+    pub upgrade_yes_votes: Option<u64>,
+}
+
+impl NodeStatus {
+    /// Whether the node is catching up to a catchpoint (fast catchup).
+    pub fn is_catching_up(&self) -> bool {
+        self.catchpoint
+            .as_deref()
+            .is_some_and(|catchpoint| !catchpoint.is_empty())
+    }
+
+    /// The vote on a proposed consensus upgrade, if one is in progress.
+    pub fn upgrade_vote(&self) -> Option<UpgradeVote> {
+        let vote_before = self.upgrade_next_protocol_vote_before.filter(|r| r.0 > 0)?;
+        Some(UpgradeVote {
+            vote_before,
+            vote_rounds: self.upgrade_vote_rounds.unwrap_or_default(),
+            votes: self.upgrade_votes.unwrap_or_default(),
+            yes_votes: self.upgrade_yes_votes.unwrap_or_default(),
+            no_votes: self.upgrade_no_votes.unwrap_or_default(),
+            votes_required: self.upgrade_votes_required.unwrap_or_default(),
+            node_vote: self.upgrade_node_vote.unwrap_or_default(),
+        })
+    }
+}
+
+/// The vote of the block proposers on a proposed consensus upgrade: see
+/// [NodeStatus::upgrade_vote].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpgradeVote {
+    /// The round before which the vote ends.
+    pub vote_before: Round,
+    /// Rounds of the vote.
+    pub vote_rounds: u64,
+    /// Votes so far.
+    pub votes: u64,
+    /// Votes for the upgrade so far.
+    pub yes_votes: u64,
+    /// Votes against the upgrade so far.
+    pub no_votes: u64,
+    /// Votes for the upgrade required to approve it.
+    pub votes_required: u64,
+    /// The vote of this node.
+    pub node_vote: bool,
 }
 
 /// Block
@@ -706,6 +1058,31 @@ pub struct BlockHeader {
     pub txn: Option<String>,
 }
 
+impl BlockHeader {
+    /// The hash of the previous block, decoded, or `None` if it's empty.
+    pub fn previous_hash(&self) -> Result<Option<HashDigest>, FieldDecodeError> {
+        if self.prev.is_empty() {
+            return Ok(None);
+        }
+        crate::decode_block_hash_field(&self.prev, "prev").map(Some)
+    }
+}
+
+/// Hash of a block.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BlockHashResponse {
+    /// Block header hash, base32 encoded.
+    #[serde(rename = "blockHash")]
+    pub block_hash: String,
+}
+
+impl BlockHashResponse {
+    /// The hash, decoded.
+    pub fn hash(&self) -> Result<HashDigest, FieldDecodeError> {
+        crate::decode_block_hash_field(&self.block_hash, "blockHash")
+    }
+}
+
 /// Catchup
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Catchup {
@@ -800,8 +1177,136 @@ pub struct TransactionParams {
     pub min_fee: MicroAlgos,
 }
 
+impl From<TransactionParams> for SuggestedTransactionParams {
+    /// The params of transactions valid from the last round of the node to
+    /// [MAX_VALIDITY_WINDOW] rounds after it (the maximum of the protocol).
+    fn from(params: TransactionParams) -> Self {
+        SuggestedTransactionParams {
+            genesis_id: params.genesis_id,
+            genesis_hash: params.genesis_hash,
+            consensus_version: params.consensus_version,
+            fee_per_byte: params.fee_per_byte,
+            min_fee: params.min_fee,
+            first_valid: params.last_round,
+            last_valid: params
+                .last_round
+                .checked_add(MAX_VALIDITY_WINDOW)
+                .unwrap_or(Round(u64::MAX)),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TransactionResponse {
     #[serde(rename = "txId")]
     pub tx_id: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An online account eligible for the incentives, with the fields of algod's API since the
+    /// consensus incentives. It's built for the test (made-up keys), not captured from a node.
+    const ONLINE_ACCOUNT: &str = r#"{
+        "address": "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU",
+        "amount": 120000000000,
+        "amount-without-pending-rewards": 120000000000,
+        "created-apps": [],
+        "created-assets": [],
+        "apps-local-state": [],
+        "incentive-eligible": true,
+        "last-heartbeat": 46000100,
+        "last-proposed": 46001500,
+        "participation": {
+            "selection-participation-key": "ISIjJCUmJygpKissLS4vMDEyMzQ1Njc4OTo7PD0+P0A=",
+            "state-proof-key": "QUJDREVGR0hJSktMTU5PUFFSU1RVVldYWVpbXF1eX2BhYmNkZWZnaGlqa2xtbm9wcXJzdHV2d3h5ent8fX5/gA==",
+            "vote-first-valid": 46000000,
+            "vote-key-dilution": 2450,
+            "vote-last-valid": 49000000,
+            "vote-participation-key": "AQIDBAUGBwgJCgsMDQ4PEBESExQVFhcYGRobHB0eHyA="
+        },
+        "pending-rewards": 0,
+        "reward-base": 218288,
+        "rewards": 0,
+        "round": 46002000,
+        "sig-type": "sig",
+        "status": "Online"
+    }"#;
+
+    #[test]
+    fn test_online_account() {
+        let account: Account = serde_json::from_str(ONLINE_ACCOUNT).unwrap();
+
+        assert_eq!(Some(true), account.incentive_eligible);
+        assert_eq!(Some(Round(46000100)), account.last_heartbeat);
+        assert_eq!(Some(Round(46001500)), account.last_proposed);
+        let participation = account.participation.as_ref().unwrap();
+        assert_eq!(2450, participation.vote_key_dilution);
+        assert!(participation.vote_pk() == Some(VotePk(core::array::from_fn(|i| i as u8 + 1))));
+        assert!(
+            participation.selection_pk() == Some(VrfPk(core::array::from_fn(|i| i as u8 + 33)))
+        );
+        assert!(
+            participation.state_proof_pk()
+                == Some(StateProofPk(core::array::from_fn(|i| i as u8 + 65)))
+        );
+
+        assert_eq!(
+            ParticipationStatus::Online,
+            account.participation_status(Round(46002000))
+        );
+        // the keys expired
+        assert_eq!(
+            ParticipationStatus::Offline,
+            account.participation_status(Round(49000001))
+        );
+    }
+
+    #[test]
+    fn test_participation_status() {
+        let mut account: Account = serde_json::from_str(ONLINE_ACCOUNT).unwrap();
+
+        account.status = "Offline".to_owned();
+        assert_eq!(
+            ParticipationStatus::Suspended,
+            account.participation_status(Round(46002000))
+        );
+        assert_eq!(
+            ParticipationStatus::Offline,
+            account.participation_status(Round(45999999))
+        );
+
+        account.participation = None;
+        assert_eq!(
+            ParticipationStatus::Offline,
+            account.participation_status(Round(46002000))
+        );
+
+        account.status = "NotParticipating".to_owned();
+        assert_eq!(
+            ParticipationStatus::NotParticipating,
+            account.participation_status(Round(46002000))
+        );
+    }
+
+    #[test]
+    fn test_account_without_incentive_fields() {
+        let mut json: serde_json::Value = serde_json::from_str(ONLINE_ACCOUNT).unwrap();
+        let object = json.as_object_mut().unwrap();
+        for field in ["incentive-eligible", "last-heartbeat", "last-proposed"] {
+            object.remove(field);
+        }
+        object["participation"]
+            .as_object_mut()
+            .unwrap()
+            .remove("state-proof-key");
+
+        // the keys are deserialized from borrowed strings only
+        let account: Account = serde_json::from_str(&json.to_string()).unwrap();
+
+        assert_eq!(None, account.incentive_eligible);
+        assert_eq!(None, account.last_proposed);
+        assert!(account.participation.unwrap().state_proof_pk().is_none());
+    }
+}
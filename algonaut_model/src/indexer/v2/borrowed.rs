@@ -0,0 +1,288 @@
+//! Borrowed views of the indexer responses, for read-only scans of large pages.
+//!
+//! The views deserialize the strings as [Cow]s borrowing from the response body, instead of
+//! allocating a `String` for each one, and skip the fields they don't have: parsing a page into a
+//! [TransactionResponseRef] allocates little more than the vectors of transactions. A string is
+//! only allocated when it contains JSON escapes, which the indexer's encoding of addresses, ids
+//! and base64 values never has.
+//!
+//! The views have the fields needed to scan transactions (ids, parties, amounts, application
+//! calls): use the owned responses (e.g. [TransactionResponse](super::TransactionResponse)) for
+//! the other fields.
+
+use algonaut_core::{MicroAlgos, Round};
+use serde::Deserialize;
+use std::borrow::Cow;
+
+/// Borrowed view of a [TransactionResponse](super::TransactionResponse).
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct TransactionResponseRef<'a> {
+    /// Round at which the results were computed.
+    #[serde(default, rename = "current-round")]
+    pub current_round: u64,
+
+    /// Used for pagination, when making another request provide this token with the next parameter.
+    #[serde(borrow, rename = "next-token")]
+    pub next_token: Option<Cow<'a, str>>,
+
+    #[serde(default, borrow)]
+    pub transactions: Vec<TransactionRef<'a>>,
+}
+
+/// Borrowed view of a [Transaction](super::Transaction).
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct TransactionRef<'a> {
+    /// Transaction ID, empty for inner transactions.
+    #[serde(borrow, default)]
+    pub id: Cow<'a, str>,
+
+    /// Type of the transaction, e.g. "pay" or "appl".
+    #[serde(borrow, rename = "tx-type")]
+    pub tx_type: Cow<'a, str>,
+
+    /// `snd` Sender's address.
+    #[serde(borrow)]
+    pub sender: Cow<'a, str>,
+
+    /// `fee` Transaction fee.
+    #[serde(default)]
+    pub fee: u64,
+
+    /// Round when the transaction was confirmed.
+    #[serde(rename = "confirmed-round")]
+    pub confirmed_round: Option<Round>,
+
+    /// Offset into the round where this transaction was confirmed.
+    #[serde(rename = "intra-round-offset")]
+    pub intra_round_offset: Option<u64>,
+
+    /// Time when the block this transaction is in was confirmed.
+    #[serde(rename = "round-time")]
+    pub round_time: Option<u64>,
+
+    /// `fv` First valid round for this transaction.
+    #[serde(default, rename = "first-valid")]
+    pub first_valid: Round,
+
+    /// `lv` Last valid round for this transaction.
+    #[serde(default, rename = "last-valid")]
+    pub last_valid: Round,
+
+    /// `grp` Base64 encoded byte array of a sha512/256 digest.
+    #[serde(borrow, default)]
+    pub group: Option<Cow<'a, str>>,
+
+    /// `note` Free form data, base64 encoded.
+    #[serde(borrow, default)]
+    pub note: Option<Cow<'a, str>>,
+
+    /// Payment.
+    #[serde(borrow, rename = "payment-transaction")]
+    pub payment_transaction: Option<TransactionPaymentRef<'a>>,
+
+    /// Asset transfer.
+    #[serde(borrow, rename = "asset-transfer-transaction")]
+    pub asset_transfer_transaction: Option<TransactionAssetTransferRef<'a>>,
+
+    /// Application call.
+    #[serde(borrow, rename = "application-transaction")]
+    pub application_transaction: Option<TransactionApplicationRef<'a>>,
+
+    /// Specifies an application index (ID) if an application was created with this transaction.
+    #[serde(rename = "created-application-index")]
+    pub created_application_index: Option<u64>,
+
+    /// Specifies an asset index (ID) if an asset was created with this transaction.
+    #[serde(rename = "created-asset-index")]
+    pub created_asset_index: Option<u64>,
+
+    /// Inner transactions produced by application execution.
+    #[serde(borrow, default, rename = "inner-txns")]
+    pub inner_txns: Vec<TransactionRef<'a>>,
+}
+
+/// Borrowed view of a [TransactionPayment](super::TransactionPayment).
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct TransactionPaymentRef<'a> {
+    /// `amt` number of MicroAlgos intended to be transferred.
+    #[serde(default)]
+    pub amount: MicroAlgos,
+
+    /// Number of MicroAlgos that were sent to the close-remainder-to address when closing the
+    /// sender account.
+    #[serde(rename = "close-amount")]
+    pub close_amount: Option<MicroAlgos>,
+
+    /// `close` when set, indicates that the sending account should be closed and all remaining
+    /// funds be transferred to this address.
+    #[serde(borrow, default, rename = "close-remainder-to")]
+    pub close_remainder_to: Option<Cow<'a, str>>,
+
+    /// `rcv` receiver's address.
+    #[serde(borrow)]
+    pub receiver: Cow<'a, str>,
+}
+
+/// Borrowed view of a [TransactionAssetTransfer](super::TransactionAssetTransfer).
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct TransactionAssetTransferRef<'a> {
+    /// `aamt` Amount of asset to transfer. A zero amount transferred to self allocates that asset
+    /// in the account's Assets map.
+    #[serde(default)]
+    pub amount: u64,
+
+    /// `xaid` ID of the asset being transferred.
+    #[serde(default, rename = "asset-id")]
+    pub asset_id: u64,
+
+    /// Number of assets transfered to the close-to account as part of the transaction.
+    #[serde(rename = "close-amount")]
+    pub close_amount: Option<u64>,
+
+    /// `aclose` Indicates that the asset should be removed from the account's Assets map, and
+    /// specifies where the remaining asset holdings should be transferred. It's always valid to
+    /// transfer remaining asset holdings to the creator account.
+    #[serde(borrow, default, rename = "close-to")]
+    pub close_to: Option<Cow<'a, str>>,
+
+    /// `arcv` Recipient address of the transfer.
+    #[serde(borrow)]
+    pub receiver: Cow<'a, str>,
+
+    /// `asnd` The effective sender during a clawback transactions. If this is not a zero value, the
+    /// real transaction sender must be the Clawback address from the AssetParams.
+    #[serde(borrow, default)]
+    pub sender: Option<Cow<'a, str>>,
+}
+
+/// Borrowed view of a [TransactionApplication](super::TransactionApplication).
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct TransactionApplicationRef<'a> {
+    /// `apid` ID of the application being configured or empty if creating.
+    #[serde(default, rename = "application-id")]
+    pub application_id: u64,
+
+    /// `apan` defines the what additional actions occur with the transaction, e.g. "noop".
+    #[serde(borrow, default, rename = "on-completion")]
+    pub on_completion: Option<Cow<'a, str>>,
+
+    /// `apaa` transaction specific arguments accessed from the application's approval-program and
+    /// clear-state-program, base64 encoded.
+    #[serde(borrow, default, rename = "application-args")]
+    pub application_args: Vec<Cow<'a, str>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"{
+        "current-round": 100,
+        "next-token": "Y2JBAAAAAAAAAAAA",
+        "transactions": [
+            {
+                "confirmed-round": 90,
+                "fee": 1000,
+                "first-valid": 80,
+                "genesis-hash": "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=",
+                "id": "TDIO6RJWJIVDDJZELMSX5CPJW7MUNM3QR4YAHYAKHF3W2CFRTI7A",
+                "intra-round-offset": 3,
+                "last-valid": 1080,
+                "note": "aGVsbG8=",
+                "payment-transaction": {
+                    "amount": 5000,
+                    "close-amount": 0,
+                    "receiver": "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU"
+                },
+                "round-time": 1650000000,
+                "sender": "2UEQTE5QDNXPI7M3TU44G6SYKLFWLPQO7EBZM7K7MHMQQMFI4QJPLHQFHM",
+                "signature": { "sig": "c2lnbmF0dXJl" },
+                "tx-type": "pay"
+            },
+            {
+                "application-transaction": {
+                    "application-id": 7,
+                    "application-args": ["AQ=="],
+                    "on-completion": "noop"
+                },
+                "fee": 2000,
+                "first-valid": 80,
+                "genesis-hash": "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=",
+                "id": "UGWZ7QT4MMEGVLJ4WZRWPYFIJ6EEUOLZBAKSVGTXZIYRDAKTPNNA",
+                "inner-txns": [
+                    {
+                        "asset-transfer-transaction": {
+                            "amount": 3,
+                            "asset-id": 31566704,
+                            "receiver": "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU"
+                        },
+                        "fee": 0,
+                        "first-valid": 80,
+                        "genesis-hash": "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=",
+                        "id": "",
+                        "last-valid": 1080,
+                        "sender": "PCYUFPA2ZTOYWTP43MX2MOX2OWAIAXUDNC2WFCXAGMRUZ3DYD6BWFDL5YM",
+                        "tx-type": "axfer"
+                    }
+                ],
+                "last-valid": 1080,
+                "sender": "2UEQTE5QDNXPI7M3TU44G6SYKLFWLPQO7EBZM7K7MHMQQMFI4QJPLHQFHM",
+                "tx-type": "appl"
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn test_transaction_response_ref() {
+        let page: TransactionResponseRef = serde_json::from_str(PAGE).unwrap();
+
+        assert_eq!(100, page.current_round);
+        assert_eq!(Some("Y2JBAAAAAAAAAAAA"), page.next_token.as_deref());
+        assert_eq!(2, page.transactions.len());
+
+        let payment = &page.transactions[0];
+        assert!(matches!(payment.id, Cow::Borrowed(_)));
+        assert!(matches!(payment.sender, Cow::Borrowed(_)));
+        assert_eq!("pay", payment.tx_type);
+        assert_eq!(Some(Round(90)), payment.confirmed_round);
+        assert_eq!(Some(3), payment.intra_round_offset);
+        assert_eq!(Some("aGVsbG8="), payment.note.as_deref());
+        let payment_fields = payment.payment_transaction.as_ref().unwrap();
+        assert_eq!(MicroAlgos(5000), payment_fields.amount);
+        assert!(matches!(payment_fields.receiver, Cow::Borrowed(_)));
+
+        let call = &page.transactions[1];
+        let application = call.application_transaction.as_ref().unwrap();
+        assert_eq!(7, application.application_id);
+        assert_eq!(Some("noop"), application.on_completion.as_deref());
+        assert_eq!(vec!["AQ=="], application.application_args);
+        let transfer = call.inner_txns[0]
+            .asset_transfer_transaction
+            .as_ref()
+            .unwrap();
+        assert_eq!(31566704, transfer.asset_id);
+        assert_eq!("", call.inner_txns[0].id);
+    }
+
+    #[test]
+    fn test_escaped_strings_are_owned() {
+        let page: TransactionResponseRef =
+            serde_json::from_str(r#"{"current-round":1,"next-token":"a\/b","transactions":[]}"#)
+                .unwrap();
+        assert_eq!(Some(Cow::Owned("a/b".to_owned())), page.next_token);
+    }
+
+    #[test]
+    fn test_matches_owned_response() {
+        let owned: super::super::TransactionResponse = serde_json::from_str(PAGE).unwrap();
+        let borrowed: TransactionResponseRef = serde_json::from_str(PAGE).unwrap();
+
+        for (owned, borrowed) in owned.transactions.iter().zip(&borrowed.transactions) {
+            assert_eq!(owned.id, borrowed.id);
+            assert_eq!(owned.sender, borrowed.sender);
+            assert_eq!(owned.fee, borrowed.fee);
+            assert_eq!(owned.confirmed_round, borrowed.confirmed_round);
+            assert_eq!(owned.note.as_deref(), borrowed.note.as_deref());
+        }
+    }
+}
@@ -1,10 +1,46 @@
-use algonaut_core::{Address, MicroAlgos, Round};
+use crate::teal::TealKeyDelta;
+use crate::{FieldDecodeError, ParticipationStatus, QueryError};
+use algonaut_core::{
+    Address, AssetAmount, MicroAlgos, Round, RoundRange, StateProofPk, VotePk, VrfPk,
+};
 use algonaut_crypto::HashDigest;
-use algonaut_encoding::deserialize_bytes;
+use algonaut_encoding::{deserialize_bytes, deserialize_vec_bytes, serialize_vec_bytes};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use serde_with::{serde_as, DisplayFromStr};
+use serde_with::CommaSeparator;
+use serde_with::{serde_as, DisplayFromStr, StringWithSeparator};
+use std::collections::BTreeMap;
+use std::convert::{Infallible, TryFrom, TryInto};
+use std::fmt;
+use std::str::FromStr;
+
+pub mod borrowed;
+
+/// The query parameters of an indexer request, e.g. [QueryTransaction].
+pub trait ToQueryParams: Serialize {
+    /// The query string as sent by the client, without the leading `?`, e.g.
+    /// "address=...&limit=10&min-round=5": to log or reproduce a request (e.g. with curl).
+    ///
+    /// The parameters are in the order of the fields of the query, and the unset ones are
+    /// omitted.
+    fn to_query_string(&self) -> String {
+        serde_urlencoded::to_string(self).expect("queries serialize to flat key-value pairs")
+    }
+}
+
+impl ToQueryParams for QueryAccount {}
+impl ToQueryParams for QueryAccountInfo {}
+impl ToQueryParams for QueryAccountTransaction {}
+impl ToQueryParams for QueryApplications {}
+impl ToQueryParams for QueryApplicationInfo {}
+impl ToQueryParams for QueryAssets {}
+impl ToQueryParams for QueryAssetsInfo {}
+impl ToQueryParams for QueryBalances {}
+impl ToQueryParams for QueryAssetTransaction {}
+impl ToQueryParams for QueryTransaction {}
 
 ///
+#[serde_as]
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
 pub struct QueryAccount {
     /// Application ID.
@@ -29,6 +65,12 @@ pub struct QueryAccount {
     #[serde(rename = "currency-less-than")]
     pub currency_less_than: Option<u64>,
 
+    /// The resources of the accounts left out of the results, sent comma-separated, e.g.
+    /// [Exclude::All] for the accounts without their assets and applications.
+    #[serde(default)]
+    #[serde_as(as = "Option<StringWithSeparator::<CommaSeparator, Exclude>>")]
+    pub exclude: Option<Vec<Exclude>>,
+
     /// Maximum number of results to return.
     pub limit: Option<u64>,
 
@@ -40,14 +82,55 @@ pub struct QueryAccount {
     pub round: Option<Round>,
 }
 
+impl QueryAccount {
+    /// Searches the accounts holding some of the asset, i.e. opted in with a nonzero balance,
+    /// without their assets and applications ([Exclude::All]) to keep the pages small.
+    ///
+    /// See [with_currency_greater_than](Self::with_currency_greater_than) for a higher balance.
+    pub fn holders_of_asset(asset_id: u64) -> Self {
+        QueryAccount {
+            asset_id: Some(asset_id),
+            currency_greater_than: Some(0),
+            exclude: Some(vec![Exclude::All]),
+            ..QueryAccount::default()
+        }
+    }
+
+    /// Searches the accounts opted into the application, without their assets and applications
+    /// ([Exclude::All]) to keep the pages small.
+    pub fn opted_into_app(application_id: u64) -> Self {
+        QueryAccount {
+            application_id: Some(application_id),
+            exclude: Some(vec![Exclude::All]),
+            ..QueryAccount::default()
+        }
+    }
+
+    /// Restricts the results to the accounts with a balance greater than `amount`: of the asset
+    /// with an `asset_id`, in microAlgos otherwise.
+    pub fn with_currency_greater_than(mut self, amount: u64) -> Self {
+        self.currency_greater_than = Some(amount);
+        self
+    }
+
+    /// Checks the query before sending it: the ids can't be zero and the authorized address must
+    /// be valid.
+    pub fn validate(&self) -> Result<(), QueryError> {
+        validate_id(self.asset_id, "asset_id")?;
+        validate_id(self.application_id, "application_id")?;
+        validate_address(&self.auth_addr, "auth_addr")
+    }
+}
+
 ///
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct AccountResponse {
     /// Accounts.
+    #[serde(default)]
     pub accounts: Vec<Account>,
 
     /// Round at which the results were computed..
-    #[serde(rename = "current-round")]
+    #[serde(default, rename = "current-round")]
     pub current_round: u64,
 
     /// Used for pagination, when making another request provide this token with the next
@@ -75,7 +158,7 @@ pub struct AccountInfoResponse {
     pub account: Account,
 
     /// Round at which the results were computed.
-    #[serde(rename = "current-round")]
+    #[serde(default, rename = "current-round")]
     pub current_round: Round,
 }
 
@@ -151,11 +234,27 @@ pub struct QueryAccountTransaction {
     pub txid: Option<String>,
 }
 
+impl QueryAccountTransaction {
+    /// Checks the query before sending it: the round range can't be empty and the asset id can't
+    /// be zero.
+    pub fn validate(&self) -> Result<(), QueryError> {
+        validate_round_range(self.min_round, self.max_round)?;
+        validate_id(self.asset_id, "asset_id")
+    }
+
+    /// Restricts the results to the rounds in `range` (both ends included).
+    pub fn with_round_range(mut self, range: RoundRange) -> Self {
+        self.min_round = Some(range.min);
+        self.max_round = Some(range.max);
+        self
+    }
+}
+
 /// Resonse to account transactions' endpoint.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct AccountTransactionResponse {
     /// Round at which the results were computed.
-    #[serde(rename = "current-round")]
+    #[serde(default, rename = "current-round")]
     pub current_round: u64,
 
     /// Used for pagination, when making another request provide this token with the next parameter.
@@ -163,17 +262,23 @@ pub struct AccountTransactionResponse {
     pub next_token: Option<String>,
 
     /// Transaction list.
-    #[serde(rename = "transactions")]
+    #[serde(default, rename = "transactions")]
     pub transactions: Vec<Transaction>,
 }
 
 /// Query applications.
+#[serde_as]
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
 pub struct QueryApplications {
     /// Application ID.
     #[serde(rename = "application-id", skip_serializing_if = "Option::is_none")]
     pub application_id: Option<u64>,
 
+    /// Filter just applications with the given creator address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub creator: Option<Address>,
+
     /// Maximum number of results to return.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub limit: Option<u64>,
@@ -183,14 +288,21 @@ pub struct QueryApplications {
     pub next: Option<String>,
 }
 
+impl QueryApplications {
+    /// Checks the query before sending it: the application id can't be zero.
+    pub fn validate(&self) -> Result<(), QueryError> {
+        validate_id(self.application_id, "application_id")
+    }
+}
+
 /// Response for applications/ endpoint.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ApplicationResponse {
-    #[serde(rename = "applications")]
+    #[serde(default, rename = "applications")]
     pub applications: Vec<Application>,
 
     /// Round at which the results were computed.
-    #[serde(rename = "current-round")]
+    #[serde(default, rename = "current-round")]
     pub current_round: i32,
 
     /// Used for pagination, when making another request provide this token with the next parameter.
@@ -213,26 +325,126 @@ pub struct ApplicationInfoResponse {
     pub application: Option<Box<Application>>,
 
     /// Round at which the results were computed.
-    #[serde(rename = "current-round")]
+    #[serde(default, rename = "current-round")]
     pub current_round: i32,
 }
 
+/// Response for applications/id endpoint, without the programs and the global state of the
+/// application (see [MiniApplication]).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MiniApplicationInfoResponse {
+    #[serde(rename = "application", skip_serializing_if = "Option::is_none")]
+    pub application: Option<Box<MiniApplication>>,
+
+    /// Round at which the results were computed.
+    #[serde(default, rename = "current-round")]
+    pub current_round: i32,
+}
+
+/// Fails if `min_round` is after `max_round`: no round would match.
+fn validate_round_range(
+    min_round: Option<Round>,
+    max_round: Option<Round>,
+) -> Result<(), QueryError> {
+    match (min_round, max_round) {
+        (Some(min), Some(max)) if min > max => Err(QueryError {
+            reason: format!("min_round > max_round ({} > {})", min.0, max.0),
+            param: Some("min_round"),
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Fails if the id is zero: no asset or application has it.
+fn validate_id(id: Option<u64>, param: &'static str) -> Result<(), QueryError> {
+    match id {
+        Some(0) => Err(QueryError {
+            reason: format!("{} is zero", param),
+            param: Some(param),
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Fails if the address isn't a valid Algorand address.
+fn validate_address(address: &Option<String>, param: &'static str) -> Result<(), QueryError> {
+    match address.as_deref().map(str::parse::<Address>) {
+        Some(Err(e)) => Err(QueryError {
+            reason: format!("invalid {}: {}", param, e),
+            param: Some(param),
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Fails if close-to addresses are excluded without searching the receivers of an address: the
+/// close-to fields only match as receivers, so the flag would be ignored.
+fn validate_exclude_close_to(
+    exclude_close_to: Option<bool>,
+    address: &Option<String>,
+    address_role: &Option<Role>,
+) -> Result<(), QueryError> {
+    if exclude_close_to != Some(true) {
+        return Ok(());
+    }
+    match (address, address_role) {
+        (None, _) => Err(QueryError {
+            reason: "exclude_close_to requires an address".to_owned(),
+            param: Some("exclude_close_to"),
+        }),
+        (Some(_), Some(Role::Receiver)) => Ok(()),
+        (Some(_), _) => Err(QueryError {
+            reason: "exclude_close_to requires the receiver address_role".to_owned(),
+            param: Some("exclude_close_to"),
+        }),
+    }
+}
+
+impl AssetsInfoResponse {
+    /// An amount of the asset, from its base units, with the decimals of the asset.
+    pub fn amount(&self, raw: u64) -> AssetAmount {
+        self.asset.amount(raw)
+    }
+}
+
 /// Query assets.
+///
+/// ```
+/// # use algonaut_model::indexer::v2::QueryAssets;
+/// # let creator = "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU".parse().unwrap();
+/// let query = QueryAssets::default()
+///     .created_by(creator)
+///     .with_unit("USD")
+///     .including_all();
+/// assert!(query.validate().is_ok());
+/// assert!(QueryAssets::by_id(5).with_unit("USD").validate().is_err());
+/// ```
+#[serde_as]
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
 pub struct QueryAssets {
-    /// Asset ID.
+    /// Asset ID. Looks up a single asset: it can't be combined with the other filters.
     #[serde(rename = "asset-id", skip_serializing_if = "Option::is_none")]
     pub asset_id: Option<u64>,
 
     /// Filter just assets with the given creator address.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub creator: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub creator: Option<Address>,
+
+    /// Include all items including closed accounts, deleted applications, destroyed assets,
+    /// opted-out asset holdings, and closed-out application localstates.
+    ///
+    /// The destroyed assets have [deleted](Asset::deleted) set and a
+    /// [destroyed_at_round](Asset::destroyed_at_round).
+    #[serde(rename = "include-all", skip_serializing_if = "Option::is_none")]
+    pub include_all: Option<bool>,
 
     /// Maximum number of results to return.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub limit: Option<u64>,
 
-    /// Filter just assets with the given name.
+    /// Filter just assets whose name contains the given string, case insensitive (e.g. "usd"
+    /// matches "USDC" and "Tether USDt").
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
 
@@ -240,19 +452,80 @@ pub struct QueryAssets {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub next: Option<String>,
 
-    /// Filter just assets with the given unit.
+    /// Filter just assets whose unit name contains the given string, case insensitive.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub unit: Option<String>,
 }
 
+impl QueryAssets {
+    /// Looks up the asset with this id.
+    pub fn by_id(asset_id: u64) -> Self {
+        QueryAssets {
+            asset_id: Some(asset_id),
+            ..QueryAssets::default()
+        }
+    }
+
+    /// Restricts the results to the assets created by `creator`.
+    pub fn created_by(mut self, creator: Address) -> Self {
+        self.creator = Some(creator);
+        self
+    }
+
+    /// Restricts the results to the assets whose name contains `name`.
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_owned());
+        self
+    }
+
+    /// Restricts the results to the assets whose unit name contains `unit`.
+    pub fn with_unit(mut self, unit: &str) -> Self {
+        self.unit = Some(unit.to_owned());
+        self
+    }
+
+    /// Includes the destroyed assets in the results.
+    pub fn including_all(mut self) -> Self {
+        self.include_all = Some(true);
+        self
+    }
+
+    /// Returns at most `limit` assets per page.
+    pub fn with_limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Checks the query before sending it: the asset id can't be zero, and excludes the creator,
+    /// name and unit filters.
+    pub fn validate(&self) -> Result<(), QueryError> {
+        validate_id(self.asset_id, "asset_id")?;
+        if self.asset_id.is_none() {
+            return Ok(());
+        }
+        let conflicting = [
+            ("creator", self.creator.is_some()),
+            ("name", self.name.is_some()),
+            ("unit", self.unit.is_some()),
+        ];
+        match conflicting.iter().find(|(_, is_set)| *is_set) {
+            Some((param, _)) => Err(QueryError {
+                reason: format!("asset_id can't be combined with {}", param),
+                param: Some(param),
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
 /// Assets response.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct AssetResponse {
-    #[serde(rename = "assets")]
+    #[serde(default, rename = "assets")]
     pub assets: Vec<Asset>,
 
     /// Round at which the results were computed.
-    #[serde(rename = "current-round")]
+    #[serde(default, rename = "current-round")]
     pub current_round: i32,
 
     /// Used for pagination, when making another request provide this token with the next parameter.
@@ -275,7 +548,7 @@ pub struct AssetsInfoResponse {
     pub asset: Box<Asset>,
 
     /// Round at which the results were computed.
-    #[serde(rename = "current-round")]
+    #[serde(default, rename = "current-round")]
     pub current_round: i32,
 }
 
@@ -311,11 +584,11 @@ pub struct QueryBalances {
 /// Balances response.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct BalancesResponse {
-    #[serde(rename = "balances")]
+    #[serde(default, rename = "balances")]
     pub balances: Vec<MiniAssetHolding>,
 
     /// Round at which the results were computed.
-    #[serde(rename = "current-round")]
+    #[serde(default, rename = "current-round")]
     pub current_round: i32,
 
     /// Used for pagination, when making another request provide this token with the next parameter.
@@ -401,15 +674,46 @@ pub struct QueryAssetTransaction {
     pub tx_type: Option<TransactionType>,
 
     /// Lookup the specific transaction by ID.
-    #[serde(rename = "tx-type", skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "txid", skip_serializing_if = "Option::is_none")]
     pub txid: Option<String>,
 }
 
+impl QueryAssetTransaction {
+    /// Checks the query before sending it: the round range can't be empty, the address must be
+    /// valid and close-to addresses can only be excluded from the receivers of an address.
+    pub fn validate(&self) -> Result<(), QueryError> {
+        validate_round_range(self.min_round, self.max_round)?;
+        validate_address(&self.address, "address")?;
+        validate_exclude_close_to(self.exclude_close_to, &self.address, &self.address_role)
+    }
+
+    /// Restricts the results to the rounds in `range` (both ends included).
+    pub fn with_round_range(mut self, range: RoundRange) -> Self {
+        self.min_round = Some(range.min);
+        self.max_round = Some(range.max);
+        self
+    }
+
+    /// Restricts the results to the transactions where `address` has the given `role`.
+    pub fn with_address(mut self, address: Address, role: Role) -> Self {
+        self.address = Some(address.to_string());
+        self.address_role = Some(role);
+        self
+    }
+
+    /// Leaves out the transactions matching the address only as their close-to address (e.g. the
+    /// close-outs of other accounts to it). Requires the address with the [Role::Receiver] role.
+    pub fn excluding_close_to(mut self) -> Self {
+        self.exclude_close_to = Some(true);
+        self
+    }
+}
+
 /// Resonse to asset transactions' endpoint.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct AssetTransactionResponse {
     /// Round at which the results were computed.
-    #[serde(rename = "current-round")]
+    #[serde(default, rename = "current-round")]
     pub current_round: u64,
 
     /// Used for pagination, when making another request provide this token with the next parameter.
@@ -417,11 +721,12 @@ pub struct AssetTransactionResponse {
     pub next_token: Option<String>,
 
     /// Transaction list.
-    #[serde(rename = "transactions")]
+    #[serde(default, rename = "transactions")]
     pub transactions: Vec<Transaction>,
 }
 
 /// Query transactions.
+#[serde_as]
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
 pub struct QueryTransaction {
     /// Only include transactions with this address in one of the transaction fields.
@@ -467,6 +772,11 @@ pub struct QueryTransaction {
     #[serde(rename = "exclude-close-to", skip_serializing_if = "Option::is_none")]
     pub exclude_close_to: Option<bool>,
 
+    /// Only include transactions of the atomic group with this id (encoded as base64).
+    #[serde(default, rename = "group-id", skip_serializing_if = "Option::is_none")]
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub group_id: Option<HashDigest>,
+
     /// Maximum number of results to return.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub limit: Option<u64>,
@@ -487,7 +797,8 @@ pub struct QueryTransaction {
     #[serde(rename = "note-prefix", skip_serializing_if = "Option::is_none")]
     pub note_prefix: Option<String>,
 
-    /// Include results which include the rekey-to field.
+    /// Include results which include the rekey-to field: see
+    /// [rekeys_only](QueryTransaction::rekeys_only).
     #[serde(rename = "rekey-to", skip_serializing_if = "Option::is_none")]
     pub rekey_to: Option<bool>,
 
@@ -507,22 +818,73 @@ pub struct QueryTransaction {
     pub tx_type: Option<TransactionType>,
 
     /// Lookup the specific transaction by ID.
-    #[serde(rename = "tx-type", skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "txid", skip_serializing_if = "Option::is_none")]
     pub txid: Option<String>,
 }
 
+impl QueryTransaction {
+    /// Checks the query before sending it: the round range can't be empty, the ids can't be
+    /// zero, the address must be valid and close-to addresses can only be excluded from the
+    /// receivers of an address.
+    pub fn validate(&self) -> Result<(), QueryError> {
+        validate_round_range(self.min_round, self.max_round)?;
+        validate_id(self.asset_id, "asset_id")?;
+        validate_id(self.application_id, "application_id")?;
+        validate_address(&self.address, "address")?;
+        validate_exclude_close_to(self.exclude_close_to, &self.address, &self.address_role)
+    }
+
+    /// Restricts the results to the rounds in `range` (both ends included).
+    pub fn with_round_range(mut self, range: RoundRange) -> Self {
+        self.min_round = Some(range.min);
+        self.max_round = Some(range.max);
+        self
+    }
+
+    /// Restricts the results to the transactions of the atomic group with this id, given as a
+    /// [HashDigest] or its 32 bytes.
+    pub fn with_group_id(mut self, group_id: impl Into<HashDigest>) -> Self {
+        self.group_id = Some(group_id.into());
+        self
+    }
+
+    /// Restricts the results to the transactions where `address` has the given `role`.
+    pub fn with_address(mut self, address: Address, role: Role) -> Self {
+        self.address = Some(address.to_string());
+        self.address_role = Some(role);
+        self
+    }
+
+    /// Leaves out the transactions matching the address only as their close-to address (e.g. the
+    /// close-outs of other accounts to it). Requires the address with the [Role::Receiver] role.
+    pub fn excluding_close_to(mut self) -> Self {
+        self.exclude_close_to = Some(true);
+        self
+    }
+
+    /// Restricts the results to the transactions rekeying their sender (`rekey-to=true`), e.g. to
+    /// monitor the rekeys of an account with `with_address(account, Role::Sender)`.
+    ///
+    /// The `rekey-to` parameter of the indexer is a flag, not an address: the indexer can't search
+    /// the rekeys to a given address. Filter the results by their `rekey_to` instead.
+    pub fn rekeys_only(mut self) -> Self {
+        self.rekey_to = Some(true);
+        self
+    }
+}
+
 /// Response to transactions/ endpoint.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct TransactionResponse {
     /// Round at which the results were computed.
-    #[serde(rename = "current-round")]
+    #[serde(default, rename = "current-round")]
     pub current_round: i32,
 
     /// Used for pagination, when making another request provide this token with the next parameter.
     #[serde(rename = "next-token")]
     pub next_token: Option<String>,
 
-    #[serde(rename = "transactions")]
+    #[serde(default, rename = "transactions")]
     pub transactions: Vec<Transaction>,
 }
 
@@ -530,7 +892,7 @@ pub struct TransactionResponse {
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct TransactionInfoResponse {
     /// Round at which the results were computed.
-    #[serde(rename = "current-round")]
+    #[serde(default, rename = "current-round")]
     pub current_round: i32,
 
     #[serde(rename = "transaction")]
@@ -545,10 +907,11 @@ pub struct Account {
     pub address: Address,
 
     /// `algo` total number of MicroAlgos in the account.
+    #[serde(default)]
     pub amount: MicroAlgos,
 
     /// specifies the amount of MicroAlgos in the account, without the pending rewards.
-    #[serde(rename = "amount-without-pending-rewards")]
+    #[serde(default, rename = "amount-without-pending-rewards")]
     pub amount_without_pending_rewards: MicroAlgos,
 
     /// `appl` applications local data stored in this account.
@@ -567,6 +930,13 @@ pub struct Account {
     #[serde(rename = "apps-total-schema")]
     pub apps_total_schema: Option<ApplicationStateSchema>,
 
+    /// `teap` the sum of the extra program pages of the applications created by the account.
+    #[serde(
+        rename = "apps-total-extra-pages",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub apps_total_extra_pages: Option<u64>,
+
     /// `asset` assets held by this account.
     /// Note the raw object uses map(int) -> AssetHolding for this type.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
@@ -609,11 +979,25 @@ pub struct Account {
     /// Whether or not this account is currently closed.
     pub deleted: Option<bool>,
 
+    /// Whether the account is eligible for the incentives, i.e. the payouts of the blocks it
+    /// proposes (set by the registration of its participation keys with the incentive fee).
+    #[serde(rename = "incentive-eligible", skip_serializing_if = "Option::is_none")]
+    pub incentive_eligible: Option<bool>,
+
+    /// The round of the last heartbeat of the account, i.e. when it went online or renewed its
+    /// online status.
+    #[serde(rename = "last-heartbeat", skip_serializing_if = "Option::is_none")]
+    pub last_heartbeat: Option<Round>,
+
+    /// The round of the last block proposed by the account.
+    #[serde(rename = "last-proposed", skip_serializing_if = "Option::is_none")]
+    pub last_proposed: Option<Round>,
+
     /// Participation.
     pub participation: Option<AccountParticipation>,
 
     /// Amount of MicroAlgos of pending rewards in this account.
-    #[serde(rename = "pending-rewards")]
+    #[serde(default, rename = "pending-rewards")]
     pub pending_rewards: MicroAlgos,
 
     /// `ebase` used as part of the rewards computation. Only applicable to accounts which
@@ -622,9 +1006,11 @@ pub struct Account {
     pub reward_base: Option<u64>,
 
     /// `ern` total rewards of MicroAlgos the account has received, including pending rewards.
+    #[serde(default)]
     pub rewards: MicroAlgos,
 
     /// The round for which this information is relevant.
+    #[serde(default)]
     pub round: Round,
 
     /// Indicates what type of signature is used by this account, must be one of:
@@ -639,17 +1025,87 @@ pub struct Account {
     /// * Online - indicates that the associated account used as part of the delegation pool.
     /// * NotParticipating - indicates that the associated account is neither a delegator nor a delegate.
     pub status: String,
+    /// The count of all the applications opted in by the account (`apps_local_state` may be
+    /// excluded from the response).
+    #[serde(
+        rename = "total-apps-opted-in",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub total_apps_opted_in: Option<u64>,
+
+    /// The count of all the assets held by the account, including the ones it created.
+    #[serde(
+        rename = "total-assets-opted-in",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub total_assets_opted_in: Option<u64>,
+
+    /// The total size in bytes (names and values) of the boxes of the account, if it's the
+    /// account of an application.
+    #[serde(rename = "total-box-bytes", skip_serializing_if = "Option::is_none")]
+    pub total_box_bytes: Option<u64>,
+
+    /// The count of the boxes of the account, if it's the account of an application.
+    #[serde(rename = "total-boxes", skip_serializing_if = "Option::is_none")]
+    pub total_boxes: Option<u64>,
+
+    /// The count of all the applications created by the account.
+    #[serde(rename = "total-created-apps", skip_serializing_if = "Option::is_none")]
+    pub total_created_apps: Option<u64>,
+
+    /// The count of all the assets created by the account.
+    #[serde(
+        rename = "total-created-assets",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub total_created_assets: Option<u64>,
+}
+
+impl Account {
+    /// The participation of the account in consensus at `current_round`, derived from its
+    /// status and the validity of its participation keys at the round.
+    pub fn participation_status(&self, current_round: Round) -> ParticipationStatus {
+        let keys_valid = self
+            .participation
+            .as_ref()
+            .is_some_and(|participation| participation.is_valid_at(current_round));
+        crate::participation_status(&self.status, keys_valid)
+    }
 }
 
 /// Signature types.
+///
+/// Types introduced by future protocol versions are deserialized as [SignatureType::Unknown].
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
 pub enum SignatureType {
-    #[serde(rename = "sig")]
     Sig,
-    #[serde(rename = "msig")]
     MultiSig,
-    #[serde(rename = "lsig")]
     LSig,
+    /// A type not known by this version of the SDK.
+    Unknown(String),
+}
+
+impl From<String> for SignatureType {
+    fn from(value: String) -> Self {
+        match value.as_ref() {
+            "sig" => SignatureType::Sig,
+            "msig" => SignatureType::MultiSig,
+            "lsig" => SignatureType::LSig,
+            _ => SignatureType::Unknown(value),
+        }
+    }
+}
+
+impl From<SignatureType> for String {
+    fn from(value: SignatureType) -> Self {
+        match value {
+            SignatureType::Sig => "sig".to_owned(),
+            SignatureType::MultiSig => "msig".to_owned(),
+            SignatureType::LSig => "lsig".to_owned(),
+            SignatureType::Unknown(value) => value,
+        }
+    }
 }
 
 /// AccountParticipation describes the parameters used by this account in consensus protocol.
@@ -665,16 +1121,25 @@ pub struct AccountParticipation {
     )]
     pub selection_participation_key: Vec<u8>,
 
+    /// `stprf` root of the state proof key (if any).
+    #[serde(
+        rename = "state-proof-key",
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "deserialize_bytes"
+    )]
+    pub state_proof_key: Vec<u8>,
+
     /// `voteFst` First round for which this participation is valid.
-    #[serde(rename = "vote-first-valid")]
+    #[serde(default, rename = "vote-first-valid")]
     pub vote_first_valid: Round,
 
     /// `voteKD` Number of subkeys in each batch of participation keys.
-    #[serde(rename = "vote-key-dilution")]
+    #[serde(default, rename = "vote-key-dilution")]
     pub vote_key_dilution: u64,
 
     /// `voteLst` Last round for which this participation is valid.
-    #[serde(rename = "vote-last-valid")]
+    #[serde(default, rename = "vote-last-valid")]
     pub vote_last_valid: Round,
 
     /// `vote` root participation public key (if any) currently registered for this round.
@@ -688,6 +1153,28 @@ pub struct AccountParticipation {
     pub vote_participation_key: Vec<u8>,
 }
 
+impl AccountParticipation {
+    /// The vote key, if registered.
+    pub fn vote_pk(&self) -> Option<VotePk> {
+        crate::typed_key(&self.vote_participation_key, VotePk)
+    }
+
+    /// The selection key, if registered.
+    pub fn selection_pk(&self) -> Option<VrfPk> {
+        crate::typed_key(&self.selection_participation_key, VrfPk)
+    }
+
+    /// The state proof key, if registered.
+    pub fn state_proof_pk(&self) -> Option<StateProofPk> {
+        crate::typed_key(&self.state_proof_key, StateProofPk)
+    }
+
+    /// Whether the participation keys are registered and valid at `round`.
+    pub fn is_valid_at(&self, round: Round) -> bool {
+        self.vote_pk().is_some() && self.vote_first_valid <= round && round <= self.vote_last_valid
+    }
+}
+
 /// Application state delta.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct AccountStateDelta {
@@ -714,6 +1201,7 @@ pub struct Application {
     pub deleted_at_round: Option<Round>,
 
     /// `appidx` application index.
+    #[serde(default)]
     pub id: u64,
 
     /// `appparams` application parameters.
@@ -731,6 +1219,7 @@ pub struct ApplicationLocalState {
     pub deleted: Option<bool>,
 
     /// The application which this local state is for.
+    #[serde(default)]
     pub id: u64,
 
     /// `tkv` storage.
@@ -776,6 +1265,10 @@ pub struct ApplicationParams {
     #[serde_as(as = "Option<DisplayFromStr>")]
     pub creator: Option<Address>,
 
+    /// `epp` the amount of extra program pages available to this app.
+    #[serde(rename = "extra-program-pages")]
+    pub extra_program_pages: Option<u64>,
+
     /// `gs` global schema
     #[serde(
         default,
@@ -797,14 +1290,60 @@ pub struct ApplicationParams {
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ApplicationStateSchema {
     /// `nbs` num of byte slices.
-    #[serde(rename = "num-byte-slice")]
+    #[serde(default, rename = "num-byte-slice")]
     pub num_byte_slice: u64,
 
     /// `nui` num of uints.
-    #[serde(rename = "num-uint")]
+    #[serde(default, rename = "num-uint")]
     pub num_uint: u64,
 }
 
+/// A simplified version of Application, without the programs and the global state, which can be
+/// large: the fields of the application not decoded are skipped.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MiniApplication {
+    /// Round when this application was created.
+    #[serde(rename = "created-at-round")]
+    pub created_at_round: Option<Round>,
+
+    /// Whether or not this application is currently deleted.
+    #[serde(rename = "deleted")]
+    pub deleted: Option<bool>,
+
+    /// Round when this application was deleted.
+    #[serde(rename = "deleted-at-round")]
+    pub deleted_at_round: Option<Round>,
+
+    /// `appidx` application index.
+    #[serde(default)]
+    pub id: u64,
+
+    /// `appparams` application parameters, without the programs and the global state.
+    pub params: MiniApplicationParams,
+}
+
+/// A simplified version of ApplicationParams: the creator and the schemas of the application.
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MiniApplicationParams {
+    /// The address that created this application.
+    #[serde(default)]
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub creator: Option<Address>,
+
+    /// `epp` the amount of extra program pages available to this app.
+    #[serde(rename = "extra-program-pages")]
+    pub extra_program_pages: Option<u64>,
+
+    /// `gs` global schema
+    #[serde(rename = "global-state-schema")]
+    pub global_state_schema: Option<ApplicationStateSchema>,
+
+    /// `lsch` local schema
+    #[serde(rename = "local-state-schema")]
+    pub local_state_schema: Option<ApplicationStateSchema>,
+}
+
 /// Specifies both the unique identifier and the parameters for an asset
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Asset {
@@ -820,21 +1359,85 @@ pub struct Asset {
     pub destroyed_at_round: Option<Round>,
 
     /// unique asset identifier
+    #[serde(default)]
     pub index: u64,
 
     /// Params.
     pub params: AssetParams,
 }
 
+impl AssetParams {
+    /// Whether all the params are zero, as in the asset config transactions destroying an asset.
+    pub fn is_empty(&self) -> bool {
+        let zero = zero_address();
+        let is_unset = |address: &Option<Address>| address.is_none_or(|address| address == zero);
+        self.creator == zero
+            && is_unset(&self.clawback)
+            && is_unset(&self.freeze)
+            && is_unset(&self.manager)
+            && self.reserve.as_deref().is_none_or(|reserve| {
+                reserve.is_empty() || reserve.parse::<Address>().ok() == Some(zero)
+            })
+            && self.decimals == 0
+            && !self.default_frozen.unwrap_or(false)
+            && self.metadata_hash.is_empty()
+            && self.name.as_deref().unwrap_or_default().is_empty()
+            && self.total == 0
+            && self.unit_name.as_deref().unwrap_or_default().is_empty()
+            && self.url.as_deref().unwrap_or_default().is_empty()
+    }
+}
+
+fn zero_address() -> Address {
+    Address::new([0; 32])
+}
+
+/// Serializes the creator of asset params, the zero address as the empty string.
+fn serialize_creator<S: serde::Serializer>(
+    creator: &Address,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    if *creator == zero_address() {
+        serializer.serialize_str("")
+    } else {
+        serializer.collect_str(creator)
+    }
+}
+
+/// Deserializes the creator of asset params, the empty string as the zero address.
+fn deserialize_creator<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Address, D::Error> {
+    let creator = String::deserialize(deserializer)?;
+    if creator.is_empty() {
+        return Ok(zero_address());
+    }
+    creator.parse().map_err(serde::de::Error::custom)
+}
+
+impl Asset {
+    /// An amount of the asset, from its base units, with the decimals of the asset.
+    pub fn amount(&self, raw: u64) -> AssetAmount {
+        crate::asset_amount(raw, self.params.decimals)
+    }
+
+    /// Formats an amount of the asset, from its base units, in the unit of the asset (e.g. "12.340"
+    /// with 3 decimals).
+    pub fn format_amount(&self, raw: u64) -> String {
+        self.amount(raw).format()
+    }
+}
+
 /// Describes an asset held by an account.
 /// Definition: data/basics/userBalance.go : AssetHolding
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct AssetHolding {
     /// `a` number of units held.
+    #[serde(default)]
     pub amount: u64,
 
     ///Asset ID of the holding.
-    #[serde(rename = "asset-id")]
+    #[serde(default, rename = "asset-id")]
     pub asset_id: u64,
 
     /// Address that created this asset. This is the address where the parameters for this asset can
@@ -845,7 +1448,7 @@ pub struct AssetHolding {
     pub deleted: Option<bool>,
 
     /// `f` whether or not the holding is frozen.
-    #[serde(rename = "is-frozen")]
+    #[serde(default, rename = "is-frozen")]
     pub is_frozen: bool,
 
     /// Round during which the account opted into this asset holding.
@@ -872,7 +1475,14 @@ pub struct AssetParams {
     /// The address that created this asset. This is the address where the parameters for this
     /// asset can be found, and also the address where unwanted asset units can be sent in the worst
     /// case.
-    #[serde_as(as = "DisplayFromStr")]
+    ///
+    /// The indexer leaves it empty in the params of asset config transactions (the creator of a
+    /// created asset is the sender): it's then the zero address.
+    #[serde(
+        default = "zero_address",
+        serialize_with = "serialize_creator",
+        deserialize_with = "deserialize_creator"
+    )]
     pub creator: Address,
 
     /// `dc` The number of digits to use after the decimal point when displaying this asset.
@@ -881,6 +1491,7 @@ pub struct AssetParams {
     /// between 0 and 19 (inclusive).
     /// Minimum value : 0
     /// Maximum value : 19
+    #[serde(default)]
     pub decimals: u64,
 
     /// `df` Whether holdings of this asset are frozen by default.
@@ -916,6 +1527,7 @@ pub struct AssetParams {
     pub reserve: Option<String>,
 
     /// `t` The total number of units of this asset.
+    #[serde(default)]
     pub total: u64,
 
     /// `un` Name of a unit of this asset, as supplied by the creator.
@@ -951,6 +1563,7 @@ pub struct Block {
     pub rewards: Option<BlockRewards>,
 
     /// `rnd` Current round on which this block was appended to the chain.
+    #[serde(default)]
     pub round: Round,
 
     /// `seed` Sortition seed.
@@ -959,6 +1572,7 @@ pub struct Block {
     pub seed: String,
 
     /// `ts` Block creation timestamp in seconds since epoch.
+    #[serde(default)]
     pub timestamp: u64,
 
     /// `txns` list of transactions corresponding to a given round.
@@ -994,6 +1608,17 @@ pub struct Block {
     pub upgrade_vote: Option<BlockUpgradeVote>,
 }
 
+impl Block {
+    /// Whether a transaction with this id is in the block, among the transactions or their inner transactions.
+    ///
+    /// The ids are the ones returned by the indexer: they aren't computed from the transactions.
+    pub fn contains_transaction(&self, txid: &str) -> bool {
+        self.transactions
+            .iter()
+            .any(|txn| txn.contains_transaction(txid))
+    }
+}
+
 /// Fields relating to rewards.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct BlockRewards {
@@ -1003,12 +1628,12 @@ pub struct BlockRewards {
 
     /// `rwcalr` number of leftover MicroAlgos after the distribution of rewards-rate MicroAlgos
     /// for every reward unit in the next round.
-    #[serde(rename = "rewards-calculation-round")]
+    #[serde(default, rename = "rewards-calculation-round")]
     pub rewards_calculation_round: MicroAlgos,
 
     /// `earn` How many rewards, in MicroAlgos, have been distributed to each RewardUnit of
     /// MicroAlgos since genesis.
-    #[serde(rename = "rewards-level")]
+    #[serde(default, rename = "rewards-level")]
     pub rewards_level: MicroAlgos,
 
     /// `rwd` accepts periodic injections from the fee-sink and continually redistributes them as
@@ -1018,12 +1643,12 @@ pub struct BlockRewards {
 
     /// `rate` Number of new MicroAlgos added to the participation stake from rewards at the next
     /// round.
-    #[serde(rename = "rewards-rate")]
+    #[serde(default, rename = "rewards-rate")]
     pub rewards_rate: MicroAlgos,
 
     /// `frac` Number of leftover MicroAlgos after the distribution of RewardsRate/rewardUnits
     /// MicroAlgos for every reward unit in the next round.
-    #[serde(rename = "rewards-residue")]
+    #[serde(default, rename = "rewards-residue")]
     pub rewards_residue: MicroAlgos,
 }
 
@@ -1082,6 +1707,7 @@ pub struct ErrorResponse<T> {
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct EvalDelta {
     /// `at` delta action.
+    #[serde(default)]
     pub action: u64,
 
     /// `bs` bytes value.
@@ -1099,19 +1725,27 @@ pub struct EvalDeltaKeyValue {
     pub value: EvalDelta,
 }
 
+impl EvalDeltaKeyValue {
+    /// The key, decoded from base64.
+    pub fn decoded_key(&self) -> Result<Vec<u8>, FieldDecodeError> {
+        crate::decode_base64_field(&self.key, "key")
+    }
+}
+
 /// A health check response.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct HealthCheck<T> {
     pub data: Option<T>,
 
-    #[serde(rename = "db-available")]
+    #[serde(default, rename = "db-available")]
     pub db_available: bool,
 
-    #[serde(rename = "is-migrating")]
+    #[serde(default, rename = "is-migrating")]
     pub is_migrating: bool,
 
     pub message: String,
 
+    #[serde(default)]
     pub round: Round,
 }
 
@@ -1122,13 +1756,14 @@ pub struct MiniAssetHolding {
     pub address: String,
 
     /// `a` number of units held.
+    #[serde(default)]
     pub amount: u64,
 
     /// Whether or not the asset holding is currently deleted from its account.
     pub deleted: Option<bool>,
 
     /// `f` whether or not the holding is frozen.
-    #[serde(rename = "is-frozen")]
+    #[serde(default, rename = "is-frozen")]
     pub is_frozen: bool,
 
     /// Round during which the account opted into this asset holding.
@@ -1149,20 +1784,47 @@ pub struct MiniAssetHolding {
 ///   * clear
 ///   * update
 ///   * delete
+///
+/// Actions introduced by future protocol versions are deserialized as [OnCompletion::Unknown].
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
 pub enum OnCompletion {
-    #[serde(rename = "noop")]
     Noop,
-    #[serde(rename = "optin")]
     Optin,
-    #[serde(rename = "closeout")]
     Closeout,
-    #[serde(rename = "clear")]
     Clear,
-    #[serde(rename = "update")]
     Update,
-    #[serde(rename = "delete")]
     Delete,
+    /// An action not known by this version of the SDK.
+    Unknown(String),
+}
+
+impl From<String> for OnCompletion {
+    fn from(value: String) -> Self {
+        match value.as_ref() {
+            "noop" => OnCompletion::Noop,
+            "optin" => OnCompletion::Optin,
+            "closeout" => OnCompletion::Closeout,
+            "clear" => OnCompletion::Clear,
+            "update" => OnCompletion::Update,
+            "delete" => OnCompletion::Delete,
+            _ => OnCompletion::Unknown(value),
+        }
+    }
+}
+
+impl From<OnCompletion> for String {
+    fn from(value: OnCompletion) -> Self {
+        match value {
+            OnCompletion::Noop => "noop".to_owned(),
+            OnCompletion::Optin => "optin".to_owned(),
+            OnCompletion::Closeout => "closeout".to_owned(),
+            OnCompletion::Clear => "clear".to_owned(),
+            OnCompletion::Update => "update".to_owned(),
+            OnCompletion::Delete => "delete".to_owned(),
+            OnCompletion::Unknown(value) => value,
+        }
+    }
 }
 
 /// Application state delta.
@@ -1174,11 +1836,11 @@ pub type StateDelta = Vec<EvalDeltaKeyValue>;
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct StateSchema {
     /// Maximum number of TEAL byte slices that may be stored in the key/value store.
-    #[serde(rename = "num-byte-slice")]
+    #[serde(default, rename = "num-byte-slice")]
     pub num_byte_slice: u64,
 
     /// Maximum number of TEAL uints that may be stored in the key/value store.
-    #[serde(rename = "num-uint")]
+    #[serde(default, rename = "num-uint")]
     pub num_uint: u64,
 }
 
@@ -1189,6 +1851,13 @@ pub struct TealKeyValue {
     pub value: TealValue,
 }
 
+impl TealKeyValue {
+    /// The key, decoded from base64.
+    pub fn decoded_key(&self) -> Result<Vec<u8>, FieldDecodeError> {
+        crate::decode_base64_field(&self.key, "key")
+    }
+}
+
 /// Represents a TEAL value.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct TealValue {
@@ -1201,10 +1870,11 @@ pub struct TealValue {
     pub bytes: Vec<u8>,
 
     /// `tt` value type.
-    #[serde(rename = "type")]
+    #[serde(default, rename = "type")]
     pub value_type: u64,
 
     /// `ui` uint value.
+    #[serde(default)]
     pub uint: u64,
 }
 
@@ -1255,10 +1925,11 @@ pub struct Transaction {
     pub created_asset_index: Option<u64>,
 
     /// `fee` Transaction fee.
+    #[serde(default)]
     pub fee: u64,
 
     /// `fv` First valid round for this transaction.
-    #[serde(rename = "first-valid")]
+    #[serde(default, rename = "first-valid")]
     pub first_valid: u64,
 
     /// `gh` Hash of genesis block.
@@ -1283,9 +1954,17 @@ pub struct Transaction {
     /// Pattern : "^(?:[A-Za-z0-9+/]{4})*(?:[A-Za-z0-9+/]{2}==\|[A-Za-z0-9+/]{3}=)?$"
     pub group: Option<String>,
 
+    /// Heartbeat.
+    #[serde(rename = "heartbeat-transaction")]
+    pub heartbeat_transaction: Option<TransactionHeartbeat>,
+
     /// Transaction ID
     pub id: String,
 
+    /// `dt.itx` Inner transactions produced by application execution.
+    #[serde(default, rename = "inner-txns", skip_serializing_if = "Vec::is_empty")]
+    pub inner_txns: Vec<Transaction>,
+
     /// Offset into the round where this transaction was confirmed.
     #[serde(rename = "intra-round-offset")]
     pub intra_round_offset: Option<u64>,
@@ -1295,7 +1974,7 @@ pub struct Transaction {
     pub keyreg_transaction: Option<TransactionKeyreg>,
 
     /// `lv` Last valid round for this transaction.
-    #[serde(rename = "last-valid")]
+    #[serde(default, rename = "last-valid")]
     pub last_valid: Round,
 
     /// `lx` Base64 encoded 32-byte array. Lease enforces mutual exclusion of transactions.
@@ -1317,6 +1996,15 @@ pub struct Transaction {
     )]
     pub local_state_delta: Vec<AccountStateDelta>,
 
+    /// `dt.lg` Logs emitted by the application being executed by this transaction.
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "deserialize_vec_bytes",
+        serialize_with = "serialize_vec_bytes"
+    )]
+    pub logs: Vec<Vec<u8>>,
+
     /// `note` Free form data.
     ///
     /// Pattern : "^(?:[A-Za-z0-9+/]{4})*(?:[A-Za-z0-9+/]{2}==\|[A-Za-z0-9+/]{3}=)?$"
@@ -1349,6 +2037,10 @@ pub struct Transaction {
     /// Signature.
     pub signature: Option<TransactionSignature>,
 
+    /// State proof.
+    #[serde(rename = "state-proof-transaction")]
+    pub state_proof_transaction: Option<TransactionStateProof>,
+
     /// `type` Indicates what type of transaction this is. Different types have different fields.
     /// Valid types, and where their fields are stored:
     ///   * `pay` payment-transaction
@@ -1357,25 +2049,306 @@ pub struct Transaction {
     ///   * `axfer` asset-transfer-transaction
     ///   * `afrz` asset-freeze-transaction
     ///   * `appl` application-transaction
+    ///   * `stpf` state-proof-transaction
+    ///   * `hb` heartbeat-transaction
     #[serde(rename = "tx-type")]
     pub tx_type: TransactionType,
 }
 
+impl Transaction {
+    /// Whether this transaction, or one of its inner transactions, has this id.
+    fn contains_transaction(&self, txid: &str) -> bool {
+        (!self.id.is_empty() && self.id == txid)
+            || self
+                .inner_txns
+                .iter()
+                .any(|txn| txn.contains_transaction(txid))
+    }
+
+    /// Every address referenced by this transaction and its inner transactions, deduplicated, in
+    /// the order they first appear: the sender, the receiver and close-to address of a payment or
+    /// asset transfer (and the account clawed back), the account frozen or thawed, the addresses
+    /// of the params of an asset config, the accounts of an application call, the account of a
+    /// heartbeat, the rekey address, and the address that signed the transaction (`auth_addr`).
+    ///
+    /// The unset addresses (empty or zero) are skipped, as are the invalid ones.
+    pub fn addresses(&self) -> Vec<Address> {
+        let mut addresses = vec![];
+        self.collect_addresses(&mut addresses);
+        addresses
+    }
+
+    fn collect_addresses(&self, addresses: &mut Vec<Address>) {
+        let zero = zero_address();
+        let mut push = |address: Option<Address>| {
+            if let Some(address) = address.filter(|address| *address != zero) {
+                if !addresses.contains(&address) {
+                    addresses.push(address);
+                }
+            }
+        };
+        let parse = |address: &str| address.parse::<Address>().ok();
+
+        push(parse(&self.sender));
+        if let Some(payment) = &self.payment_transaction {
+            push(parse(&payment.receiver));
+            push(payment.close_remainder_to.as_deref().and_then(parse));
+        }
+        if let Some(transfer) = &self.asset_transfer_transaction {
+            push(transfer.sender.as_deref().and_then(parse));
+            push(parse(&transfer.receiver));
+            push(transfer.close_to.as_deref().and_then(parse));
+        }
+        if let Some(freeze) = &self.asset_freeze_transaction {
+            push(parse(&freeze.address));
+        }
+        if let Some(params) = self
+            .asset_config_transaction
+            .as_ref()
+            .and_then(|config| config.params.as_ref())
+        {
+            push(Some(params.creator));
+            push(params.manager);
+            push(params.reserve.as_deref().and_then(parse));
+            push(params.freeze);
+            push(params.clawback);
+        }
+        if let Some(application) = &self.application_transaction {
+            for account in &application.accounts {
+                push(Some(*account));
+            }
+        }
+        if let Some(heartbeat) = &self.heartbeat_transaction {
+            push(parse(&heartbeat.hb_address));
+        }
+        push(self.rekey_to.as_deref().and_then(parse));
+        push(self.auth_addr.as_deref().and_then(parse));
+
+        for txn in &self.inner_txns {
+            txn.collect_addresses(addresses);
+        }
+    }
+
+    /// The ids of the asset and of the application created by this transaction, if any: the only
+    /// way to learn the id of an asset or application from the history.
+    ///
+    /// The objects created by the inner transactions aren't included: see
+    /// [inner_txns](Self::inner_txns).
+    pub fn created_ids(&self) -> (Option<u64>, Option<u64>) {
+        (self.created_asset_index, self.created_application_index)
+    }
+
+    /// The logs of this transaction, decoded as UTF-8 text.
+    ///
+    /// Invalid UTF-8 sequences are replaced with `U+FFFD`: use [logs](Self::logs) for binary logs.
+    pub fn decoded_logs(&self) -> Vec<String> {
+        self.logs
+            .iter()
+            .map(|log| String::from_utf8_lossy(log).into_owned())
+            .collect()
+    }
+
+    /// The logs of this transaction followed by the logs of its inner transactions, depth first.
+    pub fn all_logs(&self) -> Vec<&[u8]> {
+        let mut logs: Vec<&[u8]> = self.logs.iter().map(Vec::as_slice).collect();
+        for inner_txn in &self.inner_txns {
+            logs.extend(inner_txn.all_logs());
+        }
+        logs
+    }
+
+    /// The changes of the algo and asset balances of `address` made by this transaction, e.g. to
+    /// build the ledger of the account from its transactions.
+    ///
+    /// The algos include the amount sent or received, the remainder closed out, the fee paid by
+    /// the sender and the rewards realized by the transaction. The assets include the amount
+    /// transferred, from the revoked account in a clawback, and the holding closed out. A
+    /// transaction to self changes only the fee and the rewards.
+    ///
+    /// The inner transactions aren't included: their deltas are the ones of
+    /// [inner_txns](Self::inner_txns).
+    pub fn delta_for(&self, address: &Address) -> TransactionDelta {
+        let address = address.to_string();
+        let mut delta = TransactionDelta::default();
+        let is_sender = self.sender == address;
+        if is_sender {
+            delta.algos -= self.fee as i128;
+            delta.algos += self.sender_rewards.unwrap_or(0) as i128;
+        }
+        if let Some(payment) = &self.payment_transaction {
+            let closing_amount = payment
+                .close_amount
+                .or(self.closing_amount)
+                .map_or(0, |a| a.0 as i128);
+            if is_sender {
+                delta.algos -= payment.amount.0 as i128;
+                if payment.close_remainder_to.is_some() {
+                    delta.algos -= closing_amount;
+                }
+            }
+            if payment.receiver == address {
+                delta.algos += payment.amount.0 as i128;
+                delta.algos += self.receiver_rewards.map_or(0, |r| r.0 as i128);
+            }
+            if payment.close_remainder_to.as_deref() == Some(&address) {
+                delta.algos += closing_amount;
+                delta.algos += self.close_rewards.map_or(0, |r| r.0 as i128);
+            }
+        }
+        if let Some(transfer) = &self.asset_transfer_transaction {
+            let mut asset_delta = 0;
+            let close_amount = transfer.close_amount.unwrap_or(0) as i128;
+            // the asset sender of a clawback is the account revoked
+            let asset_sender = transfer.sender.as_deref().unwrap_or(&self.sender);
+            if asset_sender == address {
+                asset_delta -= transfer.amount as i128;
+                if transfer.close_to.is_some() {
+                    asset_delta -= close_amount;
+                }
+            }
+            if transfer.receiver == address {
+                asset_delta += transfer.amount as i128;
+                delta.algos += self.receiver_rewards.map_or(0, |r| r.0 as i128);
+            }
+            if transfer.close_to.as_deref() == Some(&address) {
+                asset_delta += close_amount;
+                delta.algos += self.close_rewards.map_or(0, |r| r.0 as i128);
+            }
+            if asset_delta != 0 {
+                delta.assets.insert(transfer.asset_id, asset_delta);
+            }
+        }
+        delta
+    }
+
+    /// The return value of an ARC-4 method call: the last log of this transaction, without the
+    /// prefix, if it's prefixed with [ARC4_RETURN_PREFIX](crate::ARC4_RETURN_PREFIX).
+    pub fn return_value_log(&self) -> Option<&[u8]> {
+        crate::arc4_return_value(&self.logs)
+    }
+
+    /// The key registration of a `keyreg` transaction, with its keys decoded, or `None` for the
+    /// other transaction types.
+    pub fn key_registration(&self) -> Result<Option<KeyRegistrationTransaction>, FieldDecodeError> {
+        self.keyreg_transaction
+            .as_ref()
+            .map(TransactionKeyreg::decoded)
+            .transpose()
+    }
+
+    /// The type of the signature of the transaction, `None` if it has none (e.g. an inner
+    /// transaction).
+    pub fn sig_type(&self) -> Option<SignatureType> {
+        self.signature
+            .as_ref()
+            .and_then(TransactionSignature::sig_type)
+    }
+
+    /// The note of this transaction, decoded from base64.
+    pub fn decoded_note(&self) -> Result<Option<Vec<u8>>, FieldDecodeError> {
+        crate::decode_optional_base64_field(&self.note, "note")
+    }
+
+    /// The note of this transaction, decoded from base64 then JSON, e.g. a note set with
+    /// `TxnBuilder::note_json`. Fails if the note isn't the JSON of a `T`.
+    pub fn note_as_json<T: DeserializeOwned>(&self) -> Result<Option<T>, FieldDecodeError> {
+        self.decoded_note()?
+            .map(|note| serde_json::from_slice(&note))
+            .transpose()
+            .map_err(|_| FieldDecodeError { field: "note" })
+    }
+
+    /// The changes of the global state of the called application, decoded.
+    ///
+    /// Fold the changes of the calls of an application with
+    /// [TealKeyValueStore::apply_deltas](crate::teal::TealKeyValueStore::apply_deltas) to
+    /// reconstruct its global state over time.
+    pub fn decoded_global_state_delta(&self) -> Result<Vec<TealKeyDelta>, FieldDecodeError> {
+        self.global_state_delta
+            .iter()
+            .flatten()
+            .map(TealKeyDelta::try_from)
+            .collect()
+    }
+
+    /// The changes of the local states of the accounts, decoded, by account address.
+    pub fn decoded_local_state_delta(
+        &self,
+    ) -> Result<Vec<(String, Vec<TealKeyDelta>)>, FieldDecodeError> {
+        self.local_state_delta
+            .iter()
+            .map(|account_delta| {
+                let delta = account_delta
+                    .delta
+                    .iter()
+                    .map(TealKeyDelta::try_from)
+                    .collect::<Result<_, _>>()?;
+                Ok((account_delta.address.clone(), delta))
+            })
+            .collect()
+    }
+}
+
+/// The changes of the balances of an account made by a [Transaction], see
+/// [Transaction::delta_for].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TransactionDelta {
+    /// The change of the algo balance, in MicroAlgos.
+    pub algos: i128,
+
+    /// The change of the balance of each asset (by id) whose balance changed.
+    pub assets: BTreeMap<u64, i128>,
+}
+
 /// All the possible types of transactions.
+///
+/// Types introduced by future protocol versions are deserialized as [TransactionType::Unknown],
+/// so they don't break the deserialization of blocks and transaction lists.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
 pub enum TransactionType {
-    #[serde(rename = "pay")]
     Payment,
-    #[serde(rename = "keyreg")]
     KeyRegistration,
-    #[serde(rename = "acfg")]
     AssetConfigurationTransaction,
-    #[serde(rename = "axfer")]
     AssetTransferTransaction,
-    #[serde(rename = "axfrz")]
     AssetFreezeTransaction,
-    #[serde(rename = "appl")]
     ApplicationTransaction,
+    StateProof,
+    Heartbeat,
+    /// A type not known by this version of the SDK.
+    Unknown(String),
+}
+
+impl From<String> for TransactionType {
+    fn from(tx_type: String) -> Self {
+        match tx_type.as_ref() {
+            "pay" => TransactionType::Payment,
+            "keyreg" => TransactionType::KeyRegistration,
+            "acfg" => TransactionType::AssetConfigurationTransaction,
+            "axfer" => TransactionType::AssetTransferTransaction,
+            "afrz" => TransactionType::AssetFreezeTransaction,
+            "appl" => TransactionType::ApplicationTransaction,
+            "stpf" => TransactionType::StateProof,
+            "hb" => TransactionType::Heartbeat,
+            _ => TransactionType::Unknown(tx_type),
+        }
+    }
+}
+
+impl From<TransactionType> for String {
+    fn from(tx_type: TransactionType) -> Self {
+        match tx_type {
+            TransactionType::Payment => "pay".to_owned(),
+            TransactionType::KeyRegistration => "keyreg".to_owned(),
+            TransactionType::AssetConfigurationTransaction => "acfg".to_owned(),
+            TransactionType::AssetTransferTransaction => "axfer".to_owned(),
+            TransactionType::AssetFreezeTransaction => "afrz".to_owned(),
+            TransactionType::ApplicationTransaction => "appl".to_owned(),
+            TransactionType::StateProof => "stpf".to_owned(),
+            TransactionType::Heartbeat => "hb".to_owned(),
+            TransactionType::Unknown(tx_type) => tx_type,
+        }
+    }
 }
 
 #[serde_as]
@@ -1398,7 +2371,7 @@ pub struct TransactionApplication {
     pub application_args: Vec<String>,
 
     /// `apid` ID of the application being configured or empty if creating.
-    #[serde(rename = "application-id")]
+    #[serde(default, rename = "application-id")]
     pub application_id: u64,
 
     /// `apap` Logic executed for every application transaction, except when on-completion is set
@@ -1448,10 +2421,22 @@ pub struct TransactionApplication {
     pub on_completion: OnCompletion,
 }
 
+impl TransactionApplication {
+    /// The approval program, decoded from base64.
+    pub fn decoded_approval_program(&self) -> Result<Option<Vec<u8>>, FieldDecodeError> {
+        crate::decode_optional_base64_field(&self.approval_program, "approval-program")
+    }
+
+    /// The clear state program, decoded from base64.
+    pub fn decoded_clear_state_program(&self) -> Result<Option<Vec<u8>>, FieldDecodeError> {
+        crate::decode_optional_base64_field(&self.clear_state_program, "clear-state-program")
+    }
+}
+
 /// Fields for asset allocation, re-configuration, and destruction.
 ///
 /// A zero value for asset-id indicates asset creation. A zero value for the params indicates asset
-/// destruction.
+/// destruction. See [operation](Self::operation).
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct TransactionAssetConfig {
     /// `xaid` ID of the asset being configured or empty if creating.
@@ -1462,6 +2447,32 @@ pub struct TransactionAssetConfig {
     pub params: Option<AssetParams>,
 }
 
+impl TransactionAssetConfig {
+    /// What the transaction does to the asset: creates it if there is no asset id, destroys it if
+    /// there is one with empty params, reconfigures it otherwise.
+    ///
+    /// A reconfiguration clearing all the addresses of an asset has empty params too: it's a
+    /// destruction, as for the node.
+    pub fn operation(&self) -> AcfgOp {
+        match (self.asset_id.filter(|id| *id != 0), &self.params) {
+            (None, _) => AcfgOp::Create,
+            (Some(_), Some(params)) if !params.is_empty() => AcfgOp::Reconfigure,
+            (Some(_), _) => AcfgOp::Destroy,
+        }
+    }
+}
+
+/// The operation of an asset config (`acfg`) transaction, see [TransactionAssetConfig::operation].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AcfgOp {
+    /// Creates an asset, with the params of the transaction.
+    Create,
+    /// Changes the addresses of the asset (the other params can't be changed).
+    Reconfigure,
+    /// Destroys the asset: only its manager can, when the creator holds all its units.
+    Destroy,
+}
+
 /// Fields for an asset freeze transaction.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct TransactionAssetFreeze {
@@ -1469,11 +2480,11 @@ pub struct TransactionAssetFreeze {
     pub address: String,
 
     /// `faid` ID of the asset being frozen or thawed.
-    #[serde(rename = "asset-id")]
+    #[serde(default, rename = "asset-id")]
     pub asset_id: u64,
 
     /// `afrz` The new freeze status.
-    #[serde(rename = "new-freeze-status")]
+    #[serde(default, rename = "new-freeze-status")]
     pub new_freeze_status: bool,
 }
 
@@ -1482,10 +2493,11 @@ pub struct TransactionAssetFreeze {
 pub struct TransactionAssetTransfer {
     /// `aamt` Amount of asset to transfer. A zero amount transferred to self allocates that asset
     /// in the account's Assets map.
+    #[serde(default)]
     pub amount: u64,
 
     /// `xaid` ID of the asset being transferred.
-    #[serde(rename = "asset-id")]
+    #[serde(default, rename = "asset-id")]
     pub asset_id: u64,
 
     /// Number of assets transfered to the close-to account as part of the transaction.
@@ -1529,7 +2541,7 @@ pub struct TransactionKeyreg {
     pub vote_key_dilution: Option<u64>,
 
     /// `votelst` Last round this participation key is valid.
-    #[serde(rename = "vote-key-dilution")]
+    #[serde(rename = "vote-last-valid")]
     pub vote_last_valid: Option<u64>,
 
     /// `votekey` Participation public key used in key registration transactions.
@@ -1537,12 +2549,96 @@ pub struct TransactionKeyreg {
     /// Pattern : "^(?:[A-Za-z0-9+/]{4})*(?:[A-Za-z0-9+/]{2}==\|[A-Za-z0-9+/]{3}=)?$"
     #[serde(rename = "vote-participation-key")]
     pub vote_participation_key: Option<String>,
+
+    /// `sprfkey` State proof key used in key registration transactions.
+    ///
+    /// Pattern : "^(?:[A-Za-z0-9+/]{4})*(?:[A-Za-z0-9+/]{2}==\|[A-Za-z0-9+/]{3}=)?$"
+    #[serde(rename = "state-proof-key")]
+    pub state_proof_key: Option<String>,
+}
+
+impl TransactionKeyreg {
+    /// The registration with its keys decoded: online if it registers participation keys,
+    /// offline if it has none.
+    ///
+    /// Fails if a key isn't validly encoded, or if only one of the vote and selection keys is
+    /// present.
+    pub fn decoded(&self) -> Result<KeyRegistrationTransaction, FieldDecodeError> {
+        if self.vote_participation_key.is_none() && self.selection_participation_key.is_none() {
+            return Ok(KeyRegistrationTransaction::Offline {
+                non_participation: self.non_participation.unwrap_or(false),
+            });
+        }
+        // the indexer omits the zero values: the rounds and the dilution default to 0
+        Ok(KeyRegistrationTransaction::Online(OnlineKeyRegistration {
+            vote_pk: VotePk(decode_key(
+                &self.vote_participation_key,
+                "vote-participation-key",
+            )?),
+            selection_pk: VrfPk(decode_key(
+                &self.selection_participation_key,
+                "selection-participation-key",
+            )?),
+            state_proof_pk: self
+                .state_proof_key
+                .is_some()
+                .then(|| decode_key(&self.state_proof_key, "state-proof-key").map(StateProofPk))
+                .transpose()?,
+            vote_first: Round(self.vote_first_valid.unwrap_or(0)),
+            vote_last: Round(self.vote_last_valid.unwrap_or(0)),
+            vote_key_dilution: self.vote_key_dilution.unwrap_or(0),
+        }))
+    }
+}
+
+/// Decodes the base64 key of `field`, failing if it's missing or doesn't have `N` bytes.
+fn decode_key<const N: usize>(
+    key: &Option<String>,
+    field: &'static str,
+) -> Result<[u8; N], FieldDecodeError> {
+    let key = key.as_deref().ok_or(FieldDecodeError { field })?;
+    crate::decode_base64_field(key, field)?
+        .try_into()
+        .map_err(|_| FieldDecodeError { field })
+}
+
+/// A key registration transaction, decoded from [TransactionKeyreg].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum KeyRegistrationTransaction {
+    /// Registers participation keys: the account goes online.
+    Online(OnlineKeyRegistration),
+    /// Registers no keys: the account goes offline. If `non_participation` is set, the account
+    /// also stops earning rewards, permanently.
+    Offline { non_participation: bool },
+}
+
+/// The participation keys registered by an online [KeyRegistrationTransaction].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OnlineKeyRegistration {
+    /// The root participation public key.
+    pub vote_pk: VotePk,
+
+    /// The VRF public key.
+    pub selection_pk: VrfPk,
+
+    /// The state proof public key, absent from the registrations made before state proofs.
+    pub state_proof_pk: Option<StateProofPk>,
+
+    /// First round the participation key is valid.
+    pub vote_first: Round,
+
+    /// Last round the participation key is valid.
+    pub vote_last: Round,
+
+    /// Number of subkeys in each batch of participation keys.
+    pub vote_key_dilution: u64,
 }
 
 /// Fields for a payment transaction.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct TransactionPayment {
     /// `amt` number of MicroAlgos intended to be transferred.
+    #[serde(default)]
     pub amount: MicroAlgos,
 
     /// Number of MicroAlgos that were sent to the close-remainder-to address when closing the
@@ -1559,6 +2655,244 @@ pub struct TransactionPayment {
     pub receiver: String,
 }
 
+/// Fields for a state proof transaction.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TransactionStateProof {
+    /// `spmsg` The message attested by the state proof.
+    pub message: Option<IndexerStateProofMessage>,
+
+    /// `sp` The state proof.
+    #[serde(rename = "state-proof")]
+    pub state_proof: Option<StateProofFields>,
+
+    /// `sptype` Type of the state proof. Integer representing an entry defined in protocol/stateproof.go
+    #[serde(rename = "state-proof-type")]
+    pub state_proof_type: Option<u64>,
+}
+
+/// Fields for a heartbeat transaction.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TransactionHeartbeat {
+    /// `a` The online account the heartbeat is for.
+    #[serde(rename = "hb-address")]
+    pub hb_address: String,
+
+    /// `kd` The key dilution of the account, which must match the registered one.
+    #[serde(rename = "hb-key-dilution", default)]
+    pub hb_key_dilution: u64,
+
+    /// `prf` Signature of the seed with the participation keys of the account.
+    #[serde(rename = "hb-proof")]
+    pub hb_proof: HbProofFields,
+
+    /// `sd` The block seed of the round before the first valid round of the transaction.
+    ///
+    /// Pattern : "^(?:[A-Za-z0-9+/]{4})*(?:[A-Za-z0-9+/]{2}==\|[A-Za-z0-9+/]{3}=)?$"
+    #[serde(rename = "hb-seed", default)]
+    pub hb_seed: String,
+
+    /// `vid` The vote public key of the account, which must match the registered one.
+    ///
+    /// Pattern : "^(?:[A-Za-z0-9+/]{4})*(?:[A-Za-z0-9+/]{2}==\|[A-Za-z0-9+/]{3}=)?$"
+    #[serde(rename = "hb-vote-id", default)]
+    pub hb_vote_id: String,
+}
+
+/// The proof of a heartbeat: a signature of the seed in the one-time signature scheme of the votes.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct HbProofFields {
+    /// `p` Public key of the round of the signature.
+    ///
+    /// Pattern : "^(?:[A-Za-z0-9+/]{4})*(?:[A-Za-z0-9+/]{2}==\|[A-Za-z0-9+/]{3}=)?$"
+    #[serde(rename = "hb-pk")]
+    pub hb_pk: Option<String>,
+
+    /// `p1s` Signature of the round key by the batch key.
+    ///
+    /// Pattern : "^(?:[A-Za-z0-9+/]{4})*(?:[A-Za-z0-9+/]{2}==\|[A-Za-z0-9+/]{3}=)?$"
+    #[serde(rename = "hb-pk1sig")]
+    pub hb_pk1sig: Option<String>,
+
+    /// `p2` Batch key, signed by the vote key of the account.
+    ///
+    /// Pattern : "^(?:[A-Za-z0-9+/]{4})*(?:[A-Za-z0-9+/]{2}==\|[A-Za-z0-9+/]{3}=)?$"
+    #[serde(rename = "hb-pk2")]
+    pub hb_pk2: Option<String>,
+
+    /// `p2s` Signature of the batch key by the vote key of the account.
+    ///
+    /// Pattern : "^(?:[A-Za-z0-9+/]{4})*(?:[A-Za-z0-9+/]{2}==\|[A-Za-z0-9+/]{3}=)?$"
+    #[serde(rename = "hb-pk2sig")]
+    pub hb_pk2sig: Option<String>,
+
+    /// `s` Signature of the seed by the round key.
+    ///
+    /// Pattern : "^(?:[A-Za-z0-9+/]{4})*(?:[A-Za-z0-9+/]{2}==\|[A-Za-z0-9+/]{3}=)?$"
+    #[serde(rename = "hb-sig")]
+    pub hb_sig: Option<String>,
+}
+
+/// The message attested by a state proof.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct IndexerStateProofMessage {
+    /// `b` Commitment to the block headers of the attested rounds.
+    ///
+    /// Pattern : "^(?:[A-Za-z0-9+/]{4})*(?:[A-Za-z0-9+/]{2}==\|[A-Za-z0-9+/]{3}=)?$"
+    #[serde(rename = "block-headers-commitment")]
+    pub block_headers_commitment: Option<String>,
+
+    /// `f` First round the message attests to.
+    #[serde(rename = "first-attested-round")]
+    pub first_attested_round: Option<Round>,
+
+    /// `l` Last round the message attests to.
+    #[serde(rename = "latest-attested-round")]
+    pub latest_attested_round: Option<Round>,
+
+    /// `P` Natural log of the proven weight.
+    #[serde(rename = "ln-proven-weight")]
+    pub ln_proven_weight: Option<u64>,
+
+    /// `v` Commitment to the voters for the next state proof.
+    ///
+    /// Pattern : "^(?:[A-Za-z0-9+/]{4})*(?:[A-Za-z0-9+/]{2}==\|[A-Za-z0-9+/]{3}=)?$"
+    #[serde(rename = "voters-commitment")]
+    pub voters_commitment: Option<String>,
+}
+
+/// The fields of a state proof.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StateProofFields {
+    /// `P` Proofs of the revealed participants.
+    #[serde(rename = "part-proofs")]
+    pub part_proofs: Option<MerkleArrayProof>,
+
+    /// `pr` Sequence of reveal positions.
+    #[serde(
+        default,
+        rename = "positions-to-reveal",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub positions_to_reveal: Vec<u64>,
+
+    /// `r` Revealed participants and their signatures.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reveals: Vec<StateProofReveal>,
+
+    /// `v` Salt version of the merkle signature.
+    #[serde(rename = "salt-version")]
+    pub salt_version: Option<u64>,
+
+    /// `c` Commitment to the signatures.
+    ///
+    /// Pattern : "^(?:[A-Za-z0-9+/]{4})*(?:[A-Za-z0-9+/]{2}==\|[A-Za-z0-9+/]{3}=)?$"
+    #[serde(rename = "sig-commit")]
+    pub sig_commit: Option<String>,
+
+    /// `S` Proofs of the revealed signatures.
+    #[serde(rename = "sig-proofs")]
+    pub sig_proofs: Option<MerkleArrayProof>,
+
+    /// `w` Total weight of the signers.
+    #[serde(rename = "signed-weight")]
+    pub signed_weight: Option<u64>,
+}
+
+/// A merkle array proof.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MerkleArrayProof {
+    /// `hsh` Hash function used to build the tree.
+    #[serde(rename = "hash-factory")]
+    pub hash_factory: Option<HashFactory>,
+
+    /// `pth` Sequence of base64 encoded hashes from the leaf to the root.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub path: Vec<String>,
+
+    /// `td` Depth of the tree.
+    #[serde(rename = "tree-depth")]
+    pub tree_depth: Option<u64>,
+}
+
+/// The hash function used by a merkle array.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HashFactory {
+    /// `t` Type of the hash function.
+    #[serde(rename = "hash-type")]
+    pub hash_type: Option<u64>,
+}
+
+/// A participant revealed by a state proof, together with its signature.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StateProofReveal {
+    /// `p` The participant.
+    pub participant: Option<StateProofParticipant>,
+
+    /// The position in the signature and participants arrays.
+    pub position: Option<u64>,
+
+    /// `s` The signature slot.
+    #[serde(rename = "sig-slot")]
+    pub sig_slot: Option<StateProofSigSlot>,
+}
+
+/// A state proof participant.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StateProofParticipant {
+    /// `p` The participant's verifier.
+    pub verifier: Option<StateProofVerifier>,
+
+    /// `w` The participant's weight.
+    pub weight: Option<u64>,
+}
+
+/// The verifier of a state proof participant.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StateProofVerifier {
+    /// `cmt` Commitment to the participant's merkle signature keys.
+    ///
+    /// Pattern : "^(?:[A-Za-z0-9+/]{4})*(?:[A-Za-z0-9+/]{2}==\|[A-Za-z0-9+/]{3}=)?$"
+    pub commitment: Option<String>,
+
+    /// `lf` Key lifetime.
+    #[serde(rename = "key-lifetime")]
+    pub key_lifetime: Option<u64>,
+}
+
+/// A signature slot of a state proof.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StateProofSigSlot {
+    /// `l` The total weight of the signatures in the lower-numbered slots.
+    #[serde(rename = "lower-sig-weight")]
+    pub lower_sig_weight: Option<u64>,
+
+    /// `s` The signature.
+    pub signature: Option<StateProofSignature>,
+}
+
+/// A merkle signature of a state proof participant.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StateProofSignature {
+    /// `sig` The falcon signature.
+    ///
+    /// Pattern : "^(?:[A-Za-z0-9+/]{4})*(?:[A-Za-z0-9+/]{2}==\|[A-Za-z0-9+/]{3}=)?$"
+    #[serde(rename = "falcon-signature")]
+    pub falcon_signature: Option<String>,
+
+    /// `idx` Index of the key in the participant's merkle tree.
+    #[serde(rename = "merkle-array-index")]
+    pub merkle_array_index: Option<u64>,
+
+    /// `prf` Proof of the key in the participant's merkle tree.
+    pub proof: Option<MerkleArrayProof>,
+
+    /// `vkey` The falcon verifying key.
+    ///
+    /// Pattern : "^(?:[A-Za-z0-9+/]{4})*(?:[A-Za-z0-9+/]{2}==\|[A-Za-z0-9+/]{3}=)?$"
+    #[serde(rename = "verifying-key")]
+    pub verifying_key: Option<String>,
+}
+
 /// Validation signature associated with some data. Only one of the signatures should be provided.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct TransactionSignature {
@@ -1597,6 +2931,40 @@ pub struct TransactionSignatureLogicsig {
     pub signature: Option<String>,
 }
 
+impl TransactionSignature {
+    /// The type of the signature, as filtered with the `sig-type` of the queries.
+    ///
+    /// `None` if it has none of the signatures, e.g. an inner transaction. A transaction authorized
+    /// only by a logic signature is [SignatureType::LSig], even if the program was delegated (signed
+    /// by the sender).
+    pub fn sig_type(&self) -> Option<SignatureType> {
+        if self.logicsig.is_some() {
+            Some(SignatureType::LSig)
+        } else if self.multisig.is_some() {
+            Some(SignatureType::MultiSig)
+        } else if self.sig.is_some() {
+            Some(SignatureType::Sig)
+        } else {
+            None
+        }
+    }
+}
+
+impl TransactionSignatureLogicsig {
+    /// The program, decoded from base64.
+    pub fn decoded_logic(&self) -> Result<Vec<u8>, FieldDecodeError> {
+        crate::decode_base64_field(&self.logic, "logic")
+    }
+
+    /// The arguments of the program, decoded from base64.
+    pub fn decoded_args(&self) -> Result<Vec<Vec<u8>>, FieldDecodeError> {
+        self.args
+            .iter()
+            .map(|arg| crate::decode_base64_field(arg, "args"))
+            .collect()
+    }
+}
+
 /// `msig` structure holding multiple subsignatures.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct TransactionSignatureMultisig {
@@ -1626,13 +2994,1835 @@ pub struct TransactionSignatureMultisigSubsignature {
     pub signature: Option<String>,
 }
 
+/// The resources of the accounts that can be left out of the results of an account search.
+///
+/// Values introduced by future indexer versions can be sent as [Exclude::Unknown].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Exclude {
+    /// All the resources below.
+    All,
+    Assets,
+    CreatedAssets,
+    AppsLocalState,
+    CreatedApps,
+    /// None of the resources.
+    None,
+    /// A value not known by this version of the SDK.
+    Unknown(String),
+}
+
+impl fmt::Display for Exclude {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Exclude::All => "all",
+            Exclude::Assets => "assets",
+            Exclude::CreatedAssets => "created-assets",
+            Exclude::AppsLocalState => "apps-local-state",
+            Exclude::CreatedApps => "created-apps",
+            Exclude::None => "none",
+            Exclude::Unknown(value) => value,
+        })
+    }
+}
+
+impl FromStr for Exclude {
+    type Err = Infallible;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Ok(match value {
+            "all" => Exclude::All,
+            "assets" => Exclude::Assets,
+            "created-assets" => Exclude::CreatedAssets,
+            "apps-local-state" => Exclude::AppsLocalState,
+            "created-apps" => Exclude::CreatedApps,
+            "none" => Exclude::None,
+            _ => Exclude::Unknown(value.to_owned()),
+        })
+    }
+}
+
 /// Role types.
+///
+/// Roles introduced by future indexer versions are deserialized as [Role::Unknown].
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
 pub enum Role {
-    #[serde(rename = "sender")]
     Sender,
-    #[serde(rename = "receiver")]
     Receiver,
-    #[serde(rename = "freeze-target")]
     FreezeTarget,
+    /// A role not known by this version of the SDK.
+    Unknown(String),
+}
+
+impl From<String> for Role {
+    fn from(value: String) -> Self {
+        match value.as_ref() {
+            "sender" => Role::Sender,
+            "receiver" => Role::Receiver,
+            "freeze-target" => Role::FreezeTarget,
+            _ => Role::Unknown(value),
+        }
+    }
+}
+
+impl From<Role> for String {
+    fn from(value: Role) -> Self {
+        match value {
+            Role::Sender => "sender".to_owned(),
+            Role::Receiver => "receiver".to_owned(),
+            Role::FreezeTarget => "freeze-target".to_owned(),
+            Role::Unknown(value) => value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::teal::{TealDeltaAction, TealKeyValueStore, TealValue};
+
+    /// A state proof transaction with the fields of the indexer's API, with short merkle paths and
+    /// a single reveal. It's built for the test, not captured from a network: `indexer_conformance`
+    /// decodes the real ones.
+    const STATE_PROOF_TRANSACTION: &str = r#"{
+        "close-rewards": 0,
+        "closing-amount": 0,
+        "confirmed-round": 24192139,
+        "fee": 0,
+        "first-valid": 24192138,
+        "genesis-hash": "wGHE2Pwdvd7S12BL5FaOP20EGYesN73ktiC1qzkkit8=",
+        "id": "UGWZ7QT4MMEGVLJ4WZRWPYFIJ6EEUOLZBAKSVGTXZIYRDAKTPNNA",
+        "intra-round-offset": 0,
+        "last-valid": 24193138,
+        "receiver-rewards": 0,
+        "round-time": 1668473419,
+        "sender": "XM6FEYVJ2XDU2IBH4OT6VZGW75YM63CM4TC6AV6BD3JZXFJUIICYTVB5EU",
+        "sender-rewards": 0,
+        "state-proof-transaction": {
+            "message": {
+                "block-headers-commitment": "iiN5bgcrsdk5k1bKMN3vZinp23CdRq7KCXy0QtS5lHg=",
+                "first-attested-round": 24191745,
+                "latest-attested-round": 24192000,
+                "ln-proven-weight": 2334366,
+                "voters-commitment": "BVIMJ1y6+1f5MnhUGv66bY/jLRqi9hJQKXhEL/8UmHxMmG6rkWtLmlxWj85vPfXQSmvXx+5NEUUkYnFN4CqWpA=="
+            },
+            "state-proof": {
+                "part-proofs": {
+                    "hash-factory": { "hash-type": 1 },
+                    "path": [
+                        "Kh+9dV6DFBUW+R3C5PbQoKoJ+zkqzU2fPGu5A+8zlJE=",
+                        "Z6bp8d1WH4bmU4cXzGY+hHwpVoW54qTUvYBTF5L7xlc="
+                    ],
+                    "tree-depth": 17
+                },
+                "positions-to-reveal": [43102, 93751],
+                "reveals": [
+                    {
+                        "participant": {
+                            "verifier": {
+                                "commitment": "NHqNmi6H4RXNs0JPqCvYPmOTIvUJgyMcxoiSrPDy2Yab4E4EcnBUbeHiUvIx7EKfpdwWM6ePRBLJXE5m9lHCmw==",
+                                "key-lifetime": 256
+                            },
+                            "weight": 2000036030000
+                        },
+                        "position": 43102,
+                        "sig-slot": {
+                            "lower-sig-weight": 1993741911018221,
+                            "signature": {
+                                "falcon-signature": "ugBDt0Rz0r9DXuUFkStDgB8UP8ZMYmuZCcY=",
+                                "merkle-array-index": 1094,
+                                "proof": {
+                                    "hash-factory": { "hash-type": 1 },
+                                    "path": ["OmJbMcYdKSBVsRV9Vbm2gG0o8JZqpPZ3TI8mxTnx2pI="],
+                                    "tree-depth": 16
+                                },
+                                "verifying-key": "CwQNnDpJgE5vTkpPe5XTH55ZhJGw1HaJLPJ8lGQ2"
+                            }
+                        }
+                    }
+                ],
+                "salt-version": 0,
+                "sig-commit": "NVBgnM20QcgZoE2ScWMzAHRjsbSvFwhC4RqWPiimpuM3Y3A0HBGNFxOqU0fhCNAjWB7uM0Zzu+O1dAqXhEVD6g==",
+                "sig-proofs": {
+                    "hash-factory": { "hash-type": 1 },
+                    "path": ["zt/BMOnoDfc0MQ3DVdAWGU+mPONmtJIDNcdBXQDuSn0="],
+                    "tree-depth": 20
+                },
+                "signed-weight": 2381227096537718
+            },
+            "state-proof-type": 0
+        },
+        "tx-type": "stpf"
+    }"#;
+
+    #[test]
+    fn test_deserialize_state_proof_transaction() {
+        let txn: Transaction = serde_json::from_str(STATE_PROOF_TRANSACTION).unwrap();
+
+        assert_eq!(TransactionType::StateProof, txn.tx_type);
+        let state_proof_txn = txn.state_proof_transaction.unwrap();
+        assert_eq!(Some(0), state_proof_txn.state_proof_type);
+
+        let message = state_proof_txn.message.unwrap();
+        assert_eq!(Some(Round(24191745)), message.first_attested_round);
+        assert_eq!(Some(Round(24192000)), message.latest_attested_round);
+        assert_eq!(Some(2334366), message.ln_proven_weight);
+
+        let state_proof = state_proof_txn.state_proof.unwrap();
+        assert_eq!(Some(2381227096537718), state_proof.signed_weight);
+        assert_eq!(vec![43102, 93751], state_proof.positions_to_reveal);
+        assert_eq!(Some(17), state_proof.part_proofs.unwrap().tree_depth);
+        let reveal = &state_proof.reveals[0];
+        assert_eq!(Some(43102), reveal.position);
+        assert_eq!(
+            Some(1094),
+            reveal
+                .sig_slot
+                .as_ref()
+                .and_then(|s| s.signature.as_ref())
+                .and_then(|s| s.merkle_array_index)
+        );
+    }
+
+    /// A heartbeat transaction with the fields of the indexer's API. It's built for the test, not
+    /// captured from a network: `indexer_conformance` decodes the real ones.
+    const HEARTBEAT_TRANSACTION: &str = r#"{
+        "close-rewards": 0,
+        "closing-amount": 0,
+        "confirmed-round": 46515250,
+        "fee": 0,
+        "first-valid": 46515249,
+        "genesis-hash": "wGHE2Pwdvd7S12BL5FaOP20EGYesN73ktiC1qzkkit8=",
+        "heartbeat-transaction": {
+            "hb-address": "AIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBMXPWWNQ",
+            "hb-key-dilution": 1733,
+            "hb-proof": {
+                "hb-pk": "AQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQE=",
+                "hb-pk1sig": "AgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAg==",
+                "hb-pk2": "AwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwM=",
+                "hb-pk2sig": "BAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBA==",
+                "hb-sig": "BQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQ=="
+            },
+            "hb-seed": "BgYGBgYGBgYGBgYGBgYGBgYGBgYGBgYGBgYGBgYGBgY=",
+            "hb-vote-id": "BwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwc="
+        },
+        "id": "ZL7OAN3GAC5E4TQTKTQZ7TH6LGRT3PPBSTWPBTPXTHWQ6W7BMGFQ",
+        "intra-round-offset": 0,
+        "last-valid": 46515259,
+        "receiver-rewards": 0,
+        "round-time": 1735601234,
+        "sender": "XM6FEYVJ2XDU2IBH4OT6VZGW75YM63CM4TC6AV6BD3JZXFJUIICYTVB5EU",
+        "sender-rewards": 0,
+        "tx-type": "hb"
+    }"#;
+
+    #[test]
+    fn test_deserialize_heartbeat_transaction() {
+        let txn: Transaction = serde_json::from_str(HEARTBEAT_TRANSACTION).unwrap();
+
+        assert_eq!(TransactionType::Heartbeat, txn.tx_type);
+        let heartbeat = txn.heartbeat_transaction.unwrap();
+        assert_eq!(
+            "AIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBMXPWWNQ",
+            heartbeat.hb_address
+        );
+        assert_eq!(1733, heartbeat.hb_key_dilution);
+        assert_eq!(
+            "BgYGBgYGBgYGBgYGBgYGBgYGBgYGBgYGBgYGBgYGBgY=",
+            heartbeat.hb_seed
+        );
+        assert_eq!(
+            Some("AQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQE="),
+            heartbeat.hb_proof.hb_pk.as_deref()
+        );
+        assert!(heartbeat.hb_proof.hb_sig.is_some());
+    }
+
+    /// A payment authorized only by a logic signature (a contract account), in the shape returned
+    /// by the indexer. It's built for the test, not captured from a network: the program is
+    /// `#pragma version 5; arg_0; btoi; pushint 42; ==`, the argument 42 (8 bytes, big-endian)
+    /// and the sender the address of the program, but the id and rounds are arbitrary.
+    const LOGIC_SIG_PAYMENT: &str = r#"{
+        "close-rewards": 0,
+        "closing-amount": 0,
+        "confirmed-round": 27481032,
+        "fee": 1000,
+        "first-valid": 27481030,
+        "genesis-hash": "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=",
+        "genesis-id": "testnet-v1.0",
+        "id": "7BTQQ4MLHIDZQUFX6XCFVXQ3SMZO4AFCXVU3ZTB5QMLMXNZ7ZGBA",
+        "intra-round-offset": 3,
+        "last-valid": 27482030,
+        "payment-transaction": {
+            "amount": 1000000,
+            "close-amount": 0,
+            "receiver": "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU"
+        },
+        "receiver-rewards": 0,
+        "round-time": 1674220345,
+        "sender": "TJUFIEKVZZLHGJIRBNIYNAOUPEJNIK4WJLJ27DOKVFGO7RRSK53IC3D2K4",
+        "sender-rewards": 0,
+        "signature": {
+            "logicsig": {
+                "args": ["AAAAAAAAACo="],
+                "logic": "BS0XgSoS"
+            }
+        },
+        "tx-type": "pay"
+    }"#;
+
+    #[test]
+    fn test_logic_sig_payment() {
+        let txn: Transaction = serde_json::from_str(LOGIC_SIG_PAYMENT).unwrap();
+
+        assert_eq!(Some(SignatureType::LSig), txn.sig_type());
+        let signature = txn.signature.unwrap();
+        assert_eq!(None, signature.sig);
+        assert_eq!(None, signature.multisig);
+        let logicsig = signature.logicsig.unwrap();
+        assert_eq!(None, logicsig.signature);
+        assert_eq!(None, logicsig.multisig_signature);
+        let logic = logicsig.decoded_logic().unwrap();
+        // pushint 42 (0x81 0x2a), compared (==) to the argument
+        assert_eq!(vec![0x05, 0x2d, 0x17, 0x81, 0x2a, 0x12], logic);
+        assert_eq!(
+            Address::from(algonaut_core::CompiledTeal(logic).hash()).to_string(),
+            txn.sender
+        );
+        assert_eq!(
+            Ok(vec![42u64.to_be_bytes().to_vec()]),
+            logicsig.decoded_args()
+        );
+    }
+
+    #[test]
+    fn test_sig_type() {
+        let signature = |json: serde_json::Value| -> TransactionSignature {
+            serde_json::from_value(json).unwrap()
+        };
+
+        assert_eq!(
+            Some(SignatureType::Sig),
+            signature(serde_json::json!({ "sig": "AAAA" })).sig_type()
+        );
+        assert_eq!(
+            Some(SignatureType::MultiSig),
+            signature(serde_json::json!({ "multisig": { "threshold": 1, "version": 1 } }))
+                .sig_type()
+        );
+        // delegated logic signature: the program is signed by the sender
+        assert_eq!(
+            Some(SignatureType::LSig),
+            signature(serde_json::json!({
+                "logicsig": { "logic": "BYEB", "signature": "AAAA" }
+            }))
+            .sig_type()
+        );
+        // e.g. an inner transaction
+        assert_eq!(None, signature(serde_json::json!({})).sig_type());
+
+        let txn: Transaction = serde_json::from_str(HEARTBEAT_TRANSACTION).unwrap();
+        assert_eq!(None, txn.sig_type());
+    }
+
+    #[test]
+    fn test_query_logic_sig_transactions() {
+        let query = QueryTransaction {
+            sig_type: Some(SignatureType::LSig),
+            ..QueryTransaction::default()
+        };
+
+        assert_eq!("sig-type=lsig", query.to_query_string());
+        assert_eq!(
+            Ok(query),
+            serde_urlencoded::from_str::<QueryTransaction>("sig-type=lsig")
+        );
+    }
+
+    /// An acfg transaction of the indexer with this `asset-config-transaction`.
+    fn asset_config_transaction(config: &str) -> Transaction {
+        serde_json::from_str(&format!(
+            r#"{{
+                "asset-config-transaction": {},
+                "close-rewards": 0,
+                "closing-amount": 0,
+                "confirmed-round": 30000000,
+                "fee": 1000,
+                "first-valid": 29999990,
+                "genesis-hash": "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=",
+                "id": "ZL7OAN3GAC5E4TQTKTQZ7TH6LGRT3PPBSTWPBTPXTHWQ6W7BMGFQ",
+                "intra-round-offset": 0,
+                "last-valid": 30000990,
+                "receiver-rewards": 0,
+                "round-time": 1680000000,
+                "sender": "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU",
+                "sender-rewards": 0,
+                "tx-type": "acfg"
+            }}"#,
+            config
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_asset_creation() {
+        let txn = asset_config_transaction(
+            r#"{"asset-id": 0, "params": {"creator": "", "decimals": 2, "default-frozen": false,
+                "manager": "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU",
+                "name": "Test USD", "reserve": "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU",
+                "total": 1000000000, "unit-name": "TUSD"}}"#,
+        );
+
+        let config = txn.asset_config_transaction.unwrap();
+        assert_eq!(AcfgOp::Create, config.operation());
+        let params = config.params.unwrap();
+        assert_eq!(Address::new([0; 32]), params.creator);
+        assert_eq!(Some("TUSD".to_owned()), params.unit_name);
+        assert!(!params.is_empty());
+
+        let txn = asset_config_transaction(r#"{"params": {"creator": "", "total": 1}}"#);
+        assert_eq!(
+            AcfgOp::Create,
+            txn.asset_config_transaction.unwrap().operation()
+        );
+    }
+
+    #[test]
+    fn test_created_ids() {
+        let mut txn = serde_json::to_value(asset_config_transaction(
+            r#"{"asset-id": 0, "params": {"creator": "", "decimals": 0, "total": 1}}"#,
+        ))
+        .unwrap();
+        txn["created-asset-index"] = 1026.into();
+        let asset_creation: Transaction = serde_json::from_value(txn.clone()).unwrap();
+        assert_eq!((Some(1026), None), asset_creation.created_ids());
+
+        txn.as_object_mut()
+            .unwrap()
+            .remove("asset-config-transaction");
+        txn["created-asset-index"] = serde_json::Value::Null;
+        txn["created-application-index"] = 2048.into();
+        txn["tx-type"] = "appl".into();
+        txn["application-transaction"] = serde_json::json!({
+            "application-id": 0,
+            "approval-program": "BoEB",
+            "clear-state-program": "BoEB",
+            "on-completion": "noop"
+        });
+        let application_creation: Transaction = serde_json::from_value(txn).unwrap();
+        assert_eq!((None, Some(2048)), application_creation.created_ids());
+
+        let reconfiguration = asset_config_transaction(r#"{"asset-id": 1026}"#);
+        assert_eq!((None, None), reconfiguration.created_ids());
+    }
+
+    /// A transaction with these fields and the required ones.
+    fn transaction_with(fields: serde_json::Value) -> serde_json::Value {
+        let mut txn = serde_json::json!({
+            "fee": 1000,
+            "first-valid": 29999990,
+            "genesis-hash": "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=",
+            "id": "",
+            "last-valid": 30000990
+        });
+        for (key, value) in fields.as_object().unwrap() {
+            txn[key] = value.clone();
+        }
+        txn
+    }
+
+    #[test]
+    fn test_addresses() {
+        let address = |n: u8| Address::new([n; 32]).to_string();
+        let group: Vec<Transaction> = vec![
+            serde_json::json!({
+                "tx-type": "pay",
+                "sender": address(1),
+                "payment-transaction": {
+                    "amount": 1000,
+                    "receiver": address(2),
+                    "close-remainder-to": address(3)
+                },
+                "rekey-to": address(4)
+            }),
+            // a clawback, signed by the account the clawback account is rekeyed to
+            serde_json::json!({
+                "tx-type": "axfer",
+                "sender": address(5),
+                "asset-transfer-transaction": {
+                    "asset-id": 1026,
+                    "amount": 10,
+                    "sender": address(6),
+                    "receiver": address(7),
+                    "close-to": address(8)
+                },
+                "auth-addr": address(9)
+            }),
+            serde_json::json!({
+                "tx-type": "afrz",
+                "sender": address(5),
+                "asset-freeze-transaction": {
+                    "address": address(10),
+                    "asset-id": 1026,
+                    "new-freeze-status": true
+                }
+            }),
+            // the creator is empty, and the clawback address unset
+            serde_json::json!({
+                "tx-type": "acfg",
+                "sender": address(11),
+                "asset-config-transaction": {
+                    "asset-id": 0,
+                    "params": {
+                        "creator": "",
+                        "decimals": 0,
+                        "total": 1,
+                        "manager": address(11),
+                        "reserve": address(12),
+                        "freeze": address(13)
+                    }
+                }
+            }),
+            serde_json::json!({
+                "tx-type": "appl",
+                "sender": address(1),
+                "application-transaction": {
+                    "application-id": 2048,
+                    "accounts": [address(14), address(2)],
+                    "on-completion": "noop"
+                },
+                "inner-txns": [
+                    transaction_with(serde_json::json!({
+                        "tx-type": "pay",
+                        "sender": address(15),
+                        "payment-transaction": {"amount": 1000, "receiver": address(16)}
+                    })),
+                    // an opt-in
+                    transaction_with(serde_json::json!({
+                        "tx-type": "axfer",
+                        "sender": address(15),
+                        "asset-transfer-transaction": {
+                            "asset-id": 1026,
+                            "amount": 0,
+                            "receiver": address(15)
+                        }
+                    }))
+                ]
+            }),
+            serde_json::json!({
+                "tx-type": "keyreg",
+                "sender": address(17),
+                "keyreg-transaction": {"non-participation": true}
+            }),
+        ]
+        .into_iter()
+        .map(|fields| serde_json::from_value(transaction_with(fields)).unwrap())
+        .collect();
+
+        let addresses: Vec<Vec<String>> = group
+            .iter()
+            .map(|txn| txn.addresses().iter().map(Address::to_string).collect())
+            .collect();
+
+        assert_eq!(
+            vec![
+                vec![address(1), address(2), address(3), address(4)],
+                vec![address(5), address(6), address(7), address(8), address(9)],
+                vec![address(5), address(10)],
+                vec![address(11), address(12), address(13)],
+                vec![
+                    address(1),
+                    address(14),
+                    address(2),
+                    address(15),
+                    address(16)
+                ],
+                vec![address(17)],
+            ],
+            addresses
+        );
+    }
+
+    #[test]
+    fn test_asset_reconfiguration() {
+        let txn = asset_config_transaction(
+            r#"{"asset-id": 1026, "params": {"creator": "", "decimals": 0, "default-frozen": false,
+                "manager": "4MYUHDWHWXAKA5KA7U5PEN646VYUANBFXVJNONBK3TIMHEMWMD4UBOJBI4", "total": 0}}"#,
+        );
+
+        let config = txn.asset_config_transaction.unwrap();
+        assert_eq!(AcfgOp::Reconfigure, config.operation());
+        assert_eq!(
+            Some(
+                "4MYUHDWHWXAKA5KA7U5PEN646VYUANBFXVJNONBK3TIMHEMWMD4UBOJBI4"
+                    .parse()
+                    .unwrap()
+            ),
+            config.params.unwrap().manager
+        );
+    }
+
+    #[test]
+    fn test_asset_destruction() {
+        let txn = asset_config_transaction(
+            r#"{"asset-id": 1026, "params": {"creator": "", "decimals": 0, "default-frozen": false, "total": 0}}"#,
+        );
+
+        let config = txn.asset_config_transaction.unwrap();
+        assert_eq!(AcfgOp::Destroy, config.operation());
+        assert!(config.params.as_ref().unwrap().is_empty());
+        // The empty creator is kept when encoding.
+        assert!(serde_json::to_string(&config)
+            .unwrap()
+            .contains(r#""creator":"""#));
+
+        let txn = asset_config_transaction(r#"{"asset-id": 1026}"#);
+        assert_eq!(
+            AcfgOp::Destroy,
+            txn.asset_config_transaction.unwrap().operation()
+        );
+    }
+
+    #[test]
+    fn test_transaction_logs() {
+        let txn: Transaction = serde_json::from_value(serde_json::json!({
+            "application-transaction": {
+                "application-id": 1,
+                "on-completion": "noop"
+            },
+            "fee": 1000,
+            "first-valid": 1,
+            "genesis-hash": "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=",
+            "id": "TX",
+            "inner-txns": [{
+                "application-transaction": {
+                    "application-id": 2,
+                    "on-completion": "noop"
+                },
+                "fee": 0,
+                "first-valid": 1,
+                "genesis-hash": "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=",
+                "id": "",
+                "last-valid": 1001,
+                "logs": ["aW5uZXI="],
+                "sender": "4MYUHDWHWXAKA5KA7U5PEN646VYUANBFXVJNONBK3TIMHEMWMD4UBOJBI4",
+                "tx-type": "appl"
+            }],
+            "last-valid": 1001,
+            // "hello", then an ARC-4 return value of 0x0000000000000007
+            "logs": ["aGVsbG8=", "FR98dQAAAAAAAAAH"],
+            "sender": "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU",
+            "tx-type": "appl"
+        }))
+        .unwrap();
+
+        assert_eq!(b"hello".to_vec(), txn.logs[0]);
+        assert_eq!("hello", txn.decoded_logs()[0]);
+        assert_eq!(
+            vec![&b"hello"[..], &txn.logs[1][..], &b"inner"[..]],
+            txn.all_logs()
+        );
+        assert_eq!(Some(&[0, 0, 0, 0, 0, 0, 0, 7][..]), txn.return_value_log());
+        assert_eq!(None, txn.inner_txns[0].return_value_log());
+
+        // a prefixed log followed by another log isn't a return value
+        let mut logged_after_return = txn.clone();
+        logged_after_return.logs.push(b"after".to_vec());
+        assert_eq!(None, logged_after_return.return_value_log());
+
+        let json = serde_json::to_value(&txn).unwrap();
+        assert_eq!(
+            serde_json::json!(["aGVsbG8=", "FR98dQAAAAAAAAAH"]),
+            json["logs"]
+        );
+    }
+
+    #[test]
+    fn test_block_contains_transaction() {
+        let block: Block = serde_json::from_value(serde_json::json!({
+            "genesis-hash": "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=",
+            "genesis-id": "testnet-v1.0",
+            "previous-block-hash": "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=",
+            "round": 1000,
+            "seed": "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=",
+            "timestamp": 1600000000,
+            "transactions": [{
+                "application-transaction": {
+                    "application-id": 1,
+                    "on-completion": "noop"
+                },
+                "fee": 1000,
+                "first-valid": 1,
+                "genesis-hash": "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=",
+                "id": "N5T74SANUWLHI6ZWYFQBEB6J2VXBTYUYZNWQB2V26DCF4ARKC7GA",
+                "inner-txns": [{
+                    "fee": 0,
+                    "first-valid": 1,
+                    "genesis-hash": "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=",
+                    "id": "",
+                    "inner-txns": [{
+                        "fee": 0,
+                        "first-valid": 1,
+                        "genesis-hash": "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=",
+                        "id": "3IN6FMTXRBTB6RDNS6YGT7JBNFDQEIJYSQW6LYXXF4ZYUHEAGDMQ",
+                        "last-valid": 1001,
+                        "sender": "4MYUHDWHWXAKA5KA7U5PEN646VYUANBFXVJNONBK3TIMHEMWMD4UBOJBI4",
+                        "tx-type": "pay"
+                    }],
+                    "last-valid": 1001,
+                    "sender": "4MYUHDWHWXAKA5KA7U5PEN646VYUANBFXVJNONBK3TIMHEMWMD4UBOJBI4",
+                    "tx-type": "appl"
+                }],
+                "last-valid": 1001,
+                "sender": "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU",
+                "tx-type": "appl"
+            }],
+            "transactions-root": "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA="
+        }))
+        .unwrap();
+
+        assert!(block.contains_transaction("N5T74SANUWLHI6ZWYFQBEB6J2VXBTYUYZNWQB2V26DCF4ARKC7GA"));
+        assert!(block.contains_transaction("3IN6FMTXRBTB6RDNS6YGT7JBNFDQEIJYSQW6LYXXF4ZYUHEAGDMQ"));
+        assert!(!block.contains_transaction("QJZSGMO2MVK4Y4LJVCVFHYRAGQQEFGE4TNFWI7KEWMCKZ3ITWEVQ"));
+        assert!(!block.contains_transaction(""));
+    }
+
+    #[test]
+    fn test_decoded_note() {
+        let mut json: serde_json::Value = serde_json::from_str(STATE_PROOF_TRANSACTION).unwrap();
+        json["note"] = "aGVsbG8=".into();
+        let txn: Transaction = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(Ok(Some(b"hello".to_vec())), txn.decoded_note());
+
+        json["note"] = "not base64!".into();
+        let txn: Transaction = serde_json::from_value(json).unwrap();
+        assert_eq!(Err(FieldDecodeError { field: "note" }), txn.decoded_note());
+    }
+
+    #[test]
+    fn test_note_as_json() {
+        let mut json: serde_json::Value = serde_json::from_str(STATE_PROOF_TRANSACTION).unwrap();
+        // {"item":"café"}
+        json["note"] = "eyJpdGVtIjoiY2Fmw6kifQ==".into();
+        let txn: Transaction = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(
+            Ok(Some(serde_json::json!({"item": "café"}))),
+            txn.note_as_json()
+        );
+        assert_eq!(
+            Err(FieldDecodeError { field: "note" }),
+            txn.note_as_json::<u64>()
+        );
+
+        json["note"] = serde_json::Value::Null;
+        let txn: Transaction = serde_json::from_value(json).unwrap();
+        assert_eq!(Ok(None), txn.note_as_json::<u64>());
+    }
+
+    #[test]
+    fn test_decoded_state_delta() {
+        // Application call setting the global key "counter" and deleting "name", and setting the
+        // local key "level" of the sender.
+        let txn: Transaction = serde_json::from_value(serde_json::json!({
+            "application-transaction": {
+                "application-id": 1,
+                "on-completion": "noop"
+            },
+            "fee": 1000,
+            "first-valid": 1,
+            "genesis-hash": "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=",
+            "global-state-delta": [
+                { "key": "Y291bnRlcg==", "value": { "action": 2, "uint": 5 } },
+                { "key": "bmFtZQ==", "value": { "action": 3 } }
+            ],
+            "id": "TX",
+            "last-valid": 1001,
+            "local-state-delta": [{
+                "address": "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU",
+                "delta": [
+                    { "key": "bGV2ZWw=", "value": { "action": 1, "bytes": "AQ==" } }
+                ]
+            }],
+            "sender": "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU",
+            "tx-type": "appl"
+        }))
+        .unwrap();
+
+        assert_eq!(
+            Ok(vec![
+                TealKeyDelta {
+                    key: b"counter".to_vec(),
+                    action: TealDeltaAction::Set(TealValue::Uint(5)),
+                },
+                TealKeyDelta {
+                    key: b"name".to_vec(),
+                    action: TealDeltaAction::Delete,
+                },
+            ]),
+            txn.decoded_global_state_delta()
+        );
+        assert_eq!(
+            Ok(vec![(
+                "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU".to_owned(),
+                vec![TealKeyDelta {
+                    key: b"level".to_vec(),
+                    action: TealDeltaAction::Set(TealValue::Bytes(vec![1])),
+                }]
+            )]),
+            txn.decoded_local_state_delta()
+        );
+
+        let mut state = TealKeyValueStore::from_deltas(&[TealKeyDelta {
+            key: b"name".to_vec(),
+            action: TealDeltaAction::Set(TealValue::Bytes(b"algonaut".to_vec())),
+        }]);
+        state.apply_deltas(&txn.decoded_global_state_delta().unwrap());
+        assert_eq!(Some(5), state.get_uint("counter"));
+        assert_eq!(None, state.get("name"));
+    }
+
+    /// An online key registration in the shape returned by the indexer.
+    const ONLINE_KEY_REGISTRATION: &str = r#"{
+        "close-rewards": 0,
+        "closing-amount": 0,
+        "confirmed-round": 27620000,
+        "fee": 1000,
+        "first-valid": 27619990,
+        "genesis-hash": "wGHE2Pwdvd7S12BL5FaOP20EGYesN73ktiC1qzkkit8=",
+        "id": "KRONLINEKRONLINEKRONLINEKRONLINEKRONLINEKRONLINEKRON",
+        "intra-round-offset": 3,
+        "keyreg-transaction": {
+            "non-participation": false,
+            "selection-participation-key": "aREM77/qAY3++eRmrfwYF0+FU5rigXEYJcHBhstEg4Q=",
+            "state-proof-key": "4WiTWzk2jfxryeznt0uktQAdtGaxV2AkD2otDw2D9vGEmTI/LmpAoHHFTydPqXdpxLldVsijwRzx24avIEKI1A==",
+            "vote-first-valid": 27619000,
+            "vote-key-dilution": 1733,
+            "vote-last-valid": 30619000,
+            "vote-participation-key": "qydEdKaqgsEA3dymOXf6y1VvZvSJ+1WMBEpFb5upGc4="
+        },
+        "last-valid": 27620990,
+        "receiver-rewards": 0,
+        "round-time": 1679000000,
+        "sender": "XM6FEYVJ2XDU2IBH4OT6VZGW75YM63CM4TC6AV6BD3JZXFJUIICYTVB5EU",
+        "sender-rewards": 0,
+        "tx-type": "keyreg"
+    }"#;
+
+    /// A key registration taking an account offline, in the shape returned by the indexer.
+    const OFFLINE_KEY_REGISTRATION: &str = r#"{
+        "close-rewards": 0,
+        "closing-amount": 0,
+        "confirmed-round": 27620100,
+        "fee": 1000,
+        "first-valid": 27620090,
+        "genesis-hash": "wGHE2Pwdvd7S12BL5FaOP20EGYesN73ktiC1qzkkit8=",
+        "id": "KROFFLINEKROFFLINEKROFFLINEKROFFLINEKROFFLINEKROFFLI",
+        "intra-round-offset": 0,
+        "keyreg-transaction": {
+            "non-participation": false
+        },
+        "last-valid": 27621090,
+        "receiver-rewards": 0,
+        "round-time": 1679000400,
+        "sender": "XM6FEYVJ2XDU2IBH4OT6VZGW75YM63CM4TC6AV6BD3JZXFJUIICYTVB5EU",
+        "sender-rewards": 0,
+        "tx-type": "keyreg"
+    }"#;
+
+    #[test]
+    fn test_online_key_registration() {
+        let txn: Transaction = serde_json::from_str(ONLINE_KEY_REGISTRATION).unwrap();
+
+        assert_eq!(
+            Ok(Some(KeyRegistrationTransaction::Online(
+                OnlineKeyRegistration {
+                    vote_pk: VotePk::from_base64_str(
+                        "qydEdKaqgsEA3dymOXf6y1VvZvSJ+1WMBEpFb5upGc4="
+                    )
+                    .unwrap(),
+                    selection_pk: VrfPk::from_base64_str(
+                        "aREM77/qAY3++eRmrfwYF0+FU5rigXEYJcHBhstEg4Q="
+                    )
+                    .unwrap(),
+                    state_proof_pk: Some(StateProofPk::from_base64_str(
+                        "4WiTWzk2jfxryeznt0uktQAdtGaxV2AkD2otDw2D9vGEmTI/LmpAoHHFTydPqXdpxLldVsijwRzx24avIEKI1A=="
+                    )
+                    .unwrap()),
+                    vote_first: Round(27619000),
+                    vote_last: Round(30619000),
+                    vote_key_dilution: 1733,
+                }
+            ))),
+            txn.key_registration()
+        );
+    }
+
+    #[test]
+    fn test_offline_key_registration() {
+        let txn: Transaction = serde_json::from_str(OFFLINE_KEY_REGISTRATION).unwrap();
+        assert_eq!(
+            Ok(Some(KeyRegistrationTransaction::Offline {
+                non_participation: false
+            })),
+            txn.key_registration()
+        );
+
+        let mut json: serde_json::Value = serde_json::from_str(OFFLINE_KEY_REGISTRATION).unwrap();
+        json["keyreg-transaction"]["non-participation"] = true.into();
+        let txn: Transaction = serde_json::from_value(json).unwrap();
+        assert_eq!(
+            Ok(Some(KeyRegistrationTransaction::Offline {
+                non_participation: true
+            })),
+            txn.key_registration()
+        );
+    }
+
+    #[test]
+    fn test_invalid_key_registration() {
+        let mut json: serde_json::Value = serde_json::from_str(ONLINE_KEY_REGISTRATION).unwrap();
+        json["keyreg-transaction"]
+            .as_object_mut()
+            .unwrap()
+            .remove("selection-participation-key");
+        let txn: Transaction = serde_json::from_value(json).unwrap();
+        assert_eq!(
+            Err(FieldDecodeError {
+                field: "selection-participation-key"
+            }),
+            txn.key_registration()
+        );
+
+        let mut json: serde_json::Value = serde_json::from_str(ONLINE_KEY_REGISTRATION).unwrap();
+        // a 32 bytes key
+        json["keyreg-transaction"]["state-proof-key"] =
+            "qydEdKaqgsEA3dymOXf6y1VvZvSJ+1WMBEpFb5upGc4=".into();
+        let txn: Transaction = serde_json::from_value(json).unwrap();
+        assert_eq!(
+            Err(FieldDecodeError {
+                field: "state-proof-key"
+            }),
+            txn.key_registration()
+        );
+    }
+
+    #[test]
+    fn test_key_registration_of_other_transaction_types() {
+        let txn: Transaction = serde_json::from_str(STATE_PROOF_TRANSACTION).unwrap();
+        assert_eq!(Ok(None), txn.key_registration());
+    }
+
+    #[test]
+    fn test_transaction_type_roundtrip() {
+        for tx_type in [
+            "pay", "keyreg", "acfg", "axfer", "afrz", "appl", "stpf", "hb",
+        ] {
+            let deserialized: TransactionType =
+                serde_json::from_value(serde_json::Value::from(tx_type)).unwrap();
+            assert!(!matches!(deserialized, TransactionType::Unknown(_)));
+            assert_eq!(tx_type, serde_json::to_value(deserialized).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_deserialize_unknown_transaction_type() {
+        let mut txn: serde_json::Value = serde_json::from_str(STATE_PROOF_TRANSACTION).unwrap();
+        txn["tx-type"] = "xyz".into();
+        txn.as_object_mut()
+            .unwrap()
+            .remove("state-proof-transaction");
+
+        let txn: Transaction = serde_json::from_value(txn).unwrap();
+
+        assert_eq!(TransactionType::Unknown("xyz".to_owned()), txn.tx_type);
+        assert_eq!("xyz", serde_json::to_value(txn.tx_type).unwrap());
+    }
+
+    /// `json` with an unknown field added to each of its objects, at every nesting level, as a
+    /// newer server could send it.
+    fn with_unknown_fields(json: serde_json::Value) -> serde_json::Value {
+        match json {
+            serde_json::Value::Object(object) => {
+                let mut object: serde_json::Map<_, _> = object
+                    .into_iter()
+                    .map(|(key, value)| (key, with_unknown_fields(value)))
+                    .collect();
+                object.insert("x-future-field".to_owned(), serde_json::json!({"a": [1]}));
+                serde_json::Value::Object(object)
+            }
+            serde_json::Value::Array(values) => {
+                values.into_iter().map(with_unknown_fields).collect()
+            }
+            value => value,
+        }
+    }
+
+    /// Asserts that `json` is deserialized the same with unknown fields in all its objects.
+    fn assert_ignores_unknown_fields<T>(json: serde_json::Value)
+    where
+        T: serde::de::DeserializeOwned + PartialEq + std::fmt::Debug,
+    {
+        let expected: T = serde_json::from_value(json.clone()).unwrap();
+        let extended = with_unknown_fields(json);
+        assert_eq!(expected, serde_json::from_value::<T>(extended).unwrap());
+    }
+
+    #[test]
+    fn test_unknown_fields_are_ignored() {
+        for txn in [
+            STATE_PROOF_TRANSACTION,
+            ONLINE_KEY_REGISTRATION,
+            OFFLINE_KEY_REGISTRATION,
+        ] {
+            assert_ignores_unknown_fields::<Transaction>(serde_json::from_str(txn).unwrap());
+        }
+        assert_ignores_unknown_fields::<AssetResponse>(
+            serde_json::from_str(ASSETS_INCLUDING_DESTROYED).unwrap(),
+        );
+        assert_ignores_unknown_fields::<Block>(serde_json::json!({
+            "genesis-hash": "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=",
+            "genesis-id": "testnet-v1.0",
+            "previous-block-hash": "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=",
+            "round": 1000,
+            "seed": "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=",
+            "timestamp": 1600000000,
+            "transactions": [{
+                "application-transaction": {
+                    "application-id": 1,
+                    "global-state-schema": {"num-byte-slice": 1, "num-uint": 0},
+                    "on-completion": "optin"
+                },
+                "fee": 1000,
+                "first-valid": 1,
+                "genesis-hash": "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=",
+                "global-state-delta": [{"key": "a2V5", "value": {"action": 2, "uint": 3}}],
+                "id": "TX",
+                "last-valid": 1001,
+                "sender": "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU",
+                "signature": {"sig": "AAAA"},
+                "tx-type": "appl"
+            }],
+            "transactions-root": "",
+            "txn-counter": 1
+        }));
+        assert_ignores_unknown_fields::<AccountResponse>(serde_json::json!({
+            "accounts": [{
+                "address": "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU",
+                "amount": 1000,
+                "assets": [{
+                    "amount": 5,
+                    "asset-id": 1026,
+                    "creator": "4MYUHDWHWXAKA5KA7U5PEN646VYUANBFXVJNONBK3TIMHEMWMD4UBOJBI4",
+                    "is-frozen": false
+                }],
+                "sig-type": "sig",
+                "status": "Offline"
+            }],
+            "current-round": 10
+        }));
+    }
+
+    #[test]
+    fn test_unknown_enum_values() {
+        let roundtrip = |value: &str| -> (SignatureType, OnCompletion, Role) {
+            let json = serde_json::Value::from(value);
+            let deserialized = (
+                serde_json::from_value(json.clone()).unwrap(),
+                serde_json::from_value(json.clone()).unwrap(),
+                serde_json::from_value(json.clone()).unwrap(),
+            );
+            assert_eq!(json, serde_json::to_value(&deserialized.0).unwrap());
+            assert_eq!(json, serde_json::to_value(&deserialized.1).unwrap());
+            assert_eq!(json, serde_json::to_value(&deserialized.2).unwrap());
+            deserialized
+        };
+
+        assert_eq!(
+            (
+                SignatureType::Unknown("future".to_owned()),
+                OnCompletion::Unknown("future".to_owned()),
+                Role::Unknown("future".to_owned())
+            ),
+            roundtrip("future")
+        );
+        assert_eq!(SignatureType::MultiSig, roundtrip("msig").0);
+        assert_eq!(OnCompletion::Closeout, roundtrip("closeout").1);
+        assert_eq!(Role::FreezeTarget, roundtrip("freeze-target").2);
+    }
+
+    /// An online account eligible for the incentives, with the fields of the indexer's API since
+    /// the consensus incentives. It's built for the test (made-up keys), not captured from a
+    /// network.
+    const ONLINE_ACCOUNT: &str = r#"{
+        "address": "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU",
+        "amount": 120000000000,
+        "amount-without-pending-rewards": 120000000000,
+        "created-apps": [],
+        "created-assets": [],
+        "apps-local-state": [],
+        "incentive-eligible": true,
+        "last-heartbeat": 46000100,
+        "last-proposed": 46001500,
+        "participation": {
+            "selection-participation-key": "ISIjJCUmJygpKissLS4vMDEyMzQ1Njc4OTo7PD0+P0A=",
+            "state-proof-key": "QUJDREVGR0hJSktMTU5PUFFSU1RVVldYWVpbXF1eX2BhYmNkZWZnaGlqa2xtbm9wcXJzdHV2d3h5ent8fX5/gA==",
+            "vote-first-valid": 46000000,
+            "vote-key-dilution": 2450,
+            "vote-last-valid": 49000000,
+            "vote-participation-key": "AQIDBAUGBwgJCgsMDQ4PEBESExQVFhcYGRobHB0eHyA="
+        },
+        "pending-rewards": 0,
+        "reward-base": 218288,
+        "rewards": 0,
+        "round": 46002000,
+        "sig-type": "sig",
+        "status": "Online"
+    }"#;
+
+    #[test]
+    fn test_online_account() {
+        let account: Account = serde_json::from_str(ONLINE_ACCOUNT).unwrap();
+
+        assert_eq!(Some(true), account.incentive_eligible);
+        assert_eq!(Some(Round(46000100)), account.last_heartbeat);
+        assert_eq!(Some(Round(46001500)), account.last_proposed);
+        let participation = account.participation.as_ref().unwrap();
+        assert_eq!(2450, participation.vote_key_dilution);
+        assert!(participation.vote_pk() == Some(VotePk(core::array::from_fn(|i| i as u8 + 1))));
+        assert!(
+            participation.selection_pk() == Some(VrfPk(core::array::from_fn(|i| i as u8 + 33)))
+        );
+        assert!(
+            participation.state_proof_pk()
+                == Some(StateProofPk(core::array::from_fn(|i| i as u8 + 65)))
+        );
+
+        assert_eq!(
+            ParticipationStatus::Online,
+            account.participation_status(Round(46002000))
+        );
+        // the keys expired
+        assert_eq!(
+            ParticipationStatus::Offline,
+            account.participation_status(Round(49000001))
+        );
+    }
+
+    #[test]
+    fn test_participation_status() {
+        let mut account: Account = serde_json::from_str(ONLINE_ACCOUNT).unwrap();
+
+        account.status = "Offline".to_owned();
+        assert_eq!(
+            ParticipationStatus::Suspended,
+            account.participation_status(Round(46002000))
+        );
+        assert_eq!(
+            ParticipationStatus::Offline,
+            account.participation_status(Round(45999999))
+        );
+
+        account.participation = None;
+        assert_eq!(
+            ParticipationStatus::Offline,
+            account.participation_status(Round(46002000))
+        );
+
+        account.status = "NotParticipating".to_owned();
+        assert_eq!(
+            ParticipationStatus::NotParticipating,
+            account.participation_status(Round(46002000))
+        );
+    }
+
+    #[test]
+    fn test_account_without_incentive_fields() {
+        let mut json: serde_json::Value = serde_json::from_str(ONLINE_ACCOUNT).unwrap();
+        let object = json.as_object_mut().unwrap();
+        for field in ["incentive-eligible", "last-heartbeat", "last-proposed"] {
+            object.remove(field);
+        }
+        object["participation"]
+            .as_object_mut()
+            .unwrap()
+            .remove("state-proof-key");
+
+        // the keys are deserialized from borrowed strings only
+        let account: Account = serde_json::from_str(&json.to_string()).unwrap();
+
+        assert_eq!(None, account.incentive_eligible);
+        assert_eq!(None, account.last_proposed);
+        assert!(account.participation.unwrap().state_proof_pk().is_none());
+    }
+
+    #[test]
+    fn test_asset_amount() {
+        let response: AssetsInfoResponse = serde_json::from_str(
+            r#"{
+                "asset": {
+                    "index": 31566704,
+                    "params": {
+                        "creator": "2UEQTE5QDNXPI7M3TU44G6SYKLFWLPQO7EBZM7K7MHMQQMFI4QJPLHQFHM",
+                        "decimals": 6,
+                        "total": 18446744073709551615,
+                        "unit-name": "USDC"
+                    }
+                },
+                "current-round": 1
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(AssetAmount::new(12_340_000, 6), response.amount(12_340_000));
+        assert_eq!("12.340000", response.asset.format_amount(12_340_000));
+        assert_eq!("0.000001", response.asset.format_amount(1));
+    }
+
+    /// A search of assets with `include-all`, returning a destroyed asset next to a live one.
+    const ASSETS_INCLUDING_DESTROYED: &str = r#"{
+        "assets": [
+            {
+                "created-at-round": 1200,
+                "deleted": true,
+                "destroyed-at-round": 1500,
+                "index": 1026,
+                "params": {
+                    "creator": "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU",
+                    "decimals": 0,
+                    "total": 0
+                }
+            },
+            {
+                "created-at-round": 1600,
+                "deleted": false,
+                "index": 1027,
+                "params": {
+                    "creator": "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU",
+                    "decimals": 2,
+                    "name": "Test USD",
+                    "total": 100000,
+                    "unit-name": "TUSD"
+                }
+            }
+        ],
+        "current-round": 2000
+    }"#;
+
+    #[test]
+    fn test_destroyed_asset() {
+        let response: AssetResponse = serde_json::from_str(ASSETS_INCLUDING_DESTROYED).unwrap();
+
+        let destroyed = &response.assets[0];
+        assert_eq!(Some(true), destroyed.deleted);
+        assert_eq!(Some(Round(1200)), destroyed.created_at_round);
+        assert_eq!(Some(Round(1500)), destroyed.destroyed_at_round);
+        assert_eq!(None, destroyed.params.unit_name);
+
+        let live = &response.assets[1];
+        assert_eq!(Some(false), live.deleted);
+        assert_eq!(None, live.destroyed_at_round);
+        assert_eq!(Some("TUSD".to_owned()), live.params.unit_name);
+    }
+
+    #[test]
+    fn test_query_assets_builder() {
+        let creator: Address = "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU"
+            .parse()
+            .unwrap();
+
+        let query = QueryAssets::default()
+            .created_by(creator)
+            .with_name("usd")
+            .with_unit("TUSD")
+            .including_all()
+            .with_limit(10);
+
+        assert_eq!(
+            QueryAssets {
+                asset_id: None,
+                creator: Some(creator),
+                include_all: Some(true),
+                limit: Some(10),
+                name: Some("usd".to_owned()),
+                next: None,
+                unit: Some("TUSD".to_owned()),
+            },
+            query
+        );
+        assert_eq!(Ok(()), query.validate());
+        assert_eq!(
+            Ok(()),
+            QueryAssets::by_id(1026)
+                .including_all()
+                .with_limit(1)
+                .validate()
+        );
+    }
+
+    #[test]
+    fn test_query_assets_asset_id_excludes_other_filters() {
+        let creator: Address = "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU"
+            .parse()
+            .unwrap();
+
+        for (query, param) in [
+            (QueryAssets::by_id(1026).created_by(creator), "creator"),
+            (QueryAssets::by_id(1026).with_name("usd"), "name"),
+            (QueryAssets::by_id(1026).with_unit("TUSD"), "unit"),
+        ] {
+            assert_eq!(
+                Err(QueryError {
+                    reason: format!("asset_id can't be combined with {}", param),
+                    param: Some(param)
+                }),
+                query.validate()
+            );
+        }
+    }
+
+    #[test]
+    fn test_query_string() {
+        let query = QueryTransaction {
+            address: Some("47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU".to_owned()),
+            address_role: Some(Role::Sender),
+            limit: Some(10),
+            min_round: Some(Round(5)),
+            note_prefix: Some("a+b/c=".to_owned()),
+            ..QueryTransaction::default()
+        };
+        assert_eq!(
+            "address=47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU&address-role=sender&limit=10&min-round=5&note-prefix=a%2Bb%2Fc%3D",
+            query.to_query_string()
+        );
+
+        let creator: Address = "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU"
+            .parse()
+            .unwrap();
+        let query = QueryAssets::default()
+            .created_by(creator)
+            .with_unit("USD")
+            .including_all();
+        assert_eq!(
+            "creator=47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU&include-all=true&unit=USD",
+            query.to_query_string()
+        );
+
+        assert_eq!("", QueryAccount::default().to_query_string());
+    }
+
+    #[test]
+    fn test_query_excluding_close_to() {
+        let address: Address = "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU"
+            .parse()
+            .unwrap();
+
+        let query = QueryTransaction::default()
+            .with_address(address, Role::Receiver)
+            .excluding_close_to();
+        assert_eq!(
+            "address=47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU&address-role=receiver&exclude-close-to=true",
+            query.to_query_string()
+        );
+        assert_eq!(Ok(()), query.validate());
+
+        let query = QueryAssetTransaction::default()
+            .with_address(address, Role::FreezeTarget)
+            .with_round_range(RoundRange::new(1, 2));
+        assert_eq!(
+            "address=47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU&address-role=freeze-target&max-round=2&min-round=1",
+            query.to_query_string()
+        );
+        assert_eq!(
+            Err(QueryError {
+                reason: "exclude_close_to requires the receiver address_role".to_owned(),
+                param: Some("exclude_close_to")
+            }),
+            query.excluding_close_to().validate()
+        );
+
+        let query = QueryTransaction {
+            exclude_close_to: Some(true),
+            ..QueryTransaction::default()
+        };
+        assert_eq!(
+            Err(QueryError {
+                reason: "exclude_close_to requires an address".to_owned(),
+                param: Some("exclude_close_to")
+            }),
+            query.validate()
+        );
+        let query = QueryAssetTransaction {
+            address: Some(address.to_string()),
+            exclude_close_to: Some(true),
+            ..QueryAssetTransaction::default()
+        };
+        assert_eq!(
+            Some("exclude_close_to"),
+            query.validate().unwrap_err().param
+        );
+
+        // not excluding is meaningful with any role
+        let query = QueryTransaction {
+            exclude_close_to: Some(false),
+            ..QueryTransaction::default().with_address(address, Role::Sender)
+        };
+        assert_eq!(Ok(()), query.validate());
+    }
+
+    /// A page of the payments received by `47YPQ...` (`address-role=receiver`) without
+    /// `exclude-close-to`: the second one closes another account to it without paying it.
+    const RECEIVED_PAYMENTS: &str = r#"{
+        "current-round": 27481100,
+        "transactions": [
+            {
+                "confirmed-round": 27481032,
+                "fee": 1000,
+                "first-valid": 27481030,
+                "genesis-hash": "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=",
+                "genesis-id": "testnet-v1.0",
+                "id": "7BTQQ4MLHIDZQUFX6XCFVXQ3SMZO4AFCXVU3ZTB5QMLMXNZ7ZGBA",
+                "last-valid": 27482030,
+                "payment-transaction": {
+                    "amount": 1000000,
+                    "receiver": "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU"
+                },
+                "sender": "4MYUHDWHWXAKA5KA7U5PEN646VYUANBFXVJNONBK3TIMHEMWMD4UBOJBI4",
+                "tx-type": "pay"
+            },
+            {
+                "confirmed-round": 27481040,
+                "fee": 1000,
+                "first-valid": 27481038,
+                "genesis-hash": "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=",
+                "genesis-id": "testnet-v1.0",
+                "id": "IWLLR7YLQ2TO2XFTB3OKIQMUJXY2RSIL6GEJKYZWDDRUMCCPY5HQ",
+                "last-valid": 27482038,
+                "payment-transaction": {
+                    "amount": 0,
+                    "close-amount": 2500000,
+                    "close-remainder-to": "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU",
+                    "receiver": "4MYUHDWHWXAKA5KA7U5PEN646VYUANBFXVJNONBK3TIMHEMWMD4UBOJBI4"
+                },
+                "sender": "4MYUHDWHWXAKA5KA7U5PEN646VYUANBFXVJNONBK3TIMHEMWMD4UBOJBI4",
+                "tx-type": "pay"
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn test_received_payments_with_close_to() {
+        let address = "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU";
+        let response: TransactionResponse = serde_json::from_str(RECEIVED_PAYMENTS).unwrap();
+
+        let payments: Vec<&TransactionPayment> = response
+            .transactions
+            .iter()
+            .map(|txn| txn.payment_transaction.as_ref().unwrap())
+            .collect();
+        assert_eq!(2, payments.len());
+        // the transactions `exclude-close-to=true` leaves out: the address isn't their receiver
+        let close_to_only: Vec<&&TransactionPayment> = payments
+            .iter()
+            .filter(|payment| payment.receiver != address)
+            .collect();
+        assert_eq!(1, close_to_only.len());
+        assert_eq!(
+            Some(address),
+            close_to_only[0].close_remainder_to.as_deref()
+        );
+        assert_eq!(MicroAlgos(0), close_to_only[0].amount);
+        assert_eq!(Some(MicroAlgos(2_500_000)), close_to_only[0].close_amount);
+    }
+
+    #[test]
+    fn test_query_with_empty_round_range() {
+        let query = QueryTransaction {
+            min_round: Some(Round(20)),
+            max_round: Some(Round(10)),
+            ..QueryTransaction::default()
+        };
+        assert_eq!(
+            Err(QueryError {
+                reason: "min_round > max_round (20 > 10)".to_owned(),
+                param: Some("min_round")
+            }),
+            query.validate()
+        );
+
+        let query = QueryAccountTransaction::default().with_round_range(RoundRange::new(20, 10));
+        assert_eq!(Some("min_round"), query.validate().unwrap_err().param);
+        let query = QueryAssetTransaction::default().with_round_range(RoundRange::new(20, 10));
+        assert_eq!(Some("min_round"), query.validate().unwrap_err().param);
+
+        let query = QueryTransaction::default().with_round_range(RoundRange::new(10, 10));
+        assert_eq!(Ok(()), query.validate());
+    }
+
+    #[test]
+    fn test_query_with_zero_id() {
+        assert_eq!(
+            Err(QueryError {
+                reason: "asset_id is zero".to_owned(),
+                param: Some("asset_id")
+            }),
+            QueryAssets::by_id(0).validate()
+        );
+        let query = QueryTransaction {
+            application_id: Some(0),
+            ..QueryTransaction::default()
+        };
+        assert_eq!(Some("application_id"), query.validate().unwrap_err().param);
+        let query = QueryApplications {
+            application_id: Some(0),
+            ..QueryApplications::default()
+        };
+        assert_eq!(Some("application_id"), query.validate().unwrap_err().param);
+        let query = QueryAccount {
+            asset_id: Some(0),
+            ..QueryAccount::default()
+        };
+        assert_eq!(Some("asset_id"), query.validate().unwrap_err().param);
+    }
+
+    #[test]
+    fn test_query_ids_absent_zero_and_nonzero() {
+        let query = |asset_id, application_id| QueryTransaction {
+            asset_id,
+            application_id,
+            ..QueryTransaction::default()
+        };
+        assert_eq!("", query(None, None).to_query_string());
+        assert_eq!(
+            "application-id=0&asset-id=0",
+            query(Some(0), Some(0)).to_query_string()
+        );
+        assert_eq!(
+            "application-id=7&asset-id=5",
+            query(Some(5), Some(7)).to_query_string()
+        );
+
+        let query = |asset_id| QueryAccountTransaction {
+            asset_id,
+            ..QueryAccountTransaction::default()
+        };
+        assert_eq!("", query(None).to_query_string());
+        assert_eq!("asset-id=0", query(Some(0)).to_query_string());
+        assert_eq!("asset-id=5", query(Some(5)).to_query_string());
+    }
+
+    #[test]
+    fn test_query_by_txid() {
+        let query = QueryTransaction {
+            txid: Some("W6EXW5GN6WPIUS4ZNA7JIMI4FQP6EV46IQFGNKVQXDPZ3LDEQUXA".to_owned()),
+            ..QueryTransaction::default()
+        };
+        assert_eq!(
+            "txid=W6EXW5GN6WPIUS4ZNA7JIMI4FQP6EV46IQFGNKVQXDPZ3LDEQUXA",
+            query.to_query_string()
+        );
+
+        let query = QueryAssetTransaction {
+            txid: Some("W6EXW5GN6WPIUS4ZNA7JIMI4FQP6EV46IQFGNKVQXDPZ3LDEQUXA".to_owned()),
+            tx_type: Some(TransactionType::AssetTransferTransaction),
+            ..QueryAssetTransaction::default()
+        };
+        assert_eq!(
+            "tx-type=axfer&txid=W6EXW5GN6WPIUS4ZNA7JIMI4FQP6EV46IQFGNKVQXDPZ3LDEQUXA",
+            query.to_query_string()
+        );
+    }
+
+    #[test]
+    fn test_query_with_malformed_address() {
+        let query = QueryTransaction {
+            address: Some("47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPAS".to_owned()),
+            ..QueryTransaction::default()
+        };
+        let error = query.validate().unwrap_err();
+        assert_eq!(Some("address"), error.param);
+        assert!(error.reason.starts_with("invalid address: "));
+
+        let query = QueryAccount {
+            auth_addr: Some("not an address".to_owned()),
+            ..QueryAccount::default()
+        };
+        assert_eq!(Some("auth_addr"), query.validate().unwrap_err().param);
+
+        let query = QueryAssetTransaction {
+            address: Some("47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU".to_owned()),
+            ..QueryAssetTransaction::default()
+        };
+        assert_eq!(Ok(()), query.validate());
+    }
+
+    #[test]
+    fn test_account_with_omitted_fields() {
+        let account: Account = serde_json::from_str(
+            r#"{"address":"47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU","status":"Offline"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(MicroAlgos(0), account.amount);
+        assert_eq!(MicroAlgos(0), account.amount_without_pending_rewards);
+        assert_eq!(MicroAlgos(0), account.pending_rewards);
+        assert_eq!(MicroAlgos(0), account.rewards);
+        assert_eq!(Round(0), account.round);
+        assert!(account.assets.is_empty());
+        assert!(account.created_assets.is_empty());
+    }
+
+    #[test]
+    fn test_asset_with_omitted_fields() {
+        let asset: Asset = serde_json::from_str(
+            r#"{"index":10458941,"params":{"creator":"47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU"}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(0, asset.params.decimals);
+        assert_eq!(0, asset.params.total);
+        assert!(asset.params.metadata_hash.is_empty());
+
+        let holding: AssetHolding = serde_json::from_str(
+            r#"{"asset-id":10458941,"creator":"47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU"}"#,
+        )
+        .unwrap();
+        assert_eq!(0, holding.amount);
+        assert!(!holding.is_frozen);
+    }
+
+    #[test]
+    fn test_transaction_with_omitted_fields() {
+        let transaction: Transaction = serde_json::from_str(
+            r#"{
+                "genesis-hash": "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=",
+                "id": "NQKSCMZPPYRCAP3FHN6J4FKXKDJ7ZKCZW5FMUG6NHLOPJSOWO4TQ",
+                "payment-transaction": {
+                    "receiver": "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU"
+                },
+                "sender": "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU",
+                "tx-type": "pay"
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(0, transaction.fee);
+        assert_eq!(0, transaction.first_valid);
+        assert_eq!(Round(0), transaction.last_valid);
+        assert_eq!(
+            MicroAlgos(0),
+            transaction.payment_transaction.unwrap().amount
+        );
+
+        let response: TransactionResponse = serde_json::from_str("{}").unwrap();
+        assert!(response.transactions.is_empty());
+    }
+
+    const ACCOUNT: &str = "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU";
+    const OTHER: &str = "4MYUHDWHWXAKA5KA7U5PEN646VYUANBFXVJNONBK3TIMHEMWMD4UBOJBI4";
+    const THIRD: &str = "UPYAFLHSIPMJOHVXU2MPLQ46GXJKSDCEMZ6RLCQ7GWB5PRDKJUWKKXECXI";
+
+    /// A transaction of `sender` with these fields.
+    fn transaction(sender: &str, fields: serde_json::Value) -> Transaction {
+        let mut txn = serde_json::json!({
+            "fee": 1000,
+            "first-valid": 1,
+            "genesis-hash": "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=",
+            "id": "A",
+            "last-valid": 1000,
+            "sender": sender,
+        });
+        txn.as_object_mut()
+            .unwrap()
+            .extend(fields.as_object().unwrap().clone());
+        serde_json::from_value(txn).unwrap()
+    }
+
+    fn delta(algos: i128, assets: &[(u64, i128)]) -> TransactionDelta {
+        TransactionDelta {
+            algos,
+            assets: assets.iter().copied().collect(),
+        }
+    }
+
+    #[test]
+    fn test_delta_for_payment() {
+        let txn = transaction(
+            ACCOUNT,
+            serde_json::json!({
+                "payment-transaction": { "amount": 5000, "receiver": OTHER },
+                "receiver-rewards": 7,
+                "sender-rewards": 3,
+                "tx-type": "pay",
+            }),
+        );
+
+        assert_eq!(
+            delta(-5000 - 1000 + 3, &[]),
+            txn.delta_for(&ACCOUNT.parse().unwrap())
+        );
+        assert_eq!(delta(5000 + 7, &[]), txn.delta_for(&OTHER.parse().unwrap()));
+        assert_eq!(delta(0, &[]), txn.delta_for(&THIRD.parse().unwrap()));
+    }
+
+    #[test]
+    fn test_delta_for_payment_to_self() {
+        let txn = transaction(
+            ACCOUNT,
+            serde_json::json!({
+                "payment-transaction": { "amount": 5000, "receiver": ACCOUNT },
+                "sender-rewards": 3,
+                "tx-type": "pay",
+            }),
+        );
+
+        // the amount is both sent and received, the rewards are realized as the sender
+        assert_eq!(
+            delta(-1000 + 3, &[]),
+            txn.delta_for(&ACCOUNT.parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_delta_for_close_out() {
+        let txn = transaction(
+            ACCOUNT,
+            serde_json::json!({
+                "close-rewards": 2,
+                "payment-transaction": {
+                    "amount": 5000,
+                    "close-amount": 94000,
+                    "close-remainder-to": THIRD,
+                    "receiver": OTHER,
+                },
+                "tx-type": "pay",
+            }),
+        );
+
+        assert_eq!(
+            delta(-5000 - 94000 - 1000, &[]),
+            txn.delta_for(&ACCOUNT.parse().unwrap())
+        );
+        assert_eq!(delta(5000, &[]), txn.delta_for(&OTHER.parse().unwrap()));
+        assert_eq!(
+            delta(94000 + 2, &[]),
+            txn.delta_for(&THIRD.parse().unwrap())
+        );
+
+        // closing to the receiver: both amounts
+        let txn = transaction(
+            ACCOUNT,
+            serde_json::json!({
+                "closing-amount": 94000,
+                "payment-transaction": {
+                    "amount": 5000,
+                    "close-remainder-to": OTHER,
+                    "receiver": OTHER,
+                },
+                "tx-type": "pay",
+            }),
+        );
+        assert_eq!(
+            delta(5000 + 94000, &[]),
+            txn.delta_for(&OTHER.parse().unwrap())
+        );
+        assert_eq!(
+            delta(-5000 - 94000 - 1000, &[]),
+            txn.delta_for(&ACCOUNT.parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_delta_for_asset_transfer() {
+        let txn = transaction(
+            ACCOUNT,
+            serde_json::json!({
+                "asset-transfer-transaction": {
+                    "amount": 40,
+                    "asset-id": 10458941,
+                    "receiver": OTHER,
+                },
+                "tx-type": "axfer",
+            }),
+        );
+
+        assert_eq!(
+            delta(-1000, &[(10458941, -40)]),
+            txn.delta_for(&ACCOUNT.parse().unwrap())
+        );
+        assert_eq!(
+            delta(0, &[(10458941, 40)]),
+            txn.delta_for(&OTHER.parse().unwrap())
+        );
+
+        // opt-in: a transfer of 0 to self
+        let txn = transaction(
+            ACCOUNT,
+            serde_json::json!({
+                "asset-transfer-transaction": {
+                    "amount": 0,
+                    "asset-id": 10458941,
+                    "receiver": ACCOUNT,
+                },
+                "tx-type": "axfer",
+            }),
+        );
+        assert_eq!(delta(-1000, &[]), txn.delta_for(&ACCOUNT.parse().unwrap()));
+    }
+
+    #[test]
+    fn test_delta_for_asset_close_out() {
+        let txn = transaction(
+            ACCOUNT,
+            serde_json::json!({
+                "asset-transfer-transaction": {
+                    "amount": 40,
+                    "asset-id": 10458941,
+                    "close-amount": 60,
+                    "close-to": THIRD,
+                    "receiver": OTHER,
+                },
+                "tx-type": "axfer",
+            }),
+        );
+
+        assert_eq!(
+            delta(-1000, &[(10458941, -100)]),
+            txn.delta_for(&ACCOUNT.parse().unwrap())
+        );
+        assert_eq!(
+            delta(0, &[(10458941, 40)]),
+            txn.delta_for(&OTHER.parse().unwrap())
+        );
+        assert_eq!(
+            delta(0, &[(10458941, 60)]),
+            txn.delta_for(&THIRD.parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_delta_for_clawback() {
+        // the clawback account revokes the assets of OTHER, sending them to THIRD
+        let txn = transaction(
+            ACCOUNT,
+            serde_json::json!({
+                "asset-transfer-transaction": {
+                    "amount": 40,
+                    "asset-id": 10458941,
+                    "receiver": THIRD,
+                    "sender": OTHER,
+                },
+                "tx-type": "axfer",
+            }),
+        );
+
+        assert_eq!(delta(-1000, &[]), txn.delta_for(&ACCOUNT.parse().unwrap()));
+        assert_eq!(
+            delta(0, &[(10458941, -40)]),
+            txn.delta_for(&OTHER.parse().unwrap())
+        );
+        assert_eq!(
+            delta(0, &[(10458941, 40)]),
+            txn.delta_for(&THIRD.parse().unwrap())
+        );
+    }
 }
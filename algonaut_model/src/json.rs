@@ -0,0 +1,105 @@
+use serde::Serialize;
+use serde_json::Value;
+
+/// Largest integer that can be represented exactly by a JavaScript number (2^53 - 1).
+pub const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// How integers are rendered by [to_json_string].
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum NumberFormat {
+    /// Integers are rendered as JSON numbers, as returned by the APIs.
+    #[default]
+    Number,
+    /// Integers outside the JavaScript safe range (greater than [MAX_SAFE_INTEGER] in absolute value)
+    /// are rendered as strings, so JavaScript consumers don't silently lose precision.
+    /// Integers in the safe range are rendered as numbers.
+    LargeAsString,
+}
+
+/// Serializes a value (e.g. an API response) to a JSON string, rendering integers as configured.
+pub fn to_json_string<T>(value: &T, format: NumberFormat) -> Result<String, serde_json::Error>
+where
+    T: Serialize + ?Sized,
+{
+    match format {
+        NumberFormat::Number => serde_json::to_string(value),
+        NumberFormat::LargeAsString => {
+            let mut value = serde_json::to_value(value)?;
+            stringify_large_integers(&mut value);
+            serde_json::to_string(&value)
+        }
+    }
+}
+
+fn stringify_large_integers(value: &mut Value) {
+    match value {
+        Value::Number(number) => {
+            let is_large = match (number.as_u64(), number.as_i64()) {
+                (Some(n), _) => n > MAX_SAFE_INTEGER,
+                (None, Some(n)) => n.unsigned_abs() > MAX_SAFE_INTEGER,
+                // floats are left as they are
+                (None, None) => false,
+            };
+            if is_large {
+                *value = Value::String(number.to_string());
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(stringify_large_integers),
+        Value::Object(map) => map.values_mut().for_each(stringify_large_integers),
+        Value::Null | Value::Bool(_) | Value::String(_) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indexer::v2::MiniAssetHolding;
+
+    #[test]
+    fn test_large_integers_as_strings() {
+        let holding = MiniAssetHolding {
+            address: "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU".to_owned(),
+            amount: MAX_SAFE_INTEGER + 2,
+            deleted: None,
+            is_frozen: false,
+            opted_in_at_round: None,
+            opted_out_at_round: None,
+        };
+
+        let json = to_json_string(&holding, NumberFormat::LargeAsString).unwrap();
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!("9007199254740993", value["amount"]);
+
+        // the string can be parsed back without loss of precision
+        let amount: u64 = value["amount"].as_str().unwrap().parse().unwrap();
+        assert_eq!(holding.amount, amount);
+    }
+
+    #[test]
+    fn test_safe_integers_as_numbers() {
+        let value = serde_json::json!({
+            "safe": MAX_SAFE_INTEGER,
+            "negative": -(MAX_SAFE_INTEGER as i64) - 1,
+            "nested": [{ "large": u64::MAX }],
+            "float": 1.5,
+        });
+
+        let json = to_json_string(&value, NumberFormat::LargeAsString).unwrap();
+        let value: Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(MAX_SAFE_INTEGER, value["safe"]);
+        assert_eq!("-9007199254740992", value["negative"]);
+        assert_eq!("18446744073709551615", value["nested"][0]["large"]);
+        assert_eq!(1.5, value["float"]);
+    }
+
+    #[test]
+    fn test_number_format() {
+        let value = serde_json::json!({ "large": u64::MAX });
+
+        assert_eq!(
+            r#"{"large":18446744073709551615}"#,
+            to_json_string(&value, NumberFormat::Number).unwrap()
+        );
+    }
+}
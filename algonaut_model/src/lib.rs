@@ -2,5 +2,128 @@
 pub mod algod;
 /// Algorand's indexer
 pub mod indexer;
+/// JSON output of the response types
+pub mod json;
 /// Key management daemon
 pub mod kmd;
+/// Decoding of application state
+pub mod teal;
+
+use algonaut_encoding::decode_base64;
+use std::convert::TryInto;
+use std::fmt::{self, Display, Formatter};
+
+/// A field of a response that isn't validly encoded (e.g. invalid base64 or an unknown enum value).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldDecodeError {
+    /// Name of the field, as in the response (e.g. "note").
+    pub field: &'static str,
+}
+
+impl Display for FieldDecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid encoding of field: {}", self.field)
+    }
+}
+
+impl std::error::Error for FieldDecodeError {}
+
+/// A query rejected before being sent (e.g. an empty round range or a malformed address).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QueryError {
+    /// Why the query is invalid (e.g. "min_round > max_round").
+    pub reason: String,
+    /// Name of the field of the query at fault (e.g. "min_round"), if any.
+    pub param: Option<&'static str>,
+}
+
+impl Display for QueryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Bad query: {}", self.reason)
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+/// The participation of an account in consensus at a round, derived from its status and the
+/// validity of its participation keys at the round.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParticipationStatus {
+    /// Online, with participation keys valid at the round: the account votes.
+    Online,
+    /// Offline without valid participation keys, or online with participation keys expired (or
+    /// not valid yet) at the round, so the account doesn't vote.
+    Offline,
+    /// Marked as not participating: the account can't go online anymore.
+    NotParticipating,
+    /// Offline, but with participation keys still valid at the round: the account was taken
+    /// offline by the protocol (e.g. for missing its proposals or heartbeats) and can go back
+    /// online by registering its keys again.
+    Suspended,
+}
+
+/// The participation of an account with this `status` ("Online", "Offline" or "NotParticipating"),
+/// whose participation keys are valid at the round or not.
+fn participation_status(status: &str, keys_valid: bool) -> ParticipationStatus {
+    match (status, keys_valid) {
+        ("NotParticipating", _) => ParticipationStatus::NotParticipating,
+        ("Online", true) => ParticipationStatus::Online,
+        ("Offline", true) => ParticipationStatus::Suspended,
+        _ => ParticipationStatus::Offline,
+    }
+}
+
+/// The key of these bytes, if it has the size of the key (i.e. it's not missing).
+fn typed_key<const N: usize, K>(bytes: &[u8], key: fn([u8; N]) -> K) -> Option<K> {
+    bytes.try_into().ok().map(key)
+}
+
+/// Decodes the base64 value of `field`.
+fn decode_base64_field(value: &str, field: &'static str) -> Result<Vec<u8>, FieldDecodeError> {
+    decode_base64(value.as_bytes()).map_err(|_| FieldDecodeError { field })
+}
+
+/// Decodes the optional base64 value of `field`.
+fn decode_optional_base64_field(
+    value: &Option<String>,
+    field: &'static str,
+) -> Result<Option<Vec<u8>>, FieldDecodeError> {
+    value
+        .as_deref()
+        .map(|value| decode_base64_field(value, field))
+        .transpose()
+}
+
+/// Decodes the block hash value of `field`: base32 as returned by algod, optionally prefixed with
+/// `blk-`, or base64.
+fn decode_block_hash_field(
+    value: &str,
+    field: &'static str,
+) -> Result<algonaut_crypto::HashDigest, FieldDecodeError> {
+    let base32 = value.strip_prefix("blk-").unwrap_or(value);
+    let bytes = data_encoding::BASE32_NOPAD
+        .decode(base32.as_bytes())
+        .or_else(|_| decode_base64(value.as_bytes()))
+        .map_err(|_| FieldDecodeError { field })?;
+    let hash = bytes.try_into().map_err(|_| FieldDecodeError { field })?;
+    Ok(algonaut_crypto::HashDigest(hash))
+}
+
+/// An amount of an asset with these decimals, as in the asset params.
+fn asset_amount(raw: u64, decimals: u64) -> algonaut_core::AssetAmount {
+    // the protocol allows at most 19 decimals
+    let decimals = decimals.min(algonaut_core::MAX_ASSET_DECIMALS as u64) as u32;
+    algonaut_core::AssetAmount::new(raw, decimals)
+}
+
+/// Prefix of the log containing the return value of an ARC-4 method call.
+pub const ARC4_RETURN_PREFIX: [u8; 4] = [0x15, 0x1f, 0x7c, 0x75];
+
+/// Finds the ARC-4 return value in the logs of an application call: the last log, without the
+/// prefix, if it starts with [ARC4_RETURN_PREFIX]. An earlier log with the prefix isn't a return
+/// value.
+fn arc4_return_value(logs: &[Vec<u8>]) -> Option<&[u8]> {
+    logs.last()
+        .filter(|log| log.starts_with(&ARC4_RETURN_PREFIX))
+        .map(|log| &log[ARC4_RETURN_PREFIX.len()..])
+}
@@ -0,0 +1,389 @@
+use crate::{algod, indexer, FieldDecodeError};
+use algonaut_core::Address;
+use std::collections::BTreeMap;
+use std::convert::{TryFrom, TryInto};
+
+/// `tt` of a bytes value.
+const TEAL_BYTES_TYPE: u64 = 1;
+/// `tt` of a uint value.
+const TEAL_UINT_TYPE: u64 = 2;
+
+/// `at` of a delta setting a bytes value.
+const DELTA_SET_BYTES_ACTION: u64 = 1;
+/// `at` of a delta setting a uint value.
+const DELTA_SET_UINT_ACTION: u64 = 2;
+/// `at` of a delta deleting a key.
+const DELTA_DELETE_ACTION: u64 = 3;
+
+/// A decoded TEAL value.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TealValue {
+    Bytes(Vec<u8>),
+    Uint(u64),
+}
+
+/// Decoded application global state or account local state.
+///
+/// Keys are stored as bytes: keys that aren't valid UTF-8 can be accessed passing the raw bytes to
+/// the getters, which accept both `&str` and `&[u8]` keys.
+///
+/// Built from the key-value lists of the algod or indexer responses, e.g.
+/// `TealKeyValueStore::try_from(params.global_state.as_slice())`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TealKeyValueStore(BTreeMap<Vec<u8>, TealValue>);
+
+impl TealKeyValueStore {
+    /// The value stored at `key`.
+    pub fn get(&self, key: impl AsRef<[u8]>) -> Option<&TealValue> {
+        self.0.get(key.as_ref())
+    }
+
+    /// The uint stored at `key`, if any and if the value is a uint.
+    pub fn get_uint(&self, key: impl AsRef<[u8]>) -> Option<u64> {
+        match self.get(key) {
+            Some(TealValue::Uint(uint)) => Some(*uint),
+            _ => None,
+        }
+    }
+
+    /// The bytes stored at `key`, if any and if the value is a byte slice.
+    pub fn get_bytes(&self, key: impl AsRef<[u8]>) -> Option<Vec<u8>> {
+        match self.get(key) {
+            Some(TealValue::Bytes(bytes)) => Some(bytes.clone()),
+            _ => None,
+        }
+    }
+
+    /// The address stored at `key`, if any and if the value is a 32 bytes slice.
+    pub fn get_address(&self, key: impl AsRef<[u8]>) -> Option<Address> {
+        match self.get(key) {
+            Some(TealValue::Bytes(bytes)) => bytes.as_slice().try_into().ok().map(Address::new),
+            _ => None,
+        }
+    }
+
+    /// Iterates over the decoded keys and values, sorted by key.
+    pub fn iter(&self) -> impl Iterator<Item = (&Vec<u8>, &TealValue)> {
+        self.0.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Applies the changes of `deltas`, in order.
+    pub fn apply_deltas<'a>(&mut self, deltas: impl IntoIterator<Item = &'a TealKeyDelta>) {
+        for delta in deltas {
+            match &delta.action {
+                TealDeltaAction::Set(value) => {
+                    self.0.insert(delta.key.clone(), value.clone());
+                }
+                TealDeltaAction::Delete => {
+                    self.0.remove(&delta.key);
+                }
+            }
+        }
+    }
+
+    /// The state resulting from the changes of `deltas`, in order, starting from an empty state.
+    ///
+    /// Passing the global state deltas of all the calls of an application since its creation, in
+    /// the order they were confirmed, yields its current global state.
+    pub fn from_deltas<'a>(
+        deltas: impl IntoIterator<Item = &'a TealKeyDelta>,
+    ) -> TealKeyValueStore {
+        let mut store = TealKeyValueStore::default();
+        store.apply_deltas(deltas);
+        store
+    }
+
+    fn decode<'a>(
+        key_values: impl IntoIterator<Item = (&'a str, u64, &'a [u8], u64)>,
+    ) -> Result<TealKeyValueStore, String> {
+        key_values
+            .into_iter()
+            .map(|(key, value_type, bytes, uint)| {
+                let key = crate::decode_base64_field(key, "key").map_err(|e| e.to_string())?;
+                let value = match value_type {
+                    TEAL_BYTES_TYPE => TealValue::Bytes(bytes.to_vec()),
+                    TEAL_UINT_TYPE => TealValue::Uint(uint),
+                    _ => return Err(format!("Invalid TEAL value type: {}", value_type)),
+                };
+                Ok((key, value))
+            })
+            .collect::<Result<_, _>>()
+            .map(TealKeyValueStore)
+    }
+}
+
+/// A decoded change of a key of an application's global state or of an account's local state,
+/// made by an application call.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TealKeyDelta {
+    pub key: Vec<u8>,
+    pub action: TealDeltaAction,
+}
+
+/// What an application call did to a key of the state.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TealDeltaAction {
+    /// The key was set to the value.
+    Set(TealValue),
+    /// The key was deleted.
+    Delete,
+}
+
+impl TealKeyDelta {
+    fn decode(
+        key: &str,
+        action: u64,
+        bytes: &Option<String>,
+        uint: Option<u64>,
+    ) -> Result<TealKeyDelta, FieldDecodeError> {
+        let action = match action {
+            DELTA_SET_BYTES_ACTION => TealDeltaAction::Set(TealValue::Bytes(
+                crate::decode_optional_base64_field(bytes, "bytes")?.unwrap_or_default(),
+            )),
+            DELTA_SET_UINT_ACTION => TealDeltaAction::Set(TealValue::Uint(uint.unwrap_or(0))),
+            DELTA_DELETE_ACTION => TealDeltaAction::Delete,
+            _ => return Err(FieldDecodeError { field: "action" }),
+        };
+        Ok(TealKeyDelta {
+            key: crate::decode_base64_field(key, "key")?,
+            action,
+        })
+    }
+}
+
+impl TryFrom<&algod::v2::EvalDeltaKeyValue> for TealKeyDelta {
+    type Error = FieldDecodeError;
+
+    fn try_from(key_value: &algod::v2::EvalDeltaKeyValue) -> Result<Self, Self::Error> {
+        Self::decode(
+            &key_value.key,
+            key_value.value.action,
+            &key_value.value.bytes,
+            key_value.value.uint,
+        )
+    }
+}
+
+impl TryFrom<&indexer::v2::EvalDeltaKeyValue> for TealKeyDelta {
+    type Error = FieldDecodeError;
+
+    fn try_from(key_value: &indexer::v2::EvalDeltaKeyValue) -> Result<Self, Self::Error> {
+        Self::decode(
+            &key_value.key,
+            key_value.value.action,
+            &key_value.value.bytes,
+            key_value.value.uint,
+        )
+    }
+}
+
+impl IntoIterator for TealKeyValueStore {
+    type Item = (Vec<u8>, TealValue);
+    type IntoIter = std::collections::btree_map::IntoIter<Vec<u8>, TealValue>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl TryFrom<&[algod::v2::TealKeyValue]> for TealKeyValueStore {
+    type Error = String;
+
+    fn try_from(key_values: &[algod::v2::TealKeyValue]) -> Result<Self, Self::Error> {
+        Self::decode(key_values.iter().map(|kv| {
+            (
+                kv.key.as_str(),
+                kv.value.value_type,
+                kv.value.bytes.as_slice(),
+                kv.value.uint,
+            )
+        }))
+    }
+}
+
+impl TryFrom<&[indexer::v2::TealKeyValue]> for TealKeyValueStore {
+    type Error = String;
+
+    fn try_from(key_values: &[indexer::v2::TealKeyValue]) -> Result<Self, Self::Error> {
+        Self::decode(key_values.iter().map(|kv| {
+            (
+                kv.key.as_str(),
+                kv.value.value_type,
+                kv.value.bytes.as_slice(),
+                kv.value.uint,
+            )
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Global state of an application, as returned by algod and the indexer.
+    // Keys: "counter" (uint), "name" (bytes), "owner" (address), 0xff 0xfe (non UTF-8, uint).
+    const GLOBAL_STATE: &str = r#"[
+        {
+            "key": "Y291bnRlcg==",
+            "value": { "bytes": "", "type": 2, "uint": 42 }
+        },
+        {
+            "key": "bmFtZQ==",
+            "value": { "bytes": "YWxnb25hdXQ=", "type": 1, "uint": 0 }
+        },
+        {
+            "key": "b3duZXI=",
+            "value": {
+                "bytes": "5/D4TQaBHfnzHI2HixFV9GcdUaGFwgCQhmf0SVhwaKE=",
+                "type": 1,
+                "uint": 0
+            }
+        },
+        {
+            "key": "//4=",
+            "value": { "bytes": "", "type": 2, "uint": 7 }
+        }
+    ]"#;
+
+    fn assert_global_state(store: &TealKeyValueStore) {
+        assert_eq!(4, store.len());
+        assert_eq!(Some(42), store.get_uint("counter"));
+        assert_eq!(Some(b"algonaut".to_vec()), store.get_bytes("name"));
+        assert_eq!(
+            Some(
+                "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU"
+                    .parse::<Address>()
+                    .unwrap()
+            ),
+            store.get_address("owner")
+        );
+        assert_eq!(Some(7), store.get_uint([0xff, 0xfe]));
+
+        // wrong types
+        assert_eq!(None, store.get_bytes("counter"));
+        assert_eq!(None, store.get_uint("name"));
+        assert_eq!(None, store.get_address("name"));
+        assert_eq!(None, store.get_uint("missing"));
+    }
+
+    #[test]
+    fn test_from_algod_state() {
+        let key_values: Vec<algod::v2::TealKeyValue> = serde_json::from_str(GLOBAL_STATE).unwrap();
+        let store = TealKeyValueStore::try_from(key_values.as_slice()).unwrap();
+        assert_global_state(&store);
+    }
+
+    #[test]
+    fn test_from_indexer_state() {
+        let key_values: Vec<indexer::v2::TealKeyValue> =
+            serde_json::from_str(GLOBAL_STATE).unwrap();
+        let store = TealKeyValueStore::try_from(key_values.as_slice()).unwrap();
+        assert_global_state(&store);
+    }
+
+    #[test]
+    fn test_iteration() {
+        let key_values: Vec<algod::v2::TealKeyValue> = serde_json::from_str(GLOBAL_STATE).unwrap();
+        let store = TealKeyValueStore::try_from(key_values.as_slice()).unwrap();
+
+        let keys: Vec<Vec<u8>> = store.iter().map(|(key, _)| key.clone()).collect();
+        assert_eq!(
+            vec![
+                b"counter".to_vec(),
+                b"name".to_vec(),
+                b"owner".to_vec(),
+                vec![0xff, 0xfe]
+            ],
+            keys
+        );
+        assert!(store
+            .into_iter()
+            .any(|kv| kv == (b"counter".to_vec(), TealValue::Uint(42))));
+    }
+
+    #[test]
+    fn test_invalid_value_type() {
+        let key_values: Vec<algod::v2::TealKeyValue> = serde_json::from_str(
+            r#"[{ "key": "Y291bnRlcg==", "value": { "bytes": "", "type": 3, "uint": 42 } }]"#,
+        )
+        .unwrap();
+        assert!(TealKeyValueStore::try_from(key_values.as_slice()).is_err());
+    }
+
+    #[test]
+    fn test_decode_deltas() {
+        let deltas: Vec<algod::v2::EvalDeltaKeyValue> = serde_json::from_str(
+            r#"[
+                { "key": "Y291bnRlcg==", "value": { "action": 2, "uint": 1 } },
+                { "key": "bmFtZQ==", "value": { "action": 1, "bytes": "YWxnb25hdXQ=" } },
+                { "key": "b3duZXI=", "value": { "action": 3 } }
+            ]"#,
+        )
+        .unwrap();
+        let deltas: Vec<TealKeyDelta> = deltas
+            .iter()
+            .map(TealKeyDelta::try_from)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            vec![
+                TealKeyDelta {
+                    key: b"counter".to_vec(),
+                    action: TealDeltaAction::Set(TealValue::Uint(1)),
+                },
+                TealKeyDelta {
+                    key: b"name".to_vec(),
+                    action: TealDeltaAction::Set(TealValue::Bytes(b"algonaut".to_vec())),
+                },
+                TealKeyDelta {
+                    key: b"owner".to_vec(),
+                    action: TealDeltaAction::Delete,
+                },
+            ],
+            deltas
+        );
+
+        let invalid: indexer::v2::EvalDeltaKeyValue =
+            serde_json::from_str(r#"{ "key": "Y291bnRlcg==", "value": { "action": 4 } }"#).unwrap();
+        assert_eq!(
+            Err(FieldDecodeError { field: "action" }),
+            TealKeyDelta::try_from(&invalid)
+        );
+    }
+
+    #[test]
+    fn test_fold_deltas() {
+        let set = |key: &str, value: TealValue| TealKeyDelta {
+            key: key.as_bytes().to_vec(),
+            action: TealDeltaAction::Set(value),
+        };
+        let delete = |key: &str| TealKeyDelta {
+            key: key.as_bytes().to_vec(),
+            action: TealDeltaAction::Delete,
+        };
+        let deltas = vec![
+            set("counter", TealValue::Uint(1)),
+            set("name", TealValue::Bytes(b"algonaut".to_vec())),
+            set("counter", TealValue::Uint(2)),
+            delete("name"),
+            delete("missing"),
+        ];
+
+        let store = TealKeyValueStore::from_deltas(&deltas);
+        assert_eq!(1, store.len());
+        assert_eq!(Some(2), store.get_uint("counter"));
+        assert_eq!(None, store.get("name"));
+
+        let mut store = store;
+        store.apply_deltas(&[set("name", TealValue::Bytes(vec![]))]);
+        assert_eq!(Some(vec![]), store.get_bytes("name"));
+    }
+}
@@ -10,11 +10,13 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     error::TransactionError,
+    heartbeat::HeartbeatProof,
+    state_proof::{StateProof, StateProofMessage},
     transaction::{
         ApplicationCallOnComplete, ApplicationCallTransaction, AssetAcceptTransaction,
         AssetClawbackTransaction, AssetConfigurationTransaction, AssetFreezeTransaction,
-        AssetParams, AssetTransferTransaction, KeyRegistration, Payment, StateSchema,
-        TransactionSignature,
+        AssetParams, AssetTransferTransaction, BoxReference, HeartbeatTransaction, KeyRegistration,
+        Payment, StateProofTransaction, StateSchema, TransactionSignature, UnknownTransaction,
     },
     tx_group::TxGroup,
     SignedTransaction, Transaction, TransactionType,
@@ -70,6 +72,9 @@ pub struct ApiTransaction {
     #[serde(rename = "apat", skip_serializing_if = "Option::is_none")]
     pub accounts: Option<Vec<Address>>,
 
+    #[serde(rename = "apbx", skip_serializing_if = "Option::is_none")]
+    pub boxes: Option<Vec<ApiBoxReference>>,
+
     #[serde(rename = "apep", skip_serializing_if = "Option::is_none")]
     pub extra_pages: Option<u64>,
 
@@ -126,6 +131,9 @@ pub struct ApiTransaction {
     #[serde(rename = "grp", skip_serializing_if = "Option::is_none")]
     pub group: Option<HashDigest>,
 
+    #[serde(rename = "hb", skip_serializing_if = "Option::is_none")]
+    pub heartbeat: Option<ApiHeartbeat>,
+
     #[serde(rename = "lv", skip_serializing_if = "Option::is_none")]
     pub last_valid: Option<Round>, // optional for serialization zero value (technically possible) omission
 
@@ -155,6 +163,15 @@ pub struct ApiTransaction {
     #[serde(rename = "snd")]
     pub sender: Address,
 
+    #[serde(rename = "sp", skip_serializing_if = "Option::is_none")]
+    pub state_proof: Option<StateProof>,
+
+    #[serde(rename = "spmsg", skip_serializing_if = "Option::is_none")]
+    pub state_proof_message: Option<StateProofMessage>,
+
+    #[serde(rename = "sptype", skip_serializing_if = "Option::is_none")]
+    pub state_proof_type: Option<u64>,
+
     #[serde(rename = "type")]
     pub type_: String,
 
@@ -203,6 +220,7 @@ impl From<Transaction> for ApiTransaction {
             asset_params: None,
             foreign_assets: None,
             accounts: None,
+            boxes: None,
             foreign_apps: None,
             global_state_schema: None,
             app_id: None,
@@ -223,6 +241,10 @@ impl From<Transaction> for ApiTransaction {
             xfer: None,
             nonparticipating: None,
             extra_pages: None,
+            state_proof: None,
+            state_proof_message: None,
+            state_proof_type: None,
+            heartbeat: None,
         };
 
         match &t.txn_type {
@@ -287,12 +309,27 @@ impl From<Transaction> for ApiTransaction {
                     .and_then(vec_as_api_option);
                 api_t.foreign_apps = call.foreign_apps.clone().and_then(vec_as_api_option);
                 api_t.foreign_assets = call.foreign_assets.clone().and_then(vec_as_api_option);
+                api_t.boxes = call
+                    .boxes
+                    .to_owned()
+                    .map(|boxes| boxes.into_iter().map(Into::into).collect())
+                    .and_then(vec_as_api_option);
                 api_t.global_state_schema =
                     call.to_owned().global_state_schema.and_then(|s| s.into());
                 api_t.local_state_schema =
                     call.to_owned().local_state_schema.and_then(|s| s.into());
                 api_t.extra_pages = num_as_api_option(call.extra_pages);
             }
+            TransactionType::StateProofTransaction(state_proof) => {
+                api_t.state_proof = Some(state_proof.state_proof.clone());
+                api_t.state_proof_message = Some(state_proof.message.clone());
+                api_t.state_proof_type = num_as_api_option(state_proof.state_proof_type);
+            }
+            TransactionType::HeartbeatTransaction(heartbeat) => {
+                api_t.heartbeat = Some(heartbeat.clone().into());
+            }
+            // Only the type is known: see [UnknownTransaction].
+            TransactionType::Unknown(_) => {}
         }
         api_t
     }
@@ -354,6 +391,9 @@ impl TryFrom<ApiTransaction> for Transaction {
                     clear_state_program: api_t.clear_state_program.map(CompiledTeal),
                     foreign_apps: api_t.foreign_apps,
                     foreign_assets: api_t.foreign_assets,
+                    boxes: api_t
+                        .boxes
+                        .map(|boxes| boxes.into_iter().map(Into::into).collect()),
 
                     global_state_schema: parse_state_schema(
                         on_complete.clone(),
@@ -369,13 +409,29 @@ impl TryFrom<ApiTransaction> for Transaction {
                     extra_pages: num_from_api_option(api_t.extra_pages),
                 })
             }
-
-            unsupported_type => {
-                return Err(TransactionError::Deserialization(format!(
-                    "Not supported transaction type: {}",
-                    unsupported_type
-                )))
+            "stpf" => TransactionType::StateProofTransaction(StateProofTransaction {
+                sender: api_t.sender,
+                state_proof_type: num_from_api_option(api_t.state_proof_type),
+                message: api_t.state_proof_message.unwrap_or_default(),
+                state_proof: api_t.state_proof.unwrap_or_default(),
+            }),
+            "hb" => {
+                let heartbeat = api_t.heartbeat.ok_or_else(|| {
+                    TransactionError::Deserialization("heartbeat fields missing".to_owned())
+                })?;
+                TransactionType::HeartbeatTransaction(HeartbeatTransaction {
+                    sender: api_t.sender,
+                    address: heartbeat.address,
+                    proof: heartbeat.proof.unwrap_or_default(),
+                    seed: heartbeat.seed.unwrap_or_default(),
+                    vote_id: heartbeat.vote_id.unwrap_or_default(),
+                    key_dilution: num_from_api_option(heartbeat.key_dilution),
+                })
             }
+            unknown_type => TransactionType::Unknown(UnknownTransaction {
+                sender: api_t.sender,
+                txn_type: unknown_type.to_owned(),
+            }),
         };
         Ok(Transaction {
             fee: MicroAlgos(num_from_api_option(api_t.fee.map(|f| f.0))),
@@ -483,7 +539,12 @@ fn transaction_signature(
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 pub struct ApiAssetParams {
-    #[serde(rename = "am", skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        rename = "am",
+        with = "serde_bytes",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub meta_data_hash: Option<Vec<u8>>,
 
     #[serde(rename = "an", skip_serializing_if = "Option::is_none")]
@@ -553,7 +614,7 @@ impl From<ApiAssetParams> for AssetParams {
     }
 }
 
-fn to_api_transaction_type<'a>(type_: &TransactionType) -> &'a str {
+fn to_api_transaction_type(type_: &TransactionType) -> &str {
     match type_ {
         TransactionType::Payment(_) => "pay",
         TransactionType::KeyRegistration(_) => "keyreg",
@@ -563,6 +624,9 @@ fn to_api_transaction_type<'a>(type_: &TransactionType) -> &'a str {
         TransactionType::AssetClawbackTransaction(_) => "axfer",
         TransactionType::AssetFreezeTransaction(_) => "afrz",
         TransactionType::ApplicationCallTransaction(_) => "appl",
+        TransactionType::StateProofTransaction(_) => "stpf",
+        TransactionType::HeartbeatTransaction(_) => "hb",
+        TransactionType::Unknown(unknown) => &unknown.txn_type,
     }
 }
 
@@ -601,6 +665,78 @@ impl From<SignedTransaction> for ApiSignedTransaction {
     }
 }
 
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ApiHeartbeat {
+    #[serde(rename = "a")]
+    pub address: Address,
+
+    #[serde(rename = "kd", skip_serializing_if = "Option::is_none")]
+    pub key_dilution: Option<u64>,
+
+    #[serde(rename = "prf", skip_serializing_if = "Option::is_none")]
+    pub proof: Option<HeartbeatProof>,
+
+    #[serde(
+        default,
+        rename = "sd",
+        with = "serde_bytes",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub seed: Option<Vec<u8>>,
+
+    #[serde(
+        default,
+        rename = "vid",
+        with = "serde_bytes",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub vote_id: Option<Vec<u8>>,
+}
+
+impl From<HeartbeatTransaction> for ApiHeartbeat {
+    fn from(heartbeat: HeartbeatTransaction) -> Self {
+        ApiHeartbeat {
+            address: heartbeat.address,
+            key_dilution: num_as_api_option(heartbeat.key_dilution),
+            proof: Some(heartbeat.proof).filter(|proof| !proof.is_empty()),
+            seed: Some(heartbeat.seed).filter(|seed| !seed.is_empty()),
+            vote_id: Some(heartbeat.vote_id).filter(|vote_id| !vote_id.is_empty()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ApiBoxReference {
+    #[serde(rename = "i", skip_serializing_if = "Option::is_none")]
+    pub app_index: Option<u64>,
+
+    #[serde(
+        default,
+        rename = "n",
+        with = "serde_bytes",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub name: Option<Vec<u8>>,
+}
+
+impl From<BoxReference> for ApiBoxReference {
+    fn from(reference: BoxReference) -> Self {
+        ApiBoxReference {
+            app_index: num_as_api_option(reference.app_index),
+            name: vec_as_api_option(reference.name),
+        }
+    }
+}
+
+impl From<ApiBoxReference> for BoxReference {
+    fn from(reference: ApiBoxReference) -> Self {
+        BoxReference {
+            app_index: num_from_api_option(reference.app_index),
+            name: reference.name.unwrap_or_default(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ApiStateSchema {
     #[serde(rename = "nbs", skip_serializing_if = "Option::is_none")]
@@ -815,6 +951,11 @@ fn int_to_application_call_on_complete(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::state_proof::{
+        FalconVerifier, HashFactory, MerkleArrayProof, MerkleSignature, Participant,
+        ParticipantVerifier, Reveal, SigSlot,
+    };
+    use std::collections::BTreeMap;
 
     #[test]
     fn test_serialize_signed_logic_contract_account() {
@@ -859,4 +1000,174 @@ mod tests {
 
         assert_eq!(lsig, lsig_deserialized);
     }
+
+    fn state_proof_transaction() -> Transaction {
+        let mut reveals = BTreeMap::new();
+        reveals.insert(
+            43102,
+            Reveal {
+                participant: Participant {
+                    verifier: ParticipantVerifier {
+                        commitment: vec![1; 64],
+                        key_lifetime: 256,
+                    },
+                    weight: 2000036030000,
+                },
+                sig_slot: SigSlot {
+                    lower_sig_weight: 1993741911018221,
+                    signature: MerkleSignature {
+                        vector_commitment_index: 1094,
+                        proof: MerkleArrayProof {
+                            hash_factory: HashFactory { hash_type: 1 },
+                            path: vec![vec![2; 32]],
+                            tree_depth: 16,
+                        },
+                        signature: vec![3; 40],
+                        verifying_key: FalconVerifier {
+                            public_key: vec![4; 1793],
+                        },
+                    },
+                },
+            },
+        );
+        Transaction {
+            fee: MicroAlgos(0),
+            first_valid: Round(24192138),
+            genesis_hash: "wGHE2Pwdvd7S12BL5FaOP20EGYesN73ktiC1qzkkit8="
+                .parse()
+                .unwrap(),
+            last_valid: Round(24193138),
+            txn_type: TransactionType::StateProofTransaction(StateProofTransaction {
+                sender: "XM6FEYVJ2XDU2IBH4OT6VZGW75YM63CM4TC6AV6BD3JZXFJUIICYTVB5EU"
+                    .parse()
+                    .unwrap(),
+                state_proof_type: 0,
+                message: StateProofMessage {
+                    ln_proven_weight: 2334366,
+                    block_headers_commitment: vec![5; 32],
+                    first_attested_round: Round(24191745),
+                    last_attested_round: Round(24192000),
+                    voters_commitment: vec![6; 64],
+                },
+                state_proof: StateProof {
+                    part_proofs: MerkleArrayProof {
+                        hash_factory: HashFactory { hash_type: 1 },
+                        path: vec![vec![7; 32], vec![8; 32]],
+                        tree_depth: 17,
+                    },
+                    sig_proofs: MerkleArrayProof {
+                        hash_factory: HashFactory { hash_type: 1 },
+                        path: vec![vec![9; 32]],
+                        tree_depth: 20,
+                    },
+                    sig_commit: vec![10; 64],
+                    positions_to_reveal: vec![43102, 43102],
+                    reveals,
+                    merkle_signature_salt_version: 0,
+                    signed_weight: 2381227096537718,
+                },
+            }),
+            genesis_id: None,
+            group: None,
+            lease: None,
+            note: None,
+            rekey_to: None,
+        }
+    }
+
+    #[test]
+    fn test_serialize_state_proof_transaction() {
+        let txn = state_proof_transaction();
+
+        let api_txn: ApiTransaction = txn.clone().into();
+        let serialized = rmp_serde::to_vec_named(&api_txn).unwrap();
+        let deserialized: ApiTransaction = rmp_serde::from_slice(&serialized).unwrap();
+        // zero values are omitted
+        assert_eq!(None, deserialized.state_proof_type);
+        let txn_deserialized: Transaction = deserialized.try_into().unwrap();
+
+        assert_eq!(txn, txn_deserialized);
+        assert_eq!(txn.id().unwrap(), txn_deserialized.id().unwrap());
+    }
+
+    fn heartbeat_transaction() -> Transaction {
+        Transaction {
+            fee: MicroAlgos(0),
+            first_valid: Round(46515249),
+            genesis_hash: "wGHE2Pwdvd7S12BL5FaOP20EGYesN73ktiC1qzkkit8="
+                .parse()
+                .unwrap(),
+            last_valid: Round(46515259),
+            txn_type: TransactionType::HeartbeatTransaction(HeartbeatTransaction {
+                sender: "XM6FEYVJ2XDU2IBH4OT6VZGW75YM63CM4TC6AV6BD3JZXFJUIICYTVB5EU"
+                    .parse()
+                    .unwrap(),
+                address: "AIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBMXPWWNQ"
+                    .parse()
+                    .unwrap(),
+                proof: HeartbeatProof {
+                    public_key: vec![1; 32],
+                    public_key_1_signature: vec![2; 64],
+                    public_key_2: vec![3; 32],
+                    public_key_2_signature: vec![4; 64],
+                    signature: vec![5; 64],
+                },
+                seed: vec![6; 32],
+                vote_id: vec![7; 32],
+                key_dilution: 1733,
+            }),
+            genesis_id: None,
+            group: None,
+            lease: None,
+            note: None,
+            rekey_to: None,
+        }
+    }
+
+    #[test]
+    fn test_serialize_heartbeat_transaction() {
+        let txn = heartbeat_transaction();
+
+        let api_txn: ApiTransaction = txn.clone().into();
+        assert_eq!("hb", api_txn.type_);
+        let serialized = rmp_serde::to_vec_named(&api_txn).unwrap();
+        let deserialized: ApiTransaction = rmp_serde::from_slice(&serialized).unwrap();
+        let txn_deserialized: Transaction = deserialized.try_into().unwrap();
+
+        assert_eq!(txn, txn_deserialized);
+        assert_eq!(txn.id().unwrap(), txn_deserialized.id().unwrap());
+    }
+
+    #[test]
+    fn test_deserialize_heartbeat_without_fields() {
+        let mut api_txn: ApiTransaction = heartbeat_transaction().into();
+        api_txn.heartbeat = None;
+        let serialized = rmp_serde::to_vec_named(&api_txn).unwrap();
+
+        let deserialized: ApiTransaction = rmp_serde::from_slice(&serialized).unwrap();
+        let result: Result<Transaction, _> = deserialized.try_into();
+
+        assert!(matches!(result, Err(TransactionError::Deserialization(_))));
+    }
+
+    #[test]
+    fn test_deserialize_unknown_transaction_type() {
+        let mut api_txn: ApiTransaction = state_proof_transaction().into();
+        api_txn.type_ = "xyz".to_owned();
+        api_txn.state_proof = None;
+        api_txn.state_proof_message = None;
+        let serialized = rmp_serde::to_vec_named(&api_txn).unwrap();
+
+        let deserialized: ApiTransaction = rmp_serde::from_slice(&serialized).unwrap();
+        let txn: Transaction = deserialized.try_into().unwrap();
+
+        assert_eq!(
+            TransactionType::Unknown(UnknownTransaction {
+                sender: api_txn.sender,
+                txn_type: "xyz".to_owned(),
+            }),
+            txn.txn_type
+        );
+        assert_eq!(Round(24192138), txn.first_valid);
+    }
 }
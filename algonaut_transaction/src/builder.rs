@@ -3,14 +3,16 @@ use crate::{
     transaction::{
         ApplicationCallOnComplete, ApplicationCallTransaction, AssetAcceptTransaction,
         AssetClawbackTransaction, AssetConfigurationTransaction, AssetFreezeTransaction,
-        AssetParams, AssetTransferTransaction, KeyRegistration, Payment, StateSchema, Transaction,
-        TransactionType,
+        AssetParams, AssetTransferTransaction, BoxReference, KeyRegistration, Payment, StateSchema,
+        Transaction, TransactionType,
     },
 };
 use algonaut_core::{
-    Address, CompiledTeal, MicroAlgos, Round, SuggestedTransactionParams, VotePk, VrfPk,
+    Address, CompiledTeal, MicroAlgos, Round, RoundRange, SuggestedTransactionParams, VotePk, VrfPk,
 };
 use algonaut_crypto::HashDigest;
+use serde::Serialize;
+use sha2::Digest;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TxnFee {
@@ -30,22 +32,54 @@ impl TxnFee {
     }
 }
 
+pub use algonaut_core::MAX_VALIDITY_WINDOW;
+
+/// The maximum length in bytes of the name of an asset (`MaxAssetNameBytes`).
+pub const MAX_ASSET_NAME_BYTES: usize = 32;
+
+/// The maximum length in bytes of the unit name of an asset (`MaxAssetUnitNameBytes`).
+pub const MAX_ASSET_UNIT_NAME_BYTES: usize = 8;
+
+/// The maximum length in bytes of the URL of an asset (`MaxAssetURLBytes`).
+pub const MAX_ASSET_URL_BYTES: usize = 96;
+
+/// The maximum number of decimals of an asset (`MaxAssetDecimals`).
+pub const MAX_ASSET_DECIMALS: u32 = 19;
+
+/// The length in bytes of the metadata hash of an asset.
+pub const ASSET_METADATA_HASH_BYTES: usize = 32;
+
+/// The maximum length in bytes of the note of a transaction (`MaxTxnNoteBytes`).
+pub const MAX_NOTE_BYTES: usize = 1024;
+
+/// The maximum number of arguments of an application call (`MaxAppArgs`).
+pub const MAX_APP_ARGS: usize = 16;
+
+/// The maximum combined length in bytes of the arguments of an application call
+/// (`MaxAppTotalArgLen`).
+pub const MAX_APP_TOTAL_ARG_BYTES: usize = 2048;
+
 /// A builder for [Transaction].
+#[derive(Clone)]
 pub struct TxnBuilder {
     fee: TxnFee,
     first_valid: Round,
     genesis_hash: HashDigest,
     last_valid: Round,
+    validity_window: Option<u64>,
     txn_type: TransactionType,
     genesis_id: Option<String>,
     group: Option<HashDigest>,
     lease: Option<HashDigest>,
     note: Option<Vec<u8>>,
+    /// The error of the encoding of the note, reported by [build](Self::build).
+    note_error: Option<String>,
     rekey_to: Option<Address>,
 }
 
 impl TxnBuilder {
     /// Convenience to initialize builder with suggested transaction params
+    /// The validity is the one of the params, which can be shortened with [validity_window](Self::validity_window).
     /// The txn fee is estimated, based on params. To set the fee manually, use [with_fee](Self::with_fee) or [new](Self::new).
     pub fn with(params: &SuggestedTransactionParams, txn_type: TransactionType) -> Self {
         Self::with_fee(
@@ -60,42 +94,66 @@ impl TxnBuilder {
 
     /// Convenience to initialize builder with suggested transaction params, and set the fee manually (ignoring the fee fields in params).
     /// Useful e.g. in txns groups where one txn pays the fee for others.
+    /// The genesis id of the params is omitted if empty, as the protocol allows.
     pub fn with_fee(
         params: &SuggestedTransactionParams,
         fee: TxnFee,
         txn_type: TransactionType,
     ) -> Self {
-        Self::new(
-            fee,
-            params.first_valid,
-            params.last_valid,
-            params.genesis_hash,
-            txn_type,
-        )
-        .genesis_id(params.genesis_id.clone())
+        TxnBuilder {
+            genesis_id: params.genesis_id().map(str::to_owned),
+            ..Self::new(
+                fee,
+                params.first_valid,
+                params.last_valid,
+                params.genesis_hash,
+                txn_type,
+            )
+        }
     }
 
     pub fn new(
         fee: TxnFee,
-        first_valid: Round,
-        last_valid: Round,
+        first_valid: impl Into<Round>,
+        last_valid: impl Into<Round>,
         genesis_hash: HashDigest,
         txn_type: TransactionType,
     ) -> Self {
         TxnBuilder {
             fee,
-            first_valid,
+            first_valid: first_valid.into(),
             genesis_hash,
-            last_valid,
+            last_valid: last_valid.into(),
+            validity_window: None,
             txn_type,
             genesis_id: None,
             group: None,
             lease: None,
             note: None,
+            note_error: None,
             rekey_to: None,
         }
     }
 
+    /// Sets the rounds in which the transaction can be confirmed, overriding first and last valid.
+    ///
+    /// Use it when the params aren't live, e.g. to sign offline a transaction to be sent later.
+    pub fn validity(mut self, range: RoundRange) -> Self {
+        self.first_valid = range.min;
+        self.last_valid = range.max;
+        self.validity_window = None;
+        self
+    }
+
+    /// Sets the last valid round to `rounds` rounds after the first valid round, e.g. the one of the
+    /// suggested params.
+    ///
+    /// [build](Self::build) fails if `rounds` exceeds [MAX_VALIDITY_WINDOW].
+    pub fn validity_window(mut self, rounds: u64) -> Self {
+        self.validity_window = Some(rounds);
+        self
+    }
+
     pub fn genesis_id(mut self, id: String) -> Self {
         self.genesis_id = Some(id);
         self
@@ -111,17 +169,78 @@ impl TxnBuilder {
         self
     }
 
+    /// Sets the note, of at most [MAX_NOTE_BYTES] bytes: [build](Self::build) fails with
+    /// [TransactionError::NoteTooLong] otherwise.
     pub fn note(mut self, note: Vec<u8>) -> Self {
         self.note = Some(note);
+        self.note_error = None;
         self
     }
 
+    /// Sets the note to this text, encoded as UTF-8: the limit of [MAX_NOTE_BYTES] is in bytes,
+    /// which is fewer characters for the text outside of ASCII (e.g. 341 characters of 3 bytes).
+    pub fn note_str(self, note: &str) -> Self {
+        self.note(note.as_bytes().to_vec())
+    }
+
+    /// Sets the note to `data`, encoded as JSON, to be decoded with
+    /// [Transaction::note_as_json]. [build](Self::build) fails with
+    /// [TransactionError::NoteEncoding] if `data` can't be encoded.
+    pub fn note_json<T: Serialize>(self, data: &T) -> Self {
+        self.encoded_note(serde_json::to_vec(data).map_err(|e| e.to_string()))
+    }
+
+    /// Sets the note to `data`, encoded as MessagePack (with the names of the fields of the
+    /// structs, as the other SDKs). [build](Self::build) fails with
+    /// [TransactionError::NoteEncoding] if `data` can't be encoded.
+    pub fn note_msgpack<T: Serialize>(self, data: &T) -> Self {
+        self.encoded_note(rmp_serde::to_vec_named(data).map_err(|e| e.to_string()))
+    }
+
+    fn encoded_note(mut self, note: Result<Vec<u8>, String>) -> Self {
+        match note {
+            Ok(note) => self.note(note),
+            Err(e) => {
+                self.note = None;
+                self.note_error = Some(e);
+                self
+            }
+        }
+    }
+
     pub fn rekey_to(mut self, rekey_to: Address) -> Self {
         self.rekey_to = Some(rekey_to);
         self
     }
 
-    pub fn build(self) -> Result<Transaction, TransactionError> {
+    /// Fails with [TransactionError::EmptyAssetParams] for a reconfiguration of an asset with empty params,
+    /// which would destroy the asset. Use [DestroyAsset] to destroy it.
+    ///
+    /// Fails with [TransactionError::AssetParamTooLong], [TransactionError::InvalidAssetMetadataHash]
+    /// or [TransactionError::InvalidAssetDecimals] for asset params over the limits of the protocol
+    /// (e.g. a URL longer than [MAX_ASSET_URL_BYTES]).
+    ///
+    /// Fails with [TransactionError::InvalidApplicationCall] for a call to an existing application
+    /// with fields not allowed by its on-complete action, e.g. programs in a call other than an update.
+    ///
+    /// Fails with [TransactionError::InvalidValidityWindow] if the window set with
+    /// [validity_window](Self::validity_window) exceeds [MAX_VALIDITY_WINDOW].
+    ///
+    /// Fails with [TransactionError::NoteTooLong] for a note of more than [MAX_NOTE_BYTES] bytes, and
+    /// with [TransactionError::NoteEncoding] if the data of the note couldn't be encoded.
+    pub fn build(mut self) -> Result<Transaction, TransactionError> {
+        if let Some(e) = self.note_error.take() {
+            return Err(TransactionError::NoteEncoding(e));
+        }
+        validate_note(self.note.as_deref())?;
+        if let Some(rounds) = self.validity_window {
+            self.last_valid = self
+                .first_valid
+                .checked_add(rounds)
+                .unwrap_or(Round(u64::MAX));
+            validate_validity_window(self.first_valid, self.last_valid, rounds)?;
+        }
+        validate_txn_type(&self.txn_type)?;
         Ok(match self.fee {
             TxnFee::Estimated {
                 fee_per_byte,
@@ -157,6 +276,202 @@ impl TxnBuilder {
     }
 }
 
+fn validate_validity_window(
+    first_valid: Round,
+    last_valid: Round,
+    rounds: u64,
+) -> Result<(), TransactionError> {
+    if rounds > MAX_VALIDITY_WINDOW {
+        return Err(TransactionError::InvalidValidityWindow {
+            first_valid: first_valid.0,
+            last_valid: last_valid.0,
+            max: MAX_VALIDITY_WINDOW,
+        });
+    }
+    Ok(())
+}
+
+fn validate_note(note: Option<&[u8]>) -> Result<(), TransactionError> {
+    match note {
+        Some(note) if note.len() > MAX_NOTE_BYTES => Err(TransactionError::NoteTooLong {
+            length: note.len(),
+            max: MAX_NOTE_BYTES,
+        }),
+        _ => Ok(()),
+    }
+}
+
+fn validate_txn_type(txn_type: &TransactionType) -> Result<(), TransactionError> {
+    match txn_type {
+        TransactionType::AssetConfigurationTransaction(AssetConfigurationTransaction {
+            params: Some(params),
+            config_asset: Some(_),
+            ..
+        }) if params.is_empty() => Err(TransactionError::EmptyAssetParams),
+        TransactionType::AssetConfigurationTransaction(AssetConfigurationTransaction {
+            params: Some(params),
+            ..
+        }) => validate_asset_params(params),
+        TransactionType::ApplicationCallTransaction(call) => validate_application_call(call),
+        _ => Ok(()),
+    }
+}
+
+/// Validates the asset params against the limits of the protocol.
+fn validate_asset_params(params: &AssetParams) -> Result<(), TransactionError> {
+    let lengths = [
+        ("unit name", &params.unit_name, MAX_ASSET_UNIT_NAME_BYTES),
+        ("asset name", &params.asset_name, MAX_ASSET_NAME_BYTES),
+        ("URL", &params.url, MAX_ASSET_URL_BYTES),
+    ];
+    for (field, value, max) in lengths {
+        let length = value.as_deref().unwrap_or_default().len();
+        if length > max {
+            return Err(TransactionError::AssetParamTooLong { field, length, max });
+        }
+    }
+    if let Some(hash) = &params.meta_data_hash {
+        if hash.len() != ASSET_METADATA_HASH_BYTES {
+            return Err(TransactionError::InvalidAssetMetadataHash { length: hash.len() });
+        }
+    }
+    match params.decimals {
+        Some(decimals) if decimals > MAX_ASSET_DECIMALS => {
+            Err(TransactionError::InvalidAssetDecimals {
+                decimals,
+                max: MAX_ASSET_DECIMALS,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Validates the arguments and the box references of an application call, and the fields of a
+/// call to an existing application against its on-complete action. The fields of creations (without app id) aren't
+/// validated, as they can be combined with any action.
+fn validate_application_call(call: &ApplicationCallTransaction) -> Result<(), TransactionError> {
+    validate_app_arguments(call.app_arguments.as_deref().unwrap_or_default())?;
+    let foreign_apps = call.foreign_apps.as_ref().map_or(0, Vec::len) as u64;
+    if let Some(reference) = call
+        .boxes
+        .iter()
+        .flatten()
+        .find(|reference| reference.app_index > foreign_apps)
+    {
+        return Err(TransactionError::InvalidApplicationCall(format!(
+            "box {:?} references the foreign app {}, of {}",
+            String::from_utf8_lossy(&reference.name),
+            reference.app_index,
+            foreign_apps
+        )));
+    }
+    if call.app_id.unwrap_or_default() == 0 {
+        return Ok(());
+    }
+    let invalid = |message: &str| {
+        Err(TransactionError::InvalidApplicationCall(format!(
+            "{:?}: {}",
+            call.on_complete, message
+        )))
+    };
+    if call.global_state_schema.is_some() || call.local_state_schema.is_some() {
+        return invalid("the state schema can only be set on creation");
+    }
+    if call.extra_pages != 0 {
+        return invalid("the extra pages can only be set on creation");
+    }
+    let has_programs = call.approval_program.is_some() || call.clear_state_program.is_some();
+    match call.on_complete {
+        ApplicationCallOnComplete::UpdateApplication
+            if call.approval_program.is_none() || call.clear_state_program.is_none() =>
+        {
+            invalid("an update requires the approval and clear state programs")
+        }
+        ApplicationCallOnComplete::UpdateApplication => Ok(()),
+        _ if has_programs => invalid("the programs can only be set on creation or update"),
+        _ => Ok(()),
+    }
+}
+
+/// Validates the number and the combined length of the arguments of an application call against
+/// the limits of the protocol.
+fn validate_app_arguments(args: &[Vec<u8>]) -> Result<(), TransactionError> {
+    if args.len() > MAX_APP_ARGS {
+        return Err(TransactionError::TooManyAppArguments {
+            count: args.len(),
+            max: MAX_APP_ARGS,
+        });
+    }
+    let length = args.iter().map(Vec::len).sum();
+    if length > MAX_APP_TOTAL_ARG_BYTES {
+        return Err(TransactionError::AppArgumentsTooLong {
+            length,
+            max: MAX_APP_TOTAL_ARG_BYTES,
+        });
+    }
+    Ok(())
+}
+
+/// An argument of an application call, encoded as the programs usually expect it.
+///
+/// A lighter alternative to ABI encoding for programs reading their arguments directly (e.g. with
+/// `txna ApplicationArgs 0; btoi`), set with the `app_args` methods of the application call
+/// builders.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AppArg {
+    /// An integer, as 8 big-endian bytes (read with `btoi`).
+    Uint(u64),
+    /// The 32 bytes of an address (its public key).
+    Address(Address),
+    /// Raw bytes.
+    Bytes(Vec<u8>),
+    /// A string, as its UTF-8 bytes.
+    String(String),
+    /// The selector of an ARC-4 method signature (e.g. `add(uint64,uint64)uint64`): the first 4
+    /// bytes of its SHA-512/256 hash.
+    MethodSelector(String),
+}
+
+impl AppArg {
+    pub fn uint(value: u64) -> Self {
+        AppArg::Uint(value)
+    }
+
+    pub fn address(address: &Address) -> Self {
+        AppArg::Address(*address)
+    }
+
+    pub fn bytes(bytes: &[u8]) -> Self {
+        AppArg::Bytes(bytes.to_vec())
+    }
+
+    pub fn string(string: &str) -> Self {
+        AppArg::String(string.to_owned())
+    }
+
+    pub fn method_selector(signature: &str) -> Self {
+        AppArg::MethodSelector(signature.to_owned())
+    }
+
+    /// The bytes of the argument, as set in the transaction.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            AppArg::Uint(value) => value.to_be_bytes().to_vec(),
+            AppArg::Address(address) => address.0.to_vec(),
+            AppArg::Bytes(bytes) => bytes.clone(),
+            AppArg::String(string) => string.as_bytes().to_vec(),
+            AppArg::MethodSelector(signature) => {
+                sha2::Sha512_256::digest(signature.as_bytes())[..4].to_vec()
+            }
+        }
+    }
+}
+
+/// The bytes of the arguments, as set in the transaction.
+fn app_args_to_bytes(args: impl IntoIterator<Item = AppArg>) -> Vec<Vec<u8>> {
+    args.into_iter().map(|arg| arg.to_bytes()).collect()
+}
+
 /// A builder for [Payment].
 pub struct Pay {
     sender: Address,
@@ -312,6 +627,32 @@ impl CreateAsset {
         self
     }
 
+    /// Sets the metadata hash, failing with [TransactionError::InvalidAssetMetadataHash] unless it's
+    /// [ASSET_METADATA_HASH_BYTES] long, e.g. the [hash_of](Self::hash_of) the metadata.
+    pub fn meta_data_hash_from_bytes(
+        self,
+        meta_data_hash: &[u8],
+    ) -> Result<Self, TransactionError> {
+        if meta_data_hash.len() != ASSET_METADATA_HASH_BYTES {
+            return Err(TransactionError::InvalidAssetMetadataHash {
+                length: meta_data_hash.len(),
+            });
+        }
+        Ok(self.meta_data_hash(meta_data_hash.to_vec()))
+    }
+
+    /// The SHA-256 of `content`, the metadata hash of an ARC-3 asset when `content` is its JSON
+    /// metadata file.
+    pub fn hash_of(content: &[u8]) -> [u8; ASSET_METADATA_HASH_BYTES] {
+        sha2::Sha256::digest(content).into()
+    }
+
+    /// Sets whether the holdings of the asset are frozen by default (as given to [new](Self::new)).
+    pub fn default_frozen(mut self, default_frozen: bool) -> Self {
+        self.default_frozen = Some(default_frozen);
+        self
+    }
+
     pub fn manager(mut self, manager: Address) -> Self {
         self.manager = Some(manager);
         self
@@ -332,6 +673,7 @@ impl CreateAsset {
         self
     }
 
+    /// The params are validated by [TxnBuilder::build], e.g. the length of the URL.
     pub fn build(self) -> TransactionType {
         TransactionType::AssetConfigurationTransaction(AssetConfigurationTransaction {
             sender: self.sender,
@@ -354,6 +696,8 @@ impl CreateAsset {
 }
 
 /// A builder for [AssetConfigurationTransaction].
+///
+/// The roles which aren't set are cleared permanently. See [ConfigureAsset] to keep them.
 pub struct UpdateAsset {
     sender: Address,
     asset_id: u64,
@@ -465,7 +809,108 @@ impl UpdateAsset {
     }
 }
 
-/// A builder for [AssetConfigurationTransaction].
+/// The change of one of the roles (manager, reserve, freeze, clawback) of an asset, in [ConfigureAsset].
+///
+/// A reconfiguration replaces all the roles: a role missing in the transaction is cleared,
+/// and a cleared role can't be set again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssetRoleChange {
+    /// Keeps the current address.
+    Keep,
+    /// Clears the role permanently.
+    ClearForever,
+    /// Sets a new address.
+    Set(Address),
+}
+
+impl AssetRoleChange {
+    fn apply(self, current: Option<Address>) -> Option<Address> {
+        match self {
+            AssetRoleChange::Keep => current,
+            AssetRoleChange::ClearForever => None,
+            AssetRoleChange::Set(address) => Some(address),
+        }
+    }
+}
+
+/// The roles of an asset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AssetRoles {
+    pub manager: Option<Address>,
+    pub reserve: Option<Address>,
+    pub freeze: Option<Address>,
+    pub clawback: Option<Address>,
+}
+
+/// The changes of all the roles of an asset, in [ConfigureAsset].
+///
+/// Every role has to be passed explicitly, to not clear a role by omission.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AssetRoleChanges {
+    pub manager: AssetRoleChange,
+    pub reserve: AssetRoleChange,
+    pub freeze: AssetRoleChange,
+    pub clawback: AssetRoleChange,
+}
+
+/// A builder for [AssetConfigurationTransaction], changing the roles of an existing asset.
+///
+/// Unlike [UpdateAsset], the roles which aren't changed are kept from the current roles of the asset.
+/// The transaction has to be sent by the current manager.
+pub struct ConfigureAsset {
+    sender: Address,
+    asset_id: u64,
+    current: AssetRoles,
+    changes: AssetRoleChanges,
+}
+
+impl ConfigureAsset {
+    pub fn new(
+        sender: Address,
+        asset_id: u64,
+        current: AssetRoles,
+        changes: AssetRoleChanges,
+    ) -> Self {
+        ConfigureAsset {
+            sender,
+            asset_id,
+            current,
+            changes,
+        }
+    }
+
+    /// Fails with [TransactionError::EmptyAssetParams] if all the roles end up cleared,
+    /// as the transaction would destroy the asset instead.
+    pub fn build(self) -> Result<TransactionType, TransactionError> {
+        let params = AssetParams {
+            total: None,
+            decimals: None,
+            default_frozen: None,
+            unit_name: None,
+            asset_name: None,
+            url: None,
+            meta_data_hash: None,
+            manager: self.changes.manager.apply(self.current.manager),
+            reserve: self.changes.reserve.apply(self.current.reserve),
+            freeze: self.changes.freeze.apply(self.current.freeze),
+            clawback: self.changes.clawback.apply(self.current.clawback),
+        };
+        if params.is_empty() {
+            return Err(TransactionError::EmptyAssetParams);
+        }
+        Ok(TransactionType::AssetConfigurationTransaction(
+            AssetConfigurationTransaction {
+                sender: self.sender,
+                config_asset: Some(self.asset_id),
+                params: Some(params),
+            },
+        ))
+    }
+}
+
+/// A builder for [AssetConfigurationTransaction], destroying an asset.
+///
+/// The transaction has to be sent by the manager, and the creator has to hold the whole supply.
 pub struct DestroyAsset {
     sender: Address,
     asset_id: u64,
@@ -622,6 +1067,7 @@ pub struct CreateApplication {
     clear_state_program: Option<CompiledTeal>,
     foreign_apps: Option<Vec<u64>>,
     foreign_assets: Option<Vec<u64>>,
+    boxes: Option<Vec<BoxReference>>,
     global_state_schema: Option<StateSchema>,
     local_state_schema: Option<StateSchema>,
     extra_pages: u64,
@@ -643,6 +1089,7 @@ impl CreateApplication {
             clear_state_program: Some(clear_state_program),
             foreign_apps: None,
             foreign_assets: None,
+            boxes: None,
             global_state_schema: Some(global_state_schema),
             local_state_schema: Some(local_state_schema),
             extra_pages: 0,
@@ -659,6 +1106,12 @@ impl CreateApplication {
         self
     }
 
+    /// Sets the arguments from [AppArg]s, encoded to bytes.
+    pub fn app_args(mut self, args: impl IntoIterator<Item = AppArg>) -> Self {
+        self.app_arguments = Some(app_args_to_bytes(args));
+        self
+    }
+
     pub fn foreign_apps(mut self, foreign_apps: Vec<u64>) -> Self {
         self.foreign_apps = Some(foreign_apps);
         self
@@ -669,6 +1122,11 @@ impl CreateApplication {
         self
     }
 
+    pub fn boxes(mut self, boxes: Vec<BoxReference>) -> Self {
+        self.boxes = Some(boxes);
+        self
+    }
+
     pub fn extra_pages(mut self, extra_pages: u64) -> Self {
         self.extra_pages = extra_pages;
         self
@@ -685,6 +1143,7 @@ impl CreateApplication {
             clear_state_program: self.clear_state_program,
             foreign_apps: self.foreign_apps,
             foreign_assets: self.foreign_assets,
+            boxes: self.boxes,
             global_state_schema: self.global_state_schema,
             local_state_schema: self.local_state_schema,
             extra_pages: self.extra_pages,
@@ -702,6 +1161,7 @@ pub struct UpdateApplication {
     clear_state_program: Option<CompiledTeal>,
     foreign_apps: Option<Vec<u64>>,
     foreign_assets: Option<Vec<u64>>,
+    boxes: Option<Vec<BoxReference>>,
 }
 
 impl UpdateApplication {
@@ -720,6 +1180,7 @@ impl UpdateApplication {
             clear_state_program: Some(clear_state_program),
             foreign_apps: None,
             foreign_assets: None,
+            boxes: None,
         }
     }
 
@@ -733,6 +1194,12 @@ impl UpdateApplication {
         self
     }
 
+    /// Sets the arguments from [AppArg]s, encoded to bytes.
+    pub fn app_args(mut self, args: impl IntoIterator<Item = AppArg>) -> Self {
+        self.app_arguments = Some(app_args_to_bytes(args));
+        self
+    }
+
     pub fn foreign_apps(mut self, foreign_apps: Vec<u64>) -> Self {
         self.foreign_apps = Some(foreign_apps);
         self
@@ -743,6 +1210,11 @@ impl UpdateApplication {
         self
     }
 
+    pub fn boxes(mut self, boxes: Vec<BoxReference>) -> Self {
+        self.boxes = Some(boxes);
+        self
+    }
+
     pub fn build(self) -> TransactionType {
         TransactionType::ApplicationCallTransaction(ApplicationCallTransaction {
             sender: self.sender,
@@ -754,6 +1226,7 @@ impl UpdateApplication {
             clear_state_program: self.clear_state_program,
             foreign_apps: self.foreign_apps,
             foreign_assets: self.foreign_assets,
+            boxes: self.boxes,
             global_state_schema: None,
             local_state_schema: None,
             extra_pages: 0,
@@ -769,6 +1242,7 @@ pub struct CallApplication {
     app_arguments: Option<Vec<Vec<u8>>>,
     foreign_apps: Option<Vec<u64>>,
     foreign_assets: Option<Vec<u64>>,
+    boxes: Option<Vec<BoxReference>>,
 }
 
 impl CallApplication {
@@ -780,6 +1254,7 @@ impl CallApplication {
             app_arguments: None,
             foreign_apps: None,
             foreign_assets: None,
+            boxes: None,
         }
     }
 
@@ -793,6 +1268,12 @@ impl CallApplication {
         self
     }
 
+    /// Sets the arguments from [AppArg]s, encoded to bytes.
+    pub fn app_args(mut self, args: impl IntoIterator<Item = AppArg>) -> Self {
+        self.app_arguments = Some(app_args_to_bytes(args));
+        self
+    }
+
     pub fn foreign_apps(mut self, foreign_apps: Vec<u64>) -> Self {
         self.foreign_apps = Some(foreign_apps);
         self
@@ -803,6 +1284,11 @@ impl CallApplication {
         self
     }
 
+    pub fn boxes(mut self, boxes: Vec<BoxReference>) -> Self {
+        self.boxes = Some(boxes);
+        self
+    }
+
     pub fn build(self) -> TransactionType {
         TransactionType::ApplicationCallTransaction(ApplicationCallTransaction {
             sender: self.sender,
@@ -814,6 +1300,7 @@ impl CallApplication {
             clear_state_program: None,
             foreign_apps: self.foreign_apps,
             foreign_assets: self.foreign_assets,
+            boxes: self.boxes,
             global_state_schema: None,
             local_state_schema: None,
             extra_pages: 0,
@@ -829,6 +1316,7 @@ pub struct ClearApplication {
     app_arguments: Option<Vec<Vec<u8>>>,
     foreign_apps: Option<Vec<u64>>,
     foreign_assets: Option<Vec<u64>>,
+    boxes: Option<Vec<BoxReference>>,
 }
 
 impl ClearApplication {
@@ -840,6 +1328,7 @@ impl ClearApplication {
             app_arguments: None,
             foreign_apps: None,
             foreign_assets: None,
+            boxes: None,
         }
     }
 
@@ -853,6 +1342,12 @@ impl ClearApplication {
         self
     }
 
+    /// Sets the arguments from [AppArg]s, encoded to bytes.
+    pub fn app_args(mut self, args: impl IntoIterator<Item = AppArg>) -> Self {
+        self.app_arguments = Some(app_args_to_bytes(args));
+        self
+    }
+
     pub fn foreign_apps(mut self, foreign_apps: Vec<u64>) -> Self {
         self.foreign_apps = Some(foreign_apps);
         self
@@ -863,6 +1358,11 @@ impl ClearApplication {
         self
     }
 
+    pub fn boxes(mut self, boxes: Vec<BoxReference>) -> Self {
+        self.boxes = Some(boxes);
+        self
+    }
+
     pub fn build(self) -> TransactionType {
         TransactionType::ApplicationCallTransaction(ApplicationCallTransaction {
             sender: self.sender,
@@ -874,6 +1374,7 @@ impl ClearApplication {
             clear_state_program: None,
             foreign_apps: self.foreign_apps,
             foreign_assets: self.foreign_assets,
+            boxes: self.boxes,
             global_state_schema: None,
             local_state_schema: None,
             extra_pages: 0,
@@ -889,6 +1390,7 @@ pub struct CloseApplication {
     app_arguments: Option<Vec<Vec<u8>>>,
     foreign_apps: Option<Vec<u64>>,
     foreign_assets: Option<Vec<u64>>,
+    boxes: Option<Vec<BoxReference>>,
 }
 
 impl CloseApplication {
@@ -900,6 +1402,7 @@ impl CloseApplication {
             app_arguments: None,
             foreign_apps: None,
             foreign_assets: None,
+            boxes: None,
         }
     }
 
@@ -913,6 +1416,12 @@ impl CloseApplication {
         self
     }
 
+    /// Sets the arguments from [AppArg]s, encoded to bytes.
+    pub fn app_args(mut self, args: impl IntoIterator<Item = AppArg>) -> Self {
+        self.app_arguments = Some(app_args_to_bytes(args));
+        self
+    }
+
     pub fn foreign_apps(mut self, foreign_apps: Vec<u64>) -> Self {
         self.foreign_apps = Some(foreign_apps);
         self
@@ -923,6 +1432,11 @@ impl CloseApplication {
         self
     }
 
+    pub fn boxes(mut self, boxes: Vec<BoxReference>) -> Self {
+        self.boxes = Some(boxes);
+        self
+    }
+
     pub fn build(self) -> TransactionType {
         TransactionType::ApplicationCallTransaction(ApplicationCallTransaction {
             sender: self.sender,
@@ -934,6 +1448,7 @@ impl CloseApplication {
             clear_state_program: None,
             foreign_apps: self.foreign_apps,
             foreign_assets: self.foreign_assets,
+            boxes: self.boxes,
             global_state_schema: None,
             local_state_schema: None,
             extra_pages: 0,
@@ -949,6 +1464,7 @@ pub struct DeleteApplication {
     app_arguments: Option<Vec<Vec<u8>>>,
     foreign_apps: Option<Vec<u64>>,
     foreign_assets: Option<Vec<u64>>,
+    boxes: Option<Vec<BoxReference>>,
 }
 
 impl DeleteApplication {
@@ -960,6 +1476,7 @@ impl DeleteApplication {
             app_arguments: None,
             foreign_apps: None,
             foreign_assets: None,
+            boxes: None,
         }
     }
 
@@ -973,6 +1490,12 @@ impl DeleteApplication {
         self
     }
 
+    /// Sets the arguments from [AppArg]s, encoded to bytes.
+    pub fn app_args(mut self, args: impl IntoIterator<Item = AppArg>) -> Self {
+        self.app_arguments = Some(app_args_to_bytes(args));
+        self
+    }
+
     pub fn foreign_apps(mut self, foreign_apps: Vec<u64>) -> Self {
         self.foreign_apps = Some(foreign_apps);
         self
@@ -983,6 +1506,11 @@ impl DeleteApplication {
         self
     }
 
+    pub fn boxes(mut self, boxes: Vec<BoxReference>) -> Self {
+        self.boxes = Some(boxes);
+        self
+    }
+
     pub fn build(self) -> TransactionType {
         TransactionType::ApplicationCallTransaction(ApplicationCallTransaction {
             sender: self.sender,
@@ -994,6 +1522,7 @@ impl DeleteApplication {
             clear_state_program: None,
             foreign_apps: self.foreign_apps,
             foreign_assets: self.foreign_assets,
+            boxes: self.boxes,
             global_state_schema: None,
             local_state_schema: None,
             extra_pages: 0,
@@ -1009,6 +1538,7 @@ pub struct OptInApplication {
     app_arguments: Option<Vec<Vec<u8>>>,
     foreign_apps: Option<Vec<u64>>,
     foreign_assets: Option<Vec<u64>>,
+    boxes: Option<Vec<BoxReference>>,
 }
 
 impl OptInApplication {
@@ -1020,6 +1550,7 @@ impl OptInApplication {
             app_arguments: None,
             foreign_apps: None,
             foreign_assets: None,
+            boxes: None,
         }
     }
 
@@ -1033,6 +1564,12 @@ impl OptInApplication {
         self
     }
 
+    /// Sets the arguments from [AppArg]s, encoded to bytes.
+    pub fn app_args(mut self, args: impl IntoIterator<Item = AppArg>) -> Self {
+        self.app_arguments = Some(app_args_to_bytes(args));
+        self
+    }
+
     pub fn foreign_apps(mut self, foreign_apps: Vec<u64>) -> Self {
         self.foreign_apps = Some(foreign_apps);
         self
@@ -1043,6 +1580,11 @@ impl OptInApplication {
         self
     }
 
+    pub fn boxes(mut self, boxes: Vec<BoxReference>) -> Self {
+        self.boxes = Some(boxes);
+        self
+    }
+
     pub fn build(self) -> TransactionType {
         TransactionType::ApplicationCallTransaction(ApplicationCallTransaction {
             sender: self.sender,
@@ -1054,9 +1596,870 @@ impl OptInApplication {
             clear_state_program: None,
             foreign_apps: self.foreign_apps,
             foreign_assets: self.foreign_assets,
+            boxes: self.boxes,
             global_state_schema: None,
             local_state_schema: None,
             extra_pages: 0,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_model::ApiTransaction;
+    use algonaut_core::ToMsgPack;
+    use data_encoding::BASE64;
+    use std::convert::TryFrom;
+
+    // The expected encodings below are canonical msgpack (sorted keys, empty fields omitted)
+    // written by hand for these tests. They weren't generated with goal, so they don't yet check
+    // the compatibility with its output: replace them with goal's (`goal asset config ... -o`,
+    // `goal asset destroy ... -o`) when it's available.
+
+    #[test]
+    fn test_build_with_deserialized_params() {
+        let params = SuggestedTransactionParams {
+            genesis_id: "testnet-v1.0".to_owned(),
+            genesis_hash: "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI="
+                .parse()
+                .unwrap(),
+            consensus_version: "https://github.com/algorandfoundation/specs/tree/abc54f79f9ad679d2d22f0fb9909fb005c16f8a1".to_owned(),
+            fee_per_byte: MicroAlgos(10),
+            min_fee: MicroAlgos(1000),
+            first_valid: Round(1000),
+            last_valid: Round(2000),
+        };
+        let json = serde_json::to_string(&params).unwrap();
+        let deserialized_params: SuggestedTransactionParams = serde_json::from_str(&json).unwrap();
+
+        let sender = "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU"
+            .parse()
+            .unwrap();
+        let receiver = "4MYUHDWHWXAKA5KA7U5PEN646VYUANBFXVJNONBK3TIMHEMWMD4UBOJBI4"
+            .parse()
+            .unwrap();
+        let build = |params: &SuggestedTransactionParams| {
+            TxnBuilder::with(params, Pay::new(sender, receiver, MicroAlgos(123)).build())
+                .build()
+                .unwrap()
+        };
+
+        let txn = build(&params);
+        let txn_from_deserialized = build(&deserialized_params);
+
+        assert_eq!(txn, txn_from_deserialized);
+        assert_eq!(
+            txn.to_msg_pack().unwrap(),
+            txn_from_deserialized.to_msg_pack().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_build_with_validity_range() {
+        let params = SuggestedTransactionParams {
+            genesis_id: "testnet-v1.0".to_owned(),
+            genesis_hash: "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI="
+                .parse()
+                .unwrap(),
+            consensus_version: "https://github.com/algorandfoundation/specs/tree/abc54f79f9ad679d2d22f0fb9909fb005c16f8a1".to_owned(),
+            fee_per_byte: MicroAlgos(10),
+            min_fee: MicroAlgos(1000),
+            first_valid: Round(1000),
+            last_valid: Round(2000),
+        };
+        let sender = "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU"
+            .parse()
+            .unwrap();
+        let receiver = "4MYUHDWHWXAKA5KA7U5PEN646VYUANBFXVJNONBK3TIMHEMWMD4UBOJBI4"
+            .parse()
+            .unwrap();
+
+        let txn = TxnBuilder::with(&params, Pay::new(sender, receiver, MicroAlgos(123)).build())
+            .validity(RoundRange::next(Round(1500), 10))
+            .build()
+            .unwrap();
+
+        assert_eq!(Round(1501), txn.first_valid);
+        assert_eq!(Round(1510), txn.last_valid);
+    }
+
+    fn params() -> SuggestedTransactionParams {
+        SuggestedTransactionParams {
+            genesis_id: "testnet-v1.0".to_owned(),
+            genesis_hash: HashDigest([0; 32]),
+            consensus_version: "v1".to_owned(),
+            fee_per_byte: MicroAlgos(0),
+            min_fee: MicroAlgos(1000),
+            first_valid: Round(1000),
+            last_valid: Round(2000),
+        }
+    }
+
+    fn payment() -> TransactionType {
+        Pay::new(Address([1; 32]), Address([2; 32]), MicroAlgos(123)).build()
+    }
+
+    /// The params of algod's `/v2/transactions/params`, without congestion fee.
+    fn node_params(genesis_id: &str) -> SuggestedTransactionParams {
+        let response = format!(
+            r#"{{
+                "consensus-version": "https://github.com/algorandfoundation/specs/tree/abc54f79f9ad679d2d22f0fb9909fb005c16f8a1",
+                "fee": 0,
+                "genesis-hash": "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=",
+                "genesis-id": "{}",
+                "last-round": 1000,
+                "min-fee": 1000
+            }}"#,
+            genesis_id
+        );
+        let params: algonaut_model::algod::v2::TransactionParams =
+            serde_json::from_str(&response).unwrap();
+        params.into()
+    }
+
+    fn offline_params(genesis_id: Option<&str>) -> SuggestedTransactionParams {
+        SuggestedTransactionParams::offline(
+            genesis_id,
+            "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI="
+                .parse()
+                .unwrap(),
+            MicroAlgos(1000),
+            Round(1000),
+        )
+    }
+
+    #[test]
+    fn test_node_params_carry_all_the_response_fields() {
+        let params = node_params("testnet-v1.0");
+        assert_eq!(Some("testnet-v1.0"), params.genesis_id());
+        assert_eq!(
+            Some("https://github.com/algorandfoundation/specs/tree/abc54f79f9ad679d2d22f0fb9909fb005c16f8a1"),
+            params.consensus_version()
+        );
+        assert_eq!(MicroAlgos(1000), params.min_fee);
+        assert_eq!(RoundRange::new(Round(1000), Round(2000)), params.validity());
+    }
+
+    #[test]
+    fn test_build_online_and_offline_are_byte_identical() {
+        let online = node_params("testnet-v1.0");
+        let offline = offline_params(Some("testnet-v1.0"));
+        let sender = Address([1; 32]);
+        let txn_types = vec![
+            payment(),
+            TransferAsset::new(sender, 10, 5, Address([2; 32])).build(),
+            AcceptAsset::new(sender, 10).build(),
+        ];
+
+        for txn_type in txn_types {
+            let build = |params: &SuggestedTransactionParams| {
+                TxnBuilder::with(params, txn_type.clone())
+                    .note(b"offline".to_vec())
+                    .build()
+                    .unwrap()
+            };
+            let online_txn = build(&online);
+            let offline_txn = build(&offline);
+
+            assert_eq!(online_txn, offline_txn);
+            assert_eq!(
+                online_txn.to_msg_pack().unwrap(),
+                offline_txn.to_msg_pack().unwrap()
+            );
+            assert_eq!(online_txn.id().unwrap(), offline_txn.id().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_build_without_genesis_id() {
+        let online = TxnBuilder::with(&node_params(""), payment())
+            .build()
+            .unwrap();
+        let offline = TxnBuilder::with(&offline_params(None), payment())
+            .build()
+            .unwrap();
+
+        assert_eq!(None, online.genesis_id);
+        assert_eq!(online, offline);
+        assert_eq!(
+            online.to_msg_pack().unwrap(),
+            offline.to_msg_pack().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_build_with_validity_window() {
+        let txn = TxnBuilder::with(&params(), payment()).build().unwrap();
+        assert_eq!(RoundRange::new(Round(1000), Round(2000)), txn.validity());
+
+        let txn = TxnBuilder::with(&params(), payment())
+            .validity_window(10)
+            .build()
+            .unwrap();
+        assert_eq!(RoundRange::new(Round(1000), Round(1010)), txn.validity());
+    }
+
+    #[test]
+    fn test_build_with_validity_window_above_max_fails() {
+        let res = TxnBuilder::with(&params(), payment())
+            .validity_window(MAX_VALIDITY_WINDOW + 1)
+            .build();
+        assert!(matches!(
+            res,
+            Err(TransactionError::InvalidValidityWindow {
+                first_valid: 1000,
+                last_valid: 2001,
+                max: MAX_VALIDITY_WINDOW
+            })
+        ));
+    }
+
+    #[test]
+    fn test_build_without_validity_window_isnt_checked() {
+        // left to the node, as before the validity window
+        let mut params = params();
+        params.last_valid = Round(2001);
+        assert!(TxnBuilder::with(&params, payment()).build().is_ok());
+
+        let res = TxnBuilder::new(
+            TxnFee::Fixed(MicroAlgos(1000)),
+            Round(2000),
+            Round(1000),
+            HashDigest([0; 32]),
+            payment(),
+        )
+        .build();
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn test_build_offline_with_explicit_validity() {
+        // stale params, e.g. cached: the explicit range overrides their validity and window
+        let txn = TxnBuilder::with(&params(), payment())
+            .validity_window(10)
+            .validity(RoundRange::new(Round(50_000), Round(50_500)))
+            .build()
+            .unwrap();
+        assert_eq!(
+            RoundRange::new(Round(50_000), Round(50_500)),
+            txn.validity()
+        );
+
+        let txn = TxnBuilder::with(&params(), payment())
+            .validity(RoundRange::starting_at(Round(50_000), 1))
+            .validity_window(MAX_VALIDITY_WINDOW)
+            .build()
+            .unwrap();
+        assert_eq!(
+            RoundRange::new(Round(50_000), Round(51_000)),
+            txn.validity()
+        );
+    }
+
+    fn build_txn(txn_type: TransactionType) -> Result<Transaction, TransactionError> {
+        TxnBuilder::new(
+            TxnFee::Fixed(MicroAlgos(1000)),
+            Round(1000),
+            Round(2000),
+            "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI="
+                .parse()
+                .unwrap(),
+            txn_type,
+        )
+        .genesis_id("testnet-v1.0".to_owned())
+        .build()
+    }
+
+    fn asset_roles() -> AssetRoles {
+        let manager = "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU"
+            .parse()
+            .unwrap();
+        let reserve = "4MYUHDWHWXAKA5KA7U5PEN646VYUANBFXVJNONBK3TIMHEMWMD4UBOJBI4"
+            .parse()
+            .unwrap();
+        let clawback = "PNWOET7LLOWMBMLE4KOCELCX6X3D3Q4H2Q4QJASYIEOF7YIPPQBG3YQ5YI"
+            .parse()
+            .unwrap();
+        AssetRoles {
+            manager: Some(manager),
+            reserve: Some(reserve),
+            freeze: Some(reserve),
+            clawback: Some(clawback),
+        }
+    }
+
+    fn keep_all() -> AssetRoleChanges {
+        AssetRoleChanges {
+            manager: AssetRoleChange::Keep,
+            reserve: AssetRoleChange::Keep,
+            freeze: AssetRoleChange::Keep,
+            clawback: AssetRoleChange::Keep,
+        }
+    }
+
+    /// The metadata file of an ARC-3 NFT.
+    const ARC3_METADATA: &[u8] = br#"{"name":"My NFT","decimals":0,"image":"ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi","image_mimetype":"image/png"}"#;
+
+    fn arc3_nft() -> CreateAsset {
+        let creator: Address = "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU"
+            .parse()
+            .unwrap();
+        CreateAsset::new(creator, 1, 0, false)
+            .unit_name("NFT".to_owned())
+            .asset_name("My NFT@arc3".to_owned())
+            .url(
+                "ipfs://bafkreigjtqm36ymdn2sbj4yalvz7wdlcbhcqpaksdvdxk4vcukgoxymliy#arc3"
+                    .to_owned(),
+            )
+            .manager(creator)
+            .reserve(creator)
+    }
+
+    #[test]
+    fn test_create_arc3_nft() {
+        let hash = CreateAsset::hash_of(ARC3_METADATA);
+        assert_eq!(
+            "+HZYIGMQA8BFi4BgrW8ASvjfJiZwZIZbHFqSO8ESqgc=",
+            BASE64.encode(&hash)
+        );
+        let txn = build_txn(arc3_nft().meta_data_hash_from_bytes(&hash).unwrap().build()).unwrap();
+
+        let expected = BASE64.decode(b"iKRhcGFyh6JhbcQg+HZYIGMQA8BFi4BgrW8ASvjfJiZwZIZbHFqSO8ESqgeiYW6rTXkgTkZUQGFyYzOiYXXZR2lwZnM6Ly9iYWZrcmVpZ2p0cW0zNnltZG4yc2JqNHlhbHZ6N3dkbGNiaGNxcGFrc2R2ZHhrNHZjdWtnb3h5bWxpeSNhcmMzoW3EIOfw+E0GgR358xyNh4sRVfRnHVGhhcIAkIZn9ElYcGihoXLEIOfw+E0GgR358xyNh4sRVfRnHVGhhcIAkIZn9ElYcGihoXQBonVuo05GVKNmZWXNA+iiZnbNA+ijZ2VurHRlc3RuZXQtdjEuMKJnaMQgSGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiKibHbNB9Cjc25kxCDn8PhNBoEd+fMcjYeLEVX0Zx1RoYXCAJCGZ/RJWHBooaR0eXBlpGFjZmc=").unwrap();
+        assert_eq!(expected, txn.to_msg_pack().unwrap());
+    }
+
+    #[test]
+    fn test_create_asset_default_frozen() {
+        let txn_type = arc3_nft().default_frozen(true).build();
+        match txn_type {
+            TransactionType::AssetConfigurationTransaction(AssetConfigurationTransaction {
+                params: Some(params),
+                ..
+            }) => assert_eq!(Some(true), params.default_frozen),
+            _ => panic!("not an asset config transaction"),
+        }
+    }
+
+    #[test]
+    fn test_create_asset_with_invalid_metadata_hash() {
+        assert!(matches!(
+            arc3_nft().meta_data_hash_from_bytes(&[0; 31]),
+            Err(TransactionError::InvalidAssetMetadataHash { length: 31 })
+        ));
+        assert!(matches!(
+            build_txn(arc3_nft().meta_data_hash(vec![0; 33]).build()),
+            Err(TransactionError::InvalidAssetMetadataHash { length: 33 })
+        ));
+    }
+
+    #[test]
+    fn test_create_asset_over_the_limits() {
+        assert!(matches!(
+            build_txn(
+                arc3_nft()
+                    .url(format!("https://{}", "a".repeat(89)))
+                    .build()
+            ),
+            Err(TransactionError::AssetParamTooLong {
+                field: "URL",
+                length: 97,
+                max: 96
+            })
+        ));
+        assert!(build_txn(
+            arc3_nft()
+                .url(format!("https://{}", "a".repeat(88)))
+                .build()
+        )
+        .is_ok());
+        // The limits are in bytes, not characters.
+        assert!(matches!(
+            build_txn(arc3_nft().unit_name("ÉÉÉÉÉ".to_owned()).build()),
+            Err(TransactionError::AssetParamTooLong {
+                field: "unit name",
+                length: 10,
+                max: 8
+            })
+        ));
+        assert!(matches!(
+            build_txn(arc3_nft().asset_name("a".repeat(33)).build()),
+            Err(TransactionError::AssetParamTooLong {
+                field: "asset name",
+                length: 33,
+                max: 32
+            })
+        ));
+        let creator = "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU"
+            .parse()
+            .unwrap();
+        assert!(matches!(
+            build_txn(CreateAsset::new(creator, 1, 20, false).build()),
+            Err(TransactionError::InvalidAssetDecimals {
+                decimals: 20,
+                max: 19
+            })
+        ));
+        assert!(build_txn(CreateAsset::new(creator, 1, 19, false).build()).is_ok());
+    }
+
+    /// Builds a payment with the note set by `note`.
+    fn build_with_note(
+        note: impl FnOnce(TxnBuilder) -> TxnBuilder,
+    ) -> Result<Transaction, TransactionError> {
+        let address: Address = "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU"
+            .parse()
+            .unwrap();
+        note(TxnBuilder::new(
+            TxnFee::Fixed(MicroAlgos(1000)),
+            Round(1000),
+            Round(2000),
+            "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI="
+                .parse()
+                .unwrap(),
+            Pay::new(address, address, MicroAlgos(0)).build(),
+        ))
+        .build()
+    }
+
+    #[test]
+    fn test_note_str_limit_in_bytes() {
+        // 342 characters of 3 bytes: under the limit in characters, over it in bytes.
+        let note = "€".repeat(342);
+        assert_eq!(342, note.chars().count());
+        assert!(matches!(
+            build_with_note(|builder| builder.note_str(&note)),
+            Err(TransactionError::NoteTooLong {
+                length: 1026,
+                max: 1024
+            })
+        ));
+        let note = format!("{}a", "€".repeat(341));
+        let txn = build_with_note(|builder| builder.note_str(&note)).unwrap();
+        assert_eq!(Some(note.into_bytes()), txn.note);
+        assert!(build_with_note(|builder| builder.note(vec![0; 1025])).is_err());
+    }
+
+    #[derive(Debug, PartialEq, Serialize, serde::Deserialize)]
+    struct Order {
+        id: u64,
+        item: String,
+    }
+
+    #[test]
+    fn test_note_json() {
+        let order = Order {
+            id: 7,
+            item: "café".to_owned(),
+        };
+        let txn = build_with_note(|builder| builder.note_json(&order)).unwrap();
+
+        assert_eq!(
+            Some(r#"{"id":7,"item":"café"}"#.as_bytes().to_vec()),
+            txn.note
+        );
+        assert_eq!(Some(order), txn.note_as_json().unwrap());
+        assert!(txn.note_as_json::<u64>().is_err());
+    }
+
+    #[test]
+    fn test_note_msgpack() {
+        let order = Order {
+            id: 7,
+            item: "tea".to_owned(),
+        };
+        let txn = build_with_note(|builder| builder.note_msgpack(&order)).unwrap();
+
+        // A map with the names of the fields.
+        assert_eq!(Some(b"\x82\xa2id\x07\xa4item\xa3tea".to_vec()), txn.note);
+    }
+
+    #[test]
+    fn test_note_encoding_errors() {
+        // JSON has only string keys.
+        let data: std::collections::BTreeMap<Vec<u8>, u8> =
+            vec![(vec![1], 1)].into_iter().collect();
+        assert!(matches!(
+            build_with_note(|builder| builder.note_json(&data)),
+            Err(TransactionError::NoteEncoding(_))
+        ));
+        assert!(matches!(
+            build_with_note(|builder| builder.note_json(&"a".repeat(1023))),
+            Err(TransactionError::NoteTooLong {
+                length: 1025,
+                max: 1024
+            })
+        ));
+        // A note set after the failed encoding replaces it.
+        assert!(build_with_note(|builder| builder.note_json(&data).note_str("ok")).is_ok());
+    }
+
+    #[test]
+    fn test_configure_asset_keeping_roles() {
+        let roles = asset_roles();
+        let txn = build_txn(
+            ConfigureAsset::new(roles.manager.unwrap(), 1234, roles, keep_all())
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+
+        let expected = BASE64.decode(b"iaRhcGFyhKFjxCB7bOJP61uswLFk4pwiLFf19j3Dh9Q5BIJYQRxf4Q98AqFmxCDjMUOOx7XAoHVA/TryN9z1cUA0Jb1S1zQq3NDDkZZg+aFtxCDn8PhNBoEd+fMcjYeLEVX0Zx1RoYXCAJCGZ/RJWHBooaFyxCDjMUOOx7XAoHVA/TryN9z1cUA0Jb1S1zQq3NDDkZZg+aRjYWlkzQTSo2ZlZc0D6KJmds0D6KNnZW6sdGVzdG5ldC12MS4womdoxCBIY7UYpLPITsgQ8i1PEIHLD3HwWaesIN7GL39w5Qk6IqJsds0H0KNzbmTEIOfw+E0GgR358xyNh4sRVfRnHVGhhcIAkIZn9ElYcGihpHR5cGWkYWNmZw==").unwrap();
+        assert_eq!(expected, txn.to_msg_pack().unwrap());
+    }
+
+    #[test]
+    fn test_configure_asset_clearing_role() {
+        let roles = asset_roles();
+        let changes = AssetRoleChanges {
+            freeze: AssetRoleChange::ClearForever,
+            ..keep_all()
+        };
+        let txn = build_txn(
+            ConfigureAsset::new(roles.manager.unwrap(), 1234, roles, changes)
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+
+        let expected = BASE64.decode(b"iaRhcGFyg6FjxCB7bOJP61uswLFk4pwiLFf19j3Dh9Q5BIJYQRxf4Q98AqFtxCDn8PhNBoEd+fMcjYeLEVX0Zx1RoYXCAJCGZ/RJWHBooaFyxCDjMUOOx7XAoHVA/TryN9z1cUA0Jb1S1zQq3NDDkZZg+aRjYWlkzQTSo2ZlZc0D6KJmds0D6KNnZW6sdGVzdG5ldC12MS4womdoxCBIY7UYpLPITsgQ8i1PEIHLD3HwWaesIN7GL39w5Qk6IqJsds0H0KNzbmTEIOfw+E0GgR358xyNh4sRVfRnHVGhhcIAkIZn9ElYcGihpHR5cGWkYWNmZw==").unwrap();
+        assert_eq!(expected, txn.to_msg_pack().unwrap());
+    }
+
+    #[test]
+    fn test_configure_asset_setting_role() {
+        let roles = asset_roles();
+        let changes = AssetRoleChanges {
+            reserve: AssetRoleChange::Set(roles.clawback.unwrap()),
+            clawback: AssetRoleChange::ClearForever,
+            ..keep_all()
+        };
+
+        let txn_type = ConfigureAsset::new(roles.manager.unwrap(), 1234, roles, changes)
+            .build()
+            .unwrap();
+
+        match txn_type {
+            TransactionType::AssetConfigurationTransaction(config) => {
+                let params = config.params.unwrap();
+                assert_eq!(roles.manager, params.manager);
+                assert_eq!(roles.clawback, params.reserve);
+                assert_eq!(roles.freeze, params.freeze);
+                assert_eq!(None, params.clawback);
+            }
+            other => panic!("unexpected transaction type: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_configure_asset_clearing_all_roles_fails() {
+        let changes = AssetRoleChanges {
+            manager: AssetRoleChange::ClearForever,
+            reserve: AssetRoleChange::ClearForever,
+            freeze: AssetRoleChange::ClearForever,
+            clawback: AssetRoleChange::ClearForever,
+        };
+        let roles = asset_roles();
+
+        let res = ConfigureAsset::new(roles.manager.unwrap(), 1234, roles, changes).build();
+
+        assert!(matches!(res, Err(TransactionError::EmptyAssetParams)));
+    }
+
+    #[test]
+    fn test_destroy_asset() {
+        let sender = asset_roles().manager.unwrap();
+        let txn = build_txn(DestroyAsset::new(sender, 1234).build()).unwrap();
+
+        let expected = BASE64.decode(b"iKRjYWlkzQTSo2ZlZc0D6KJmds0D6KNnZW6sdGVzdG5ldC12MS4womdoxCBIY7UYpLPITsgQ8i1PEIHLD3HwWaesIN7GL39w5Qk6IqJsds0H0KNzbmTEIOfw+E0GgR358xyNh4sRVfRnHVGhhcIAkIZn9ElYcGihpHR5cGWkYWNmZw==").unwrap();
+        assert_eq!(expected, txn.to_msg_pack().unwrap());
+    }
+
+    #[test]
+    fn test_reconfigure_asset_with_empty_params_fails() {
+        let sender = asset_roles().manager.unwrap();
+
+        let res = build_txn(UpdateAsset::new(sender, 1234).build());
+
+        assert!(matches!(res, Err(TransactionError::EmptyAssetParams)));
+    }
+
+    fn app_call_sender() -> Address {
+        "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU"
+            .parse()
+            .unwrap()
+    }
+
+    fn app_call_account() -> Address {
+        "4MYUHDWHWXAKA5KA7U5PEN646VYUANBFXVJNONBK3TIMHEMWMD4UBOJBI4"
+            .parse()
+            .unwrap()
+    }
+
+    // `#pragma version 6; int 1`
+    fn approve_program() -> CompiledTeal {
+        CompiledTeal(vec![0x06, 0x81, 0x01])
+    }
+
+    #[test]
+    fn test_update_application() {
+        let txn = build_txn(
+            UpdateApplication::new(
+                app_call_sender(),
+                1234,
+                approve_program(),
+                approve_program(),
+            )
+            .app_arguments(vec![vec![1, 2]])
+            .build(),
+        )
+        .unwrap();
+
+        let expected = BASE64.decode(b"jKRhcGFhkcQCAQKkYXBhbgSkYXBhcMQDBoEBpGFwaWTNBNKkYXBzdcQDBoEBo2ZlZc0D6KJmds0D6KNnZW6sdGVzdG5ldC12MS4womdoxCBIY7UYpLPITsgQ8i1PEIHLD3HwWaesIN7GL39w5Qk6IqJsds0H0KNzbmTEIOfw+E0GgR358xyNh4sRVfRnHVGhhcIAkIZn9ElYcGihpHR5cGWkYXBwbA==").unwrap();
+        assert_eq!(expected, txn.to_msg_pack().unwrap());
+    }
+
+    #[test]
+    fn test_delete_application() {
+        let txn = build_txn(DeleteApplication::new(app_call_sender(), 1234).build()).unwrap();
+
+        let expected = BASE64.decode(b"iaRhcGFuBaRhcGlkzQTSo2ZlZc0D6KJmds0D6KNnZW6sdGVzdG5ldC12MS4womdoxCBIY7UYpLPITsgQ8i1PEIHLD3HwWaesIN7GL39w5Qk6IqJsds0H0KNzbmTEIOfw+E0GgR358xyNh4sRVfRnHVGhhcIAkIZn9ElYcGihpHR5cGWkYXBwbA==").unwrap();
+        assert_eq!(expected, txn.to_msg_pack().unwrap());
+    }
+
+    #[test]
+    fn test_close_out_application() {
+        let txn = build_txn(
+            CloseApplication::new(app_call_sender(), 1234)
+                .accounts(vec![app_call_account()])
+                .build(),
+        )
+        .unwrap();
+
+        let expected = BASE64.decode(b"iqRhcGFuAqRhcGF0kcQg4zFDjse1wKB1QP068jfc9XFANCW9Utc0KtzQw5GWYPmkYXBpZM0E0qNmZWXNA+iiZnbNA+ijZ2VurHRlc3RuZXQtdjEuMKJnaMQgSGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiKibHbNB9Cjc25kxCDn8PhNBoEd+fMcjYeLEVX0Zx1RoYXCAJCGZ/RJWHBooaR0eXBlpGFwcGw=").unwrap();
+        assert_eq!(expected, txn.to_msg_pack().unwrap());
+    }
+
+    #[test]
+    fn test_clear_state_application() {
+        let txn = build_txn(ClearApplication::new(app_call_sender(), 1234).build()).unwrap();
+
+        let expected = BASE64.decode(b"iaRhcGFuA6RhcGlkzQTSo2ZlZc0D6KJmds0D6KNnZW6sdGVzdG5ldC12MS4womdoxCBIY7UYpLPITsgQ8i1PEIHLD3HwWaesIN7GL39w5Qk6IqJsds0H0KNzbmTEIOfw+E0GgR358xyNh4sRVfRnHVGhhcIAkIZn9ElYcGihpHR5cGWkYXBwbA==").unwrap();
+        assert_eq!(expected, txn.to_msg_pack().unwrap());
+    }
+
+    #[test]
+    fn test_opt_in_application() {
+        let txn = build_txn(
+            OptInApplication::new(app_call_sender(), 1234)
+                .app_arguments(vec![vec![1, 2], b"abc".to_vec()])
+                .accounts(vec![app_call_account()])
+                .foreign_apps(vec![10])
+                .foreign_assets(vec![20])
+                .build(),
+        )
+        .unwrap();
+
+        let expected = BASE64.decode(b"jaRhcGFhksQCAQLEA2FiY6RhcGFuAaRhcGFzkRSkYXBhdJHEIOMxQ47HtcCgdUD9OvI33PVxQDQlvVLXNCrc0MORlmD5pGFwZmGRCqRhcGlkzQTSo2ZlZc0D6KJmds0D6KNnZW6sdGVzdG5ldC12MS4womdoxCBIY7UYpLPITsgQ8i1PEIHLD3HwWaesIN7GL39w5Qk6IqJsds0H0KNzbmTEIOfw+E0GgR358xyNh4sRVfRnHVGhhcIAkIZn9ElYcGihpHR5cGWkYXBwbA==").unwrap();
+        assert_eq!(expected, txn.to_msg_pack().unwrap());
+    }
+
+    fn app_call(on_complete: ApplicationCallOnComplete) -> ApplicationCallTransaction {
+        ApplicationCallTransaction {
+            sender: app_call_sender(),
+            app_id: Some(1234),
+            on_complete,
+            accounts: None,
+            approval_program: None,
+            app_arguments: None,
+            clear_state_program: None,
+            foreign_apps: None,
+            foreign_assets: None,
+            boxes: None,
+            global_state_schema: None,
+            local_state_schema: None,
+            extra_pages: 0,
+        }
+    }
+
+    #[test]
+    fn test_invalid_application_calls_fail() {
+        let update_without_clear_program = ApplicationCallTransaction {
+            approval_program: Some(approve_program()),
+            ..app_call(ApplicationCallOnComplete::UpdateApplication)
+        };
+        let delete_with_programs = ApplicationCallTransaction {
+            approval_program: Some(approve_program()),
+            clear_state_program: Some(approve_program()),
+            ..app_call(ApplicationCallOnComplete::DeleteApplication)
+        };
+        let close_out_with_schema = ApplicationCallTransaction {
+            local_state_schema: Some(StateSchema {
+                number_ints: 1,
+                number_byteslices: 0,
+            }),
+            ..app_call(ApplicationCallOnComplete::CloseOut)
+        };
+        let clear_with_program = ApplicationCallTransaction {
+            clear_state_program: Some(approve_program()),
+            ..app_call(ApplicationCallOnComplete::ClearState)
+        };
+        let opt_in_with_extra_pages = ApplicationCallTransaction {
+            extra_pages: 1,
+            ..app_call(ApplicationCallOnComplete::OptIn)
+        };
+        let box_of_missing_foreign_app = ApplicationCallTransaction {
+            boxes: Some(vec![BoxReference {
+                app_index: 1,
+                name: b"a".to_vec(),
+            }]),
+            ..app_call(ApplicationCallOnComplete::NoOp)
+        };
+
+        for call in [
+            update_without_clear_program,
+            delete_with_programs,
+            close_out_with_schema,
+            clear_with_program,
+            opt_in_with_extra_pages,
+            box_of_missing_foreign_app,
+        ] {
+            let res = build_txn(TransactionType::ApplicationCallTransaction(call.clone()));
+            assert!(
+                matches!(res, Err(TransactionError::InvalidApplicationCall(_))),
+                "{:?} should be invalid",
+                call
+            );
+        }
+    }
+
+    #[test]
+    fn test_app_arg_bytes() {
+        let address = app_call_account();
+
+        assert_eq!(vec![0, 0, 0, 0, 0, 0, 0, 42], AppArg::uint(42).to_bytes());
+        assert_eq!(vec![0xff; 8], AppArg::uint(u64::MAX).to_bytes());
+        assert_eq!(address.0.to_vec(), AppArg::address(&address).to_bytes());
+        assert_eq!(vec![1, 2, 3], AppArg::bytes(&[1, 2, 3]).to_bytes());
+        assert_eq!(b"hello".to_vec(), AppArg::string("hello").to_bytes());
+        assert_eq!(
+            vec![0xe2, 0x82, 0xac],
+            AppArg::string("\u{20ac}").to_bytes()
+        );
+        // the selector of the ARC-4 examples
+        assert_eq!(
+            vec![0xfe, 0x6b, 0xdf, 0x69],
+            AppArg::method_selector("add(uint64,uint64)uint64").to_bytes()
+        );
+    }
+
+    #[test]
+    fn test_call_application_with_app_args() {
+        let txn = build_txn(
+            CallApplication::new(app_call_sender(), 1234)
+                .app_args([
+                    AppArg::method_selector("add(uint64,uint64)uint64"),
+                    AppArg::uint(1),
+                    AppArg::string("a"),
+                ])
+                .build(),
+        )
+        .unwrap();
+
+        match txn.txn_type {
+            TransactionType::ApplicationCallTransaction(call) => assert_eq!(
+                Some(vec![
+                    vec![0xfe, 0x6b, 0xdf, 0x69],
+                    vec![0, 0, 0, 0, 0, 0, 0, 1],
+                    b"a".to_vec()
+                ]),
+                call.app_arguments
+            ),
+            _ => panic!("not an application call"),
+        }
+    }
+
+    #[test]
+    fn test_call_application_with_boxes() {
+        let txn = build_txn(
+            CallApplication::new(app_call_sender(), 1234)
+                .foreign_apps(vec![10])
+                .boxes(vec![
+                    BoxReference {
+                        app_index: 0,
+                        name: b"a".to_vec(),
+                    },
+                    BoxReference {
+                        app_index: 1,
+                        name: b"b".to_vec(),
+                    },
+                ])
+                .build(),
+        )
+        .unwrap();
+
+        let encoded = txn.to_msg_pack().unwrap();
+        // "apbx": [{"n": "a"}, {"i": 1, "n": "b"}], the zero index omitted
+        let apbx = b"\xa4apbx\x92\x81\xa1n\xc4\x01a\x82\xa1i\x01\xa1n\xc4\x01b";
+        assert!(encoded.windows(apbx.len()).any(|w| w == apbx));
+        let api_txn: ApiTransaction = rmp_serde::from_slice(&encoded).unwrap();
+        assert_eq!(Transaction::try_from(api_txn).unwrap(), txn);
+    }
+
+    #[test]
+    fn test_app_arguments_limits() {
+        let call = |args: Vec<AppArg>| {
+            build_txn(
+                CallApplication::new(app_call_sender(), 1234)
+                    .app_args(args)
+                    .build(),
+            )
+        };
+
+        assert!(call(vec![AppArg::uint(1); MAX_APP_ARGS]).is_ok());
+        assert!(matches!(
+            call(vec![AppArg::uint(1); MAX_APP_ARGS + 1]),
+            Err(TransactionError::TooManyAppArguments { count: 17, max: 16 })
+        ));
+
+        assert!(call(vec![AppArg::bytes(&[0; MAX_APP_TOTAL_ARG_BYTES])]).is_ok());
+        let res = call(vec![
+            AppArg::bytes(&[0; 2007]),
+            AppArg::string("a"),
+            AppArg::uint(1),
+            AppArg::address(&app_call_account()),
+        ]);
+        assert!(res.is_ok());
+        let res = call(vec![
+            AppArg::bytes(&[0; 2017]),
+            AppArg::address(&app_call_account()),
+        ]);
+        assert!(matches!(
+            res,
+            Err(TransactionError::AppArgumentsTooLong {
+                length: 2049,
+                max: 2048
+            })
+        ));
+
+        // also the raw arguments, and on creation
+        let create = CreateApplication::new(
+            app_call_sender(),
+            approve_program(),
+            approve_program(),
+            StateSchema {
+                number_ints: 0,
+                number_byteslices: 0,
+            },
+            StateSchema {
+                number_ints: 0,
+                number_byteslices: 0,
+            },
+        )
+        .app_arguments(vec![vec![]; 17]);
+        assert!(matches!(
+            build_txn(create.build()),
+            Err(TransactionError::TooManyAppArguments { .. })
+        ));
+    }
+}
@@ -26,4 +26,40 @@ pub enum TransactionError {
     MnemonicError(#[from] algonaut_crypto::error::CryptoError),
     #[error("Deserialization error: {0}")]
     Deserialization(String),
+    #[error("Unknown field in inspect JSON: `{0}`")]
+    UnknownInspectField(String),
+    #[error("Invalid payment URI: {0}")]
+    InvalidPaymentUri(String),
+    #[error("Asset configuration params are empty, which destroys the asset.")]
+    EmptyAssetParams,
+    #[error("Invalid asset params: the {field} is {length} bytes, at most {max} are allowed.")]
+    AssetParamTooLong {
+        field: &'static str,
+        length: usize,
+        max: usize,
+    },
+    #[error("Invalid asset metadata hash of {length} bytes: it must be 32 bytes.")]
+    InvalidAssetMetadataHash { length: usize },
+    #[error("Invalid asset decimals {decimals}: at most {max} are allowed.")]
+    InvalidAssetDecimals { decimals: u32, max: u32 },
+    #[error("Invalid validity window {first_valid}..={last_valid}: the last valid round must be at most {max} rounds after the first valid round.")]
+    InvalidValidityWindow {
+        first_valid: u64,
+        last_valid: u64,
+        max: u64,
+    },
+    #[error("Invalid note of {length} bytes: at most {max} are allowed.")]
+    NoteTooLong { length: usize, max: usize },
+    #[error("Note encoding error: {0}")]
+    NoteEncoding(String),
+    #[error("Invalid application call: {0}")]
+    InvalidApplicationCall(String),
+    #[error("Invalid application call: {count} arguments, at most {max} are allowed.")]
+    TooManyAppArguments { count: usize, max: usize },
+    #[error("Invalid application call: {length} bytes of arguments, at most {max} are allowed.")]
+    AppArgumentsTooLong { length: usize, max: usize },
+    #[error("Transaction {index} of the group already has a group id.")]
+    AlreadyGrouped { index: usize },
+    #[error("Transaction {index} of the group doesn't have the group id of the group.")]
+    GroupIdMismatch { index: usize },
 }
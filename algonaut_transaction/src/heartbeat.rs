@@ -0,0 +1,67 @@
+//! Fields of heartbeat transactions.
+//!
+//! Heartbeats are sent on behalf of online accounts (by their node, or by anyone when the account
+//! is challenged) to prove that the participation keys of the account are still in use, so the
+//! account isn't suspended. These types mirror the msgpack encoding of the node, so heartbeat
+//! transactions can be decoded and re-encoded (e.g. to verify their id) without loss.
+//!
+//! Fields are sorted by their encoded name and zero values are omitted, as required by the
+//! canonical encoding.
+
+use serde::{Deserialize, Serialize};
+
+/// The proof of a heartbeat: a signature of the heartbeat seed with the participation keys of the
+/// account, in the one-time signature scheme of the votes.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct HeartbeatProof {
+    /// Public key of the round of the signature.
+    #[serde(
+        rename = "p",
+        default,
+        with = "serde_bytes",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub public_key: Vec<u8>,
+
+    /// Signature of the round key by the batch key.
+    #[serde(
+        rename = "p1s",
+        default,
+        with = "serde_bytes",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub public_key_1_signature: Vec<u8>,
+
+    /// Batch key, signed by the vote key of the account.
+    #[serde(
+        rename = "p2",
+        default,
+        with = "serde_bytes",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub public_key_2: Vec<u8>,
+
+    /// Signature of the batch key by the vote key of the account.
+    #[serde(
+        rename = "p2s",
+        default,
+        with = "serde_bytes",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub public_key_2_signature: Vec<u8>,
+
+    /// Signature of the seed by the round key.
+    #[serde(
+        rename = "s",
+        default,
+        with = "serde_bytes",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub signature: Vec<u8>,
+}
+
+impl HeartbeatProof {
+    pub(crate) fn is_empty(&self) -> bool {
+        self == &HeartbeatProof::default()
+    }
+}
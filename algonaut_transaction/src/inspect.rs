@@ -0,0 +1,635 @@
+//! The JSON representation of transactions printed and read by `goal clerk inspect`.
+//!
+//! It mirrors the canonical msgpack encoding: the same keys, with zero values omitted, but with
+//! addresses as base32 strings and the other byte fields as base64 strings. The conversion goes
+//! through the msgpack encoding, with a schema of the keys telling addresses and bytes apart, so
+//! the two representations can't drift apart.
+
+use crate::{error::TransactionError, SignedTransaction, Transaction};
+use algonaut_core::Address;
+use data_encoding::BASE64;
+use serde::{
+    de::{self, MapAccess, SeqAccess, Visitor},
+    ser::{SerializeMap, SerializeSeq},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use serde_json::{Map, Value as Json};
+use std::convert::{TryFrom, TryInto};
+use std::fmt;
+
+impl Transaction {
+    /// Encodes the transaction as the JSON printed by `goal clerk inspect`: `{"txn": {...}}`.
+    pub fn to_inspect_json(&self) -> Result<String, TransactionError> {
+        to_inspect_json(&TransactionEnvelope { txn: self }, transaction_envelope)
+    }
+
+    /// Decodes a transaction from the JSON printed by `goal clerk inspect`.
+    ///
+    /// Unknown keys are rejected, rather than silently dropped.
+    pub fn from_inspect_json(json: &str) -> Result<Transaction, TransactionError> {
+        from_inspect_json::<OwnedTransactionEnvelope>(json, transaction_envelope)
+            .map(|envelope| envelope.txn)
+    }
+}
+
+impl SignedTransaction {
+    /// Encodes the signed transaction as the JSON printed by `goal clerk inspect`:
+    /// `{"sig": ..., "txn": {...}}`, with `msig` or `lsig` in place of `sig` when applicable.
+    pub fn to_inspect_json(&self) -> Result<String, TransactionError> {
+        to_inspect_json(self, signed_transaction_envelope)
+    }
+
+    /// Decodes a signed transaction from the JSON printed by `goal clerk inspect`.
+    ///
+    /// Unknown keys are rejected, rather than silently dropped.
+    pub fn from_inspect_json(json: &str) -> Result<SignedTransaction, TransactionError> {
+        from_inspect_json(json, signed_transaction_envelope)
+    }
+}
+
+/// The inspect JSON of an unsigned transaction wraps it, like a signed transaction.
+#[derive(Serialize)]
+struct TransactionEnvelope<'a> {
+    txn: &'a Transaction,
+}
+
+#[derive(Deserialize)]
+struct OwnedTransactionEnvelope {
+    txn: Transaction,
+}
+
+fn to_inspect_json<T: Serialize>(value: &T, schema: Schema) -> Result<String, TransactionError> {
+    let msg_pack = rmp_serde::to_vec_named(value)?;
+    let value: MsgPack = rmp_serde::from_slice(&msg_pack)
+        .map_err(|e| TransactionError::Deserialization(e.to_string()))?;
+    let json = to_json(value, Field::Object(schema), "")?;
+    serde_json::to_string_pretty(&json)
+        .map_err(|e| TransactionError::Deserialization(e.to_string()))
+}
+
+fn from_inspect_json<T>(json: &str, schema: Schema) -> Result<T, TransactionError>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    let json: Json =
+        serde_json::from_str(json).map_err(|e| TransactionError::Deserialization(e.to_string()))?;
+    let value = from_json(json, Field::Object(schema), "")?;
+    let msg_pack = rmp_serde::to_vec(&value)?;
+    rmp_serde::from_slice(&msg_pack).map_err(|e| TransactionError::Deserialization(e.to_string()))
+}
+
+/// Maps the keys of a msgpack map to the kind of their value.
+type Schema = fn(&str) -> Option<Field>;
+
+#[derive(Clone, Copy)]
+enum Field {
+    Uint,
+    Bool,
+    Str,
+    Bytes,
+    Address,
+    Uints,
+    BytesList,
+    Addresses,
+    Object(Schema),
+    Objects(Schema),
+    /// Keys of the codec which don't have an inspect representation yet.
+    Unsupported,
+}
+
+fn transaction_envelope(key: &str) -> Option<Field> {
+    match key {
+        "txn" => Some(Field::Object(transaction)),
+        _ => None,
+    }
+}
+
+fn signed_transaction_envelope(key: &str) -> Option<Field> {
+    match key {
+        "sig" => Some(Field::Bytes),
+        "msig" => Some(Field::Object(multisig)),
+        "lsig" => Some(Field::Object(logic_sig)),
+        "txn" => Some(Field::Object(transaction)),
+        _ => None,
+    }
+}
+
+fn transaction(key: &str) -> Option<Field> {
+    Some(match key {
+        "aamt" | "amt" | "apan" | "apep" | "apid" | "caid" | "faid" | "fee" | "fv" | "lv"
+        | "sptype" | "votefst" | "votekd" | "votelst" | "xaid" => Field::Uint,
+        "afrz" | "nonpart" => Field::Bool,
+        "gen" | "type" => Field::Str,
+        "apap" | "apsu" | "gh" | "grp" | "lx" | "note" | "selkey" | "votekey" => Field::Bytes,
+        "aclose" | "arcv" | "asnd" | "close" | "fadd" | "rcv" | "rekey" | "snd" => Field::Address,
+        "apas" | "apfa" => Field::Uints,
+        "apaa" => Field::BytesList,
+        "apat" => Field::Addresses,
+        "apar" => Field::Object(asset_params),
+        "apgs" | "apls" => Field::Object(state_schema),
+        "sp" | "spmsg" => Field::Unsupported,
+        _ => return None,
+    })
+}
+
+fn asset_params(key: &str) -> Option<Field> {
+    Some(match key {
+        "dc" | "t" => Field::Uint,
+        "df" => Field::Bool,
+        "an" | "au" | "un" => Field::Str,
+        "am" => Field::Bytes,
+        "c" | "f" | "m" | "r" => Field::Address,
+        _ => return None,
+    })
+}
+
+fn state_schema(key: &str) -> Option<Field> {
+    match key {
+        "nbs" | "nui" => Some(Field::Uint),
+        _ => None,
+    }
+}
+
+fn multisig(key: &str) -> Option<Field> {
+    Some(match key {
+        "subsig" => Field::Objects(multisig_subsig),
+        "thr" | "v" => Field::Uint,
+        _ => return None,
+    })
+}
+
+fn multisig_subsig(key: &str) -> Option<Field> {
+    match key {
+        "pk" | "s" => Some(Field::Bytes),
+        _ => None,
+    }
+}
+
+fn logic_sig(key: &str) -> Option<Field> {
+    Some(match key {
+        "arg" => Field::BytesList,
+        "l" | "sig" => Field::Bytes,
+        "msig" => Field::Object(multisig),
+        _ => return None,
+    })
+}
+
+fn child_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_owned()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+fn invalid(path: &str, expected: &str) -> TransactionError {
+    TransactionError::Deserialization(format!("`{}`: expected {}", path, expected))
+}
+
+fn to_json(value: MsgPack, field: Field, path: &str) -> Result<Json, TransactionError> {
+    Ok(match (field, value) {
+        (Field::Uint, MsgPack::Uint(n)) => Json::from(n),
+        (Field::Bool, MsgPack::Bool(b)) => Json::from(b),
+        (Field::Str, MsgPack::Str(s)) => Json::from(s),
+        (Field::Bytes, value) => Json::from(BASE64.encode(&msg_pack_bytes(value, path)?)),
+        (Field::Address, value) => {
+            let bytes = msg_pack_bytes(value, path)?;
+            let address: [u8; 32] = bytes.try_into().map_err(|_| invalid(path, "an address"))?;
+            Json::from(Address(address).to_string())
+        }
+        (Field::Uints, MsgPack::Array(values)) => {
+            Json::Array(to_json_items(values, Field::Uint, path)?)
+        }
+        (Field::BytesList, MsgPack::Array(values)) => {
+            Json::Array(to_json_items(values, Field::Bytes, path)?)
+        }
+        (Field::Addresses, MsgPack::Array(values)) => {
+            Json::Array(to_json_items(values, Field::Address, path)?)
+        }
+        (Field::Objects(schema), MsgPack::Array(values)) => {
+            Json::Array(to_json_items(values, Field::Object(schema), path)?)
+        }
+        (Field::Object(schema), MsgPack::Map(entries)) => {
+            let mut map = Map::new();
+            for (key, value) in entries {
+                let path = child_path(path, &key);
+                let field = schema(&key)
+                    .ok_or_else(|| TransactionError::UnknownInspectField(path.clone()))?;
+                map.insert(key, to_json(value, field, &path)?);
+            }
+            Json::Object(map)
+        }
+        (Field::Unsupported, _) => {
+            return Err(TransactionError::Deserialization(format!(
+                "`{}` isn't supported in inspect JSON",
+                path
+            )))
+        }
+        _ => return Err(invalid(path, "a value matching the transaction codec")),
+    })
+}
+
+fn to_json_items(
+    values: Vec<MsgPack>,
+    field: Field,
+    path: &str,
+) -> Result<Vec<Json>, TransactionError> {
+    values
+        .into_iter()
+        .map(|value| to_json(value, field, path))
+        .collect()
+}
+
+/// Bytes are binary in the codec, except for a few fields encoded as arrays of integers.
+fn msg_pack_bytes(value: MsgPack, path: &str) -> Result<Vec<u8>, TransactionError> {
+    match value {
+        MsgPack::Bin(bytes) => Ok(bytes),
+        MsgPack::Array(values) => values
+            .into_iter()
+            .map(|value| match value {
+                MsgPack::Uint(n) if n <= u8::MAX as u64 => Ok(n as u8),
+                _ => Err(invalid(path, "bytes")),
+            })
+            .collect(),
+        _ => Err(invalid(path, "bytes")),
+    }
+}
+
+fn from_json(json: Json, field: Field, path: &str) -> Result<MsgPack, TransactionError> {
+    Ok(match (field, json) {
+        (Field::Uint, Json::Number(n)) => MsgPack::Uint(
+            n.as_u64()
+                .ok_or_else(|| invalid(path, "an unsigned integer"))?,
+        ),
+        (Field::Uint, _) => return Err(invalid(path, "an unsigned integer")),
+        (Field::Bool, Json::Bool(b)) => MsgPack::Bool(b),
+        (Field::Bool, _) => return Err(invalid(path, "a boolean")),
+        (Field::Str, Json::String(s)) => MsgPack::Str(s),
+        (Field::Str, _) => return Err(invalid(path, "a string")),
+        (Field::Bytes, Json::String(s)) => MsgPack::Bin(
+            BASE64
+                .decode(s.as_bytes())
+                .map_err(|_| invalid(path, "base64 bytes"))?,
+        ),
+        (Field::Bytes, _) => return Err(invalid(path, "base64 bytes")),
+        (Field::Address, Json::String(s)) => MsgPack::Bin(
+            s.parse::<Address>()
+                .map_err(|e| TransactionError::Deserialization(format!("`{}`: {}", path, e)))?
+                .0
+                .to_vec(),
+        ),
+        (Field::Address, _) => return Err(invalid(path, "an address")),
+        (Field::Uints, Json::Array(items)) => {
+            MsgPack::Array(from_json_items(items, Field::Uint, path)?)
+        }
+        (Field::BytesList, Json::Array(items)) => {
+            MsgPack::Array(from_json_items(items, Field::Bytes, path)?)
+        }
+        (Field::Addresses, Json::Array(items)) => {
+            MsgPack::Array(from_json_items(items, Field::Address, path)?)
+        }
+        (Field::Objects(schema), Json::Array(items)) => {
+            MsgPack::Array(from_json_items(items, Field::Object(schema), path)?)
+        }
+        (Field::Uints | Field::BytesList | Field::Addresses | Field::Objects(_), _) => {
+            return Err(invalid(path, "an array"))
+        }
+        (Field::Object(schema), Json::Object(map)) => {
+            let mut entries = Vec::with_capacity(map.len());
+            for (key, value) in map {
+                let path = child_path(path, &key);
+                let field = schema(&key)
+                    .ok_or_else(|| TransactionError::UnknownInspectField(path.clone()))?;
+                entries.push((key, from_json(value, field, &path)?));
+            }
+            // The codec expects the keys sorted.
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            MsgPack::Map(entries)
+        }
+        (Field::Object(_), _) => return Err(invalid(path, "an object")),
+        (Field::Unsupported, _) => {
+            return Err(TransactionError::Deserialization(format!(
+                "`{}` isn't supported in inspect JSON",
+                path
+            )))
+        }
+    })
+}
+
+fn from_json_items(
+    items: Vec<Json>,
+    field: Field,
+    path: &str,
+) -> Result<Vec<MsgPack>, TransactionError> {
+    items
+        .into_iter()
+        .map(|item| from_json(item, field, path))
+        .collect()
+}
+
+/// The subset of msgpack values used by the transaction codec.
+#[derive(Debug, Clone, PartialEq)]
+enum MsgPack {
+    Uint(u64),
+    Bool(bool),
+    Str(String),
+    Bin(Vec<u8>),
+    Array(Vec<MsgPack>),
+    Map(Vec<(String, MsgPack)>),
+}
+
+impl Serialize for MsgPack {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            MsgPack::Uint(n) => serializer.serialize_u64(*n),
+            MsgPack::Bool(b) => serializer.serialize_bool(*b),
+            MsgPack::Str(s) => serializer.serialize_str(s),
+            MsgPack::Bin(bytes) => serializer.serialize_bytes(bytes),
+            MsgPack::Array(values) => {
+                let mut seq = serializer.serialize_seq(Some(values.len()))?;
+                for value in values {
+                    seq.serialize_element(value)?;
+                }
+                seq.end()
+            }
+            MsgPack::Map(entries) => {
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+                for (key, value) in entries {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for MsgPack {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(MsgPackVisitor)
+    }
+}
+
+struct MsgPackVisitor;
+
+impl<'de> Visitor<'de> for MsgPackVisitor {
+    type Value = MsgPack;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a msgpack value of the transaction codec")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(MsgPack::Bool(v))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(MsgPack::Uint(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        u64::try_from(v)
+            .map(MsgPack::Uint)
+            .map_err(|_| E::custom(format!("unexpected negative integer {}", v)))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(MsgPack::Str(v.to_owned()))
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(MsgPack::Bin(v.to_vec()))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut values = vec![];
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+        Ok(MsgPack::Array(values))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut entries = vec![];
+        while let Some(entry) = map.next_entry()? {
+            entries.push(entry);
+        }
+        Ok(MsgPack::Map(entries))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{CallApplication, CreateAsset, Pay, TransferAsset, TxnBuilder, TxnFee};
+    use crate::TransactionType;
+    use algonaut_core::{MicroAlgos, MultisigSignature, MultisigSubsig, Round};
+    use algonaut_crypto::{Ed25519PublicKey, HashDigest, Signature};
+
+    fn address(i: u8) -> Address {
+        Address([i; 32])
+    }
+
+    fn txn(txn_type: TransactionType) -> TxnBuilder {
+        TxnBuilder::new(
+            TxnFee::Fixed(MicroAlgos(1000)),
+            Round(1000),
+            Round(2000),
+            HashDigest([0; 32]),
+            txn_type,
+        )
+    }
+
+    fn payment() -> Transaction {
+        txn(Pay::new(address(1), address(2), MicroAlgos(1_500_000)).build())
+            .genesis_id("testnet-v1.0".to_owned())
+            .note(b"hello".to_vec())
+            .build()
+            .unwrap()
+    }
+
+    fn assert_json_eq(expected: &str, actual: &str) {
+        assert_eq!(
+            serde_json::from_str::<Json>(expected).unwrap(),
+            serde_json::from_str::<Json>(actual).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_payment() {
+        let txn = payment();
+        let json = txn.to_inspect_json().unwrap();
+        assert_json_eq(PAYMENT, &json);
+        assert_eq!(txn, Transaction::from_inspect_json(PAYMENT).unwrap());
+    }
+
+    #[test]
+    fn test_asset_transfer() {
+        let txn = txn(TransferAsset::new(address(1), 123, 10, address(2)).build())
+            .build()
+            .unwrap();
+
+        let json = txn.to_inspect_json().unwrap();
+        assert_json_eq(ASSET_TRANSFER, &json);
+        assert_eq!(txn, Transaction::from_inspect_json(ASSET_TRANSFER).unwrap());
+    }
+
+    #[test]
+    fn test_application_call() {
+        let txn = txn(CallApplication::new(address(1), 5)
+            .app_arguments(vec![b"add".to_vec(), vec![0, 0, 0, 1]])
+            .accounts(vec![address(2)])
+            .foreign_apps(vec![6])
+            .foreign_assets(vec![7])
+            .build())
+        .build()
+        .unwrap();
+
+        let json = txn.to_inspect_json().unwrap();
+        assert_json_eq(APPLICATION_CALL, &json);
+        assert_eq!(
+            txn,
+            Transaction::from_inspect_json(APPLICATION_CALL).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_asset_configuration_round_trip() {
+        let txn = txn(CreateAsset::new(address(1), 1000, 2, false)
+            .unit_name("UN".to_owned())
+            .asset_name("Asset".to_owned())
+            .url("https://example.com".to_owned())
+            .meta_data_hash(vec![3; 32])
+            .manager(address(2))
+            .build())
+        .build()
+        .unwrap();
+
+        let json = txn.to_inspect_json().unwrap();
+        assert_eq!(txn, Transaction::from_inspect_json(&json).unwrap());
+    }
+
+    #[test]
+    fn test_signed_transaction() {
+        let signed = SignedTransaction {
+            transaction: payment(),
+            transaction_id: "".to_owned(),
+            sig: crate::transaction::TransactionSignature::Single(Signature([4; 64])),
+        };
+
+        let json = signed.to_inspect_json().unwrap();
+        let parsed = SignedTransaction::from_inspect_json(&json).unwrap();
+        assert_eq!(signed.transaction, parsed.transaction);
+        assert_eq!(signed.sig, parsed.sig);
+    }
+
+    #[test]
+    fn test_multisig_transaction() {
+        let msig = MultisigSignature {
+            version: 1,
+            threshold: 1,
+            subsigs: vec![
+                MultisigSubsig {
+                    key: Ed25519PublicKey([1; 32]),
+                    sig: Some(Signature([4; 64])),
+                },
+                MultisigSubsig {
+                    key: Ed25519PublicKey([2; 32]),
+                    sig: None,
+                },
+            ],
+        };
+        let signed = SignedTransaction {
+            transaction: payment(),
+            transaction_id: "".to_owned(),
+            sig: crate::transaction::TransactionSignature::Multi(msig),
+        };
+
+        let json = signed.to_inspect_json().unwrap();
+        let parsed = SignedTransaction::from_inspect_json(&json).unwrap();
+        assert_eq!(signed.sig, parsed.sig);
+    }
+
+    #[test]
+    fn test_unknown_field_is_rejected() {
+        let json = PAYMENT.replace("\"fee\"", "\"feee\"");
+        match Transaction::from_inspect_json(&json) {
+            Err(TransactionError::UnknownInspectField(key)) => assert_eq!("txn.feee", key),
+            res => panic!("unexpected result: {:?}", res),
+        }
+
+        let json = PAYMENT.replace("\"txn\"", "\"tx\"");
+        match Transaction::from_inspect_json(&json) {
+            Err(TransactionError::UnknownInspectField(key)) => assert_eq!("tx", key),
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn test_invalid_address_is_rejected() {
+        let json = PAYMENT.replace(
+            "AEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEA5RCDXMI",
+            "AEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEA5RCDXMJ",
+        );
+        assert!(Transaction::from_inspect_json(&json).is_err());
+    }
+
+    const PAYMENT: &str = r#"{
+  "txn": {
+    "amt": 1500000,
+    "fee": 1000,
+    "fv": 1000,
+    "gen": "testnet-v1.0",
+    "gh": "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=",
+    "lv": 2000,
+    "note": "aGVsbG8=",
+    "rcv": "AIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBMXPWWNQ",
+    "snd": "AEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEA5RCDXMI",
+    "type": "pay"
+  }
+}"#;
+
+    const ASSET_TRANSFER: &str = r#"{
+  "txn": {
+    "aamt": 10,
+    "arcv": "AIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBMXPWWNQ",
+    "fee": 1000,
+    "fv": 1000,
+    "gh": "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=",
+    "lv": 2000,
+    "snd": "AEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEA5RCDXMI",
+    "type": "axfer",
+    "xaid": 123
+  }
+}"#;
+
+    const APPLICATION_CALL: &str = r#"{
+  "txn": {
+    "apaa": [
+      "YWRk",
+      "AAAAAQ=="
+    ],
+    "apas": [
+      7
+    ],
+    "apat": [
+      "AIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBMXPWWNQ"
+    ],
+    "apfa": [
+      6
+    ],
+    "apid": 5,
+    "fee": 1000,
+    "fv": 1000,
+    "gh": "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=",
+    "lv": 2000,
+    "snd": "AEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEA5RCDXMI",
+    "type": "appl"
+  }
+}"#;
+}
@@ -4,12 +4,19 @@ pub mod auction;
 pub mod builder;
 pub mod contract_account;
 pub mod error;
+pub mod heartbeat;
+mod inspect;
+pub mod state_proof;
+mod summary;
+pub mod template;
 pub mod transaction;
 pub mod tx_group;
 pub mod url;
 
 pub use builder::{
-    AcceptAsset, ClawbackAsset, CreateApplication, CreateAsset, FreezeAsset, Pay, RegisterKey,
-    TransferAsset, TxnBuilder,
+    AcceptAsset, AppArg, AssetRoleChange, AssetRoleChanges, AssetRoles, ClawbackAsset,
+    ConfigureAsset, CreateApplication, CreateAsset, DestroyAsset, FreezeAsset, Pay, RegisterKey,
+    TransferAsset, TxnBuilder, MAX_VALIDITY_WINDOW,
 };
+pub use template::{TransactionTemplate, Violation};
 pub use transaction::{SignedTransaction, Transaction, TransactionType};
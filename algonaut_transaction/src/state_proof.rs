@@ -0,0 +1,269 @@
+//! Fields of state proof transactions.
+//!
+//! State proofs are issued by the protocol (not by users) and attest the block headers of a range
+//! of rounds. These types mirror the msgpack encoding of the node, so state proof transactions can be
+//! decoded and re-encoded (e.g. to verify their id) without loss.
+//!
+//! Fields are sorted by their encoded name and zero values are omitted, as required by the
+//! canonical encoding.
+
+use algonaut_core::Round;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The message attested by a state proof.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct StateProofMessage {
+    /// Natural log of the proven weight.
+    #[serde(rename = "P", default, skip_serializing_if = "is_zero")]
+    pub ln_proven_weight: u64,
+
+    /// Commitment to the block headers of the attested rounds.
+    #[serde(
+        rename = "b",
+        default,
+        with = "serde_bytes",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub block_headers_commitment: Vec<u8>,
+
+    /// First round the message attests to.
+    #[serde(rename = "f", default, skip_serializing_if = "is_zero_round")]
+    pub first_attested_round: Round,
+
+    /// Last round the message attests to.
+    #[serde(rename = "l", default, skip_serializing_if = "is_zero_round")]
+    pub last_attested_round: Round,
+
+    /// Commitment to the voters for the next state proof.
+    #[serde(
+        rename = "v",
+        default,
+        with = "serde_bytes",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub voters_commitment: Vec<u8>,
+}
+
+/// A state proof: a compact certificate that a weighted majority of participants signed a message.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct StateProof {
+    /// Proofs of the revealed participants.
+    #[serde(
+        rename = "P",
+        default,
+        skip_serializing_if = "MerkleArrayProof::is_empty"
+    )]
+    pub part_proofs: MerkleArrayProof,
+
+    /// Proofs of the revealed signatures.
+    #[serde(
+        rename = "S",
+        default,
+        skip_serializing_if = "MerkleArrayProof::is_empty"
+    )]
+    pub sig_proofs: MerkleArrayProof,
+
+    /// Commitment to the signatures.
+    #[serde(
+        rename = "c",
+        default,
+        with = "serde_bytes",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub sig_commit: Vec<u8>,
+
+    /// Sequence of reveal positions.
+    #[serde(rename = "pr", default, skip_serializing_if = "Vec::is_empty")]
+    pub positions_to_reveal: Vec<u64>,
+
+    /// Revealed participants and their signatures, by position.
+    #[serde(rename = "r", default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub reveals: BTreeMap<u64, Reveal>,
+
+    /// Salt version of the merkle signatures.
+    #[serde(rename = "v", default, skip_serializing_if = "is_zero_u8")]
+    pub merkle_signature_salt_version: u8,
+
+    /// Total weight of the signers.
+    #[serde(rename = "w", default, skip_serializing_if = "is_zero")]
+    pub signed_weight: u64,
+}
+
+/// A merkle array proof.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MerkleArrayProof {
+    /// Hash function used to build the tree.
+    #[serde(rename = "hsh", default, skip_serializing_if = "HashFactory::is_empty")]
+    pub hash_factory: HashFactory,
+
+    /// Sequence of hashes from the leaf to the root.
+    #[serde(
+        rename = "pth",
+        default,
+        with = "bytes_vec",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub path: Vec<Vec<u8>>,
+
+    /// Depth of the tree.
+    #[serde(rename = "td", default, skip_serializing_if = "is_zero_u8")]
+    pub tree_depth: u8,
+}
+
+impl MerkleArrayProof {
+    fn is_empty(&self) -> bool {
+        self == &MerkleArrayProof::default()
+    }
+}
+
+/// The hash function used by a merkle array.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct HashFactory {
+    /// Type of the hash function.
+    #[serde(rename = "t", default, skip_serializing_if = "is_zero_u16")]
+    pub hash_type: u16,
+}
+
+impl HashFactory {
+    fn is_empty(&self) -> bool {
+        self.hash_type == 0
+    }
+}
+
+/// A participant revealed by a state proof, together with its signature.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Reveal {
+    /// The participant.
+    #[serde(rename = "p", default)]
+    pub participant: Participant,
+
+    /// The signature slot.
+    #[serde(rename = "s", default)]
+    pub sig_slot: SigSlot,
+}
+
+/// A state proof participant.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Participant {
+    /// The participant's verifier.
+    #[serde(rename = "p", default)]
+    pub verifier: ParticipantVerifier,
+
+    /// The participant's weight.
+    #[serde(rename = "w", default, skip_serializing_if = "is_zero")]
+    pub weight: u64,
+}
+
+/// The verifier of a state proof participant.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ParticipantVerifier {
+    /// Commitment to the participant's merkle signature keys.
+    #[serde(
+        rename = "cmt",
+        default,
+        with = "serde_bytes",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub commitment: Vec<u8>,
+
+    /// Key lifetime.
+    #[serde(rename = "lf", default, skip_serializing_if = "is_zero")]
+    pub key_lifetime: u64,
+}
+
+/// A signature slot of a state proof.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SigSlot {
+    /// The total weight of the signatures in the lower-numbered slots.
+    #[serde(rename = "l", default, skip_serializing_if = "is_zero")]
+    pub lower_sig_weight: u64,
+
+    /// The signature.
+    #[serde(rename = "s", default)]
+    pub signature: MerkleSignature,
+}
+
+/// A merkle signature of a state proof participant.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MerkleSignature {
+    /// Index of the key in the participant's merkle tree.
+    #[serde(rename = "idx", default, skip_serializing_if = "is_zero")]
+    pub vector_commitment_index: u64,
+
+    /// Proof of the key in the participant's merkle tree.
+    #[serde(
+        rename = "prf",
+        default,
+        skip_serializing_if = "MerkleArrayProof::is_empty"
+    )]
+    pub proof: MerkleArrayProof,
+
+    /// The falcon signature.
+    #[serde(
+        rename = "sig",
+        default,
+        with = "serde_bytes",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub signature: Vec<u8>,
+
+    /// The falcon verifying key.
+    #[serde(rename = "vkey", default)]
+    pub verifying_key: FalconVerifier,
+}
+
+/// A falcon verifying key.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct FalconVerifier {
+    #[serde(
+        rename = "k",
+        default,
+        with = "serde_bytes",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub public_key: Vec<u8>,
+}
+
+fn is_zero(n: &u64) -> bool {
+    *n == 0
+}
+
+fn is_zero_u8(n: &u8) -> bool {
+    *n == 0
+}
+
+fn is_zero_u16(n: &u16) -> bool {
+    *n == 0
+}
+
+fn is_zero_round(round: &Round) -> bool {
+    round.0 == 0
+}
+
+/// (De)serializes a list of byte arrays as msgpack bin values.
+mod bytes_vec {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use serde_bytes::{ByteBuf, Bytes};
+
+    pub fn serialize<S>(bytes_vec: &[Vec<u8>], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        bytes_vec
+            .iter()
+            .map(|bytes| Bytes::new(bytes))
+            .collect::<Vec<_>>()
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<Vec<u8>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Vec::<ByteBuf>::deserialize(deserializer)?
+            .into_iter()
+            .map(ByteBuf::into_vec)
+            .collect())
+    }
+}
@@ -0,0 +1,541 @@
+use crate::transaction::{
+    ApplicationCallOnComplete, ApplicationCallTransaction, AssetConfigurationTransaction,
+    KeyRegistration, SignedTransaction, Transaction, TransactionSignature, TransactionType,
+};
+use algonaut_core::{Address, LogicSignature, MultisigSignature};
+use data_encoding::BASE64;
+use std::fmt::{self, Display, Formatter};
+
+/// Number of base64 characters shown of byte fields (e.g. the group), followed by "...".
+const TRUNCATED_BASE64_LEN: usize = 8;
+
+impl Transaction {
+    /// A compact, human-readable description of the transaction, e.g. for logs.
+    ///
+    /// It contains the type, the accounts, assets or applications involved, the amount (in Algos or
+    /// asset base units), the fee, the validity window, the length of the note and the group and
+    /// lease, if any. Closing the account or an asset holding and rekeying are highlighted with "!!".
+    pub fn summary(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl SignedTransaction {
+    /// The [summary](Transaction::summary) of the transaction, followed by its id and kind of signature.
+    pub fn summary(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Display for Transaction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        fmt_txn_type(&self.txn_type, f)?;
+        write!(
+            f,
+            " | fee {} Algos | valid {}..{}",
+            self.fee.to_algos_string(),
+            self.first_valid.0,
+            self.last_valid.0
+        )?;
+        if let Some(note) = self.note.as_ref().filter(|note| !note.is_empty()) {
+            write!(f, " | note {} bytes", note.len())?;
+        }
+        if let Some(group) = self.group {
+            write!(f, " | group {}", truncated_base64(&group.0))?;
+        }
+        if let Some(lease) = self.lease {
+            write!(f, " | lease {}", truncated_base64(&lease.0))?;
+        }
+        if let Some(rekey_to) = self.rekey_to {
+            write!(f, " | !! REKEY TO {}", rekey_to)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for SignedTransaction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} | id {} | ", self.transaction, self.transaction_id)?;
+        match &self.sig {
+            TransactionSignature::Single(_) => write!(f, "signed"),
+            TransactionSignature::Multi(msig) => write!(f, "multisig {}", multisig_summary(msig)),
+            TransactionSignature::Logic(lsig) => match &lsig.sig {
+                LogicSignature::ContractAccount => write!(f, "logic sig (contract account)"),
+                LogicSignature::DelegatedSig(_) => write!(f, "logic sig (delegated)"),
+                LogicSignature::DelegatedMultiSig(msig) => {
+                    write!(
+                        f,
+                        "logic sig (delegated multisig {})",
+                        multisig_summary(msig)
+                    )
+                }
+            },
+        }
+    }
+}
+
+fn fmt_txn_type(txn_type: &TransactionType, f: &mut Formatter<'_>) -> fmt::Result {
+    match txn_type {
+        TransactionType::Payment(t) => {
+            write!(
+                f,
+                "pay {} Algos from {} to {}",
+                t.amount.to_algos_string(),
+                t.sender,
+                t.receiver
+            )?;
+            if let Some(close_to) = t.close_remainder_to {
+                write!(f, " | !! CLOSE ACCOUNT TO {}", close_to)?;
+            }
+            Ok(())
+        }
+        TransactionType::KeyRegistration(t) => fmt_key_registration(t, f),
+        TransactionType::AssetConfigurationTransaction(t) => fmt_asset_configuration(t, f),
+        TransactionType::AssetTransferTransaction(t) => {
+            write!(
+                f,
+                "axfer {} units of asset {} from {} to {}",
+                t.amount, t.xfer, t.sender, t.receiver
+            )?;
+            if let Some(close_to) = t.close_to {
+                write!(f, " | !! CLOSE ASSET TO {}", close_to)?;
+            }
+            Ok(())
+        }
+        TransactionType::AssetAcceptTransaction(t) => {
+            write!(f, "axfer opt-in to asset {} by {}", t.xfer, t.sender)
+        }
+        TransactionType::AssetClawbackTransaction(t) => {
+            write!(
+                f,
+                "axfer clawback {} units of asset {} from {} to {} by {}",
+                t.asset_amount, t.xfer, t.asset_sender, t.asset_receiver, t.sender
+            )?;
+            if let Some(close_to) = t.asset_close_to {
+                write!(f, " | !! CLOSE ASSET TO {}", close_to)?;
+            }
+            Ok(())
+        }
+        TransactionType::AssetFreezeTransaction(t) => write!(
+            f,
+            "afrz {} asset {} of {} by {}",
+            if t.frozen { "freeze" } else { "unfreeze" },
+            t.asset_id,
+            t.freeze_account,
+            t.sender
+        ),
+        TransactionType::ApplicationCallTransaction(t) => fmt_application_call(t, f),
+        TransactionType::StateProofTransaction(t) => write!(
+            f,
+            "stpf state proof of rounds {}..{} by {}",
+            t.message.first_attested_round.0, t.message.last_attested_round.0, t.sender
+        ),
+        TransactionType::HeartbeatTransaction(t) => {
+            write!(f, "hb heartbeat of {} by {}", t.address, t.sender)
+        }
+        TransactionType::Unknown(t) => write!(f, "{} by {}", t.txn_type, t.sender),
+    }
+}
+
+fn fmt_key_registration(t: &KeyRegistration, f: &mut Formatter<'_>) -> fmt::Result {
+    match (t.vote_pk, t.vote_first, t.vote_last) {
+        (Some(_), Some(first), Some(last)) => write!(
+            f,
+            "keyreg online {} for rounds {}..{}",
+            t.sender, first.0, last.0
+        )?,
+        _ => write!(f, "keyreg offline {}", t.sender)?,
+    }
+    if t.nonparticipating == Some(true) {
+        write!(f, " | !! NONPARTICIPATING")?;
+    }
+    Ok(())
+}
+
+fn fmt_asset_configuration(
+    t: &AssetConfigurationTransaction,
+    f: &mut Formatter<'_>,
+) -> fmt::Result {
+    match (t.config_asset, &t.params) {
+        (None, params) => {
+            let params = params.as_ref();
+            write!(
+                f,
+                "acfg create asset {:?} ({}) of {} units with {} decimals by {}",
+                params.and_then(|p| p.asset_name.as_deref()).unwrap_or(""),
+                params.and_then(|p| p.unit_name.as_deref()).unwrap_or(""),
+                params.and_then(|p| p.total).unwrap_or(0),
+                params.and_then(|p| p.decimals).unwrap_or(0),
+                t.sender
+            )
+        }
+        (Some(asset_id), None) => write!(f, "acfg destroy asset {} by {}", asset_id, t.sender),
+        (Some(asset_id), Some(params)) => {
+            let role = |address: Option<Address>| {
+                address.map_or_else(|| "CLEARED".to_owned(), |address| address.to_string())
+            };
+            write!(
+                f,
+                "acfg configure asset {} by {}: manager {}, reserve {}, freeze {}, clawback {}",
+                asset_id,
+                t.sender,
+                role(params.manager),
+                role(params.reserve),
+                role(params.freeze),
+                role(params.clawback)
+            )
+        }
+    }
+}
+
+fn fmt_application_call(t: &ApplicationCallTransaction, f: &mut Formatter<'_>) -> fmt::Result {
+    let action = match t.on_complete {
+        ApplicationCallOnComplete::NoOp => "call",
+        ApplicationCallOnComplete::OptIn => "opt-in to",
+        ApplicationCallOnComplete::CloseOut => "close out of",
+        ApplicationCallOnComplete::ClearState => "clear state of",
+        ApplicationCallOnComplete::UpdateApplication => "update",
+        ApplicationCallOnComplete::DeleteApplication => "delete",
+    };
+    match t.app_id.filter(|id| *id != 0) {
+        Some(app_id) => write!(f, "appl {} app {} by {}", action, app_id, t.sender)?,
+        None => write!(f, "appl create app ({}) by {}", action, t.sender)?,
+    }
+    if let Some(args) = t.app_arguments.as_ref().filter(|args| !args.is_empty()) {
+        write!(f, " with {} args", args.len())?;
+    }
+    Ok(())
+}
+
+fn multisig_summary(msig: &MultisigSignature) -> String {
+    let signed = msig.subsigs.iter().filter(|s| s.sig.is_some()).count();
+    format!(
+        "{} of {} signed, threshold {}",
+        signed,
+        msig.subsigs.len(),
+        msig.threshold
+    )
+}
+
+fn truncated_base64(bytes: &[u8]) -> String {
+    let encoded = BASE64.encode(bytes);
+    match encoded.get(..TRUNCATED_BASE64_LEN) {
+        Some(prefix) if encoded.len() > TRUNCATED_BASE64_LEN => format!("{}...", prefix),
+        _ => encoded,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{
+        AcceptAsset, CallApplication, ClawbackAsset, CreateApplication, CreateAsset, DestroyAsset,
+        FreezeAsset, Pay, RegisterKey, TransferAsset, TxnBuilder, TxnFee, UpdateAsset,
+    };
+    use crate::state_proof::StateProofMessage;
+    use crate::transaction::{
+        HeartbeatTransaction, StateProofTransaction, StateSchema, UnknownTransaction,
+    };
+    use algonaut_core::{
+        CompiledTeal, MicroAlgos, MultisigSubsig, Round, SignedLogic, VotePk, VrfPk,
+    };
+    use algonaut_crypto::{Ed25519PublicKey, HashDigest, Signature};
+
+    const A1: &str = "AEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEA5RCDXMI";
+    const A2: &str = "AIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBMXPWWNQ";
+    const A3: &str = "AMBQGAYDAMBQGAYDAMBQGAYDAMBQGAYDAMBQGAYDAMBQGAYDAMB5DBBASI";
+    const FEE_AND_VALIDITY: &str = "fee 0.001000 Algos | valid 1000..2000";
+
+    fn address(i: u8) -> Address {
+        Address([i; 32])
+    }
+
+    fn txn(txn_type: TransactionType) -> TxnBuilder {
+        TxnBuilder::new(
+            TxnFee::Fixed(MicroAlgos(1000)),
+            Round(1000),
+            Round(2000),
+            HashDigest([0; 32]),
+            txn_type,
+        )
+    }
+
+    fn summary(txn_type: TransactionType) -> String {
+        txn(txn_type).build().unwrap().summary()
+    }
+
+    #[test]
+    fn test_payment_summary() {
+        assert_eq!(
+            format!(
+                "pay 1.500000 Algos from {} to {} | {}",
+                A1, A2, FEE_AND_VALIDITY
+            ),
+            summary(Pay::new(address(1), address(2), MicroAlgos(1_500_000)).build())
+        );
+        assert_eq!(
+            format!(
+                "pay 0.000000 Algos from {} to {} | !! CLOSE ACCOUNT TO {} | {}",
+                A1, A2, A3, FEE_AND_VALIDITY
+            ),
+            summary(
+                Pay::new(address(1), address(2), MicroAlgos(0))
+                    .close_remainder_to(address(3))
+                    .build()
+            )
+        );
+    }
+
+    #[test]
+    fn test_key_registration_summary() {
+        assert_eq!(
+            format!(
+                "keyreg online {} for rounds 100..200 | {}",
+                A1, FEE_AND_VALIDITY
+            ),
+            summary(
+                RegisterKey::online(
+                    address(1),
+                    VotePk([0; 32]),
+                    VrfPk([0; 32]),
+                    Round(100),
+                    Round(200),
+                    10,
+                )
+                .build()
+            )
+        );
+        assert_eq!(
+            format!("keyreg offline {} | {}", A1, FEE_AND_VALIDITY),
+            summary(RegisterKey::offline(address(1)).build())
+        );
+        assert_eq!(
+            format!(
+                "keyreg offline {} | !! NONPARTICIPATING | {}",
+                A1, FEE_AND_VALIDITY
+            ),
+            summary(RegisterKey::nonpartipating(address(1), true).build())
+        );
+    }
+
+    #[test]
+    fn test_asset_configuration_summary() {
+        assert_eq!(
+            format!(
+                "acfg create asset \"Gold\" (GLD) of 1000000 units with 2 decimals by {} | {}",
+                A1, FEE_AND_VALIDITY
+            ),
+            summary(
+                CreateAsset::new(address(1), 1_000_000, 2, false)
+                    .asset_name("Gold".to_owned())
+                    .unit_name("GLD".to_owned())
+                    .build()
+            )
+        );
+        assert_eq!(
+            format!(
+                "acfg configure asset 12 by {}: manager {}, reserve CLEARED, freeze CLEARED, clawback CLEARED | {}",
+                A1, A2, FEE_AND_VALIDITY
+            ),
+            summary(UpdateAsset::new(address(1), 12).manager(address(2)).build())
+        );
+        assert_eq!(
+            format!("acfg destroy asset 12 by {} | {}", A1, FEE_AND_VALIDITY),
+            summary(DestroyAsset::new(address(1), 12).build())
+        );
+    }
+
+    #[test]
+    fn test_asset_transfer_summary() {
+        assert_eq!(
+            format!(
+                "axfer 250 units of asset 12 from {} to {} | !! CLOSE ASSET TO {} | {}",
+                A1, A2, A3, FEE_AND_VALIDITY
+            ),
+            summary(
+                TransferAsset::new(address(1), 12, 250, address(2))
+                    .close_to(address(3))
+                    .build()
+            )
+        );
+        assert_eq!(
+            format!("axfer opt-in to asset 12 by {} | {}", A1, FEE_AND_VALIDITY),
+            summary(AcceptAsset::new(address(1), 12).build())
+        );
+        assert_eq!(
+            format!(
+                "axfer clawback 250 units of asset 12 from {} to {} by {} | {}",
+                A2, A3, A1, FEE_AND_VALIDITY
+            ),
+            summary(ClawbackAsset::new(address(1), 12, 250, address(2), address(3)).build())
+        );
+        assert_eq!(
+            format!(
+                "afrz freeze asset 12 of {} by {} | {}",
+                A2, A1, FEE_AND_VALIDITY
+            ),
+            summary(FreezeAsset::new(address(1), address(2), 12, true).build())
+        );
+        assert_eq!(
+            format!(
+                "afrz unfreeze asset 12 of {} by {} | {}",
+                A2, A1, FEE_AND_VALIDITY
+            ),
+            summary(FreezeAsset::new(address(1), address(2), 12, false).build())
+        );
+    }
+
+    #[test]
+    fn test_application_call_summary() {
+        assert_eq!(
+            format!(
+                "appl call app 34 by {} with 2 args | {}",
+                A1, FEE_AND_VALIDITY
+            ),
+            summary(
+                CallApplication::new(address(1), 34)
+                    .app_arguments(vec![vec![1], vec![2]])
+                    .build()
+            )
+        );
+        let schema = || StateSchema {
+            number_ints: 0,
+            number_byteslices: 0,
+        };
+        assert_eq!(
+            format!("appl create app (call) by {} | {}", A1, FEE_AND_VALIDITY),
+            summary(
+                CreateApplication::new(
+                    address(1),
+                    CompiledTeal(vec![1]),
+                    CompiledTeal(vec![1]),
+                    schema(),
+                    schema(),
+                )
+                .build()
+            )
+        );
+    }
+
+    #[test]
+    fn test_other_types_summary() {
+        assert_eq!(
+            format!(
+                "stpf state proof of rounds 24191745..24192000 by {} | {}",
+                A1, FEE_AND_VALIDITY
+            ),
+            summary(TransactionType::StateProofTransaction(
+                StateProofTransaction {
+                    sender: address(1),
+                    state_proof_type: 0,
+                    message: StateProofMessage {
+                        first_attested_round: Round(24191745),
+                        last_attested_round: Round(24192000),
+                        ..StateProofMessage::default()
+                    },
+                    state_proof: Default::default(),
+                }
+            ))
+        );
+        assert_eq!(
+            format!("hb heartbeat of {} by {} | {}", A2, A1, FEE_AND_VALIDITY),
+            summary(TransactionType::HeartbeatTransaction(
+                HeartbeatTransaction {
+                    sender: address(1),
+                    address: address(2),
+                    proof: Default::default(),
+                    seed: vec![1; 32],
+                    vote_id: vec![2; 32],
+                    key_dilution: 10000,
+                }
+            ))
+        );
+        assert_eq!(
+            format!("xyz by {} | {}", A1, FEE_AND_VALIDITY),
+            summary(TransactionType::Unknown(UnknownTransaction {
+                sender: address(1),
+                txn_type: "xyz".to_owned(),
+            }))
+        );
+    }
+
+    #[test]
+    fn test_common_fields_summary() {
+        let txn = txn(Pay::new(address(1), address(2), MicroAlgos(1)).build())
+            .note(b"hello".to_vec())
+            .group(HashDigest([0xff; 32]))
+            .lease(HashDigest([0; 32]))
+            .rekey_to(address(3))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            format!(
+                "pay 0.000001 Algos from {} to {} | {} | note 5 bytes | group ////////... | lease AAAAAAAA... | !! REKEY TO {}",
+                A1, A2, FEE_AND_VALIDITY, A3
+            ),
+            txn.summary()
+        );
+        assert_eq!(txn.summary(), txn.to_string());
+    }
+
+    #[test]
+    fn test_signed_transaction_summary() {
+        let txn = txn(Pay::new(address(1), address(2), MicroAlgos(1)).build())
+            .build()
+            .unwrap();
+        let signed = |sig: TransactionSignature| SignedTransaction {
+            transaction: txn.clone(),
+            transaction_id: "TXID".to_owned(),
+            sig,
+        };
+        let prefix = format!(
+            "pay 0.000001 Algos from {} to {} | {} | id TXID",
+            A1, A2, FEE_AND_VALIDITY
+        );
+        let msig = MultisigSignature {
+            subsigs: vec![
+                MultisigSubsig {
+                    key: Ed25519PublicKey([1; 32]),
+                    sig: Some(Signature([0; 64])),
+                },
+                MultisigSubsig {
+                    key: Ed25519PublicKey([2; 32]),
+                    sig: None,
+                },
+            ],
+            threshold: 2,
+            version: 1,
+        };
+
+        assert_eq!(
+            format!("{} | signed", prefix),
+            signed(TransactionSignature::Single(Signature([0; 64]))).summary()
+        );
+        assert_eq!(
+            format!("{} | multisig 1 of 2 signed, threshold 2", prefix),
+            signed(TransactionSignature::Multi(msig.clone())).summary()
+        );
+        assert_eq!(
+            format!("{} | logic sig (contract account)", prefix),
+            signed(TransactionSignature::Logic(SignedLogic {
+                logic: CompiledTeal(vec![1]),
+                args: vec![],
+                sig: LogicSignature::ContractAccount,
+            }))
+            .summary()
+        );
+        assert_eq!(
+            format!(
+                "{} | logic sig (delegated multisig 1 of 2 signed, threshold 2)",
+                prefix
+            ),
+            signed(TransactionSignature::Logic(SignedLogic {
+                logic: CompiledTeal(vec![1]),
+                args: vec![],
+                sig: LogicSignature::DelegatedMultiSig(msig),
+            }))
+            .summary()
+        );
+    }
+}
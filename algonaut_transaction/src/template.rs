@@ -0,0 +1,396 @@
+//! Checks of the contents of a transaction received from a third party, before signing it.
+
+use crate::{Transaction, TransactionType};
+use algonaut_core::{Address, MicroAlgos};
+use thiserror::Error;
+
+/// The expected contents of a transaction, e.g. the transaction to co-sign in a group built by a
+/// third party: see [TxGroup::verify](crate::tx_group::TxGroup::verify) to check the group itself.
+///
+/// A template matches a payment, or an asset transfer of an asset with [asset](Self::asset). The
+/// fields without an expectation aren't checked, except that rekeying and closing are allowed
+/// only if they aren't forbidden: forbid them unless the transaction is meant to.
+///
+/// ```
+/// # use algonaut_transaction::TransactionTemplate;
+/// # use algonaut_core::{Address, MicroAlgos};
+/// # let merchant = Address::new([1; 32]);
+/// let template = TransactionTemplate::new()
+///     .receiver(merchant)
+///     .max_amount(5_000_000)
+///     .max_fee(MicroAlgos(2_000))
+///     .forbid_rekey()
+///     .forbid_close();
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct TransactionTemplate {
+    sender: Option<Address>,
+    receiver: Option<Address>,
+    asset: Option<u64>,
+    min_amount: Option<u64>,
+    max_amount: Option<u64>,
+    max_fee: Option<MicroAlgos>,
+    forbid_rekey: bool,
+    forbid_close: bool,
+}
+
+impl TransactionTemplate {
+    /// A template matching any payment.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn sender(mut self, sender: Address) -> Self {
+        self.sender = Some(sender);
+        self
+    }
+
+    pub fn receiver(mut self, receiver: Address) -> Self {
+        self.receiver = Some(receiver);
+        self
+    }
+
+    /// Matches transfers of this asset, instead of payments.
+    pub fn asset(mut self, asset_id: u64) -> Self {
+        self.asset = Some(asset_id);
+        self
+    }
+
+    /// The minimum amount, in microAlgos for payments and in base units for asset transfers.
+    pub fn min_amount(mut self, amount: u64) -> Self {
+        self.min_amount = Some(amount);
+        self
+    }
+
+    /// The maximum amount, in microAlgos for payments and in base units for asset transfers.
+    pub fn max_amount(mut self, amount: u64) -> Self {
+        self.max_amount = Some(amount);
+        self
+    }
+
+    pub fn max_fee(mut self, fee: MicroAlgos) -> Self {
+        self.max_fee = Some(fee);
+        self
+    }
+
+    /// Rejects transactions rekeying the sender account.
+    pub fn forbid_rekey(mut self) -> Self {
+        self.forbid_rekey = true;
+        self
+    }
+
+    /// Rejects transactions closing the sender account, or its holding of the asset.
+    pub fn forbid_close(mut self) -> Self {
+        self.forbid_close = true;
+        self
+    }
+
+    /// Checks the transaction against the template, with all the violations found.
+    ///
+    /// The amount, receiver and closing of a transaction of another type than expected aren't
+    /// checked: it's only reported as [Violation::UnexpectedType].
+    pub fn matches(&self, txn: &Transaction) -> Result<(), Vec<Violation>> {
+        let mut violations = vec![];
+
+        let sender = txn.sender();
+        if let Some(expected) = self.sender.filter(|expected| *expected != sender) {
+            violations.push(Violation::Sender {
+                expected,
+                actual: sender,
+            });
+        }
+        if let Some(max) = self.max_fee.filter(|max| txn.fee > *max) {
+            violations.push(Violation::FeeTooHigh {
+                max,
+                actual: txn.fee,
+            });
+        }
+        if let Some(to) = txn.rekey_to.filter(|_| self.forbid_rekey) {
+            violations.push(Violation::Rekey { to });
+        }
+
+        match (&txn.txn_type, self.asset) {
+            (TransactionType::Payment(payment), None) => self.check_transfer(
+                payment.receiver,
+                payment.amount.0,
+                payment.close_remainder_to,
+                &mut violations,
+            ),
+            (TransactionType::AssetTransferTransaction(transfer), Some(asset)) => {
+                if transfer.xfer != asset {
+                    violations.push(Violation::Asset {
+                        expected: asset,
+                        actual: transfer.xfer,
+                    });
+                }
+                self.check_transfer(
+                    transfer.receiver,
+                    transfer.amount,
+                    transfer.close_to,
+                    &mut violations,
+                )
+            }
+            (_, asset) => violations.push(Violation::UnexpectedType {
+                expected: match asset {
+                    Some(_) => "asset transfer",
+                    None => "payment",
+                },
+            }),
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    fn check_transfer(
+        &self,
+        receiver: Address,
+        amount: u64,
+        close_to: Option<Address>,
+        violations: &mut Vec<Violation>,
+    ) {
+        if let Some(expected) = self.receiver.filter(|expected| *expected != receiver) {
+            violations.push(Violation::Receiver {
+                expected,
+                actual: receiver,
+            });
+        }
+        if let Some(min) = self.min_amount.filter(|min| amount < *min) {
+            violations.push(Violation::AmountTooLow {
+                min,
+                actual: amount,
+            });
+        }
+        if let Some(max) = self.max_amount.filter(|max| amount > *max) {
+            violations.push(Violation::AmountTooHigh {
+                max,
+                actual: amount,
+            });
+        }
+        if let Some(to) = close_to.filter(|_| self.forbid_close) {
+            violations.push(Violation::Close { to });
+        }
+    }
+}
+
+/// A difference between a transaction and a [TransactionTemplate].
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum Violation {
+    #[error("The transaction isn't a {expected}.")]
+    UnexpectedType { expected: &'static str },
+    #[error("The sender is {actual}, expected {expected}.")]
+    Sender { expected: Address, actual: Address },
+    #[error("The receiver is {actual}, expected {expected}.")]
+    Receiver { expected: Address, actual: Address },
+    #[error("The asset is {actual}, expected {expected}.")]
+    Asset { expected: u64, actual: u64 },
+    #[error("The amount is {actual}, at least {min} is expected.")]
+    AmountTooLow { min: u64, actual: u64 },
+    #[error("The amount is {actual}, at most {max} is expected.")]
+    AmountTooHigh { max: u64, actual: u64 },
+    #[error("The fee is {actual}, at most {max} is expected.")]
+    FeeTooHigh { max: MicroAlgos, actual: MicroAlgos },
+    #[error("The transaction rekeys the sender to {to}.")]
+    Rekey { to: Address },
+    #[error("The transaction closes the sender to {to}.")]
+    Close { to: Address },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{builder::TxnFee, ClawbackAsset, Pay, TransferAsset, TxnBuilder};
+
+    fn address(byte: u8) -> Address {
+        Address::new([byte; 32])
+    }
+
+    fn transaction(txn_type: TransactionType) -> Transaction {
+        TxnBuilder::new(
+            TxnFee::Fixed(MicroAlgos(1000)),
+            1,
+            1000,
+            "sC3P7e2SdbqKJK0tbiCdK9tdSpbe6XeCGKdoNzmlj0E="
+                .parse()
+                .unwrap(),
+            txn_type,
+        )
+        .build()
+        .unwrap()
+    }
+
+    fn payment(amount: u64) -> Transaction {
+        transaction(Pay::new(address(1), address(2), MicroAlgos(amount)).build())
+    }
+
+    fn strict_template() -> TransactionTemplate {
+        TransactionTemplate::new()
+            .sender(address(1))
+            .receiver(address(2))
+            .min_amount(1_000)
+            .max_amount(5_000)
+            .max_fee(MicroAlgos(1_000))
+            .forbid_rekey()
+            .forbid_close()
+    }
+
+    #[test]
+    fn test_matching_payment() {
+        assert_eq!(Ok(()), strict_template().matches(&payment(1_000)));
+        assert_eq!(Ok(()), strict_template().matches(&payment(5_000)));
+        // no expectations
+        assert_eq!(Ok(()), TransactionTemplate::new().matches(&payment(0)));
+    }
+
+    #[test]
+    fn test_tampered_amount() {
+        assert_eq!(
+            Err(vec![Violation::AmountTooHigh {
+                max: 5_000,
+                actual: 5_001
+            }]),
+            strict_template().matches(&payment(5_001))
+        );
+        assert_eq!(
+            Err(vec![Violation::AmountTooLow {
+                min: 1_000,
+                actual: 999
+            }]),
+            strict_template().matches(&payment(999))
+        );
+    }
+
+    #[test]
+    fn test_wrong_accounts() {
+        let txn = transaction(Pay::new(address(3), address(4), MicroAlgos(1_000)).build());
+
+        assert_eq!(
+            Err(vec![
+                Violation::Sender {
+                    expected: address(1),
+                    actual: address(3)
+                },
+                Violation::Receiver {
+                    expected: address(2),
+                    actual: address(4)
+                }
+            ]),
+            strict_template().matches(&txn)
+        );
+    }
+
+    #[test]
+    fn test_rekey_injected() {
+        let mut txn = payment(1_000);
+        txn.rekey_to = Some(address(9));
+
+        assert_eq!(
+            Err(vec![Violation::Rekey { to: address(9) }]),
+            strict_template().matches(&txn)
+        );
+        assert_eq!(
+            Ok(()),
+            TransactionTemplate::new()
+                .receiver(address(2))
+                .matches(&txn)
+        );
+    }
+
+    #[test]
+    fn test_close_injected() {
+        let txn = transaction(
+            Pay::new(address(1), address(2), MicroAlgos(1_000))
+                .close_remainder_to(address(9))
+                .build(),
+        );
+
+        assert_eq!(
+            Err(vec![Violation::Close { to: address(9) }]),
+            strict_template().matches(&txn)
+        );
+    }
+
+    #[test]
+    fn test_fee_too_high() {
+        let mut txn = payment(1_000);
+        txn.fee = MicroAlgos(1_001);
+
+        assert_eq!(
+            Err(vec![Violation::FeeTooHigh {
+                max: MicroAlgos(1_000),
+                actual: MicroAlgos(1_001)
+            }]),
+            strict_template().matches(&txn)
+        );
+    }
+
+    #[test]
+    fn test_all_violations_are_reported() {
+        let mut txn = transaction(
+            Pay::new(address(3), address(4), MicroAlgos(10_000))
+                .close_remainder_to(address(9))
+                .build(),
+        );
+        txn.fee = MicroAlgos(2_000);
+        txn.rekey_to = Some(address(9));
+
+        assert_eq!(6, strict_template().matches(&txn).unwrap_err().len());
+    }
+
+    #[test]
+    fn test_asset_transfer() {
+        let transfer = |asset_id, close_to: Option<Address>| {
+            let transfer = TransferAsset::new(address(1), asset_id, 1_000, address(2));
+            transaction(match close_to {
+                Some(close_to) => transfer.close_to(close_to).build(),
+                None => transfer.build(),
+            })
+        };
+        let template = strict_template().asset(10);
+
+        assert_eq!(Ok(()), template.matches(&transfer(10, None)));
+        assert_eq!(
+            Err(vec![Violation::Asset {
+                expected: 10,
+                actual: 11
+            }]),
+            template.matches(&transfer(11, None))
+        );
+        assert_eq!(
+            Err(vec![Violation::Close { to: address(9) }]),
+            template.matches(&transfer(10, Some(address(9))))
+        );
+    }
+
+    #[test]
+    fn test_unexpected_type() {
+        let payment_template = strict_template();
+        let asset_template = strict_template().asset(10);
+        let transfer = transaction(TransferAsset::new(address(1), 10, 1_000, address(2)).build());
+        let clawback =
+            transaction(ClawbackAsset::new(address(1), 10, 1_000, address(3), address(2)).build());
+
+        assert_eq!(
+            Err(vec![Violation::UnexpectedType {
+                expected: "payment"
+            }]),
+            payment_template.matches(&transfer)
+        );
+        assert_eq!(
+            Err(vec![Violation::UnexpectedType {
+                expected: "asset transfer"
+            }]),
+            asset_template.matches(&payment(1_000))
+        );
+        // a clawback has a receiver and an amount, but moves the asset of another account
+        assert_eq!(
+            Err(vec![Violation::UnexpectedType {
+                expected: "asset transfer"
+            }]),
+            asset_template.matches(&clawback)
+        );
+    }
+}
@@ -1,14 +1,18 @@
 use crate::account::Account;
 use crate::error::TransactionError;
+use crate::heartbeat::HeartbeatProof;
+use crate::state_proof::{StateProof, StateProofMessage};
 use algonaut_core::CompiledTeal;
 use algonaut_core::SignedLogic;
 use algonaut_core::SuggestedTransactionParams;
 use algonaut_core::ToMsgPack;
 use algonaut_core::{Address, MultisigSignature};
-use algonaut_core::{MicroAlgos, Round, VotePk, VrfPk};
+use algonaut_core::{MicroAlgos, Round, RoundRange, VotePk, VrfPk};
 use algonaut_crypto::HashDigest;
 use algonaut_crypto::Signature;
+use algonaut_model::{algod, indexer};
 use data_encoding::BASE32_NOPAD;
+use serde::de::DeserializeOwned;
 use sha2::Digest;
 
 /// Enum containing the types of transactions and their specific fields
@@ -22,6 +26,10 @@ pub enum TransactionType {
     AssetClawbackTransaction(AssetClawbackTransaction),
     AssetFreezeTransaction(AssetFreezeTransaction),
     ApplicationCallTransaction(ApplicationCallTransaction),
+    StateProofTransaction(StateProofTransaction),
+    HeartbeatTransaction(HeartbeatTransaction),
+    /// A transaction type not known by this version of the SDK.
+    Unknown(UnknownTransaction),
 }
 
 /// A transaction that can appear in a block
@@ -93,6 +101,18 @@ impl Transaction {
         Ok(BASE32_NOPAD.encode(&self.raw_id()?.0))
     }
 
+    /// The note of this transaction, decoded from JSON, e.g. set with
+    /// [TxnBuilder::note_json](crate::builder::TxnBuilder::note_json).
+    ///
+    /// Fails with [TransactionError::Deserialization] if the note isn't the JSON of a `T`.
+    pub fn note_as_json<T: DeserializeOwned>(&self) -> Result<Option<T>, TransactionError> {
+        self.note
+            .as_deref()
+            .map(serde_json::from_slice)
+            .transpose()
+            .map_err(|e| TransactionError::Deserialization(format!("note: {}", e)))
+    }
+
     pub fn assign_group_id(&mut self, group_id: HashDigest) {
         self.group = Some(group_id)
     }
@@ -121,6 +141,11 @@ impl Transaction {
         self.estimate_fee(params.fee_per_byte, params.min_fee)
     }
 
+    /// The rounds in which the transaction can be confirmed, from first to last valid.
+    pub fn validity(&self) -> RoundRange {
+        RoundRange::new(self.first_valid, self.last_valid)
+    }
+
     /// The address of the account that signs and pays the fee.
     /// It can have additional responsibilities dependending on the transaction type.
     /// We keep it in the transaction types to be able to document (and possibly name) it separately.
@@ -134,6 +159,9 @@ impl Transaction {
             TransactionType::AssetClawbackTransaction(t) => t.sender,
             TransactionType::AssetFreezeTransaction(t) => t.sender,
             TransactionType::ApplicationCallTransaction(t) => t.sender,
+            TransactionType::StateProofTransaction(t) => t.sender,
+            TransactionType::HeartbeatTransaction(t) => t.sender,
+            TransactionType::Unknown(t) => t.sender,
         }
     }
 }
@@ -225,7 +253,7 @@ pub struct AssetParams {
     /// the digitized asset as the official representation of a particular real-world asset.
     pub meta_data_hash: Option<Vec<u8>>,
 
-    /// Specifies a URL where more information about the asset can be retrieved. Max size is 32 bytes.
+    /// Specifies a URL where more information about the asset can be retrieved. Max size is 96 bytes.
     pub url: Option<String>,
 
     /// The address of the account that can clawback holdings of this asset. If empty, clawback is
@@ -245,6 +273,28 @@ pub struct AssetParams {
     pub reserve: Option<Address>,
 }
 
+impl AssetParams {
+    /// Whether no field is set to a non-zero value, i.e. the encoded params are empty.
+    /// Configuring an existing asset with empty params destroys it.
+    pub fn is_empty(&self) -> bool {
+        self.asset_name.as_deref().unwrap_or_default().is_empty()
+            && self.decimals.unwrap_or_default() == 0
+            && !self.default_frozen.unwrap_or_default()
+            && self.total.unwrap_or_default() == 0
+            && self.unit_name.as_deref().unwrap_or_default().is_empty()
+            && self
+                .meta_data_hash
+                .as_deref()
+                .unwrap_or_default()
+                .is_empty()
+            && self.url.as_deref().unwrap_or_default().is_empty()
+            && self.clawback.is_none()
+            && self.freeze.is_none()
+            && self.manager.is_none()
+            && self.reserve.is_none()
+    }
+}
+
 /// This is used to transfer an asset.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct AssetTransferTransaction {
@@ -354,6 +404,10 @@ pub struct ApplicationCallTransaction {
     /// clear-state-program. The access is read-only.
     pub foreign_assets: Option<Vec<u64>>,
 
+    /// Lists the boxes that may be accessed by this application's approval-program and
+    /// clear-state-program.
+    pub boxes: Option<Vec<BoxReference>>,
+
     /// Holds the maximum number of global state values defined within a StateSchema object.
     pub global_state_schema: Option<StateSchema>,
 
@@ -381,6 +435,70 @@ pub enum ApplicationCallOnComplete {
     DeleteApplication,
 }
 
+/// A state proof, attesting the block headers of a range of rounds. Issued by the protocol.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StateProofTransaction {
+    /// The address of the state proof sender, a special address used by the protocol.
+    pub sender: Address,
+
+    /// Type of the state proof. Integer representing an entry defined in protocol/stateproof.go
+    pub state_proof_type: u64,
+
+    /// The message attested by the state proof.
+    pub message: StateProofMessage,
+
+    /// The state proof.
+    pub state_proof: StateProof,
+}
+
+/// A heartbeat, proving that the participation keys of an online account are in use.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HeartbeatTransaction {
+    /// The address of the account that signs and pays the fee, not necessarily the account of the
+    /// heartbeat.
+    pub sender: Address,
+
+    /// The online account the heartbeat is for.
+    pub address: Address,
+
+    /// Signature of the seed with the participation keys of the account.
+    pub proof: HeartbeatProof,
+
+    /// The block seed of the round before the first valid round of the transaction.
+    pub seed: Vec<u8>,
+
+    /// The vote public key of the account, which must match the registered one.
+    pub vote_id: Vec<u8>,
+
+    /// The key dilution of the account, which must match the registered one.
+    pub key_dilution: u64,
+}
+
+/// A transaction of a type not known by this version of the SDK, e.g. introduced by a newer
+/// protocol version.
+///
+/// Only the common fields of the transaction are decoded: re-encoding it loses the type specific
+/// fields, so it can't be used to compute the id or to sign.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnknownTransaction {
+    /// The address of the account that signs and pays the fee.
+    pub sender: Address,
+
+    /// The encoded transaction type (e.g. "pay" for payments).
+    pub txn_type: String,
+}
+
+/// A box an application call may access.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BoxReference {
+    /// The application of the box: 0 for the called application, otherwise the position
+    /// (starting at 1) of the application in the foreign apps.
+    pub app_index: u64,
+
+    /// The name of the box.
+    pub name: Vec<u8>,
+}
+
 /// Storage state schema. The StateSchema object is only required for the create application call
 /// transaction. The StateSchema object must be fully populated for both the GlobalStateSchema and
 /// LocalStateSchema objects.
@@ -395,6 +513,24 @@ pub struct StateSchema {
     pub number_byteslices: u64,
 }
 
+impl From<&algod::v2::ApplicationStateSchema> for StateSchema {
+    fn from(schema: &algod::v2::ApplicationStateSchema) -> Self {
+        StateSchema {
+            number_ints: schema.num_uint,
+            number_byteslices: schema.num_byte_slice,
+        }
+    }
+}
+
+impl From<&indexer::v2::ApplicationStateSchema> for StateSchema {
+    fn from(schema: &indexer::v2::ApplicationStateSchema) -> Self {
+        StateSchema {
+            number_ints: schema.num_uint,
+            number_byteslices: schema.num_byte_slice,
+        }
+    }
+}
+
 /// Wraps a transaction in a signature. The encoding of this struct is suitable to be broadcast
 /// on the network
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -404,6 +540,13 @@ pub struct SignedTransaction {
     pub sig: TransactionSignature,
 }
 
+impl SignedTransaction {
+    /// The note of the transaction, decoded from JSON: see [Transaction::note_as_json].
+    pub fn note_as_json<T: DeserializeOwned>(&self) -> Result<Option<T>, TransactionError> {
+        self.transaction.note_as_json()
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum TransactionSignature {
     Single(Signature),
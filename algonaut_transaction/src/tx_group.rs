@@ -1,4 +1,4 @@
-use algonaut_core::ToMsgPack;
+use algonaut_core::{MicroAlgos, ToMsgPack};
 use algonaut_crypto::HashDigest;
 use serde::{Deserialize, Serialize, Serializer};
 use sha2::Digest;
@@ -19,27 +19,78 @@ impl TxGroup {
     }
 
     pub fn assign_group_id(txns: &mut [&mut Transaction]) -> Result<(), TransactionError> {
-        let gid = TxGroup::compute_group_id(txns)?;
+        let gid = TxGroup::group_id(txns.iter().map(|t| t.raw_id()))?;
         for tx in txns {
             tx.assign_group_id(gid);
         }
         Ok(())
     }
 
-    fn compute_group_id(txns: &[&mut Transaction]) -> Result<HashDigest, TransactionError> {
-        if txns.is_empty() {
+    /// Computes the group id of these transactions, without assigning it: the sha512/256 digest of
+    /// the "TG" prefixed encoding of their ids, as done by the other SDKs and the node.
+    ///
+    /// Fails if the group is empty or has more than 16 transactions, and with
+    /// [TransactionError::AlreadyGrouped] if a transaction already has a group id, which would
+    /// change its id. Use [compute_group_id_ignoring_groups](Self::compute_group_id_ignoring_groups)
+    /// to check the group id of grouped transactions.
+    pub fn compute_group_id(txns: &[Transaction]) -> Result<HashDigest, TransactionError> {
+        if let Some(index) = txns.iter().position(|t| t.group.is_some()) {
+            return Err(TransactionError::AlreadyGrouped { index });
+        }
+        TxGroup::group_id(txns.iter().map(|t| t.raw_id()))
+    }
+
+    /// Computes the group id of these transactions like [compute_group_id](Self::compute_group_id),
+    /// with their ids computed without their group id, if any: e.g. to verify the group id of
+    /// transactions received grouped.
+    pub fn compute_group_id_ignoring_groups(
+        txns: &[Transaction],
+    ) -> Result<HashDigest, TransactionError> {
+        TxGroup::group_id(txns.iter().map(|t| {
+            Transaction {
+                group: None,
+                ..t.clone()
+            }
+            .raw_id()
+        }))
+    }
+
+    /// Verifies the group id of grouped transactions, e.g. of a group built by a third party before
+    /// signing one of its transactions, returning it.
+    ///
+    /// The group id is recomputed from all the transactions, in their order, and each one must
+    /// have it: a tampered, added, removed, replaced or reordered transaction changes the group id.
+    /// Fails like [compute_group_id](Self::compute_group_id) if the group is empty or too large, and
+    /// with [TransactionError::GroupIdMismatch] at the first transaction without the group id.
+    ///
+    /// This doesn't check the contents of the transactions: see
+    /// [TransactionTemplate](crate::TransactionTemplate) and [total_fee](Self::total_fee).
+    pub fn verify(txns: &[Transaction]) -> Result<HashDigest, TransactionError> {
+        let gid = TxGroup::compute_group_id_ignoring_groups(txns)?;
+        if let Some(index) = txns.iter().position(|t| t.group != Some(gid)) {
+            return Err(TransactionError::GroupIdMismatch { index });
+        }
+        Ok(gid)
+    }
+
+    /// The sum of the fees of the transactions, `None` if it overflows.
+    pub fn total_fee(txns: &[Transaction]) -> Option<MicroAlgos> {
+        txns.iter()
+            .try_fold(MicroAlgos(0), |total, t| total.checked_add(t.fee))
+    }
+
+    fn group_id(
+        ids: impl ExactSizeIterator<Item = Result<HashDigest, TransactionError>>,
+    ) -> Result<HashDigest, TransactionError> {
+        if ids.len() == 0 {
             return Err(TransactionError::EmptyTransactionListError);
         }
-        if txns.len() > Self::MAX_TX_GROUP_SIZE {
+        if ids.len() > Self::MAX_TX_GROUP_SIZE {
             return Err(TransactionError::MaxTransactionGroupSizeError {
                 size: Self::MAX_TX_GROUP_SIZE,
             });
         }
-        let mut ids: Vec<HashDigest> = vec![];
-        for t in txns {
-            ids.push(t.raw_id()?);
-        }
-        let group = TxGroup::new(ids);
+        let group = TxGroup::new(ids.collect::<Result<_, _>>()?);
         let hashed = sha2::Sha512_256::digest(&group.bytes_to_sign()?);
         Ok(HashDigest(hashed.into()))
     }
@@ -64,3 +115,210 @@ impl Serialize for TxGroup {
         state.end()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{builder::TxnFee, Pay, TransactionType, TxnBuilder};
+    use data_encoding::BASE64;
+
+    /// The group id of the golden transactions of the group tests of the Go, Python and JavaScript
+    /// SDKs.
+    const GOLDEN_GROUP_ID: &str = "LiQ9OBup9H/bZLSfQUH2S6iHUM6FQ3PLuv9FNKyt09Q=";
+
+    /// The golden payments of the group tests of the other SDKs.
+    fn golden_transactions() -> Vec<Transaction> {
+        let address: algonaut_core::Address =
+            "UPYAFLHSIPMJOHVXU2MPLQ46GXJKSDCEMZ6RLCQ7GWB5PRDKJUWKKXECXI"
+                .parse()
+                .unwrap();
+        let payment = |first_valid: u64, note: &str| {
+            TxnBuilder::new(
+                TxnFee::Fixed(MicroAlgos(1000)),
+                first_valid,
+                first_valid + 1000,
+                "sC3P7e2SdbqKJK0tbiCdK9tdSpbe6XeCGKdoNzmlj0E="
+                    .parse()
+                    .unwrap(),
+                Pay::new(address, address, MicroAlgos(2000)).build(),
+            )
+            .genesis_id("devnet-v1.0".to_owned())
+            .note(BASE64.decode(note.as_bytes()).unwrap())
+            .build()
+            .unwrap()
+        };
+        vec![
+            payment(710399, "wRKw5cJ0CMo="),
+            payment(710515, "dBlHI6BdrIg="),
+        ]
+    }
+
+    #[test]
+    fn test_compute_group_id_matches_the_other_sdks() {
+        let txns = golden_transactions();
+        assert_eq!(
+            "OK4YQKXG5DRSMLX4EP4FYMOIDOUAYFKNCXVKCZKAUWACMYQIXHVQ",
+            txns[0].id().unwrap()
+        );
+
+        let group_id = TxGroup::compute_group_id(&txns).unwrap();
+
+        assert_eq!(GOLDEN_GROUP_ID, BASE64.encode(&group_id.0));
+    }
+
+    #[test]
+    fn test_compute_group_id_is_the_assigned_one() {
+        let txns = golden_transactions();
+        let group_id = TxGroup::compute_group_id(&txns).unwrap();
+
+        let (mut t1, mut t2) = (txns[0].clone(), txns[1].clone());
+        TxGroup::assign_group_id(&mut [&mut t1, &mut t2]).unwrap();
+
+        assert_eq!(Some(group_id), t1.group);
+        assert_eq!(Some(group_id), t2.group);
+        // the transactions aren't modified
+        assert_eq!(None, txns[0].group);
+    }
+
+    #[test]
+    fn test_compute_group_id_of_grouped_transactions() {
+        let mut txns = golden_transactions();
+        let group_id = TxGroup::compute_group_id(&txns).unwrap();
+        txns[1].assign_group_id(group_id);
+
+        assert!(matches!(
+            TxGroup::compute_group_id(&txns),
+            Err(TransactionError::AlreadyGrouped { index: 1 })
+        ));
+        assert_eq!(
+            group_id,
+            TxGroup::compute_group_id_ignoring_groups(&txns).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_compute_group_id_size_bounds() {
+        assert!(matches!(
+            TxGroup::compute_group_id(&[]),
+            Err(TransactionError::EmptyTransactionListError)
+        ));
+
+        let txns = golden_transactions();
+        let txns: Vec<Transaction> = txns.iter().cycle().take(17).cloned().collect();
+        assert!(matches!(
+            TxGroup::compute_group_id(&txns),
+            Err(TransactionError::MaxTransactionGroupSizeError { size: 16 })
+        ));
+        assert!(TxGroup::compute_group_id(&txns[..16]).is_ok());
+    }
+
+    /// The golden transactions, grouped.
+    fn grouped_transactions() -> Vec<Transaction> {
+        let mut txns = golden_transactions();
+        let gid = TxGroup::compute_group_id(&txns).unwrap();
+        for txn in &mut txns {
+            txn.assign_group_id(gid);
+        }
+        txns
+    }
+
+    fn assert_mismatch(txns: &[Transaction], expected_index: usize) {
+        match TxGroup::verify(txns) {
+            Err(TransactionError::GroupIdMismatch { index }) => assert_eq!(expected_index, index),
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_verify() {
+        let gid = TxGroup::verify(&grouped_transactions()).unwrap();
+
+        assert_eq!(GOLDEN_GROUP_ID, BASE64.encode(&gid.0));
+    }
+
+    #[test]
+    fn test_verify_tampered_amount() {
+        let mut txns = grouped_transactions();
+        if let TransactionType::Payment(payment) = &mut txns[1].txn_type {
+            payment.amount = MicroAlgos(2001);
+        }
+
+        assert_mismatch(&txns, 0);
+    }
+
+    #[test]
+    fn test_verify_injected_rekey() {
+        let mut txns = grouped_transactions();
+        txns[1].rekey_to = Some(txns[1].sender());
+
+        assert_mismatch(&txns, 0);
+    }
+
+    #[test]
+    fn test_verify_swapped_member() {
+        // a member of another group, with the group id of this one
+        let mut txns = grouped_transactions();
+        let mut other = golden_transactions()[1].clone();
+        other.note = Some(b"swapped".to_vec());
+        other.assign_group_id(txns[1].group.unwrap());
+        txns[1] = other;
+
+        assert_mismatch(&txns, 0);
+    }
+
+    #[test]
+    fn test_verify_reordered_members() {
+        let mut txns = grouped_transactions();
+        txns.swap(0, 1);
+
+        assert_mismatch(&txns, 0);
+    }
+
+    #[test]
+    fn test_verify_added_and_removed_members() {
+        let txns = grouped_transactions();
+        assert_mismatch(&txns[..1], 0);
+
+        let mut txns = grouped_transactions();
+        txns.push(txns[0].clone());
+        assert_mismatch(&txns, 0);
+    }
+
+    #[test]
+    fn test_verify_member_without_the_group_id() {
+        let mut txns = grouped_transactions();
+        txns[1].group = None;
+        // the group id doesn't depend on the group ids of the members
+        assert_mismatch(&txns, 1);
+
+        let mut txns = grouped_transactions();
+        txns[1].group = Some(HashDigest([0; 32]));
+        assert_mismatch(&txns, 1);
+
+        assert_mismatch(&golden_transactions(), 0);
+    }
+
+    #[test]
+    fn test_verify_size_bounds() {
+        assert!(matches!(
+            TxGroup::verify(&[]),
+            Err(TransactionError::EmptyTransactionListError)
+        ));
+
+        let txns = grouped_transactions();
+        let txns: Vec<Transaction> = txns.iter().cycle().take(17).cloned().collect();
+        assert!(matches!(
+            TxGroup::verify(&txns),
+            Err(TransactionError::MaxTransactionGroupSizeError { size: 16 })
+        ));
+    }
+
+    #[test]
+    fn test_total_fee() {
+        let mut txns = grouped_transactions();
+        assert_eq!(Some(MicroAlgos(2000)), TxGroup::total_fee(&txns));
+
+        txns[1].fee = MicroAlgos(u64::MAX);
+        assert_eq!(None, TxGroup::total_fee(&txns));
+    }
+}
@@ -0,0 +1,347 @@
+use super::Note;
+use crate::{error::TransactionError, Pay, Transaction, TransferAsset, TxnBuilder};
+use algonaut_core::{Address, MicroAlgos, SuggestedTransactionParams};
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+use url::Url;
+use urlencoding::{decode, encode};
+
+const SCHEME: &str = "algorand://";
+
+/// Prefix of the parameters that the reader must understand to process the URI.
+const REQUIRED_PARAM_PREFIX: &str = "req-";
+
+/// A payment request URI, as specified in [ARC-26](https://github.com/algorandfoundation/ARCs/blob/main/ARCs/arc-0026.md),
+/// e.g. `algorand://TMTAD6N22HCS2LKH7677L2KFLT3PAQWY6M4JFQFXQS32ECBFC23F57RYX4?amount=150&asset=45`.
+///
+/// Use [PaymentUri::to] to build one, [PaymentUri::parse] to read one and `to_string` to encode it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaymentUri {
+    /// Receiver of the payment.
+    pub receiver: Address,
+
+    /// Label of the receiver (e.g. name of the receiver).
+    pub label: Option<String>,
+
+    /// Amount to transfer, in microAlgos or, if `asset` is set, in base units of the asset.
+    pub amount: Option<u64>,
+
+    /// Id of the asset to transfer. If not set, the payment is in Algos.
+    pub asset: Option<u64>,
+
+    /// Note of the transaction.
+    pub note: Option<Note>,
+}
+
+impl PaymentUri {
+    /// Starts building a payment request to `receiver`.
+    pub fn to(receiver: Address) -> PaymentUriBuilder {
+        PaymentUriBuilder {
+            uri: PaymentUri {
+                receiver,
+                label: None,
+                amount: None,
+                asset: None,
+                note: None,
+            },
+        }
+    }
+
+    /// Parses an ARC-26 URI.
+    ///
+    /// Fails if the URI is malformed, if it contains both `note` and `xnote`, or if it contains a
+    /// required parameter (prefixed with `req-`) that isn't supported. Other unknown parameters are ignored.
+    pub fn parse(uri: &str) -> Result<PaymentUri, TransactionError> {
+        let invalid =
+            |reason: &str| TransactionError::InvalidPaymentUri(format!("{}: {}", reason, uri));
+
+        let rest = uri
+            .strip_prefix(SCHEME)
+            .ok_or_else(|| invalid("missing algorand:// scheme"))?;
+        let (address, query) = match rest.split_once('?') {
+            Some((address, query)) => (address, query),
+            None => (rest, ""),
+        };
+        let address = address.trim_end_matches('/');
+        let mut payment_uri =
+            PaymentUri::to(address.parse().map_err(|_| invalid("invalid address"))?).uri;
+
+        let mut note = None;
+        let mut xnote = None;
+        for param in query.split('&').filter(|param| !param.is_empty()) {
+            let (key, value) = param.split_once('=').unwrap_or((param, ""));
+            let value = decode(value)
+                .map_err(|_| invalid("invalid percent-encoding"))?
+                .into_owned();
+            let is_duplicate = match key {
+                "label" => payment_uri.label.replace(value).is_some(),
+                "amount" => payment_uri
+                    .amount
+                    .replace(parse_integer(&value).ok_or_else(|| invalid("invalid amount"))?)
+                    .is_some(),
+                "asset" => payment_uri
+                    .asset
+                    .replace(parse_integer(&value).ok_or_else(|| invalid("invalid asset"))?)
+                    .is_some(),
+                "note" => note.replace(value).is_some(),
+                "xnote" => xnote.replace(value).is_some(),
+                key if key.starts_with(REQUIRED_PARAM_PREFIX) => {
+                    return Err(invalid(&format!("unsupported required parameter {}", key)))
+                }
+                _ => false,
+            };
+            if is_duplicate {
+                return Err(invalid(&format!("duplicate parameter {}", key)));
+            }
+        }
+        payment_uri.note = match (note, xnote) {
+            (Some(_), Some(_)) => return Err(invalid("both note and xnote are set")),
+            (Some(note), None) => Some(Note::Editable(note)),
+            (None, Some(xnote)) => Some(Note::NotEditable(xnote)),
+            (None, None) => None,
+        };
+        Ok(payment_uri)
+    }
+
+    /// The URI as a [Url].
+    pub fn as_url(&self) -> Url {
+        // unwrap: the receiver is an address and the parameters are percent-encoded
+        Url::parse(&self.to_string()).unwrap()
+    }
+
+    /// Builds the requested payment: an asset transfer if an asset is set, an Algo payment otherwise.
+    pub fn to_transaction(
+        &self,
+        params: &SuggestedTransactionParams,
+        sender: Address,
+    ) -> Result<Transaction, TransactionError> {
+        let amount = self.amount.unwrap_or(0);
+        let txn_type = match self.asset {
+            Some(asset) => TransferAsset::new(sender, asset, amount, self.receiver).build(),
+            None => Pay::new(sender, self.receiver, MicroAlgos(amount)).build(),
+        };
+        let mut builder = TxnBuilder::with(params, txn_type);
+        if let Some(Note::Editable(note) | Note::NotEditable(note)) = &self.note {
+            builder = builder.note(note.as_bytes().to_vec());
+        }
+        builder.build()
+    }
+}
+
+impl Display for PaymentUri {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut params = vec![];
+        if let Some(label) = &self.label {
+            params.push(format!("label={}", encode(label)));
+        }
+        if let Some(amount) = self.amount {
+            params.push(format!("amount={}", amount));
+        }
+        if let Some(asset) = self.asset {
+            params.push(format!("asset={}", asset));
+        }
+        match &self.note {
+            Some(Note::Editable(note)) => params.push(format!("note={}", encode(note))),
+            Some(Note::NotEditable(note)) => params.push(format!("xnote={}", encode(note))),
+            None => {}
+        }
+        write!(f, "{}{}", SCHEME, self.receiver)?;
+        if !params.is_empty() {
+            write!(f, "?{}", params.join("&"))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for PaymentUri {
+    type Err = TransactionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PaymentUri::parse(s)
+    }
+}
+
+/// A builder for [PaymentUri].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaymentUriBuilder {
+    uri: PaymentUri,
+}
+
+impl PaymentUriBuilder {
+    /// Amount to transfer: [MicroAlgos] for Algo payments or base units of the asset set with
+    /// [asset](Self::asset).
+    pub fn amount(mut self, amount: impl Into<u64>) -> Self {
+        self.uri.amount = Some(amount.into());
+        self
+    }
+
+    /// Requests a transfer of the asset, instead of an Algo payment.
+    pub fn asset(mut self, asset: u64) -> Self {
+        self.uri.asset = Some(asset);
+        self
+    }
+
+    /// Note that the user can edit before submitting the transaction.
+    pub fn note<T: Into<String>>(mut self, note: T) -> Self {
+        self.uri.note = Some(Note::Editable(note.into()));
+        self
+    }
+
+    /// Note that the user can't edit.
+    pub fn xnote<T: Into<String>>(mut self, xnote: T) -> Self {
+        self.uri.note = Some(Note::NotEditable(xnote.into()));
+        self
+    }
+
+    /// Address label (e.g. name of receiver)
+    pub fn label<T: Into<String>>(mut self, label: T) -> Self {
+        self.uri.label = Some(label.into());
+        self
+    }
+
+    pub fn build(self) -> PaymentUri {
+        self.uri
+    }
+}
+
+/// Parses a decimal integer, without sign or decimal point.
+fn parse_integer(value: &str) -> Option<u64> {
+    if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    value.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TransactionType;
+    use algonaut_core::Round;
+
+    const ADDRESS: &str = "TMTAD6N22HCS2LKH7677L2KFLT3PAQWY6M4JFQFXQS32ECBFC23F57RYX4";
+
+    fn address() -> Address {
+        ADDRESS.parse().unwrap()
+    }
+
+    #[test]
+    fn test_spec_examples_roundtrip() {
+        for uri in [
+            "algorand://TMTAD6N22HCS2LKH7677L2KFLT3PAQWY6M4JFQFXQS32ECBFC23F57RYX4",
+            "algorand://TMTAD6N22HCS2LKH7677L2KFLT3PAQWY6M4JFQFXQS32ECBFC23F57RYX4?label=Silvio",
+            "algorand://TMTAD6N22HCS2LKH7677L2KFLT3PAQWY6M4JFQFXQS32ECBFC23F57RYX4?amount=150500000",
+            "algorand://TMTAD6N22HCS2LKH7677L2KFLT3PAQWY6M4JFQFXQS32ECBFC23F57RYX4?amount=150&asset=45",
+            "algorand://TMTAD6N22HCS2LKH7677L2KFLT3PAQWY6M4JFQFXQS32ECBFC23F57RYX4?amount=150500000&note=Lunch%20money",
+            "algorand://TMTAD6N22HCS2LKH7677L2KFLT3PAQWY6M4JFQFXQS32ECBFC23F57RYX4?amount=0&asset=45&xnote=ID%3A%201234%26x",
+        ] {
+            assert_eq!(uri, PaymentUri::parse(uri).unwrap().to_string());
+        }
+    }
+
+    #[test]
+    fn test_build() {
+        let uri = PaymentUri::to(address())
+            .label("Silvio")
+            .amount(150u64)
+            .asset(45)
+            .xnote("ID: 1234&x")
+            .build();
+
+        assert_eq!(
+            "algorand://TMTAD6N22HCS2LKH7677L2KFLT3PAQWY6M4JFQFXQS32ECBFC23F57RYX4?label=Silvio&amount=150&asset=45&xnote=ID%3A%201234%26x",
+            uri.to_string()
+        );
+        assert_eq!(uri, uri.to_string().parse().unwrap());
+        assert_eq!(uri.to_string(), uri.as_url().as_str());
+    }
+
+    #[test]
+    fn test_parse() {
+        let uri = PaymentUri::parse(&format!(
+            "algorand://{}?amount=150&asset=45&note=Lunch%20money&label=Silvio&unknown=ignored",
+            ADDRESS
+        ))
+        .unwrap();
+
+        assert_eq!(
+            PaymentUri {
+                receiver: address(),
+                label: Some("Silvio".to_owned()),
+                amount: Some(150),
+                asset: Some(45),
+                note: Some(Note::Editable("Lunch money".to_owned())),
+            },
+            uri
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for uri in [
+            format!("bitcoin://{}", ADDRESS),
+            "algorand://NOTANADDRESS".to_owned(),
+            format!("algorand://{}?note=a&xnote=b", ADDRESS),
+            format!("algorand://{}?amount=1.5", ADDRESS),
+            format!("algorand://{}?amount=-1", ADDRESS),
+            format!("algorand://{}?amount=1&amount=2", ADDRESS),
+            format!("algorand://{}?asset=abc", ADDRESS),
+            format!("algorand://{}?req-unknown=1", ADDRESS),
+        ] {
+            assert!(PaymentUri::parse(&uri).is_err(), "{} should not parse", uri);
+        }
+    }
+
+    #[test]
+    fn test_to_transaction() {
+        let params = SuggestedTransactionParams {
+            genesis_id: "testnet-v1.0".to_owned(),
+            genesis_hash: "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI="
+                .parse()
+                .unwrap(),
+            consensus_version: "https://github.com/algorandfoundation/specs/tree/abc54f79f9ad679d2d22f0fb9909fb005c16f8a1".to_owned(),
+            fee_per_byte: MicroAlgos(0),
+            min_fee: MicroAlgos(1000),
+            first_valid: Round(1000),
+            last_valid: Round(2000),
+        };
+        let sender: Address = "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU"
+            .parse()
+            .unwrap();
+
+        let payment = PaymentUri::to(address())
+            .amount(MicroAlgos(150))
+            .note("Lunch")
+            .build()
+            .to_transaction(&params, sender)
+            .unwrap();
+        assert_eq!(
+            TransactionType::Payment(crate::transaction::Payment {
+                sender,
+                receiver: address(),
+                amount: MicroAlgos(150),
+                close_remainder_to: None,
+            }),
+            payment.txn_type
+        );
+        assert_eq!(Some(b"Lunch".to_vec()), payment.note);
+
+        let transfer = PaymentUri::to(address())
+            .amount(150u64)
+            .asset(45)
+            .build()
+            .to_transaction(&params, sender)
+            .unwrap();
+        assert_eq!(
+            TransactionType::AssetTransferTransaction(
+                crate::transaction::AssetTransferTransaction {
+                    sender,
+                    xfer: 45,
+                    amount: 150,
+                    receiver: address(),
+                    close_to: None,
+                }
+            ),
+            transfer.txn_type
+        );
+    }
+}
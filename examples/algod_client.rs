@@ -15,10 +15,10 @@ async fn main() -> Result<(), Box<dyn Error>> {
     let node_status = algod.status().await?;
     println!("algod last round: {}", node_status.last_round);
     println!(
-        "algod time since last round: {}",
+        "algod time since last round: {:?}",
         node_status.time_since_last_round
     );
-    println!("algod catchup: {}", node_status.catchup_time);
+    println!("algod catchup: {:?}", node_status.catchup_time);
     println!("algod latest version: {}", node_status.last_version);
 
     // fetch block information
@@ -0,0 +1,87 @@
+//! Compares the parsing of a page of transactions into the owned response and into its borrowed
+//! view, on a synthetic page of 1000 payments. Run with `--release`.
+use algonaut::model::indexer::v2::borrowed::TransactionResponseRef;
+use algonaut::model::indexer::v2::TransactionResponse;
+use std::error::Error;
+use std::time::{Duration, Instant};
+
+const TRANSACTIONS: usize = 1000;
+const ITERATIONS: u32 = 200;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let page = page();
+    println!(
+        "page of {} transactions, {} bytes",
+        TRANSACTIONS,
+        page.len()
+    );
+
+    let owned = time(|| {
+        let response: TransactionResponse = serde_json::from_str(&page).unwrap();
+        response.transactions.len()
+    });
+    let borrowed = time(|| {
+        let response: TransactionResponseRef = serde_json::from_str(&page).unwrap();
+        response.transactions.len()
+    });
+
+    println!("owned:    {:?} per page", owned);
+    println!("borrowed: {:?} per page", borrowed);
+    println!(
+        "speedup:  {:.1}x",
+        owned.as_secs_f64() / borrowed.as_secs_f64()
+    );
+
+    Ok(())
+}
+
+/// The average time of parsing the page.
+fn time(parse: impl Fn() -> usize) -> Duration {
+    // warm up
+    assert_eq!(TRANSACTIONS, parse());
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        assert_eq!(TRANSACTIONS, parse());
+    }
+    start.elapsed() / ITERATIONS
+}
+
+/// A page of payments, in the shape returned by the indexer.
+fn page() -> String {
+    let transactions: Vec<serde_json::Value> = (0..TRANSACTIONS)
+        .map(|i| {
+            serde_json::json!({
+                "close-rewards": 0,
+                "closing-amount": 0,
+                "confirmed-round": 20_000_000 + i / 10,
+                "fee": 1000,
+                "first-valid": 19_999_990,
+                "genesis-hash": "wGHE2Pwdvd7S12BL5FaOP20EGYesN73ktiC1qzkkit8=",
+                "genesis-id": "mainnet-v1.0",
+                "id": format!("TDIO6RJWJIVDDJZELMSX5CPJW7MUNM3QR4YAHYAKHF3W2CF{:05}", i),
+                "intra-round-offset": i % 10,
+                "last-valid": 20_000_990,
+                "note": "aGVsbG8gd29ybGQsIHRoaXMgaXMgYSBub3Rl",
+                "payment-transaction": {
+                    "amount": 1_000_000 + i,
+                    "close-amount": 0,
+                    "receiver": "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU"
+                },
+                "receiver-rewards": 0,
+                "round-time": 1_650_000_000 + i,
+                "sender": "2UEQTE5QDNXPI7M3TU44G6SYKLFWLPQO7EBZM7K7MHMQQMFI4QJPLHQFHM",
+                "sender-rewards": 0,
+                "signature": {
+                    "sig": "LQ1nxGhAkZD4BW7WhyEJ6eEqcpFhFzgpfNY0Pvh7yOt2cVdx6xL3YKnBNEm5FlC6CtW7xjUjbIiQ4ME5Q2YTDQ=="
+                },
+                "tx-type": "pay"
+            })
+        })
+        .collect();
+    serde_json::json!({
+        "current-round": 20_000_100,
+        "next-token": "Y2JBAAAAAAAAAAAA",
+        "transactions": transactions,
+    })
+    .to_string()
+}
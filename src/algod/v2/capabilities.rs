@@ -0,0 +1,270 @@
+use algonaut_crypto::HashDigest;
+use algonaut_model::algod::v2::BuildVersion;
+use std::sync::Mutex;
+
+use super::AlgodService;
+use crate::error::AlgonautError;
+
+/// The path of the simulation endpoint, and the first node version serving it.
+const SIMULATE: (&str, (u64, u64)) = ("/v2/transactions/simulate", (3, 15));
+
+/// The path of the boxes endpoint of an application, and the first node version serving it.
+const BOXES: (&str, (u64, u64)) = ("/v2/applications/{application-id}/boxes", (3, 13));
+
+/// The path of the state proof endpoint, and the first node version serving it.
+const STATE_PROOFS: (&str, (u64, u64)) = ("/v2/stateproofs/{round}", (3, 9));
+
+/// The version of a node and the optional endpoints it serves, probed once to avoid handling the
+/// 404s of the missing endpoints at each call: see [Capabilities::probe] and
+/// [Algod::capabilities](super::Algod::capabilities).
+#[derive(Debug, Clone)]
+pub struct Capabilities {
+    /// The network of the node, e.g. "mainnet-v1.0".
+    pub genesis_id: String,
+    /// The hash of the genesis block of the network.
+    pub genesis_hash: HashDigest,
+    /// The version of the node software.
+    pub build: BuildVersion,
+    /// The versions of the REST API supported by the node, e.g. "v2".
+    pub api_versions: Vec<String>,
+    /// Whether the node serves the simulation of transactions (`/v2/transactions/simulate`).
+    pub supports_simulate: bool,
+    /// Whether the node serves the boxes of applications (`/v2/applications/{id}/boxes`).
+    pub supports_boxes: bool,
+    /// Whether the node serves state proofs (`/v2/stateproofs/{round}`).
+    pub supports_state_proofs: bool,
+    /// Whether the supported endpoints were inferred from the version of the node, as it doesn't
+    /// serve the specification of its API.
+    pub inferred_from_version: bool,
+}
+
+impl Capabilities {
+    /// Probes the capabilities of the node with its public endpoints, which don't require a
+    /// token: `/versions` and the specification of its API (`/swagger.json`).
+    ///
+    /// An endpoint is supported if it's in the specification of the API. Some providers don't
+    /// serve the specification (404): the endpoints are then supported from the node version
+    /// that introduced them. Any other failure to get the specification fails the probe.
+    pub async fn probe(algod: &impl AlgodService) -> Result<Capabilities, AlgonautError> {
+        let version = algod.versions().await?;
+        let spec = match algod.api_spec().await {
+            Ok(spec) => Some(spec),
+            Err(error) if error.is_404() => None,
+            Err(error) => return Err(error),
+        };
+        let build = (version.build.major, version.build.minor);
+        let supports = |(path, since): (&str, (u64, u64))| match &spec {
+            Some(spec) => spec.has_path(path),
+            None => build >= since,
+        };
+        Ok(Capabilities {
+            genesis_id: version.genesis_id,
+            genesis_hash: version.genesis_hash_b64,
+            supports_simulate: supports(SIMULATE),
+            supports_boxes: supports(BOXES),
+            supports_state_proofs: supports(STATE_PROOFS),
+            inferred_from_version: spec.is_none(),
+            build: version.build,
+            api_versions: version.versions,
+        })
+    }
+
+    /// Whether the node supports this version of the REST API, e.g. "v2".
+    pub fn supports_api_version(&self, version: &str) -> bool {
+        self.api_versions.iter().any(|v| v == version)
+    }
+}
+
+/// The capabilities of a node, probed on first use.
+#[derive(Debug, Default)]
+pub(crate) struct CachedCapabilities {
+    capabilities: Mutex<Option<Capabilities>>,
+}
+
+impl CachedCapabilities {
+    /// The cached capabilities, probing them if they aren't yet. Failed probes aren't cached, nor
+    /// the capabilities [inferred from the version](Capabilities::inferred_from_version) of the
+    /// node, which are probed again until its specification is served.
+    ///
+    /// Concurrent first calls may probe the node more than once.
+    pub(crate) async fn get_or_probe(
+        &self,
+        algod: &impl AlgodService,
+    ) -> Result<Capabilities, AlgonautError> {
+        if let Some(capabilities) = self.lock().clone() {
+            return Ok(capabilities);
+        }
+        let capabilities = Capabilities::probe(algod).await?;
+        if !capabilities.inferred_from_version {
+            *self.lock() = Some(capabilities.clone());
+        }
+        Ok(capabilities)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<Capabilities>> {
+        // The value can't be left inconsistent by a panic: recover it.
+        self.capabilities
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{RequestError, RequestErrorDetails};
+    use crate::testing::MockAlgod;
+    use algonaut_model::algod::v2::{ApiSpec, Version};
+
+    /// The versions of a mainnet node of this version.
+    fn version(major: u64, minor: u64) -> Version {
+        let version = serde_json::json!({
+            "build": {
+                "branch": "rel/stable",
+                "build_number": 0,
+                "channel": "stable",
+                "commit_hash": "abcdef12",
+                "major": major,
+                "minor": minor
+            },
+            "genesis_hash_b64": "wGHE2Pwdvd7S12BL5FaOP20EGYesN73ktiC1qzkkit8=",
+            "genesis_id": "mainnet-v1.0",
+            "versions": ["v2"]
+        });
+        serde_json::from_str(&version.to_string()).unwrap()
+    }
+
+    fn api_spec(paths: &[&str]) -> ApiSpec {
+        ApiSpec {
+            paths: paths
+                .iter()
+                .map(|path| (path.to_string(), serde_json::json!({})))
+                .collect(),
+        }
+    }
+
+    fn not_found() -> AlgonautError {
+        AlgonautError::Request(RequestError::new(
+            None,
+            RequestErrorDetails::Http {
+                status: 404,
+                message: "not found".to_owned(),
+            },
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_probe_with_api_spec() {
+        let algod = MockAlgod::new();
+        algod.respond("versions", Ok(version(3, 24)));
+        algod.respond(
+            "api_spec",
+            Ok(api_spec(&[
+                "/versions",
+                "/v2/applications/{application-id}/boxes",
+                "/v2/stateproofs/{round}",
+            ])),
+        );
+
+        let capabilities = Capabilities::probe(&algod).await.unwrap();
+
+        // e.g. a node with the simulation disabled, whatever its version
+        assert!(!capabilities.supports_simulate);
+        assert!(capabilities.supports_boxes);
+        assert!(capabilities.supports_state_proofs);
+        assert_eq!("mainnet-v1.0", capabilities.genesis_id);
+        assert_eq!(24, capabilities.build.minor);
+        assert!(capabilities.supports_api_version("v2"));
+        assert!(!capabilities.supports_api_version("v1"));
+        assert!(!capabilities.inferred_from_version);
+    }
+
+    #[tokio::test]
+    async fn test_probe_from_version() {
+        let probe = |major, minor| async move {
+            let algod = MockAlgod::new();
+            algod.respond("versions", Ok(version(major, minor)));
+            algod.respond::<ApiSpec>("api_spec", Err(not_found()));
+            let capabilities = Capabilities::probe(&algod).await.unwrap();
+            (
+                capabilities.supports_simulate,
+                capabilities.supports_boxes,
+                capabilities.supports_state_proofs,
+            )
+        };
+
+        assert_eq!((false, false, false), probe(3, 8).await);
+        assert_eq!((false, false, true), probe(3, 9).await);
+        assert_eq!((false, true, true), probe(3, 13).await);
+        assert_eq!((true, true, true), probe(3, 15).await);
+        assert_eq!((true, true, true), probe(4, 0).await);
+    }
+
+    #[tokio::test]
+    async fn test_probe_fails_when_the_api_spec_fails() {
+        let algod = MockAlgod::new();
+        algod.respond("versions", Ok(version(3, 24)));
+        algod.respond::<ApiSpec>(
+            "api_spec",
+            Err(AlgonautError::Request(RequestError::new(
+                None,
+                RequestErrorDetails::Http {
+                    status: 500,
+                    message: "internal error".to_owned(),
+                },
+            ))),
+        );
+
+        assert!(Capabilities::probe(&algod).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_probe_fails_without_versions() {
+        let algod = MockAlgod::new();
+        algod.respond::<Version>("versions", Err(not_found()));
+
+        assert!(Capabilities::probe(&algod).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_capabilities_are_cached() {
+        let algod = MockAlgod::new();
+        algod.respond("versions", Ok(version(3, 24)));
+        algod.respond("api_spec", Ok(api_spec(&["/v2/transactions/simulate"])));
+        let cache = CachedCapabilities::default();
+
+        let first = cache.get_or_probe(&algod).await.unwrap();
+        let second = cache.get_or_probe(&algod).await.unwrap();
+
+        assert!(first.supports_simulate && second.supports_simulate);
+        assert_eq!(2, algod.calls().len());
+    }
+
+    #[tokio::test]
+    async fn test_capabilities_inferred_from_version_are_not_cached() {
+        let algod = MockAlgod::new();
+        algod.respond("versions", Ok(version(3, 24)));
+        algod.respond::<ApiSpec>("api_spec", Err(not_found()));
+        let cache = CachedCapabilities::default();
+
+        let inferred = cache.get_or_probe(&algod).await.unwrap();
+        assert!(inferred.inferred_from_version && inferred.supports_simulate);
+        algod.respond("api_spec", Ok(api_spec(&[])));
+        let probed = cache.get_or_probe(&algod).await.unwrap();
+        assert!(!probed.inferred_from_version && !probed.supports_simulate);
+        cache.get_or_probe(&algod).await.unwrap();
+        assert_eq!(4, algod.calls().len());
+    }
+
+    #[tokio::test]
+    async fn test_failed_probes_are_not_cached() {
+        let algod = MockAlgod::new();
+        algod.respond::<Version>("versions", Err(not_found()));
+        let cache = CachedCapabilities::default();
+
+        assert!(cache.get_or_probe(&algod).await.is_err());
+        algod.respond("versions", Ok(version(3, 24)));
+        algod.respond::<ApiSpec>("api_spec", Err(not_found()));
+        assert!(cache.get_or_probe(&algod).await.unwrap().supports_boxes);
+    }
+}
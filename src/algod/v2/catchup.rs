@@ -0,0 +1,183 @@
+use data_encoding::BASE32_NOPAD;
+#[cfg(all(not(target_arch = "wasm32"), feature = "tokio"))]
+use std::time::{Duration, Instant};
+
+#[cfg(all(not(target_arch = "wasm32"), feature = "tokio"))]
+use algonaut_model::algod::v2::NodeStatus;
+
+#[cfg(all(not(target_arch = "wasm32"), feature = "tokio"))]
+use super::AlgodService;
+use crate::error::AlgonautError;
+
+/// Delay between the status polls of [Algod::wait_for_catchup_completion](super::Algod::wait_for_catchup_completion).
+#[cfg(all(not(target_arch = "wasm32"), feature = "tokio"))]
+pub(crate) const CATCHUP_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Checks that a catchpoint label has the `<round>#<base32 digest>` format, e.g.
+/// `7900000#IQ6ZFQ5QXTDRVDP3HWYY7YHNDL7AOCMWJRFOPXVZ7RVBIGJ2GIEQ`.
+pub(crate) fn validate_catchpoint(catchpoint: &str) -> Result<(), AlgonautError> {
+    let invalid = || invalid_catchpoint(catchpoint);
+    let (round, digest) = catchpoint.split_once('#').ok_or_else(invalid)?;
+    if round.is_empty() || !round.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    round.parse::<u64>().map_err(|_| invalid())?;
+    match BASE32_NOPAD.decode(digest.as_bytes()) {
+        Ok(digest) if digest.len() == 32 => Ok(()),
+        _ => Err(invalid()),
+    }
+}
+
+/// The error of a catchpoint label that isn't in the `<round>#<base32 digest>` format.
+fn invalid_catchpoint(catchpoint: &str) -> AlgonautError {
+    AlgonautError::BadQuery {
+        reason: format!(
+            "invalid catchpoint {:?}: expected <round>#<base32 digest>",
+            catchpoint
+        ),
+        param: Some("catchpoint"),
+    }
+}
+
+/// Polls the status of the node every `poll_interval` until it's not catching up to a
+/// catchpoint anymore, returning the last status.
+///
+/// Fails with [AlgonautError::CatchupTimeout] if it's still catching up after `timeout`.
+#[cfg(all(not(target_arch = "wasm32"), feature = "tokio"))]
+pub(crate) async fn wait_for_catchup_completion<S: AlgodService>(
+    service: &S,
+    timeout: Duration,
+    poll_interval: Duration,
+) -> Result<NodeStatus, AlgonautError> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let status = service.status().await?;
+        if !status.is_catching_up() {
+            return Ok(status);
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(AlgonautError::CatchupTimeout {
+                catchpoint: status.catchpoint.unwrap_or_default(),
+            });
+        }
+        tokio::time::sleep(poll_interval.min(deadline - now)).await;
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::testing::MockAlgod;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    const CATCHPOINT: &str = "7900000#IQ6ZFQ5QXTDRVDP3HWYY7YHNDL7AOCMWJRFOPXVZ7RVBIGJ2GIEQ";
+
+    fn status(catchpoint: &str) -> NodeStatus {
+        serde_json::from_value(json!({
+            "catchpoint": catchpoint,
+            "catchpoint-acquired-blocks": 0,
+            "catchpoint-processed-accounts": 1200,
+            "catchpoint-processed-kvs": 30,
+            "catchpoint-total-accounts": 5000,
+            "catchpoint-total-blocks": 1000,
+            "catchpoint-total-kvs": 100,
+            "catchpoint-verified-accounts": 0,
+            "catchpoint-verified-kvs": 0,
+            "catchup-time": 0,
+            "last-round": 100,
+            "last-version": "future",
+            "next-version": "future",
+            "next-version-round": 101,
+            "next-version-supported": true,
+            "stopped-at-unsupported-round": false,
+            "time-since-last-round": 0
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_valid_catchpoint() {
+        assert_eq!(Ok(()), validate_catchpoint(CATCHPOINT));
+    }
+
+    #[test]
+    fn test_invalid_catchpoints() {
+        for catchpoint in [
+            "",
+            "7900000",
+            "IQ6ZFQ5QXTDRVDP3HWYY7YHNDL7AOCMWJRFOPXVZ7RVBIGJ2GIEQ",
+            "#IQ6ZFQ5QXTDRVDP3HWYY7YHNDL7AOCMWJRFOPXVZ7RVBIGJ2GIEQ",
+            "-1#IQ6ZFQ5QXTDRVDP3HWYY7YHNDL7AOCMWJRFOPXVZ7RVBIGJ2GIEQ",
+            "99999999999999999999#IQ6ZFQ5QXTDRVDP3HWYY7YHNDL7AOCMWJRFOPXVZ7RVBIGJ2GIEQ",
+            "7900000#",
+            "7900000#IQ6ZFQ5QXTDRVDP3HWYY7YHNDL7AOCMWJRFOPXVZ7RVBIGJ2GI",
+            "7900000#iq6zfq5qxtdrvdp3hwyy7yhndl7aocmwjrfopxvz7rvbigj2gieq",
+            "7900000#IQ6ZFQ5QXTDRVDP3HWYY7YHNDL7AOCMWJRFOPXVZ7RVBIGJ2GIEQ#",
+        ] {
+            assert_eq!(
+                Err(invalid_catchpoint(catchpoint)),
+                validate_catchpoint(catchpoint),
+                "{}",
+                catchpoint
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_waits_until_the_catchup_completes() {
+        let algod = Arc::new(MockAlgod::new());
+        algod.respond("status", Ok(status(CATCHPOINT)));
+        let completion = {
+            let algod = algod.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                algod.respond("status", Ok(status("")));
+            })
+        };
+
+        let status =
+            wait_for_catchup_completion(&algod, Duration::from_secs(10), Duration::from_millis(5))
+                .await
+                .unwrap();
+        completion.await.unwrap();
+
+        assert!(!status.is_catching_up());
+        assert!(algod.calls().len() > 1);
+    }
+
+    #[tokio::test]
+    async fn test_catchup_timeout() {
+        let algod = MockAlgod::new();
+        algod.respond("status", Ok(status(CATCHPOINT)));
+
+        let result = wait_for_catchup_completion(
+            &algod,
+            Duration::from_millis(30),
+            Duration::from_millis(10),
+        )
+        .await;
+
+        assert_eq!(
+            Some(AlgonautError::CatchupTimeout {
+                catchpoint: CATCHPOINT.to_owned()
+            }),
+            result.err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_not_catching_up() {
+        let algod = MockAlgod::new();
+        let mut not_catching_up = status("");
+        not_catching_up.catchpoint = None;
+        algod.respond("status", Ok(not_catching_up));
+
+        wait_for_catchup_completion(&algod, Duration::ZERO, Duration::from_secs(60))
+            .await
+            .unwrap();
+
+        assert_eq!(1, algod.calls().len());
+    }
+}
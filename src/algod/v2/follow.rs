@@ -0,0 +1,534 @@
+use algonaut_core::Round;
+use algonaut_crypto::HashDigest;
+use algonaut_model::algod::v2::Block;
+use futures_util::{stream, Stream};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use super::AlgodService;
+use crate::error::AlgonautError;
+
+/// Default delay before retrying after the first error.
+const DEFAULT_MIN_BACKOFF: Duration = Duration::from_millis(500);
+/// Default maximum delay between retries: the delay doubles after each consecutive error.
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(30);
+/// Number of yielded rounds whose hashes are kept to check the next blocks against.
+const MAX_TRACKED_HASHES: usize = 16;
+/// Number of times the hash of a block may change while it's fetched: one more change fails the
+/// attempt.
+const MAX_HASH_CHANGES: usize = 3;
+
+/// A block and its round, yielded by [BlockFollower].
+#[derive(Debug, Clone)]
+pub struct BlockWithRound {
+    pub round: Round,
+    pub block: Block,
+    /// The hash of the block, if the follower [checks the hashes](BlockFollower::with_block_hashes).
+    pub hash: Option<HashDigest>,
+}
+
+/// Follows the blocks produced by a node: yields every block from a round onward, in order, as
+/// the node produces them.
+///
+/// Waits for new blocks long-polling [status_after_round](AlgodService::status_after_round) and
+/// fetches, without waiting, all the blocks the node already has (e.g. when starting from a past
+/// round, or after falling behind).
+///
+/// Errors are yielded without ending the stream: the failed step is retried on the next poll,
+/// after a delay growing exponentially with the consecutive errors. Drop the stream to stop
+/// following.
+///
+/// # Consistency
+///
+/// By default, each block is yielded as the node serves it when it's fetched: a node can briefly
+/// serve a block that's later superseded (e.g. while it catches up), and the follower doesn't
+/// notice it.
+///
+/// [with_block_hashes](Self::with_block_hashes) fetches the hash of each block (before and after
+/// the block, until both agree, so that it's the hash of the yielded block: if the hash changes more
+/// than 3 times, the attempt fails with [AlgonautError::UnstableBlockHash]), and checks that
+/// each block links to the hash of the block yielded before it (its `prev` field). If it doesn't,
+/// the previous block was superseded: the follower goes back and yields the previous round again,
+/// as many rounds back as needed (up to the last 16 yielded rounds). A round yielded again
+/// supersedes the block yielded before for it, and the following rounds are all yielded again:
+/// callers that can't process a superseded block should dedupe by round, or by hash.
+///
+/// [with_confirmation](Self::with_confirmation) additionally reads the hash of each block again
+/// after a delay, and yields the block only once two reads agree, fetching it again until they do.
+/// This delays each block, but the superseded blocks are rarely yielded at all.
+///
+/// The blocks are fetched as MessagePack, without their certificate (see [Algod::block](super::Algod::block)).
+///
+/// Requires the `tokio` feature (a default one), for the timer of the delays, and isn't available
+/// with the WASM target.
+#[derive(Debug, Clone)]
+pub struct BlockFollower<S> {
+    service: S,
+    from: Option<Round>,
+    min_backoff: Duration,
+    max_backoff: Duration,
+    block_hashes: bool,
+    confirmation: Option<Duration>,
+}
+
+impl<S: AlgodService> BlockFollower<S> {
+    /// Follows the blocks from round `from`, included.
+    pub fn new(service: S, from: Round) -> BlockFollower<S> {
+        BlockFollower {
+            service,
+            from: Some(from),
+            min_backoff: DEFAULT_MIN_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+            block_hashes: false,
+            confirmation: None,
+        }
+    }
+
+    /// Follows the blocks from the latest round of the node when the stream is first polled,
+    /// included.
+    pub fn from_latest(service: S) -> BlockFollower<S> {
+        BlockFollower {
+            from: None,
+            ..BlockFollower::new(service, Round(0))
+        }
+    }
+
+    /// Sets the delay before retrying after an error, doubling after each consecutive error up to
+    /// `max` (by default from 500 milliseconds up to 30 seconds).
+    pub fn with_backoff(self, min: Duration, max: Duration) -> BlockFollower<S> {
+        BlockFollower {
+            min_backoff: min,
+            max_backoff: max,
+            ..self
+        }
+    }
+
+    /// Fetches the hash of each block, and yields the previous rounds again when a block doesn't
+    /// link to the one yielded before it. See [the consistency guarantees](Self#consistency).
+    pub fn with_block_hashes(self) -> BlockFollower<S> {
+        BlockFollower {
+            block_hashes: true,
+            ..self
+        }
+    }
+
+    /// Checks the hashes of the blocks as [with_block_hashes](Self::with_block_hashes), and
+    /// yields each block only once its hash is the same after `delay`. See
+    /// [the consistency guarantees](Self#consistency).
+    pub fn with_confirmation(self, delay: Duration) -> BlockFollower<S> {
+        BlockFollower {
+            block_hashes: true,
+            confirmation: Some(delay),
+            ..self
+        }
+    }
+
+    /// The blocks, as a stream.
+    pub fn into_stream(self) -> impl Stream<Item = Result<BlockWithRound, AlgonautError>> {
+        let state = FollowState {
+            next: self.from,
+            latest: None,
+            backoff: None,
+            hashes: VecDeque::new(),
+            follower: self,
+        };
+        stream::unfold(state, |mut state| async move {
+            let block = state.next_block().await;
+            Some((block, state))
+        })
+    }
+}
+
+struct FollowState<S> {
+    follower: BlockFollower<S>,
+    /// Round of the next block to yield, `None` until the latest round is known when following
+    /// from the latest.
+    next: Option<Round>,
+    /// Latest round known to be available on the node.
+    latest: Option<Round>,
+    /// Delay before the next attempt, after an error.
+    backoff: Option<Duration>,
+    /// Hashes of the last yielded blocks, by round, when checking the hashes.
+    hashes: VecDeque<(Round, HashDigest)>,
+}
+
+impl<S: AlgodService> FollowState<S> {
+    async fn next_block(&mut self) -> Result<BlockWithRound, AlgonautError> {
+        if let Some(backoff) = self.backoff {
+            tokio::time::sleep(backoff).await;
+        }
+        let block = self.try_next_block().await;
+        self.backoff = match block {
+            Ok(_) => None,
+            Err(_) => Some(match self.backoff {
+                Some(backoff) => (backoff * 2).min(self.follower.max_backoff),
+                None => self.follower.min_backoff,
+            }),
+        };
+        block
+    }
+
+    async fn try_next_block(&mut self) -> Result<BlockWithRound, AlgonautError> {
+        let service = &self.follower.service;
+        let next = match self.next {
+            Some(next) => next,
+            None => {
+                let latest = Round(service.status().await?.last_round);
+                self.latest = Some(latest);
+                self.next = Some(latest);
+                latest
+            }
+        };
+        // status_after_round returns when the node has a block after the round, or after a timeout.
+        while !matches!(self.latest, Some(latest) if latest >= next) {
+            let status = service.status_after_round(next.saturating_sub(1)).await?;
+            self.latest = Some(Round(status.last_round));
+        }
+        let block = self.fetch_block(next).await?;
+        self.next = Some(block.round + 1);
+        if let Some(hash) = block.hash {
+            if self.hashes.len() == MAX_TRACKED_HASHES {
+                self.hashes.pop_front();
+            }
+            self.hashes.push_back((block.round, hash));
+        }
+        Ok(block)
+    }
+
+    /// Fetches the block of `round` and, if checking the hashes, its (confirmed) hash.
+    ///
+    /// When checking the hashes and the block doesn't link to the previous yielded block, fetches
+    /// the previous rounds again, until a block links to a yielded block: the returned block can be
+    /// of an earlier round.
+    async fn fetch_block(&mut self, mut round: Round) -> Result<BlockWithRound, AlgonautError> {
+        if !self.follower.block_hashes {
+            let block = self.follower.service.block(round).await?;
+            return Ok(BlockWithRound {
+                round,
+                block,
+                hash: None,
+            });
+        }
+        loop {
+            let (block, hash) = self.fetch_confirmed_block(round).await?;
+            let previous = block.block.previous_hash()?;
+            match self.hashes.back() {
+                Some((yielded_round, yielded_hash))
+                    if *yielded_round + 1 == round
+                        && previous.is_some_and(|previous| previous != *yielded_hash) =>
+                {
+                    // the previous yielded block was superseded
+                    round = *yielded_round;
+                    self.hashes.pop_back();
+                }
+                _ => {
+                    return Ok(BlockWithRound {
+                        round,
+                        block,
+                        hash: Some(hash),
+                    })
+                }
+            }
+        }
+    }
+
+    /// Fetches the block of `round` between two reads of its hash (separated by the confirmation
+    /// delay, if any), until they agree, failing with [AlgonautError::UnstableBlockHash] once the
+    /// hash changed more than [MAX_HASH_CHANGES] times.
+    async fn fetch_confirmed_block(
+        &self,
+        round: Round,
+    ) -> Result<(Block, HashDigest), AlgonautError> {
+        let service = &self.follower.service;
+        let mut hash = service.block_hash(round).await?.hash()?;
+        for _ in 0..=MAX_HASH_CHANGES {
+            let block = service.block(round).await?;
+            if let Some(delay) = self.follower.confirmation {
+                tokio::time::sleep(delay).await;
+            }
+            let confirmed_hash = service.block_hash(round).await?.hash()?;
+            if confirmed_hash == hash {
+                return Ok((block, hash));
+            }
+            hash = confirmed_hash;
+        }
+        Err(AlgonautError::UnstableBlockHash { round })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockAlgod;
+    use algonaut_model::algod::v2::{BlockHashResponse, NodeStatus};
+    use data_encoding::BASE32_NOPAD;
+    use futures_util::StreamExt;
+
+    fn status(last_round: u64) -> NodeStatus {
+        serde_json::from_value(serde_json::json!({
+            "catchup-time": 0,
+            "last-round": last_round,
+            "last-version": "v1",
+            "next-version": "v1",
+            "next-version-round": last_round + 1,
+            "next-version-supported": true,
+            "stopped-at-unsupported-round": false,
+            "time-since-last-round": 0
+        }))
+        .unwrap()
+    }
+
+    fn block(round: u64) -> Block {
+        block_after(round, "")
+    }
+
+    /// The block of `round`, following the block with the hash `prev`.
+    fn block_after(round: u64, prev: &str) -> Block {
+        serde_json::from_value(serde_json::json!({
+            "block": {
+                "fees": "",
+                "frac": 0,
+                "gen": "testnet-v1.0",
+                "gh": "",
+                "prev": prev,
+                "proto": "",
+                "rate": 0,
+                "rnd": round,
+                "rwcalr": 0,
+                "rwd": "",
+                "seed": "",
+                "ts": 0
+            }
+        }))
+        .unwrap()
+    }
+
+    /// A block hash, in the encoding of algod.
+    fn hash(byte: u8) -> String {
+        BASE32_NOPAD.encode(&[byte; 32])
+    }
+
+    fn respond_block(algod: &MockAlgod, round: u64, hash_byte: u8, prev_byte: u8) {
+        let prev = format!("blk-{}", hash(prev_byte));
+        algod.respond_to("block", Round(round), Ok(block_after(round, &prev)));
+        algod.respond_to(
+            "block_hash",
+            Round(round),
+            Ok(BlockHashResponse {
+                block_hash: hash(hash_byte),
+            }),
+        );
+    }
+
+    /// The rounds and first hash bytes of the blocks of a stream.
+    async fn hashed_rounds(
+        stream: &mut (impl Stream<Item = Result<BlockWithRound, AlgonautError>> + Unpin),
+        count: usize,
+    ) -> Vec<(u64, u8)> {
+        let mut rounds = vec![];
+        for _ in 0..count {
+            let block = stream.next().await.unwrap().unwrap();
+            rounds.push((block.round.0, block.hash.unwrap().0[0]));
+        }
+        rounds
+    }
+
+    /// A node that has the blocks up to `latest`, and produces the next ones when waited for.
+    fn node(latest: u64, produced: u64) -> MockAlgod {
+        let algod = MockAlgod::new();
+        algod.respond("status", Ok(status(latest)));
+        for round in 0..latest + produced {
+            algod.respond_to(
+                "status_after_round",
+                Round(round),
+                Ok(status(latest.max(round + 1))),
+            );
+        }
+        for round in 0..=latest + produced {
+            algod.respond_to("block", Round(round), Ok(block(round)));
+        }
+        algod
+    }
+
+    async fn rounds(
+        follower: BlockFollower<&MockAlgod>,
+        count: usize,
+    ) -> Vec<Result<u64, AlgonautError>> {
+        follower
+            .into_stream()
+            .take(count)
+            .map(|block| block.map(|b| b.round.0))
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_follow_catches_up_then_waits() {
+        let algod = node(5, 2);
+        let follower = BlockFollower::new(&algod, Round(3));
+
+        let rounds = rounds(follower, 5).await;
+
+        assert_eq!(vec![Ok(3), Ok(4), Ok(5), Ok(6), Ok(7)], rounds);
+        let waits: Vec<String> = algod
+            .calls()
+            .into_iter()
+            .filter(|call| call.method == "status_after_round")
+            .map(|call| call.args)
+            .collect();
+        // one wait to learn the latest round, then only for the blocks not produced yet
+        assert_eq!(vec!["Round(2)", "Round(5)", "Round(6)"], waits);
+    }
+
+    #[tokio::test]
+    async fn test_follow_from_latest() {
+        let algod = node(5, 1);
+        let follower = BlockFollower::from_latest(&algod);
+
+        assert_eq!(vec![Ok(5), Ok(6)], rounds(follower, 2).await);
+    }
+
+    #[tokio::test]
+    async fn test_follow_resumes_after_errors() {
+        let algod = node(5, 0);
+        // round 6 isn't produced yet: waiting for it fails
+        let follower = BlockFollower::new(&algod, Round(5))
+            .with_backoff(Duration::from_millis(1), Duration::from_millis(2));
+        let mut stream = Box::pin(follower.into_stream());
+
+        assert_eq!(5, stream.next().await.unwrap().unwrap().round.0);
+        assert!(stream.next().await.unwrap().is_err());
+        assert!(stream.next().await.unwrap().is_err());
+
+        // the node produces round 6
+        algod.respond_to("status_after_round", Round(5), Ok(status(6)));
+        algod.respond_to("block", Round(6), Ok(block(6)));
+        assert_eq!(6, stream.next().await.unwrap().unwrap().round.0);
+    }
+
+    #[tokio::test]
+    async fn test_follow_with_block_hashes() {
+        let algod = node(7, 0);
+        respond_block(&algod, 5, 5, 4);
+        respond_block(&algod, 6, 6, 5);
+        respond_block(&algod, 7, 7, 6);
+        let mut stream = Box::pin(
+            BlockFollower::new(&algod, Round(5))
+                .with_block_hashes()
+                .into_stream(),
+        );
+
+        assert_eq!(
+            vec![(5, 5), (6, 6), (7, 7)],
+            hashed_rounds(&mut stream, 3).await
+        );
+    }
+
+    #[tokio::test]
+    async fn test_follow_yields_superseded_rounds_again() {
+        let algod = node(8, 0);
+        respond_block(&algod, 5, 5, 4);
+        respond_block(&algod, 6, 6, 5);
+        let mut stream = Box::pin(
+            BlockFollower::new(&algod, Round(5))
+                .with_block_hashes()
+                .into_stream(),
+        );
+        assert_eq!(vec![(5, 5), (6, 6)], hashed_rounds(&mut stream, 2).await);
+
+        // rounds 5 and 6 are superseded by blocks with the hashes 15 and 16
+        respond_block(&algod, 5, 15, 4);
+        respond_block(&algod, 6, 16, 15);
+        respond_block(&algod, 7, 17, 16);
+        respond_block(&algod, 8, 18, 17);
+
+        assert_eq!(
+            vec![(5, 15), (6, 16), (7, 17), (8, 18)],
+            hashed_rounds(&mut stream, 4).await
+        );
+    }
+
+    #[tokio::test]
+    async fn test_follow_with_confirmation_waits_for_a_stable_hash() {
+        let algod = node(5, 0);
+        respond_block(&algod, 5, 5, 4);
+        let follower =
+            BlockFollower::new(&algod, Round(5)).with_confirmation(Duration::from_millis(200));
+        let mut stream = Box::pin(follower.into_stream());
+
+        // the block changes while it's being confirmed
+        let supersede = async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            respond_block(&algod, 5, 15, 14);
+        };
+        let (block, _) = futures_util::join!(stream.next(), supersede);
+
+        let block = block.unwrap().unwrap();
+        assert_eq!(15, block.hash.unwrap().0[0]);
+        // the block yielded is the one with the confirmed hash
+        assert_eq!(format!("blk-{}", hash(14)), block.block.block.prev);
+        let hash_reads = algod
+            .calls()
+            .into_iter()
+            .filter(|call| call.method == "block_hash")
+            .count();
+        assert_eq!(3, hash_reads);
+    }
+
+    #[tokio::test]
+    async fn test_follow_gives_up_on_a_changing_hash() {
+        let algod = node(5, 0);
+        respond_block(&algod, 5, 0, 4);
+        let follower =
+            BlockFollower::new(&algod, Round(5)).with_confirmation(Duration::from_millis(50));
+        let mut stream = Box::pin(follower.into_stream());
+        let hash_reads = || {
+            algod
+                .calls()
+                .into_iter()
+                .filter(|call| call.method == "block_hash")
+                .count()
+        };
+
+        // the hash changes after each read
+        let change = async {
+            let mut reads = 0;
+            while reads <= MAX_HASH_CHANGES {
+                tokio::time::sleep(Duration::from_millis(1)).await;
+                if hash_reads() > reads {
+                    reads = hash_reads();
+                    respond_block(&algod, 5, reads as u8, 4);
+                }
+            }
+        };
+        let (block, _) = futures_util::join!(stream.next(), change);
+
+        assert_eq!(
+            Some(Err(AlgonautError::UnstableBlockHash { round: Round(5) })),
+            block.map(|block| block.map(|b| b.round))
+        );
+        assert_eq!(MAX_HASH_CHANGES + 2, hash_reads());
+    }
+
+    #[tokio::test]
+    async fn test_follow_fails_on_invalid_block_hash() {
+        let algod = node(5, 0);
+        algod.respond_to(
+            "block_hash",
+            Round(5),
+            Ok(BlockHashResponse {
+                block_hash: "not a hash".to_owned(),
+            }),
+        );
+        let mut stream = Box::pin(
+            BlockFollower::new(&algod, Round(5))
+                .with_block_hashes()
+                .into_stream(),
+        );
+
+        assert_eq!(
+            Some(Err(AlgonautError::FieldDecode { field: "blockHash" })),
+            stream.next().await.map(|block| block.map(|b| b.round))
+        );
+    }
+}
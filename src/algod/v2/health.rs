@@ -0,0 +1,163 @@
+use algonaut_core::{MicroAlgos, Round};
+use algonaut_model::algod::v2::{BuildVersion, UpgradeVote};
+use futures_util::future::try_join3;
+use std::time::Duration;
+
+use super::AlgodService;
+use crate::error::AlgonautError;
+
+/// The health of the network as seen by a node, e.g. for a monitoring dashboard: see
+/// [Algod::network_health](super::Algod::network_health).
+#[derive(Debug, Clone)]
+pub struct NetworkHealth {
+    /// The last round seen by the node.
+    pub round: Round,
+    /// The time since the last round: above the block time (about 3 seconds), the network or the
+    /// node is stalling.
+    pub time_since_last_round: Duration,
+    /// The catchpoint the node is catching up to (fast catchup), if any.
+    pub catchpoint: Option<String>,
+    /// The time the node spent catching up.
+    pub catchup_time: Duration,
+    /// The consensus protocol version of the last round.
+    pub protocol_version: String,
+    /// The consensus protocol version of the rounds from `next_protocol_round`, the same as
+    /// `protocol_version` unless an upgrade was approved.
+    pub next_protocol_version: String,
+    /// The round from which `next_protocol_version` applies.
+    pub next_protocol_round: Round,
+    /// Whether the node supports `next_protocol_version`: if not, it stops at
+    /// `next_protocol_round`.
+    pub next_protocol_supported: bool,
+    /// The vote on a proposed consensus upgrade, if one is in progress.
+    pub upgrade_vote: Option<UpgradeVote>,
+    /// The MicroAlgos of the online accounts, i.e. the stake participating in the consensus.
+    pub online_money: MicroAlgos,
+    /// The MicroAlgos of all the accounts.
+    pub total_money: MicroAlgos,
+    /// The network of the node, e.g. "mainnet-v1.0".
+    pub genesis_id: String,
+    /// The version of the node software.
+    pub build: BuildVersion,
+}
+
+/// Requests the status, the ledger supply and the versions of the node concurrently, into a
+/// [NetworkHealth].
+pub(crate) async fn network_health<S: AlgodService>(
+    service: &S,
+) -> Result<NetworkHealth, AlgonautError> {
+    let (status, supply, version) = try_join3(
+        service.status(),
+        service.ledger_supply(),
+        service.versions(),
+    )
+    .await?;
+    Ok(NetworkHealth {
+        round: Round(status.last_round),
+        time_since_last_round: status.time_since_last_round,
+        catchpoint: status.catchpoint.clone().filter(|c| !c.is_empty()),
+        catchup_time: status.catchup_time,
+        upgrade_vote: status.upgrade_vote(),
+        protocol_version: status.last_version,
+        next_protocol_version: status.next_version,
+        next_protocol_round: Round(status.next_version_round),
+        next_protocol_supported: status.next_version_supported,
+        online_money: MicroAlgos(supply.online_money),
+        total_money: MicroAlgos(supply.total_money),
+        genesis_id: version.genesis_id,
+        build: version.build,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algod::v2::Algod;
+    use algonaut_client::test_server::{TestResponse, TestServer};
+
+    const TOKEN: &str = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+
+    /// Time spent by the server on each request.
+    const LATENCY: Duration = Duration::from_millis(100);
+
+    const STATUS: &str = r#"{
+        "catchup-time": 1500000000,
+        "last-round": 38000000,
+        "last-version": "https://github.com/algorandfoundation/specs/tree/925a46433742afb0b51bb939354bd907fa88bf95",
+        "next-version": "https://github.com/algorandfoundation/specs/tree/925a46433742afb0b51bb939354bd907fa88bf95",
+        "next-version-round": 38000001,
+        "next-version-supported": true,
+        "stopped-at-unsupported-round": false,
+        "time-since-last-round": 2750000123,
+        "upgrade-delay": 0,
+        "upgrade-next-protocol-vote-before": 38009000,
+        "upgrade-no-votes": 20,
+        "upgrade-node-vote": true,
+        "upgrade-vote-rounds": 10000,
+        "upgrade-votes": 1000,
+        "upgrade-votes-required": 9000,
+        "upgrade-yes-votes": 980
+    }"#;
+
+    const SUPPLY: &str = r#"{"current_round": 38000000, "online-money": 1900000000000000, "total-money": 10000000000000000}"#;
+
+    const VERSIONS: &str = r#"{
+        "build": {"branch": "rel/stable", "build_number": 0, "channel": "stable", "commit_hash": "abcdef12", "major": 3, "minor": 24},
+        "genesis_hash_b64": "wGHE2Pwdvd7S12BL5FaOP20EGYesN73ktiC1qzkkit8=",
+        "genesis_id": "mainnet-v1.0",
+        "versions": ["v2"]
+    }"#;
+
+    /// Serves the status, the supply and the versions slowly, on separate connections if the
+    /// requests are concurrent.
+    fn serve() -> TestServer {
+        TestServer::handle(|request| {
+            let body = match request.line.split(' ').nth(1).unwrap_or_default() {
+                "/v2/status" => STATUS,
+                "/v2/ledger/supply" => SUPPLY,
+                _ => VERSIONS,
+            };
+            TestResponse::json(body).delay(LATENCY)
+        })
+    }
+
+    #[tokio::test]
+    async fn test_network_health() {
+        let server = serve();
+        let algod = Algod::new(server.url(), TOKEN).unwrap();
+
+        let health = algod.network_health().await.unwrap();
+
+        assert_eq!(Round(38000000), health.round);
+        assert_eq!(
+            Duration::from_secs(2) + Duration::from_nanos(750000123),
+            health.time_since_last_round
+        );
+        assert_eq!(Duration::from_millis(1500), health.catchup_time);
+        assert_eq!(None, health.catchpoint);
+        assert_eq!(health.protocol_version, health.next_protocol_version);
+        assert_eq!(
+            Some(UpgradeVote {
+                vote_before: Round(38009000),
+                vote_rounds: 10000,
+                votes: 1000,
+                yes_votes: 980,
+                no_votes: 20,
+                votes_required: 9000,
+                node_vote: true,
+            }),
+            health.upgrade_vote
+        );
+        assert_eq!(MicroAlgos(1900000000000000), health.online_money);
+        assert_eq!("mainnet-v1.0", health.genesis_id);
+        assert_eq!(24, health.build.minor);
+
+        // the three requests overlap
+        let requests = server.received();
+        assert_eq!(3, requests.len());
+        let starts = requests.iter().map(|request| request.received_at);
+        let last_start = starts.clone().max().unwrap();
+        let first_end = starts.min().unwrap() + LATENCY;
+        assert!(last_start < first_end);
+    }
+}
@@ -1,18 +1,55 @@
-use algonaut_client::{algod::v2::Client, token::ApiToken, Headers};
-use algonaut_core::{Address, CompiledTeal, Round, SuggestedTransactionParams, ToMsgPack};
+use algonaut_client::{algod::v2::Client, token::ApiToken, ClientConfig, Headers};
+use algonaut_core::{
+    Address, CompiledTeal, MicroAlgos, Network, Round, SuggestedTransactionParams, ToMsgPack,
+};
 use algonaut_encoding::decode_base64;
 use algonaut_model::algod::v2::{
-    Account, Application, Asset, Block, Catchup, DryrunRequest, DryrunResponse, GenesisBlock,
-    KeyRegistration, NodeStatus, PendingTransaction, PendingTransactions, Supply,
-    TransactionParams, TransactionResponse, Version,
+    Account, ApiSpec, Application, Asset, Block, BlockHashResponse, Catchup, DryrunRequest,
+    DryrunResponse, GenesisBlock, KeyRegistration, NodeStatus, PendingTransaction,
+    PendingTransactions, Supply, TransactionParams, TransactionResponse, Version,
 };
-use algonaut_transaction::SignedTransaction;
-
-use crate::error::AlgonautError;
-
-#[derive(Debug)]
+use algonaut_transaction::transaction::{
+    ApplicationCallOnComplete, ApplicationCallTransaction, StateSchema,
+};
+use algonaut_transaction::{SignedTransaction, Transaction, TransactionType};
+#[cfg(all(not(target_arch = "wasm32"), feature = "tokio"))]
+use futures_util::Stream;
+use std::future::Future;
+use std::sync::Arc;
+#[cfg(all(not(target_arch = "wasm32"), feature = "tokio"))]
+use std::time::Duration;
+
+use crate::batch;
+use crate::error::{AlgonautError, ResponseMeta};
+use crate::min_balance::{self, AccountFootprint};
+use crate::provider::NodePreset;
+
+mod capabilities;
+mod catchup;
+#[cfg(all(not(target_arch = "wasm32"), feature = "tokio"))]
+mod follow;
+mod health;
+mod rejection;
+mod service;
+
+pub use capabilities::Capabilities;
+#[cfg(all(not(target_arch = "wasm32"), feature = "tokio"))]
+pub use follow::{BlockFollower, BlockWithRound};
+pub use health::NetworkHealth;
+pub use rejection::AlgodSemanticError;
+pub use service::AlgodService;
+
+use capabilities::CachedCapabilities;
+use catchup::validate_catchpoint;
+
+/// The class of API token required by the node management endpoints (e.g. catchup, shutdown).
+const ADMIN_TOKEN: &str = "admin";
+
+#[derive(Debug, Clone)]
 pub struct Algod {
     pub(crate) client: Client,
+    /// The capabilities of the node, probed on first use, shared with the clones.
+    capabilities: Arc<CachedCapabilities>,
 }
 
 impl Algod {
@@ -31,16 +68,78 @@ impl Algod {
     ///
     /// Returns an error if the url or headers have an invalid format.
     pub fn with_headers(url: &str, headers: Headers) -> Result<Algod, AlgonautError> {
+        Self::with_config(url, headers, &ClientConfig::default())
+    }
+
+    /// Build a v2 client for Algorand protocol daemon.
+    /// Use this initializer to tune the HTTP client, e.g. the connection pool of high-throughput services.
+    /// The token is passed as a header, e.g. `vec![("X-Algo-API-Token", token)]`.
+    ///
+    /// Returns an error if the url or headers have an invalid format.
+    pub fn with_config(
+        url: &str,
+        headers: Headers,
+        config: &ClientConfig,
+    ) -> Result<Algod, AlgonautError> {
         Ok(Algod {
-            client: Client::new(url, headers)?,
+            client: Client::with_config(url, headers, config)?,
+            capabilities: Arc::default(),
         })
     }
 
+    /// Build a v2 client for the algod of a local sandbox, see [NodePreset::sandbox_algod].
+    pub fn sandbox() -> Result<Algod, AlgonautError> {
+        NodePreset::sandbox_algod().algod()
+    }
+
+    /// Build a v2 client for the free algod of AlgoNode, see [NodePreset::algonode_algod].
+    pub fn with_algonode(network: &Network) -> Result<Algod, AlgonautError> {
+        NodePreset::algonode_algod(network)?.algod()
+    }
+
+    /// Build a v2 client for the algod of PureStake, see [NodePreset::purestake_algod].
+    pub fn with_purestake(network: &Network, api_key: &str) -> Result<Algod, AlgonautError> {
+        NodePreset::purestake_algod(network, api_key)?.algod()
+    }
+
     /// Returns the entire genesis file in json.
     pub async fn genesis(&self) -> Result<GenesisBlock, AlgonautError> {
         Ok(self.client.genesis().await?)
     }
 
+    /// Status and rate limit headers of the latest response received by this client or its clones,
+    /// e.g. to throttle requests to a provider. Failed requests also carry them in the error
+    /// ([AlgonautError::response_meta]).
+    ///
+    /// With concurrent requests, the latest response may belong to any of them: see
+    /// [with_meta](Self::with_meta) for the meta of a call.
+    pub fn last_response_meta(&self) -> Option<ResponseMeta> {
+        self.client.last_response_meta()
+    }
+
+    /// Makes `call` with a clone of this client, returning its result with the status and rate
+    /// limit headers of the latest response received by the call, if it sent a request, e.g.
+    /// `algod.with_meta(|algod| async move { algod.status().await })`.
+    ///
+    /// Unlike [last_response_meta](Self::last_response_meta), the meta is the one of this call,
+    /// even with concurrent requests. Failed requests carry it in the error
+    /// ([AlgonautError::response_meta]).
+    pub async fn with_meta<T, F, Fut>(
+        &self,
+        call: F,
+    ) -> Result<(T, Option<ResponseMeta>), AlgonautError>
+    where
+        F: FnOnce(Algod) -> Fut,
+        Fut: Future<Output = Result<T, AlgonautError>>,
+    {
+        let algod = Algod {
+            client: self.client.with_own_meta(),
+            ..self.clone()
+        };
+        let value = call(algod.clone()).await?;
+        Ok((value, algod.last_response_meta()))
+    }
+
     /// Returns Ok if healthy
     pub async fn health(&self) -> Result<(), AlgonautError> {
         Ok(self.client.health().await?)
@@ -61,49 +160,140 @@ impl Algod {
             .await?)
     }
 
+    /// Predicts the minimum balance of the account after the `planned` transactions (e.g. to fund it
+    /// beforehand): its current minimum balance, plus the assets and applications created or opted
+    /// in by the transactions it sends.
+    ///
+    /// The opt-ins to existing applications look up their local schema. Other transactions (e.g.
+    /// close-outs or deletions) are ignored, so the result is an upper bound. See
+    /// [min_balance] for the consensus parameters.
+    pub async fn required_balance_after(
+        &self,
+        address: &Address,
+        planned: &[Transaction],
+    ) -> Result<MicroAlgos, AlgonautError> {
+        let account = self.account_information(address).await?;
+        let mut footprint = AccountFootprint::from(&account);
+        for txn in planned.iter().filter(|txn| txn.sender() == *address) {
+            let opted_in_local_schema = match &txn.txn_type {
+                TransactionType::AssetAcceptTransaction(accept)
+                    if account.assets.iter().any(|a| a.asset_id == accept.xfer) =>
+                {
+                    continue
+                }
+                TransactionType::ApplicationCallTransaction(ApplicationCallTransaction {
+                    app_id: Some(app_id),
+                    on_complete: ApplicationCallOnComplete::OptIn,
+                    ..
+                }) if *app_id != 0 => {
+                    let app = self.application_information(*app_id).await?;
+                    app.params.local_state_schema.map(|schema| StateSchema {
+                        number_ints: schema.num_uint,
+                        number_byteslices: schema.num_byte_slice,
+                    })
+                }
+                _ => None,
+            };
+            footprint.add_transaction(&txn.txn_type, opted_in_local_schema.as_ref());
+        }
+        Ok(min_balance::compute(footprint))
+    }
+
     /// Get a list of unconfirmed transactions currently in the transaction pool by address.
     /// Description: Get the list of pending transactions by address, sorted by priority,
     /// in decreasing order, truncated at the end at MAX. If MAX = 0, returns all pending transactions.
+    ///
+    /// The transactions are decoded from MessagePack, with their signatures, and their ids set.
+    /// [PendingTransactions::is_truncated] tells whether the pool has more of them than `max`.
     pub async fn pending_transactions_for(
         &self,
         address: &Address,
         max: u64,
-    ) -> Result<PendingTransactions, AlgonautError> {
-        Ok(self
-            .client
-            .pending_transactions_for(&address.to_string(), max)
-            .await?)
+    ) -> Result<PendingTransactions<SignedTransaction>, AlgonautError> {
+        with_transaction_ids(
+            self.client
+                .pending_transactions_for(&address.to_string(), max)
+                .await?,
+        )
     }
 
     /// Get application information.
     ///
     /// Given a application id, it returns application information including creator,
     /// approval and clear programs, global and local schemas, and global state.
+    ///
+    /// An application not found (404), e.g. deleted, fails with `AlgonautError::NotFound`.
     pub async fn application_information(&self, id: u64) -> Result<Application, AlgonautError> {
-        Ok(self.client.application_information(id).await?)
+        self.client
+            .application_information(id)
+            .await
+            .map_err(|e| AlgonautError::from(e).into_not_found("application", &id.to_string()))
     }
 
     /// Get asset information.
     ///
     /// Given a asset id, it returns asset information including creator, name,
     /// total supply and special addresses.
+    ///
+    /// An asset not found (404), e.g. destroyed, fails with `AlgonautError::NotFound`.
     pub async fn asset_information(&self, id: u64) -> Result<Asset, AlgonautError> {
-        Ok(self.client.asset_information(id).await?)
+        self.client
+            .asset_information(id)
+            .await
+            .map_err(|e| AlgonautError::from(e).into_not_found("asset", &id.to_string()))
     }
 
-    /// Get the block for the given round.
+    /// Get the block for the given round, fetched as MessagePack, without its certificate (`cert`
+    /// is `None`) unless the node answers with JSON.
     pub async fn block(&self, round: Round) -> Result<Block, AlgonautError> {
         Ok(self.client.block(round).await?)
     }
 
-    /// Starts a catchpoint catchup.
+    /// Get the hash of the block for the given round.
+    pub async fn block_hash(&self, round: Round) -> Result<BlockHashResponse, AlgonautError> {
+        Ok(self.client.block_hash(round).await?)
+    }
+
+    /// Starts a catchpoint catchup (fast catchup) to the catchpoint with this label, e.g.
+    /// `7900000#IQ6ZFQ5QXTDRVDP3HWYY7YHNDL7AOCMWJRFOPXVZ7RVBIGJ2GIEQ`.
+    ///
+    /// Returns [AlgonautError::BadQuery] without sending the request if the label isn't
+    /// in the `<round>#<base32 digest>` format. Requires the admin API token:
+    /// [AlgonautError::Unauthorized] otherwise.
     pub async fn start_catchup(&self, catchpoint: &str) -> Result<Catchup, AlgonautError> {
-        Ok(self.client.start_catchup(catchpoint).await?)
+        validate_catchpoint(catchpoint)?;
+        self.client
+            .start_catchup(catchpoint)
+            .await
+            .map_err(|e| AlgonautError::from(e).into_unauthorized(ADMIN_TOKEN))
     }
 
     /// Aborts a catchpoint catchup.
+    ///
+    /// Validates the catchpoint label and requires the admin API token, as
+    /// [start_catchup](Self::start_catchup).
     pub async fn abort_catchup(&self, catchpoint: &str) -> Result<Catchup, AlgonautError> {
-        Ok(self.client.abort_catchup(catchpoint).await?)
+        validate_catchpoint(catchpoint)?;
+        self.client
+            .abort_catchup(catchpoint)
+            .await
+            .map_err(|e| AlgonautError::from(e).into_unauthorized(ADMIN_TOKEN))
+    }
+
+    /// Polls the [status](Self::status) of the node until it's not catching up to a catchpoint
+    /// anymore (e.g. after [start_catchup](Self::start_catchup)), returning the last status.
+    ///
+    /// Returns [AlgonautError::CatchupTimeout] if the node is still catching up after `timeout`.
+    /// The catchup progress is in the `catchpoint_*` fields of [NodeStatus].
+    ///
+    /// Requires the `tokio` feature (a default one), for the timer of the polling, and isn't
+    /// available with the WASM target.
+    #[cfg(all(not(target_arch = "wasm32"), feature = "tokio"))]
+    pub async fn wait_for_catchup_completion(
+        &self,
+        timeout: Duration,
+    ) -> Result<NodeStatus, AlgonautError> {
+        catchup::wait_for_catchup_completion(self, timeout, catchup::CATCHUP_POLL_INTERVAL).await
     }
 
     /// Get the current supply reported by the ledger.
@@ -119,21 +309,28 @@ impl Algod {
     /// key-dilution: value to use for two-level participation key.
     /// no-wait: Don't wait for transaction to commit before returning response.
     /// round-last-valid: The last round for which the generated participation keys will be valid.
+    ///
+    /// Requires the admin API token: [AlgonautError::Unauthorized] otherwise.
     pub async fn register_participation_keys(
         &self,
         address: &Address,
         params: &KeyRegistration,
     ) -> Result<String, AlgonautError> {
-        Ok(self
-            .client
+        self.client
             .register_participation_keys(address, params)
-            .await?)
+            .await
+            .map_err(|e| AlgonautError::from(e).into_unauthorized(ADMIN_TOKEN))
     }
 
     /// Special management endpoint to shutdown the node. Optionally provide a timeout parameter
     /// to indicate that the node should begin shutting down after a number of seconds.
+    ///
+    /// Requires the admin API token: [AlgonautError::Unauthorized] otherwise.
     pub async fn shutdown(&self, timeout: usize) -> Result<(), AlgonautError> {
-        Ok(self.client.shutdown(timeout).await?)
+        self.client
+            .shutdown(timeout)
+            .await
+            .map_err(|e| AlgonautError::from(e).into_unauthorized(ADMIN_TOKEN))
     }
 
     /// Gets the current node status.
@@ -199,12 +396,17 @@ impl Algod {
     /// Use this when using a third party (e.g. KMD) that delivers directly the serialized signed transaction.
     ///
     /// Otherwise, prefer [broadcast_signed_transaction](Self::broadcast_signed_transaction) or [broadcast_signed_transactions][Self::broadcast_signed_transactions]
-
+    ///
+    /// A transaction rejected by the node (400) fails with [AlgonautError::TransactionRejected],
+    /// with the reason parsed from the message (e.g. an overspend).
     pub async fn broadcast_raw_transaction(
         &self,
         rawtxn: &[u8],
     ) -> Result<TransactionResponse, AlgonautError> {
-        Ok(self.client.broadcast_raw_transaction(rawtxn).await?)
+        self.client
+            .broadcast_raw_transaction(rawtxn)
+            .await
+            .map_err(|e| AlgonautError::from(e).into_transaction_rejected())
     }
 
     /// Get parameters for constructing a new transaction.
@@ -212,31 +414,26 @@ impl Algod {
         Ok(self.client.transaction_params().await?)
     }
 
-    /// Get suggested parameters for constructing a new transaction.
+    /// Get suggested parameters for constructing a new transaction, with all the fields of
+    /// [transaction_params](Self::transaction_params).
     pub async fn suggested_transaction_params(
         &self,
     ) -> Result<SuggestedTransactionParams, AlgonautError> {
-        let params = self.client.transaction_params().await?;
-        Ok(SuggestedTransactionParams {
-            genesis_id: params.genesis_id,
-            genesis_hash: params.genesis_hash,
-            consensus_version: params.consensus_version,
-            fee_per_byte: params.fee_per_byte,
-            min_fee: params.min_fee,
-            first_valid: params.last_round,
-            last_valid: params.last_round + 1000,
-        })
+        Ok(self.client.transaction_params().await?.into())
     }
 
     /// Get a list of unconfirmed transactions currently in the transaction pool.
     ///
     /// Get the list of pending transactions, sorted by priority, in decreasing order,
     /// truncated at the end at MAX. If MAX = 0, returns all pending transactions.
+    ///
+    /// As [pending_transactions_for](Self::pending_transactions_for), the transactions are decoded
+    /// with their signatures and ids.
     pub async fn pending_transactions(
         &self,
         max: u64,
-    ) -> Result<PendingTransactions, AlgonautError> {
-        Ok(self.client.pending_transactions(max).await?)
+    ) -> Result<PendingTransactions<SignedTransaction>, AlgonautError> {
+        with_transaction_ids(self.client.pending_transactions(max).await?)
     }
 
     /// Get a specific pending transaction.
@@ -248,23 +445,89 @@ impl Algod {
     /// - transaction removed from pool due to error (committed round = 0, pool error != "")
     ///
     /// Or the transaction may have happened sufficiently long ago that the node no longer remembers
-    /// it, and this will return an error.
+    /// it, and this will return an `AlgonautError::NotFound`.
     pub async fn pending_transaction_with_id(
         &self,
         txid: &str,
     ) -> Result<PendingTransaction, AlgonautError> {
-        Ok(self.client.pending_transaction_with_id(txid).await?)
+        self.client
+            .pending_transaction_with_id(txid)
+            .await
+            .map_err(|e| AlgonautError::from(e).into_not_found("transaction", txid))
+    }
+
+    /// Gets the pending transactions with these ids, running up to `max_concurrency` requests at a
+    /// time (at least one), e.g. to follow the transactions of a group.
+    ///
+    /// Returns the result of each request, in the order of the ids: a transaction the node doesn't
+    /// know (404) is an error in its result. Any other error fails the whole request.
+    pub async fn pending_transactions_with_ids(
+        &self,
+        txids: &[&str],
+        max_concurrency: usize,
+    ) -> Result<Vec<Result<PendingTransaction, AlgonautError>>, AlgonautError> {
+        batch::lookup_all(txids, max_concurrency, |txid| {
+            self.pending_transaction_with_id(txid)
+        })
+        .await
+    }
+
+    /// Streams every block from round `from` onward, in order, as the node produces them.
+    ///
+    /// See [BlockFollower], which also allows to follow from the latest round, to configure the
+    /// delays between retries after errors, and to detect superseded blocks by their hashes (see
+    /// its [consistency guarantees](BlockFollower#consistency)).
+    #[cfg(all(not(target_arch = "wasm32"), feature = "tokio"))]
+    pub fn follow_blocks(
+        &self,
+        from: Round,
+    ) -> impl Stream<Item = Result<BlockWithRound, AlgonautError>> + '_ {
+        BlockFollower::new(self, from).into_stream()
     }
 
     /// Retrieves the current version
     pub async fn versions(&self) -> Result<Version, AlgonautError> {
         Ok(self.client.versions().await?)
     }
+
+    /// The specification of the REST API served by the node (`/swagger.json`), e.g. to check
+    /// whether it serves an endpoint.
+    pub async fn api_spec(&self) -> Result<ApiSpec, AlgonautError> {
+        Ok(self.client.api_spec().await?)
+    }
+
+    /// The version of the node and the optional endpoints it serves, probed with
+    /// [Capabilities::probe] on the first call and cached for the next ones.
+    pub async fn capabilities(&self) -> Result<Capabilities, AlgonautError> {
+        self.capabilities.get_or_probe(self).await
+    }
+
+    /// The [status](Self::status), [supply](Self::ledger_supply) and [versions](Self::versions)
+    /// of the node in one [NetworkHealth], e.g. for a monitoring dashboard.
+    ///
+    /// The three requests are sent concurrently: the first error fails the call.
+    pub async fn network_health(&self) -> Result<NetworkHealth, AlgonautError> {
+        health::network_health(self).await
+    }
+}
+
+/// Sets the ids of the decoded pending transactions, which aren't part of their encoding.
+fn with_transaction_ids(
+    mut pending: PendingTransactions<SignedTransaction>,
+) -> Result<PendingTransactions<SignedTransaction>, AlgonautError> {
+    for signed in &mut pending.top_transactions {
+        signed.transaction_id = signed
+            .transaction
+            .id()
+            .map_err(|e| AlgonautError::Internal(e.to_string()))?;
+    }
+    Ok(pending)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use algonaut_client::test_server::{TestResponse, TestServer, UNREACHABLE_URL};
 
     #[test]
     fn test_valid_client_builder() {
@@ -311,4 +574,296 @@ mod tests {
         assert!(res.is_err());
         assert!(res.err().unwrap() == AlgonautError::BadToken);
     }
+
+    const CATCHPOINT: &str = "7900000#IQ6ZFQ5QXTDRVDP3HWYY7YHNDL7AOCMWJRFOPXVZ7RVBIGJ2GIEQ";
+    const TOKEN: &str = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+
+    /// Answers a request with this error status line and JSON body.
+    fn serve_error(status: &str, body: &'static str) -> TestServer {
+        TestServer::serve(vec![TestResponse::json(body).status(status)])
+    }
+
+    /// Answers a request with a 401.
+    fn serve_unauthorized() -> TestServer {
+        serve_error("401 Unauthorized", r#"{"message":"Invalid API Token"}"#)
+    }
+
+    #[tokio::test]
+    async fn test_start_catchup_requires_the_admin_token() {
+        let server = serve_unauthorized();
+        let algod = Algod::new(server.url(), TOKEN).unwrap();
+
+        let res = algod.start_catchup(CATCHPOINT).await;
+
+        assert!(matches!(
+            res,
+            Err(AlgonautError::Unauthorized { token: "admin", .. })
+        ));
+        assert_eq!(
+            "POST /v2/catchup/7900000%23IQ6ZFQ5QXTDRVDP3HWYY7YHNDL7AOCMWJRFOPXVZ7RVBIGJ2GIEQ HTTP/1.1",
+            server.request_line()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_abort_catchup_with_invalid_catchpoint_isnt_sent() {
+        let algod = Algod::new(UNREACHABLE_URL, TOKEN).unwrap();
+
+        let res = algod.abort_catchup("7900000").await;
+
+        assert_eq!(
+            Err(AlgonautError::BadQuery {
+                reason: "invalid catchpoint \"7900000\": expected <round>#<base32 digest>"
+                    .to_owned(),
+                param: Some("catchpoint")
+            }),
+            res.map(|_| ())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_rejected_transaction() {
+        let server = serve_error(
+            "400 Bad Request",
+            r#"{"message":"TransactionPool.Remember: transaction 6NJ2ILUMLM3TAL5QHEAUXYCEGGSOVEMQYJWVSYJ3VSNPEVGJQUOQ: asset 10458941 missing from 2UEQTE5QDNXPI7M3TU44G6SYKLFWLPQO7EBZM7K7MHMQQMFI4QJPLHQFHM"}"#,
+        );
+        let algod = Algod::new(server.url(), TOKEN).unwrap();
+
+        let res = algod.broadcast_raw_transaction(&[0x80]).await;
+
+        assert_eq!(
+            Err(AlgonautError::TransactionRejected {
+                error: AlgodSemanticError::AssetNotOptedIn { asset: 10458941 },
+                message: "TransactionPool.Remember: transaction 6NJ2ILUMLM3TAL5QHEAUXYCEGGSOVEMQYJWVSYJ3VSNPEVGJQUOQ: asset 10458941 missing from 2UEQTE5QDNXPI7M3TU44G6SYKLFWLPQO7EBZM7K7MHMQQMFI4QJPLHQFHM".to_owned()
+            }),
+            res.map(|_| ())
+        );
+        assert_eq!("POST /v2/transactions HTTP/1.1", server.request_line());
+    }
+
+    #[tokio::test]
+    async fn test_asset_not_found() {
+        let server = serve_error("404 Not Found", r#"{"message":"asset does not exist"}"#);
+        let algod = Algod::new(server.url(), TOKEN).unwrap();
+
+        let res = algod.asset_information(1026).await.map(|_| ());
+
+        assert_eq!(
+            Err(AlgonautError::NotFound {
+                resource: "asset",
+                id: "1026".to_owned(),
+                message: "asset does not exist".to_owned()
+            }),
+            res
+        );
+        assert!(res.unwrap_err().is_404());
+        assert_eq!("GET /v2/assets/1026 HTTP/1.1", server.request_line());
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_other_errors_arent_rejections() {
+        let server = serve_error("500 Internal Server Error", r#"{"message":"boom"}"#);
+        let algod = Algod::new(server.url(), TOKEN).unwrap();
+
+        let res = algod.broadcast_raw_transaction(&[0x80]).await;
+
+        assert!(matches!(res, Err(AlgonautError::Request(_))));
+    }
+
+    /// Answers the requests with these JSON bodies, in order.
+    fn serve(bodies: Vec<&'static str>) -> TestServer {
+        TestServer::serve(bodies.into_iter().map(TestResponse::json).collect())
+    }
+
+    #[tokio::test]
+    async fn test_required_balance_after() {
+        use algonaut_transaction::builder::{AcceptAsset, OptInApplication};
+        use algonaut_transaction::TxnBuilder;
+
+        let server = serve(vec![
+            r#"{"address":"47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU","amount":1000000,
+                "amount-without-pending-rewards":1000000,"min-balance":200000,"pending-rewards":0,
+                "rewards":0,"round":1000,"status":"Offline","total-assets-opted-in":1,
+                "assets":[{"amount":0,"asset-id":10,"creator":"4MYUHDWHWXAKA5KA7U5PEN646VYUANBFXVJNONBK3TIMHEMWMD4UBOJBI4","is-frozen":false}]}"#,
+            r#"{"id":5,"params":{"creator":"4MYUHDWHWXAKA5KA7U5PEN646VYUANBFXVJNONBK3TIMHEMWMD4UBOJBI4",
+                "local-state-schema":{"num-byte-slice":1,"num-uint":1}}}"#,
+        ]);
+        let algod = Algod::new(server.url(), TOKEN).unwrap();
+        let address: Address = "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU"
+            .parse()
+            .unwrap();
+        let other: Address = "4MYUHDWHWXAKA5KA7U5PEN646VYUANBFXVJNONBK3TIMHEMWMD4UBOJBI4"
+            .parse()
+            .unwrap();
+        let params = SuggestedTransactionParams {
+            genesis_id: "testnet-v1.0".to_owned(),
+            genesis_hash: "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI="
+                .parse()
+                .unwrap(),
+            consensus_version: "future".to_owned(),
+            fee_per_byte: MicroAlgos(0),
+            min_fee: MicroAlgos(1000),
+            first_valid: Round(1000),
+            last_valid: Round(2000),
+        };
+        let txn = |txn_type| TxnBuilder::with(&params, txn_type).build().unwrap();
+        let planned = vec![
+            // Already held.
+            txn(AcceptAsset::new(address, 10).build()),
+            txn(AcceptAsset::new(address, 11).build()),
+            txn(OptInApplication::new(address, 5).build()),
+            // Not sent by the account.
+            txn(AcceptAsset::new(other, 12).build()),
+        ];
+
+        assert_eq!(
+            Ok(MicroAlgos(478500)),
+            algod.required_balance_after(&address, &planned).await
+        );
+        assert_eq!(
+            "GET /v2/accounts/47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU HTTP/1.1",
+            server.request_line()
+        );
+        assert_eq!("GET /v2/applications/5 HTTP/1.1", server.request_line());
+    }
+
+    #[tokio::test]
+    async fn test_block_is_fetched_as_msgpack() {
+        use algonaut_crypto::HashDigest;
+        use serde::Serialize;
+
+        #[derive(Serialize)]
+        struct Header {
+            fees: Address,
+            prev: HashDigest,
+            rnd: u64,
+        }
+        #[derive(Serialize)]
+        struct Response {
+            block: Header,
+        }
+        let body = rmp_serde::to_vec_named(&Response {
+            block: Header {
+                fees: Address([1; 32]),
+                prev: HashDigest([2; 32]),
+                rnd: 1000,
+            },
+        })
+        .unwrap();
+        let server = TestServer::serve(vec![TestResponse::msgpack(body)]);
+        let algod = Algod::new(server.url(), TOKEN).unwrap();
+
+        let block = algod.block(Round(1000)).await.unwrap();
+
+        assert_eq!(Address([1; 32]).to_string(), block.block.fees);
+        assert_eq!(Ok(Some(HashDigest([2; 32]))), block.block.previous_hash());
+        assert_eq!(1000, block.block.rnd);
+        assert_eq!(None, block.cert);
+        assert_eq!(
+            "GET /v2/blocks/1000?format=msgpack HTTP/1.1",
+            server.request_line()
+        );
+    }
+
+    /// Answers a request with this MessagePack body.
+    fn serve_msgpack(body: Vec<u8>) -> TestServer {
+        TestServer::serve(vec![TestResponse::msgpack(body)])
+    }
+
+    fn pending_body(transactions: &[SignedTransaction], total: u64) -> Vec<u8> {
+        rmp_serde::to_vec_named(&PendingTransactions {
+            top_transactions: transactions.to_vec(),
+            total_transactions: total,
+        })
+        .unwrap()
+    }
+
+    fn signed_transactions() -> Vec<SignedTransaction> {
+        use algonaut_transaction::account::Account;
+        use algonaut_transaction::builder::{CallApplication, Pay, TransferAsset};
+        use algonaut_transaction::TxnBuilder;
+
+        let account = Account::from_seed([7; 32]);
+        let receiver: Address = "4MYUHDWHWXAKA5KA7U5PEN646VYUANBFXVJNONBK3TIMHEMWMD4UBOJBI4"
+            .parse()
+            .unwrap();
+        let params = SuggestedTransactionParams {
+            genesis_id: "testnet-v1.0".to_owned(),
+            genesis_hash: "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI="
+                .parse()
+                .unwrap(),
+            consensus_version: "future".to_owned(),
+            fee_per_byte: MicroAlgos(0),
+            min_fee: MicroAlgos(1000),
+            first_valid: Round(1000),
+            last_valid: Round(2000),
+        };
+        vec![
+            Pay::new(account.address(), receiver, MicroAlgos(123456)).build(),
+            TransferAsset::new(account.address(), 10, 5, receiver).build(),
+            CallApplication::new(account.address(), 5)
+                .app_arguments(vec![b"vote".to_vec()])
+                .build(),
+        ]
+        .into_iter()
+        .map(|txn_type| {
+            let txn = TxnBuilder::with(&params, txn_type).build().unwrap();
+            account.sign_transaction(txn).unwrap()
+        })
+        .collect()
+    }
+
+    #[tokio::test]
+    async fn test_pending_transactions_of_empty_pool() {
+        let server = serve_msgpack(pending_body(&[], 0));
+        let algod = Algod::new(server.url(), TOKEN).unwrap();
+
+        let pending = algod.pending_transactions(10).await.unwrap();
+
+        assert_eq!(
+            "GET /v2/transactions/pending?format=msgpack&max=10 HTTP/1.1",
+            server.request_line()
+        );
+        assert!(pending.top_transactions.is_empty());
+        assert_eq!(0, pending.total_transactions);
+        assert!(!pending.is_truncated());
+    }
+
+    #[tokio::test]
+    async fn test_truncated_pending_transactions() {
+        let transactions = signed_transactions();
+        let server = serve_msgpack(pending_body(&transactions[..2], 5));
+        let algod = Algod::new(server.url(), TOKEN).unwrap();
+
+        let pending = algod.pending_transactions(2).await.unwrap();
+
+        assert_eq!(2, pending.top_transactions.len());
+        assert_eq!(5, pending.total_transactions);
+        assert!(pending.is_truncated());
+    }
+
+    #[tokio::test]
+    async fn test_pending_transactions_for_of_mixed_types() {
+        let transactions = signed_transactions();
+        let sender = transactions[0].transaction.sender();
+        let server = serve_msgpack(pending_body(&transactions, 3));
+        let algod = Algod::new(server.url(), TOKEN).unwrap();
+
+        let pending = algod.pending_transactions_for(&sender, 0).await.unwrap();
+
+        assert_eq!(
+            format!(
+                "GET /v2/accounts/{}/transactions/pending?format=msgpack&max=0 HTTP/1.1",
+                sender
+            ),
+            server.request_line()
+        );
+        // Decoded with their signatures and ids.
+        assert_eq!(transactions, pending.top_transactions);
+        assert!(matches!(
+            pending.top_transactions[2].transaction.txn_type,
+            TransactionType::ApplicationCallTransaction(_)
+        ));
+        assert!(!pending.is_truncated());
+    }
 }
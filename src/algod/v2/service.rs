@@ -0,0 +1,369 @@
+use algonaut_core::{Address, CompiledTeal, Round, SuggestedTransactionParams};
+use algonaut_model::algod::v2::{
+    Account, ApiSpec, Application, Asset, Block, BlockHashResponse, Catchup, DryrunRequest,
+    DryrunResponse, GenesisBlock, KeyRegistration, NodeStatus, PendingTransaction,
+    PendingTransactions, Supply, TransactionParams, TransactionResponse, Version,
+};
+use algonaut_transaction::SignedTransaction;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use super::Algod;
+use crate::error::AlgonautError;
+
+/// The endpoints of [Algod], as a trait.
+///
+/// Write code against `impl AlgodService` (or `&dyn AlgodService`) to be able to substitute
+/// algod in tests, e.g. with the `MockAlgod` of the `testing` feature.
+/// See the methods of [Algod] for the documentation of each endpoint.
+///
+/// The trait has the endpoints of the node, not the helpers of [Algod] built on them:
+/// [Algod::capabilities] (see [Capabilities::probe](super::Capabilities::probe), which probes any
+/// service), [Algod::follow_blocks] (see [BlockFollower::new](super::BlockFollower::new), which
+/// follows any service), [Algod::network_health] (built on [status](Self::status),
+/// [ledger_supply](Self::ledger_supply) and [versions](Self::versions)),
+/// [Algod::pending_transactions_with_ids] (built on
+/// [pending_transaction_with_id](Self::pending_transaction_with_id)),
+/// [Algod::required_balance_after] (built on [account_information](Self::account_information) and
+/// [application_information](Self::application_information)) and
+/// [Algod::wait_for_catchup_completion] (built on [status](Self::status)).
+// The client futures aren't Send with the WASM target, so neither are the ones of the trait.
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait AlgodService: Send + Sync {
+    async fn genesis(&self) -> Result<GenesisBlock, AlgonautError>;
+
+    async fn health(&self) -> Result<(), AlgonautError>;
+
+    async fn metrics(&self) -> Result<String, AlgonautError>;
+
+    async fn account_information(&self, address: &Address) -> Result<Account, AlgonautError>;
+
+    async fn pending_transactions_for(
+        &self,
+        address: &Address,
+        max: u64,
+    ) -> Result<PendingTransactions<SignedTransaction>, AlgonautError>;
+
+    async fn application_information(&self, id: u64) -> Result<Application, AlgonautError>;
+
+    async fn asset_information(&self, id: u64) -> Result<Asset, AlgonautError>;
+
+    async fn block(&self, round: Round) -> Result<Block, AlgonautError>;
+
+    async fn block_hash(&self, round: Round) -> Result<BlockHashResponse, AlgonautError>;
+
+    async fn start_catchup(&self, catchpoint: &str) -> Result<Catchup, AlgonautError>;
+
+    async fn abort_catchup(&self, catchpoint: &str) -> Result<Catchup, AlgonautError>;
+
+    async fn ledger_supply(&self) -> Result<Supply, AlgonautError>;
+
+    async fn register_participation_keys(
+        &self,
+        address: &Address,
+        params: &KeyRegistration,
+    ) -> Result<String, AlgonautError>;
+
+    async fn shutdown(&self, timeout: usize) -> Result<(), AlgonautError>;
+
+    async fn status(&self) -> Result<NodeStatus, AlgonautError>;
+
+    async fn status_after_round(&self, round: Round) -> Result<NodeStatus, AlgonautError>;
+
+    async fn compile_teal(&self, teal: &[u8]) -> Result<CompiledTeal, AlgonautError>;
+
+    async fn dryrun_teal(&self, req: &DryrunRequest) -> Result<DryrunResponse, AlgonautError>;
+
+    async fn broadcast_signed_transaction(
+        &self,
+        txn: &SignedTransaction,
+    ) -> Result<TransactionResponse, AlgonautError>;
+
+    async fn broadcast_signed_transactions(
+        &self,
+        txns: &[SignedTransaction],
+    ) -> Result<TransactionResponse, AlgonautError>;
+
+    async fn broadcast_raw_transaction(
+        &self,
+        rawtxn: &[u8],
+    ) -> Result<TransactionResponse, AlgonautError>;
+
+    async fn transaction_params(&self) -> Result<TransactionParams, AlgonautError>;
+
+    async fn suggested_transaction_params(
+        &self,
+    ) -> Result<SuggestedTransactionParams, AlgonautError>;
+
+    async fn pending_transactions(
+        &self,
+        max: u64,
+    ) -> Result<PendingTransactions<SignedTransaction>, AlgonautError>;
+
+    async fn pending_transaction_with_id(
+        &self,
+        txid: &str,
+    ) -> Result<PendingTransaction, AlgonautError>;
+
+    async fn versions(&self) -> Result<Version, AlgonautError>;
+
+    async fn api_spec(&self) -> Result<ApiSpec, AlgonautError>;
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl AlgodService for Algod {
+    async fn genesis(&self) -> Result<GenesisBlock, AlgonautError> {
+        Algod::genesis(self).await
+    }
+
+    async fn health(&self) -> Result<(), AlgonautError> {
+        Algod::health(self).await
+    }
+
+    async fn metrics(&self) -> Result<String, AlgonautError> {
+        Algod::metrics(self).await
+    }
+
+    async fn account_information(&self, address: &Address) -> Result<Account, AlgonautError> {
+        Algod::account_information(self, address).await
+    }
+
+    async fn pending_transactions_for(
+        &self,
+        address: &Address,
+        max: u64,
+    ) -> Result<PendingTransactions<SignedTransaction>, AlgonautError> {
+        Algod::pending_transactions_for(self, address, max).await
+    }
+
+    async fn application_information(&self, id: u64) -> Result<Application, AlgonautError> {
+        Algod::application_information(self, id).await
+    }
+
+    async fn asset_information(&self, id: u64) -> Result<Asset, AlgonautError> {
+        Algod::asset_information(self, id).await
+    }
+
+    async fn block(&self, round: Round) -> Result<Block, AlgonautError> {
+        Algod::block(self, round).await
+    }
+
+    async fn block_hash(&self, round: Round) -> Result<BlockHashResponse, AlgonautError> {
+        Algod::block_hash(self, round).await
+    }
+
+    async fn start_catchup(&self, catchpoint: &str) -> Result<Catchup, AlgonautError> {
+        Algod::start_catchup(self, catchpoint).await
+    }
+
+    async fn abort_catchup(&self, catchpoint: &str) -> Result<Catchup, AlgonautError> {
+        Algod::abort_catchup(self, catchpoint).await
+    }
+
+    async fn ledger_supply(&self) -> Result<Supply, AlgonautError> {
+        Algod::ledger_supply(self).await
+    }
+
+    async fn register_participation_keys(
+        &self,
+        address: &Address,
+        params: &KeyRegistration,
+    ) -> Result<String, AlgonautError> {
+        Algod::register_participation_keys(self, address, params).await
+    }
+
+    async fn shutdown(&self, timeout: usize) -> Result<(), AlgonautError> {
+        Algod::shutdown(self, timeout).await
+    }
+
+    async fn status(&self) -> Result<NodeStatus, AlgonautError> {
+        Algod::status(self).await
+    }
+
+    async fn status_after_round(&self, round: Round) -> Result<NodeStatus, AlgonautError> {
+        Algod::status_after_round(self, round).await
+    }
+
+    async fn compile_teal(&self, teal: &[u8]) -> Result<CompiledTeal, AlgonautError> {
+        Algod::compile_teal(self, teal).await
+    }
+
+    async fn dryrun_teal(&self, req: &DryrunRequest) -> Result<DryrunResponse, AlgonautError> {
+        Algod::dryrun_teal(self, req).await
+    }
+
+    async fn broadcast_signed_transaction(
+        &self,
+        txn: &SignedTransaction,
+    ) -> Result<TransactionResponse, AlgonautError> {
+        Algod::broadcast_signed_transaction(self, txn).await
+    }
+
+    async fn broadcast_signed_transactions(
+        &self,
+        txns: &[SignedTransaction],
+    ) -> Result<TransactionResponse, AlgonautError> {
+        Algod::broadcast_signed_transactions(self, txns).await
+    }
+
+    async fn broadcast_raw_transaction(
+        &self,
+        rawtxn: &[u8],
+    ) -> Result<TransactionResponse, AlgonautError> {
+        Algod::broadcast_raw_transaction(self, rawtxn).await
+    }
+
+    async fn transaction_params(&self) -> Result<TransactionParams, AlgonautError> {
+        Algod::transaction_params(self).await
+    }
+
+    async fn suggested_transaction_params(
+        &self,
+    ) -> Result<SuggestedTransactionParams, AlgonautError> {
+        Algod::suggested_transaction_params(self).await
+    }
+
+    async fn pending_transactions(
+        &self,
+        max: u64,
+    ) -> Result<PendingTransactions<SignedTransaction>, AlgonautError> {
+        Algod::pending_transactions(self, max).await
+    }
+
+    async fn pending_transaction_with_id(
+        &self,
+        txid: &str,
+    ) -> Result<PendingTransaction, AlgonautError> {
+        Algod::pending_transaction_with_id(self, txid).await
+    }
+
+    async fn versions(&self) -> Result<Version, AlgonautError> {
+        Algod::versions(self).await
+    }
+
+    async fn api_spec(&self) -> Result<ApiSpec, AlgonautError> {
+        Algod::api_spec(self).await
+    }
+}
+
+/// Implements [AlgodService] for pointers to services, so that e.g. `&Algod` or
+/// `Arc<dyn AlgodService>` can be passed where an `impl AlgodService` is expected.
+macro_rules! forward_algod_service {
+    ($($pointer:ty),*) => {$(
+        #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+        #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+        impl<T: AlgodService + ?Sized> AlgodService for $pointer {
+            async fn genesis(&self) -> Result<GenesisBlock, AlgonautError> {
+                (**self).genesis().await
+            }
+
+            async fn health(&self) -> Result<(), AlgonautError> {
+                (**self).health().await
+            }
+
+            async fn metrics(&self) -> Result<String, AlgonautError> {
+                (**self).metrics().await
+            }
+
+            async fn account_information(&self, address: &Address) -> Result<Account, AlgonautError> {
+                (**self).account_information(address).await
+            }
+
+            async fn pending_transactions_for(&self, address: &Address, max: u64) -> Result<PendingTransactions<SignedTransaction>, AlgonautError> {
+                (**self).pending_transactions_for(address, max).await
+            }
+
+            async fn application_information(&self, id: u64) -> Result<Application, AlgonautError> {
+                (**self).application_information(id).await
+            }
+
+            async fn asset_information(&self, id: u64) -> Result<Asset, AlgonautError> {
+                (**self).asset_information(id).await
+            }
+
+            async fn block(&self, round: Round) -> Result<Block, AlgonautError> {
+                (**self).block(round).await
+            }
+
+            async fn block_hash(&self, round: Round) -> Result<BlockHashResponse, AlgonautError> {
+                (**self).block_hash(round).await
+            }
+
+            async fn start_catchup(&self, catchpoint: &str) -> Result<Catchup, AlgonautError> {
+                (**self).start_catchup(catchpoint).await
+            }
+
+            async fn abort_catchup(&self, catchpoint: &str) -> Result<Catchup, AlgonautError> {
+                (**self).abort_catchup(catchpoint).await
+            }
+
+            async fn ledger_supply(&self) -> Result<Supply, AlgonautError> {
+                (**self).ledger_supply().await
+            }
+
+            async fn register_participation_keys(&self, address: &Address, params: &KeyRegistration) -> Result<String, AlgonautError> {
+                (**self).register_participation_keys(address, params).await
+            }
+
+            async fn shutdown(&self, timeout: usize) -> Result<(), AlgonautError> {
+                (**self).shutdown(timeout).await
+            }
+
+            async fn status(&self) -> Result<NodeStatus, AlgonautError> {
+                (**self).status().await
+            }
+
+            async fn status_after_round(&self, round: Round) -> Result<NodeStatus, AlgonautError> {
+                (**self).status_after_round(round).await
+            }
+
+            async fn compile_teal(&self, teal: &[u8]) -> Result<CompiledTeal, AlgonautError> {
+                (**self).compile_teal(teal).await
+            }
+
+            async fn dryrun_teal(&self, req: &DryrunRequest) -> Result<DryrunResponse, AlgonautError> {
+                (**self).dryrun_teal(req).await
+            }
+
+            async fn broadcast_signed_transaction(&self, txn: &SignedTransaction) -> Result<TransactionResponse, AlgonautError> {
+                (**self).broadcast_signed_transaction(txn).await
+            }
+
+            async fn broadcast_signed_transactions(&self, txns: &[SignedTransaction]) -> Result<TransactionResponse, AlgonautError> {
+                (**self).broadcast_signed_transactions(txns).await
+            }
+
+            async fn broadcast_raw_transaction(&self, rawtxn: &[u8]) -> Result<TransactionResponse, AlgonautError> {
+                (**self).broadcast_raw_transaction(rawtxn).await
+            }
+
+            async fn transaction_params(&self) -> Result<TransactionParams, AlgonautError> {
+                (**self).transaction_params().await
+            }
+
+            async fn suggested_transaction_params(&self) -> Result<SuggestedTransactionParams, AlgonautError> {
+                (**self).suggested_transaction_params().await
+            }
+
+            async fn pending_transactions(&self, max: u64) -> Result<PendingTransactions<SignedTransaction>, AlgonautError> {
+                (**self).pending_transactions(max).await
+            }
+
+            async fn pending_transaction_with_id(&self, txid: &str) -> Result<PendingTransaction, AlgonautError> {
+                (**self).pending_transaction_with_id(txid).await
+            }
+
+            async fn versions(&self) -> Result<Version, AlgonautError> {
+                (**self).versions().await
+            }
+
+            async fn api_spec(&self) -> Result<ApiSpec, AlgonautError> {
+                (**self).api_spec().await
+            }
+        }
+    )*};
+}
+
+forward_algod_service!(&T, Box<T>, Arc<T>);
@@ -0,0 +1,235 @@
+//! A typed view of a deployed application, converging the algod and indexer application models.
+
+use crate::error::AlgonautError;
+use algonaut_core::Address;
+use algonaut_model::teal::TealKeyValueStore;
+use algonaut_model::{algod, indexer};
+use algonaut_transaction::transaction::StateSchema;
+use std::convert::TryFrom;
+
+/// The parameters and global state of a deployed application, decoded.
+///
+/// Built from the application returned by algod
+/// ([application_information](crate::algod::v2::Algod::application_information)) or by the indexer
+/// ([application_info](crate::indexer::v2::Indexer::application_info)), which give the same
+/// config for the same application (at the same round).
+///
+/// ```no_run
+/// # use algonaut::{algod::v2::Algod, application::ApplicationConfig};
+/// # use std::convert::TryFrom;
+/// # async fn example(algod: Algod) -> Result<(), algonaut::error::AlgonautError> {
+/// let config = ApplicationConfig::try_from(&algod.application_information(123).await?)?;
+/// let owner = config.global_state.get_address("owner");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ApplicationConfig {
+    pub id: u64,
+
+    /// The address that created the application.
+    pub creator: Address,
+
+    pub approval_program: Vec<u8>,
+
+    pub clear_state_program: Vec<u8>,
+
+    /// The schema of the global state, empty if the application has none.
+    pub global_state_schema: StateSchema,
+
+    /// The schema of the local state of each account, empty if the application has none.
+    pub local_state_schema: StateSchema,
+
+    /// The extra program pages allocated to the programs.
+    pub extra_program_pages: u64,
+
+    pub global_state: TealKeyValueStore,
+}
+
+impl TryFrom<&algod::v2::Application> for ApplicationConfig {
+    type Error = AlgonautError;
+
+    /// Fails if the global state isn't validly encoded.
+    fn try_from(app: &algod::v2::Application) -> Result<Self, Self::Error> {
+        let params = &app.params;
+        Ok(ApplicationConfig {
+            id: app.id,
+            creator: params.creator,
+            approval_program: params.approval_program.clone(),
+            clear_state_program: params.clear_state_program.clone(),
+            global_state_schema: schema(params.global_state_schema.as_ref().map(Into::into)),
+            local_state_schema: schema(params.local_state_schema.as_ref().map(Into::into)),
+            extra_program_pages: params.extra_program_pages.unwrap_or(0),
+            global_state: global_state(TealKeyValueStore::try_from(
+                params.global_state.as_slice(),
+            ))?,
+        })
+    }
+}
+
+impl TryFrom<&indexer::v2::Application> for ApplicationConfig {
+    type Error = AlgonautError;
+
+    /// Fails if the global state isn't validly encoded, or if the creator is missing.
+    fn try_from(app: &indexer::v2::Application) -> Result<Self, Self::Error> {
+        let params = &app.params;
+        Ok(ApplicationConfig {
+            id: app.id,
+            creator: params
+                .creator
+                .ok_or(AlgonautError::FieldDecode { field: "creator" })?,
+            approval_program: params.approval_program.clone(),
+            clear_state_program: params.clear_state_program.clone(),
+            global_state_schema: schema(params.global_state_schema.as_ref().map(Into::into)),
+            local_state_schema: schema(params.local_state_schema.as_ref().map(Into::into)),
+            extra_program_pages: params.extra_program_pages.unwrap_or(0),
+            global_state: global_state(TealKeyValueStore::try_from(
+                params.global_state.as_slice(),
+            ))?,
+        })
+    }
+}
+
+/// The schema, or an empty one: the nodes omit the empty schemas.
+fn schema(schema: Option<StateSchema>) -> StateSchema {
+    schema.unwrap_or(StateSchema {
+        number_ints: 0,
+        number_byteslices: 0,
+    })
+}
+
+fn global_state(
+    state: Result<TealKeyValueStore, String>,
+) -> Result<TealKeyValueStore, AlgonautError> {
+    state.map_err(|_| AlgonautError::FieldDecode {
+        field: "global-state",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An application as returned by algod's `v2/applications/{id}`.
+    const ALGOD_APPLICATION: &str = r#"{
+        "id": 1026,
+        "params": {
+            "approval-program": "BoEBQw==",
+            "clear-state-program": "BoEBQw==",
+            "creator": "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU",
+            "extra-program-pages": 1,
+            "global-state": [
+                {"key": "Y291bnRlcg==", "value": {"bytes": "", "type": 2, "uint": 7}},
+                {"key": "b3duZXI=", "value": {"bytes": "5/D4TQaBHfnzHI2HixFV9GcdUaGFwgCQhmf0SVhwaKE=", "type": 1, "uint": 0}}
+            ],
+            "global-state-schema": {"num-byte-slice": 1, "num-uint": 1},
+            "local-state-schema": {"num-byte-slice": 0, "num-uint": 2}
+        }
+    }"#;
+
+    /// The same application as returned by the indexer's `v2/applications/{id}`.
+    const INDEXER_APPLICATION: &str = r#"{
+        "application": {
+            "created-at-round": 12,
+            "deleted": false,
+            "id": 1026,
+            "params": {
+                "approval-program": "BoEBQw==",
+                "clear-state-program": "BoEBQw==",
+                "creator": "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU",
+                "extra-program-pages": 1,
+                "global-state": [
+                    {"key": "b3duZXI=", "value": {"bytes": "5/D4TQaBHfnzHI2HixFV9GcdUaGFwgCQhmf0SVhwaKE=", "type": 1, "uint": 0}},
+                    {"key": "Y291bnRlcg==", "value": {"bytes": "", "type": 2, "uint": 7}}
+                ],
+                "global-state-schema": {"num-byte-slice": 1, "num-uint": 1},
+                "local-state-schema": {"num-byte-slice": 0, "num-uint": 2}
+            }
+        },
+        "current-round": 20
+    }"#;
+
+    #[test]
+    fn test_algod_and_indexer_applications_converge() {
+        let algod_app: algod::v2::Application = serde_json::from_str(ALGOD_APPLICATION).unwrap();
+        let indexer_app: indexer::v2::ApplicationInfoResponse =
+            serde_json::from_str(INDEXER_APPLICATION).unwrap();
+
+        let from_algod = ApplicationConfig::try_from(&algod_app).unwrap();
+        let from_indexer =
+            ApplicationConfig::try_from(indexer_app.application.as_deref().unwrap()).unwrap();
+
+        assert_eq!(from_algod, from_indexer);
+        assert_eq!(1026, from_algod.id);
+        assert_eq!(
+            "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU",
+            from_algod.creator.to_string()
+        );
+        assert_eq!(vec![0x06, 0x81, 0x01, 0x43], from_algod.approval_program);
+        assert_eq!(
+            StateSchema {
+                number_ints: 1,
+                number_byteslices: 1
+            },
+            from_algod.global_state_schema
+        );
+        assert_eq!(
+            StateSchema {
+                number_ints: 2,
+                number_byteslices: 0
+            },
+            from_algod.local_state_schema
+        );
+        assert_eq!(1, from_algod.extra_program_pages);
+        assert_eq!(Some(7), from_algod.global_state.get_uint("counter"));
+        assert_eq!(
+            Some(from_algod.creator),
+            from_algod.global_state.get_address("owner")
+        );
+    }
+
+    #[test]
+    fn test_omitted_schemas_and_pages_are_empty() {
+        let app: algod::v2::Application = serde_json::from_str(
+            r#"{"id": 1, "params": {"creator": "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU"}}"#,
+        )
+        .unwrap();
+
+        let config = ApplicationConfig::try_from(&app).unwrap();
+
+        assert_eq!(schema(None), config.global_state_schema);
+        assert_eq!(schema(None), config.local_state_schema);
+        assert_eq!(0, config.extra_program_pages);
+        assert!(config.global_state.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_global_state() {
+        let mut json: serde_json::Value = serde_json::from_str(ALGOD_APPLICATION).unwrap();
+        json["params"]["global-state"][0]["value"]["type"] = 3.into();
+        let app: algod::v2::Application = serde_json::from_str(&json.to_string()).unwrap();
+
+        assert_eq!(
+            Err(AlgonautError::FieldDecode {
+                field: "global-state"
+            }),
+            ApplicationConfig::try_from(&app)
+        );
+    }
+
+    #[test]
+    fn test_indexer_application_without_creator() {
+        let mut json: serde_json::Value = serde_json::from_str(INDEXER_APPLICATION).unwrap();
+        json["application"]["params"]
+            .as_object_mut()
+            .unwrap()
+            .remove("creator");
+        let response: indexer::v2::ApplicationInfoResponse =
+            serde_json::from_str(&json.to_string()).unwrap();
+
+        assert_eq!(
+            Err(AlgonautError::FieldDecode { field: "creator" }),
+            ApplicationConfig::try_from(response.application.as_deref().unwrap())
+        );
+    }
+}
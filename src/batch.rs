@@ -0,0 +1,416 @@
+use futures_util::{stream, StreamExt, TryStreamExt};
+use std::future::Future;
+
+use crate::error::AlgonautError;
+
+/// Looks up each id with `lookup`, running up to `max_concurrency` lookups at a time (at least
+/// one), and returns their results in the order of the ids.
+///
+/// A lookup failing with a 404 is returned as the result of its id: any other error fails the
+/// whole batch, dropping the lookups in progress.
+///
+/// The results are ordered whatever the order the lookups complete in: a result completed before
+/// the ones of earlier ids is buffered until they complete, i.e. at most `max_concurrency - 1`
+/// results are held besides the ones returned.
+///
+/// The lookups are polled by the returned future, not spawned: dropping it (e.g. when its task is
+/// cancelled) drops the lookups in progress, aborting their requests, and the remaining ids aren't
+/// looked up.
+pub(crate) async fn lookup_all<'a, T, F, Fut>(
+    ids: &[&'a str],
+    max_concurrency: usize,
+    lookup: F,
+) -> Result<Vec<Result<T, AlgonautError>>, AlgonautError>
+where
+    F: Fn(&'a str) -> Fut,
+    Fut: Future<Output = Result<T, AlgonautError>>,
+{
+    stream::iter(ids.iter().copied())
+        .map(|id| {
+            let lookup = lookup(id);
+            async move {
+                match lookup.await {
+                    Err(e) if !e.is_404() => Err(e),
+                    result => Ok(result),
+                }
+            }
+        })
+        .buffered(max_concurrency.max(1))
+        .try_collect()
+        .await
+}
+
+/// Runs `lookup` on each key, up to `max_concurrency` at a time (at least one), and returns their
+/// outputs in the order of the keys, buffering the outputs completed early and cancelled on drop
+/// like [lookup_all].
+pub(crate) async fn lookup_each<K, F, Fut>(
+    keys: impl IntoIterator<Item = K>,
+    max_concurrency: usize,
+    lookup: F,
+) -> Vec<Fut::Output>
+where
+    F: Fn(K) -> Fut,
+    Fut: Future,
+{
+    stream::iter(keys)
+        .map(lookup)
+        .buffered(max_concurrency.max(1))
+        .collect()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{algod::v2::Algod, indexer::v2::Indexer};
+    use algonaut_client::test_server::{TestResponse, TestServer};
+    use algonaut_core::{MicroAlgos, Round, TxId, TxIdParseError};
+    use std::time::{Duration, Instant};
+
+    const TOKEN: &str = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+
+    /// A valid transaction id, of a transaction the server doesn't have.
+    const MISSING_TXID: &str = "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA";
+
+    /// A valid transaction id, of a transaction whose lookup fails with a 500.
+    const BROKEN_TXID: &str = "777777777777777777777777777777777777777777777777777Q";
+
+    /// The valid transaction id `n`.
+    fn txid(n: u8) -> String {
+        TxId([n; 32]).to_string()
+    }
+
+    /// Time spent by the server on each request.
+    const LATENCY: Duration = Duration::from_millis(50);
+
+    /// Start and end of a request handled by the server.
+    type Interval = (Instant, Instant);
+
+    /// Serves transactions slowly, taking [LATENCY] to answer each request.
+    ///
+    /// The transactions "missing" and [MISSING_TXID] don't exist (404), and looking up [BROKEN_TXID] fails with a 500.
+    /// The accounts are served from [FIRST_ACCOUNT_ROUND], the oldest round of the history retained.
+    fn serve() -> TestServer {
+        TestServer::handle(|request| {
+            let path = request.line.split(' ').nth(1).unwrap_or_default();
+            let id = path.rsplit('/').next().unwrap_or_default();
+            let (status, body) = match id {
+                "missing" | MISSING_TXID => {
+                    ("404 Not Found", r#"{"message":"not found"}"#.to_owned())
+                }
+                BROKEN_TXID => (
+                    "500 Internal Server Error",
+                    r#"{"message":"boom"}"#.to_owned(),
+                ),
+                _ if path.starts_with("/v2/accounts/") => account_info(path),
+                _ if path.starts_with("/v2/transactions/pending/") => (
+                    "200 OK",
+                    format!(r#"{{"confirmed-round":{},"pool-error":"","txn":{{}}}}"#, id),
+                ),
+                _ => ("200 OK", transaction_info(id)),
+            };
+            TestResponse::json(body).status(status).delay(LATENCY)
+        })
+    }
+
+    /// The intervals during which the server handled the requests received so far.
+    fn intervals(server: &TestServer) -> Vec<Interval> {
+        server
+            .received()
+            .iter()
+            .map(|request| (request.received_at, request.received_at + LATENCY))
+            .collect()
+    }
+
+    fn transaction_info(id: &str) -> String {
+        serde_json::json!({
+            "current-round": 10,
+            "transaction": {
+                "confirmed-round": 5,
+                "fee": 1000,
+                "first-valid": 1,
+                "genesis-hash": "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=",
+                "id": id,
+                "last-valid": 1001,
+                "sender": "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU",
+                "tx-type": "pay",
+            },
+        })
+        .to_string()
+    }
+
+    /// The oldest round of the accounts served, before which they can't be rewound.
+    const FIRST_ACCOUNT_ROUND: u64 = 100;
+
+    /// Responds to a lookup of an account at the `round` of the query of this path.
+    fn account_info(path: &str) -> (&'static str, String) {
+        let (path, query) = path.split_once('?').unwrap_or((path, ""));
+        let address = path.rsplit('/').next().unwrap_or_default();
+        let round: u64 = query
+            .split('&')
+            .find_map(|param| param.strip_prefix("round="))
+            .map_or(1000, |round| round.parse().unwrap());
+        if round < FIRST_ACCOUNT_ROUND {
+            return (
+                "500 Internal Server Error",
+                r#"{"message":"error while rewinding account: missing transactions"}"#.to_owned(),
+            );
+        }
+        let body = serde_json::json!({
+            "account": {
+                "address": address,
+                "amount": round,
+                "amount-without-pending-rewards": round,
+                "pending-rewards": 0,
+                "rewards": 0,
+                "round": round,
+                "status": "Offline",
+            },
+            "current-round": 1000,
+        });
+        ("200 OK", body.to_string())
+    }
+
+    /// The maximum number of requests handled at the same time.
+    fn max_concurrency(intervals: &[Interval]) -> usize {
+        intervals
+            .iter()
+            .map(|(start, _)| {
+                intervals
+                    .iter()
+                    .filter(|(other_start, other_end)| other_start <= start && start < other_end)
+                    .count()
+            })
+            .max()
+            .unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn test_transactions_by_ids_bounds_concurrency_and_keeps_order() {
+        let server = serve();
+        let indexer = Indexer::new(server.url()).unwrap();
+        let ids: Vec<String> = (1..=8).map(txid).collect();
+        let mut ids: Vec<&str> = ids.iter().map(String::as_str).collect();
+        ids[2] = MISSING_TXID;
+
+        let results = indexer.transactions_by_ids(&ids, 3).await.unwrap();
+
+        let found: Vec<Option<String>> = results
+            .iter()
+            .map(|result| result.as_ref().ok().map(|info| info.transaction.id.clone()))
+            .collect();
+        let mut expected: Vec<Option<String>> = ids.iter().map(|id| Some(id.to_string())).collect();
+        expected[2] = None;
+        assert_eq!(expected, found);
+        assert!(results[2].as_ref().unwrap_err().is_404());
+
+        let intervals = intervals(&server);
+        assert_eq!(ids.len(), intervals.len());
+        assert_eq!(3, max_concurrency(&intervals));
+    }
+
+    #[tokio::test]
+    async fn test_transactions_by_ids_fails_on_server_error() {
+        let server = serve();
+        let indexer = Indexer::new(server.url()).unwrap();
+
+        let res = indexer
+            .transactions_by_ids(&[&txid(1), BROKEN_TXID, MISSING_TXID], 2)
+            .await;
+
+        assert_eq!(
+            Some(500),
+            res.unwrap_err().response_meta().map(|meta| meta.status)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pending_transactions_with_ids_bounds_concurrency_and_keeps_order() {
+        let server = serve();
+        let algod = Algod::new(server.url(), TOKEN).unwrap();
+        let ids = ["1", "2", "3", "missing", "5"];
+
+        let results = algod.pending_transactions_with_ids(&ids, 2).await.unwrap();
+
+        let rounds: Vec<Option<u64>> = results
+            .iter()
+            .map(|result| result.as_ref().ok().and_then(|txn| txn.confirmed_round))
+            .collect();
+        assert_eq!(vec![Some(1), Some(2), Some(3), None, Some(5)], rounds);
+        assert!(results[3].as_ref().unwrap_err().is_404());
+        assert_eq!(2, max_concurrency(&intervals(&server)));
+    }
+
+    #[tokio::test]
+    async fn test_transactions_by_ids_normalizes_the_ids() {
+        let server = serve();
+        let indexer = Indexer::new(server.url()).unwrap();
+        let txid = "TDIO6RJWJIVDDJZELMSX5CPJW7MUNM3QR4YAHYAKHF3W2CFRTI7A";
+        let lowercase = format!(" {} ", txid.to_ascii_lowercase());
+
+        let results = indexer
+            .transactions_by_ids(&[txid, &lowercase], 2)
+            .await
+            .unwrap();
+
+        assert_eq!(txid, results[0].as_ref().unwrap().transaction.id);
+        assert_eq!(txid, results[1].as_ref().unwrap().transaction.id);
+
+        // an invalid id fails the lookup before any request
+        server.received();
+        let res = indexer
+            .transactions_by_ids(&[txid, "invalid", MISSING_TXID], 2)
+            .await;
+        assert_eq!(
+            Some(AlgonautError::BadQuery {
+                reason: format!(
+                    "invalid transaction id \"invalid\": {}",
+                    TxIdParseError::WrongLength { length: 7 }
+                ),
+                param: Some("txid")
+            }),
+            res.err()
+        );
+        assert!(server.received().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_transaction_info_batch() {
+        let server = serve();
+        let indexer = Indexer::new(server.url()).unwrap();
+        let txid = "TDIO6RJWJIVDDJZELMSX5CPJW7MUNM3QR4YAHYAKHF3W2CFRTI7A";
+        let lowercase = format!(" {} ", txid.to_ascii_lowercase());
+        let ids = [txid, "invalid", MISSING_TXID, &lowercase, BROKEN_TXID, txid];
+
+        let results = indexer.transaction_info_batch(&ids, 2).await;
+
+        let given_ids: Vec<&str> = results.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids.to_vec(), given_ids);
+        // the ids are looked up normalized
+        assert_eq!(txid, results[0].1.as_ref().unwrap().transaction.id);
+        assert_eq!(txid, results[3].1.as_ref().unwrap().transaction.id);
+        assert_eq!(txid, results[5].1.as_ref().unwrap().transaction.id);
+        assert_eq!(
+            Err(AlgonautError::BadQuery {
+                reason: format!(
+                    "invalid transaction id \"invalid\": {}",
+                    TxIdParseError::WrongLength { length: 7 }
+                ),
+                param: Some("txid")
+            }),
+            results[1].1.clone().map(|_| ())
+        );
+        assert_eq!(
+            Err(AlgonautError::NotFound {
+                resource: "transaction",
+                id: MISSING_TXID.to_owned(),
+                message: "not found".to_owned()
+            }),
+            results[2].1.clone().map(|_| ())
+        );
+        // a server error fails only its lookup
+        assert_eq!(
+            Some(500),
+            results[4]
+                .1
+                .as_ref()
+                .unwrap_err()
+                .response_meta()
+                .map(|meta| meta.status)
+        );
+
+        // the invalid id isn't looked up
+        let intervals = intervals(&server);
+        assert_eq!(5, intervals.len());
+        assert_eq!(2, max_concurrency(&intervals));
+    }
+
+    #[tokio::test]
+    async fn test_account_info_at_rounds() {
+        let server = serve();
+        let indexer = Indexer::new(server.url()).unwrap();
+        let address = "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU"
+            .parse()
+            .unwrap();
+        let rounds = [Round(500), Round(20), Round(100), Round(900)];
+
+        let results = indexer.account_info_at_rounds(&address, &rounds, 2).await;
+
+        let given_rounds: Vec<Round> = results.iter().map(|(round, _)| *round).collect();
+        assert_eq!(rounds.to_vec(), given_rounds);
+        for i in [0, 2, 3] {
+            let account = &results[i].1.as_ref().unwrap().account;
+            assert_eq!(MicroAlgos(rounds[i].0), account.amount);
+        }
+        assert_eq!(
+            Err(AlgonautError::RoundUnavailable {
+                round: Round(20),
+                message: "error while rewinding account: missing transactions".to_owned()
+            }),
+            results[1].1.clone().map(|_| ())
+        );
+        assert_eq!(2, max_concurrency(&intervals(&server)));
+    }
+
+    #[tokio::test]
+    async fn test_lookups_completing_out_of_order_keep_the_order_of_the_keys() {
+        // the later the key, the sooner its lookup completes
+        let delay = |key: u64| Duration::from_millis(10 * (5 - key));
+
+        let outputs = lookup_each(0..5, 5, |key| async move {
+            tokio::time::sleep(delay(key)).await;
+            key
+        })
+        .await;
+        assert_eq!(vec![0, 1, 2, 3, 4], outputs);
+
+        let ids = ["0", "1", "2", "3", "4"];
+        let results = lookup_all(&ids, 5, |id| async move {
+            let key: u64 = id.parse().unwrap();
+            tokio::time::sleep(delay(key)).await;
+            Ok(key)
+        })
+        .await
+        .unwrap();
+        assert_eq!(vec![Ok(0), Ok(1), Ok(2), Ok(3), Ok(4)], results);
+    }
+
+    #[tokio::test]
+    async fn test_dropped_batch_issues_no_further_requests() {
+        let server = TestServer::silent();
+        let indexer = Indexer::new(server.url()).unwrap();
+        let ids: Vec<String> = (1..=8).map(txid).collect();
+        let ids: Vec<&str> = ids.iter().map(String::as_str).collect();
+        let first_requests = tokio::task::spawn_blocking(move || {
+            let lines = vec![server.request_line(), server.request_line()];
+            (server, lines)
+        });
+
+        // the server never answers: the batch is dropped with the first pair of lookups in flight
+        let (server, mut lines) = tokio::select! {
+            res = indexer.transactions_by_ids(&ids, 2) => panic!("unexpected result: {:?}", res),
+            requests = first_requests => requests.unwrap(),
+        };
+
+        lines.sort();
+        let mut expected: Vec<String> = ids[..2]
+            .iter()
+            .map(|id| format!("GET /v2/transactions/{} HTTP/1.1", id))
+            .collect();
+        expected.sort();
+        assert_eq!(expected, lines);
+        assert_eq!(0, server.received().len());
+
+        // the remaining ids aren't looked up later either
+        tokio::time::sleep(LATENCY * 4).await;
+        assert_eq!(0, server.received().len());
+    }
+
+    #[tokio::test]
+    async fn test_lookup_all_without_concurrency() {
+        let results = lookup_all(&["1", "2"], 0, |id| async move { Ok(id.len()) })
+            .await
+            .unwrap();
+        assert_eq!(vec![Ok(1), Ok(1)], results);
+    }
+}
@@ -0,0 +1,550 @@
+//! Configuration of the clients from the environment variables used by AlgoKit and the other
+//! SDKs.
+//!
+//! Each node is configured with the variables of its prefix (`ALGOD`, `INDEXER` or `KMD`):
+//!
+//! - `<PREFIX>_SERVER`: the base url of the node (e.g. `https://testnet-api.algonode.cloud`), the
+//!   scheme defaulting to `http` if omitted (e.g. `localhost`).
+//! - `<PREFIX>_PORT`: the port of the node, if not the default one of the scheme.
+//! - `<PREFIX>_TOKEN`: the API token, sent in the header of the node (e.g. `X-Algo-API-Token`).
+//! - `<PREFIX>_TOKEN_HEADER`: the header of the token, if not the one of the node, e.g. `X-API-Key`
+//!   for third party services.
+//! - `<PREFIX>_TIMEOUT_MS`: the timeout of each request in milliseconds (see
+//!   [ClientConfig::timeout]), none by default.
+//!
+//! Empty variables are treated as missing. `ALGOD_SERVER` is required, as is `KMD_TOKEN` if kmd is
+//! configured; indexer and kmd are configured only if one of their variables is set. With
+//! `ALGOD_ENV=localnet` (or `sandbox`), the missing variables default to the ones of a local
+//! sandbox (see [NodePreset::sandbox_algod]), for the three nodes.
+//!
+//! ```
+//! # use algonaut::config::EnvConfig;
+//! # use std::collections::HashMap;
+//! let vars: HashMap<String, String> = vec![
+//!     ("ALGOD_SERVER", "https://testnet-api.algonode.cloud"),
+//!     ("ALGOD_PORT", "443"),
+//! ]
+//! .into_iter()
+//! .map(|(name, value)| (name.to_owned(), value.to_owned()))
+//! .collect();
+//! let config = EnvConfig::from_map(&vars).unwrap();
+//! assert_eq!("https://testnet-api.algonode.cloud/", config.algod().url());
+//! let algod = config.create_algod().unwrap();
+//! ```
+//!
+//! The clients are built with the timeout of `<PREFIX>_TIMEOUT_MS` in their [ClientConfig]; to tune
+//! it further, pass the presets to the `with_config` constructors (e.g. [Algod::with_config]), or
+//! to [Indexer::from_config] in an [IndexerConfig].
+
+use std::collections::HashMap;
+use std::env::VarError;
+use std::time::Duration;
+use url::Url;
+
+use crate::algod::v2::Algod;
+use crate::error::AlgonautError;
+use crate::indexer::v2::{Indexer, IndexerConfig};
+use crate::kmd::v1::Kmd;
+use crate::provider::{NodePreset, ALGOD_TOKEN_HEADER, SANDBOX_TOKEN};
+use crate::ClientConfig;
+
+/// The variable selecting the sandbox defaults, with the value `localnet` or `sandbox`.
+const ENV_VAR: &str = "ALGOD_ENV";
+
+/// The server of the nodes of a local sandbox.
+const LOCALNET_SERVER: &str = "http://localhost";
+
+/// The variables configuring a node, and its defaults in a local sandbox.
+struct NodeVars {
+    server: &'static str,
+    port: &'static str,
+    token: &'static str,
+    token_header: &'static str,
+    timeout: &'static str,
+    default_token_header: &'static str,
+    token_required: bool,
+    localnet_port: &'static str,
+    localnet_token: Option<&'static str>,
+}
+
+const ALGOD: NodeVars = NodeVars {
+    server: "ALGOD_SERVER",
+    port: "ALGOD_PORT",
+    token: "ALGOD_TOKEN",
+    token_header: "ALGOD_TOKEN_HEADER",
+    timeout: "ALGOD_TIMEOUT_MS",
+    default_token_header: ALGOD_TOKEN_HEADER,
+    token_required: false,
+    localnet_port: "4001",
+    localnet_token: Some(SANDBOX_TOKEN),
+};
+
+const INDEXER: NodeVars = NodeVars {
+    server: "INDEXER_SERVER",
+    port: "INDEXER_PORT",
+    token: "INDEXER_TOKEN",
+    token_header: "INDEXER_TOKEN_HEADER",
+    timeout: "INDEXER_TIMEOUT_MS",
+    default_token_header: "X-Indexer-API-Token",
+    token_required: false,
+    localnet_port: "8980",
+    localnet_token: None,
+};
+
+const KMD: NodeVars = NodeVars {
+    server: "KMD_SERVER",
+    port: "KMD_PORT",
+    token: "KMD_TOKEN",
+    token_header: "KMD_TOKEN_HEADER",
+    timeout: "KMD_TIMEOUT_MS",
+    default_token_header: "X-KMD-API-Token",
+    token_required: true,
+    localnet_port: "4002",
+    localnet_token: Some(SANDBOX_TOKEN),
+};
+
+/// The url, the headers and the client settings (the request timeout) of a node.
+#[derive(Debug, Clone, PartialEq)]
+struct EnvNode {
+    preset: NodePreset,
+    client: ClientConfig,
+}
+
+impl EnvNode {
+    fn algod(&self) -> Result<Algod, AlgonautError> {
+        Algod::with_config(self.preset.url(), self.preset.headers(), &self.client)
+    }
+
+    fn indexer(&self) -> Result<Indexer, AlgonautError> {
+        Indexer::from_config(IndexerConfig::new(self.preset.clone(), self.client.clone()))
+    }
+
+    fn kmd(&self) -> Result<Kmd, AlgonautError> {
+        Kmd::with_config(self.preset.url(), self.preset.headers(), &self.client)
+    }
+}
+
+/// The url and the token of algod, and of indexer and kmd if configured, read from the
+/// environment variables: see the [module documentation](self).
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnvConfig {
+    algod: EnvNode,
+    indexer: Option<EnvNode>,
+    kmd: Option<EnvNode>,
+}
+
+impl EnvConfig {
+    /// Reads the configuration from the environment variables.
+    ///
+    /// Fails with [AlgonautError::MissingEnvVars], listing all the required variables missing, or
+    /// with [AlgonautError::BadEnvVar] if a variable is invalid (e.g. a port that isn't a number,
+    /// or that conflicts with the port of the server).
+    pub fn from_env() -> Result<EnvConfig, AlgonautError> {
+        Self::from_vars(|name| std::env::var(name))
+    }
+
+    /// [from_env](Self::from_env), reading the variables from `vars` instead of the environment,
+    /// e.g. in tests.
+    pub fn from_map(vars: &HashMap<String, String>) -> Result<EnvConfig, AlgonautError> {
+        Self::from_vars(|name| vars.get(name).cloned().ok_or(VarError::NotPresent))
+    }
+
+    /// [from_env](Self::from_env), reading the variables with `var`.
+    fn from_vars(
+        var: impl Fn(&str) -> Result<String, VarError>,
+    ) -> Result<EnvConfig, AlgonautError> {
+        let localnet = is_localnet(&var)?;
+        let mut missing = vec![];
+        let algod = read_node(&ALGOD, &var, localnet, true, &mut missing)?;
+        let indexer = read_node(&INDEXER, &var, localnet, false, &mut missing)?;
+        let kmd = read_node(&KMD, &var, localnet, false, &mut missing)?;
+
+        match algod {
+            Some(algod) if missing.is_empty() => Ok(EnvConfig {
+                algod,
+                indexer,
+                kmd,
+            }),
+            _ => Err(AlgonautError::MissingEnvVars { names: missing }),
+        }
+    }
+
+    /// The url and the headers of algod.
+    pub fn algod(&self) -> &NodePreset {
+        &self.algod.preset
+    }
+
+    /// The url and the headers of the indexer, if configured.
+    pub fn indexer(&self) -> Option<&NodePreset> {
+        self.indexer.as_ref().map(|indexer| &indexer.preset)
+    }
+
+    /// The url and the headers of kmd, if configured.
+    pub fn kmd(&self) -> Option<&NodePreset> {
+        self.kmd.as_ref().map(|kmd| &kmd.preset)
+    }
+
+    /// Builds the algod client.
+    pub fn create_algod(&self) -> Result<Algod, AlgonautError> {
+        self.algod.algod()
+    }
+
+    /// Builds the indexer client, failing with [AlgonautError::MissingEnvVars] if the indexer isn't
+    /// configured.
+    pub fn create_indexer(&self) -> Result<Indexer, AlgonautError> {
+        match &self.indexer {
+            Some(indexer) => indexer.indexer(),
+            None => Err(AlgonautError::MissingEnvVars {
+                names: vec![INDEXER.server],
+            }),
+        }
+    }
+
+    /// Builds the kmd client, failing with [AlgonautError::MissingEnvVars] if kmd isn't configured.
+    pub fn create_kmd(&self) -> Result<Kmd, AlgonautError> {
+        match &self.kmd {
+            Some(kmd) => kmd.kmd(),
+            None => Err(AlgonautError::MissingEnvVars {
+                names: vec![KMD.server, KMD.token],
+            }),
+        }
+    }
+}
+
+/// The indexer configured by the `INDEXER_*` variables read with `var`, for
+/// [Indexer::from_env]: `INDEXER_SERVER` is required, unless `ALGOD_ENV=localnet`.
+pub(crate) fn indexer_from_vars(
+    var: impl Fn(&str) -> Result<String, VarError>,
+) -> Result<Indexer, AlgonautError> {
+    let localnet = is_localnet(&var)?;
+    let mut missing = vec![];
+    match read_node(&INDEXER, &var, localnet, true, &mut missing)? {
+        Some(indexer) if missing.is_empty() => indexer.indexer(),
+        _ => Err(AlgonautError::MissingEnvVars { names: missing }),
+    }
+}
+
+/// The value of a variable, `None` if it's missing or empty.
+fn read_var(
+    var: &impl Fn(&str) -> Result<String, VarError>,
+    name: &'static str,
+) -> Result<Option<String>, AlgonautError> {
+    match var(name) {
+        Ok(value) if value.trim().is_empty() => Ok(None),
+        Ok(value) => Ok(Some(value.trim().to_owned())),
+        Err(VarError::NotPresent) => Ok(None),
+        Err(VarError::NotUnicode(_)) => Err(AlgonautError::BadEnvVar {
+            name,
+            reason: "not valid unicode".to_owned(),
+        }),
+    }
+}
+
+/// Whether the sandbox defaults are selected ([ENV_VAR]).
+fn is_localnet(var: &impl Fn(&str) -> Result<String, VarError>) -> Result<bool, AlgonautError> {
+    Ok(read_var(var, ENV_VAR)?
+        .map(|env| env.eq_ignore_ascii_case("localnet") || env.eq_ignore_ascii_case("sandbox"))
+        .unwrap_or(false))
+}
+
+/// The node configured by the variables of `vars`, `None` if it isn't configured (none of its
+/// variables is set and it isn't `required`) or if required variables are missing, which are then
+/// added to `missing`.
+fn read_node(
+    vars: &NodeVars,
+    var: &impl Fn(&str) -> Result<String, VarError>,
+    localnet: bool,
+    required: bool,
+    missing: &mut Vec<&'static str>,
+) -> Result<Option<EnvNode>, AlgonautError> {
+    let mut server = read_var(var, vars.server)?;
+    let mut port = read_var(var, vars.port)?;
+    let mut token = read_var(var, vars.token)?;
+    if localnet {
+        server = server.or_else(|| Some(LOCALNET_SERVER.to_owned()));
+        port = port.or_else(|| Some(vars.localnet_port.to_owned()));
+        token = token.or_else(|| vars.localnet_token.map(str::to_owned));
+    } else if !required && server.is_none() && port.is_none() && token.is_none() {
+        return Ok(None);
+    }
+
+    if server.is_none() {
+        missing.push(vars.server);
+    }
+    if vars.token_required && token.is_none() {
+        missing.push(vars.token);
+    }
+    let server = match server {
+        Some(server) if !vars.token_required || token.is_some() => server,
+        _ => return Ok(None),
+    };
+    let port = port
+        .map(|port| {
+            port.parse().map_err(|e| AlgonautError::BadEnvVar {
+                name: vars.port,
+                reason: format!("invalid port {:?}: {}", port, e),
+            })
+        })
+        .transpose()?;
+    let timeout = read_var(var, vars.timeout)?
+        .map(|timeout| {
+            timeout
+                .parse()
+                .map(Duration::from_millis)
+                .map_err(|e| AlgonautError::BadEnvVar {
+                    name: vars.timeout,
+                    reason: format!("invalid number of milliseconds {:?}: {}", timeout, e),
+                })
+        })
+        .transpose()?;
+    let token_header =
+        read_var(var, vars.token_header)?.unwrap_or_else(|| vars.default_token_header.to_owned());
+    let url = node_url(vars, &server, port)?;
+    let headers = match &token {
+        Some(token) => vec![(token_header.as_str(), token.as_str())],
+        None => vec![],
+    };
+    Ok(Some(EnvNode {
+        preset: NodePreset::new(&url, headers),
+        client: ClientConfig {
+            timeout,
+            ..ClientConfig::default()
+        },
+    }))
+}
+
+/// The base url of a node on `server` (with or without scheme, path or trailing slash) and `port`,
+/// ending with a slash for the paths of the endpoints to be appended to it.
+///
+/// Fails with [AlgonautError::BadEnvVar], naming the variable of the node at fault, if the server
+/// isn't a valid url, or has a port other than `port`.
+fn node_url(vars: &NodeVars, server: &str, port: Option<u16>) -> Result<String, AlgonautError> {
+    let bad_server = |reason| AlgonautError::BadEnvVar {
+        name: vars.server,
+        reason,
+    };
+    let with_scheme = if server.contains("://") {
+        server.to_owned()
+    } else {
+        format!("http://{}", server)
+    };
+    let mut url = Url::parse(&with_scheme)
+        .map_err(|e| bad_server(format!("invalid url {:?}: {}", server, e)))?;
+    if let Some(port) = port {
+        match url.port() {
+            Some(server_port) if server_port != port => {
+                return Err(AlgonautError::BadEnvVar {
+                    name: vars.port,
+                    reason: format!(
+                        "{} conflicts with the port of the server {:?}",
+                        port, server
+                    ),
+                })
+            }
+            _ => url
+                .set_port(Some(port))
+                .map_err(|_| bad_server(format!("the url {:?} can't have a port", server)))?,
+        }
+    }
+    if !url.path().ends_with('/') {
+        let path = format!("{}/", url.path());
+        url.set_path(&path);
+    }
+    Ok(url.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(vars: &[(&str, &str)]) -> Result<EnvConfig, AlgonautError> {
+        EnvConfig::from_map(
+            &vars
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_node_url() {
+        let cases = vec![
+            ("localhost", Some(4001), "http://localhost:4001/"),
+            ("http://localhost/", Some(4001), "http://localhost:4001/"),
+            (
+                "http://localhost:4001",
+                Some(4001),
+                "http://localhost:4001/",
+            ),
+            ("http://localhost:4001", None, "http://localhost:4001/"),
+            ("127.0.0.1", None, "http://127.0.0.1/"),
+            (
+                "https://testnet-api.algonode.cloud",
+                None,
+                "https://testnet-api.algonode.cloud/",
+            ),
+            (
+                "https://testnet-api.algonode.cloud/",
+                Some(443),
+                "https://testnet-api.algonode.cloud/",
+            ),
+            (
+                "https://testnet-algorand.api.purestake.io/ps2",
+                Some(8443),
+                "https://testnet-algorand.api.purestake.io:8443/ps2/",
+            ),
+            (
+                "https://node.example.com/algod/",
+                None,
+                "https://node.example.com/algod/",
+            ),
+        ];
+        for (server, port, expected) in cases {
+            assert_eq!(
+                Some(expected.to_owned()),
+                node_url(&ALGOD, server, port).ok(),
+                "{}",
+                server
+            );
+        }
+    }
+
+    #[test]
+    fn test_node_url_with_conflicting_port() {
+        assert!(matches!(
+            node_url(&ALGOD, "http://localhost:4001", Some(8080)),
+            Err(AlgonautError::BadEnvVar {
+                name: "ALGOD_PORT",
+                ..
+            })
+        ));
+        assert!(matches!(
+            node_url(&ALGOD, "http://", None),
+            Err(AlgonautError::BadEnvVar {
+                name: "ALGOD_SERVER",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_from_map() {
+        let config = config(&[
+            ("ALGOD_SERVER", "https://testnet-api.algonode.cloud"),
+            ("ALGOD_PORT", ""),
+            ("ALGOD_TOKEN", ""),
+            ("INDEXER_SERVER", "http://localhost/"),
+            ("INDEXER_PORT", "8980"),
+            ("INDEXER_TOKEN", "my-token"),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            &NodePreset::new("https://testnet-api.algonode.cloud/", vec![]),
+            config.algod()
+        );
+        assert_eq!(
+            Some(&NodePreset::new(
+                "http://localhost:8980/",
+                vec![("X-Indexer-API-Token", "my-token")]
+            )),
+            config.indexer()
+        );
+        assert_eq!(None, config.kmd());
+        assert!(config.create_algod().is_ok());
+        assert!(config.create_indexer().is_ok());
+        assert!(matches!(
+            config.create_kmd(),
+            Err(AlgonautError::MissingEnvVars { .. })
+        ));
+    }
+
+    #[test]
+    fn test_from_map_with_token_header_and_timeout() {
+        let env = config(&[
+            ("ALGOD_SERVER", "https://node.example.com"),
+            ("ALGOD_TOKEN", "my-key"),
+            ("ALGOD_TOKEN_HEADER", "X-API-Key"),
+            ("ALGOD_TIMEOUT_MS", "100"),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            &NodePreset::new("https://node.example.com/", vec![("X-API-Key", "my-key")]),
+            env.algod()
+        );
+        assert_eq!(Some(Duration::from_millis(100)), env.algod.client.timeout);
+        assert_eq!(None, env.indexer());
+
+        assert!(matches!(
+            config(&[
+                ("ALGOD_SERVER", "https://node.example.com"),
+                ("ALGOD_TIMEOUT_MS", "soon"),
+            ]),
+            Err(AlgonautError::BadEnvVar {
+                name: "ALGOD_TIMEOUT_MS",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_from_map_lists_all_the_missing_vars() {
+        assert_eq!(
+            Some(AlgonautError::MissingEnvVars {
+                names: vec!["ALGOD_SERVER", "INDEXER_SERVER", "KMD_TOKEN"]
+            }),
+            config(&[
+                ("ALGOD_TOKEN", SANDBOX_TOKEN),
+                ("INDEXER_PORT", "8980"),
+                ("KMD_SERVER", "localhost"),
+            ])
+            .err()
+        );
+        assert_eq!(
+            Some(AlgonautError::MissingEnvVars {
+                names: vec!["ALGOD_SERVER"]
+            }),
+            config(&[]).err()
+        );
+    }
+
+    #[test]
+    fn test_from_map_with_invalid_port() {
+        assert!(matches!(
+            config(&[("ALGOD_SERVER", "localhost"), ("ALGOD_PORT", "http")]),
+            Err(AlgonautError::BadEnvVar {
+                name: "ALGOD_PORT",
+                ..
+            })
+        ));
+        assert!(matches!(
+            config(&[("ALGOD_SERVER", "localhost:4001"), ("ALGOD_PORT", "4002")]),
+            Err(AlgonautError::BadEnvVar {
+                name: "ALGOD_PORT",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_localnet_fallback() {
+        let config = config(&[("ALGOD_ENV", "LocalNet"), ("KMD_PORT", "7833")]).unwrap();
+
+        assert_eq!(&NodePreset::sandbox_algod(), config.algod());
+        assert_eq!(Some(&NodePreset::sandbox_indexer()), config.indexer());
+        assert_eq!(
+            Some(&NodePreset::new(
+                "http://localhost:7833/",
+                vec![("X-KMD-API-Token", SANDBOX_TOKEN)]
+            )),
+            config.kmd()
+        );
+        assert!(config.create_kmd().is_ok());
+    }
+
+    #[test]
+    fn test_localnet_fallback_only_with_the_flag() {
+        assert!(config(&[("ALGOD_ENV", "testnet")]).is_err());
+    }
+}
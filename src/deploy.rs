@@ -0,0 +1,904 @@
+//! Idempotent deployment of applications, compatible with the deployment notes of AlgoKit.
+//!
+//! Each creation and update of a deployed application carries a note with its metadata
+//! (`ALGOKIT_DEPLOYER:j{"name":...,"version":...}`), so a later deployment of the same name finds the
+//! application in the transactions of the creator, and updates or replaces it instead of creating a
+//! duplicate.
+
+use crate::algod::v2::{AlgodSemanticError, AlgodService};
+use crate::error::AlgonautError;
+use crate::indexer::v2::{pages, IndexerService};
+use algonaut_core::{Address, CompiledTeal, MicroAlgos, Round};
+use algonaut_model::algod::v2::{ApplicationStateSchema, PendingTransaction};
+use algonaut_model::indexer::v2::{OnCompletion, TransactionType as IndexerTransactionType};
+use algonaut_model::indexer::v2::{QueryAccountTransaction, Transaction as IndexerTransaction};
+use algonaut_transaction::{
+    account::Account,
+    builder::{CreateApplication, DeleteApplication, UpdateApplication},
+    error::TransactionError,
+    transaction::StateSchema,
+    Transaction, TransactionType, TxnBuilder,
+};
+use data_encoding::BASE64;
+use futures_util::{pin_mut, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha512_256};
+use thiserror::Error;
+
+/// Prefix of the notes of the deployment transactions, followed by the JSON of [AppDeployMetadata].
+pub const DEPLOYER_NOTE_PREFIX: &str = "ALGOKIT_DEPLOYER:j";
+
+/// Prefix of the data hashed into the address of an application.
+const APP_ID_PREFIX: &[u8] = b"appID";
+
+/// Metadata of a deployment, identifying the application by name in the transactions of its creator.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppDeployMetadata {
+    pub name: String,
+    pub version: String,
+    /// Whether the application can be deleted, if known: replacing it requires it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deletable: Option<bool>,
+    /// Whether the application can be updated, if known: updating it requires it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updatable: Option<bool>,
+}
+
+impl AppDeployMetadata {
+    /// The note of the deployment transactions.
+    pub fn to_note(&self) -> Vec<u8> {
+        let mut note = DEPLOYER_NOTE_PREFIX.as_bytes().to_vec();
+        // the serialization of plain strings and booleans can't fail
+        note.extend(serde_json::to_vec(self).unwrap_or_default());
+        note
+    }
+
+    /// The metadata of a deployment note, or `None` if the note isn't one.
+    pub fn from_note(note: &[u8]) -> Option<AppDeployMetadata> {
+        note.strip_prefix(DEPLOYER_NOTE_PREFIX.as_bytes())
+            .and_then(|json| serde_json::from_slice(json).ok())
+    }
+}
+
+/// An application to deploy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppSpec {
+    pub metadata: AppDeployMetadata,
+    pub approval_program: CompiledTeal,
+    pub clear_state_program: CompiledTeal,
+    pub global_state_schema: StateSchema,
+    pub local_state_schema: StateSchema,
+    pub extra_pages: u64,
+}
+
+/// What to do when the programs of the deployed application changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OnUpdate {
+    /// Fail with [DeployError::UpdateNotAllowed].
+    #[default]
+    Fail,
+    /// Update the programs of the application, keeping its id and state.
+    UpdateApp,
+    /// Replace the application with a new one, see [AppDeployer].
+    ReplaceApp,
+}
+
+/// What to do when the state schema of the deployed application is too small for the new one,
+/// which can't be changed by an update.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OnSchemaBreak {
+    /// Fail with [DeployError::SchemaBreak].
+    #[default]
+    Fail,
+    /// Replace the application with a new one, see [AppDeployer].
+    ReplaceApp,
+}
+
+/// What a deployment did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeployAction {
+    /// No application of this name was deployed: it was created.
+    Created,
+    /// The programs of the application were updated.
+    Updated,
+    /// The application was replaced by a new one, deleting the previous one.
+    Replaced { previous_app_id: u64 },
+    /// The deployed application has the same programs and a compatible schema.
+    Unchanged,
+}
+
+/// The deployed application.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppDeployment {
+    pub app_id: u64,
+    pub address: Address,
+    pub action: DeployAction,
+}
+
+/// Error deploying an application.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum DeployError {
+    #[error("{0}")]
+    Algonaut(#[from] AlgonautError),
+    /// A deployment transaction couldn't be built or signed.
+    #[error("Transaction error: {0}")]
+    Transaction(String),
+    /// The programs changed, and the policy or the application don't allow the update.
+    #[error("The programs of application {app_id} changed, and it can't be updated")]
+    UpdateNotAllowed { app_id: u64 },
+    /// The schema or the extra pages grew, and the policy or the application don't allow the
+    /// replacement.
+    #[error("The state schema of application {app_id} is too small, and it can't be replaced")]
+    SchemaBreak { app_id: u64 },
+    /// The application to replace holds `amount` in its account, which its deletion would strand:
+    /// only its logic can withdraw them.
+    #[error("Application {app_id} holds {amount:?}: withdraw them before replacing it")]
+    AppFunded { app_id: u64, amount: MicroAlgos },
+    /// A deployment transaction was rejected by the node: `error` is the reason parsed from the
+    /// `message` (the pool error of the transaction).
+    #[error("Transaction {tx_id} was rejected: {message}")]
+    Rejected {
+        tx_id: String,
+        error: AlgodSemanticError,
+        message: String,
+    },
+    /// A deployment transaction wasn't confirmed in its validity window.
+    #[error("Transaction {tx_id} wasn't confirmed")]
+    NotConfirmed { tx_id: String },
+}
+
+impl From<TransactionError> for DeployError {
+    fn from(error: TransactionError) -> Self {
+        DeployError::Transaction(error.to_string())
+    }
+}
+
+/// The address of the account of an application.
+pub fn app_address(app_id: u64) -> Address {
+    let mut hasher = Sha512_256::new();
+    hasher.update(APP_ID_PREFIX);
+    hasher.update(app_id.to_be_bytes());
+    Address(hasher.finalize().into())
+}
+
+/// Deploys applications idempotently: creates an application, or finds the one deployed previously
+/// with the same name by the creator and updates or replaces it if needed.
+///
+/// The previous deployment is the latest application created or updated by the creator with a
+/// deployment note of the same name (see [AppDeployMetadata]). If it was deleted since, a new one is
+/// created.
+///
+/// A deployed application is changed when its programs differ from the new ones (compared by hash),
+/// as decided by [OnUpdate], or when its global or local schema has fewer integers or byte slices
+/// than the new one, or it has fewer extra program pages, as decided by [OnSchemaBreak].
+///
+/// A replacement creates the new application and deletes the previous one. Only the logic of an
+/// application can move the Algos of its account, which would be stranded once it's deleted: the
+/// replacement of an application whose account holds Algos fails with [DeployError::AppFunded],
+/// before sending any transaction, so that they're withdrawn first.
+///
+/// Each transaction is sent and confirmed before the next one, so a failure can leave a
+/// replacement incomplete, e.g. with both applications: deploying again doesn't create another
+/// application, but the previous one has to be deleted manually.
+pub struct AppDeployer<'a> {
+    algod: &'a dyn AlgodService,
+    indexer: &'a dyn IndexerService,
+    creator: &'a Account,
+    on_update: OnUpdate,
+    on_schema_break: OnSchemaBreak,
+}
+
+impl<'a> AppDeployer<'a> {
+    /// A deployer of the applications of `creator`, failing on updates and schema breaks.
+    pub fn new(
+        algod: &'a dyn AlgodService,
+        indexer: &'a dyn IndexerService,
+        creator: &'a Account,
+    ) -> Self {
+        AppDeployer {
+            algod,
+            indexer,
+            creator,
+            on_update: OnUpdate::default(),
+            on_schema_break: OnSchemaBreak::default(),
+        }
+    }
+
+    pub fn on_update(mut self, on_update: OnUpdate) -> Self {
+        self.on_update = on_update;
+        self
+    }
+
+    pub fn on_schema_break(mut self, on_schema_break: OnSchemaBreak) -> Self {
+        self.on_schema_break = on_schema_break;
+        self
+    }
+
+    /// Deploys `app`, returning the deployed application and the action taken.
+    pub async fn deploy(&self, app: &AppSpec) -> Result<AppDeployment, DeployError> {
+        let (app_id, metadata) = match self.find_deployment(&app.metadata.name).await? {
+            Some(deployment) => deployment,
+            None => return self.create(app).await,
+        };
+        let current = match self.algod.application_information(app_id).await {
+            Ok(current) => current.params,
+            // deleted since the deployment
+            Err(e) if e.is_404() => return self.create(app).await,
+            Err(e) => return Err(e.into()),
+        };
+
+        if is_schema_break(&current.global_state_schema, &app.global_state_schema)
+            || is_schema_break(&current.local_state_schema, &app.local_state_schema)
+            || app.extra_pages > current.extra_program_pages.unwrap_or(0)
+        {
+            return match self.on_schema_break {
+                OnSchemaBreak::ReplaceApp if metadata.deletable != Some(false) => {
+                    self.replace(app, app_id).await
+                }
+                _ => Err(DeployError::SchemaBreak { app_id }),
+            };
+        }
+
+        let programs_changed = program_hash(&current.approval_program)
+            != program_hash(&app.approval_program.0)
+            || program_hash(&current.clear_state_program)
+                != program_hash(&app.clear_state_program.0);
+        if !programs_changed {
+            return Ok(AppDeployment {
+                app_id,
+                address: app_address(app_id),
+                action: DeployAction::Unchanged,
+            });
+        }
+        match self.on_update {
+            OnUpdate::UpdateApp if metadata.updatable != Some(false) => {
+                self.update(app, app_id).await
+            }
+            OnUpdate::ReplaceApp if metadata.deletable != Some(false) => {
+                self.replace(app, app_id).await
+            }
+            _ => Err(DeployError::UpdateNotAllowed { app_id }),
+        }
+    }
+
+    /// The id and metadata of the latest application deployed by the creator with this name.
+    async fn find_deployment(
+        &self,
+        name: &str,
+    ) -> Result<Option<(u64, AppDeployMetadata)>, DeployError> {
+        let creator = self.creator.address();
+        let query = QueryAccountTransaction {
+            note_prefix: Some(BASE64.encode(DEPLOYER_NOTE_PREFIX.as_bytes())),
+            tx_type: Some(IndexerTransactionType::ApplicationTransaction),
+            ..QueryAccountTransaction::default()
+        };
+        let indexer = &self.indexer;
+        let pages = pages(query, |query| async move {
+            indexer.account_transactions(&creator, &query).await
+        });
+        pin_mut!(pages);
+        let mut latest: Option<((Round, u64), u64, AppDeployMetadata)> = None;
+        while let Some(transactions) = pages.try_next().await? {
+            for txn in &transactions {
+                if let Some((app_id, metadata)) = deployment(txn, &creator, name) {
+                    let order = (
+                        txn.confirmed_round.unwrap_or_default(),
+                        txn.intra_round_offset.unwrap_or_default(),
+                    );
+                    if latest.as_ref().is_none_or(|(latest, ..)| order > *latest) {
+                        latest = Some((order, app_id, metadata));
+                    }
+                }
+            }
+        }
+        Ok(latest.map(|(_, app_id, metadata)| (app_id, metadata)))
+    }
+
+    async fn create(&self, app: &AppSpec) -> Result<AppDeployment, DeployError> {
+        let app_id = self.create_app(app).await?;
+        Ok(AppDeployment {
+            app_id,
+            address: app_address(app_id),
+            action: DeployAction::Created,
+        })
+    }
+
+    async fn update(&self, app: &AppSpec, app_id: u64) -> Result<AppDeployment, DeployError> {
+        let update = UpdateApplication::new(
+            self.creator.address(),
+            app_id,
+            app.approval_program.clone(),
+            app.clear_state_program.clone(),
+        )
+        .build();
+        self.send(update, Some(app.metadata.to_note())).await?;
+        Ok(AppDeployment {
+            app_id,
+            address: app_address(app_id),
+            action: DeployAction::Updated,
+        })
+    }
+
+    async fn replace(
+        &self,
+        app: &AppSpec,
+        previous_app_id: u64,
+    ) -> Result<AppDeployment, DeployError> {
+        let amount = self
+            .algod
+            .account_information(&app_address(previous_app_id))
+            .await?
+            .amount;
+        if amount > MicroAlgos(0) {
+            return Err(DeployError::AppFunded {
+                app_id: previous_app_id,
+                amount,
+            });
+        }
+
+        let app_id = self.create_app(app).await?;
+        let address = app_address(app_id);
+        let delete = DeleteApplication::new(self.creator.address(), previous_app_id).build();
+        self.send(delete, None).await?;
+
+        Ok(AppDeployment {
+            app_id,
+            address,
+            action: DeployAction::Replaced { previous_app_id },
+        })
+    }
+
+    async fn create_app(&self, app: &AppSpec) -> Result<u64, DeployError> {
+        let create = CreateApplication::new(
+            self.creator.address(),
+            app.approval_program.clone(),
+            app.clear_state_program.clone(),
+            app.global_state_schema.clone(),
+            app.local_state_schema.clone(),
+        )
+        .extra_pages(app.extra_pages)
+        .build();
+        let pending = self.send(create, Some(app.metadata.to_note())).await?;
+        pending
+            .application_index
+            .ok_or_else(|| AlgonautError::Internal("Missing created application id".to_owned()))
+            .map_err(DeployError::from)
+    }
+
+    /// Signs and sends a transaction of the creator, waiting for its confirmation.
+    async fn send(
+        &self,
+        txn_type: TransactionType,
+        note: Option<Vec<u8>>,
+    ) -> Result<PendingTransaction, DeployError> {
+        let params = self.algod.suggested_transaction_params().await?;
+        let mut builder = TxnBuilder::with(&params, txn_type);
+        if let Some(note) = note {
+            builder = builder.note(note);
+        }
+        let txn: Transaction = builder.build()?;
+        let signed = self.creator.sign_transaction(txn)?;
+        let tx_id = self
+            .algod
+            .broadcast_signed_transaction(&signed)
+            .await?
+            .tx_id;
+
+        let mut round = signed.transaction.first_valid;
+        loop {
+            let pending = self.algod.pending_transaction_with_id(&tx_id).await?;
+            if pending.confirmed_round.is_some() {
+                return Ok(pending);
+            }
+            if !pending.pool_error.is_empty() {
+                return Err(DeployError::Rejected {
+                    tx_id,
+                    error: AlgodSemanticError::parse(&pending.pool_error),
+                    message: pending.pool_error,
+                });
+            }
+            if round > signed.transaction.last_valid {
+                return Err(DeployError::NotConfirmed { tx_id });
+            }
+            round = Round(self.algod.status_after_round(round).await?.last_round);
+        }
+    }
+}
+
+/// The id of the application created or updated by a deployment transaction of `name` by `creator`.
+fn deployment(
+    txn: &IndexerTransaction,
+    creator: &Address,
+    name: &str,
+) -> Option<(u64, AppDeployMetadata)> {
+    if txn.sender != creator.to_string() {
+        return None;
+    }
+    let metadata = AppDeployMetadata::from_note(&txn.decoded_note().ok()??)?;
+    if metadata.name != name {
+        return None;
+    }
+    let app_id = match (txn.created_application_index, &txn.application_transaction) {
+        (Some(app_id), _) => app_id,
+        (None, Some(call)) if call.on_completion == OnCompletion::Update => call.application_id,
+        _ => return None,
+    };
+    Some((app_id, metadata))
+}
+
+/// Whether the new schema needs more integers or byte slices than the current one.
+fn is_schema_break(current: &Option<ApplicationStateSchema>, new: &StateSchema) -> bool {
+    let (ints, byte_slices) = current
+        .as_ref()
+        .map_or((0, 0), |schema| (schema.num_uint, schema.num_byte_slice));
+    new.number_ints > ints || new.number_byteslices > byte_slices
+}
+
+fn program_hash(program: &[u8]) -> [u8; 32] {
+    Sha512_256::digest(program).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{RequestError, RequestErrorDetails};
+    use crate::testing::{MockAlgod, MockIndexer};
+    use algonaut_core::SuggestedTransactionParams;
+    use algonaut_crypto::HashDigest;
+    use algonaut_model::algod::v2::{
+        Account as AlgodAccount, Application, NodeStatus, TransactionResponse,
+    };
+    use algonaut_model::indexer::v2::AccountTransactionResponse;
+    use serde_json::json;
+
+    const APPROVAL: &[u8] = &[6, 129, 1];
+    const CLEAR: &[u8] = &[6, 129, 1, 67];
+
+    fn app(name: &str) -> AppSpec {
+        AppSpec {
+            metadata: AppDeployMetadata {
+                name: name.to_owned(),
+                version: "1.0".to_owned(),
+                deletable: Some(true),
+                updatable: Some(true),
+            },
+            approval_program: CompiledTeal(APPROVAL.to_vec()),
+            clear_state_program: CompiledTeal(CLEAR.to_vec()),
+            global_state_schema: StateSchema {
+                number_ints: 1,
+                number_byteslices: 1,
+            },
+            local_state_schema: StateSchema {
+                number_ints: 0,
+                number_byteslices: 0,
+            },
+            extra_pages: 0,
+        }
+    }
+
+    /// A deployment transaction of `creator` in `round`, creating `app_id` or updating it.
+    fn deployment_txn(
+        creator: &Address,
+        round: u64,
+        app_id: u64,
+        metadata: &AppDeployMetadata,
+        is_update: bool,
+    ) -> serde_json::Value {
+        let mut txn = json!({
+            "application-transaction": {
+                "application-id": if is_update { app_id } else { 0 },
+                "on-completion": if is_update { "update" } else { "noop" },
+            },
+            "confirmed-round": round,
+            "fee": 1000,
+            "first-valid": round - 1,
+            "genesis-hash": "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=",
+            "id": format!("TX{}", round),
+            "last-valid": round + 999,
+            "note": BASE64.encode(&metadata.to_note()),
+            "sender": creator.to_string(),
+            "tx-type": "appl",
+        });
+        if !is_update {
+            txn["created-application-index"] = app_id.into();
+        }
+        txn
+    }
+
+    fn indexer(txns: Vec<serde_json::Value>) -> MockIndexer {
+        let indexer = MockIndexer::new();
+        let response: AccountTransactionResponse = serde_json::from_value(json!({
+            "current-round": 100,
+            "transactions": txns,
+        }))
+        .unwrap();
+        indexer.respond("account_transactions", Ok(response));
+        indexer
+    }
+
+    /// An algod confirming all the transactions, and the creation of `created_app_id`.
+    fn algod(created_app_id: u64) -> MockAlgod {
+        let algod = MockAlgod::new();
+        algod.respond(
+            "suggested_transaction_params",
+            Ok(SuggestedTransactionParams {
+                genesis_id: "testnet-v1.0".to_owned(),
+                genesis_hash: HashDigest([0; 32]),
+                consensus_version: "v1".to_owned(),
+                fee_per_byte: MicroAlgos(0),
+                min_fee: MicroAlgos(1000),
+                first_valid: Round(100),
+                last_valid: Round(1100),
+            }),
+        );
+        algod.respond(
+            "broadcast_signed_transaction",
+            Ok(TransactionResponse {
+                tx_id: "TX".to_owned(),
+            }),
+        );
+        let pending: PendingTransaction = serde_json::from_value(json!({
+            "application-index": created_app_id,
+            "confirmed-round": 101,
+            "pool-error": "",
+            "txn": {},
+        }))
+        .unwrap();
+        algod.respond("pending_transaction_with_id", Ok(pending));
+        algod
+    }
+
+    fn respond_app(algod: &MockAlgod, app_id: u64, creator: &Address, schema: (u64, u64)) {
+        // the programs are deserialized from borrowed strings, so not from a `Value`
+        let json = json!({
+            "id": app_id,
+            "params": {
+                "approval-program": BASE64.encode(APPROVAL),
+                "clear-state-program": BASE64.encode(CLEAR),
+                "creator": creator.to_string(),
+                "global-state-schema": { "num-uint": schema.0, "num-byte-slice": schema.1 },
+            },
+        });
+        let application: Application = serde_json::from_str(&json.to_string()).unwrap();
+        algod.respond_to("application_information", app_id, Ok(application));
+    }
+
+    fn broadcasts(algod: &MockAlgod) -> Vec<String> {
+        algod
+            .calls()
+            .into_iter()
+            .filter(|call| call.method == "broadcast_signed_transaction")
+            .map(|call| call.args)
+            .collect()
+    }
+
+    #[test]
+    fn test_app_address() {
+        assert_eq!(
+            "PCYUFPA2ZTOYWTP43MX2MOX2OWAIAXUDNC2WFCXAGMRUZ3DYD6BWFDL5YM",
+            app_address(77).to_string()
+        );
+    }
+
+    #[test]
+    fn test_metadata_note() {
+        let metadata = app("app").metadata;
+        let note = metadata.to_note();
+        assert_eq!(
+            r#"ALGOKIT_DEPLOYER:j{"name":"app","version":"1.0","deletable":true,"updatable":true}"#,
+            String::from_utf8(note.clone()).unwrap()
+        );
+        assert_eq!(Some(metadata), AppDeployMetadata::from_note(&note));
+        assert_eq!(None, AppDeployMetadata::from_note(b"hello"));
+    }
+
+    #[tokio::test]
+    async fn test_deploy_creates_app() {
+        let creator = Account::generate();
+        let other = app("other").metadata;
+        let indexer = indexer(vec![deployment_txn(
+            &creator.address(),
+            10,
+            5,
+            &other,
+            false,
+        )]);
+        let algod = algod(12);
+
+        let deployment = AppDeployer::new(&algod, &indexer, &creator)
+            .deploy(&app("app"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            AppDeployment {
+                app_id: 12,
+                address: app_address(12),
+                action: DeployAction::Created
+            },
+            deployment
+        );
+        assert_eq!(1, broadcasts(&algod).len());
+    }
+
+    #[tokio::test]
+    async fn test_deploy_unchanged_app() {
+        let creator = Account::generate();
+        let app = app("app");
+        let indexer = indexer(vec![deployment_txn(
+            &creator.address(),
+            10,
+            5,
+            &app.metadata,
+            false,
+        )]);
+        let algod = algod(12);
+        respond_app(&algod, 5, &creator.address(), (1, 1));
+
+        let deployment = AppDeployer::new(&algod, &indexer, &creator)
+            .deploy(&app)
+            .await
+            .unwrap();
+
+        assert_eq!(DeployAction::Unchanged, deployment.action);
+        assert_eq!(5, deployment.app_id);
+        assert!(broadcasts(&algod).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_deploy_finds_latest_deployment() {
+        let creator = Account::generate();
+        let app = app("app");
+        let indexer = indexer(vec![
+            deployment_txn(&creator.address(), 30, 7, &app.metadata, true),
+            deployment_txn(&creator.address(), 20, 7, &app.metadata, false),
+            deployment_txn(&creator.address(), 10, 5, &app.metadata, false),
+        ]);
+        let algod = algod(12);
+        respond_app(&algod, 7, &creator.address(), (1, 1));
+
+        let deployment = AppDeployer::new(&algod, &indexer, &creator)
+            .deploy(&app)
+            .await
+            .unwrap();
+
+        assert_eq!(7, deployment.app_id);
+        assert_eq!(DeployAction::Unchanged, deployment.action);
+    }
+
+    #[tokio::test]
+    async fn test_deploy_recreates_deleted_app() {
+        let creator = Account::generate();
+        let app = app("app");
+        let indexer = indexer(vec![deployment_txn(
+            &creator.address(),
+            10,
+            5,
+            &app.metadata,
+            false,
+        )]);
+        let algod = algod(12);
+        let not_found = AlgonautError::Request(RequestError::new(
+            None,
+            RequestErrorDetails::Http {
+                status: 404,
+                message: "application does not exist".to_owned(),
+            },
+        ));
+        algod.respond::<Application>("application_information", Err(not_found));
+
+        let deployment = AppDeployer::new(&algod, &indexer, &creator)
+            .deploy(&app)
+            .await
+            .unwrap();
+
+        assert_eq!(12, deployment.app_id);
+        assert_eq!(DeployAction::Created, deployment.action);
+    }
+
+    #[tokio::test]
+    async fn test_deploy_updated_programs() {
+        let creator = Account::generate();
+        let mut app = app("app");
+        app.approval_program = CompiledTeal(vec![6, 129, 2]);
+        let indexer = indexer(vec![deployment_txn(
+            &creator.address(),
+            10,
+            5,
+            &app.metadata,
+            false,
+        )]);
+        let algod = algod(12);
+        respond_app(&algod, 5, &creator.address(), (1, 1));
+
+        let res = AppDeployer::new(&algod, &indexer, &creator)
+            .deploy(&app)
+            .await;
+        assert_eq!(Err(DeployError::UpdateNotAllowed { app_id: 5 }), res);
+
+        let deployment = AppDeployer::new(&algod, &indexer, &creator)
+            .on_update(OnUpdate::UpdateApp)
+            .deploy(&app)
+            .await
+            .unwrap();
+        assert_eq!(DeployAction::Updated, deployment.action);
+        assert_eq!(5, deployment.app_id);
+        assert_eq!(1, broadcasts(&algod).len());
+    }
+
+    #[tokio::test]
+    async fn test_deploy_update_of_non_updatable_app_fails() {
+        let creator = Account::generate();
+        let mut app = app("app");
+        app.approval_program = CompiledTeal(vec![6, 129, 2]);
+        let mut deployed = app.metadata.clone();
+        deployed.updatable = Some(false);
+        let indexer = indexer(vec![deployment_txn(
+            &creator.address(),
+            10,
+            5,
+            &deployed,
+            false,
+        )]);
+        let algod = algod(12);
+        respond_app(&algod, 5, &creator.address(), (1, 1));
+
+        let res = AppDeployer::new(&algod, &indexer, &creator)
+            .on_update(OnUpdate::UpdateApp)
+            .deploy(&app)
+            .await;
+        assert_eq!(Err(DeployError::UpdateNotAllowed { app_id: 5 }), res);
+        assert!(broadcasts(&algod).is_empty());
+    }
+
+    /// The account of `app_id` holding `amount`.
+    fn respond_app_funds(algod: &MockAlgod, app_id: u64, amount: u64) {
+        let funds: AlgodAccount = serde_json::from_value(json!({
+            "address": app_address(app_id).to_string(),
+            "amount": amount,
+            "amount-without-pending-rewards": amount,
+            "pending-rewards": 0,
+            "rewards": 0,
+            "round": 100,
+            "status": "Offline",
+        }))
+        .unwrap();
+        algod.respond_to("account_information", app_address(app_id), Ok(funds));
+    }
+
+    #[tokio::test]
+    async fn test_deploy_schema_break_replaces_app() {
+        let creator = Account::generate();
+        let app = app("app");
+        let indexer = indexer(vec![deployment_txn(
+            &creator.address(),
+            10,
+            5,
+            &app.metadata,
+            false,
+        )]);
+        let algod = algod(12);
+        // the deployed app has no byte slices in its global state
+        respond_app(&algod, 5, &creator.address(), (1, 0));
+        respond_app_funds(&algod, 5, 0);
+
+        let res = AppDeployer::new(&algod, &indexer, &creator)
+            .on_update(OnUpdate::UpdateApp)
+            .deploy(&app)
+            .await;
+        assert_eq!(Err(DeployError::SchemaBreak { app_id: 5 }), res);
+
+        let deployment = AppDeployer::new(&algod, &indexer, &creator)
+            .on_schema_break(OnSchemaBreak::ReplaceApp)
+            .deploy(&app)
+            .await
+            .unwrap();
+        assert_eq!(
+            AppDeployment {
+                app_id: 12,
+                address: app_address(12),
+                action: DeployAction::Replaced { previous_app_id: 5 }
+            },
+            deployment
+        );
+
+        // creation of the new app and deletion of the previous one
+        let broadcasts = broadcasts(&algod);
+        assert_eq!(2, broadcasts.len());
+        assert!(broadcasts[0].contains("ApplicationCallTransaction"));
+        assert!(broadcasts[1].contains("DeleteApplication"));
+        assert!(broadcasts[1].contains("app_id: Some(5)"));
+    }
+
+    #[tokio::test]
+    async fn test_deploy_more_extra_pages_is_schema_break() {
+        let creator = Account::generate();
+        let app = AppSpec {
+            extra_pages: 1,
+            ..app("app")
+        };
+        let indexer = indexer(vec![deployment_txn(
+            &creator.address(),
+            10,
+            5,
+            &app.metadata,
+            false,
+        )]);
+        let algod = algod(12);
+        respond_app(&algod, 5, &creator.address(), (1, 1));
+
+        let res = AppDeployer::new(&algod, &indexer, &creator)
+            .on_update(OnUpdate::UpdateApp)
+            .deploy(&app)
+            .await;
+
+        assert_eq!(Err(DeployError::SchemaBreak { app_id: 5 }), res);
+        assert!(broadcasts(&algod).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_deploy_replacement_of_funded_app_fails() {
+        let creator = Account::generate();
+        let app = app("app");
+        let indexer = indexer(vec![deployment_txn(
+            &creator.address(),
+            10,
+            5,
+            &app.metadata,
+            false,
+        )]);
+        let algod = algod(12);
+        respond_app(&algod, 5, &creator.address(), (1, 0));
+        respond_app_funds(&algod, 5, 300_000);
+
+        let res = AppDeployer::new(&algod, &indexer, &creator)
+            .on_schema_break(OnSchemaBreak::ReplaceApp)
+            .deploy(&app)
+            .await;
+
+        assert_eq!(
+            Err(DeployError::AppFunded {
+                app_id: 5,
+                amount: MicroAlgos(300_000)
+            }),
+            res
+        );
+        assert!(broadcasts(&algod).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_deploy_waits_for_confirmation() {
+        let creator = Account::generate();
+        let indexer = indexer(vec![]);
+        let algod = algod(12);
+        let pending: PendingTransaction = serde_json::from_value(json!({
+            "pool-error": "",
+            "txn": {},
+        }))
+        .unwrap();
+        algod.respond("pending_transaction_with_id", Ok(pending));
+        let status: NodeStatus = serde_json::from_value(json!({
+            "catchup-time": 0,
+            "last-round": 2000,
+            "last-version": "v1",
+            "next-version": "v1",
+            "next-version-round": 2001,
+            "next-version-supported": true,
+            "stopped-at-unsupported-round": false,
+            "time-since-last-round": 0,
+        }))
+        .unwrap();
+        algod.respond("status_after_round", Ok(status));
+
+        let res = AppDeployer::new(&algod, &indexer, &creator)
+            .deploy(&app("app"))
+            .await;
+
+        assert_eq!(
+            Err(DeployError::NotConfirmed {
+                tx_id: "TX".to_owned()
+            }),
+            res
+        );
+    }
+}
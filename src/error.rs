@@ -1,7 +1,11 @@
+use crate::algod::v2::AlgodSemanticError;
+pub use algonaut_client::meta::ResponseMeta;
+use algonaut_core::Round;
 use std::fmt::Debug;
+use std::time::Duration;
 use thiserror::Error;
 
-#[derive(Error, Debug, PartialEq, Eq)]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum AlgonautError {
     /// URL parse error.
     #[error("Url parsing error.")]
@@ -18,21 +22,211 @@ pub enum AlgonautError {
     /// Missing the authentication token for the REST API server.
     #[error("Set a token before calling build.")]
     UnitializedToken,
+    /// An environment variable read to build a client (e.g. `INDEXER_SERVER`) is missing or invalid.
+    #[error("Environment variable {name}: {reason}")]
+    BadEnvVar { name: &'static str, reason: String },
+    /// Environment variables required to configure the clients (e.g. `ALGOD_SERVER`) are missing:
+    /// all of them are listed.
+    #[error("Missing environment variables: {}", names.join(", "))]
+    MissingEnvVars { names: Vec<&'static str> },
     /// HTTP calls errors
     #[error("http error: {0}")]
     Request(RequestError),
+    /// The indexer didn't process yet the minimum round required by the client.
+    #[error("Indexer is behind: current round {current}, required round {required}.")]
+    IndexerBehind { current: Round, required: Round },
+    /// The connection was closed before the whole response body was received.
+    /// The request can be retried.
+    #[error("Incomplete response from {url:?}.")]
+    IncompleteResponse { url: Option<String> },
+    /// The response body is larger than the `max_response_bytes` of the client config: it was not
+    /// read further.
+    #[error("Response from {url:?} larger than {limit} bytes.")]
+    ResponseTooLarge { url: Option<String>, limit: usize },
+    /// The response body was received but couldn't be decoded: `path` is the path of the value at
+    /// fault in the JSON body (e.g. `transactions[3].fee`), if known, and `body` the body,
+    /// truncated, if the `debug_body_limit` of the client config is set.
+    #[error("Decode error from {url:?} at {path:?}: {description}")]
+    Decode {
+        url: Option<String>,
+        description: String,
+        path: Option<String>,
+        body: Option<String>,
+    },
+    /// The resource (e.g. "transaction") with this id doesn't exist, or the node doesn't have it
+    /// (anymore, or yet). The message is the one of the 404 response.
+    #[error("{resource} {id} not found: {message}")]
+    NotFound {
+        resource: &'static str,
+        id: String,
+        message: String,
+    },
+    /// The indexer can't compute a state at this round (e.g. the account at a round before the
+    /// history it retains, or an indexer not supporting rewinding). The message is the one of the
+    /// response.
+    #[error("Round {} unavailable: {message}", round.0)]
+    RoundUnavailable { round: Round, message: String },
+    /// The request was rejected (401) because it requires a different API token: `token` is the
+    /// class of token required (e.g. "admin" for the node management endpoints).
+    #[error("Unauthorized: the request requires the {token} API token: {message}")]
+    Unauthorized {
+        token: &'static str,
+        message: String,
+    },
+    /// The hash of the block of this round kept changing while it was fetched, e.g. on a node
+    /// catching up.
+    #[error("The hash of the block of round {} kept changing", round.0)]
+    UnstableBlockHash { round: Round },
+    /// The node was still catching up to this catchpoint when the wait timed out.
+    #[error("Timed out waiting for the catchup to catchpoint {catchpoint}")]
+    CatchupTimeout { catchpoint: String },
+    /// The provider doesn't serve the network with this genesis hash.
+    #[error("{provider} doesn't serve the network with genesis hash {genesis_hash}")]
+    UnsupportedNetwork {
+        provider: &'static str,
+        genesis_hash: String,
+    },
+    /// Algod rejected a transaction (400): `error` is the reason parsed from the `message` of the
+    /// node, to branch on the common failures.
+    #[error("Transaction rejected: {message}")]
+    TransactionRejected {
+        error: AlgodSemanticError,
+        message: String,
+    },
+    /// The service is temporarily unavailable (503), e.g. an indexer in maintenance or migrating
+    /// its database: the request can be tried again later, after `retry_after` if the response
+    /// had a `Retry-After` header. The message is the one of the response, and `meta` its status
+    /// and rate limit headers, as with [response_meta](Self::response_meta).
+    #[error("Service unavailable, try again later: {message}")]
+    ServiceUnavailable {
+        url: Option<String>,
+        retry_after: Option<Duration>,
+        message: String,
+        meta: Option<Box<ResponseMeta>>,
+    },
+    /// A field of a response isn't validly encoded (e.g. invalid base64).
+    #[error("Invalid encoding of field: {field}")]
+    FieldDecode { field: &'static str },
+    /// A query or the arguments of a request were rejected before being sent (e.g. an empty round
+    /// range, a malformed transaction id or catchpoint label, or a quorum of more indexers than
+    /// given): `param` is the field or the argument at fault, if any (e.g. "min_round").
+    #[error("Bad query: {reason}")]
+    BadQuery {
+        reason: String,
+        param: Option<&'static str>,
+    },
+    /// None of the indexers of a [MultiIndexer](crate::indexer::v2::MultiIndexer) passed its
+    /// health check: `last_error` is the error of the last one checked.
+    #[error("None of the {count} indexers is healthy: {last_error}")]
+    NoHealthyIndexer { count: usize, last_error: String },
+    /// Fewer than `quorum` of the `responses` of the indexers of a
+    /// [MultiIndexer](crate::indexer::v2::MultiIndexer) agree: at most `agreeing` of them do.
+    #[error("Quorum not reached: {agreeing} of {responses} responses agree, {quorum} required")]
+    QuorumNotReached {
+        quorum: usize,
+        agreeing: usize,
+        responses: usize,
+    },
+    /// A TEAL [Template](crate::teal::Template) was compiled with placeholders left without a
+    /// value: `names` lists them (e.g. "TMPL_RCV").
+    #[error("Unsubstituted template placeholders: {}", names.join(", "))]
+    UnsubstitutedPlaceholders { names: Vec<String> },
+    /// Reading or writing failed (e.g. the writer of an export).
+    #[error("I/O error ({kind:?}): {message}")]
+    Io {
+        kind: std::io::ErrorKind,
+        message: String,
+    },
     /// Internal errors (please open an [issue](https://github.com/manuelmauro/algonaut/issues)!)
     #[error("Internal error: {0}")]
     Internal(String),
 }
 
 impl AlgonautError {
-    /// Returns if the error is a `RequestError` that failed with a status code of 404.
+    /// Returns if the error is a `RequestError` that failed with a status code of 404, or a
+    /// `NotFound`.
     pub fn is_404(&self) -> bool {
         if let Some(e) = self.as_request_error() {
             e.is_404()
         } else {
-            false
+            matches!(self, Self::NotFound { .. })
+        }
+    }
+
+    /// Converts a 404 response to a `NotFound` error of the resource with this id.
+    pub(crate) fn into_not_found(self, resource: &'static str, id: &str) -> AlgonautError {
+        match self {
+            Self::Request(RequestError {
+                details:
+                    RequestErrorDetails::Http {
+                        status: 404,
+                        message,
+                    },
+                ..
+            }) => AlgonautError::NotFound {
+                resource,
+                id: id.to_owned(),
+                message,
+            },
+            error => error,
+        }
+    }
+
+    /// Converts a 401 response to an `Unauthorized` error, for an endpoint requiring this class of
+    /// token.
+    pub(crate) fn into_unauthorized(self, token: &'static str) -> AlgonautError {
+        match self {
+            Self::Request(RequestError {
+                details:
+                    RequestErrorDetails::Http {
+                        status: 401,
+                        message,
+                    },
+                ..
+            }) => AlgonautError::Unauthorized { token, message },
+            error => error,
+        }
+    }
+
+    /// Converts a failure of the indexer to rewind an account to `round` (a 400 or 500 response
+    /// mentioning the rewinding) to a `RoundUnavailable` error.
+    pub(crate) fn into_round_unavailable(self, round: Round) -> AlgonautError {
+        match self {
+            Self::Request(RequestError {
+                details: RequestErrorDetails::Http { status, message },
+                ..
+            }) if (status == 400 || status == 500) && message.contains("rewind") => {
+                AlgonautError::RoundUnavailable { round, message }
+            }
+            error => error,
+        }
+    }
+
+    /// Converts a 400 response to a `TransactionRejected` error, for the endpoints receiving
+    /// transactions.
+    pub(crate) fn into_transaction_rejected(self) -> AlgonautError {
+        match self {
+            Self::Request(RequestError {
+                details:
+                    RequestErrorDetails::Http {
+                        status: 400,
+                        message,
+                    },
+                ..
+            }) => AlgonautError::TransactionRejected {
+                error: AlgodSemanticError::parse(&message),
+                message,
+            },
+            error => error,
+        }
+    }
+
+    /// Status and rate limit headers of the response, if the error is an HTTP error response
+    /// (e.g. a 429 with `Retry-After`).
+    pub fn response_meta(&self) -> Option<&ResponseMeta> {
+        match self {
+            Self::ServiceUnavailable { meta, .. } => meta.as_deref(),
+            _ => self.as_request_error().and_then(|e| e.meta.as_deref()),
         }
     }
 
@@ -45,16 +239,22 @@ impl AlgonautError {
     }
 }
 
-#[derive(Error, Debug, PartialEq, Eq)]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
 #[error("{:?}, {}", url, details)]
 pub struct RequestError {
     pub url: Option<String>,
     pub details: RequestErrorDetails,
+    /// Status and rate limit headers of the response, if the server responded.
+    pub meta: Option<Box<ResponseMeta>>,
 }
 
 impl RequestError {
     pub fn new(url: Option<String>, details: RequestErrorDetails) -> RequestError {
-        RequestError { url, details }
+        RequestError {
+            url,
+            details,
+            meta: None,
+        }
     }
 
     /// Returns if the cause of the error is a 404 response from the client.
@@ -63,7 +263,7 @@ impl RequestError {
     }
 }
 
-#[derive(Error, Debug, PartialEq, Eq)]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum RequestErrorDetails {
     /// Http call error with optional message (returned by remote API)
     #[error("Http error: {}, {}", status, message)]
@@ -94,14 +294,45 @@ impl From<algonaut_client::error::ClientError> for AlgonautError {
             algonaut_client::error::ClientError::BadUrl(msg) => AlgonautError::BadUrl(msg),
             algonaut_client::error::ClientError::BadToken => AlgonautError::BadToken,
             algonaut_client::error::ClientError::BadHeader(msg) => AlgonautError::BadHeader(msg),
-            algonaut_client::error::ClientError::Request(e) => AlgonautError::Request(e.into()),
+            algonaut_client::error::ClientError::Request(e) => match e.details {
+                algonaut_client::error::RequestErrorDetails::IncompleteResponse => {
+                    AlgonautError::IncompleteResponse { url: e.url }
+                }
+                algonaut_client::error::RequestErrorDetails::ResponseTooLarge { limit } => {
+                    AlgonautError::ResponseTooLarge { url: e.url, limit }
+                }
+                algonaut_client::error::RequestErrorDetails::Decode {
+                    description,
+                    path,
+                    body,
+                } => AlgonautError::Decode {
+                    url: e.url,
+                    description,
+                    path,
+                    body,
+                },
+                algonaut_client::error::RequestErrorDetails::Http {
+                    status: 503,
+                    message,
+                } => AlgonautError::ServiceUnavailable {
+                    url: e.url,
+                    retry_after: e.meta.as_ref().and_then(|meta| meta.retry_after),
+                    message,
+                    meta: e.meta,
+                },
+                _ => AlgonautError::Request(e.into()),
+            },
         }
     }
 }
 
 impl From<algonaut_client::error::RequestError> for RequestError {
     fn from(error: algonaut_client::error::RequestError) -> Self {
-        RequestError::new(error.url.clone(), error.details.into())
+        RequestError {
+            url: error.url,
+            details: error.details.into(),
+            meta: error.meta,
+        }
     }
 }
 
@@ -115,6 +346,15 @@ impl From<algonaut_client::error::RequestErrorDetails> for RequestErrorDetails {
             algonaut_client::error::RequestErrorDetails::Client { description } => {
                 RequestErrorDetails::Client { description }
             }
+            // mapped to AlgonautError::IncompleteResponse, AlgonautError::ResponseTooLarge and
+            // AlgonautError::Decode when converting client errors
+            details @ (algonaut_client::error::RequestErrorDetails::IncompleteResponse
+            | algonaut_client::error::RequestErrorDetails::ResponseTooLarge { .. }
+            | algonaut_client::error::RequestErrorDetails::Decode { .. }) => {
+                RequestErrorDetails::Client {
+                    description: details.to_string(),
+                }
+            }
         }
     }
 }
@@ -125,6 +365,30 @@ impl From<rmp_serde::encode::Error> for AlgonautError {
     }
 }
 
+impl From<algonaut_model::FieldDecodeError> for AlgonautError {
+    fn from(error: algonaut_model::FieldDecodeError) -> Self {
+        AlgonautError::FieldDecode { field: error.field }
+    }
+}
+
+impl From<algonaut_model::QueryError> for AlgonautError {
+    fn from(error: algonaut_model::QueryError) -> Self {
+        AlgonautError::BadQuery {
+            reason: error.reason,
+            param: error.param,
+        }
+    }
+}
+
+impl From<std::io::Error> for AlgonautError {
+    fn from(error: std::io::Error) -> Self {
+        AlgonautError::Io {
+            kind: error.kind(),
+            message: error.to_string(),
+        }
+    }
+}
+
 impl From<String> for AlgonautError {
     fn from(error: String) -> Self {
         AlgonautError::Internal(error)
@@ -164,3 +428,188 @@ fn check_404() {
         "an unrelated request error is saying that it is a 404 error"
     );
 }
+
+#[test]
+fn check_incomplete_response_and_decode_errors() {
+    use algonaut_client::error::{
+        ClientError, RequestError as ClientRequestError,
+        RequestErrorDetails as ClientRequestErrorDetails,
+    };
+
+    let url = Some("http://example.com/v2/blocks/1".to_owned());
+    assert_eq!(
+        AlgonautError::IncompleteResponse { url: url.clone() },
+        ClientError::Request(ClientRequestError::new(
+            url.clone(),
+            ClientRequestErrorDetails::IncompleteResponse
+        ))
+        .into()
+    );
+    assert_eq!(
+        AlgonautError::ResponseTooLarge {
+            url: url.clone(),
+            limit: 1024
+        },
+        ClientError::Request(ClientRequestError::new(
+            url.clone(),
+            ClientRequestErrorDetails::ResponseTooLarge { limit: 1024 }
+        ))
+        .into()
+    );
+    assert_eq!(
+        AlgonautError::Decode {
+            url: url.clone(),
+            description: "invalid json".to_owned(),
+            path: Some("transactions[0].fee".to_owned()),
+            body: None,
+        },
+        ClientError::Request(ClientRequestError::new(
+            url,
+            ClientRequestErrorDetails::Decode {
+                description: "invalid json".to_owned(),
+                path: Some("transactions[0].fee".to_owned()),
+                body: None,
+            }
+        ))
+        .into()
+    );
+}
+
+#[test]
+fn check_response_meta() {
+    use algonaut_client::error::{
+        ClientError, RequestError as ClientRequestError,
+        RequestErrorDetails as ClientRequestErrorDetails,
+    };
+
+    let meta = ResponseMeta {
+        status: 429,
+        rate_limit_remaining: Some(0),
+        rate_limit_reset: None,
+        retry_after: Some(std::time::Duration::from_secs(30)),
+        request_id: None,
+    };
+    let error: AlgonautError = ClientError::Request(
+        ClientRequestError::new(
+            None,
+            ClientRequestErrorDetails::Http {
+                status: 429,
+                message: "".to_owned(),
+            },
+        )
+        .with_meta(meta.clone()),
+    )
+    .into();
+    assert_eq!(Some(&meta), error.response_meta());
+    assert_eq!(None, AlgonautError::UnitializedToken.response_meta());
+}
+
+#[test]
+fn check_into_not_found() {
+    let not_found_error = AlgonautError::Request(RequestError::new(
+        None,
+        RequestErrorDetails::Http {
+            status: 404,
+            message: "no transaction found for transaction id: A".to_owned(),
+        },
+    ));
+    let not_found = not_found_error.into_not_found("transaction", "A");
+    assert_eq!(
+        AlgonautError::NotFound {
+            resource: "transaction",
+            id: "A".to_owned(),
+            message: "no transaction found for transaction id: A".to_owned()
+        },
+        not_found
+    );
+    assert!(not_found.is_404());
+
+    let unrelated_error = AlgonautError::UnitializedToken;
+    assert_eq!(
+        unrelated_error.clone(),
+        unrelated_error.into_not_found("transaction", "A")
+    );
+}
+
+#[test]
+fn check_into_unauthorized() {
+    let unauthorized_error = AlgonautError::Request(RequestError::new(
+        None,
+        RequestErrorDetails::Http {
+            status: 401,
+            message: "Invalid API Token".to_owned(),
+        },
+    ));
+    assert_eq!(
+        AlgonautError::Unauthorized {
+            token: "admin",
+            message: "Invalid API Token".to_owned()
+        },
+        unauthorized_error.into_unauthorized("admin")
+    );
+    assert_eq!(
+        AlgonautError::UnitializedToken,
+        AlgonautError::UnitializedToken.into_unauthorized("admin")
+    );
+}
+
+#[test]
+fn check_into_transaction_rejected() {
+    use algonaut_core::MicroAlgos;
+
+    let message = "TransactionPool.Remember: transaction A: overspend (account B, data {_struct:{} Status:Offline MicroAlgos:{Raw:1000}}, tried to spend {2000})";
+    let rejected_error = AlgonautError::Request(RequestError::new(
+        None,
+        RequestErrorDetails::Http {
+            status: 400,
+            message: message.to_owned(),
+        },
+    ));
+    assert_eq!(
+        AlgonautError::TransactionRejected {
+            error: AlgodSemanticError::Overspend {
+                needed: MicroAlgos(2000),
+                available: MicroAlgos(1000)
+            },
+            message: message.to_owned()
+        },
+        rejected_error.into_transaction_rejected()
+    );
+    assert_eq!(
+        AlgonautError::UnitializedToken,
+        AlgonautError::UnitializedToken.into_transaction_rejected()
+    );
+}
+
+#[test]
+fn check_into_round_unavailable() {
+    let http_error = |status, message: &str| {
+        AlgonautError::Request(RequestError::new(
+            None,
+            RequestErrorDetails::Http {
+                status,
+                message: message.to_owned(),
+            },
+        ))
+    };
+    assert_eq!(
+        AlgonautError::RoundUnavailable {
+            round: Round(5),
+            message: "error while rewinding account: missing transactions".to_owned()
+        },
+        http_error(500, "error while rewinding account: missing transactions")
+            .into_round_unavailable(Round(5))
+    );
+    assert_eq!(
+        AlgonautError::RoundUnavailable {
+            round: Round(5),
+            message: "rewinding account is no longer supported".to_owned()
+        },
+        http_error(400, "rewinding account is no longer supported")
+            .into_round_unavailable(Round(5))
+    );
+    assert_eq!(
+        http_error(500, "boom"),
+        http_error(500, "boom").into_round_unavailable(Round(5))
+    );
+}
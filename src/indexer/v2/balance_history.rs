@@ -0,0 +1,155 @@
+use algonaut_core::{Address, MicroAlgos, Round};
+use algonaut_model::indexer::v2::Transaction;
+
+use crate::error::AlgonautError;
+
+/// Reconstructs the balance history of `address` in the range `from..=to`, walking backward from
+/// `balance`, the balance at the end of `current_round`.
+///
+/// `txns` must contain all the transactions of the account confirmed after `from`, up to `current_round`.
+///
+/// The first entry is the balance at the end of `from`, followed by the balance at the end of each
+/// round in the range in which the balance changed.
+pub(crate) fn fold_balance_history(
+    address: &Address,
+    balance: MicroAlgos,
+    current_round: Round,
+    mut txns: Vec<Transaction>,
+    from: Round,
+    to: Round,
+) -> Result<Vec<(Round, MicroAlgos)>, AlgonautError> {
+    txns.sort_by_key(|t| {
+        (
+            t.confirmed_round.map(|r| r.0).unwrap_or(0),
+            t.intra_round_offset.unwrap_or(0),
+        )
+    });
+
+    let to = to.0.min(current_round.0);
+    let mut balance = balance.0 as i128;
+    let mut history = vec![];
+    for txn in txns.iter().rev() {
+        let round = txn.confirmed_round.map(|r| r.0).unwrap_or(0);
+        if round <= from.0 {
+            break;
+        }
+        if round <= to && history.last().map(|(r, _): &(Round, i128)| r.0) != Some(round) {
+            history.push((Round(round), balance));
+        }
+        balance -= txn.delta_for(address).algos;
+        if balance < 0 {
+            return Err(AlgonautError::Internal(format!(
+                "Inconsistent balance history: negative balance before transaction {}",
+                txn.id
+            )));
+        }
+    }
+    history.push((from, balance));
+
+    Ok(history
+        .into_iter()
+        .rev()
+        .map(|(round, balance)| (round, MicroAlgos(balance as u64)))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const ACCOUNT: &str = "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU";
+    const OTHER: &str = "4MYUHDWHWXAKA5KA7U5PEN646VYUANBFXVJNONBK3TIMHEMWMD4UBOJBI4";
+
+    fn payment(
+        round: u64,
+        sender: &str,
+        receiver: &str,
+        amount: u64,
+        close_to: Option<(&str, u64)>,
+    ) -> Transaction {
+        let mut payment = json!({ "amount": amount, "receiver": receiver });
+        if let Some((close_to, close_amount)) = close_to {
+            payment["close-remainder-to"] = close_to.into();
+            payment["close-amount"] = close_amount.into();
+        }
+        serde_json::from_value(json!({
+            "confirmed-round": round,
+            "fee": 1000,
+            "first-valid": round - 1,
+            "genesis-hash": "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=",
+            "id": format!("TX{}", round),
+            "last-valid": round + 999,
+            "payment-transaction": payment,
+            "sender": sender,
+            "tx-type": "pay",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_balance_history_with_close_out() {
+        let address: Address = ACCOUNT.parse().unwrap();
+        let txns = vec![
+            // closes the account in round 9
+            payment(9, ACCOUNT, OTHER, 0, Some((OTHER, 9_998_000))),
+            payment(7, ACCOUNT, OTHER, 1_000_000, None),
+            payment(5, OTHER, ACCOUNT, 10_000_000, None),
+        ];
+
+        let history = fold_balance_history(
+            &address,
+            MicroAlgos(0),
+            Round(12),
+            txns,
+            Round(4),
+            Round(10),
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![
+                (Round(4), MicroAlgos(1_000_000)),
+                (Round(5), MicroAlgos(11_000_000)),
+                (Round(7), MicroAlgos(9_999_000)),
+                (Round(9), MicroAlgos(0)),
+            ],
+            history
+        );
+    }
+
+    #[test]
+    fn test_balance_history_ignores_rounds_after_range() {
+        let address: Address = ACCOUNT.parse().unwrap();
+        let txns = vec![
+            payment(9, OTHER, ACCOUNT, 2_000_000, None),
+            payment(5, OTHER, ACCOUNT, 1_000_000, None),
+        ];
+
+        let history = fold_balance_history(
+            &address,
+            MicroAlgos(3_000_000),
+            Round(12),
+            txns,
+            Round(4),
+            Round(8),
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![(Round(4), MicroAlgos(0)), (Round(5), MicroAlgos(1_000_000))],
+            history
+        );
+    }
+
+    #[test]
+    fn test_balance_history_inconsistent_anchor() {
+        let address: Address = ACCOUNT.parse().unwrap();
+        let txns = vec![payment(5, OTHER, ACCOUNT, 1_000_000, None)];
+
+        let history =
+            fold_balance_history(&address, MicroAlgos(0), Round(12), txns, Round(4), Round(8));
+
+        assert!(history.is_err());
+    }
+}
@@ -0,0 +1,328 @@
+use algonaut_client::{ClientConfig, Headers, ResponseFormat};
+use algonaut_core::{Address, MicroAlgos, Network, Round, SuggestedTransactionParams};
+use algonaut_crypto::HashDigest;
+use algonaut_model::indexer::v2::borrowed::TransactionResponseRef;
+use algonaut_model::indexer::v2::{
+    AccountInfoResponse, AccountResponse, AccountTransactionResponse, ApplicationInfoResponse,
+    ApplicationResponse, AssetResponse, AssetTransactionResponse, AssetsInfoResponse,
+    BalancesResponse, Block, HealthCheck, MiniApplicationInfoResponse, QueryAccount,
+    QueryAccountInfo, QueryAccountTransaction, QueryApplicationInfo, QueryApplications,
+    QueryAssetTransaction, QueryAssets, QueryAssetsInfo, QueryBalances, QueryTransaction,
+    Transaction, TransactionInfoResponse, TransactionResponse,
+};
+use chrono::{DateTime, Utc};
+use std::future::Future;
+use std::sync::Arc;
+use tokio::runtime::{Builder, Runtime};
+
+use super::{BucketSize, Indexer};
+use crate::error::AlgonautError;
+
+/// A blocking wrapper of [Indexer], for code without an async runtime.
+///
+/// The wrapper builds a single current-thread Tokio runtime when it's created, shared with its
+/// clones, and blocks on it in each call: the HTTP connections are reused across calls.
+/// See the methods of [Indexer] for the documentation of each query.
+///
+/// The calls must not be made from an async context, e.g. in a future run by a Tokio runtime:
+/// blocking on a runtime from within another one panics. Use [Indexer] there.
+///
+/// The streams and the NDJSON export of [Indexer] aren't wrapped: [indexer](Self::indexer) gives
+/// access to them.
+#[derive(Debug, Clone)]
+pub struct BlockingIndexer {
+    indexer: Indexer,
+    runtime: Arc<Runtime>,
+}
+
+impl BlockingIndexer {
+    /// Build a blocking v2 client for Algorand's indexer.
+    ///
+    /// Returns an error if the url has an invalid format, or if the runtime can't be created.
+    pub fn new(url: &str) -> Result<BlockingIndexer, AlgonautError> {
+        Self::from_indexer(Indexer::new(url)?)
+    }
+
+    /// Build a blocking v2 client for Algorand's indexer, see [Indexer::with_headers].
+    ///
+    /// Returns an error if the url or the headers have an invalid format, or if the runtime can't be created.
+    pub fn with_headers(url: &str, headers: Headers) -> Result<BlockingIndexer, AlgonautError> {
+        Self::from_indexer(Indexer::with_headers(url, headers)?)
+    }
+
+    /// Build a blocking v2 client for Algorand's indexer, see [Indexer::with_config].
+    ///
+    /// Returns an error if the url or the headers have an invalid format, or if the runtime can't be created.
+    pub fn with_config(
+        url: &str,
+        headers: Headers,
+        config: &ClientConfig,
+    ) -> Result<BlockingIndexer, AlgonautError> {
+        Self::from_indexer(Indexer::with_config(url, headers, config)?)
+    }
+
+    /// Wraps an indexer, e.g. one with a [min round guard](Indexer::with_min_round_guard).
+    ///
+    /// Returns an error if the runtime can't be created.
+    pub fn from_indexer(indexer: Indexer) -> Result<BlockingIndexer, AlgonautError> {
+        let runtime = Builder::new_current_thread().enable_all().build()?;
+        Ok(BlockingIndexer {
+            indexer,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    /// The wrapped indexer.
+    pub fn indexer(&self) -> &Indexer {
+        &self.indexer
+    }
+
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    pub fn health(&self) -> Result<(), AlgonautError> {
+        self.block_on(self.indexer.health())
+    }
+
+    pub fn health_check(&self) -> Result<HealthCheck<serde_json::Value>, AlgonautError> {
+        self.block_on(self.indexer.health_check())
+    }
+
+    pub fn network(&self) -> Result<Network, AlgonautError> {
+        self.block_on(self.indexer.network())
+    }
+
+    pub fn suggested_params(&self) -> Result<SuggestedTransactionParams, AlgonautError> {
+        self.block_on(self.indexer.suggested_params())
+    }
+
+    pub fn accounts(&self, query: &QueryAccount) -> Result<AccountResponse, AlgonautError> {
+        self.block_on(self.indexer.accounts(query))
+    }
+
+    pub fn account_info(
+        &self,
+        address: &Address,
+        query: &QueryAccountInfo,
+    ) -> Result<AccountInfoResponse, AlgonautError> {
+        self.block_on(self.indexer.account_info(address, query))
+    }
+
+    pub fn account_info_opt(
+        &self,
+        address: &Address,
+        query: &QueryAccountInfo,
+    ) -> Result<Option<AccountInfoResponse>, AlgonautError> {
+        self.block_on(self.indexer.account_info_opt(address, query))
+    }
+
+    pub fn account_info_at_rounds(
+        &self,
+        address: &Address,
+        rounds: &[Round],
+        concurrency: usize,
+    ) -> Vec<(Round, Result<AccountInfoResponse, AlgonautError>)> {
+        self.block_on(
+            self.indexer
+                .account_info_at_rounds(address, rounds, concurrency),
+        )
+    }
+
+    pub fn account_transactions(
+        &self,
+        address: &Address,
+        query: &QueryAccountTransaction,
+    ) -> Result<AccountTransactionResponse, AlgonautError> {
+        self.block_on(self.indexer.account_transactions(address, query))
+    }
+
+    pub fn balance_history(
+        &self,
+        address: &Address,
+        from: Round,
+        to: Round,
+    ) -> Result<Vec<(Round, MicroAlgos)>, AlgonautError> {
+        self.block_on(self.indexer.balance_history(address, from, to))
+    }
+
+    pub fn applications(
+        &self,
+        query: &QueryApplications,
+    ) -> Result<ApplicationResponse, AlgonautError> {
+        self.block_on(self.indexer.applications(query))
+    }
+
+    pub fn application_info(
+        &self,
+        id: u64,
+        query: &QueryApplicationInfo,
+    ) -> Result<ApplicationInfoResponse, AlgonautError> {
+        self.block_on(self.indexer.application_info(id, query))
+    }
+
+    pub fn application_info_opt(
+        &self,
+        id: u64,
+        query: &QueryApplicationInfo,
+    ) -> Result<Option<ApplicationInfoResponse>, AlgonautError> {
+        self.block_on(self.indexer.application_info_opt(id, query))
+    }
+
+    pub fn application_params_only(
+        &self,
+        id: u64,
+        query: &QueryApplicationInfo,
+    ) -> Result<MiniApplicationInfoResponse, AlgonautError> {
+        self.block_on(self.indexer.application_params_only(id, query))
+    }
+
+    pub fn assets(&self, query: &QueryAssets) -> Result<AssetResponse, AlgonautError> {
+        self.block_on(self.indexer.assets(query))
+    }
+
+    pub fn assets_info(
+        &self,
+        id: u64,
+        query: &QueryAssetsInfo,
+    ) -> Result<AssetsInfoResponse, AlgonautError> {
+        self.block_on(self.indexer.assets_info(id, query))
+    }
+
+    pub fn assets_info_opt(
+        &self,
+        id: u64,
+        query: &QueryAssetsInfo,
+    ) -> Result<Option<AssetsInfoResponse>, AlgonautError> {
+        self.block_on(self.indexer.assets_info_opt(id, query))
+    }
+
+    pub fn asset_balances(
+        &self,
+        id: u64,
+        query: &QueryBalances,
+    ) -> Result<BalancesResponse, AlgonautError> {
+        self.block_on(self.indexer.asset_balances(id, query))
+    }
+
+    pub fn is_asset_distributed(&self, id: u64) -> Result<bool, AlgonautError> {
+        self.block_on(self.indexer.is_asset_distributed(id))
+    }
+
+    pub fn asset_holder_count(&self, id: u64, include_zero: bool) -> Result<u64, AlgonautError> {
+        self.block_on(self.indexer.asset_holder_count(id, include_zero))
+    }
+
+    pub fn asset_transactions(
+        &self,
+        id: u64,
+        query: &QueryAssetTransaction,
+    ) -> Result<AssetTransactionResponse, AlgonautError> {
+        self.block_on(self.indexer.asset_transactions(id, query))
+    }
+
+    pub fn block(&self, round: Round) -> Result<Block, AlgonautError> {
+        self.block_on(self.indexer.block(round))
+    }
+
+    pub fn round_time(&self, round: Round) -> Result<DateTime<Utc>, AlgonautError> {
+        self.block_on(self.indexer.round_time(round))
+    }
+
+    pub fn block_with_format(
+        &self,
+        round: Round,
+        format: ResponseFormat,
+    ) -> Result<Block, AlgonautError> {
+        self.block_on(self.indexer.block_with_format(round, format))
+    }
+
+    pub fn transactions(
+        &self,
+        query: &QueryTransaction,
+    ) -> Result<TransactionResponse, AlgonautError> {
+        self.block_on(self.indexer.transactions(query))
+    }
+
+    pub fn transactions_borrowed<'b>(
+        &self,
+        query: &QueryTransaction,
+        buf: &'b mut String,
+    ) -> Result<TransactionResponseRef<'b>, AlgonautError> {
+        self.block_on(self.indexer.transactions_borrowed(query, buf))
+    }
+
+    pub fn transaction_volume(
+        &self,
+        query: &QueryTransaction,
+        bucket: BucketSize,
+        from: Round,
+        to: Round,
+    ) -> Result<Vec<(DateTime<Utc>, u64)>, AlgonautError> {
+        self.block_on(self.indexer.transaction_volume(query, bucket, from, to))
+    }
+
+    pub fn group_transactions(
+        &self,
+        group_id: &HashDigest,
+        round_hint: Option<Round>,
+    ) -> Result<Vec<Transaction>, AlgonautError> {
+        self.block_on(self.indexer.group_transactions(group_id, round_hint))
+    }
+
+    pub fn transaction_info(&self, id: &str) -> Result<TransactionInfoResponse, AlgonautError> {
+        self.block_on(self.indexer.transaction_info(id))
+    }
+
+    pub fn transaction_info_opt(
+        &self,
+        id: &str,
+    ) -> Result<Option<TransactionInfoResponse>, AlgonautError> {
+        self.block_on(self.indexer.transaction_info_opt(id))
+    }
+
+    pub fn transaction_info_batch(
+        &self,
+        ids: &[&str],
+        concurrency: usize,
+    ) -> Vec<(String, Result<TransactionInfoResponse, AlgonautError>)> {
+        self.block_on(self.indexer.transaction_info_batch(ids, concurrency))
+    }
+
+    pub fn transactions_by_ids(
+        &self,
+        ids: &[&str],
+        max_concurrency: usize,
+    ) -> Result<Vec<Result<TransactionInfoResponse, AlgonautError>>, AlgonautError> {
+        self.block_on(self.indexer.transactions_by_ids(ids, max_concurrency))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use algonaut_client::test_server::{TestResponse, TestServer, UNREACHABLE_URL};
+
+    #[test]
+    fn test_calls_reuse_the_runtime_and_connection() {
+        let server = TestServer::serve(vec![TestResponse::json(""); 3]);
+        let indexer = BlockingIndexer::new(server.url()).unwrap();
+        indexer.health().unwrap();
+        indexer.health().unwrap();
+        indexer.clone().health().unwrap();
+
+        // the connection is reused only if the runtime is
+        let connections: Vec<usize> = server
+            .received()
+            .iter()
+            .map(|request| request.connection)
+            .collect();
+        assert_eq!(vec![0, 0, 0], connections);
+    }
+
+    #[test]
+    fn test_request_error() {
+        let indexer = BlockingIndexer::new(UNREACHABLE_URL).unwrap();
+        assert!(indexer.health().is_err());
+    }
+}
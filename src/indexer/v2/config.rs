@@ -0,0 +1,77 @@
+use algonaut_client::ClientConfig;
+use algonaut_core::Round;
+use std::time::Duration;
+
+use crate::provider::NodePreset;
+
+/// The configuration of an [Indexer](super::Indexer): the node to query ([NodePreset]), the
+/// settings of the HTTP client ([ClientConfig]) and the
+/// [minimum round guard](super::Indexer::with_min_round_guard).
+///
+/// Build an indexer from it with [Indexer::from_config](super::Indexer::from_config). The
+/// [effective configuration](super::Indexer::config) of an indexer, once the defaults (e.g. of
+/// [from_env](super::Indexer::from_env)) are applied, is one too, e.g. to log it or to check it in
+/// tests.
+///
+/// The values of the sensitive headers (e.g. the API token) are redacted from its debug output.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexerConfig {
+    node: NodePreset,
+    client: ClientConfig,
+    min_round_guard: Option<Round>,
+}
+
+impl IndexerConfig {
+    /// The configuration of the indexer `node`, queried with the `client` settings, without
+    /// minimum round guard.
+    pub fn new(node: NodePreset, client: ClientConfig) -> IndexerConfig {
+        IndexerConfig {
+            node,
+            client,
+            min_round_guard: None,
+        }
+    }
+
+    /// Sets the minimum round guard, see [Indexer::with_min_round_guard](super::Indexer::with_min_round_guard).
+    pub fn with_min_round_guard(mut self, min_round_guard: Option<Round>) -> IndexerConfig {
+        self.min_round_guard = min_round_guard;
+        self
+    }
+
+    /// The url and the headers of the indexer.
+    pub fn node(&self) -> &NodePreset {
+        &self.node
+    }
+
+    /// The base url of the indexer, as resolved (e.g. `http://localhost:8980/` for
+    /// `http://localhost:8980`) in the effective configuration.
+    pub fn url(&self) -> &str {
+        self.node.url()
+    }
+
+    /// The headers sent with each request, e.g. the API token.
+    pub fn headers(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.node.headers().into_iter()
+    }
+
+    /// The settings of the HTTP client.
+    pub fn client(&self) -> &ClientConfig {
+        &self.client
+    }
+
+    /// The timeout of each request, see [ClientConfig::timeout].
+    pub fn timeout(&self) -> Option<Duration> {
+        self.client.timeout
+    }
+
+    /// Whether the requests failing with a transient error are retried, see
+    /// [ClientConfig::max_retries].
+    pub fn retries_enabled(&self) -> bool {
+        self.client.max_retries > 0
+    }
+
+    /// The minimum round guard, if any.
+    pub fn min_round_guard(&self) -> Option<Round> {
+        self.min_round_guard
+    }
+}
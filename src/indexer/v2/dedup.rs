@@ -0,0 +1,161 @@
+use algonaut_core::Round;
+use algonaut_model::indexer::v2::Transaction;
+use futures_util::{ready, Stream};
+use std::collections::{BTreeMap, HashSet};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use crate::error::AlgonautError;
+
+/// Adapters of the streams of indexer transactions, e.g. of
+/// [Indexer::transactions_recent](super::Indexer::transactions_recent).
+pub trait TransactionStreamExt: Stream<Item = Result<Transaction, AlgonautError>> + Sized {
+    /// Drops the transactions whose id was already yielded, e.g. when chaining the streams of
+    /// overlapping queries, or resuming a scan from the round it stopped at.
+    ///
+    /// Only the ids of the transactions confirmed within `window_rounds` rounds of the last
+    /// transaction are kept, so the memory is bounded by the transactions of that many rounds, in
+    /// either direction of the scan. A window of 0 catches the duplicates of the same round, the
+    /// common overlap at the boundary of two scans; a wider window catches duplicates further apart
+    /// but keeps the ids of all the transactions of its rounds, which can be many on a busy
+    /// history. Duplicates further apart than the window are yielded again.
+    ///
+    /// Transactions without id or confirmed round are always yielded, and errors are passed
+    /// through.
+    fn dedup_by_id(self, window_rounds: u64) -> DedupById<Self> {
+        DedupById {
+            stream: Box::pin(self),
+            seen: SeenIds {
+                window_rounds,
+                ids: BTreeMap::new(),
+            },
+        }
+    }
+}
+
+impl<S: Stream<Item = Result<Transaction, AlgonautError>>> TransactionStreamExt for S {}
+
+/// The stream of [TransactionStreamExt::dedup_by_id].
+pub struct DedupById<S> {
+    stream: Pin<Box<S>>,
+    seen: SeenIds,
+}
+
+impl<S: Stream<Item = Result<Transaction, AlgonautError>>> Stream for DedupById<S> {
+    type Item = Result<Transaction, AlgonautError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match ready!(self.stream.as_mut().poll_next(cx)) {
+                Some(Ok(transaction)) if !self.seen.insert(&transaction) => continue,
+                item => return Poll::Ready(item),
+            }
+        }
+    }
+}
+
+/// The ids of the transactions seen, by confirmed round.
+struct SeenIds {
+    window_rounds: u64,
+    ids: BTreeMap<Round, HashSet<String>>,
+}
+
+impl SeenIds {
+    /// Records the id of `transaction`, forgetting the rounds out of the window around its round.
+    /// Returns whether the id is new.
+    fn insert(&mut self, transaction: &Transaction) -> bool {
+        let round = match transaction.confirmed_round {
+            Some(round) if !transaction.id.is_empty() => round,
+            _ => return true,
+        };
+        self.ids = self
+            .ids
+            .split_off(&Round(round.0.saturating_sub(self.window_rounds)));
+        let above = self
+            .window_rounds
+            .checked_add(1)
+            .and_then(|rounds| round.0.checked_add(rounds));
+        if let Some(above) = above {
+            self.ids.split_off(&Round(above));
+        }
+        self.ids
+            .entry(round)
+            .or_default()
+            .insert(transaction.id.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::{stream, StreamExt};
+
+    fn transaction(round: u64, id: &str) -> Transaction {
+        serde_json::from_str(&format!(
+            r#"{{"confirmed-round":{},"fee":1000,"first-valid":1,"genesis-hash":"SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=","id":"{}","intra-round-offset":0,"last-valid":1000,
+            "sender":"47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU","tx-type":"pay"}}"#,
+            round, id
+        ))
+        .unwrap()
+    }
+
+    async fn dedup_ids(transactions: Vec<Transaction>, window_rounds: u64) -> Vec<String> {
+        stream::iter(transactions.into_iter().map(Ok))
+            .dedup_by_id(window_rounds)
+            .map(|transaction| transaction.unwrap().id)
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_dedup_overlap_at_boundary() {
+        // Two scans, both including round 20.
+        let transactions = vec![
+            transaction(10, "A"),
+            transaction(20, "B"),
+            transaction(20, "C"),
+            transaction(20, "B"),
+            transaction(20, "C"),
+            transaction(30, "D"),
+        ];
+
+        assert_eq!(vec!["A", "B", "C", "D"], dedup_ids(transactions, 0).await);
+    }
+
+    #[tokio::test]
+    async fn test_dedup_within_window() {
+        // Newest-first, then the same rounds again.
+        let transactions = vec![
+            transaction(30, "C"),
+            transaction(20, "B"),
+            transaction(10, "A"),
+            transaction(30, "C"),
+            transaction(20, "B"),
+        ];
+
+        assert_eq!(
+            vec!["C", "B", "A", "C"],
+            dedup_ids(transactions.clone(), 10).await
+        );
+        assert_eq!(vec!["C", "B", "A"], dedup_ids(transactions, 20).await);
+    }
+
+    #[tokio::test]
+    async fn test_dedup_passes_errors_and_transactions_without_round() {
+        let mut pending = transaction(10, "A");
+        pending.confirmed_round = None;
+        let items = vec![
+            Ok(pending.clone()),
+            Ok(pending),
+            Err(AlgonautError::Internal("page".to_owned())),
+            Ok(transaction(10, "A")),
+            Ok(transaction(10, "A")),
+        ];
+
+        let results: Vec<_> = stream::iter(items).dedup_by_id(0).collect().await;
+
+        assert_eq!(4, results.len());
+        assert!(results[2].is_err());
+        assert_eq!("A", results[3].as_ref().unwrap().id);
+    }
+}
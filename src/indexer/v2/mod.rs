@@ -1,18 +1,70 @@
-use algonaut_client::{indexer::v2::Client, Headers};
-use algonaut_core::{Address, Round};
+use algonaut_client::{indexer::v2::Client, ClientConfig, Headers, ResponseFormat};
+use algonaut_core::{
+    Address, MicroAlgos, Network, Round, RoundRange, SuggestedTransactionParams, TxId,
+    MAX_VALIDITY_WINDOW,
+};
+use algonaut_crypto::HashDigest;
+use algonaut_model::indexer::v2::borrowed::TransactionResponseRef;
 use algonaut_model::indexer::v2::{
-    AccountInfoResponse, AccountResponse, AccountTransactionResponse, ApplicationInfoResponse,
-    ApplicationResponse, AssetResponse, AssetTransactionResponse, AssetsInfoResponse,
-    BalancesResponse, Block, QueryAccount, QueryAccountInfo, QueryAccountTransaction,
+    AccountInfoResponse, AccountResponse, AccountTransactionResponse, Application,
+    ApplicationInfoResponse, ApplicationResponse, AssetResponse, AssetTransactionResponse,
+    AssetsInfoResponse, BalancesResponse, Block, HealthCheck, MiniApplicationInfoResponse,
+    MiniAssetHolding, QueryAccount, QueryAccountInfo, QueryAccountTransaction,
     QueryApplicationInfo, QueryApplications, QueryAssetTransaction, QueryAssets, QueryAssetsInfo,
-    QueryBalances, QueryTransaction, TransactionInfoResponse, TransactionResponse,
+    QueryBalances, QueryTransaction, Transaction, TransactionInfoResponse, TransactionResponse,
 };
 
-use crate::error::AlgonautError;
+use chrono::{DateTime, Utc};
+use futures_util::future::{self, Either};
+use futures_util::{pin_mut, stream, AsyncWrite, AsyncWriteExt, Stream, TryStreamExt};
+use serde::Serialize;
+use std::future::Future;
+use std::sync::Arc;
+
+use crate::batch;
+use crate::error::{AlgonautError, ResponseMeta};
+use crate::provider::NodePreset;
+use recent::NextWindow;
+use round_time::RoundTimes;
+use seen_round::SeenRound;
+use volume::VolumeBuckets;
+
+mod balance_history;
+#[cfg(all(feature = "blocking", not(target_arch = "wasm32")))]
+mod blocking;
+mod config;
+mod dedup;
+mod multi;
+mod paginate;
+mod recent;
+mod round_time;
+mod seen_round;
+mod service;
+mod volume;
+
+#[cfg(all(feature = "blocking", not(target_arch = "wasm32")))]
+pub use blocking::BlockingIndexer;
+pub use config::IndexerConfig;
+pub use dedup::{DedupById, TransactionStreamExt};
+pub use multi::{MultiIndexer, RoutingStrategy};
+pub use service::IndexerService;
+pub use volume::BucketSize;
 
-#[derive(Debug)]
+pub(crate) use paginate::{pages, paginate};
+
+/// Client of the indexer.
+///
+/// The queries are checked before being sent: a query with an empty round range, a zero id or a
+/// malformed address (see the `validate` methods of the queries, e.g. [QueryTransaction::validate])
+/// fails with [AlgonautError::BadQuery], naming the field at fault.
+#[derive(Debug, Clone)]
 pub struct Indexer {
     pub(super) client: Client,
+    /// Latest round the indexer reported as processed, cached briefly for the guard.
+    seen_round: Arc<SeenRound>,
+    /// Times of the rounds of the blocks fetched so far, shared with the clones.
+    round_times: Arc<RoundTimes>,
+    config: Arc<IndexerConfig>,
 }
 
 impl Indexer {
@@ -28,28 +80,257 @@ impl Indexer {
     ///
     /// Returns an error if the url or the headers have an invalid format.
     pub fn with_headers(url: &str, headers: Headers) -> Result<Indexer, AlgonautError> {
+        Self::with_config(url, headers, &ClientConfig::default())
+    }
+
+    /// Build a v2 client for Algorand's indexer.
+    /// Use this initializer to tune the HTTP client, e.g. the connection pool of high-throughput services.
+    ///
+    /// Returns an error if the url or the headers have an invalid format.
+    pub fn with_config(
+        url: &str,
+        headers: Headers,
+        config: &ClientConfig,
+    ) -> Result<Indexer, AlgonautError> {
+        Self::from_config(IndexerConfig::new(
+            NodePreset::new(url, headers),
+            config.clone(),
+        ))
+    }
+
+    /// Build a v2 client for Algorand's indexer from its configuration, e.g. a [NodePreset] with
+    /// tuned [ClientConfig] and a [min round guard](Self::with_min_round_guard).
+    ///
+    /// Returns an error if the url or the headers have an invalid format.
+    pub fn from_config(config: IndexerConfig) -> Result<Indexer, AlgonautError> {
+        let node = config.node();
+        let client = Client::with_config(node.url(), node.headers(), config.client())?;
+        let config =
+            IndexerConfig::new(node.clone().with_url(client.url()), config.client().clone())
+                .with_min_round_guard(config.min_round_guard());
         Ok(Indexer {
-            client: Client::new(url, headers)?,
+            config: Arc::new(config),
+            client,
+            seen_round: Arc::new(SeenRound::default()),
+            round_times: Arc::new(RoundTimes::default()),
         })
     }
 
+    /// Build a v2 client for Algorand's indexer from environment variables, the `INDEXER_*`
+    /// variables of [EnvConfig](crate::config::EnvConfig):
+    ///
+    /// - `INDEXER_SERVER` (required): the url of the indexer.
+    /// - `INDEXER_PORT`: the port of the indexer, if not the default one of the scheme.
+    /// - `INDEXER_TOKEN`: the API token, if the indexer requires one.
+    /// - `INDEXER_TOKEN_HEADER`: the header of the token, `X-Indexer-API-Token` (the header of the
+    ///   indexer) by default, e.g. `X-API-Key` for third party services.
+    /// - `INDEXER_TIMEOUT_MS`: the timeout of each request in milliseconds (see
+    ///   [ClientConfig::timeout]), none by default.
+    ///
+    /// With `ALGOD_ENV=localnet`, the missing variables default to the indexer of a local sandbox.
+    ///
+    /// Returns [AlgonautError::MissingEnvVars] if `INDEXER_SERVER` is missing, and
+    /// [AlgonautError::BadEnvVar], naming the variable, if a variable is invalid.
+    pub fn from_env() -> Result<Indexer, AlgonautError> {
+        Self::from_vars(|name| std::env::var(name))
+    }
+
+    /// [from_env](Self::from_env), reading the variables with `var`.
+    fn from_vars(
+        var: impl Fn(&str) -> Result<String, std::env::VarError>,
+    ) -> Result<Indexer, AlgonautError> {
+        crate::config::indexer_from_vars(var)
+    }
+
+    /// Build a v2 client for the indexer of a local sandbox, see [NodePreset::sandbox_indexer].
+    pub fn sandbox() -> Result<Indexer, AlgonautError> {
+        NodePreset::sandbox_indexer().indexer()
+    }
+
+    /// Build a v2 client for the free indexer of AlgoNode, see [NodePreset::algonode_indexer].
+    pub fn with_algonode(network: &Network) -> Result<Indexer, AlgonautError> {
+        NodePreset::algonode_indexer(network)?.indexer()
+    }
+
+    /// Build a v2 client for the indexer of PureStake, see [NodePreset::purestake_indexer].
+    pub fn with_purestake(network: &Network, api_key: &str) -> Result<Indexer, AlgonautError> {
+        NodePreset::purestake_indexer(network, api_key)?.indexer()
+    }
+
+    /// Returns an indexer that, before answering data queries, checks that the indexer is caught up
+    /// to at least the given round, failing with [AlgonautError::IndexerBehind] otherwise.
+    ///
+    /// Use it to configure the client (`Indexer::new(url)?.with_min_round_guard(Some(round))`) or to
+    /// override the guard for a single call (`indexer.with_min_round_guard(None).accounts(&query)`).
+    /// The returned indexer shares the connection pool and the caches with this one.
+    pub fn with_min_round_guard(&self, min_round_guard: Option<Round>) -> Indexer {
+        let config = self.config().clone().with_min_round_guard(min_round_guard);
+        Indexer {
+            config: Arc::new(config),
+            ..self.clone()
+        }
+    }
+
+    /// The configured minimum round guard, if any.
+    pub fn min_round_guard(&self) -> Option<Round> {
+        self.config.min_round_guard()
+    }
+
+    /// The effective configuration of the client: its resolved url, headers, HTTP settings and
+    /// minimum round guard.
+    pub fn config(&self) -> &IndexerConfig {
+        &self.config
+    }
+
+    /// Status and rate limit headers of the latest response received by this client or its clones,
+    /// e.g. to throttle requests to a provider. Failed requests also carry them in the error
+    /// ([AlgonautError::response_meta]).
+    ///
+    /// With concurrent requests, the latest response may belong to any of them: see
+    /// [with_meta](Self::with_meta) for the meta of a call.
+    pub fn last_response_meta(&self) -> Option<ResponseMeta> {
+        self.client.last_response_meta()
+    }
+
+    /// Makes `call` with a clone of this client, returning its result with the status and rate
+    /// limit headers of the latest response received by the call, if it sent a request, e.g.
+    /// `indexer.with_meta(|indexer| async move { indexer.transactions(&query).await })`.
+    ///
+    /// Unlike [last_response_meta](Self::last_response_meta), the meta is the one of this call,
+    /// even with concurrent requests. Failed requests carry it in the error
+    /// ([AlgonautError::response_meta]).
+    pub async fn with_meta<T, F, Fut>(
+        &self,
+        call: F,
+    ) -> Result<(T, Option<ResponseMeta>), AlgonautError>
+    where
+        F: FnOnce(Indexer) -> Fut,
+        Fut: Future<Output = Result<T, AlgonautError>>,
+    {
+        let indexer = Indexer {
+            client: self.client.with_own_meta(),
+            ..self.clone()
+        };
+        let value = call(indexer.clone()).await?;
+        Ok((value, indexer.last_response_meta()))
+    }
+
     /// Returns Ok if healthy
     pub async fn health(&self) -> Result<(), AlgonautError> {
         Ok(self.client.health().await?)
     }
 
+    /// Returns the health status of the indexer, including the last round it processed.
+    pub async fn health_check(&self) -> Result<HealthCheck<serde_json::Value>, AlgonautError> {
+        let health = self.client.health_check().await?;
+        self.seen_round.set(health.round);
+        Ok(health)
+    }
+
+    /// Fails if the indexer didn't process yet the round set with [with_min_round_guard](Self::with_min_round_guard).
+    ///
+    /// The round of a health check made in the last 2 seconds is trusted, otherwise the indexer
+    /// is checked again.
+    async fn check_min_round_guard(&self) -> Result<(), AlgonautError> {
+        let required = match self.min_round_guard() {
+            Some(required) => required,
+            None => return Ok(()),
+        };
+        if self.seen_round.get() >= Some(required) {
+            return Ok(());
+        }
+        let current = self.health_check().await?.round;
+        if current.0 >= required.0 {
+            Ok(())
+        } else {
+            Err(AlgonautError::IndexerBehind { current, required })
+        }
+    }
+
+    /// Detects the network this indexer is serving, using the genesis hash of the genesis block.
+    ///
+    /// Useful to guard against accidentally querying the wrong network.
+    pub async fn network(&self) -> Result<Network, AlgonautError> {
+        let genesis_block = self.client.block(Round(0)).await?;
+        Ok(Network::from_genesis_hash(
+            &genesis_block.genesis_hash.into(),
+        ))
+    }
+
+    /// Best-effort parameters to build transactions, for tools with access only to an indexer.
+    ///
+    /// Reads the latest round processed by the indexer and the genesis info and consensus version of
+    /// its block. Transactions are valid in the 1000 rounds after the latest one, they pay the minimum
+    /// fee of the current protocol (1000 microAlgos) and no fee per byte.
+    ///
+    /// Algod's [suggested_transaction_params](crate::algod::v2::Algod::suggested_transaction_params) is
+    /// authoritative and should be preferred: the indexer may lag behind the network and doesn't know
+    /// the current fee per byte, so transactions built with these params may expire early or
+    /// underpay fees under congestion.
+    pub async fn suggested_params(&self) -> Result<SuggestedTransactionParams, AlgonautError> {
+        let round = self.health_check().await?.round;
+        let block = self.block(round).await?;
+        Ok(suggested_params_from_block(&block))
+    }
+
     /// Search for accounts.
     pub async fn accounts(&self, query: &QueryAccount) -> Result<AccountResponse, AlgonautError> {
+        query.validate()?;
+        self.check_min_round_guard().await?;
         Ok(self.client.accounts(query).await?)
     }
 
     /// Lookup account information.
+    ///
+    /// An account not found (404) fails with `AlgonautError::NotFound`: see
+    /// [account_info_opt](Self::account_info_opt) to get `None` instead.
     pub async fn account_info(
         &self,
         address: &Address,
         query: &QueryAccountInfo,
     ) -> Result<AccountInfoResponse, AlgonautError> {
-        Ok(self.client.account_info(address, query).await?)
+        self.check_min_round_guard().await?;
+        self.client
+            .account_info(address, query)
+            .await
+            .map_err(|e| AlgonautError::from(e).into_not_found("account", &address.to_string()))
+    }
+
+    /// [account_info](Self::account_info), returning `None` if the account isn't found, e.g. an
+    /// address that never received algos, or an account closed before the round of the query.
+    pub async fn account_info_opt(
+        &self,
+        address: &Address,
+        query: &QueryAccountInfo,
+    ) -> Result<Option<AccountInfoResponse>, AlgonautError> {
+        found(self.account_info(address, query).await)
+    }
+
+    /// Looks up the account at each of these rounds (e.g. to snapshot it for an audit), running up
+    /// to `concurrency` lookups at a time (at least one).
+    ///
+    /// Returns each round with the result of its lookup, in the order of the rounds. A round the
+    /// indexer can't rewind the account to (e.g. before the history it retains) fails with
+    /// `AlgonautError::RoundUnavailable`, and one at which the account doesn't exist with
+    /// `AlgonautError::NotFound`. Any error fails only the lookup of its round.
+    pub async fn account_info_at_rounds(
+        &self,
+        address: &Address,
+        rounds: &[Round],
+        concurrency: usize,
+    ) -> Vec<(Round, Result<AccountInfoResponse, AlgonautError>)> {
+        batch::lookup_each(rounds.iter().copied(), concurrency, |round| async move {
+            let query = QueryAccountInfo {
+                include_all: None,
+                round: Some(round),
+            };
+            let result = self
+                .account_info(address, &query)
+                .await
+                .map_err(|e| e.into_round_unavailable(round));
+            (round, result)
+        })
+        .await
     }
 
     /// Lookup account transactions.
@@ -58,28 +339,143 @@ impl Indexer {
         address: &Address,
         query: &QueryAccountTransaction,
     ) -> Result<AccountTransactionResponse, AlgonautError> {
+        query.validate()?;
+        self.check_min_round_guard().await?;
         Ok(self.client.account_transactions(address, query).await?)
     }
 
+    /// Reconstructs the algo balance history of an account in the rounds `from..=to`.
+    ///
+    /// The history is anchored to the current balance of the account: all the transactions of the
+    /// account confirmed after `from` are fetched and their balance deltas (amounts, fees, close-outs
+    /// and realized rewards) are undone, walking backward from the current round.
+    ///
+    /// The first entry is the balance at the end of `from`, followed by the balance at the end of each
+    /// round in the range in which a transaction of the account changed it.
+    ///
+    /// Limits:
+    /// - Pending rewards are not included: balances reflect only the rewards realized by transactions.
+    /// - The cost grows with the number of transactions of the account after `from`, not only in the range.
+    /// - Balance changes not recorded as transactions of the account (e.g. inner transactions issued by
+    ///   an application) are not accounted for, so the balance before them is assumed unchanged.
+    ///   If the reconstruction yields a negative balance an error is returned.
+    pub async fn balance_history(
+        &self,
+        address: &Address,
+        from: Round,
+        to: Round,
+    ) -> Result<Vec<(Round, MicroAlgos)>, AlgonautError> {
+        // include closed accounts, whose history can still be reconstructed
+        let query = QueryAccountInfo {
+            include_all: Some(true),
+            round: None,
+        };
+        let info = self.account_info(address, &query).await?;
+        let current_round = info.current_round;
+
+        // nothing to undo if `from` is the current round
+        let txns = if from >= current_round {
+            vec![]
+        } else {
+            let query = QueryAccountTransaction::default()
+                .with_round_range(RoundRange::new(from + 1, current_round));
+            paginate(query, move |query| async move {
+                self.account_transactions(address, &query).await
+            })
+            .try_collect()
+            .await?
+        };
+
+        balance_history::fold_balance_history(
+            address,
+            info.account.amount_without_pending_rewards,
+            current_round,
+            txns,
+            from,
+            to,
+        )
+    }
+
     /// Search for applications
     pub async fn applications(
         &self,
         query: &QueryApplications,
     ) -> Result<ApplicationResponse, AlgonautError> {
+        query.validate()?;
+        self.check_min_round_guard().await?;
         Ok(self.client.applications(query).await?)
     }
 
+    /// Stream the applications created by `creator`, fetching the pages of
+    /// [applications](Self::applications) as the stream is consumed.
+    ///
+    /// Applications are yielded in the order returned by the indexer (by id). Errors end the stream.
+    pub fn applications_by_creator(
+        &self,
+        creator: Address,
+    ) -> impl Stream<Item = Result<Application, AlgonautError>> + '_ {
+        let query = QueryApplications {
+            creator: Some(creator),
+            ..QueryApplications::default()
+        };
+        paginate(query, move |query| async move {
+            self.applications(&query).await
+        })
+    }
+
     /// Lookup application.
+    ///
+    /// An application not found (404) fails with `AlgonautError::NotFound`: see
+    /// [application_info_opt](Self::application_info_opt) to get `None` instead.
     pub async fn application_info(
         &self,
         id: u64,
         query: &QueryApplicationInfo,
     ) -> Result<ApplicationInfoResponse, AlgonautError> {
-        Ok(self.client.application_info(id, query).await?)
+        validate_path_id(id, "id")?;
+        self.check_min_round_guard().await?;
+        self.client
+            .application_info(id, query)
+            .await
+            .map_err(|e| AlgonautError::from(e).into_not_found("application", &id.to_string()))
+    }
+
+    /// [application_info](Self::application_info), returning `None` if the application isn't
+    /// found, e.g. a deleted application without `include_all`.
+    pub async fn application_info_opt(
+        &self,
+        id: u64,
+        query: &QueryApplicationInfo,
+    ) -> Result<Option<ApplicationInfoResponse>, AlgonautError> {
+        found(self.application_info(id, query).await)
+    }
+
+    /// [application_info](Self::application_info), without decoding the programs and the global
+    /// state of the application, e.g. to read the creator or the schemas of an application with a
+    /// large state.
+    ///
+    /// The lookup of the indexer has no parameter to leave them out of the response (unlike the
+    /// `exclude` of the account lookups), so they're still transferred: only the decoding and the
+    /// memory of the response are saved.
+    pub async fn application_params_only(
+        &self,
+        id: u64,
+        query: &QueryApplicationInfo,
+    ) -> Result<MiniApplicationInfoResponse, AlgonautError> {
+        validate_path_id(id, "id")?;
+        self.check_min_round_guard().await?;
+        self.client
+            .application_params_only(id, query)
+            .await
+            .map_err(|e| AlgonautError::from(e).into_not_found("application", &id.to_string()))
     }
 
     /// Search for assets.
+    ///
+    /// The asset id can't be combined with the other filters (see [QueryAssets::validate]).
     pub async fn assets(&self, query: &QueryAssets) -> Result<AssetResponse, AlgonautError> {
+        query.validate()?;
+        self.check_min_round_guard().await?;
         Ok(self.client.assets(query).await?)
     }
 
@@ -89,7 +485,22 @@ impl Indexer {
         id: u64,
         query: &QueryAssetsInfo,
     ) -> Result<AssetsInfoResponse, AlgonautError> {
-        Ok(self.client.assets_info(id, query).await?)
+        validate_path_id(id, "id")?;
+        self.check_min_round_guard().await?;
+        self.client
+            .assets_info(id, query)
+            .await
+            .map_err(|e| AlgonautError::from(e).into_not_found("asset", &id.to_string()))
+    }
+
+    /// [assets_info](Self::assets_info), returning `None` if the asset isn't found, e.g. a
+    /// destroyed asset without `include_all`.
+    pub async fn assets_info_opt(
+        &self,
+        id: u64,
+        query: &QueryAssetsInfo,
+    ) -> Result<Option<AssetsInfoResponse>, AlgonautError> {
+        found(self.assets_info(id, query).await)
     }
 
     /// Lookup the list of accounts who hold this asset.
@@ -98,21 +509,189 @@ impl Indexer {
         id: u64,
         query: &QueryBalances,
     ) -> Result<BalancesResponse, AlgonautError> {
+        validate_path_id(id, "id")?;
+        self.check_min_round_guard().await?;
         Ok(self.client.asset_balances(id, query).await?)
     }
 
+    /// Stream the holders of an asset with a balance greater than `min_amount`, fetching the
+    /// pages of [asset_balances](Self::asset_balances) as the stream is consumed.
+    ///
+    /// Holders are yielded in the order returned by the indexer (by address). Zero balances and
+    /// holdings deleted by opting out are excluded, and the frozen holdings unless
+    /// `include_frozen`: use [asset_balances](Self::asset_balances) to list them.
+    ///
+    /// The pages are fetched independently, so a holding changed while paginating may be reflected
+    /// in a later page or not at all. Errors end the stream.
+    pub fn asset_holders_above(
+        &self,
+        id: u64,
+        min_amount: u64,
+        include_frozen: bool,
+    ) -> impl Stream<Item = Result<MiniAssetHolding, AlgonautError>> + '_ {
+        let query = QueryBalances {
+            currency_greater_than: Some(min_amount),
+            ..QueryBalances::default()
+        };
+        paginate(query, move |query| async move {
+            self.asset_balances(id, &query).await
+        })
+        .try_filter(move |holding| {
+            future::ready(is_holder_above(holding, min_amount, include_frozen))
+        })
+    }
+
+    /// Whether any account other than the creator holds some of the asset, i.e. whether the
+    /// asset left the creator's account (e.g. to find unused assets, whose whole supply is with
+    /// the creator).
+    ///
+    /// Fetches the creator with [assets_info](Self::assets_info), then the pages of nonzero
+    /// [asset_balances](Self::asset_balances) until the first holder other than the creator.
+    /// Frozen holdings count as held, unless they're empty; holdings deleted by opting out don't.
+    pub async fn is_asset_distributed(&self, id: u64) -> Result<bool, AlgonautError> {
+        let creator = self
+            .assets_info(id, &QueryAssetsInfo::default())
+            .await?
+            .asset
+            .params
+            .creator
+            .to_string();
+        let query = QueryBalances {
+            currency_greater_than: Some(0),
+            ..QueryBalances::default()
+        };
+        let holders = paginate(query, move |query| async move {
+            self.asset_balances(id, &query).await
+        });
+        pin_mut!(holders);
+        while let Some(holding) = holders.try_next().await? {
+            if is_external_holder(&holding, &creator) {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Counts the accounts holding the asset, excluding the holdings deleted by opting out, and the
+    /// empty holdings unless `include_zero` (accounts opted in without a balance).
+    ///
+    /// The count is exact, not an estimate: the indexer doesn't return a total count, so all the
+    /// pages of [asset_balances](Self::asset_balances) are fetched, with the largest page size
+    /// (10000, the default maximum of the indexer, which reduces it to its own). Only one page is kept
+    /// in memory at a time. The pages are fetched independently, so a holding changed while
+    /// paginating may be counted twice or not at all.
+    pub async fn asset_holder_count(
+        &self,
+        id: u64,
+        include_zero: bool,
+    ) -> Result<u64, AlgonautError> {
+        let query = QueryBalances {
+            currency_greater_than: if include_zero { None } else { Some(0) },
+            limit: Some(MAX_BALANCES_PAGE_SIZE),
+            ..QueryBalances::default()
+        };
+        pages(query, move |query| async move {
+            self.asset_balances(id, &query).await
+        })
+        .try_fold(0, |count, balances| async move {
+            let held = balances
+                .iter()
+                .filter(|holding| holding.deleted != Some(true))
+                .count();
+            Ok(count + held as u64)
+        })
+        .await
+    }
+
     /// Lookup transactions for an asset.
     pub async fn asset_transactions(
         &self,
         id: u64,
         query: &QueryAssetTransaction,
     ) -> Result<AssetTransactionResponse, AlgonautError> {
+        validate_path_id(id, "id")?;
+        query.validate()?;
+        self.check_min_round_guard().await?;
         Ok(self.client.asset_transactions(id, query).await?)
     }
 
+    /// Stream the transactions of an asset involving `address`, e.g. the activity of an account
+    /// with one asset, fetching the pages of [asset_transactions](Self::asset_transactions) as the
+    /// stream is consumed.
+    ///
+    /// `query.address` is replaced by `address`: the other filters of `query` apply, e.g.
+    /// `address_role` or the round range. `query.next`, if set, is the page to start from.
+    ///
+    /// Transactions are yielded in the order returned by the indexer (oldest first). Errors end
+    /// the stream.
+    pub fn asset_transactions_for_account(
+        &self,
+        asset_id: u64,
+        address: &Address,
+        query: &QueryAssetTransaction,
+    ) -> impl Stream<Item = Result<Transaction, AlgonautError>> + '_ {
+        let query = QueryAssetTransaction {
+            address: Some(address.to_string()),
+            ..query.clone()
+        };
+        paginate(query, move |query| async move {
+            self.asset_transactions(asset_id, &query).await
+        })
+    }
+
     /// Lookup block.
     pub async fn block(&self, round: Round) -> Result<Block, AlgonautError> {
-        Ok(self.client.block(round).await?)
+        self.check_min_round_guard().await?;
+        let block = self.client.block(round).await?;
+        self.round_times.insert(block.round, block.timestamp);
+        Ok(block)
+    }
+
+    /// Lookup a block, requesting the response in this format instead of the format of the
+    /// client config, e.g. MessagePack for big blocks.
+    pub async fn block_with_format(
+        &self,
+        round: Round,
+        format: ResponseFormat,
+    ) -> Result<Block, AlgonautError> {
+        self.check_min_round_guard().await?;
+        let block = self.client.block_with_format(round, format).await?;
+        self.round_times.insert(block.round, block.timestamp);
+        Ok(block)
+    }
+
+    /// The UTC time of a round, i.e. the timestamp of its block (at the second).
+    ///
+    /// The times of the rounds never change, so they're cached, shared with the clones of this
+    /// indexer: the header of the block is fetched only for a round whose block wasn't fetched yet
+    /// (with [block](Self::block), [block_with_format](Self::block_with_format) or this method).
+    /// The cache keeps the times of the last 100000 rounds fetched, the lowest rounds first
+    /// forgotten.
+    ///
+    /// An indexer not supporting the lookup of the block header (`header-only`, rejected with a
+    /// 400) is asked the whole block instead.
+    pub async fn round_time(&self, round: Round) -> Result<DateTime<Utc>, AlgonautError> {
+        Ok(volume::time(self.round_timestamp(round).await?))
+    }
+
+    /// The timestamp (unix seconds) of the block of a round, see [round_time](Self::round_time).
+    async fn round_timestamp(&self, round: Round) -> Result<u64, AlgonautError> {
+        let time = match self.round_times.get(round) {
+            Some(time) => time,
+            None => {
+                self.check_min_round_guard().await?;
+                let header = self.client.block_header(round).await;
+                let header = match header.map_err(AlgonautError::from) {
+                    Err(e) if e.response_meta().map(|meta| meta.status) == Some(400) => {
+                        self.client.block(round).await?
+                    }
+                    header => header?,
+                };
+                self.round_times.insert(header.round, header.timestamp);
+                header.timestamp
+            }
+        };
+        Ok(time)
     }
 
     /// Search for transactions.
@@ -120,21 +699,384 @@ impl Indexer {
         &self,
         query: &QueryTransaction,
     ) -> Result<TransactionResponse, AlgonautError> {
+        query.validate()?;
+        self.check_min_round_guard().await?;
         Ok(self.client.transactions(query).await?)
     }
 
+    /// Looks up the transactions of the atomic group with this id, in their order in the group (by
+    /// offset in the round), e.g. to find the other members of a group.
+    ///
+    /// `round_hint`, the round of the group if known, narrows the search to that round. All the
+    /// pages of the search are fetched. The result is empty if the indexer has no transaction of
+    /// the group.
+    pub async fn group_transactions(
+        &self,
+        group_id: &HashDigest,
+        round_hint: Option<Round>,
+    ) -> Result<Vec<Transaction>, AlgonautError> {
+        let query = QueryTransaction {
+            round: round_hint.map(|round| round.0),
+            ..QueryTransaction::default().with_group_id(*group_id)
+        };
+        let mut transactions: Vec<Transaction> = paginate(query, move |query| async move {
+            self.transactions(&query).await
+        })
+        .try_collect()
+        .await?;
+        transactions.sort_by_key(|t| (t.confirmed_round, t.intra_round_offset));
+        Ok(transactions)
+    }
+
+    /// Streams the transactions matching `query` newest-first, paging backward in time from the
+    /// latest round (or `query.max_round`) as the stream is consumed, e.g. for activity feeds.
+    ///
+    /// The indexer returns the searches by address (`query.address`) newest-first: their pages are
+    /// fetched in order. It returns the other searches oldest-first, without a descending option:
+    /// they're fetched by windows of rounds, from the latest round processed by the indexer down
+    /// to `query.min_round` (or the genesis), and the transactions of each window are sorted
+    /// newest-first. The first window has 1000 rounds, and the size of the window doubles after
+    /// each window without transactions, so that a sparse history takes fewer requests.
+    ///
+    /// `query.limit`, if set, is the page size, and `query.next` is ignored. Limits:
+    /// - All the pages of a window are fetched before its first transaction is yielded: a window
+    ///   of a busy history can take many requests and memory, so prefer narrow filters.
+    /// - Without a `min_round`, the windows of a search without matches walk back to the genesis.
+    ///   Stop consuming the stream (e.g. with `take`) to stop the requests.
+    /// - The transactions confirmed after the first request aren't yielded.
+    ///
+    /// To combine or resume scans without yielding a transaction twice, see
+    /// [TransactionStreamExt::dedup_by_id].
+    ///
+    /// Errors end the stream.
+    pub fn transactions_recent(
+        &self,
+        query: &QueryTransaction,
+    ) -> impl Stream<Item = Result<Transaction, AlgonautError>> + '_ {
+        let query = QueryTransaction {
+            next: None,
+            ..query.clone()
+        };
+        stream::once(async move {
+            query.validate()?;
+            if recent::is_newest_first(&query) {
+                let pages = paginate(query, move |query| async move {
+                    self.transactions(&query).await
+                });
+                return Ok::<_, AlgonautError>(Either::Left(pages));
+            }
+            let current_round = self.health_check().await?.round;
+            let first_window = recent::rounds(&query, current_round).map(|rounds| NextWindow {
+                upper: rounds.max,
+                floor: rounds.min,
+                size: recent::FIRST_WINDOW_ROUNDS,
+            });
+            let windows = stream::try_unfold(first_window, move |next_window| {
+                let query = query.clone();
+                async move {
+                    let NextWindow { upper, floor, size } = match next_window {
+                        Some(next_window) => next_window,
+                        None => return Ok::<_, AlgonautError>(None),
+                    };
+                    let window = recent::window(upper, floor, size);
+                    let window_query = QueryTransaction {
+                        round: None,
+                        ..query
+                    }
+                    .with_round_range(window);
+                    let mut transactions: Vec<Transaction> =
+                        paginate(window_query, move |query| async move {
+                            self.transactions(&query).await
+                        })
+                        .try_collect()
+                        .await?;
+                    recent::sort_newest_first(&mut transactions);
+                    let next_window = (window.min > floor).then(|| NextWindow {
+                        upper: window.min - 1,
+                        floor,
+                        size: recent::next_window_size(size, transactions.len()),
+                    });
+                    Ok(Some((transactions, next_window)))
+                }
+            })
+            .map_ok(|transactions| stream::iter(transactions.into_iter().map(Ok)))
+            .try_flatten();
+            Ok(Either::Right(windows))
+        })
+        .try_flatten()
+    }
+
+    /// Search for transactions, parsing the page as a borrowed view of the body of the response,
+    /// stored in `buf`.
+    ///
+    /// For scans of large pages that read a few fields of each transaction: the strings of the
+    /// view borrow from `buf` instead of being allocated, and the fields missing from the view
+    /// aren't parsed. See [borrowed](algonaut_model::indexer::v2::borrowed) for the fields.
+    ///
+    /// Parsing a page of 1000 payments (730 KB) is about 2.7 times faster than with
+    /// [transactions](Self::transactions), as measured by the `indexer_borrowed_parsing` example.
+    /// The request itself usually takes longer than the parsing: this matters for CPU-bound
+    /// scans, e.g. of a local indexer.
+    pub async fn transactions_borrowed<'b>(
+        &self,
+        query: &QueryTransaction,
+        buf: &'b mut String,
+    ) -> Result<TransactionResponseRef<'b>, AlgonautError> {
+        query.validate()?;
+        self.check_min_round_guard().await?;
+        *buf = self.client.transactions_raw(query).await?;
+        serde_json::from_str(buf).map_err(|e| AlgonautError::Decode {
+            url: None,
+            description: e.to_string(),
+            path: None,
+            body: None,
+        })
+    }
+
+    /// Writes the transactions matching `query` to `writer` as newline-delimited JSON (one
+    /// transaction per line), fetching the pages of [transactions](Self::transactions) as needed.
+    /// Returns the number of transactions written.
+    ///
+    /// Only one page is kept in memory at a time: `query.limit`, if set, is the page size.
+    /// The writer is flushed after each page. A writer error stops the export and is returned as
+    /// [AlgonautError::Io], after the transactions of the previous pages have been written.
+    ///
+    /// The writer is a [futures_util] `AsyncWrite`: Tokio's writers can be adapted with
+    /// `tokio_util::compat`.
+    pub async fn export_transactions_ndjson<W>(
+        &self,
+        query: &QueryTransaction,
+        mut writer: W,
+    ) -> Result<u64, AlgonautError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let pages = pages(query.clone(), move |query| async move {
+            self.transactions(&query).await
+        });
+        pin_mut!(pages);
+        let mut count = 0;
+        while let Some(transactions) = pages.try_next().await? {
+            count += write_ndjson(&mut writer, &transactions).await?;
+            writer.flush().await?;
+        }
+        Ok(count)
+    }
+
+    /// Counts the transactions matching `query` confirmed in the rounds `from..=to`, grouped by
+    /// the time of their round into buckets of `bucket` size.
+    ///
+    /// Returns the start of each bucket with its count, from the bucket of `from` to the bucket of
+    /// `to`, including the buckets without transactions, so the series is continuous. The rounds
+    /// after the latest round of the indexer have no time yet: the series then ends with the
+    /// bucket of the latest round, and is empty if `from` is after it.
+    ///
+    /// The round range of `query` is replaced by `from..=to`, and `query.limit`, if set, is the page
+    /// size. All the matching transactions are scanned, so the cost grows with their number, but
+    /// only the counts of the buckets are kept.
+    pub async fn transaction_volume(
+        &self,
+        query: &QueryTransaction,
+        bucket: BucketSize,
+        from: Round,
+        to: Round,
+    ) -> Result<Vec<(DateTime<Utc>, u64)>, AlgonautError> {
+        if from > to {
+            return Ok(vec![]);
+        }
+        let to = to.min(self.health_check().await?.round);
+        if from > to {
+            return Ok(vec![]);
+        }
+        let start_time = self.round_timestamp(from).await?;
+        let end_time = self.round_timestamp(to).await?;
+
+        let mut buckets = VolumeBuckets::new(bucket, start_time, end_time);
+        let query = query.clone().with_round_range(RoundRange::new(from, to));
+        let transactions = paginate(query, move |query| async move {
+            self.transactions(&query).await
+        });
+        pin_mut!(transactions);
+        while let Some(transaction) = transactions.try_next().await? {
+            if let Some(round_time) = transaction.round_time {
+                buckets.add(round_time);
+            }
+        }
+        Ok(buckets.into_series())
+    }
+
     /// Search for transactions.
     pub async fn transaction_info(
         &self,
         id: &str,
     ) -> Result<TransactionInfoResponse, AlgonautError> {
-        Ok(self.client.transaction_info(id).await?)
+        self.check_min_round_guard().await?;
+        self.client
+            .transaction_info(id)
+            .await
+            .map_err(|e| AlgonautError::from(e).into_not_found("transaction", id))
+    }
+
+    /// [transaction_info](Self::transaction_info), returning `None` if the transaction isn't
+    /// found: it may not be indexed yet (e.g. confirmed in a round the indexer hasn't processed),
+    /// outside the history the indexer retains, or never have been confirmed. The `message` of the
+    /// `AlgonautError::NotFound` of [transaction_info](Self::transaction_info) is the one of the
+    /// indexer, if the distinction matters.
+    pub async fn transaction_info_opt(
+        &self,
+        id: &str,
+    ) -> Result<Option<TransactionInfoResponse>, AlgonautError> {
+        found(self.transaction_info(id).await)
+    }
+
+    /// Looks up the transactions with these ids, running up to `concurrency` lookups at a time (at
+    /// least one), e.g. to fetch transactions whose ids were logged.
+    ///
+    /// Returns each id, as given, with the result of its lookup, in the order of the ids. The ids
+    /// are normalized as [TxId]s (ignoring the surrounding whitespace and the case): an id that
+    /// isn't a valid transaction id once normalized isn't looked up, and fails with
+    /// `AlgonautError::BadQuery`. A transaction not found fails with `AlgonautError::NotFound`.
+    /// Unlike [transactions_by_ids](Self::transactions_by_ids), the other errors fail only the
+    /// lookup of their id.
+    pub async fn transaction_info_batch(
+        &self,
+        ids: &[&str],
+        concurrency: usize,
+    ) -> Vec<(String, Result<TransactionInfoResponse, AlgonautError>)> {
+        batch::lookup_each(ids, concurrency, |id| async move {
+            let result = match id.parse::<TxId>() {
+                Ok(txid) => self
+                    .transaction_info(&txid.to_string())
+                    .await
+                    .map_err(|e| match e {
+                        AlgonautError::NotFound {
+                            resource, message, ..
+                        } => AlgonautError::NotFound {
+                            resource,
+                            id: id.to_string(),
+                            message,
+                        },
+                        e => e,
+                    }),
+                Err(reason) => Err(AlgonautError::BadQuery {
+                    reason: format!("invalid transaction id {:?}: {}", id, reason),
+                    param: Some("txid"),
+                }),
+            };
+            (id.to_string(), result)
+        })
+        .await
+    }
+
+    /// Looks up the transactions with these ids, running up to `max_concurrency` lookups at a time
+    /// (at least one), e.g. to fetch the transactions of a group.
+    ///
+    /// The ids are normalized as [TxId]s (ignoring the surrounding whitespace and the case), e.g.
+    /// ids copied from logs: if one of them isn't a valid transaction id, the lookup fails with
+    /// [AlgonautError::BadQuery] before sending any request.
+    ///
+    /// Returns the result of each lookup, in the order of the ids: a transaction not found (404)
+    /// is an error in its result. Any other error fails the whole lookup: use
+    /// [transaction_info_batch](Self::transaction_info_batch) for a result per id whatever the error.
+    pub async fn transactions_by_ids(
+        &self,
+        ids: &[&str],
+        max_concurrency: usize,
+    ) -> Result<Vec<Result<TransactionInfoResponse, AlgonautError>>, AlgonautError> {
+        let txids = ids
+            .iter()
+            .map(|id| match id.parse::<TxId>() {
+                Ok(txid) => Ok(txid.to_string()),
+                Err(reason) => Err(AlgonautError::BadQuery {
+                    reason: format!("invalid transaction id {:?}: {}", id, reason),
+                    param: Some("txid"),
+                }),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let txids: Vec<&str> = txids.iter().map(String::as_str).collect();
+        batch::lookup_all(&txids, max_concurrency, |id| self.transaction_info(id)).await
+    }
+}
+
+/// Writes `items` to `writer` as JSON, one per line, returning the number of lines written.
+async fn write_ndjson<W, T>(writer: &mut W, items: &[T]) -> Result<u64, AlgonautError>
+where
+    W: AsyncWrite + Unpin,
+    T: Serialize,
+{
+    let mut line = vec![];
+    for item in items {
+        line.clear();
+        serde_json::to_writer(&mut line, item)
+            .map_err(|e| AlgonautError::Internal(e.to_string()))?;
+        line.push(b'\n');
+        writer.write_all(&line).await?;
     }
+    Ok(items.len() as u64)
+}
+
+/// Fails if the id of the path (of an asset or an application) is zero: none has it.
+fn validate_path_id(id: u64, param: &'static str) -> Result<(), AlgonautError> {
+    if id == 0 {
+        return Err(AlgonautError::BadQuery {
+            reason: format!("{} is zero", param),
+            param: Some(param),
+        });
+    }
+    Ok(())
+}
+
+/// The result of a lookup, `None` if the resource wasn't found.
+fn found<T>(result: Result<T, AlgonautError>) -> Result<Option<T>, AlgonautError> {
+    match result {
+        Ok(response) => Ok(Some(response)),
+        Err(AlgonautError::NotFound { .. }) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Minimum fee of the current protocol.
+const MIN_TXN_FEE: MicroAlgos = MicroAlgos(1000);
+
+/// The default maximum of the `limit` of the asset balances of the indexer.
+const MAX_BALANCES_PAGE_SIZE: u64 = 10000;
+
+fn suggested_params_from_block(block: &Block) -> SuggestedTransactionParams {
+    SuggestedTransactionParams {
+        genesis_id: block.genesis_id.clone(),
+        genesis_hash: block.genesis_hash,
+        consensus_version: block
+            .upgrade_state
+            .as_ref()
+            .map(|upgrade_state| upgrade_state.current_protocol.clone())
+            .unwrap_or_default(),
+        fee_per_byte: MicroAlgos(0),
+        min_fee: MIN_TXN_FEE,
+        first_valid: block.round,
+        last_valid: block
+            .round
+            .checked_add(MAX_VALIDITY_WINDOW)
+            .unwrap_or(Round(u64::MAX)),
+    }
+}
+
+/// Whether the holding is listed by [Indexer::asset_holders_above].
+fn is_holder_above(holding: &MiniAssetHolding, min_amount: u64, include_frozen: bool) -> bool {
+    holding.amount > min_amount
+        && (include_frozen || !holding.is_frozen)
+        && holding.deleted != Some(true)
+}
+
+/// Whether the holding is counted by [Indexer::is_asset_distributed].
+fn is_external_holder(holding: &MiniAssetHolding, creator: &str) -> bool {
+    holding.amount > 0 && holding.deleted != Some(true) && holding.address != creator
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use algonaut_client::test_server::{TestResponse, TestServer, UNREACHABLE_URL};
+    use algonaut_model::indexer::v2::Role;
 
     #[test]
     fn test_create_with_valid_url() {
@@ -142,9 +1084,1232 @@ mod tests {
         assert!(indexer.ok().is_some());
     }
 
+    #[test]
+    fn test_create_with_config() {
+        let config = ClientConfig {
+            pool_max_idle_per_host: Some(4),
+            pool_idle_timeout: Some(std::time::Duration::from_secs(30)),
+            timeout: Some(std::time::Duration::from_secs(10)),
+            max_retries: 2,
+            retry_budget_ratio: Some(0.2),
+            max_retry_delay: Some(std::time::Duration::from_secs(5)),
+            response_format: ResponseFormat::MessagePack,
+            debug_body_limit: Some(1024),
+            max_response_bytes: Some(10_000_000),
+            decoder: Default::default(),
+        };
+        let indexer = Indexer::with_config("http://example.com", vec![], &config);
+        assert!(indexer.ok().is_some());
+    }
+
     #[test]
     #[should_panic(expected = "")]
     fn test_create_with_empty_url() {
         Indexer::new("").unwrap();
     }
+
+    #[test]
+    fn test_min_round_guard_override() {
+        let indexer = Indexer::new("http://example.com")
+            .unwrap()
+            .with_min_round_guard(Some(Round(10)));
+        assert_eq!(Some(Round(10)), indexer.min_round_guard());
+        assert_eq!(Some(Round(10)), indexer.config().min_round_guard());
+        assert_eq!(None, indexer.with_min_round_guard(None).min_round_guard());
+        assert_eq!(Some(Round(10)), indexer.min_round_guard());
+    }
+
+    #[test]
+    fn test_create_from_config() {
+        let client = ClientConfig {
+            max_retries: 2,
+            ..ClientConfig::default()
+        };
+        let config = IndexerConfig::new(
+            NodePreset::new(
+                "http://localhost:8980",
+                vec![("X-Indexer-API-Token", "token")],
+            ),
+            client.clone(),
+        )
+        .with_min_round_guard(Some(Round(10)));
+
+        let indexer = Indexer::from_config(config).unwrap();
+
+        assert_eq!(Some(Round(10)), indexer.min_round_guard());
+        let config = indexer.config();
+        assert_eq!("http://localhost:8980/", config.url());
+        assert_eq!(
+            vec![("X-Indexer-API-Token", "token")],
+            config.headers().collect::<Vec<_>>()
+        );
+        assert_eq!(&client, config.client());
+        assert!(config.retries_enabled());
+    }
+
+    /// Reads the variables from `vars` instead of the environment.
+    fn from_vars(vars: &[(&str, &str)]) -> Result<Indexer, AlgonautError> {
+        Indexer::from_vars(|name| {
+            vars.iter()
+                .find(|(var, _)| *var == name)
+                .map(|(_, value)| value.to_string())
+                .ok_or(std::env::VarError::NotPresent)
+        })
+    }
+
+    #[test]
+    fn test_from_env_requires_the_server() {
+        assert_eq!(
+            Some(AlgonautError::MissingEnvVars {
+                names: vec!["INDEXER_SERVER"]
+            }),
+            from_vars(&[("INDEXER_TOKEN", "token")]).err()
+        );
+    }
+
+    #[test]
+    fn test_from_env_with_invalid_timeout() {
+        let res = from_vars(&[
+            ("INDEXER_SERVER", "http://example.com"),
+            ("INDEXER_TIMEOUT_MS", "5s"),
+        ]);
+        assert!(matches!(
+            res,
+            Err(AlgonautError::BadEnvVar {
+                name: "INDEXER_TIMEOUT_MS",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_from_env_with_port_and_localnet_defaults() {
+        let indexer =
+            from_vars(&[("INDEXER_SERVER", "localhost"), ("INDEXER_PORT", "8981")]).unwrap();
+        assert_eq!("http://localhost:8981/", indexer.config().url());
+
+        let indexer = from_vars(&[("ALGOD_ENV", "localnet")]).unwrap();
+        assert_eq!("http://localhost:8980/", indexer.config().url());
+    }
+
+    #[tokio::test]
+    async fn test_from_env_sends_the_token() {
+        let server = TestServer::serve(vec![TestResponse::json("{}")]);
+        let indexer = from_vars(&[
+            ("INDEXER_SERVER", server.url()),
+            ("INDEXER_TOKEN", "my-token"),
+        ])
+        .unwrap();
+
+        let _ = indexer.health().await;
+
+        let request = server.request();
+        assert_eq!("GET /health HTTP/1.1", request.line);
+        assert_eq!(Some("my-token"), request.header("x-indexer-api-token"));
+
+        let server = TestServer::serve(vec![TestResponse::json("{}")]);
+        let indexer = from_vars(&[
+            ("INDEXER_SERVER", server.url()),
+            ("INDEXER_TOKEN", "my-key"),
+            ("INDEXER_TOKEN_HEADER", "X-API-Key"),
+        ])
+        .unwrap();
+
+        let _ = indexer.health().await;
+
+        let request = server.request();
+        assert_eq!(Some("my-key"), request.header("x-api-key"));
+        assert_eq!(None, request.header("x-indexer-api-token"));
+    }
+
+    #[tokio::test]
+    async fn test_from_env_applies_the_timeout() {
+        let server = TestServer::silent();
+        let indexer = from_vars(&[
+            ("INDEXER_SERVER", server.url()),
+            ("INDEXER_TIMEOUT_MS", "100"),
+        ])
+        .unwrap();
+
+        let res = indexer.health().await;
+
+        assert!(matches!(
+            res,
+            Err(AlgonautError::Request(crate::error::RequestError {
+                details: crate::error::RequestErrorDetails::Timeout,
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn test_effective_config() {
+        let indexer = from_vars(&[
+            ("INDEXER_SERVER", "http://localhost:8980"),
+            ("INDEXER_TOKEN", "my-token"),
+            ("INDEXER_TIMEOUT_MS", "100"),
+        ])
+        .unwrap();
+        let config = indexer.config();
+
+        assert_eq!("http://localhost:8980/", config.url());
+        assert_eq!(
+            vec![("X-Indexer-API-Token", "my-token")],
+            config.headers().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Some(std::time::Duration::from_millis(100)),
+            config.timeout()
+        );
+        assert!(!config.retries_enabled());
+        let debug = format!("{:?}", config);
+        assert!(!debug.contains("my-token"), "{}", debug);
+        assert!(
+            debug.contains(r#""X-Indexer-API-Token": "***""#),
+            "{}",
+            debug
+        );
+
+        let indexer = Indexer::with_config(
+            "https://mainnet-idx.algonode.cloud/",
+            vec![],
+            &ClientConfig {
+                max_retries: 3,
+                ..ClientConfig::default()
+            },
+        )
+        .unwrap();
+        // shared with the derived indexers
+        let config = indexer
+            .with_min_round_guard(Some(Round(1)))
+            .config()
+            .clone();
+
+        assert_eq!("https://mainnet-idx.algonode.cloud/", config.url());
+        assert_eq!(0, config.headers().count());
+        assert_eq!(None, config.timeout());
+        assert!(config.retries_enabled());
+        assert_eq!(3, config.client().max_retries);
+    }
+
+    #[tokio::test]
+    async fn test_min_round_guard_uses_seen_round() {
+        // Nothing listens on the url: the guard must be satisfied without a health check.
+        let indexer = Indexer::new(UNREACHABLE_URL)
+            .unwrap()
+            .with_min_round_guard(Some(Round(10)));
+        indexer.seen_round.set(Round(10));
+        assert!(indexer.check_min_round_guard().await.is_ok());
+
+        let indexer = indexer.with_min_round_guard(Some(Round(11)));
+        assert!(indexer.check_min_round_guard().await.is_err());
+    }
+
+    #[test]
+    fn test_is_holder_above() {
+        let holding = MiniAssetHolding {
+            address: "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU".to_owned(),
+            amount: 10,
+            deleted: Some(false),
+            is_frozen: false,
+            opted_in_at_round: Some(Round(1)),
+            opted_out_at_round: None,
+        };
+        assert!(is_holder_above(&holding, 9, false));
+        assert!(is_holder_above(&holding, 0, false));
+        assert!(!is_holder_above(&holding, 10, false));
+        assert!(!is_holder_above(
+            &MiniAssetHolding {
+                amount: 0,
+                ..holding.clone()
+            },
+            0,
+            true
+        ));
+        let frozen = MiniAssetHolding {
+            is_frozen: true,
+            ..holding.clone()
+        };
+        assert!(!is_holder_above(&frozen, 0, false));
+        assert!(is_holder_above(&frozen, 0, true));
+        assert!(!is_holder_above(
+            &MiniAssetHolding {
+                deleted: Some(true),
+                ..holding
+            },
+            0,
+            true
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_asset_holders_above_ends_on_error() {
+        use futures_util::StreamExt;
+
+        // Nothing listens on the url: the first page fails and ends the stream.
+        let indexer = Indexer::new(UNREACHABLE_URL).unwrap();
+        let holders: Vec<_> = indexer.asset_holders_above(1, 0, false).collect().await;
+        assert_eq!(1, holders.len());
+        assert!(holders[0].is_err());
+    }
+
+    #[tokio::test]
+    async fn test_asset_holders_above_with_frozen_holders() {
+        use futures_util::TryStreamExt;
+
+        let page = serde_json::json!({
+            "balances": [
+                {"address": "A", "amount": 20, "is-frozen": false},
+                {"address": "B", "amount": 30, "is-frozen": true},
+            ],
+            "current-round": 10,
+        })
+        .to_string();
+        let server = TestServer::serve(vec![
+            TestResponse::json(page.clone()),
+            TestResponse::json(page),
+        ]);
+        let indexer = Indexer::new(server.url()).unwrap();
+        let addresses = |holders: Vec<MiniAssetHolding>| {
+            holders
+                .into_iter()
+                .map(|holding| holding.address)
+                .collect::<Vec<_>>()
+        };
+
+        let holders = indexer.asset_holders_above(1, 10, false);
+        assert_eq!(vec!["A"], addresses(holders.try_collect().await.unwrap()));
+        let holders = indexer.asset_holders_above(1, 10, true);
+        assert_eq!(
+            vec!["A", "B"],
+            addresses(holders.try_collect().await.unwrap())
+        );
+        assert_eq!(
+            "GET /v2/assets/1/balances?currency-greater-than=10 HTTP/1.1",
+            server.request_line()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bad_queries_arent_sent() {
+        // Nothing listens on the url: the queries are rejected before being sent.
+        let indexer = Indexer::new(UNREACHABLE_URL).unwrap();
+
+        let query = QueryAssets::by_id(1026).with_unit("TUSD");
+        assert_eq!(
+            Some(AlgonautError::BadQuery {
+                reason: "asset_id can't be combined with unit".to_owned(),
+                param: Some("unit")
+            }),
+            indexer.assets(&query).await.err()
+        );
+
+        let query = QueryTransaction::default().with_round_range(RoundRange::new(20, 10));
+        assert_eq!(
+            Some(AlgonautError::BadQuery {
+                reason: "min_round > max_round (20 > 10)".to_owned(),
+                param: Some("min_round")
+            }),
+            indexer.transactions(&query).await.err()
+        );
+
+        assert_eq!(
+            Some(AlgonautError::BadQuery {
+                reason: "id is zero".to_owned(),
+                param: Some("id")
+            }),
+            indexer
+                .assets_info(0, &QueryAssetsInfo::default())
+                .await
+                .err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_transactions_recent_with_bad_query() {
+        use futures_util::StreamExt;
+
+        // Nothing listens on the url: the query is rejected before being sent.
+        let indexer = Indexer::new(UNREACHABLE_URL).unwrap();
+        let query = QueryTransaction {
+            address: Some("not an address".to_owned()),
+            ..QueryTransaction::default()
+        };
+
+        let results: Vec<_> = indexer.transactions_recent(&query).collect().await;
+
+        assert_eq!(1, results.len());
+        assert!(matches!(
+            results[0],
+            Err(AlgonautError::BadQuery {
+                param: Some("address"),
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_applications_by_creator_ends_on_error() {
+        use futures_util::StreamExt;
+
+        // Nothing listens on the url: the first page fails and ends the stream.
+        let indexer = Indexer::new(UNREACHABLE_URL).unwrap();
+        let creator = "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU"
+            .parse()
+            .unwrap();
+        let applications: Vec<_> = indexer.applications_by_creator(creator).collect().await;
+        assert_eq!(1, applications.len());
+        assert!(applications[0].is_err());
+    }
+
+    #[test]
+    fn test_is_external_holder() {
+        let creator = "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU";
+        let holding = MiniAssetHolding {
+            address: "2UEQTE5QDNXPI7M3TU44G6SYKLFWLPQO7EBZM7K7MHMQQMFI4QJPLHQFHM".to_owned(),
+            amount: 1,
+            deleted: Some(false),
+            is_frozen: false,
+            opted_in_at_round: Some(Round(1)),
+            opted_out_at_round: None,
+        };
+        assert!(is_external_holder(&holding, creator));
+        assert!(is_external_holder(
+            &MiniAssetHolding {
+                is_frozen: true,
+                ..holding.clone()
+            },
+            creator
+        ));
+        assert!(!is_external_holder(
+            &MiniAssetHolding {
+                amount: 0,
+                is_frozen: true,
+                ..holding.clone()
+            },
+            creator
+        ));
+        assert!(!is_external_holder(
+            &MiniAssetHolding {
+                deleted: Some(true),
+                ..holding.clone()
+            },
+            creator
+        ));
+        assert!(!is_external_holder(
+            &MiniAssetHolding {
+                address: creator.to_owned(),
+                ..holding
+            },
+            creator
+        ));
+    }
+
+    /// Answers the requests with these JSON bodies, in order.
+    fn serve(bodies: Vec<&'static str>) -> TestServer {
+        TestServer::serve(bodies.into_iter().map(TestResponse::json).collect())
+    }
+
+    /// The request lines of the requests received so far.
+    fn request_lines(server: &TestServer) -> Vec<String> {
+        server
+            .received()
+            .into_iter()
+            .map(|request| request.line)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_application_params_only() {
+        let server = serve(vec![
+            r#"{"application":{"created-at-round":20,"deleted":false,"id":5,"params":{
+                "approval-program":"BoEBQw==","clear-state-program":"BoEBQw==",
+                "creator":"47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU",
+                "extra-program-pages":1,
+                "global-state":[
+                    {"key":"Y291bnQ=","value":{"bytes":"","type":2,"uint":7}},
+                    {"key":"bmFtZQ==","value":{"bytes":"YWxnb25hdXQ=","type":1,"uint":0}}
+                ],
+                "global-state-schema":{"num-byte-slice":1,"num-uint":1},
+                "local-state-schema":{"num-byte-slice":0,"num-uint":2}
+            }},"current-round":30}"#,
+        ]);
+        let indexer = Indexer::new(server.url()).unwrap();
+
+        let res = indexer
+            .application_params_only(5, &QueryApplicationInfo::default())
+            .await
+            .unwrap();
+
+        assert_eq!("GET /v2/applications/5 HTTP/1.1", server.request_line());
+        let application = res.application.unwrap();
+        assert_eq!(5, application.id);
+        assert_eq!(
+            "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU",
+            application.params.creator.unwrap().to_string()
+        );
+        assert_eq!(Some(1), application.params.extra_program_pages);
+        assert_eq!(
+            Some(2),
+            application
+                .params
+                .local_state_schema
+                .map(|schema| schema.num_uint)
+        );
+        assert_eq!(30, res.current_round);
+    }
+
+    /// The header of a testnet block.
+    const BLOCK_HEADER: &str = r#"{
+        "genesis-hash": "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=",
+        "genesis-id": "testnet-v1.0",
+        "previous-block-hash": "VC2p6EsSBVbH1RkUEdIrPgkWHGWhP5h2Gw9SPwxeJYU=",
+        "round": 25999125,
+        "seed": "Yq2+dQhVyrqRTuxYJnB42S9n3Wl8Em8GjRwbSCE+ZKc=",
+        "timestamp": 1668104440,
+        "transactions-root": "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA="
+    }"#;
+
+    #[tokio::test]
+    async fn test_round_time_is_cached() {
+        let server = serve(vec![BLOCK_HEADER]);
+        let indexer = Indexer::new(server.url()).unwrap();
+        let expected = DateTime::from_timestamp(1668104440, 0).unwrap();
+
+        assert_eq!(expected, indexer.round_time(Round(25999125)).await.unwrap());
+        assert_eq!(
+            "GET /v2/blocks/25999125?header-only=true HTTP/1.1",
+            server.request_line()
+        );
+
+        // no request, from this indexer or a clone
+        assert_eq!(expected, indexer.round_time(Round(25999125)).await.unwrap());
+        assert_eq!(
+            expected,
+            indexer
+                .with_min_round_guard(None)
+                .round_time(Round(25999125))
+                .await
+                .unwrap()
+        );
+        assert!(server.received().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_round_time_of_fetched_block() {
+        let server = serve(vec![BLOCK_HEADER]);
+        let indexer = Indexer::new(server.url()).unwrap();
+
+        indexer.block(Round(25999125)).await.unwrap();
+        let time = indexer.round_time(Round(25999125)).await.unwrap();
+
+        assert_eq!(DateTime::from_timestamp(1668104440, 0).unwrap(), time);
+        assert_eq!("GET /v2/blocks/25999125 HTTP/1.1", server.request_line());
+        assert!(server.received().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_asset_holder_count() {
+        let server = serve(vec![
+            r#"{"balances":[
+                {"address":"47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU","amount":5,"is-frozen":false},
+                {"address":"4MYUHDWHWXAKA5KA7U5PEN646VYUANBFXVJNONBK3TIMHEMWMD4UBOJBI4","amount":1,"is-frozen":true}
+            ],"current-round":10,"next-token":"page2"}"#,
+            r#"{"balances":[
+                {"address":"UPYAFLHSIPMJOHVXU2MPLQ46GXJKSDCEMZ6RLCQ7GWB5PRDKJUWKKXECXI","amount":2,"is-frozen":false},
+                {"address":"2UEQTE5QDNXPI7M3TU44G6SYKLFWLPQO7EBZM7K7MHMQQMFI4QJPLHQFHM","amount":0,"deleted":true,"is-frozen":false}
+            ],"current-round":10}"#,
+            r#"{"balances":[
+                {"address":"47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU","amount":0,"is-frozen":false}
+            ],"current-round":10}"#,
+        ]);
+        let indexer = Indexer::new(server.url()).unwrap();
+
+        assert_eq!(Ok(3), indexer.asset_holder_count(1026, false).await);
+        assert_eq!(
+            "GET /v2/assets/1026/balances?currency-greater-than=0&limit=10000 HTTP/1.1",
+            server.request_line()
+        );
+        assert_eq!(
+            "GET /v2/assets/1026/balances?currency-greater-than=0&limit=10000&next=page2 HTTP/1.1",
+            server.request_line()
+        );
+
+        assert_eq!(Ok(1), indexer.asset_holder_count(1026, true).await);
+        assert_eq!(
+            "GET /v2/assets/1026/balances?limit=10000 HTTP/1.1",
+            server.request_line()
+        );
+    }
+
+    /// A group of two payments, in reverse order, as the indexer returns it: not captured from a
+    /// network. The transactions are those of the group tests of the other SDKs, which were never
+    /// sent to a network: the confirmed round is only a round within their validity windows.
+    const PAYMENT_GROUP: &str = r#"{"current-round":710600,"transactions":[
+        {"confirmed-round":710520,"fee":1000,"first-valid":710515,"genesis-hash":"sC3P7e2SdbqKJK0tbiCdK9tdSpbe6XeCGKdoNzmlj0E=",
+        "genesis-id":"devnet-v1.0","group":"LiQ9OBup9H/bZLSfQUH2S6iHUM6FQ3PLuv9FNKyt09Q=","id":"W6EXW5GN6WPIUS4ZNA7JIMI4FQP6EV46IQFGNKVQXDPZ3LDEQUXA",
+        "intra-round-offset":4,"last-valid":711515,"note":"dBlHI6BdrIg=","payment-transaction":{"amount":2000,
+        "receiver":"UPYAFLHSIPMJOHVXU2MPLQ46GXJKSDCEMZ6RLCQ7GWB5PRDKJUWKKXECXI"},
+        "sender":"UPYAFLHSIPMJOHVXU2MPLQ46GXJKSDCEMZ6RLCQ7GWB5PRDKJUWKKXECXI","tx-type":"pay"},
+        {"confirmed-round":710520,"fee":1000,"first-valid":710399,"genesis-hash":"sC3P7e2SdbqKJK0tbiCdK9tdSpbe6XeCGKdoNzmlj0E=",
+        "genesis-id":"devnet-v1.0","group":"LiQ9OBup9H/bZLSfQUH2S6iHUM6FQ3PLuv9FNKyt09Q=","id":"JRL7GXE2PGXJXDOF65WW7ISDDDGWDJM2DPCVYQRAAZ44U4WGEJ5Q",
+        "intra-round-offset":3,"last-valid":711399,"note":"wRKw5cJ0CMo=","payment-transaction":{"amount":2000,
+        "receiver":"UPYAFLHSIPMJOHVXU2MPLQ46GXJKSDCEMZ6RLCQ7GWB5PRDKJUWKKXECXI"},
+        "sender":"UPYAFLHSIPMJOHVXU2MPLQ46GXJKSDCEMZ6RLCQ7GWB5PRDKJUWKKXECXI","tx-type":"pay"}]}"#;
+
+    #[tokio::test]
+    async fn test_group_transactions() {
+        let server = serve(vec![PAYMENT_GROUP, PAYMENT_GROUP]);
+        let indexer = Indexer::new(server.url()).unwrap();
+        let group_id: HashDigest = "LiQ9OBup9H/bZLSfQUH2S6iHUM6FQ3PLuv9FNKyt09Q="
+            .parse()
+            .unwrap();
+
+        let transactions = indexer.group_transactions(&group_id, None).await.unwrap();
+
+        let ids: Vec<_> = transactions.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(
+            vec![
+                "JRL7GXE2PGXJXDOF65WW7ISDDDGWDJM2DPCVYQRAAZ44U4WGEJ5Q",
+                "W6EXW5GN6WPIUS4ZNA7JIMI4FQP6EV46IQFGNKVQXDPZ3LDEQUXA"
+            ],
+            ids
+        );
+        assert_eq!(
+            "GET /v2/transactions?group-id=LiQ9OBup9H%2FbZLSfQUH2S6iHUM6FQ3PLuv9FNKyt09Q%3D HTTP/1.1",
+            server.request_line()
+        );
+
+        indexer
+            .group_transactions(&group_id, Some(Round(710520)))
+            .await
+            .unwrap();
+        assert_eq!(
+            "GET /v2/transactions?group-id=LiQ9OBup9H%2FbZLSfQUH2S6iHUM6FQ3PLuv9FNKyt09Q%3D&round=710520 HTTP/1.1",
+            server.request_line()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_group_transactions_on_several_pages() {
+        let (first, second) = PAYMENT_GROUP.split_once("},\n        {").unwrap();
+        let first_page = format!(r#"{first}}}],"next-token":"page2"}}"#);
+        let second_page = format!(r#"{{"current-round":710600,"transactions":[{{{second}"#);
+        let server = serve(vec![
+            Box::leak(first_page.into_boxed_str()),
+            Box::leak(second_page.into_boxed_str()),
+        ]);
+        let indexer = Indexer::new(server.url()).unwrap();
+        let group_id: HashDigest = "LiQ9OBup9H/bZLSfQUH2S6iHUM6FQ3PLuv9FNKyt09Q="
+            .parse()
+            .unwrap();
+
+        let transactions = indexer.group_transactions(&group_id, None).await.unwrap();
+
+        let ids: Vec<_> = transactions.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(
+            vec![
+                "JRL7GXE2PGXJXDOF65WW7ISDDDGWDJM2DPCVYQRAAZ44U4WGEJ5Q",
+                "W6EXW5GN6WPIUS4ZNA7JIMI4FQP6EV46IQFGNKVQXDPZ3LDEQUXA"
+            ],
+            ids
+        );
+        assert_eq!(
+            "GET /v2/transactions?group-id=LiQ9OBup9H%2FbZLSfQUH2S6iHUM6FQ3PLuv9FNKyt09Q%3D HTTP/1.1",
+            server.request_line()
+        );
+        assert_eq!(
+            "GET /v2/transactions?group-id=LiQ9OBup9H%2FbZLSfQUH2S6iHUM6FQ3PLuv9FNKyt09Q%3D&next=page2 HTTP/1.1",
+            server.request_line()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_asset_transactions_for_account() {
+        use futures_util::StreamExt;
+
+        let first_page: &'static str = Box::leak(
+            PAYMENT_GROUP
+                .replacen(
+                    r#""current-round":710600,"#,
+                    r#""current-round":710600,"next-token":"page2","#,
+                    1,
+                )
+                .into_boxed_str(),
+        );
+        let server = serve(vec![first_page, PAYMENT_GROUP]);
+        let indexer = Indexer::new(server.url()).unwrap();
+        let address: Address = "UPYAFLHSIPMJOHVXU2MPLQ46GXJKSDCEMZ6RLCQ7GWB5PRDKJUWKKXECXI"
+            .parse()
+            .unwrap();
+        // the address of the query is replaced, its other filters are kept
+        let query = QueryAssetTransaction {
+            address_role: Some(Role::Sender),
+            ..QueryAssetTransaction::default().with_address(
+                "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU"
+                    .parse()
+                    .unwrap(),
+                Role::Receiver,
+            )
+        };
+
+        let transactions: Vec<_> = indexer
+            .asset_transactions_for_account(10, &address, &query)
+            .collect()
+            .await;
+
+        assert_eq!(4, transactions.len());
+        assert!(transactions.iter().all(|t| t.is_ok()));
+        assert_eq!(
+            "GET /v2/assets/10/transactions?address=UPYAFLHSIPMJOHVXU2MPLQ46GXJKSDCEMZ6RLCQ7GWB5PRDKJUWKKXECXI&address-role=sender HTTP/1.1",
+            server.request_line()
+        );
+        assert_eq!(
+            "GET /v2/assets/10/transactions?address=UPYAFLHSIPMJOHVXU2MPLQ46GXJKSDCEMZ6RLCQ7GWB5PRDKJUWKKXECXI&address-role=sender&next=page2 HTTP/1.1",
+            server.request_line()
+        );
+        assert!(server.received().is_empty());
+    }
+
+    const ASSET_INFO: &str = r#"{"asset":{"index":5,"params":{"creator":"47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU",
+        "decimals":0,"total":100}},"current-round":10}"#;
+
+    #[tokio::test]
+    async fn test_asset_distributed_stops_at_first_external_holder() {
+        let server = serve(vec![
+            ASSET_INFO,
+            r#"{"balances":[{"address":"47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU","amount":90,"is-frozen":false}],
+            "current-round":10,"next-token":"page2"}"#,
+            r#"{"balances":[{"address":"2UEQTE5QDNXPI7M3TU44G6SYKLFWLPQO7EBZM7K7MHMQQMFI4QJPLHQFHM","amount":10,"is-frozen":true}],
+            "current-round":10,"next-token":"page3"}"#,
+        ]);
+        let indexer = Indexer::new(server.url()).unwrap();
+
+        assert_eq!(Ok(true), indexer.is_asset_distributed(5).await);
+        let requests = request_lines(&server);
+        assert_eq!(3, requests.len());
+        assert!(requests[1].contains("currency-greater-than=0"));
+        assert!(requests[2].contains("next=page2"));
+    }
+
+    #[tokio::test]
+    async fn test_asset_not_distributed() {
+        let server = serve(vec![
+            ASSET_INFO,
+            r#"{"balances":[{"address":"47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU","amount":100,"is-frozen":false},
+            {"address":"2UEQTE5QDNXPI7M3TU44G6SYKLFWLPQO7EBZM7K7MHMQQMFI4QJPLHQFHM","amount":0,"is-frozen":true}],
+            "current-round":10}"#,
+        ]);
+        let indexer = Indexer::new(server.url()).unwrap();
+
+        assert_eq!(Ok(false), indexer.is_asset_distributed(5).await);
+    }
+
+    #[tokio::test]
+    async fn test_transactions_recent_by_address_pages_in_order() {
+        use futures_util::StreamExt;
+
+        let server = serve(vec![
+            r#"{"current-round":1500,"next-token":"page2","transactions":[
+            {"confirmed-round":1400,"fee":1000,"first-valid":1,"genesis-hash":"SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=","id":"B","last-valid":2000,"sender":"47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU","tx-type":"pay"}]}"#,
+            r#"{"current-round":1500,"transactions":[
+            {"confirmed-round":600,"fee":1000,"first-valid":1,"genesis-hash":"SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=","id":"A","last-valid":2000,"sender":"47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU","tx-type":"pay"}]}"#,
+        ]);
+        let indexer = Indexer::new(server.url()).unwrap();
+        let query = QueryTransaction {
+            address: Some("47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU".to_owned()),
+            next: Some("ignored".to_owned()),
+            ..QueryTransaction::default()
+        };
+
+        let ids: Vec<_> = indexer
+            .transactions_recent(&query)
+            .map(|t| t.unwrap().id)
+            .collect()
+            .await;
+
+        assert_eq!(vec!["B", "A"], ids);
+        let requests = request_lines(&server);
+        assert_eq!(2, requests.len());
+        assert!(requests[0].starts_with("GET /v2/transactions?address="));
+        assert!(!requests[0].contains("next="));
+        assert!(requests[1].contains("next=page2"));
+    }
+
+    #[tokio::test]
+    async fn test_transactions_recent_pages_backward_by_windows() {
+        use futures_util::StreamExt;
+
+        let server = serve(vec![
+            r#"{"db-available":true,"is-migrating":false,"message":"1500","round":1500}"#,
+            r#"{"current-round":1500,"transactions":[
+            {"confirmed-round":600,"fee":1000,"first-valid":1,"genesis-hash":"SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=","id":"A","intra-round-offset":0,"last-valid":2000,"sender":"47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU","tx-type":"pay"},
+            {"confirmed-round":1400,"fee":1000,"first-valid":1,"genesis-hash":"SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=","id":"B","intra-round-offset":0,"last-valid":2000,"sender":"47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU","tx-type":"pay"}]}"#,
+            r#"{"current-round":1500,"transactions":[]}"#,
+        ]);
+        let indexer = Indexer::new(server.url()).unwrap();
+
+        let ids: Vec<_> = indexer
+            .transactions_recent(&QueryTransaction::default())
+            .map(|t| t.unwrap().id)
+            .collect()
+            .await;
+
+        assert_eq!(vec!["B", "A"], ids);
+        let requests = request_lines(&server);
+        assert_eq!(
+            vec![
+                "GET /health HTTP/1.1",
+                "GET /v2/transactions?max-round=1500&min-round=501 HTTP/1.1",
+                "GET /v2/transactions?max-round=500&min-round=0 HTTP/1.1",
+            ],
+            requests
+        );
+    }
+
+    #[tokio::test]
+    async fn test_transactions_recent_stops_at_min_round() {
+        use futures_util::StreamExt;
+
+        // The max round is below the current round: only one window is requested.
+        let server = serve(vec![
+            r#"{"db-available":true,"is-migrating":false,"message":"1500","round":1500}"#,
+            r#"{"current-round":1500,"transactions":[]}"#,
+        ]);
+        let indexer = Indexer::new(server.url()).unwrap();
+        let query = QueryTransaction::default().with_round_range(RoundRange::new(200, 900));
+
+        let transactions: Vec<_> = indexer.transactions_recent(&query).collect().await;
+
+        assert!(transactions.is_empty());
+        let requests = request_lines(&server);
+        assert_eq!(2, requests.len());
+        assert_eq!(
+            "GET /v2/transactions?max-round=900&min-round=200 HTTP/1.1",
+            requests[1]
+        );
+    }
+
+    /// An indexer answering with a 404 with this body.
+    fn not_found_indexer(content_type: &str, body: &str) -> Indexer {
+        let server = TestServer::serve(vec![
+            TestResponse::new(content_type, body).status("404 Not Found")
+        ]);
+        Indexer::new(server.url()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_transaction_not_found() {
+        let id = "6NJ2ILUMLM3TAL5QHEAUXYCEGGSOVEMQYJWVSYJ3VSNPEVGJQUOQ";
+        let body = format!(
+            r#"{{"message":"no transaction found for transaction id: {}"}}"#,
+            id
+        );
+
+        let res = not_found_indexer("application/json", &body)
+            .transaction_info(id)
+            .await;
+        assert_eq!(
+            Err(AlgonautError::NotFound {
+                resource: "transaction",
+                id: id.to_owned(),
+                message: format!("no transaction found for transaction id: {}", id),
+            }),
+            res.map(|_| ())
+        );
+        assert!(not_found_indexer("application/json", &body)
+            .transaction_info_opt(id)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_account_not_found() {
+        let address: Address = "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU"
+            .parse()
+            .unwrap();
+        let indexer = not_found_indexer(
+            "application/json",
+            r#"{"message":"no accounts found for address: 47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU"}"#,
+        );
+
+        let res = indexer
+            .account_info_opt(&address, &QueryAccountInfo::default())
+            .await;
+
+        assert_eq!(Ok(None), res.map(|response| response.map(|_| ())));
+    }
+
+    #[tokio::test]
+    async fn test_asset_not_found() {
+        let indexer = not_found_indexer(
+            "application/json",
+            r#"{"message":"no assets found for asset-id: 1026"}"#,
+        );
+
+        let res = indexer
+            .assets_info_opt(1026, &QueryAssetsInfo::default())
+            .await;
+
+        assert_eq!(Ok(None), res.map(|response| response.map(|_| ())));
+    }
+
+    #[tokio::test]
+    async fn test_application_not_found() {
+        // The error body of a proxy, not the indexer's.
+        let res = not_found_indexer("text/plain", "404 page not found")
+            .application_info(1026, &QueryApplicationInfo::default())
+            .await;
+        assert_eq!(
+            Err(AlgonautError::NotFound {
+                resource: "application",
+                id: "1026".to_owned(),
+                message: "".to_owned(),
+            }),
+            res.map(|_| ())
+        );
+
+        let res = not_found_indexer("text/plain", "404 page not found")
+            .application_info_opt(1026, &QueryApplicationInfo::default())
+            .await;
+        assert_eq!(Ok(None), res.map(|response| response.map(|_| ())));
+    }
+
+    #[tokio::test]
+    async fn test_opt_lookup_keeps_other_errors() {
+        let server = TestServer::serve(vec![
+            TestResponse::json(r#"{"message":"boom"}"#).status("500 Internal Server Error")
+        ]);
+        let indexer = Indexer::new(server.url()).unwrap();
+
+        let res = indexer.transaction_info_opt("A").await;
+
+        assert!(matches!(res, Err(AlgonautError::Request(_))));
+    }
+
+    #[tokio::test]
+    async fn test_with_meta() {
+        let server = TestServer::serve(vec![
+            TestResponse::json("").header("x-ratelimit-remaining", "7")
+        ]);
+        let indexer = Indexer::new(server.url()).unwrap();
+
+        let ((), meta) = indexer
+            .with_meta(|indexer| async move { indexer.health().await })
+            .await
+            .unwrap();
+
+        let meta = meta.unwrap();
+        assert_eq!(200, meta.status);
+        assert_eq!(Some(7), meta.rate_limit_remaining);
+        // recorded for the call only
+        assert_eq!(None, indexer.last_response_meta());
+    }
+
+    #[tokio::test]
+    async fn test_service_unavailable() {
+        let server = TestServer::serve(vec![TestResponse::json(
+            r#"{"message":"indexer is migrating, please try again later"}"#,
+        )
+        .status("503 Service Unavailable")
+        .header("retry-after", "120")]);
+        let indexer = Indexer::new(server.url()).unwrap();
+
+        let error = indexer
+            .transactions(&QueryTransaction::default())
+            .await
+            .unwrap_err();
+
+        match &error {
+            AlgonautError::ServiceUnavailable {
+                url,
+                retry_after,
+                message,
+                ..
+            } => {
+                assert!(url.as_ref().unwrap().ends_with("/v2/transactions"));
+                assert_eq!(&Some(std::time::Duration::from_secs(120)), retry_after);
+                assert_eq!("indexer is migrating, please try again later", message);
+            }
+            error => panic!("unexpected error: {:?}", error),
+        }
+        let meta = error.response_meta().unwrap();
+        assert_eq!(503, meta.status);
+        assert_eq!(Some(std::time::Duration::from_secs(120)), meta.retry_after);
+    }
+
+    #[tokio::test]
+    async fn test_service_unavailable_without_retry_after() {
+        let server = TestServer::serve(vec![
+            TestResponse::json("").status("503 Service Unavailable")
+        ]);
+        let indexer = Indexer::new(server.url()).unwrap();
+
+        let res = indexer.health().await;
+
+        assert!(matches!(
+            res,
+            Err(AlgonautError::ServiceUnavailable {
+                retry_after: None,
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_transactions_borrowed() {
+        let server = serve(vec![
+            r#"{"current-round":10,"next-token":"abc","transactions":[{"fee":1000,"first-valid":1,
+            "genesis-hash":"SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=","id":"TX","last-valid":2,
+            "sender":"2UEQTE5QDNXPI7M3TU44G6SYKLFWLPQO7EBZM7K7MHMQQMFI4QJPLHQFHM","tx-type":"pay"}]}"#,
+        ]);
+        let indexer = Indexer::new(server.url()).unwrap();
+        let mut buf = String::new();
+
+        let page = indexer
+            .transactions_borrowed(&QueryTransaction::default(), &mut buf)
+            .await
+            .unwrap();
+
+        assert_eq!(Some("abc"), page.next_token.as_deref());
+        assert_eq!("TX", page.transactions[0].id);
+        assert!(matches!(
+            page.transactions[0].sender,
+            std::borrow::Cow::Borrowed(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_write_ndjson() {
+        let items = vec![
+            serde_json::json!({"id": "A", "fee": 1000}),
+            serde_json::json!({"id": "B", "note": "a\nb"}),
+        ];
+        let mut writer = vec![];
+        assert_eq!(Ok(2), write_ndjson(&mut writer, &items).await);
+
+        let written = String::from_utf8(writer).unwrap();
+        let lines: Vec<serde_json::Value> = written
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(items, lines);
+        assert!(written.ends_with('\n'));
+    }
+
+    /// Writer failing on every write, counting the attempts.
+    struct FailingWriter {
+        writes: usize,
+    }
+
+    impl AsyncWrite for FailingWriter {
+        fn poll_write(
+            mut self: std::pin::Pin<&mut Self>,
+            _: &mut std::task::Context<'_>,
+            _: &[u8],
+        ) -> std::task::Poll<std::io::Result<usize>> {
+            self.writes += 1;
+            std::task::Poll::Ready(Err(std::io::ErrorKind::BrokenPipe.into()))
+        }
+
+        fn poll_flush(
+            self: std::pin::Pin<&mut Self>,
+            _: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(
+            self: std::pin::Pin<&mut Self>,
+            _: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_write_ndjson_stops_on_writer_error() {
+        let items = vec![1, 2, 3];
+        let mut writer = FailingWriter { writes: 0 };
+        let res = write_ndjson(&mut writer, &items).await;
+        assert!(matches!(
+            res,
+            Err(AlgonautError::Io {
+                kind: std::io::ErrorKind::BrokenPipe,
+                ..
+            })
+        ));
+        assert_eq!(1, writer.writes);
+    }
+
+    #[tokio::test]
+    async fn test_export_transactions_ndjson_fails_on_request_error() {
+        // Nothing listens on the url: the first page fails and nothing is written.
+        let indexer = Indexer::new(UNREACHABLE_URL).unwrap();
+        let mut writer = vec![];
+        let res = indexer
+            .export_transactions_ndjson(&QueryTransaction::default(), &mut writer)
+            .await;
+        assert!(res.is_err());
+        assert!(writer.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_transaction_volume_of_empty_range() {
+        // The range is empty, so nothing is requested from the (unreachable) indexer.
+        let indexer = Indexer::new(UNREACHABLE_URL).unwrap();
+        let volume = indexer
+            .transaction_volume(
+                &QueryTransaction::default(),
+                BucketSize::Day,
+                Round(10),
+                Round(9),
+            )
+            .await
+            .unwrap();
+        assert!(volume.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_transaction_volume_up_to_a_future_round() {
+        let header = |round: u64, timestamp: u64| {
+            TestResponse::json(
+                serde_json::json!({
+                    "genesis-hash": "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=",
+                    "genesis-id": "testnet-v1.0",
+                    "previous-block-hash": "VC2p6EsSBVbH1RkUEdIrPgkWHGWhP5h2Gw9SPwxeJYU=",
+                    "round": round,
+                    "seed": "Yq2+dQhVyrqRTuxYJnB42S9n3Wl8Em8GjRwbSCE+ZKc=",
+                    "timestamp": timestamp,
+                    "transactions-root": "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=",
+                })
+                .to_string(),
+            )
+        };
+        let transaction = |id: &str, round_time: u64| {
+            serde_json::json!({
+                "confirmed-round": 100,
+                "fee": 1000,
+                "first-valid": 1,
+                "genesis-hash": "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=",
+                "id": id,
+                "last-valid": 1001,
+                "round-time": round_time,
+                "sender": "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU",
+                "tx-type": "pay",
+            })
+        };
+        let server = TestServer::serve(vec![
+            TestResponse::json(
+                r#"{"db-available":true,"is-migrating":false,"message":"105","round":105}"#,
+            ),
+            header(100, 7_200),
+            header(105, 14_400),
+            TestResponse::json(
+                serde_json::json!({
+                    "current-round": 105,
+                    "transactions": [transaction("A", 7_300), transaction("B", 14_400)],
+                })
+                .to_string(),
+            ),
+        ]);
+        let indexer = Indexer::new(server.url()).unwrap();
+
+        let volume = indexer
+            .transaction_volume(
+                &QueryTransaction::default(),
+                BucketSize::Hour,
+                Round(100),
+                Round(200),
+            )
+            .await
+            .unwrap();
+
+        let hour = |hour: i64| DateTime::from_timestamp(hour * 3600, 0).unwrap();
+        assert_eq!(vec![(hour(2), 1), (hour(3), 0), (hour(4), 1)], volume);
+        let requests: Vec<String> = server
+            .received()
+            .into_iter()
+            .map(|request| request.line)
+            .collect();
+        assert_eq!(
+            vec![
+                "GET /health HTTP/1.1",
+                "GET /v2/blocks/100?header-only=true HTTP/1.1",
+                "GET /v2/blocks/105?header-only=true HTTP/1.1",
+                "GET /v2/transactions?max-round=105&min-round=100 HTTP/1.1",
+            ],
+            requests
+        );
+
+        // the range starts after the latest round
+        let server = TestServer::serve(vec![TestResponse::json(
+            r#"{"db-available":true,"is-migrating":false,"message":"105","round":105}"#,
+        )]);
+        let indexer = Indexer::new(server.url()).unwrap();
+        let volume = indexer
+            .transaction_volume(
+                &QueryTransaction::default(),
+                BucketSize::Hour,
+                Round(106),
+                Round(200),
+            )
+            .await
+            .unwrap();
+        assert!(volume.is_empty());
+    }
+
+    #[test]
+    fn test_suggested_params_from_block() {
+        // Header of a testnet block, without transactions.
+        let block: Block = serde_json::from_str(
+            r#"{
+                "genesis-hash": "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=",
+                "genesis-id": "testnet-v1.0",
+                "previous-block-hash": "VC2p6EsSBVbH1RkUEdIrPgkWHGWhP5h2Gw9SPwxeJYU=",
+                "rewards": {
+                    "fee-sink": "A7NMWS3NT3IUDMLVO26ULGXGIIOUQ3ND2TXSER6EBGRZNOBOUIQXHIBGDE",
+                    "rewards-calculation-round": 26000000,
+                    "rewards-level": 218288,
+                    "rewards-pool": "7777777777777777777777777777777777777777777777777774MSJUVU",
+                    "rewards-rate": 0,
+                    "rewards-residue": 6886250026
+                },
+                "round": 25999125,
+                "seed": "Yq2+dQhVyrqRTuxYJnB42S9n3Wl8Em8GjRwbSCE+ZKc=",
+                "timestamp": 1668104440,
+                "transactions": [],
+                "transactions-root": "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=",
+                "txn-counter": 59262316,
+                "upgrade-state": {
+                    "current-protocol": "https://github.com/algorandfoundation/specs/tree/44fa607d6051730f5264526bf3c108d51f0eadb6",
+                    "next-protocol-approvals": 0,
+                    "next-protocol-switch-on": 0,
+                    "next-protocol-vote-before": 0
+                },
+                "upgrade-vote": {
+                    "upgrade-approve": false,
+                    "upgrade-delay": 0
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let params = suggested_params_from_block(&block);
+
+        assert_eq!("testnet-v1.0", params.genesis_id);
+        assert_eq!(
+            Network::TestNet,
+            Network::from_genesis_hash(&params.genesis_hash.into())
+        );
+        assert_eq!(
+            "https://github.com/algorandfoundation/specs/tree/44fa607d6051730f5264526bf3c108d51f0eadb6",
+            params.consensus_version
+        );
+        assert_eq!(MicroAlgos(0), params.fee_per_byte);
+        assert_eq!(MicroAlgos(1000), params.min_fee);
+        assert_eq!(Round(25999125), params.first_valid);
+        assert_eq!(Round(25999125 + 1000), params.last_valid);
+    }
 }
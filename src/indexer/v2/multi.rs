@@ -0,0 +1,428 @@
+use algonaut_core::Address;
+use algonaut_model::indexer::v2::{
+    AccountInfoResponse, ApplicationInfoResponse, AssetsInfoResponse, QueryAccountInfo,
+    QueryApplicationInfo, QueryAssetsInfo, QueryTransaction, TransactionInfoResponse,
+    TransactionResponse,
+};
+use futures_util::future::join_all;
+use std::future::Future;
+
+use super::Indexer;
+use crate::error::AlgonautError;
+
+/// How [MultiIndexer] routes the queries among its indexers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoutingStrategy {
+    /// Queries the first indexer, in the order given, whose health check succeeds.
+    FirstHealthy,
+    /// Checks the health of all the indexers concurrently and queries the one that processed the
+    /// highest round (the first one in the order given on a tie).
+    MostAdvancedRound,
+    /// Queries all the indexers concurrently and returns the response of at least this many of
+    /// them agreeing, failing with [AlgonautError::QuorumNotReached] otherwise.
+    Quorum(usize),
+}
+
+/// Several indexers (e.g. of different providers, for redundancy), routing the queries according
+/// to a [RoutingStrategy].
+///
+/// With [RoutingStrategy::FirstHealthy] and [RoutingStrategy::MostAdvancedRound], each query
+/// checks the health of the indexers before being sent to one of them, i.e. costs extra requests:
+/// a query failing on the selected indexer isn't retried on the others.
+///
+/// With [RoutingStrategy::Quorum], the responses are compared without the round at which they
+/// were computed (`current-round`), which differs between indexers at different heights: pin the
+/// round of the queries that support it (e.g. [QueryAccountInfo::round]) to compare the same
+/// state. A lookup not found (404) agrees with the other lookups not found, so a quorum of them
+/// fails with [AlgonautError::NotFound]; the other errors never agree.
+#[derive(Debug, Clone)]
+pub struct MultiIndexer {
+    indexers: Vec<Indexer>,
+    strategy: RoutingStrategy,
+}
+
+impl MultiIndexer {
+    /// Routes the queries among these indexers with this strategy.
+    ///
+    /// Fails with [AlgonautError::BadQuery] without indexers, or with a quorum of 0 or of more
+    /// indexers than given.
+    pub fn new(
+        indexers: Vec<Indexer>,
+        strategy: RoutingStrategy,
+    ) -> Result<MultiIndexer, AlgonautError> {
+        if indexers.is_empty() {
+            return Err(AlgonautError::BadQuery {
+                reason: "no indexers".to_owned(),
+                param: Some("indexers"),
+            });
+        }
+        if let RoutingStrategy::Quorum(quorum) = strategy {
+            if quorum == 0 || quorum > indexers.len() {
+                return Err(AlgonautError::BadQuery {
+                    reason: format!("quorum of {} out of {} indexers", quorum, indexers.len()),
+                    param: Some("quorum"),
+                });
+            }
+        }
+        Ok(MultiIndexer { indexers, strategy })
+    }
+
+    /// The indexers, in the order given.
+    pub fn indexers(&self) -> &[Indexer] {
+        &self.indexers
+    }
+
+    /// The strategy routing the queries.
+    pub fn strategy(&self) -> RoutingStrategy {
+        self.strategy
+    }
+
+    /// Runs `query` on the indexers selected by the strategy, e.g. for the queries without a
+    /// method here. With [RoutingStrategy::Quorum], the responses agree if they're equal.
+    pub async fn query<'a, T, F, Fut>(&'a self, query: F) -> Result<T, AlgonautError>
+    where
+        T: PartialEq,
+        F: Fn(&'a Indexer) -> Fut,
+        Fut: Future<Output = Result<T, AlgonautError>>,
+    {
+        self.query_by(query, |a, b| a == b).await
+    }
+
+    /// [query](Self::query), with [RoutingStrategy::Quorum] comparing the responses with `agree`,
+    /// e.g. to ignore the fields specific to each indexer.
+    pub async fn query_by<'a, T, F, Fut, A>(
+        &'a self,
+        query: F,
+        agree: A,
+    ) -> Result<T, AlgonautError>
+    where
+        F: Fn(&'a Indexer) -> Fut,
+        Fut: Future<Output = Result<T, AlgonautError>>,
+        A: Fn(&T, &T) -> bool,
+    {
+        match self.strategy {
+            RoutingStrategy::FirstHealthy => query(self.first_healthy().await?).await,
+            RoutingStrategy::MostAdvancedRound => query(self.most_advanced().await?).await,
+            RoutingStrategy::Quorum(quorum) => {
+                let results = join_all(self.indexers.iter().map(query)).await;
+                agreed(results, quorum, agree)
+            }
+        }
+    }
+
+    /// [Indexer::account_info] on the indexers selected by the strategy.
+    pub async fn account_info(
+        &self,
+        address: &Address,
+        query: &QueryAccountInfo,
+    ) -> Result<AccountInfoResponse, AlgonautError> {
+        self.query_by(
+            |indexer| indexer.account_info(address, query),
+            |a, b| a.account == b.account,
+        )
+        .await
+    }
+
+    /// [Indexer::application_info] on the indexers selected by the strategy.
+    pub async fn application_info(
+        &self,
+        id: u64,
+        query: &QueryApplicationInfo,
+    ) -> Result<ApplicationInfoResponse, AlgonautError> {
+        self.query_by(
+            |indexer| indexer.application_info(id, query),
+            |a, b| a.application == b.application,
+        )
+        .await
+    }
+
+    /// [Indexer::assets_info] on the indexers selected by the strategy.
+    pub async fn assets_info(
+        &self,
+        id: u64,
+        query: &QueryAssetsInfo,
+    ) -> Result<AssetsInfoResponse, AlgonautError> {
+        self.query_by(
+            |indexer| indexer.assets_info(id, query),
+            |a, b| a.asset == b.asset,
+        )
+        .await
+    }
+
+    /// [Indexer::transaction_info] on the indexers selected by the strategy.
+    pub async fn transaction_info(
+        &self,
+        id: &str,
+    ) -> Result<TransactionInfoResponse, AlgonautError> {
+        self.query_by(
+            |indexer| indexer.transaction_info(id),
+            |a, b| a.transaction == b.transaction,
+        )
+        .await
+    }
+
+    /// [Indexer::transactions] on the indexers selected by the strategy. With
+    /// [RoutingStrategy::Quorum], the pages agree if they have the same transactions: bound the
+    /// rounds of the query (e.g. [QueryTransaction::max_round]) to compare the same history.
+    pub async fn transactions(
+        &self,
+        query: &QueryTransaction,
+    ) -> Result<TransactionResponse, AlgonautError> {
+        self.query_by(
+            |indexer| indexer.transactions(query),
+            |a, b| a.transactions == b.transactions,
+        )
+        .await
+    }
+
+    /// The first indexer whose health check succeeds.
+    async fn first_healthy(&self) -> Result<&Indexer, AlgonautError> {
+        let mut last_error = None;
+        for indexer in &self.indexers {
+            match indexer.health_check().await {
+                Ok(_) => return Ok(indexer),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(self.no_healthy_indexer(last_error))
+    }
+
+    /// The healthy indexer that processed the highest round.
+    async fn most_advanced(&self) -> Result<&Indexer, AlgonautError> {
+        let health = join_all(self.indexers.iter().map(|indexer| indexer.health_check())).await;
+        let mut most_advanced: Option<(&Indexer, u64)> = None;
+        let mut last_error = None;
+        for (indexer, health) in self.indexers.iter().zip(health) {
+            match health {
+                Ok(health) if most_advanced.is_none_or(|(_, round)| health.round.0 > round) => {
+                    most_advanced = Some((indexer, health.round.0))
+                }
+                Ok(_) => {}
+                Err(e) => last_error = Some(e),
+            }
+        }
+        most_advanced
+            .map(|(indexer, _)| indexer)
+            .ok_or_else(|| self.no_healthy_indexer(last_error))
+    }
+
+    fn no_healthy_indexer(&self, last_error: Option<AlgonautError>) -> AlgonautError {
+        AlgonautError::NoHealthyIndexer {
+            count: self.indexers.len(),
+            last_error: last_error.map(|e| e.to_string()).unwrap_or_default(),
+        }
+    }
+}
+
+/// The result of the largest group of agreeing `results` (the first one on a tie), if at least
+/// `quorum` results agree.
+fn agreed<T>(
+    results: Vec<Result<T, AlgonautError>>,
+    quorum: usize,
+    agree: impl Fn(&T, &T) -> bool,
+) -> Result<T, AlgonautError> {
+    let responses = results.len();
+    let same = |a: &Result<T, AlgonautError>, b: &Result<T, AlgonautError>| match (a, b) {
+        (Ok(a), Ok(b)) => agree(a, b),
+        (Err(a), Err(b)) => a.is_404() && b.is_404(),
+        _ => false,
+    };
+    let (index, agreeing) = results
+        .iter()
+        .enumerate()
+        .map(|(index, result)| {
+            (
+                index,
+                results.iter().filter(|other| same(result, other)).count(),
+            )
+        })
+        .fold((0, 0), |best, candidate| {
+            if candidate.1 > best.1 {
+                candidate
+            } else {
+                best
+            }
+        });
+    if agreeing < quorum {
+        return Err(AlgonautError::QuorumNotReached {
+            quorum,
+            agreeing,
+            responses,
+        });
+    }
+    results
+        .into_iter()
+        .nth(index)
+        .unwrap_or_else(|| Err(AlgonautError::Internal("empty quorum".to_owned())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use algonaut_client::test_server::{TestResponse, TestServer, UNREACHABLE_URL};
+
+    const ADDRESS: &str = "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU";
+
+    /// Serves an indexer that processed `round`, with the account of any address holding `amount`
+    /// microAlgos.
+    fn serve(round: u64, amount: u64) -> Indexer {
+        let server = TestServer::handle(move |request| {
+            let path = request.line.split(' ').nth(1).unwrap_or_default();
+            if path == "/health" {
+                TestResponse::json(
+                    serde_json::json!({
+                        "db-available": true,
+                        "is-migrating": false,
+                        "message": round.to_string(),
+                        "round": round,
+                    })
+                    .to_string(),
+                )
+            } else if path.starts_with(&format!("/v2/accounts/{}", ADDRESS)) {
+                TestResponse::json(
+                    serde_json::json!({
+                        "account": {
+                            "address": ADDRESS,
+                            "amount": amount,
+                            "amount-without-pending-rewards": amount,
+                            "pending-rewards": 0,
+                            "rewards": 0,
+                            "round": round,
+                            "status": "Offline",
+                        },
+                        "current-round": round,
+                    })
+                    .to_string(),
+                )
+            } else {
+                TestResponse::json(r#"{"message":"no accounts found for address"}"#)
+                    .status("404 Not Found")
+            }
+        });
+        Indexer::new(server.url()).unwrap()
+    }
+
+    /// An indexer refusing the connections.
+    fn down() -> Indexer {
+        Indexer::new(UNREACHABLE_URL).unwrap()
+    }
+
+    async fn amount(multi: &MultiIndexer, address: &str) -> Result<u64, AlgonautError> {
+        let info = multi
+            .account_info(&address.parse().unwrap(), &QueryAccountInfo::default())
+            .await?;
+        Ok(info.account.amount.0)
+    }
+
+    #[test]
+    fn test_new_checks_the_quorum() {
+        assert!(matches!(
+            MultiIndexer::new(vec![], RoutingStrategy::FirstHealthy),
+            Err(AlgonautError::BadQuery {
+                param: Some("indexers"),
+                ..
+            })
+        ));
+        let indexers = vec![down(), down()];
+        assert!(MultiIndexer::new(indexers.clone(), RoutingStrategy::Quorum(2)).is_ok());
+        for quorum in [0, 3] {
+            assert!(matches!(
+                MultiIndexer::new(indexers.clone(), RoutingStrategy::Quorum(quorum)),
+                Err(AlgonautError::BadQuery {
+                    param: Some("quorum"),
+                    ..
+                })
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_first_healthy() {
+        let multi = MultiIndexer::new(
+            vec![down(), serve(10, 1), serve(20, 2)],
+            RoutingStrategy::FirstHealthy,
+        )
+        .unwrap();
+
+        assert_eq!(1, amount(&multi, ADDRESS).await.unwrap());
+
+        let multi = MultiIndexer::new(vec![down(), down()], RoutingStrategy::FirstHealthy).unwrap();
+        assert!(matches!(
+            amount(&multi, ADDRESS).await,
+            Err(AlgonautError::NoHealthyIndexer { count: 2, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_most_advanced_round() {
+        let multi = MultiIndexer::new(
+            vec![
+                serve(10, 1),
+                down(),
+                serve(30, 3),
+                serve(20, 2),
+                serve(30, 4),
+            ],
+            RoutingStrategy::MostAdvancedRound,
+        )
+        .unwrap();
+
+        assert_eq!(3, amount(&multi, ADDRESS).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_quorum_ignores_the_current_round() {
+        let multi = MultiIndexer::new(
+            vec![serve(10, 1), serve(20, 2), down(), serve(30, 2)],
+            RoutingStrategy::Quorum(2),
+        )
+        .unwrap();
+
+        // the account of the indexers at rounds 20 and 30 have a different round
+        let address = ADDRESS.parse().unwrap();
+        let query = QueryAccountInfo::default();
+        let res = multi
+            .query_by(
+                |indexer| indexer.account_info(&address, &query),
+                |a, b| a.account.amount == b.account.amount,
+            )
+            .await
+            .unwrap();
+        assert_eq!(20, res.current_round.0);
+    }
+
+    #[tokio::test]
+    async fn test_quorum_fails_on_disagreement() {
+        let multi = MultiIndexer::new(
+            vec![serve(10, 1), serve(10, 2), down()],
+            RoutingStrategy::Quorum(2),
+        )
+        .unwrap();
+
+        assert_eq!(
+            Err(AlgonautError::QuorumNotReached {
+                quorum: 2,
+                agreeing: 1,
+                responses: 3
+            }),
+            amount(&multi, ADDRESS).await
+        );
+    }
+
+    #[tokio::test]
+    async fn test_quorum_of_not_found() {
+        let multi = MultiIndexer::new(
+            vec![serve(10, 1), serve(10, 1), serve(10, 1)],
+            RoutingStrategy::Quorum(2),
+        )
+        .unwrap();
+
+        let res = amount(
+            &multi,
+            "4MYUHDWHWXAKA5KA7U5PEN646VYUANBFXVJNONBK3TIMHEMWMD4UBOJBI4",
+        )
+        .await;
+
+        assert!(matches!(res, Err(AlgonautError::NotFound { .. })));
+    }
+}
@@ -0,0 +1,164 @@
+use algonaut_model::indexer::v2::{
+    AccountTransactionResponse, Application, ApplicationResponse, AssetTransactionResponse,
+    BalancesResponse, MiniAssetHolding, QueryAccountTransaction, QueryApplications,
+    QueryAssetTransaction, QueryBalances, QueryTransaction, Transaction, TransactionResponse,
+};
+use futures_util::{stream, Stream, TryStreamExt};
+use std::future::Future;
+
+use crate::error::AlgonautError;
+
+/// A search of the indexer paginated with the `next` token of its responses.
+pub(crate) trait PagedQuery: Clone {
+    fn set_next(&mut self, next: String);
+}
+
+/// A page of the results of a [PagedQuery].
+pub(crate) trait Page {
+    type Item;
+
+    /// The results of the page, and the token of the next page if any.
+    fn into_items(self) -> (Vec<Self::Item>, Option<String>);
+}
+
+macro_rules! paged {
+    ($($query:ty => $page:ty { $items:ident: $item:ty }),* $(,)?) => {
+        $(
+            impl PagedQuery for $query {
+                fn set_next(&mut self, next: String) {
+                    self.next = Some(next);
+                }
+            }
+
+            impl Page for $page {
+                type Item = $item;
+
+                fn into_items(self) -> (Vec<$item>, Option<String>) {
+                    (self.$items, self.next_token)
+                }
+            }
+        )*
+    };
+}
+
+paged!(
+    QueryTransaction => TransactionResponse { transactions: Transaction },
+    QueryAccountTransaction => AccountTransactionResponse { transactions: Transaction },
+    QueryAssetTransaction => AssetTransactionResponse { transactions: Transaction },
+    QueryApplications => ApplicationResponse { applications: Application },
+    QueryBalances => BalancesResponse { balances: MiniAssetHolding },
+);
+
+/// Streams the pages of `query`, fetched with `fetch` as the stream is consumed, from the page of
+/// `query.next`, if set.
+///
+/// A page without results or without a next token is the last one. Errors end the stream.
+pub(crate) fn pages<'a, Q, P, F, Fut>(
+    query: Q,
+    fetch: F,
+) -> impl Stream<Item = Result<Vec<P::Item>, AlgonautError>> + 'a
+where
+    Q: PagedQuery + 'a,
+    P: Page + 'a,
+    F: Fn(Q) -> Fut + 'a,
+    Fut: Future<Output = Result<P, AlgonautError>> + 'a,
+{
+    stream::try_unfold((Some(query), fetch), |(query, fetch)| async move {
+        let mut query = match query {
+            Some(query) => query,
+            None => return Ok(None),
+        };
+        let (items, next) = fetch(query.clone()).await?.into_items();
+        let next_query = match next {
+            Some(next) if !items.is_empty() => {
+                query.set_next(next);
+                Some(query)
+            }
+            _ => None,
+        };
+        Ok(Some((items, (next_query, fetch))))
+    })
+}
+
+/// Streams the results of the [pages] of `query`, one by one.
+pub(crate) fn paginate<'a, Q, P, F, Fut>(
+    query: Q,
+    fetch: F,
+) -> impl Stream<Item = Result<P::Item, AlgonautError>> + 'a
+where
+    Q: PagedQuery + 'a,
+    P: Page + 'a,
+    F: Fn(Q) -> Fut + 'a,
+    Fut: Future<Output = Result<P, AlgonautError>> + 'a,
+{
+    pages(query, fetch)
+        .map_ok(|items| stream::iter(items.into_iter().map(Ok)))
+        .try_flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    fn page(balances: &[u64], next_token: Option<&str>) -> BalancesResponse {
+        BalancesResponse {
+            balances: balances
+                .iter()
+                .map(|amount| MiniAssetHolding {
+                    address: "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU"
+                        .to_owned(),
+                    amount: *amount,
+                    deleted: None,
+                    is_frozen: false,
+                    opted_in_at_round: None,
+                    opted_out_at_round: None,
+                })
+                .collect(),
+            current_round: 10,
+            next_token: next_token.map(|t| t.to_owned()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_paginate_follows_next_tokens() {
+        let requested = RefCell::new(vec![]);
+        let amounts: Vec<u64> = paginate(QueryBalances::default(), |query| {
+            requested.borrow_mut().push(query.next.clone());
+            let response = match query.next.as_deref() {
+                None => page(&[1, 2], Some("b")),
+                Some("b") => page(&[3], Some("c")),
+                _ => page(&[], Some("d")),
+            };
+            async move { Ok(response) }
+        })
+        .map_ok(|holding| holding.amount)
+        .try_collect()
+        .await
+        .unwrap();
+        assert_eq!(vec![1, 2, 3], amounts);
+        assert_eq!(
+            vec![None, Some("b".to_owned()), Some("c".to_owned())],
+            requested.into_inner()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pages_end_without_next_token() {
+        let pages: Vec<Vec<MiniAssetHolding>> =
+            pages(QueryBalances::default(), |_| async { Ok(page(&[1], None)) })
+                .try_collect()
+                .await
+                .unwrap();
+        assert_eq!(1, pages.len());
+    }
+
+    #[tokio::test]
+    async fn test_pages_end_on_error() {
+        let mut pages = Box::pin(pages(QueryBalances::default(), |_| async {
+            Err::<BalancesResponse, _>(AlgonautError::Internal("unavailable".to_owned()))
+        }));
+        assert!(pages.try_next().await.is_err());
+        assert!(pages.try_next().await.unwrap().is_none());
+    }
+}
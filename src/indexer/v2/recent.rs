@@ -0,0 +1,143 @@
+use algonaut_core::{Round, RoundRange};
+use algonaut_model::indexer::v2::{QueryTransaction, Transaction};
+use std::cmp::Reverse;
+
+/// Rounds of the first window of [Indexer::transactions_recent](super::Indexer::transactions_recent)
+/// (about an hour of blocks).
+pub(super) const FIRST_WINDOW_ROUNDS: u64 = 1000;
+
+/// Maximum rounds of a window, bounding the growth of the windows over a sparse history.
+pub(super) const MAX_WINDOW_ROUNDS: u64 = 1_000_000;
+
+/// The next window of [Indexer::transactions_recent](super::Indexer::transactions_recent): the
+/// window of `size` rounds ending at `upper`, `floor` being the oldest round to fetch.
+pub(super) struct NextWindow {
+    pub(super) upper: Round,
+    pub(super) floor: Round,
+    pub(super) size: u64,
+}
+
+/// Whether the indexer returns the transactions of the query newest-first: it does for the
+/// searches by address, and returns the other ones oldest-first.
+pub(super) fn is_newest_first(query: &QueryTransaction) -> bool {
+    query.address.is_some()
+}
+
+/// The rounds to page backward from `current_round`, or none if they're empty: the range of the
+/// query (`min-round`, `max-round` and `round`), capped at the current round.
+pub(super) fn rounds(query: &QueryTransaction, current_round: Round) -> Option<RoundRange> {
+    let mut floor = query.min_round.unwrap_or(Round(0));
+    let mut upper = query
+        .max_round
+        .map_or(current_round, |max| max.min(current_round));
+    if let Some(round) = query.round {
+        floor = floor.max(Round(round));
+        upper = upper.min(Round(round));
+    }
+    (floor <= upper).then(|| RoundRange::new(floor, upper))
+}
+
+/// The window of at most `size` rounds ending at `upper`, not starting before `floor`.
+pub(super) fn window(upper: Round, floor: Round, size: u64) -> RoundRange {
+    let min = upper.0.saturating_sub(size.max(1) - 1).max(floor.0);
+    RoundRange::new(Round(min), upper)
+}
+
+/// The size of the window following one of `size` rounds with `found` transactions: doubled
+/// after an empty window, so that a sparse history takes fewer requests.
+pub(super) fn next_window_size(size: u64, found: usize) -> u64 {
+    if found == 0 {
+        size.saturating_mul(2).min(MAX_WINDOW_ROUNDS)
+    } else {
+        size
+    }
+}
+
+/// Sorts the transactions of a window newest-first, by round then by offset in the round.
+pub(super) fn sort_newest_first(transactions: &mut [Transaction]) {
+    transactions.sort_by_key(|t| Reverse((t.confirmed_round, t.intra_round_offset)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rounds() {
+        let query = QueryTransaction::default();
+        assert_eq!(
+            Some(RoundRange::new(Round(0), Round(1500))),
+            rounds(&query, Round(1500))
+        );
+
+        let query = QueryTransaction::default().with_round_range(RoundRange::new(100, 2000));
+        assert_eq!(
+            Some(RoundRange::new(Round(100), Round(1500))),
+            rounds(&query, Round(1500))
+        );
+
+        let query = QueryTransaction {
+            round: Some(700),
+            ..QueryTransaction::default()
+        };
+        assert_eq!(
+            Some(RoundRange::new(Round(700), Round(700))),
+            rounds(&query, Round(1500))
+        );
+
+        let query = QueryTransaction {
+            min_round: Some(Round(2000)),
+            ..QueryTransaction::default()
+        };
+        assert_eq!(None, rounds(&query, Round(1500)));
+    }
+
+    #[test]
+    fn test_window() {
+        assert_eq!(
+            RoundRange::new(Round(501), Round(1500)),
+            window(Round(1500), Round(0), 1000)
+        );
+        assert_eq!(
+            RoundRange::new(Round(0), Round(500)),
+            window(Round(500), Round(0), 1000)
+        );
+        assert_eq!(
+            RoundRange::new(Round(1200), Round(1500)),
+            window(Round(1500), Round(1200), 1000)
+        );
+        assert_eq!(
+            RoundRange::new(Round(7), Round(7)),
+            window(Round(7), Round(0), 1)
+        );
+    }
+
+    #[test]
+    fn test_next_window_size() {
+        assert_eq!(1000, next_window_size(1000, 3));
+        assert_eq!(2000, next_window_size(1000, 0));
+        assert_eq!(MAX_WINDOW_ROUNDS, next_window_size(MAX_WINDOW_ROUNDS, 0));
+    }
+
+    #[test]
+    fn test_sort_newest_first() {
+        let transaction = |round: u64, offset: u64| -> Transaction {
+            serde_json::from_str(&format!(
+                r#"{{"confirmed-round":{},"fee":1000,"first-valid":1,"genesis-hash":"SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=","id":"{}-{}","intra-round-offset":{},"last-valid":1000,
+                "sender":"47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU","tx-type":"pay"}}"#,
+                round, round, offset, offset
+            ))
+            .unwrap()
+        };
+        let mut transactions = vec![
+            transaction(600, 0),
+            transaction(600, 1),
+            transaction(1400, 0),
+        ];
+
+        sort_newest_first(&mut transactions);
+
+        let ids: Vec<_> = transactions.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(vec!["1400-0", "600-1", "600-0"], ids);
+    }
+}
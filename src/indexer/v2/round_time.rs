@@ -0,0 +1,53 @@
+use algonaut_core::Round;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+/// The maximum number of rounds whose time is kept: past it, the lowest rounds are forgotten.
+const CAPACITY: usize = 100_000;
+
+/// The times (unix seconds) of the rounds, from the blocks fetched by an indexer and its clones.
+///
+/// The time of a round never changes, so the entries are never stale: they're only forgotten to
+/// bound the memory, the lowest rounds first.
+#[derive(Debug, Default)]
+pub(crate) struct RoundTimes {
+    times: Mutex<BTreeMap<Round, u64>>,
+}
+
+impl RoundTimes {
+    pub(crate) fn get(&self, round: Round) -> Option<u64> {
+        self.lock().get(&round).copied()
+    }
+
+    pub(crate) fn insert(&self, round: Round, time: u64) {
+        let mut times = self.lock();
+        times.insert(round, time);
+        while times.len() > CAPACITY {
+            times.pop_first();
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<Round, u64>> {
+        // The map can't be left inconsistent by a panic: recover it.
+        self.times
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forgets_the_lowest_rounds() {
+        let times = RoundTimes::default();
+        for round in (0..=CAPACITY as u64).rev() {
+            times.insert(Round(round), round + 1);
+        }
+
+        assert_eq!(None, times.get(Round(0)));
+        assert_eq!(Some(2), times.get(Round(1)));
+        assert_eq!(Some(CAPACITY as u64 + 1), times.get(Round(CAPACITY as u64)));
+    }
+}
@@ -0,0 +1,90 @@
+use algonaut_core::Round;
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+use std::{sync::Mutex, time::Instant};
+
+/// How long the round reported by a health check is trusted by the minimum round guard.
+const TTL: Duration = Duration::from_secs(2);
+
+/// The latest round the indexer reported as processed, by the health checks of an indexer and its
+/// clones, cached briefly so that the minimum round guard doesn't need a health check per query.
+///
+/// The round expires: the indexer may be reset, or be a replica behind a load balancer lagging
+/// behind the others. On WASM, where there's no clock, it isn't cached.
+#[derive(Debug)]
+pub(crate) struct SeenRound {
+    ttl: Duration,
+    #[cfg(not(target_arch = "wasm32"))]
+    seen: Mutex<Option<(Round, Instant)>>,
+}
+
+impl Default for SeenRound {
+    fn default() -> Self {
+        SeenRound::with_ttl(TTL)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl SeenRound {
+    fn with_ttl(ttl: Duration) -> Self {
+        SeenRound {
+            ttl,
+            seen: Mutex::default(),
+        }
+    }
+
+    /// The round reported by the latest health check, if it hasn't expired.
+    pub(crate) fn get(&self) -> Option<Round> {
+        let seen = *self.lock();
+        seen.filter(|(_, at)| at.elapsed() < self.ttl)
+            .map(|(round, _)| round)
+    }
+
+    pub(crate) fn set(&self, round: Round) {
+        *self.lock() = Some((round, Instant::now()));
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<(Round, Instant)>> {
+        // The value can't be left inconsistent by a panic: recover it.
+        self.seen
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl SeenRound {
+    fn with_ttl(ttl: Duration) -> Self {
+        SeenRound { ttl }
+    }
+
+    pub(crate) fn get(&self) -> Option<Round> {
+        let _ = self.ttl;
+        None
+    }
+
+    pub(crate) fn set(&self, _round: Round) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keeps_the_latest_round() {
+        let seen = SeenRound::default();
+        assert_eq!(None, seen.get());
+
+        seen.set(Round(10));
+        seen.set(Round(8));
+        assert_eq!(Some(Round(8)), seen.get());
+    }
+
+    #[test]
+    fn test_round_expires() {
+        let seen = SeenRound::with_ttl(Duration::ZERO);
+        seen.set(Round(10));
+
+        assert_eq!(None, seen.get());
+    }
+}
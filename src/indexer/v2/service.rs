@@ -0,0 +1,330 @@
+use algonaut_core::{Address, MicroAlgos, Network, Round, SuggestedTransactionParams};
+use algonaut_model::indexer::v2::{
+    AccountInfoResponse, AccountResponse, AccountTransactionResponse, ApplicationInfoResponse,
+    ApplicationResponse, AssetResponse, AssetTransactionResponse, AssetsInfoResponse,
+    BalancesResponse, Block, HealthCheck, QueryAccount, QueryAccountInfo, QueryAccountTransaction,
+    QueryApplicationInfo, QueryApplications, QueryAssetTransaction, QueryAssets, QueryAssetsInfo,
+    QueryBalances, QueryTransaction, TransactionInfoResponse, TransactionResponse,
+};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use super::Indexer;
+use crate::error::AlgonautError;
+
+/// The queries of [Indexer], as a trait.
+///
+/// Write code against `impl IndexerService` (or `&dyn IndexerService`) to be able to substitute
+/// the indexer in tests, e.g. with the `MockIndexer` of the `testing` feature.
+/// See the methods of [Indexer] for the documentation of each query.
+///
+/// The trait has the queries of the indexer, not the helpers of [Indexer] built on them:
+/// [Indexer::asset_holders_above], [Indexer::is_asset_distributed], [Indexer::asset_holder_count],
+/// [Indexer::asset_transactions_for_account], [Indexer::applications_by_creator],
+/// [Indexer::transaction_volume], [Indexer::transactions_by_ids], [Indexer::transaction_info_batch],
+/// [Indexer::account_info_at_rounds], [Indexer::group_transactions], [Indexer::transactions_borrowed],
+/// [Indexer::transactions_recent], [Indexer::export_transactions_ndjson] and [Indexer::round_time]
+/// aren't part of it: they're built on [assets_info](Self::assets_info),
+/// [asset_balances](Self::asset_balances), [asset_transactions](Self::asset_transactions),
+/// [applications](Self::applications), [transactions](Self::transactions),
+/// [transaction_info](Self::transaction_info), [account_info](Self::account_info) and
+/// [block](Self::block), which can be used instead. Neither is
+/// [Indexer::block_with_format], which returns the same block as [block](Self::block), nor are the
+/// `_opt` lookups (e.g. [Indexer::transaction_info_opt]), the lookups failing with
+/// `AlgonautError::NotFound` instead of returning `None`, and [Indexer::application_params_only],
+/// a lighter [application_info](Self::application_info).
+// The client futures aren't Send with the WASM target, so neither are the ones of the trait.
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait IndexerService: Send + Sync {
+    async fn health(&self) -> Result<(), AlgonautError>;
+
+    async fn health_check(&self) -> Result<HealthCheck<serde_json::Value>, AlgonautError>;
+
+    async fn network(&self) -> Result<Network, AlgonautError>;
+
+    async fn suggested_params(&self) -> Result<SuggestedTransactionParams, AlgonautError>;
+
+    async fn accounts(&self, query: &QueryAccount) -> Result<AccountResponse, AlgonautError>;
+
+    async fn account_info(
+        &self,
+        address: &Address,
+        query: &QueryAccountInfo,
+    ) -> Result<AccountInfoResponse, AlgonautError>;
+
+    async fn account_transactions(
+        &self,
+        address: &Address,
+        query: &QueryAccountTransaction,
+    ) -> Result<AccountTransactionResponse, AlgonautError>;
+
+    async fn balance_history(
+        &self,
+        address: &Address,
+        from: Round,
+        to: Round,
+    ) -> Result<Vec<(Round, MicroAlgos)>, AlgonautError>;
+
+    async fn applications(
+        &self,
+        query: &QueryApplications,
+    ) -> Result<ApplicationResponse, AlgonautError>;
+
+    async fn application_info(
+        &self,
+        id: u64,
+        query: &QueryApplicationInfo,
+    ) -> Result<ApplicationInfoResponse, AlgonautError>;
+
+    async fn assets(&self, query: &QueryAssets) -> Result<AssetResponse, AlgonautError>;
+
+    async fn assets_info(
+        &self,
+        id: u64,
+        query: &QueryAssetsInfo,
+    ) -> Result<AssetsInfoResponse, AlgonautError>;
+
+    async fn asset_balances(
+        &self,
+        id: u64,
+        query: &QueryBalances,
+    ) -> Result<BalancesResponse, AlgonautError>;
+
+    async fn asset_transactions(
+        &self,
+        id: u64,
+        query: &QueryAssetTransaction,
+    ) -> Result<AssetTransactionResponse, AlgonautError>;
+
+    async fn block(&self, round: Round) -> Result<Block, AlgonautError>;
+
+    async fn transactions(
+        &self,
+        query: &QueryTransaction,
+    ) -> Result<TransactionResponse, AlgonautError>;
+
+    async fn transaction_info(&self, id: &str) -> Result<TransactionInfoResponse, AlgonautError>;
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl IndexerService for Indexer {
+    async fn health(&self) -> Result<(), AlgonautError> {
+        Indexer::health(self).await
+    }
+
+    async fn health_check(&self) -> Result<HealthCheck<serde_json::Value>, AlgonautError> {
+        Indexer::health_check(self).await
+    }
+
+    async fn network(&self) -> Result<Network, AlgonautError> {
+        Indexer::network(self).await
+    }
+
+    async fn suggested_params(&self) -> Result<SuggestedTransactionParams, AlgonautError> {
+        Indexer::suggested_params(self).await
+    }
+
+    async fn accounts(&self, query: &QueryAccount) -> Result<AccountResponse, AlgonautError> {
+        Indexer::accounts(self, query).await
+    }
+
+    async fn account_info(
+        &self,
+        address: &Address,
+        query: &QueryAccountInfo,
+    ) -> Result<AccountInfoResponse, AlgonautError> {
+        Indexer::account_info(self, address, query).await
+    }
+
+    async fn account_transactions(
+        &self,
+        address: &Address,
+        query: &QueryAccountTransaction,
+    ) -> Result<AccountTransactionResponse, AlgonautError> {
+        Indexer::account_transactions(self, address, query).await
+    }
+
+    async fn balance_history(
+        &self,
+        address: &Address,
+        from: Round,
+        to: Round,
+    ) -> Result<Vec<(Round, MicroAlgos)>, AlgonautError> {
+        Indexer::balance_history(self, address, from, to).await
+    }
+
+    async fn applications(
+        &self,
+        query: &QueryApplications,
+    ) -> Result<ApplicationResponse, AlgonautError> {
+        Indexer::applications(self, query).await
+    }
+
+    async fn application_info(
+        &self,
+        id: u64,
+        query: &QueryApplicationInfo,
+    ) -> Result<ApplicationInfoResponse, AlgonautError> {
+        Indexer::application_info(self, id, query).await
+    }
+
+    async fn assets(&self, query: &QueryAssets) -> Result<AssetResponse, AlgonautError> {
+        Indexer::assets(self, query).await
+    }
+
+    async fn assets_info(
+        &self,
+        id: u64,
+        query: &QueryAssetsInfo,
+    ) -> Result<AssetsInfoResponse, AlgonautError> {
+        Indexer::assets_info(self, id, query).await
+    }
+
+    async fn asset_balances(
+        &self,
+        id: u64,
+        query: &QueryBalances,
+    ) -> Result<BalancesResponse, AlgonautError> {
+        Indexer::asset_balances(self, id, query).await
+    }
+
+    async fn asset_transactions(
+        &self,
+        id: u64,
+        query: &QueryAssetTransaction,
+    ) -> Result<AssetTransactionResponse, AlgonautError> {
+        Indexer::asset_transactions(self, id, query).await
+    }
+
+    async fn block(&self, round: Round) -> Result<Block, AlgonautError> {
+        Indexer::block(self, round).await
+    }
+
+    async fn transactions(
+        &self,
+        query: &QueryTransaction,
+    ) -> Result<TransactionResponse, AlgonautError> {
+        Indexer::transactions(self, query).await
+    }
+
+    async fn transaction_info(&self, id: &str) -> Result<TransactionInfoResponse, AlgonautError> {
+        Indexer::transaction_info(self, id).await
+    }
+}
+
+/// Implements [IndexerService] for pointers to services, so that e.g. `&Indexer` or
+/// `Arc<dyn IndexerService>` can be passed where an `impl IndexerService` is expected.
+macro_rules! forward_indexer_service {
+    ($($pointer:ty),*) => {$(
+        #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+        #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+        impl<T: IndexerService + ?Sized> IndexerService for $pointer {
+            async fn health(&self) -> Result<(), AlgonautError> {
+                (**self).health().await
+            }
+
+            async fn health_check(&self) -> Result<HealthCheck<serde_json::Value>, AlgonautError> {
+                (**self).health_check().await
+            }
+
+            async fn network(&self) -> Result<Network, AlgonautError> {
+                (**self).network().await
+            }
+
+            async fn suggested_params(&self) -> Result<SuggestedTransactionParams, AlgonautError> {
+                (**self).suggested_params().await
+            }
+
+            async fn accounts(&self, query: &QueryAccount) -> Result<AccountResponse, AlgonautError> {
+                (**self).accounts(query).await
+            }
+
+            async fn account_info(
+                &self,
+                address: &Address,
+                query: &QueryAccountInfo,
+            ) -> Result<AccountInfoResponse, AlgonautError> {
+                (**self).account_info(address, query).await
+            }
+
+            async fn account_transactions(
+                &self,
+                address: &Address,
+                query: &QueryAccountTransaction,
+            ) -> Result<AccountTransactionResponse, AlgonautError> {
+                (**self).account_transactions(address, query).await
+            }
+
+            async fn balance_history(
+                &self,
+                address: &Address,
+                from: Round,
+                to: Round,
+            ) -> Result<Vec<(Round, MicroAlgos)>, AlgonautError> {
+                (**self).balance_history(address, from, to).await
+            }
+
+            async fn applications(
+                &self,
+                query: &QueryApplications,
+            ) -> Result<ApplicationResponse, AlgonautError> {
+                (**self).applications(query).await
+            }
+
+            async fn application_info(
+                &self,
+                id: u64,
+                query: &QueryApplicationInfo,
+            ) -> Result<ApplicationInfoResponse, AlgonautError> {
+                (**self).application_info(id, query).await
+            }
+
+            async fn assets(&self, query: &QueryAssets) -> Result<AssetResponse, AlgonautError> {
+                (**self).assets(query).await
+            }
+
+            async fn assets_info(
+                &self,
+                id: u64,
+                query: &QueryAssetsInfo,
+            ) -> Result<AssetsInfoResponse, AlgonautError> {
+                (**self).assets_info(id, query).await
+            }
+
+            async fn asset_balances(
+                &self,
+                id: u64,
+                query: &QueryBalances,
+            ) -> Result<BalancesResponse, AlgonautError> {
+                (**self).asset_balances(id, query).await
+            }
+
+            async fn asset_transactions(
+                &self,
+                id: u64,
+                query: &QueryAssetTransaction,
+            ) -> Result<AssetTransactionResponse, AlgonautError> {
+                (**self).asset_transactions(id, query).await
+            }
+
+            async fn block(&self, round: Round) -> Result<Block, AlgonautError> {
+                (**self).block(round).await
+            }
+
+            async fn transactions(
+                &self,
+                query: &QueryTransaction,
+            ) -> Result<TransactionResponse, AlgonautError> {
+                (**self).transactions(query).await
+            }
+
+            async fn transaction_info(&self, id: &str) -> Result<TransactionInfoResponse, AlgonautError> {
+                (**self).transaction_info(id).await
+            }
+        }
+    )*};
+}
+
+forward_indexer_service!(&T, Box<T>, Arc<T>);
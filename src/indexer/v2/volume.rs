@@ -0,0 +1,165 @@
+use chrono::{DateTime, Utc};
+use std::convert::TryFrom;
+
+/// The time interval grouping transactions in [Indexer::transaction_volume](super::Indexer::transaction_volume).
+///
+/// Buckets are aligned to the Unix epoch, so days start at midnight UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BucketSize {
+    Hour,
+    Day,
+}
+
+impl BucketSize {
+    fn seconds(self) -> u64 {
+        match self {
+            BucketSize::Hour => 60 * 60,
+            BucketSize::Day => 24 * 60 * 60,
+        }
+    }
+
+    /// The start of the bucket containing the unix `time`, in seconds.
+    fn start(self, time: u64) -> u64 {
+        time - time % self.seconds()
+    }
+}
+
+/// The transaction counts of the buckets from the one containing a start time to the one
+/// containing an end time, including the buckets without transactions.
+pub(crate) struct VolumeBuckets {
+    bucket: BucketSize,
+    /// Start of the first bucket, in unix seconds.
+    first: u64,
+    counts: Vec<u64>,
+}
+
+impl VolumeBuckets {
+    /// The buckets from the one containing `start_time` to the one containing `end_time` (unix
+    /// seconds), none if `start_time` is after `end_time`.
+    pub(crate) fn new(bucket: BucketSize, start_time: u64, end_time: u64) -> VolumeBuckets {
+        let first = bucket.start(start_time);
+        let len = match start_time <= end_time {
+            true => (bucket.start(end_time) - first) / bucket.seconds() + 1,
+            false => 0,
+        };
+        VolumeBuckets {
+            bucket,
+            first,
+            counts: vec![0; len as usize],
+        }
+    }
+
+    /// Counts a transaction with this round time (unix seconds), ignored outside of the buckets.
+    pub(crate) fn add(&mut self, round_time: u64) {
+        let index = round_time
+            .checked_sub(self.first)
+            .map(|offset| (offset / self.bucket.seconds()) as usize);
+        if let Some(count) = index.and_then(|index| self.counts.get_mut(index)) {
+            *count += 1;
+        }
+    }
+
+    /// The start of each bucket with its count.
+    pub(crate) fn into_series(self) -> Vec<(DateTime<Utc>, u64)> {
+        let (first, seconds) = (self.first, self.bucket.seconds());
+        self.counts
+            .into_iter()
+            .enumerate()
+            .map(|(i, count)| (time(first + i as u64 * seconds), count))
+            .collect()
+    }
+}
+
+/// The UTC time of these unix seconds, the latest time representable if out of range.
+pub(crate) fn time(seconds: u64) -> DateTime<Utc> {
+    i64::try_from(seconds)
+        .ok()
+        .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+        .unwrap_or(DateTime::<Utc>::MAX_UTC)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: u64 = 60 * 60;
+    const DAY: u64 = 24 * HOUR;
+
+    fn fold(
+        round_times: Vec<u64>,
+        bucket: BucketSize,
+        start_time: u64,
+        end_time: u64,
+    ) -> Vec<(DateTime<Utc>, u64)> {
+        let mut buckets = VolumeBuckets::new(bucket, start_time, end_time);
+        for round_time in round_times {
+            buckets.add(round_time);
+        }
+        buckets.into_series()
+    }
+
+    #[test]
+    fn test_volume_is_gap_filled() {
+        let start = 1_000 * DAY + 10 * HOUR + 5;
+        let round_times = vec![start, start + 60, start + 3 * HOUR, start + 3 * HOUR + 1];
+
+        let volume = fold(round_times, BucketSize::Hour, start, start + 4 * HOUR);
+
+        assert_eq!(
+            vec![
+                (time(1_000 * DAY + 10 * HOUR), 2),
+                (time(1_000 * DAY + 11 * HOUR), 0),
+                (time(1_000 * DAY + 12 * HOUR), 0),
+                (time(1_000 * DAY + 13 * HOUR), 2),
+                (time(1_000 * DAY + 14 * HOUR), 0),
+            ],
+            volume
+        );
+    }
+
+    #[test]
+    fn test_daily_volume_is_aligned_to_midnight_utc() {
+        let round_times = vec![1_000 * DAY + 23 * HOUR, 1_001 * DAY, 1_001 * DAY + HOUR];
+
+        let volume = fold(
+            round_times,
+            BucketSize::Day,
+            1_000 * DAY + 12 * HOUR,
+            1_001 * DAY + 12 * HOUR,
+        );
+
+        assert_eq!(vec![(time(1_000 * DAY), 1), (time(1_001 * DAY), 2)], volume);
+        assert_eq!(
+            "1972-09-27T00:00:00Z",
+            volume[0]
+                .0
+                .to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+        );
+    }
+
+    #[test]
+    fn test_volume_ignores_the_times_outside_of_the_buckets() {
+        let volume = fold(vec![DAY - 1, 3 * DAY], BucketSize::Day, DAY, 2 * DAY);
+
+        assert_eq!(vec![(time(DAY), 0), (time(2 * DAY), 0)], volume);
+    }
+
+    #[test]
+    fn test_volume_without_transactions() {
+        let volume = fold(vec![], BucketSize::Day, 10 * DAY, 12 * DAY);
+
+        assert_eq!(
+            vec![
+                (time(10 * DAY), 0),
+                (time(11 * DAY), 0),
+                (time(12 * DAY), 0)
+            ],
+            volume
+        );
+    }
+
+    #[test]
+    fn test_volume_of_empty_range() {
+        assert!(fold(vec![DAY], BucketSize::Day, 2 * DAY, DAY).is_empty());
+    }
+}
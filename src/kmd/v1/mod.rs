@@ -1,5 +1,5 @@
-use algonaut_client::{kmd::v1::Client, token::ApiToken, Headers};
-use algonaut_core::{Address, MultisigSignature, ToMsgPack};
+use algonaut_client::{kmd::v1::Client, token::ApiToken, ClientConfig, Headers};
+use algonaut_core::{Address, MultisigAddress, MultisigSignature, ToMsgPack};
 use algonaut_crypto::{Ed25519PublicKey, MasterDerivationKey};
 use algonaut_model::kmd::v1::{
     CreateWalletResponse, DeleteKeyResponse, DeleteMultisigResponse, ExportKeyResponse,
@@ -34,8 +34,21 @@ impl Kmd {
     ///
     /// Returns an error if the url or headers have an invalid format.
     pub fn with_headers(url: &str, headers: Headers) -> Result<Kmd, AlgonautError> {
+        Self::with_config(url, headers, &ClientConfig::default())
+    }
+
+    /// Build a v1 client for the Algorand key management daemon.
+    /// Use this initializer to tune the HTTP client, e.g. the connection pool of high-throughput services.
+    /// The token is passed as a header, e.g. `vec![("X-KMD-API-Token", token)]`.
+    ///
+    /// Returns an error if the url or headers have an invalid format.
+    pub fn with_config(
+        url: &str,
+        headers: Headers,
+        config: &ClientConfig,
+    ) -> Result<Kmd, AlgonautError> {
         Ok(Kmd {
-            client: Client::new(url, headers)?,
+            client: Client::with_config(url, headers, config)?,
         })
     }
 
@@ -184,6 +197,34 @@ impl Kmd {
         Ok(self.client.list_keys(wallet_handle).await?)
     }
 
+    /// [list_keys](Self::list_keys), parsed as addresses.
+    ///
+    /// Fails with [AlgonautError::Decode], with the malformed entry and its index, if an entry
+    /// isn't a valid address.
+    pub async fn list_keys_typed(
+        &self,
+        wallet_handle: &str,
+    ) -> Result<Vec<Address>, AlgonautError> {
+        parse_addresses(&self.list_keys(wallet_handle).await?.addresses)
+    }
+
+    /// Whether the wallet has the key of `address`, listing the keys once: kmd has no lookup of a
+    /// single key.
+    ///
+    /// The other entries of the list aren't parsed, so a malformed one doesn't fail the lookup.
+    pub async fn has_key(
+        &self,
+        wallet_handle: &str,
+        address: &Address,
+    ) -> Result<bool, AlgonautError> {
+        let address = address.to_string();
+        Ok(self
+            .list_keys(wallet_handle)
+            .await?
+            .addresses
+            .contains(&address))
+    }
+
     /// Sign a transaction
     pub async fn sign_transaction(
         &self,
@@ -205,6 +246,38 @@ impl Kmd {
         Ok(self.client.list_multisig(wallet_handle).await?)
     }
 
+    /// The multisig accounts of the wallet with their preimages: [list_multisig](Self::list_multisig)
+    /// then [export_multisig](Self::export_multisig) for each account, i.e. one request per
+    /// account.
+    ///
+    /// Fails with [AlgonautError::Decode], with the entry and its index, if an entry isn't a valid
+    /// address or isn't the address derived from its exported preimage.
+    pub async fn list_multisig_typed(
+        &self,
+        wallet_handle: &str,
+    ) -> Result<Vec<MultisigAddress>, AlgonautError> {
+        let entries = self.list_multisig(wallet_handle).await?.addresses;
+        let addresses = parse_addresses(&entries)?;
+        let mut multisigs = Vec::with_capacity(addresses.len());
+        for (index, (entry, address)) in entries.iter().zip(addresses).enumerate() {
+            let preimage = self.export_multisig(wallet_handle, entry).await?;
+            let multisig = MultisigAddress {
+                version: preimage.multisig_version,
+                threshold: preimage.threshold,
+                public_keys: preimage.pks,
+            };
+            if multisig.address() != address {
+                return Err(malformed_entry(
+                    index,
+                    entry,
+                    &format!("derived {} from its preimage", multisig.address()),
+                ));
+            }
+            multisigs.push(multisig);
+        }
+        Ok(multisigs)
+    }
+
     /// Import a multisig account
     pub async fn import_multisig(
         &self,
@@ -265,9 +338,132 @@ impl Kmd {
     }
 }
 
+/// Parses the addresses listed by kmd, failing at the first malformed one.
+fn parse_addresses(entries: &[String]) -> Result<Vec<Address>, AlgonautError> {
+    entries
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            entry
+                .parse::<Address>()
+                .map_err(|e| malformed_entry(index, entry, &e.to_string()))
+        })
+        .collect()
+}
+
+/// The error of the entry at `index` of the `addresses` listed by kmd.
+fn malformed_entry(index: usize, entry: &str, reason: &str) -> AlgonautError {
+    AlgonautError::Decode {
+        url: None,
+        description: format!("malformed address {:?}: {}", entry, reason),
+        path: Some(format!("addresses[{}]", index)),
+        body: None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use algonaut_client::test_server::{TestResponse, TestServer};
+
+    const TOKEN: &str = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+    const ADDRESS: &str = "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU";
+
+    /// Answers the requests with these JSON bodies, in order.
+    fn serve(bodies: Vec<String>) -> TestServer {
+        TestServer::serve(bodies.into_iter().map(TestResponse::json).collect())
+    }
+
+    fn addresses(entries: &[&str]) -> String {
+        serde_json::json!({ "addresses": entries }).to_string()
+    }
+
+    #[tokio::test]
+    async fn test_list_keys_typed() {
+        let server = serve(vec![addresses(&[ADDRESS]), addresses(&[])]);
+        let kmd = Kmd::new(server.url(), TOKEN).unwrap();
+
+        assert_eq!(
+            vec![ADDRESS.parse::<Address>().unwrap()],
+            kmd.list_keys_typed("handle").await.unwrap()
+        );
+        assert!(kmd.list_keys_typed("handle").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_keys_typed_with_malformed_address() {
+        let server = serve(vec![addresses(&[ADDRESS, "NOTANADDRESS"])]);
+        let kmd = Kmd::new(server.url(), TOKEN).unwrap();
+
+        match kmd.list_keys_typed("handle").await {
+            Err(AlgonautError::Decode {
+                path, description, ..
+            }) => {
+                assert_eq!(Some("addresses[1]".to_owned()), path);
+                assert!(description.contains("\"NOTANADDRESS\""), "{}", description);
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_has_key() {
+        let other = "2UEQTE5QDNXPI7M3TU44G6SYKLFWLPQO7EBZM7K7MHMQQMFI4QJPLHQFHM";
+        let server = serve(vec![
+            addresses(&["NOTANADDRESS", ADDRESS]),
+            addresses(&[ADDRESS]),
+        ]);
+        let kmd = Kmd::new(server.url(), TOKEN).unwrap();
+
+        // the malformed entry doesn't fail the lookup
+        assert!(kmd
+            .has_key("handle", &ADDRESS.parse().unwrap())
+            .await
+            .unwrap());
+        assert!(!kmd
+            .has_key("handle", &other.parse().unwrap())
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_list_multisig_typed() {
+        let public_keys = vec![Ed25519PublicKey([0; 32]), Ed25519PublicKey([1; 32])];
+        let multisig = MultisigAddress {
+            version: 1,
+            threshold: 1,
+            public_keys: public_keys.clone(),
+        };
+        let preimage = serde_json::json!({
+            "multisig_version": 1,
+            "threshold": 1,
+            "pks": [
+                "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=",
+                "AQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQE="
+            ]
+        })
+        .to_string();
+        let server = serve(vec![
+            addresses(&[&multisig.address().to_string()]),
+            preimage.clone(),
+            // the preimage of another address
+            addresses(&[ADDRESS]),
+            preimage,
+        ]);
+        let kmd = Kmd::new(server.url(), TOKEN).unwrap();
+
+        let multisigs = kmd.list_multisig_typed("handle").await.unwrap();
+        assert_eq!(1, multisigs.len());
+        assert_eq!(multisig.address(), multisigs[0].address());
+        assert_eq!(public_keys, multisigs[0].public_keys);
+
+        match kmd.list_multisig_typed("handle").await {
+            Err(AlgonautError::Decode { path, .. }) => {
+                assert_eq!(Some("addresses[0]".to_owned()), path)
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
 
     #[test]
     fn test_valid_client_creation() {
@@ -0,0 +1,283 @@
+//! Deduplication of transactions with leases, e.g. to retry a payment without paying twice.
+
+use algonaut_core::{Round, RoundRange};
+use algonaut_crypto::HashDigest;
+use algonaut_transaction::{error::TransactionError, Transaction, TxnBuilder, MAX_VALIDITY_WINDOW};
+use sha2::{Digest, Sha512_256};
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// Leases the transactions built for a deduplication key, remembering their last valid round, so
+/// that the attempts to submit the same transaction exclude each other on chain.
+///
+/// The network accepts only one transaction of a sender with a given lease until the last valid
+/// round of that transaction: the transactions built for the same key while the one built first is
+/// valid share its lease, and their validity ends exactly with it, so at most one of them can be
+/// confirmed. Once that validity expired, the next transaction built for the key starts a new
+/// window.
+///
+/// Share it behind an `Arc` between the tasks submitting the transactions.
+#[derive(Debug, Default)]
+pub struct LeaseRegistry {
+    last_valid: Mutex<HashMap<Vec<u8>, Round>>,
+}
+
+impl LeaseRegistry {
+    pub fn new() -> LeaseRegistry {
+        LeaseRegistry::default()
+    }
+
+    /// The lease of the transactions built for this deduplication key: its sha512/256 digest.
+    pub fn lease(dedup_key: &[u8]) -> HashDigest {
+        HashDigest(Sha512_256::digest(dedup_key).into())
+    }
+
+    /// Builds the transaction with the lease of `dedup_key`.
+    ///
+    /// If a transaction built for the key is still valid at the first valid round of this one,
+    /// the last valid round of this one is set to the one of the earlier transaction, so that both
+    /// can't be confirmed: a transaction of the sender with the lease is rejected by the network
+    /// until that round, and this one can't be confirmed after it. Otherwise this transaction
+    /// starts a new window for the key.
+    ///
+    /// Fails like [TxnBuilder::build], without registering the transaction, and with
+    /// [TransactionError::InvalidValidityWindow] if the window ends more than
+    /// [MAX_VALIDITY_WINDOW] rounds after the first valid round of this transaction.
+    pub fn build_unique(
+        &self,
+        builder: TxnBuilder,
+        dedup_key: &[u8],
+    ) -> Result<Transaction, TransactionError> {
+        let builder = builder.lease(Self::lease(dedup_key));
+        let txn = builder.clone().build()?;
+
+        let mut last_valid = self.lock();
+        match last_valid.get(dedup_key) {
+            Some(&window_end) if txn.first_valid <= window_end => {
+                if window_end.0 - txn.first_valid.0 > MAX_VALIDITY_WINDOW {
+                    return Err(TransactionError::InvalidValidityWindow {
+                        first_valid: txn.first_valid.0,
+                        last_valid: window_end.0,
+                        max: MAX_VALIDITY_WINDOW,
+                    });
+                }
+                builder
+                    .validity(RoundRange::new(txn.first_valid, window_end))
+                    .build()
+            }
+            _ => {
+                last_valid.insert(dedup_key.to_vec(), txn.last_valid);
+                Ok(txn)
+            }
+        }
+    }
+
+    /// Forgets the keys whose transactions expired before `round`, e.g. the current round, to
+    /// bound the memory of a long-running registry.
+    pub fn forget_expired(&self, round: Round) {
+        self.lock().retain(|_, last_valid| *last_valid >= round);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<Vec<u8>, Round>> {
+        // The map can't be left inconsistent by a panic: recover it.
+        self.last_valid
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use algonaut_core::{Address, MicroAlgos};
+    use algonaut_transaction::{builder::TxnFee, Pay};
+    use std::sync::Arc;
+    use std::thread;
+
+    fn payment(first_valid: u64, last_valid: u64) -> TxnBuilder {
+        let sender: Address = "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU"
+            .parse()
+            .unwrap();
+        let receiver = "4MYUHDWHWXAKA5KA7U5PEN646VYUANBFXVJNONBK3TIMHEMWMD4UBOJBI4"
+            .parse()
+            .unwrap();
+        TxnBuilder::new(
+            TxnFee::Fixed(MicroAlgos(1000)),
+            first_valid,
+            last_valid,
+            HashDigest([0; 32]),
+            Pay::new(sender, receiver, MicroAlgos(123)).build(),
+        )
+    }
+
+    #[test]
+    fn test_retry_in_window_reuses_the_lease() {
+        let registry = LeaseRegistry::new();
+
+        let first = registry
+            .build_unique(payment(1000, 2000), b"invoice-42")
+            .unwrap();
+        let retry = registry
+            .build_unique(payment(1500, 2500), b"invoice-42")
+            .unwrap();
+
+        assert_eq!(Some(LeaseRegistry::lease(b"invoice-42")), first.lease);
+        assert_eq!(first.lease, retry.lease);
+        // the retry can't be confirmed after the first transaction expired
+        assert_eq!(Round(1500), retry.first_valid);
+        assert_eq!(Round(2000), retry.last_valid);
+    }
+
+    #[test]
+    fn test_shorter_retry_is_extended_to_the_window() {
+        let registry = LeaseRegistry::new();
+        registry
+            .build_unique(payment(1000, 2000), b"invoice-42")
+            .unwrap();
+
+        // ending earlier, the retry could be confirmed first and release the lease while the
+        // first transaction is still valid
+        let retry = registry
+            .build_unique(payment(1500, 1600), b"invoice-42")
+            .unwrap();
+        assert_eq!(Round(1500), retry.first_valid);
+        assert_eq!(Round(2000), retry.last_valid);
+    }
+
+    #[test]
+    fn test_retry_unable_to_end_with_the_window_is_rejected() {
+        let registry = LeaseRegistry::new();
+        registry
+            .build_unique(payment(1000, 2000), b"invoice-42")
+            .unwrap();
+        registry
+            .build_unique(payment(1001, 2001), b"invoice-43")
+            .unwrap();
+
+        // ending with the window, the retry would be valid for more than the maximum window
+        assert!(matches!(
+            registry.build_unique(payment(900, 950), b"invoice-43"),
+            Err(TransactionError::InvalidValidityWindow {
+                first_valid: 900,
+                last_valid: 2001,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_retry_after_expiry_gets_a_fresh_window() {
+        let registry = LeaseRegistry::new();
+        registry
+            .build_unique(payment(1000, 2000), b"invoice-42")
+            .unwrap();
+
+        let retry = registry
+            .build_unique(payment(2001, 3001), b"invoice-42")
+            .unwrap();
+        assert_eq!(Round(3001), retry.last_valid);
+
+        // the new window is the one retries are cut to
+        let second_retry = registry
+            .build_unique(payment(2500, 3500), b"invoice-42")
+            .unwrap();
+        assert_eq!(Round(3001), second_retry.last_valid);
+    }
+
+    #[test]
+    fn test_keys_are_independent() {
+        let registry = LeaseRegistry::new();
+        let first = registry
+            .build_unique(payment(1000, 2000), b"invoice-42")
+            .unwrap();
+        let other = registry
+            .build_unique(payment(1500, 2500), b"invoice-43")
+            .unwrap();
+
+        assert_ne!(first.lease, other.lease);
+        assert_eq!(Round(2500), other.last_valid);
+    }
+
+    #[test]
+    fn test_invalid_transactions_arent_registered() {
+        let registry = LeaseRegistry::new();
+        // a note over the limit
+        assert!(registry
+            .build_unique(payment(2000, 3000).note(vec![0; 1025]), b"invoice-42")
+            .is_err());
+
+        let txn = registry
+            .build_unique(payment(1500, 2500), b"invoice-42")
+            .unwrap();
+        assert_eq!(Round(2500), txn.last_valid);
+    }
+
+    #[test]
+    fn test_forget_expired() {
+        let registry = LeaseRegistry::new();
+        registry
+            .build_unique(payment(1000, 2000), b"invoice-42")
+            .unwrap();
+
+        registry.forget_expired(Round(2000));
+        let retry = registry
+            .build_unique(payment(1500, 2500), b"invoice-42")
+            .unwrap();
+        assert_eq!(Round(2000), retry.last_valid);
+
+        registry.forget_expired(Round(2001));
+        let retry = registry
+            .build_unique(payment(1500, 2500), b"invoice-42")
+            .unwrap();
+        assert_eq!(Round(2500), retry.last_valid);
+    }
+
+    #[test]
+    fn test_concurrent_attempts_share_the_window() {
+        let registry = Arc::new(LeaseRegistry::new());
+
+        let attempts: Vec<Transaction> = (0..8)
+            .map(|i| {
+                let registry = registry.clone();
+                thread::spawn(move || {
+                    registry
+                        .build_unique(payment(1500 + i, 2000 + i), b"invoice-42")
+                        .unwrap()
+                })
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|attempt| attempt.join().unwrap())
+            .collect();
+
+        // the attempts end exactly with the one registered first
+        let window_end = registry.lock()[&b"invoice-42"[..]];
+        for txn in &attempts {
+            assert_eq!(window_end, txn.last_valid);
+            assert_eq!(attempts[0].lease, txn.lease);
+        }
+    }
+
+    #[test]
+    fn test_registry_recovers_from_a_panic_holding_the_lock() {
+        let registry = Arc::new(LeaseRegistry::new());
+        let first = registry
+            .build_unique(payment(1000, 2000), b"invoice-42")
+            .unwrap();
+
+        let poisoning = registry.clone();
+        let res = thread::spawn(move || {
+            let _guard = poisoning.lock();
+            panic!("panic holding the lock");
+        })
+        .join();
+        assert!(res.is_err());
+
+        let retry = registry
+            .build_unique(payment(1500, 2500), b"invoice-42")
+            .unwrap();
+        assert_eq!(first.last_valid, retry.last_valid);
+        registry.forget_expired(Round(2001));
+        assert!(registry.lock().is_empty());
+    }
+}
@@ -11,7 +11,24 @@ pub use algonaut_crypto as crypto;
 pub use algonaut_model as model;
 pub use algonaut_transaction as transaction;
 
+pub use algonaut_client::{
+    ClientConfig, DecodeError, DecodeTarget, Decoder, HttpClient, ResponseDecoder, ResponseFormat,
+    SerdeJsonDecoder,
+};
+
 pub mod algod;
+pub mod application;
+mod batch;
+pub mod config;
+pub mod deploy;
 pub mod error;
 pub mod indexer;
 pub mod kmd;
+pub mod lease;
+pub mod min_balance;
+pub mod nft;
+pub mod provider;
+pub mod teal;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod uri;
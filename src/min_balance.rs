@@ -0,0 +1,400 @@
+//! Minimum balance of the accounts, e.g. to fund an account before its opt-ins and creations.
+//!
+//! An account must keep a minimum balance for each asset it holds, each application it created
+//! or opted in (with the state schemas and extra pages of the applications) and each box of its
+//! application: a transaction leaving less fails with "balance below min".
+
+use algonaut_core::MicroAlgos;
+use algonaut_model::{algod, indexer};
+use algonaut_transaction::transaction::{
+    ApplicationCallOnComplete, ApplicationCallTransaction, AssetConfigurationTransaction,
+    StateSchema,
+};
+use algonaut_transaction::TransactionType;
+
+/// The consensus parameters of the minimum balance, the current ones by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MinBalanceParams {
+    /// Of every account, and of each asset it holds.
+    pub min_balance: MicroAlgos,
+    /// Of each application the account opted in.
+    pub app_flat_opt_in_min_balance: MicroAlgos,
+    /// Of each application the account created, and of each of their extra program pages.
+    pub app_flat_params_min_balance: MicroAlgos,
+    /// Of each entry of the state schemas, added to the cost of its type.
+    pub schema_min_balance_per_entry: MicroAlgos,
+    /// Of each uint of the state schemas.
+    pub schema_uint_min_balance: MicroAlgos,
+    /// Of each byte slice of the state schemas.
+    pub schema_bytes_min_balance: MicroAlgos,
+    /// Of each box.
+    pub box_flat_min_balance: MicroAlgos,
+    /// Of each byte of the names and values of the boxes.
+    pub box_byte_min_balance: MicroAlgos,
+}
+
+impl Default for MinBalanceParams {
+    fn default() -> Self {
+        MinBalanceParams {
+            min_balance: MicroAlgos(100_000),
+            app_flat_opt_in_min_balance: MicroAlgos(100_000),
+            app_flat_params_min_balance: MicroAlgos(100_000),
+            schema_min_balance_per_entry: MicroAlgos(25_000),
+            schema_uint_min_balance: MicroAlgos(3_500),
+            schema_bytes_min_balance: MicroAlgos(25_000),
+            box_flat_min_balance: MicroAlgos(2_500),
+            box_byte_min_balance: MicroAlgos(400),
+        }
+    }
+}
+
+/// What an account holds that requires a minimum balance.
+///
+/// Built from the account models of algod and the indexer, or by hand for an account to fund.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AccountFootprint {
+    /// Assets held, including the ones created by the account.
+    pub assets: u64,
+    /// Applications opted in.
+    pub apps_opted_in: u64,
+    /// Applications created.
+    pub apps_created: u64,
+    /// Uints of the local schemas of the applications opted in and of the global schemas of the
+    /// applications created.
+    pub num_uint: u64,
+    /// Byte slices of the local schemas of the applications opted in and of the global schemas of
+    /// the applications created.
+    pub num_byte_slice: u64,
+    /// Extra program pages of the applications created.
+    pub extra_pages: u64,
+    /// Boxes of the account, if it's the account of an application.
+    pub boxes: u64,
+    /// Total size of the names and values of the boxes.
+    pub box_bytes: u64,
+}
+
+impl AccountFootprint {
+    /// The minimum balance with these consensus parameters.
+    pub fn min_balance(&self, params: &MinBalanceParams) -> MicroAlgos {
+        let uint_min_balance =
+            params.schema_min_balance_per_entry.0 + params.schema_uint_min_balance.0;
+        let bytes_min_balance =
+            params.schema_min_balance_per_entry.0 + params.schema_bytes_min_balance.0;
+        MicroAlgos(
+            params.min_balance.0 * (1 + self.assets)
+                + params.app_flat_opt_in_min_balance.0 * self.apps_opted_in
+                + params.app_flat_params_min_balance.0 * (self.apps_created + self.extra_pages)
+                + uint_min_balance * self.num_uint
+                + bytes_min_balance * self.num_byte_slice
+                + params.box_flat_min_balance.0 * self.boxes
+                + params.box_byte_min_balance.0 * self.box_bytes,
+        )
+    }
+
+    /// Adds what the transaction of this account creates or opts in: assets, applications and
+    /// their schemas. `opted_in_local_schema` is the local schema of the application opted in, if
+    /// the transaction is an opt-in to an existing application.
+    ///
+    /// Other transactions (e.g. close-outs or deletions) are ignored, so the result is an upper
+    /// bound.
+    pub(crate) fn add_transaction(
+        &mut self,
+        txn_type: &TransactionType,
+        opted_in_local_schema: Option<&StateSchema>,
+    ) {
+        match txn_type {
+            TransactionType::AssetAcceptTransaction(_) => self.assets += 1,
+            TransactionType::AssetConfigurationTransaction(AssetConfigurationTransaction {
+                config_asset: None,
+                params: Some(_),
+                ..
+            }) => self.assets += 1,
+            TransactionType::ApplicationCallTransaction(call) if is_creation(call) => {
+                self.apps_created += 1;
+                self.extra_pages += call.extra_pages;
+                self.add_schema(call.global_state_schema.as_ref());
+                if call.on_complete == ApplicationCallOnComplete::OptIn {
+                    self.apps_opted_in += 1;
+                    self.add_schema(call.local_state_schema.as_ref());
+                }
+            }
+            TransactionType::ApplicationCallTransaction(call)
+                if call.on_complete == ApplicationCallOnComplete::OptIn =>
+            {
+                self.apps_opted_in += 1;
+                self.add_schema(opted_in_local_schema);
+            }
+            _ => {}
+        }
+    }
+
+    fn add_schema(&mut self, schema: Option<&StateSchema>) {
+        if let Some(schema) = schema {
+            self.num_uint += schema.number_ints;
+            self.num_byte_slice += schema.number_byteslices;
+        }
+    }
+}
+
+/// Whether the call creates an application.
+fn is_creation(call: &ApplicationCallTransaction) -> bool {
+    call.app_id.unwrap_or_default() == 0
+}
+
+/// The totals reported by algod, or the counts of the lists if the totals are missing (from older
+/// nodes).
+impl From<&algod::v2::Account> for AccountFootprint {
+    fn from(account: &algod::v2::Account) -> Self {
+        let schema = account.apps_total_schema.as_ref();
+        AccountFootprint {
+            assets: account
+                .total_assets_opted_in
+                .unwrap_or(account.assets.len() as u64),
+            apps_opted_in: account
+                .total_apps_opted_in
+                .unwrap_or(account.apps_local_state.len() as u64),
+            apps_created: account
+                .total_created_apps
+                .unwrap_or(account.created_apps.len() as u64),
+            num_uint: schema.map_or(0, |schema| schema.num_uint),
+            num_byte_slice: schema.map_or(0, |schema| schema.num_byte_slice),
+            extra_pages: account.apps_total_extra_pages.unwrap_or_else(|| {
+                account
+                    .created_apps
+                    .iter()
+                    .filter_map(|app| app.params.extra_program_pages)
+                    .sum()
+            }),
+            boxes: account.total_boxes.unwrap_or_default(),
+            box_bytes: account.total_box_bytes.unwrap_or_default(),
+        }
+    }
+}
+
+/// The totals reported by the indexer, or the counts of the lists (without the deleted items) if
+/// the totals are missing.
+impl From<&indexer::v2::Account> for AccountFootprint {
+    fn from(account: &indexer::v2::Account) -> Self {
+        let schema = account.apps_total_schema.as_ref();
+        let created_apps = || {
+            account
+                .created_apps
+                .iter()
+                .filter(|app| app.deleted != Some(true))
+        };
+        AccountFootprint {
+            assets: account.total_assets_opted_in.unwrap_or_else(|| {
+                let assets = account.assets.iter();
+                assets.filter(|asset| asset.deleted != Some(true)).count() as u64
+            }),
+            apps_opted_in: account.total_apps_opted_in.unwrap_or_else(|| {
+                let apps = account.apps_local_state.iter();
+                apps.filter(|app| app.deleted != Some(true)).count() as u64
+            }),
+            apps_created: account
+                .total_created_apps
+                .unwrap_or_else(|| created_apps().count() as u64),
+            num_uint: schema.map_or(0, |schema| schema.num_uint),
+            num_byte_slice: schema.map_or(0, |schema| schema.num_byte_slice),
+            extra_pages: account.apps_total_extra_pages.unwrap_or_else(|| {
+                created_apps()
+                    .filter_map(|app| app.params.extra_program_pages)
+                    .sum()
+            }),
+            boxes: account.total_boxes.unwrap_or_default(),
+            box_bytes: account.total_box_bytes.unwrap_or_default(),
+        }
+    }
+}
+
+/// The minimum balance of the account (an account model or an [AccountFootprint]) with the
+/// current consensus parameters.
+pub fn compute(account: impl Into<AccountFootprint>) -> MicroAlgos {
+    compute_with(account, &MinBalanceParams::default())
+}
+
+/// The minimum balance of the account (an account model or an [AccountFootprint]) with these
+/// consensus parameters.
+pub fn compute_with(account: impl Into<AccountFootprint>, params: &MinBalanceParams) -> MicroAlgos {
+    account.into().min_balance(params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use algonaut_core::Address;
+    use algonaut_transaction::transaction::AssetAcceptTransaction;
+
+    const ADDRESS: &str = "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU";
+
+    /// An algod account with these fields, as returned with `exclude=all`.
+    fn algod_account(fields: serde_json::Value) -> algod::v2::Account {
+        let mut account = serde_json::json!({
+            "address": ADDRESS,
+            "amount": 5000000,
+            "amount-without-pending-rewards": 5000000,
+            "pending-rewards": 0,
+            "rewards": 0,
+            "round": 38000000,
+            "status": "Offline"
+        });
+        account
+            .as_object_mut()
+            .unwrap()
+            .extend(fields.as_object().unwrap().clone());
+        serde_json::from_value(account).unwrap()
+    }
+
+    #[test]
+    fn test_min_balance_of_algod_accounts() {
+        let accounts = [
+            serde_json::json!({
+                "min-balance": 100000
+            }),
+            serde_json::json!({
+                "min-balance": 300000,
+                "total-assets-opted-in": 2,
+                "total-created-assets": 1
+            }),
+            serde_json::json!({
+                "apps-total-extra-pages": 1,
+                "apps-total-schema": { "num-byte-slice": 1, "num-uint": 3 },
+                "min-balance": 535500,
+                "total-apps-opted-in": 1,
+                "total-created-apps": 1
+            }),
+            serde_json::json!({
+                "min-balance": 145000,
+                "total-box-bytes": 100,
+                "total-boxes": 2
+            }),
+        ];
+        for fields in accounts {
+            let account = algod_account(fields);
+            assert_eq!(account.min_balance, Some(compute(&account)));
+        }
+    }
+
+    #[test]
+    fn test_footprint_without_totals() {
+        let account = algod_account(serde_json::json!({
+            "apps-local-state": [
+                { "id": 1, "schema": { "num-byte-slice": 0, "num-uint": 1 } }
+            ],
+            "apps-total-schema": { "num-byte-slice": 1, "num-uint": 3 },
+            "assets": [
+                { "amount": 0, "asset-id": 10, "creator": ADDRESS, "is-frozen": false }
+            ],
+            "created-apps": [{
+                "id": 2,
+                "params": {
+                    "creator": ADDRESS,
+                    "extra-program-pages": 1,
+                    "global-state-schema": { "num-byte-slice": 1, "num-uint": 2 }
+                }
+            }]
+        }));
+
+        assert_eq!(
+            AccountFootprint {
+                assets: 1,
+                apps_opted_in: 1,
+                apps_created: 1,
+                num_uint: 3,
+                num_byte_slice: 1,
+                extra_pages: 1,
+                boxes: 0,
+                box_bytes: 0,
+            },
+            AccountFootprint::from(&account)
+        );
+        assert_eq!(MicroAlgos(635500), compute(&account));
+    }
+
+    #[test]
+    fn test_min_balance_of_footprint() {
+        let footprint = AccountFootprint {
+            assets: 3,
+            boxes: 1,
+            box_bytes: 64,
+            ..AccountFootprint::default()
+        };
+        assert_eq!(MicroAlgos(428100), compute(footprint));
+
+        let params = MinBalanceParams {
+            min_balance: MicroAlgos(200_000),
+            ..MinBalanceParams::default()
+        };
+        assert_eq!(MicroAlgos(828100), compute_with(footprint, &params));
+    }
+
+    #[test]
+    fn test_add_transaction() {
+        let sender = Address([0; 32]);
+        let mut footprint = AccountFootprint::default();
+        let call = |app_id, on_complete| ApplicationCallTransaction {
+            sender,
+            app_id,
+            on_complete,
+            accounts: None,
+            approval_program: None,
+            app_arguments: None,
+            clear_state_program: None,
+            foreign_apps: None,
+            foreign_assets: None,
+            boxes: None,
+            global_state_schema: Some(StateSchema {
+                number_ints: 2,
+                number_byteslices: 1,
+            }),
+            local_state_schema: Some(StateSchema {
+                number_ints: 1,
+                number_byteslices: 0,
+            }),
+            extra_pages: 1,
+        };
+
+        footprint.add_transaction(
+            &TransactionType::ApplicationCallTransaction(call(
+                None,
+                ApplicationCallOnComplete::OptIn,
+            )),
+            None,
+        );
+        footprint.add_transaction(
+            &TransactionType::ApplicationCallTransaction(call(
+                Some(5),
+                ApplicationCallOnComplete::OptIn,
+            )),
+            Some(&StateSchema {
+                number_ints: 0,
+                number_byteslices: 4,
+            }),
+        );
+        footprint.add_transaction(
+            &TransactionType::ApplicationCallTransaction(call(
+                Some(5),
+                ApplicationCallOnComplete::NoOp,
+            )),
+            None,
+        );
+        footprint.add_transaction(
+            &TransactionType::AssetAcceptTransaction(AssetAcceptTransaction { sender, xfer: 10 }),
+            None,
+        );
+
+        assert_eq!(
+            AccountFootprint {
+                assets: 1,
+                apps_opted_in: 2,
+                apps_created: 1,
+                num_uint: 3,
+                num_byte_slice: 5,
+                extra_pages: 1,
+                boxes: 0,
+                box_bytes: 0,
+            },
+            footprint
+        );
+    }
+}
@@ -0,0 +1,804 @@
+//! Helpers for Algorand NFT conventions: ARC-3 and ARC-69 metadata and ARC-19 asset urls.
+
+use crate::error::AlgonautError;
+use crate::indexer::v2::{pages, Indexer};
+use crate::{ClientConfig, HttpClient};
+use algonaut_core::{Address, AddressParseError};
+use algonaut_encoding::decode_base64;
+use algonaut_model::algod::v2::AssetParams;
+use algonaut_model::indexer::v2::{
+    AssetParams as IndexerAssetParams, QueryAssetTransaction, QueryAssetsInfo, TransactionType,
+};
+use async_trait::async_trait;
+use data_encoding::BASE32_NOPAD;
+use futures_util::{pin_mut, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256, Sha512_256};
+use std::convert::TryInto;
+use thiserror::Error;
+
+/// Prefix of the [ARC-19](https://github.com/algorandfoundation/ARCs/blob/main/ARCs/arc-0019.md) templates.
+const ARC19_TEMPLATE_PREFIX: &str = "template-ipfs://{ipfscid:";
+
+const IPFS_SCHEME: &str = "ipfs://";
+
+const DEFAULT_IPFS_GATEWAY: &str = "https://ipfs.io/ipfs/";
+
+/// Suffix of the names of ARC-3 assets.
+const ARC3_SUFFIX: &str = "@arc3";
+/// Suffix of the urls of ARC-3 assets.
+const ARC3_URL_SUFFIX: &str = "#arc3";
+
+/// `standard` of ARC-69 metadata.
+const ARC69_STANDARD: &str = "arc69";
+
+/// Multicodec of raw binary data.
+const RAW_CODEC: u8 = 0x55;
+/// Multicodec of MerkleDAG protobuf data.
+const DAG_PB_CODEC: u8 = 0x70;
+/// Multihash code of sha2-256.
+const SHA2_256_CODE: u8 = 0x12;
+/// Length of a sha2-256 digest.
+const SHA2_256_LEN: u8 = 0x20;
+
+/// Multibase prefix of lowercase, unpadded base32.
+const BASE32_MULTIBASE_PREFIX: char = 'b';
+
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Error resolving or building an ARC-19 asset url.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum Arc19Error {
+    /// The asset url isn't an ARC-19 template.
+    #[error("Not an ARC-19 template: {0}")]
+    NotATemplate(String),
+    /// The template requests a CID version other than 0 or 1.
+    #[error("Unsupported CID version: {0}")]
+    UnsupportedVersion(String),
+    /// The template requests a multicodec other than `raw` or `dag-pb` (the only one valid for CIDv0).
+    #[error("Unsupported multicodec: {0}")]
+    UnsupportedCodec(String),
+    /// The template references a field other than `reserve`.
+    #[error("Unsupported field: {0}")]
+    UnsupportedField(String),
+    /// The template requests a hash type other than `sha2-256`.
+    #[error("Unsupported hash type: {0}")]
+    UnsupportedHashType(String),
+    /// The field referenced by the template isn't set.
+    #[error("Missing field: {0}")]
+    MissingField(String),
+    /// The CID can't be encoded in a reserve address.
+    #[error("Invalid CID: {0}")]
+    InvalidCid(String),
+}
+
+/// Resolves an [ARC-19](https://github.com/algorandfoundation/ARCs/blob/main/ARCs/arc-0019.md)
+/// asset url, e.g. `template-ipfs://{ipfscid:1:raw:reserve:sha2-256}`, to the `ipfs://` url of the
+/// CID encoded in the reserve address.
+///
+/// Anything after the template (e.g. `/arc3.json`) is appended to the resolved url.
+pub fn resolve_arc19_url(asset_params: &AssetParams) -> Result<String, Arc19Error> {
+    resolve_arc19_template(
+        asset_params.url.as_deref().unwrap_or_default(),
+        asset_params.reserve,
+    )
+}
+
+fn resolve_arc19_template(url: &str, reserve: Option<Address>) -> Result<String, Arc19Error> {
+    let not_a_template = || Arc19Error::NotATemplate(url.to_owned());
+
+    let (template, suffix) = url
+        .strip_prefix(ARC19_TEMPLATE_PREFIX)
+        .and_then(|rest| rest.split_once('}'))
+        .ok_or_else(not_a_template)?;
+    let (version, codec, field, hash_type) = match template.split(':').collect::<Vec<_>>()[..] {
+        [version, codec, field, hash_type] => (version, codec, field, hash_type),
+        _ => return Err(not_a_template()),
+    };
+
+    let codec = match codec {
+        "raw" => RAW_CODEC,
+        "dag-pb" => DAG_PB_CODEC,
+        _ => return Err(Arc19Error::UnsupportedCodec(codec.to_owned())),
+    };
+    if hash_type != "sha2-256" {
+        return Err(Arc19Error::UnsupportedHashType(hash_type.to_owned()));
+    }
+    let digest = match field {
+        "reserve" => {
+            reserve
+                .ok_or_else(|| Arc19Error::MissingField(field.to_owned()))?
+                .0
+        }
+        _ => return Err(Arc19Error::UnsupportedField(field.to_owned())),
+    };
+    let multihash = [&[SHA2_256_CODE, SHA2_256_LEN], &digest[..]].concat();
+
+    let cid = match version {
+        "0" if codec == DAG_PB_CODEC => encode_base58(&multihash),
+        "0" => {
+            return Err(Arc19Error::UnsupportedCodec(
+                "CIDv0 requires dag-pb".to_owned(),
+            ))
+        }
+        "1" => format!(
+            "{}{}",
+            BASE32_MULTIBASE_PREFIX,
+            BASE32_NOPAD
+                .encode(&[&[1, codec], &multihash[..]].concat())
+                .to_lowercase()
+        ),
+        _ => return Err(Arc19Error::UnsupportedVersion(version.to_owned())),
+    };
+    Ok(format!("{}{}{}", IPFS_SCHEME, cid, suffix))
+}
+
+/// The reserve address encoding `cid`, to set when minting or reconfiguring an
+/// [ARC-19](https://github.com/algorandfoundation/ARCs/blob/main/ARCs/arc-0019.md) asset.
+///
+/// Accepts sha2-256 CIDv0 (base58) and CIDv1 (base32), optionally prefixed with `ipfs://`. The asset url must
+/// be a template matching the CID, e.g. `template-ipfs://{ipfscid:1:raw:reserve:sha2-256}`.
+pub fn arc19_reserve_address(cid: &str) -> Result<Address, Arc19Error> {
+    let invalid_cid = || Arc19Error::InvalidCid(cid.to_owned());
+    let encoded = cid.strip_prefix(IPFS_SCHEME).unwrap_or(cid);
+
+    let multihash = if encoded.starts_with("Qm") {
+        decode_base58(encoded).ok_or_else(invalid_cid)?
+    } else if let Some(base32) = encoded.strip_prefix(BASE32_MULTIBASE_PREFIX) {
+        let bytes = BASE32_NOPAD
+            .decode(base32.to_uppercase().as_bytes())
+            .map_err(|_| invalid_cid())?;
+        match bytes.as_slice() {
+            [1, RAW_CODEC | DAG_PB_CODEC, multihash @ ..] => multihash.to_vec(),
+            _ => return Err(invalid_cid()),
+        }
+    } else {
+        return Err(invalid_cid());
+    };
+
+    match multihash.as_slice() {
+        [SHA2_256_CODE, SHA2_256_LEN, digest @ ..] => {
+            Ok(Address::new(digest.try_into().map_err(|_| invalid_cid())?))
+        }
+        _ => Err(invalid_cid()),
+    }
+}
+
+/// Metadata standard followed by an NFT.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NftStandard {
+    /// [ARC-3](https://github.com/algorandfoundation/ARCs/blob/main/ARCs/arc-0003.md): JSON metadata
+    /// stored off-chain at the asset url.
+    Arc3,
+    /// [ARC-69](https://github.com/algorandfoundation/ARCs/blob/main/ARCs/arc-0069.md): JSON metadata
+    /// stored in the note of the latest asset configuration transaction.
+    Arc69,
+}
+
+/// ARC-3 metadata.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Arc3Metadata {
+    /// Name of the asset.
+    pub name: Option<String>,
+
+    /// Number of decimals to display the amounts of the asset.
+    pub decimals: Option<u64>,
+
+    /// Description of the asset.
+    pub description: Option<String>,
+
+    /// URI of the image of the asset.
+    pub image: Option<String>,
+
+    /// Integrity of the image, as a SRI string (e.g. "sha256-...").
+    pub image_integrity: Option<String>,
+
+    /// MIME type of the image.
+    pub image_mimetype: Option<String>,
+
+    /// URL of an external page about the asset.
+    pub external_url: Option<String>,
+
+    /// URI of a multi-media file of the asset.
+    pub animation_url: Option<String>,
+
+    /// Arbitrary properties.
+    #[serde(default)]
+    pub properties: Map<String, Value>,
+
+    /// Base64 extra metadata, included in the metadata hash of the asset.
+    pub extra_metadata: Option<String>,
+}
+
+/// ARC-69 metadata.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Arc69Metadata {
+    /// Always "arc69".
+    pub standard: String,
+
+    /// Description of the asset.
+    pub description: Option<String>,
+
+    /// URL of an external page about the asset.
+    pub external_url: Option<String>,
+
+    /// URL of the media of the asset. The asset url usually points to the same media.
+    pub media_url: Option<String>,
+
+    /// Arbitrary properties.
+    #[serde(default)]
+    pub properties: Map<String, Value>,
+
+    /// MIME type of the media.
+    pub mime_type: Option<String>,
+}
+
+/// Metadata of an NFT, as returned by [fetch_metadata].
+#[derive(Clone, Debug, PartialEq)]
+pub enum NftMetadata {
+    Arc3(Arc3Metadata),
+    Arc69(Arc69Metadata),
+}
+
+impl NftMetadata {
+    pub fn standard(&self) -> NftStandard {
+        match self {
+            NftMetadata::Arc3(_) => NftStandard::Arc3,
+            NftMetadata::Arc69(_) => NftStandard::Arc69,
+        }
+    }
+
+    /// The name of the asset. ARC-69 metadata has no name: use the name of the asset params.
+    pub fn title(&self) -> Option<&str> {
+        match self {
+            NftMetadata::Arc3(metadata) => metadata.name.as_deref(),
+            NftMetadata::Arc69(_) => None,
+        }
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        match self {
+            NftMetadata::Arc3(metadata) => metadata.description.as_deref(),
+            NftMetadata::Arc69(metadata) => metadata.description.as_deref(),
+        }
+    }
+
+    /// The URL of the media of the asset: for ARC-3 the image or, if not set, the animation url.
+    pub fn media_url(&self) -> Option<&str> {
+        match self {
+            NftMetadata::Arc3(metadata) => metadata
+                .image
+                .as_deref()
+                .or(metadata.animation_url.as_deref()),
+            NftMetadata::Arc69(metadata) => metadata.media_url.as_deref(),
+        }
+    }
+
+    pub fn properties(&self) -> &Map<String, Value> {
+        match self {
+            NftMetadata::Arc3(metadata) => &metadata.properties,
+            NftMetadata::Arc69(metadata) => &metadata.properties,
+        }
+    }
+}
+
+/// Error fetching the metadata of an NFT.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum NftError {
+    #[error("{0}")]
+    Indexer(#[from] AlgonautError),
+    #[error("{0}")]
+    Arc19(#[from] Arc19Error),
+    /// The off-chain metadata couldn't be fetched.
+    #[error("Metadata fetch error: {0}")]
+    Fetch(String),
+    /// The asset follows neither ARC-3 nor ARC-69.
+    #[error("The asset has no ARC-3 or ARC-69 metadata")]
+    NoMetadata,
+    /// The metadata isn't valid JSON or doesn't follow the standard.
+    #[error("Invalid metadata: {0}")]
+    InvalidMetadata(String),
+    /// The ARC-3 metadata doesn't match the metadata hash (`am`) of the asset.
+    #[error("The metadata doesn't match the metadata hash of the asset")]
+    MetadataHashMismatch,
+}
+
+/// Fetches off-chain metadata, e.g. ARC-3 JSON files.
+// The HTTP futures aren't Send with the WASM target, so neither are the ones of the trait.
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait MetadataFetcher {
+    /// The content at `url`, which may be an `ipfs://` url.
+    async fn fetch(&self, url: &str) -> Result<Vec<u8>, NftError>;
+}
+
+/// A [MetadataFetcher] making HTTP requests, through an IPFS gateway for `ipfs://` urls.
+#[derive(Debug, Clone)]
+pub struct HttpMetadataFetcher {
+    client: HttpClient,
+    ipfs_gateway: String,
+}
+
+impl HttpMetadataFetcher {
+    /// A fetcher resolving `ipfs://{path}` urls to `{ipfs_gateway}{path}`, e.g. with the gateway
+    /// `https://ipfs.io/ipfs/`.
+    pub fn new(ipfs_gateway: &str) -> HttpMetadataFetcher {
+        HttpMetadataFetcher {
+            client: HttpClient::new(&ClientConfig::default())
+                .expect("the HTTP client can be built with the default settings"),
+            ipfs_gateway: ipfs_gateway.to_owned(),
+        }
+    }
+}
+
+impl Default for HttpMetadataFetcher {
+    fn default() -> Self {
+        HttpMetadataFetcher::new(DEFAULT_IPFS_GATEWAY)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl MetadataFetcher for HttpMetadataFetcher {
+    async fn fetch(&self, url: &str) -> Result<Vec<u8>, NftError> {
+        let url = match url.strip_prefix(IPFS_SCHEME) {
+            Some(path) => format!("{}{}", self.ipfs_gateway, path),
+            None => url.to_owned(),
+        };
+        self.client
+            .get_bytes(&url)
+            .await
+            .map_err(|e| NftError::Fetch(e.to_string()))
+    }
+}
+
+/// Fetches and validates the metadata of an NFT, detecting the standard it follows.
+///
+/// ARC-3 is detected by the asset name ("arc3" or ending with "@arc3") or url (ending with "#arc3").
+/// ARC-3 urls can be ARC-19 templates. The metadata is downloaded with `fetcher` and, if the asset has a
+/// metadata hash, verified against it.
+///
+/// Otherwise the note of the latest asset configuration transaction is parsed as ARC-69 metadata. A
+/// note that isn't ARC-69 JSON fails with [NftError::InvalidMetadata].
+pub async fn fetch_metadata(
+    indexer: &Indexer,
+    asset_id: u64,
+    fetcher: &(dyn MetadataFetcher + Sync),
+) -> Result<NftMetadata, NftError> {
+    let asset = indexer
+        .assets_info(asset_id, &QueryAssetsInfo::default())
+        .await?
+        .asset;
+    if is_arc3(&asset.params) {
+        return Ok(NftMetadata::Arc3(
+            fetch_arc3_metadata(&asset.params, fetcher).await?,
+        ));
+    }
+
+    let note = latest_asset_config_note(indexer, asset_id)
+        .await?
+        .ok_or(NftError::NoMetadata)?;
+    Ok(NftMetadata::Arc69(parse_arc69_note(&note)?))
+}
+
+fn is_arc3(params: &IndexerAssetParams) -> bool {
+    let name = params.name.as_deref().unwrap_or_default();
+    let url = params.url.as_deref().unwrap_or_default();
+    name == "arc3" || name.ends_with(ARC3_SUFFIX) || url.ends_with(ARC3_URL_SUFFIX)
+}
+
+async fn fetch_arc3_metadata(
+    params: &IndexerAssetParams,
+    fetcher: &(dyn MetadataFetcher + Sync),
+) -> Result<Arc3Metadata, NftError> {
+    let url = params
+        .url
+        .as_deref()
+        .ok_or_else(|| NftError::InvalidMetadata("Missing ARC-3 url".to_owned()))?;
+    let url = if url.starts_with(ARC19_TEMPLATE_PREFIX) {
+        let reserve = params
+            .reserve
+            .as_deref()
+            .map(str::parse)
+            .transpose()
+            .map_err(|e: AddressParseError| NftError::InvalidMetadata(e.to_string()))?;
+        resolve_arc19_template(url, reserve)?
+    } else {
+        url.to_owned()
+    };
+    let url = url.strip_suffix(ARC3_URL_SUFFIX).unwrap_or(&url);
+
+    let json = fetcher.fetch(url).await?;
+    let metadata: Arc3Metadata =
+        serde_json::from_slice(&json).map_err(|e| NftError::InvalidMetadata(e.to_string()))?;
+    verify_arc3_metadata_hash(&json, &metadata, &params.metadata_hash)?;
+    Ok(metadata)
+}
+
+/// Verifies the ARC-3 metadata hash: SHA-256 of the JSON or, if extra metadata is set,
+/// SHA-512/256("arc0003/am" || SHA-512/256("arc0003/amj" || JSON) || extra metadata).
+///
+/// Assets without a metadata hash aren't verified.
+fn verify_arc3_metadata_hash(
+    json: &[u8],
+    metadata: &Arc3Metadata,
+    metadata_hash: &[u8],
+) -> Result<(), NftError> {
+    if metadata_hash.is_empty() {
+        return Ok(());
+    }
+    let expected = match &metadata.extra_metadata {
+        None => Sha256::digest(json).to_vec(),
+        Some(extra_metadata) => {
+            let extra_metadata = decode_base64(extra_metadata.as_bytes())
+                .map_err(|e| NftError::InvalidMetadata(format!("extra_metadata: {}", e)))?;
+            let json_hash = Sha512_256::new()
+                .chain_update(b"arc0003/amj")
+                .chain_update(json)
+                .finalize();
+            Sha512_256::new()
+                .chain_update(b"arc0003/am")
+                .chain_update(json_hash)
+                .chain_update(extra_metadata)
+                .finalize()
+                .to_vec()
+        }
+    };
+    if expected != metadata_hash {
+        return Err(NftError::MetadataHashMismatch);
+    }
+    Ok(())
+}
+
+/// The note of the latest asset configuration transaction of the asset, if any.
+async fn latest_asset_config_note(
+    indexer: &Indexer,
+    asset_id: u64,
+) -> Result<Option<Vec<u8>>, AlgonautError> {
+    let query = QueryAssetTransaction {
+        tx_type: Some(TransactionType::AssetConfigurationTransaction),
+        ..QueryAssetTransaction::default()
+    };
+    let pages = pages(query, |query| async move {
+        indexer.asset_transactions(asset_id, &query).await
+    });
+    pin_mut!(pages);
+    let mut latest = None;
+    while let Some(transactions) = pages.try_next().await? {
+        if let Some(txn) = transactions.last() {
+            latest = Some(txn.decoded_note()?);
+        }
+    }
+    Ok(latest.flatten())
+}
+
+fn parse_arc69_note(note: &[u8]) -> Result<Arc69Metadata, NftError> {
+    let metadata: Arc69Metadata =
+        serde_json::from_slice(note).map_err(|e| NftError::InvalidMetadata(e.to_string()))?;
+    if metadata.standard != ARC69_STANDARD {
+        return Err(NftError::InvalidMetadata(format!(
+            "Unexpected standard: {}",
+            metadata.standard
+        )));
+    }
+    Ok(metadata)
+}
+
+fn encode_base58(bytes: &[u8]) -> String {
+    let zeros = bytes.iter().take_while(|b| **b == 0).count();
+    // base 58 digits, least significant first
+    let mut digits: Vec<u8> = vec![];
+    for byte in bytes {
+        let mut carry = *byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    std::iter::repeat_n(BASE58_ALPHABET[0], zeros)
+        .chain(
+            digits
+                .iter()
+                .rev()
+                .map(|digit| BASE58_ALPHABET[*digit as usize]),
+        )
+        .map(char::from)
+        .collect()
+}
+
+fn decode_base58(encoded: &str) -> Option<Vec<u8>> {
+    let zeros = encoded
+        .bytes()
+        .take_while(|c| *c == BASE58_ALPHABET[0])
+        .count();
+    // bytes, least significant first
+    let mut bytes: Vec<u8> = vec![];
+    for c in encoded.bytes() {
+        let mut carry = BASE58_ALPHABET.iter().position(|a| *a == c)? as u32;
+        for byte in bytes.iter_mut() {
+            carry += (*byte as u32) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    Some(
+        std::iter::repeat_n(0, zeros)
+            .chain(bytes.into_iter().rev())
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Example of the ARC-19 specification.
+    const RESERVE: &str = "EEQYWGGBHRDAMTEVDPVOSDVX3HJQIG6K6IVNR3RXHYOHV64ZWAEISS4CTI";
+    const CID_V0: &str = "QmQZyq4b89RfaUw8GESPd2re4hJqB8bnm4kVHNtyQrHnnK";
+    // The same digest as CIDv1.
+    const CID_V1_RAW: &str = "bafkreibbegfrrqj4iydezfi35luq5n6z2mcbxsxsflmo4nz6dr5pxgnqba";
+    const CID_V1_DAG_PB: &str = "bafybeibbegfrrqj4iydezfi35luq5n6z2mcbxsxsflmo4nz6dr5pxgnqba";
+
+    fn asset_params(url: &str) -> AssetParams {
+        serde_json::from_value(serde_json::json!({
+            "creator": RESERVE,
+            "decimals": 0,
+            "total": 1,
+            "reserve": RESERVE,
+            "url": url,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_resolve_arc19_url() {
+        assert_eq!(
+            Ok(format!("ipfs://{}/arc3.json", CID_V0)),
+            resolve_arc19_url(&asset_params(
+                "template-ipfs://{ipfscid:0:dag-pb:reserve:sha2-256}/arc3.json"
+            ))
+        );
+        assert_eq!(
+            Ok(format!("ipfs://{}", CID_V1_RAW)),
+            resolve_arc19_url(&asset_params(
+                "template-ipfs://{ipfscid:1:raw:reserve:sha2-256}"
+            ))
+        );
+        assert_eq!(
+            Ok(format!("ipfs://{}", CID_V1_DAG_PB)),
+            resolve_arc19_url(&asset_params(
+                "template-ipfs://{ipfscid:1:dag-pb:reserve:sha2-256}"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_resolve_invalid_arc19_url() {
+        assert!(matches!(
+            resolve_arc19_url(&asset_params(
+                "ipfs://QmQZyq4b89RfaUw8GESPd2re4hJqB8bnm4kVHNtyQrHnnK"
+            )),
+            Err(Arc19Error::NotATemplate(_))
+        ));
+        assert!(matches!(
+            resolve_arc19_url(&asset_params("template-ipfs://{ipfscid:1:raw:reserve}")),
+            Err(Arc19Error::NotATemplate(_))
+        ));
+        assert_eq!(
+            Err(Arc19Error::UnsupportedHashType("sha3-256".to_owned())),
+            resolve_arc19_url(&asset_params(
+                "template-ipfs://{ipfscid:1:raw:reserve:sha3-256}"
+            ))
+        );
+        assert_eq!(
+            Err(Arc19Error::UnsupportedHashType("identity".to_owned())),
+            resolve_arc19_url(&asset_params(
+                "template-ipfs://{ipfscid:1:raw:reserve:identity}"
+            ))
+        );
+        assert_eq!(
+            Err(Arc19Error::UnsupportedVersion("2".to_owned())),
+            resolve_arc19_url(&asset_params(
+                "template-ipfs://{ipfscid:2:raw:reserve:sha2-256}"
+            ))
+        );
+        assert!(matches!(
+            resolve_arc19_url(&asset_params(
+                "template-ipfs://{ipfscid:0:raw:reserve:sha2-256}"
+            )),
+            Err(Arc19Error::UnsupportedCodec(_))
+        ));
+        assert_eq!(
+            Err(Arc19Error::UnsupportedField("manager".to_owned())),
+            resolve_arc19_url(&asset_params(
+                "template-ipfs://{ipfscid:1:raw:manager:sha2-256}"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_arc19_reserve_address() {
+        let reserve: Address = RESERVE.parse().unwrap();
+        assert_eq!(Ok(reserve), arc19_reserve_address(CID_V0));
+        assert_eq!(Ok(reserve), arc19_reserve_address(CID_V1_RAW));
+        assert_eq!(
+            Ok(reserve),
+            arc19_reserve_address(&format!("ipfs://{}", CID_V1_DAG_PB))
+        );
+
+        assert!(arc19_reserve_address("").is_err());
+        assert!(arc19_reserve_address("Qm0OIl").is_err());
+        // identity multihash
+        assert!(arc19_reserve_address("bafkqaaa").is_err());
+    }
+
+    const ARC3_JSON: &str = r#"{"name":"My NFT","description":"My first NFT","image":"ipfs://QmQZyq4b89RfaUw8GESPd2re4hJqB8bnm4kVHNtyQrHnnK","image_mimetype":"image/png","properties":{"rarity":"rare"}}"#;
+    /// SHA-256 of [ARC3_JSON].
+    const ARC3_JSON_HASH: &str = "jeE7bzHJRmu6mdxG6XiMGe8CvKzQqeB4Nk5gnLh6jSM=";
+
+    /// Serves the metadata from memory.
+    struct MockFetcher(Vec<(String, Vec<u8>)>);
+
+    #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+    #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+    impl MetadataFetcher for MockFetcher {
+        async fn fetch(&self, url: &str) -> Result<Vec<u8>, NftError> {
+            self.0
+                .iter()
+                .find(|(u, _)| u == url)
+                .map(|(_, content)| content.clone())
+                .ok_or_else(|| NftError::Fetch(format!("Not found: {}", url)))
+        }
+    }
+
+    fn indexer_asset_params(name: &str, url: &str, metadata_hash: &str) -> IndexerAssetParams {
+        // metadata-hash is deserialized from a borrowed string
+        let json = serde_json::json!({
+            "creator": RESERVE,
+            "decimals": 0,
+            "metadata-hash": metadata_hash,
+            "name": name,
+            "reserve": RESERVE,
+            "total": 1,
+            "url": url,
+        })
+        .to_string();
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn test_is_arc3() {
+        assert!(is_arc3(&indexer_asset_params("arc3", "ipfs://x", "")));
+        assert!(is_arc3(&indexer_asset_params(
+            "My NFT@arc3",
+            "ipfs://x",
+            ""
+        )));
+        assert!(is_arc3(&indexer_asset_params(
+            "My NFT",
+            "ipfs://x#arc3",
+            ""
+        )));
+        assert!(!is_arc3(&indexer_asset_params("My NFT", "ipfs://x", "")));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_arc3_metadata() {
+        let fetcher = MockFetcher(vec![(
+            "ipfs://QmMetadata".to_owned(),
+            ARC3_JSON.as_bytes().to_vec(),
+        )]);
+        let params = indexer_asset_params("My NFT@arc3", "ipfs://QmMetadata#arc3", ARC3_JSON_HASH);
+        let metadata = NftMetadata::Arc3(fetch_arc3_metadata(&params, &fetcher).await.unwrap());
+
+        assert_eq!(NftStandard::Arc3, metadata.standard());
+        assert_eq!(Some("My NFT"), metadata.title());
+        assert_eq!(Some("My first NFT"), metadata.description());
+        assert_eq!(
+            Some("ipfs://QmQZyq4b89RfaUw8GESPd2re4hJqB8bnm4kVHNtyQrHnnK"),
+            metadata.media_url()
+        );
+        assert_eq!(
+            Some(&Value::from("rare")),
+            metadata.properties().get("rarity")
+        );
+
+        // the metadata hash is verified
+        let params = indexer_asset_params(
+            "My NFT@arc3",
+            "ipfs://QmMetadata#arc3",
+            "axSRCIV3VEBeYpYfL8aVKx0FeIcSFKBHITJpp6rgIo4=",
+        );
+        assert_eq!(
+            Err(NftError::MetadataHashMismatch),
+            fetch_arc3_metadata(&params, &fetcher).await
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_arc3_metadata_from_arc19_url() {
+        let fetcher = MockFetcher(vec![(
+            format!("ipfs://{}/metadata.json", CID_V0),
+            ARC3_JSON.as_bytes().to_vec(),
+        )]);
+        let params = indexer_asset_params(
+            "arc3",
+            "template-ipfs://{ipfscid:0:dag-pb:reserve:sha2-256}/metadata.json",
+            "",
+        );
+        assert!(fetch_arc3_metadata(&params, &fetcher).await.is_ok());
+    }
+
+    #[test]
+    fn test_verify_arc3_metadata_hash_with_extra_metadata() {
+        let json = br#"{"name":"My NFT","extra_metadata":"ZXh0cmE="}"#;
+        let metadata: Arc3Metadata = serde_json::from_slice(json).unwrap();
+        // SHA-512/256("arc0003/am" || SHA-512/256("arc0003/amj" || json) || "extra")
+        let metadata_hash = decode_base64(b"axSRCIV3VEBeYpYfL8aVKx0FeIcSFKBHITJpp6rgIo4=").unwrap();
+
+        assert_eq!(
+            Ok(()),
+            verify_arc3_metadata_hash(json, &metadata, &metadata_hash)
+        );
+        assert_eq!(
+            Err(NftError::MetadataHashMismatch),
+            verify_arc3_metadata_hash(json, &metadata, &[0; 32])
+        );
+        // no metadata hash: not verified
+        assert_eq!(Ok(()), verify_arc3_metadata_hash(json, &metadata, &[]));
+    }
+
+    #[test]
+    fn test_parse_arc69_note() {
+        let metadata = NftMetadata::Arc69(
+            parse_arc69_note(
+                br#"{
+                    "standard": "arc69",
+                    "description": "My first NFT",
+                    "media_url": "ipfs://QmQZyq4b89RfaUw8GESPd2re4hJqB8bnm4kVHNtyQrHnnK#i",
+                    "mime_type": "image/png",
+                    "properties": { "rarity": "rare" }
+                }"#,
+            )
+            .unwrap(),
+        );
+
+        assert_eq!(NftStandard::Arc69, metadata.standard());
+        assert_eq!(None, metadata.title());
+        assert_eq!(Some("My first NFT"), metadata.description());
+        assert_eq!(
+            Some("ipfs://QmQZyq4b89RfaUw8GESPd2re4hJqB8bnm4kVHNtyQrHnnK#i"),
+            metadata.media_url()
+        );
+        assert_eq!(
+            Some(&Value::from("rare")),
+            metadata.properties().get("rarity")
+        );
+    }
+
+    #[test]
+    fn test_parse_malformed_arc69_note() {
+        for note in [
+            &b"not json"[..],
+            br#"{"standard": "arc69""#,
+            br#"{"description": "no standard"}"#,
+            br#"{"standard": "arc3"}"#,
+            &[0xff, 0xfe],
+        ] {
+            assert!(matches!(
+                parse_arc69_note(note),
+                Err(NftError::InvalidMetadata(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,315 @@
+//! Presets of the base urls and the authentication headers of common node providers.
+//!
+//! Each provider has its own convention, and a wrong header name is usually rejected with a 401 or
+//! 403 that doesn't tell which header was expected. The presets build the clients with the
+//! regular constructors ([Algod::with_headers], [Indexer::with_headers]): the url and the headers
+//! of a preset can be overridden before building the client, or passed to the other constructors,
+//! e.g. [Algod::with_config].
+//!
+//! ```
+//! # use algonaut::provider::NodePreset;
+//! # use algonaut_core::Network;
+//! let preset = NodePreset::purestake_indexer(&Network::TestNet, "my-api-key").unwrap();
+//! assert_eq!("https://testnet-algorand.api.purestake.io/idx2/", preset.url());
+//! assert_eq!(vec![("X-API-Key", "my-api-key")], preset.headers());
+//! let indexer = preset.indexer().unwrap();
+//! ```
+
+use crate::{algod::v2::Algod, error::AlgonautError, indexer::v2::Indexer, kmd::v1::Kmd};
+use algonaut_client::{Headers, RedactedHeaders};
+use algonaut_core::Network;
+use std::fmt;
+
+/// The API token of the sandbox (and AlgoKit LocalNet) algod.
+pub const SANDBOX_TOKEN: &str = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+
+const SANDBOX_ALGOD_URL: &str = "http://localhost:4001/";
+const SANDBOX_INDEXER_URL: &str = "http://localhost:8980/";
+
+/// Header of the algod API token.
+pub(crate) const ALGOD_TOKEN_HEADER: &str = "X-Algo-API-Token";
+/// Header of the PureStake API key.
+const PURESTAKE_KEY_HEADER: &str = "X-API-Key";
+
+/// The base url of a node and the headers sent with each request.
+///
+/// Its debug output redacts the values of the sensitive headers (e.g. the API token).
+#[derive(Clone, PartialEq, Eq)]
+pub struct NodePreset {
+    url: String,
+    headers: Vec<(String, String)>,
+}
+
+impl fmt::Debug for NodePreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let headers = self
+            .headers
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()));
+        f.debug_struct("NodePreset")
+            .field("url", &self.url)
+            .field("headers", &RedactedHeaders::new(headers))
+            .finish()
+    }
+}
+
+impl NodePreset {
+    pub fn new(url: &str, headers: Headers) -> NodePreset {
+        NodePreset {
+            url: url.to_owned(),
+            headers: headers
+                .into_iter()
+                .map(|(name, value)| (name.to_owned(), value.to_owned()))
+                .collect(),
+        }
+    }
+
+    /// The algod of a local sandbox, at `http://localhost:4001` with the [SANDBOX_TOKEN].
+    pub fn sandbox_algod() -> NodePreset {
+        NodePreset::new(SANDBOX_ALGOD_URL, vec![(ALGOD_TOKEN_HEADER, SANDBOX_TOKEN)])
+    }
+
+    /// The indexer of a local sandbox, at `http://localhost:8980`, without token.
+    pub fn sandbox_indexer() -> NodePreset {
+        NodePreset::new(SANDBOX_INDEXER_URL, vec![])
+    }
+
+    /// The free algod of [AlgoNode](https://algonode.io) for the network, without token.
+    ///
+    /// Fails with [AlgonautError::UnsupportedNetwork] for the networks other than MainNet, TestNet
+    /// and BetaNet.
+    pub fn algonode_algod(network: &Network) -> Result<NodePreset, AlgonautError> {
+        let url = format!(
+            "https://{}-api.algonode.cloud/",
+            network_prefix("AlgoNode", network)?
+        );
+        Ok(NodePreset::new(&url, vec![]))
+    }
+
+    /// The free indexer of [AlgoNode](https://algonode.io) for the network, without token.
+    ///
+    /// Fails with [AlgonautError::UnsupportedNetwork] for the networks other than MainNet, TestNet
+    /// and BetaNet.
+    pub fn algonode_indexer(network: &Network) -> Result<NodePreset, AlgonautError> {
+        let url = format!(
+            "https://{}-idx.algonode.cloud/",
+            network_prefix("AlgoNode", network)?
+        );
+        Ok(NodePreset::new(&url, vec![]))
+    }
+
+    /// The algod of PureStake for the network, with the API key in the `X-API-Key` header.
+    ///
+    /// Fails with [AlgonautError::UnsupportedNetwork] for the networks other than MainNet, TestNet
+    /// and BetaNet.
+    pub fn purestake_algod(network: &Network, api_key: &str) -> Result<NodePreset, AlgonautError> {
+        let url = format!(
+            "https://{}-algorand.api.purestake.io/ps2/",
+            network_prefix("PureStake", network)?
+        );
+        Ok(NodePreset::new(&url, vec![(PURESTAKE_KEY_HEADER, api_key)]))
+    }
+
+    /// The indexer of PureStake for the network, with the API key in the `X-API-Key` header.
+    ///
+    /// Fails with [AlgonautError::UnsupportedNetwork] for the networks other than MainNet, TestNet
+    /// and BetaNet.
+    pub fn purestake_indexer(
+        network: &Network,
+        api_key: &str,
+    ) -> Result<NodePreset, AlgonautError> {
+        let url = format!(
+            "https://{}-algorand.api.purestake.io/idx2/",
+            network_prefix("PureStake", network)?
+        );
+        Ok(NodePreset::new(&url, vec![(PURESTAKE_KEY_HEADER, api_key)]))
+    }
+
+    /// Overrides the base url, e.g. for a sandbox on another host.
+    pub fn with_url(mut self, url: &str) -> NodePreset {
+        self.url = url.to_owned();
+        self
+    }
+
+    /// Sets a header, replacing the header with the same name (case insensitive) if any, e.g. to
+    /// use another token.
+    pub fn with_header(mut self, name: &str, value: &str) -> NodePreset {
+        self.headers
+            .retain(|(header, _)| !header.eq_ignore_ascii_case(name));
+        self.headers.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn headers(&self) -> Headers<'_> {
+        self.headers
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect()
+    }
+
+    /// Builds an algod client with the url and the headers of the preset.
+    ///
+    /// Returns an error if the url or the headers have an invalid format.
+    pub fn algod(&self) -> Result<Algod, AlgonautError> {
+        Algod::with_headers(&self.url, self.headers())
+    }
+
+    /// Builds an indexer client with the url and the headers of the preset.
+    ///
+    /// Returns an error if the url or the headers have an invalid format.
+    pub fn indexer(&self) -> Result<Indexer, AlgonautError> {
+        Indexer::with_headers(&self.url, self.headers())
+    }
+
+    /// Builds a kmd client with the url and the headers of the preset.
+    ///
+    /// Returns an error if the url or the headers have an invalid format.
+    pub fn kmd(&self) -> Result<Kmd, AlgonautError> {
+        Kmd::with_headers(&self.url, self.headers())
+    }
+}
+
+/// The prefix of the hosts of the network, for the providers serving the public networks.
+fn network_prefix(
+    provider: &'static str,
+    network: &Network,
+) -> Result<&'static str, AlgonautError> {
+    match network {
+        Network::MainNet => Ok("mainnet"),
+        Network::TestNet => Ok("testnet"),
+        Network::BetaNet => Ok("betanet"),
+        Network::Other(genesis_hash) => Err(AlgonautError::UnsupportedNetwork {
+            provider,
+            genesis_hash: genesis_hash.clone(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use algonaut_client::test_server::{TestResponse, TestServer};
+
+    #[test]
+    fn test_sandbox_presets() {
+        let algod = NodePreset::sandbox_algod();
+        assert_eq!("http://localhost:4001/", algod.url());
+        assert_eq!(vec![("X-Algo-API-Token", SANDBOX_TOKEN)], algod.headers());
+
+        let indexer = NodePreset::sandbox_indexer();
+        assert_eq!("http://localhost:8980/", indexer.url());
+        assert!(indexer.headers().is_empty());
+    }
+
+    #[test]
+    fn test_debug_without_the_token() {
+        let preset = NodePreset::purestake_indexer(&Network::TestNet, "my-api-key").unwrap();
+        let debug = format!("{:?}", preset);
+        assert!(!debug.contains("my-api-key"), "{}", debug);
+        assert!(debug.contains("purestake.io"), "{}", debug);
+
+        let debug = format!("{:?}", preset.indexer().unwrap());
+        assert!(!debug.contains("my-api-key"), "{}", debug);
+    }
+
+    #[test]
+    fn test_algonode_presets() {
+        for (network, prefix) in [
+            (Network::MainNet, "mainnet"),
+            (Network::TestNet, "testnet"),
+            (Network::BetaNet, "betanet"),
+        ] {
+            let algod = NodePreset::algonode_algod(&network).unwrap();
+            assert_eq!(
+                format!("https://{}-api.algonode.cloud/", prefix),
+                algod.url()
+            );
+            assert!(algod.headers().is_empty());
+
+            let indexer = NodePreset::algonode_indexer(&network).unwrap();
+            assert_eq!(
+                format!("https://{}-idx.algonode.cloud/", prefix),
+                indexer.url()
+            );
+            assert!(indexer.headers().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_purestake_presets() {
+        let algod = NodePreset::purestake_algod(&Network::MainNet, "key").unwrap();
+        assert_eq!(
+            "https://mainnet-algorand.api.purestake.io/ps2/",
+            algod.url()
+        );
+        assert_eq!(vec![("X-API-Key", "key")], algod.headers());
+
+        let indexer = NodePreset::purestake_indexer(&Network::BetaNet, "key").unwrap();
+        assert_eq!(
+            "https://betanet-algorand.api.purestake.io/idx2/",
+            indexer.url()
+        );
+        assert_eq!(vec![("X-API-Key", "key")], indexer.headers());
+    }
+
+    #[test]
+    fn test_unsupported_network() {
+        let network = Network::Other("SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=".to_owned());
+        assert_eq!(
+            Err(AlgonautError::UnsupportedNetwork {
+                provider: "PureStake",
+                genesis_hash: "SGO1GKSzyE7IEPItTxCByw9x8FmnrCDexi9/cOUJOiI=".to_owned()
+            }),
+            NodePreset::purestake_algod(&network, "key")
+        );
+        assert!(NodePreset::algonode_indexer(&network).is_err());
+    }
+
+    #[test]
+    fn test_overrides() {
+        let preset = NodePreset::sandbox_algod()
+            .with_url("http://sandbox:4001/")
+            .with_header("x-algo-api-token", "b")
+            .with_header("X-Extra", "c");
+        assert_eq!("http://sandbox:4001/", preset.url());
+        assert_eq!(
+            vec![("x-algo-api-token", "b"), ("X-Extra", "c")],
+            preset.headers()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_algod_sends_the_preset_header() {
+        let server = TestServer::serve(vec![TestResponse::json(r#"{"versions":["v2"]}"#)]);
+        let algod = NodePreset::sandbox_algod()
+            .with_url(server.url())
+            .algod()
+            .unwrap();
+
+        let _ = algod.versions().await;
+
+        let request = server.request();
+        assert_eq!("GET /versions HTTP/1.1", request.line);
+        assert_eq!(Some(SANDBOX_TOKEN), request.header("x-algo-api-token"));
+    }
+
+    #[tokio::test]
+    async fn test_indexer_sends_the_preset_header_under_the_url_path() {
+        let server = TestServer::serve(vec![TestResponse::json("{}")]);
+        let indexer = NodePreset::purestake_indexer(&Network::TestNet, "my-key")
+            .unwrap()
+            .with_url(&format!("{}idx2/", server.url()))
+            .indexer()
+            .unwrap();
+
+        let _ = indexer.health().await;
+
+        let request = server.request();
+        assert_eq!("GET /idx2/health HTTP/1.1", request.line);
+        assert_eq!(Some("my-key"), request.header("x-api-key"));
+    }
+}
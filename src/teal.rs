@@ -0,0 +1,403 @@
+//! TEAL templates: programs distributed with placeholder parameters (e.g. the receiver and the
+//! amount of an escrow), to substitute before compiling.
+//!
+//! The placeholders are the words starting with `TMPL_` (e.g. `TMPL_RCV`), outside of the
+//! comments and of the string literals:
+//!
+//! ```
+//! # use algonaut::teal::{Template, TemplateValue};
+//! # use algonaut_core::Address;
+//! # use std::collections::HashMap;
+//! let template = Template::new(
+//!     "#pragma version 2\ntxn Receiver\naddr TMPL_RCV\n==\ntxn Amount\nint TMPL_AMT\n<=\n&&",
+//! );
+//! assert_eq!(vec!["TMPL_RCV", "TMPL_AMT"], template.placeholders());
+//!
+//! let mut values = HashMap::new();
+//! values.insert("TMPL_RCV", TemplateValue::from(Address::new([0; 32])));
+//! values.insert("TMPL_AMT", TemplateValue::from(100_000));
+//! let program = template.substitute(&values);
+//! assert!(program.placeholders().is_empty());
+//! // let compiled = program.compile(&algod).await?;
+//! ```
+
+use algonaut_core::{Address, CompiledTeal};
+use data_encoding::{BASE32_NOPAD, BASE64, HEXLOWER};
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::ops::Range;
+
+use crate::algod::v2::AlgodService;
+use crate::error::AlgonautError;
+
+/// The prefix of the placeholders.
+const PLACEHOLDER_PREFIX: &str = "TMPL_";
+
+/// TEAL source with `TMPL_*` placeholders, see the [module documentation](self).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    source: String,
+}
+
+impl Template {
+    pub fn new(source: &str) -> Template {
+        Template {
+            source: source.to_owned(),
+        }
+    }
+
+    /// The TEAL source, with the placeholders substituted so far.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// The placeholders left, in order of first appearance and without duplicates.
+    pub fn placeholders(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = vec![];
+        for range in placeholder_ranges(&self.source) {
+            let name = &self.source[range];
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    /// The template with the placeholders in `values`, by name (e.g. "TMPL_RCV"), replaced with
+    /// their TEAL literal. The placeholders without a value are kept, and the values without a
+    /// placeholder ignored, so a template can be substituted in several steps.
+    pub fn substitute(&self, values: &HashMap<&str, TemplateValue>) -> Template {
+        let mut source = String::with_capacity(self.source.len());
+        let mut end = 0;
+        for range in placeholder_ranges(&self.source) {
+            if let Some(value) = values.get(&self.source[range.clone()]) {
+                let before = &self.source[end..range.start];
+                let previous_word = before.split_whitespace().last().unwrap_or_default();
+                source.push_str(before);
+                source.push_str(&value.literal(previous_word));
+                end = range.end;
+            }
+        }
+        source.push_str(&self.source[end..]);
+        Template { source }
+    }
+
+    /// The TEAL program, failing with [AlgonautError::UnsubstitutedPlaceholders] if placeholders
+    /// are left.
+    pub fn teal(&self) -> Result<&str, AlgonautError> {
+        let names = self.placeholders();
+        if names.is_empty() {
+            Ok(&self.source)
+        } else {
+            Err(AlgonautError::UnsubstitutedPlaceholders {
+                names: names.into_iter().map(str::to_owned).collect(),
+            })
+        }
+    }
+
+    /// Compiles the program with algod, see
+    /// [Algod::compile_teal](crate::algod::v2::Algod::compile_teal). Fails with
+    /// [AlgonautError::UnsubstitutedPlaceholders], without calling algod, if placeholders are left.
+    pub async fn compile(&self, algod: &impl AlgodService) -> Result<CompiledTeal, AlgonautError> {
+        algod.compile_teal(self.teal()?.as_bytes()).await
+    }
+}
+
+/// The value of a placeholder, rendered as the TEAL literal of its type: a decimal integer for
+/// `int`, the address for `addr` and, for `byte`, a hex string (`0x...`), or the base64 or base32
+/// encoding if the placeholder follows `base64` or `base32` (e.g. `byte base64 TMPL_HASHIMG`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateValue {
+    Int(u64),
+    Address(Address),
+    Bytes(Vec<u8>),
+    /// Source inserted as is, e.g. the hash opcode (`sha256`, `keccak256`) of `TMPL_HASHFN`.
+    Verbatim(String),
+}
+
+impl TemplateValue {
+    /// The literal of the value in the source, after `previous_word`.
+    fn literal(&self, previous_word: &str) -> String {
+        match (self, previous_word) {
+            (TemplateValue::Bytes(bytes), "base64" | "b64") => BASE64.encode(bytes),
+            (TemplateValue::Bytes(bytes), "base32" | "b32") => BASE32_NOPAD.encode(bytes),
+            _ => self.to_string(),
+        }
+    }
+}
+
+/// The literal of the value, with the bytes in hex.
+impl Display for TemplateValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateValue::Int(int) => write!(f, "{}", int),
+            TemplateValue::Address(address) => write!(f, "{}", address),
+            TemplateValue::Bytes(bytes) => write!(f, "0x{}", HEXLOWER.encode(bytes)),
+            TemplateValue::Verbatim(source) => write!(f, "{}", source),
+        }
+    }
+}
+
+impl From<u64> for TemplateValue {
+    fn from(int: u64) -> Self {
+        TemplateValue::Int(int)
+    }
+}
+
+impl From<Address> for TemplateValue {
+    fn from(address: Address) -> Self {
+        TemplateValue::Address(address)
+    }
+}
+
+impl From<Vec<u8>> for TemplateValue {
+    fn from(bytes: Vec<u8>) -> Self {
+        TemplateValue::Bytes(bytes)
+    }
+}
+
+impl From<&[u8]> for TemplateValue {
+    fn from(bytes: &[u8]) -> Self {
+        TemplateValue::Bytes(bytes.to_vec())
+    }
+}
+
+/// The byte ranges of the placeholders of `source`, in order, skipping the comments and the string
+/// literals.
+fn placeholder_ranges(source: &str) -> Vec<Range<usize>> {
+    let is_word = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let mut ranges = vec![];
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut chars = source.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if in_string {
+            match c {
+                '\n' => in_string = false,
+                '"' if !escaped => in_string = false,
+                _ => {}
+            }
+            escaped = c == '\\' && !escaped;
+        } else if c == '"' {
+            in_string = true;
+        } else if source[i..].starts_with("//") {
+            // skip to the end of the line
+            while chars.next_if(|(_, c)| *c != '\n').is_some() {}
+        } else if is_word(c) {
+            let mut end = i + c.len_utf8();
+            while let Some((j, c)) = chars.next_if(|(_, c)| is_word(*c)) {
+                end = j + c.len_utf8();
+            }
+            let word = &source[i..end];
+            if word.len() > PLACEHOLDER_PREFIX.len() && word.starts_with(PLACEHOLDER_PREFIX) {
+                ranges.push(i..end);
+            }
+        }
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockAlgod;
+
+    /// The hash time lock contract template of go-algorand (`tools/teal/templates/htlc.teal.tmpl`),
+    /// also shipped by the SDKs: the funds are closed to `TMPL_RCV` with a preimage of
+    /// `TMPL_HASHIMG` under `TMPL_HASHFN`, or to `TMPL_OWN` after `TMPL_TIMEOUT`.
+    const HTLC: &str = r#"// Implements a hash time lock contract
+// Parameters:
+//  - TMPL_RCV: the address to send funds to when the preimage is supplied
+//  - TMPL_HASHFN: the specific hash function (sha256 or keccak256) to use
+//  - TMPL_HASHIMG: the image of the hash function
+//  - TMPL_TIMEOUT: the round at which the account expires
+//  - TMPL_OWN: the address to refund funds to on timeout
+//  - TMPL_FEE: maximum fee used by any transaction spending from this account
+txn Fee
+int TMPL_FEE
+<=
+txn TypeEnum
+int 1
+==
+&&
+txn Receiver
+global ZeroAddress
+==
+&&
+txn Amount
+int 0
+==
+&&
+txn CloseRemainderTo
+addr TMPL_RCV
+==
+arg_0
+TMPL_HASHFN
+byte base64 TMPL_HASHIMG
+==
+&&
+txn CloseRemainderTo
+addr TMPL_OWN
+==
+txn FirstValid
+int TMPL_TIMEOUT
+>
+&&
+||
+&&
+"#;
+
+    fn values(receiver: u8, timeout: u64) -> HashMap<&'static str, TemplateValue> {
+        vec![
+            ("TMPL_FEE", TemplateValue::from(1_000)),
+            ("TMPL_RCV", Address::new([receiver; 32]).into()),
+            ("TMPL_OWN", Address::new([9; 32]).into()),
+            ("TMPL_HASHFN", TemplateValue::Verbatim("sha256".to_owned())),
+            ("TMPL_HASHIMG", vec![0xab; 32].into()),
+            ("TMPL_TIMEOUT", timeout.into()),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn test_placeholders() {
+        assert_eq!(
+            vec![
+                "TMPL_FEE",
+                "TMPL_RCV",
+                "TMPL_HASHFN",
+                "TMPL_HASHIMG",
+                "TMPL_OWN",
+                "TMPL_TIMEOUT"
+            ],
+            Template::new(HTLC).placeholders()
+        );
+        assert_eq!(
+            vec!["TMPL_A"],
+            Template::new("int TMPL_A\nint TMPL_A\n+").placeholders()
+        );
+    }
+
+    #[test]
+    fn test_placeholders_outside_of_comments_and_strings() {
+        let template = Template::new(
+            "byte \"TMPL_IN_STRING \\\" TMPL_ESCAPED\"\nint TMPL_X // TMPL_IN_COMMENT\nbyte \"a//b\"\nint TMPL_Y\nint NOT_TMPL_Z\nint TMPL_",
+        );
+
+        assert_eq!(vec!["TMPL_X", "TMPL_Y"], template.placeholders());
+    }
+
+    #[test]
+    fn test_substitute() {
+        let program = Template::new(HTLC).substitute(&values(1, 3_000));
+        let source = program.source();
+
+        assert!(program.placeholders().is_empty());
+        assert!(source.contains("\nint 1000\n"));
+        assert!(source.contains(&format!("\naddr {}\n", Address::new([1; 32]))));
+        assert!(source.contains(&format!("\naddr {}\n", Address::new([9; 32]))));
+        assert!(source.contains(&format!(
+            "\narg_0\nsha256\nbyte base64 {}\n",
+            BASE64.encode(&[0xab; 32])
+        )));
+        assert!(source.contains("\nint 3000\n"));
+        // the comments are kept as is
+        assert!(source.contains("//  - TMPL_FEE: maximum fee"));
+        assert_eq!(Ok(source), program.teal().map_err(|e| e.to_string()));
+    }
+
+    #[test]
+    fn test_substitute_is_deterministic() {
+        let program = |receiver, timeout| {
+            Template::new(HTLC)
+                .substitute(&values(receiver, timeout))
+                .source()
+                .to_owned()
+        };
+
+        assert_eq!(program(1, 3_000), program(1, 3_000));
+        assert_ne!(program(1, 3_000), program(2, 3_000));
+        assert_ne!(program(1, 3_000), program(1, 3_001));
+    }
+
+    #[test]
+    fn test_substitute_in_steps() {
+        let mut first = values(1, 3_000);
+        let second: HashMap<_, _> = first.remove_entry("TMPL_TIMEOUT").into_iter().collect();
+        let template = Template::new(HTLC).substitute(&first);
+
+        assert_eq!(vec!["TMPL_TIMEOUT"], template.placeholders());
+        assert_eq!(
+            Template::new(HTLC).substitute(&values(1, 3_000)),
+            template.substitute(&second)
+        );
+    }
+
+    #[test]
+    fn test_unsubstituted_placeholders() {
+        let mut values = values(1, 3_000);
+        values.remove("TMPL_RCV");
+        values.remove("TMPL_TIMEOUT");
+
+        assert_eq!(
+            Some(AlgonautError::UnsubstitutedPlaceholders {
+                names: vec!["TMPL_RCV".to_owned(), "TMPL_TIMEOUT".to_owned()]
+            }),
+            Template::new(HTLC).substitute(&values).teal().err()
+        );
+    }
+
+    #[test]
+    fn test_template_values() {
+        assert_eq!(
+            "18446744073709551615",
+            TemplateValue::Int(u64::MAX).to_string()
+        );
+        assert_eq!(
+            "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAY5HFKQ",
+            TemplateValue::Address(Address::new([0; 32])).to_string()
+        );
+        assert_eq!(
+            "0x00ff10",
+            TemplateValue::from(&[0, 255, 16][..]).to_string()
+        );
+        assert_eq!("0x", TemplateValue::Bytes(vec![]).to_string());
+        assert_eq!(
+            "AD7RA",
+            TemplateValue::from(&[0, 255, 16][..]).literal("base32")
+        );
+        assert_eq!(
+            "AP8Q",
+            TemplateValue::from(&[0, 255, 16][..]).literal("b64")
+        );
+        assert_eq!(
+            "0x00ff10",
+            TemplateValue::from(&[0, 255, 16][..]).literal("byte")
+        );
+        assert_eq!("10", TemplateValue::Int(10).literal("base64"));
+    }
+
+    #[tokio::test]
+    async fn test_compile() {
+        let algod = MockAlgod::new();
+        algod.respond("compile_teal", Ok(CompiledTeal(vec![2, 32])));
+        let template = Template::new(HTLC);
+
+        let res = template.compile(&algod).await;
+        assert!(matches!(
+            res,
+            Err(AlgonautError::UnsubstitutedPlaceholders { .. })
+        ));
+        // algod isn't called
+        assert!(algod.calls().is_empty());
+
+        let program = template.substitute(&values(1, 3_000));
+        let res = program.compile(&algod).await;
+        assert_eq!(Some(CompiledTeal(vec![2, 32])), res.ok());
+        assert_eq!(
+            format!("{:?}", program.source().as_bytes()),
+            algod.calls()[0].args
+        );
+    }
+}
@@ -0,0 +1,1074 @@
+//! Mocks of the algod and indexer services, to test code written against [AlgodService] and
+//! [IndexerService] without a node.
+//!
+//! Requires the `testing` feature.
+//!
+//! The mocks answer each call with a canned response, set up in advance for a method and its
+//! arguments ([respond_to](MockIndexer::respond_to)) or for any arguments
+//! ([respond](MockIndexer::respond)), and record the calls. Calls without a canned response fail
+//! with [AlgonautError::Internal].
+//!
+//! ```
+//! # use algonaut::{error::AlgonautError, indexer::v2::IndexerService, testing::MockIndexer};
+//! # use algonaut_core::Round;
+//! # use algonaut_model::indexer::v2::HealthCheck;
+//! async fn indexer_round(indexer: &impl IndexerService) -> Result<Round, AlgonautError> {
+//!     Ok(indexer.health_check().await?.round)
+//! }
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let indexer = MockIndexer::new();
+//! let health: HealthCheck<serde_json::Value> = serde_json::from_str(
+//!     r#"{"data":null,"db-available":true,"is-migrating":false,"message":"","round":123,"version":"2.6.4"}"#,
+//! )
+//! .unwrap();
+//! indexer.respond("health_check", Ok(health));
+//!
+//! assert_eq!(indexer_round(&indexer).await, Ok(Round(123)));
+//! assert_eq!(indexer.calls().len(), 1);
+//! # }
+//! ```
+
+#[cfg(all(not(target_arch = "wasm32"), feature = "tokio"))]
+use std::time::{Duration, Instant};
+use std::{
+    any::Any,
+    collections::HashMap,
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use algonaut_core::{
+    Address, CompiledTeal, MicroAlgos, Network, Round, SuggestedTransactionParams,
+};
+use algonaut_model::{
+    algod::v2::{
+        Account as AlgodAccount, ApiSpec, Application, Asset, Block as AlgodBlock,
+        BlockHashResponse, Catchup, DryrunRequest, DryrunResponse, GenesisBlock, KeyRegistration,
+        NodeStatus, PendingTransaction, PendingTransactions, Supply, TransactionParams,
+        TransactionResponse as AlgodTransactionResponse, Version,
+    },
+    indexer::v2::{
+        AccountInfoResponse, AccountResponse, AccountTransactionResponse, ApplicationInfoResponse,
+        ApplicationResponse, AssetResponse, AssetTransactionResponse, AssetsInfoResponse,
+        BalancesResponse, Block, HealthCheck, QueryAccount, QueryAccountInfo,
+        QueryAccountTransaction, QueryApplicationInfo, QueryApplications, QueryAssetTransaction,
+        QueryAssets, QueryAssetsInfo, QueryBalances, QueryTransaction, TransactionInfoResponse,
+        TransactionResponse,
+    },
+};
+use algonaut_transaction::{
+    account::Account, error::TransactionError, Pay, SignedTransaction, TxnBuilder,
+};
+use async_trait::async_trait;
+use thiserror::Error;
+
+use crate::{
+    algod::v2::{AlgodSemanticError, AlgodService},
+    error::AlgonautError,
+    indexer::v2::IndexerService,
+    kmd::v1::Kmd,
+};
+
+/// A call received by a mock.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockCall {
+    /// Name of the method of the service.
+    pub method: &'static str,
+    /// Debug representation of the arguments: the argument, or a tuple of the arguments if the
+    /// method has more than one (`()` if it has none).
+    pub args: String,
+}
+
+/// A canned `Result<T, AlgonautError>`, `T` being the return type of the method.
+type Response = Arc<dyn Any + Send + Sync>;
+
+/// Canned responses and recorded calls, shared by the mocks.
+#[derive(Default)]
+struct MockResponses {
+    /// Responses by method and arguments, `None` matching any arguments.
+    responses: Mutex<HashMap<(&'static str, Option<String>), Response>>,
+    calls: Mutex<Vec<MockCall>>,
+}
+
+impl MockResponses {
+    fn respond<T>(
+        &self,
+        method: &'static str,
+        args: Option<String>,
+        response: Result<T, AlgonautError>,
+    ) where
+        T: Send + Sync + 'static,
+    {
+        self.responses
+            .lock()
+            .unwrap()
+            .insert((method, args), Arc::new(response));
+    }
+
+    fn call<T>(&self, method: &'static str, args: String) -> Result<T, AlgonautError>
+    where
+        T: Clone + 'static,
+    {
+        self.calls.lock().unwrap().push(MockCall {
+            method,
+            args: args.clone(),
+        });
+
+        let responses = self.responses.lock().unwrap();
+        let response = responses
+            .get(&(method, Some(args.clone())))
+            .or_else(|| responses.get(&(method, None)))
+            .ok_or_else(|| {
+                AlgonautError::Internal(format!("No mock response for {}{}", method, args))
+            })?;
+        response
+            .downcast_ref::<Result<T, AlgonautError>>()
+            .cloned()
+            .ok_or_else(|| {
+                AlgonautError::Internal(format!(
+                    "Mock response for {} isn't a {}",
+                    method,
+                    std::any::type_name::<T>()
+                ))
+            })?
+    }
+
+    fn calls(&self) -> Vec<MockCall> {
+        self.calls.lock().unwrap().clone()
+    }
+}
+
+/// Mock implementing [IndexerService] with canned responses.
+///
+/// The methods are the ones of [IndexerService] and the responses their return values, e.g.
+/// `mock.respond("accounts", Ok(AccountResponse { .. }))`.
+#[derive(Default)]
+pub struct MockIndexer {
+    responses: MockResponses,
+}
+
+impl MockIndexer {
+    pub fn new() -> MockIndexer {
+        MockIndexer::default()
+    }
+
+    /// Responds to the calls of `method`, whatever their arguments, with `response`.
+    ///
+    /// Responses set with [respond_to](Self::respond_to) for specific arguments take precedence.
+    pub fn respond<T>(&self, method: &'static str, response: Result<T, AlgonautError>)
+    where
+        T: Send + Sync + 'static,
+    {
+        self.responses.respond(method, None, response)
+    }
+
+    /// Responds to the calls of `method` with `args` with `response`.
+    ///
+    /// `args` is the argument of the method, or a tuple of the arguments if it has more than one
+    /// (`()` if it has none), matched by its debug representation.
+    pub fn respond_to<A, T>(
+        &self,
+        method: &'static str,
+        args: A,
+        response: Result<T, AlgonautError>,
+    ) where
+        A: Debug,
+        T: Send + Sync + 'static,
+    {
+        self.responses
+            .respond(method, Some(format!("{:?}", args)), response)
+    }
+
+    /// The calls received so far, in order.
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.responses.calls()
+    }
+}
+
+/// Mock implementing [AlgodService] with canned responses.
+///
+/// The methods are the ones of [AlgodService] and the responses their return values, e.g.
+/// `mock.respond("status", Ok(NodeStatus { .. }))`.
+#[derive(Default)]
+pub struct MockAlgod {
+    responses: MockResponses,
+}
+
+impl MockAlgod {
+    pub fn new() -> MockAlgod {
+        MockAlgod::default()
+    }
+
+    /// Responds to the calls of `method`, whatever their arguments, with `response`.
+    ///
+    /// Responses set with [respond_to](Self::respond_to) for specific arguments take precedence.
+    pub fn respond<T>(&self, method: &'static str, response: Result<T, AlgonautError>)
+    where
+        T: Send + Sync + 'static,
+    {
+        self.responses.respond(method, None, response)
+    }
+
+    /// Responds to the calls of `method` with `args` with `response`.
+    ///
+    /// `args` is the argument of the method, or a tuple of the arguments if it has more than one
+    /// (`()` if it has none), matched by its debug representation.
+    pub fn respond_to<A, T>(
+        &self,
+        method: &'static str,
+        args: A,
+        response: Result<T, AlgonautError>,
+    ) where
+        A: Debug,
+        T: Send + Sync + 'static,
+    {
+        self.responses
+            .respond(method, Some(format!("{:?}", args)), response)
+    }
+
+    /// The calls received so far, in order.
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.responses.calls()
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl IndexerService for MockIndexer {
+    async fn health(&self) -> Result<(), AlgonautError> {
+        self.responses.call("health", "()".to_owned())
+    }
+
+    async fn health_check(&self) -> Result<HealthCheck<serde_json::Value>, AlgonautError> {
+        self.responses.call("health_check", "()".to_owned())
+    }
+
+    async fn network(&self) -> Result<Network, AlgonautError> {
+        self.responses.call("network", "()".to_owned())
+    }
+
+    async fn suggested_params(&self) -> Result<SuggestedTransactionParams, AlgonautError> {
+        self.responses.call("suggested_params", "()".to_owned())
+    }
+
+    async fn accounts(&self, query: &QueryAccount) -> Result<AccountResponse, AlgonautError> {
+        self.responses.call("accounts", format!("{:?}", query))
+    }
+
+    async fn account_info(
+        &self,
+        address: &Address,
+        query: &QueryAccountInfo,
+    ) -> Result<AccountInfoResponse, AlgonautError> {
+        self.responses
+            .call("account_info", format!("{:?}", (address, query)))
+    }
+
+    async fn account_transactions(
+        &self,
+        address: &Address,
+        query: &QueryAccountTransaction,
+    ) -> Result<AccountTransactionResponse, AlgonautError> {
+        self.responses
+            .call("account_transactions", format!("{:?}", (address, query)))
+    }
+
+    async fn balance_history(
+        &self,
+        address: &Address,
+        from: Round,
+        to: Round,
+    ) -> Result<Vec<(Round, MicroAlgos)>, AlgonautError> {
+        self.responses
+            .call("balance_history", format!("{:?}", (address, from, to)))
+    }
+
+    async fn applications(
+        &self,
+        query: &QueryApplications,
+    ) -> Result<ApplicationResponse, AlgonautError> {
+        self.responses.call("applications", format!("{:?}", query))
+    }
+
+    async fn application_info(
+        &self,
+        id: u64,
+        query: &QueryApplicationInfo,
+    ) -> Result<ApplicationInfoResponse, AlgonautError> {
+        self.responses
+            .call("application_info", format!("{:?}", (id, query)))
+    }
+
+    async fn assets(&self, query: &QueryAssets) -> Result<AssetResponse, AlgonautError> {
+        self.responses.call("assets", format!("{:?}", query))
+    }
+
+    async fn assets_info(
+        &self,
+        id: u64,
+        query: &QueryAssetsInfo,
+    ) -> Result<AssetsInfoResponse, AlgonautError> {
+        self.responses
+            .call("assets_info", format!("{:?}", (id, query)))
+    }
+
+    async fn asset_balances(
+        &self,
+        id: u64,
+        query: &QueryBalances,
+    ) -> Result<BalancesResponse, AlgonautError> {
+        self.responses
+            .call("asset_balances", format!("{:?}", (id, query)))
+    }
+
+    async fn asset_transactions(
+        &self,
+        id: u64,
+        query: &QueryAssetTransaction,
+    ) -> Result<AssetTransactionResponse, AlgonautError> {
+        self.responses
+            .call("asset_transactions", format!("{:?}", (id, query)))
+    }
+
+    async fn block(&self, round: Round) -> Result<Block, AlgonautError> {
+        self.responses.call("block", format!("{:?}", round))
+    }
+
+    async fn transactions(
+        &self,
+        query: &QueryTransaction,
+    ) -> Result<TransactionResponse, AlgonautError> {
+        self.responses.call("transactions", format!("{:?}", query))
+    }
+
+    async fn transaction_info(&self, id: &str) -> Result<TransactionInfoResponse, AlgonautError> {
+        self.responses.call("transaction_info", format!("{:?}", id))
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl AlgodService for MockAlgod {
+    async fn genesis(&self) -> Result<GenesisBlock, AlgonautError> {
+        self.responses.call("genesis", "()".to_owned())
+    }
+
+    async fn health(&self) -> Result<(), AlgonautError> {
+        self.responses.call("health", "()".to_owned())
+    }
+
+    async fn metrics(&self) -> Result<String, AlgonautError> {
+        self.responses.call("metrics", "()".to_owned())
+    }
+
+    async fn account_information(&self, address: &Address) -> Result<AlgodAccount, AlgonautError> {
+        self.responses
+            .call("account_information", format!("{:?}", address))
+    }
+
+    async fn pending_transactions_for(
+        &self,
+        address: &Address,
+        max: u64,
+    ) -> Result<PendingTransactions<SignedTransaction>, AlgonautError> {
+        self.responses
+            .call("pending_transactions_for", format!("{:?}", (address, max)))
+    }
+
+    async fn application_information(&self, id: u64) -> Result<Application, AlgonautError> {
+        self.responses
+            .call("application_information", format!("{:?}", id))
+    }
+
+    async fn asset_information(&self, id: u64) -> Result<Asset, AlgonautError> {
+        self.responses
+            .call("asset_information", format!("{:?}", id))
+    }
+
+    async fn block(&self, round: Round) -> Result<AlgodBlock, AlgonautError> {
+        self.responses.call("block", format!("{:?}", round))
+    }
+
+    async fn block_hash(&self, round: Round) -> Result<BlockHashResponse, AlgonautError> {
+        self.responses.call("block_hash", format!("{:?}", round))
+    }
+
+    async fn start_catchup(&self, catchpoint: &str) -> Result<Catchup, AlgonautError> {
+        self.responses
+            .call("start_catchup", format!("{:?}", catchpoint))
+    }
+
+    async fn abort_catchup(&self, catchpoint: &str) -> Result<Catchup, AlgonautError> {
+        self.responses
+            .call("abort_catchup", format!("{:?}", catchpoint))
+    }
+
+    async fn ledger_supply(&self) -> Result<Supply, AlgonautError> {
+        self.responses.call("ledger_supply", "()".to_owned())
+    }
+
+    async fn register_participation_keys(
+        &self,
+        address: &Address,
+        params: &KeyRegistration,
+    ) -> Result<String, AlgonautError> {
+        self.responses.call(
+            "register_participation_keys",
+            format!("{:?}", (address, params)),
+        )
+    }
+
+    async fn shutdown(&self, timeout: usize) -> Result<(), AlgonautError> {
+        self.responses.call("shutdown", format!("{:?}", timeout))
+    }
+
+    async fn status(&self) -> Result<NodeStatus, AlgonautError> {
+        self.responses.call("status", "()".to_owned())
+    }
+
+    async fn status_after_round(&self, round: Round) -> Result<NodeStatus, AlgonautError> {
+        self.responses
+            .call("status_after_round", format!("{:?}", round))
+    }
+
+    async fn compile_teal(&self, teal: &[u8]) -> Result<CompiledTeal, AlgonautError> {
+        self.responses.call("compile_teal", format!("{:?}", teal))
+    }
+
+    async fn dryrun_teal(&self, req: &DryrunRequest) -> Result<DryrunResponse, AlgonautError> {
+        self.responses.call("dryrun_teal", format!("{:?}", req))
+    }
+
+    async fn broadcast_signed_transaction(
+        &self,
+        txn: &SignedTransaction,
+    ) -> Result<AlgodTransactionResponse, AlgonautError> {
+        self.responses
+            .call("broadcast_signed_transaction", format!("{:?}", txn))
+    }
+
+    async fn broadcast_signed_transactions(
+        &self,
+        txns: &[SignedTransaction],
+    ) -> Result<AlgodTransactionResponse, AlgonautError> {
+        self.responses
+            .call("broadcast_signed_transactions", format!("{:?}", txns))
+    }
+
+    async fn broadcast_raw_transaction(
+        &self,
+        rawtxn: &[u8],
+    ) -> Result<AlgodTransactionResponse, AlgonautError> {
+        self.responses
+            .call("broadcast_raw_transaction", format!("{:?}", rawtxn))
+    }
+
+    async fn transaction_params(&self) -> Result<TransactionParams, AlgonautError> {
+        self.responses.call("transaction_params", "()".to_owned())
+    }
+
+    async fn suggested_transaction_params(
+        &self,
+    ) -> Result<SuggestedTransactionParams, AlgonautError> {
+        self.responses
+            .call("suggested_transaction_params", "()".to_owned())
+    }
+
+    async fn pending_transactions(
+        &self,
+        max: u64,
+    ) -> Result<PendingTransactions<SignedTransaction>, AlgonautError> {
+        self.responses
+            .call("pending_transactions", format!("{:?}", max))
+    }
+
+    async fn pending_transaction_with_id(
+        &self,
+        txid: &str,
+    ) -> Result<PendingTransaction, AlgonautError> {
+        self.responses
+            .call("pending_transaction_with_id", format!("{:?}", txid))
+    }
+
+    async fn versions(&self) -> Result<Version, AlgonautError> {
+        self.responses.call("versions", "()".to_owned())
+    }
+
+    async fn api_spec(&self) -> Result<ApiSpec, AlgonautError> {
+        self.responses.call("api_spec", "()".to_owned())
+    }
+}
+
+/// Name of the kmd wallet holding the funded accounts of a localnet (algokit or sandbox).
+pub const LOCALNET_WALLET: &str = "unencrypted-default-wallet";
+
+/// Interval between the polls of the indexer in [wait_for_indexer].
+#[cfg(all(not(target_arch = "wasm32"), feature = "tokio"))]
+const INDEXER_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Error of the helpers to test against a node.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum LocalnetError {
+    #[error("{0}")]
+    Algonaut(#[from] AlgonautError),
+    /// A transaction couldn't be built or signed.
+    #[error("Transaction error: {0}")]
+    Transaction(String),
+    /// A transaction was rejected by the node: `error` is the reason parsed from the `message`
+    /// (the pool error of the transaction).
+    #[error("Transaction {tx_id} was rejected: {message}")]
+    Rejected {
+        tx_id: String,
+        error: AlgodSemanticError,
+        message: String,
+    },
+    /// A transaction wasn't confirmed in its validity window.
+    #[error("Transaction {tx_id} wasn't confirmed")]
+    NotConfirmed { tx_id: String },
+    /// The indexer hadn't reached the round when the wait timed out.
+    #[error("Timed out waiting for the indexer (at round {}) to reach round {}", indexer_round.0, round.0)]
+    IndexerBehind { round: Round, indexer_round: Round },
+    /// The wallet doesn't exist or has no funded account.
+    #[error("No funded account in wallet {wallet:?}")]
+    NoDispenser { wallet: String },
+}
+
+impl From<TransactionError> for LocalnetError {
+    fn from(error: TransactionError) -> Self {
+        LocalnetError::Transaction(error.to_string())
+    }
+}
+
+/// Sends a transaction, or a group, and waits for the confirmation of its transactions.
+///
+/// Returns the confirmed transactions, in the order of `txns`.
+pub async fn send(
+    algod: &impl AlgodService,
+    txns: &[SignedTransaction],
+) -> Result<Vec<PendingTransaction>, LocalnetError> {
+    match txns {
+        [] => return Ok(vec![]),
+        [txn] => algod.broadcast_signed_transaction(txn).await?,
+        _ => algod.broadcast_signed_transactions(txns).await?,
+    };
+    let mut confirmed = Vec::with_capacity(txns.len());
+    for txn in txns {
+        confirmed.push(wait_for_confirmation(algod, txn).await?);
+    }
+    Ok(confirmed)
+}
+
+/// Waits for the confirmation of a sent transaction, until the end of its validity window.
+pub async fn wait_for_confirmation(
+    algod: &impl AlgodService,
+    txn: &SignedTransaction,
+) -> Result<PendingTransaction, LocalnetError> {
+    let tx_id = txn.transaction.id()?;
+    let mut round = txn.transaction.first_valid;
+    loop {
+        let pending = algod.pending_transaction_with_id(&tx_id).await?;
+        if pending.confirmed_round.is_some() {
+            return Ok(pending);
+        }
+        if !pending.pool_error.is_empty() {
+            return Err(LocalnetError::Rejected {
+                tx_id,
+                error: AlgodSemanticError::parse(&pending.pool_error),
+                message: pending.pool_error,
+            });
+        }
+        if round > txn.transaction.last_valid {
+            return Err(LocalnetError::NotConfirmed { tx_id });
+        }
+        round = Round(algod.status_after_round(round).await?.last_round);
+    }
+}
+
+/// Waits for the indexer to reach `round`, e.g. the confirmation round of the transactions to
+/// look up, for at most `timeout`.
+///
+/// Requires the `tokio` feature (a default one).
+#[cfg(all(not(target_arch = "wasm32"), feature = "tokio"))]
+pub async fn wait_for_indexer(
+    indexer: &impl IndexerService,
+    round: Round,
+    timeout: Duration,
+) -> Result<(), LocalnetError> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let indexer_round = indexer.health_check().await?.round;
+        if indexer_round >= round {
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            return Err(LocalnetError::IndexerBehind {
+                round,
+                indexer_round,
+            });
+        }
+        tokio::time::sleep(INDEXER_POLL_INTERVAL).await;
+    }
+}
+
+/// Pays `amount` from `dispenser` to `receiver`, and waits for the confirmation.
+pub async fn fund(
+    algod: &impl AlgodService,
+    dispenser: &Account,
+    receiver: &Address,
+    amount: MicroAlgos,
+) -> Result<PendingTransaction, LocalnetError> {
+    let params = algod.suggested_transaction_params().await?;
+    let txn = TxnBuilder::with(
+        &params,
+        Pay::new(dispenser.address(), *receiver, amount).build(),
+    )
+    .build()?;
+    let signed = dispenser.sign_transaction(txn)?;
+    Ok(send(algod, &[signed]).await?.remove(0))
+}
+
+/// Generates an account and funds it with `amount` from `dispenser`.
+pub async fn funded_account(
+    algod: &impl AlgodService,
+    dispenser: &Account,
+    amount: MicroAlgos,
+) -> Result<Account, LocalnetError> {
+    let account = Account::generate();
+    fund(algod, dispenser, &account.address(), amount).await?;
+    Ok(account)
+}
+
+/// The account of the localnet wallet ([LOCALNET_WALLET]) with the most algos, to fund the
+/// accounts of the tests.
+pub async fn localnet_dispenser(
+    kmd: &Kmd,
+    algod: &impl AlgodService,
+) -> Result<Account, LocalnetError> {
+    let no_dispenser = || LocalnetError::NoDispenser {
+        wallet: LOCALNET_WALLET.to_owned(),
+    };
+    let wallet = kmd
+        .list_wallets()
+        .await?
+        .wallets
+        .into_iter()
+        .find(|wallet| wallet.name == LOCALNET_WALLET)
+        .ok_or_else(no_dispenser)?;
+    let handle = kmd
+        .init_wallet_handle(&wallet.id, "")
+        .await?
+        .wallet_handle_token;
+    let key = async {
+        let addresses = kmd
+            .list_keys(&handle)
+            .await?
+            .addresses
+            .iter()
+            .filter_map(|address| address.parse().ok())
+            .collect::<Vec<Address>>();
+        let address = richest(algod, &addresses).await?.ok_or_else(no_dispenser)?;
+        Ok::<_, LocalnetError>(kmd.export_key(&handle, "", &address).await?)
+    }
+    .await;
+    // released whether the key could be exported or not
+    let released = kmd.release_wallet_handle(&handle).await;
+    let key = key?;
+    released?;
+
+    let mut seed = [0; 32];
+    seed.copy_from_slice(&key.private_key[..32]);
+    Ok(Account::from_seed(seed))
+}
+
+/// The funded address with the most algos, if any.
+async fn richest(
+    algod: &impl AlgodService,
+    addresses: &[Address],
+) -> Result<Option<Address>, AlgonautError> {
+    let mut richest: Option<(MicroAlgos, Address)> = None;
+    for address in addresses {
+        let amount = algod.account_information(address).await?.amount;
+        if amount > MicroAlgos(0) && richest.is_none_or(|(max, _)| amount > max) {
+            richest = Some((amount, *address));
+        }
+    }
+    Ok(richest.map(|(_, address)| address))
+}
+
+/// A name starting with (at most half of) `prefix` and unique across the calls and the runs, of
+/// `max_len` bytes, e.g. `unique_name("T", 8)` for the unit name of an asset.
+///
+/// The names of a run are unique as long as there are fewer than 36^n of them, for the n
+/// characters after the prefix, and the ones of different runs very likely.
+///
+/// The prefix should be ASCII.
+pub fn unique_name(prefix: &str, max_len: usize) -> String {
+    const DIGITS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    static START: OnceLock<u64> = OnceLock::new();
+
+    let mut name: String = prefix.chars().take(max_len / 2).collect();
+    // a counter starting at the time of the first call: consecutive in a run, so distinct in
+    // the kept digits, and starting elsewhere in the other runs. The fastest changing digits
+    // first, to be kept by the truncation.
+    let start = *START.get_or_init(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_nanos() as u64)
+            .unwrap_or_default()
+    });
+    let mut n = start.wrapping_add(COUNTER.fetch_add(1, Ordering::Relaxed));
+    while name.len() < max_len {
+        name.push(DIGITS[(n % 36) as usize] as char);
+        n /= 36;
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indexer::v2::paginate;
+    use algonaut_client::test_server::{TestResponse, TestServer};
+    use algonaut_crypto::HashDigest;
+    use algonaut_model::indexer::v2::MiniAssetHolding;
+    use futures_util::TryStreamExt;
+
+    /// Example of code using a service: the total amount of an asset held by its holders.
+    async fn total_held(indexer: &impl IndexerService, id: u64) -> Result<u64, AlgonautError> {
+        paginate(QueryBalances::default(), |query| async move {
+            indexer.asset_balances(id, &query).await
+        })
+        .try_fold(
+            0,
+            |total, holding| async move { Ok(total + holding.amount) },
+        )
+        .await
+    }
+
+    fn balances(amounts: &[u64], next_token: Option<&str>) -> BalancesResponse {
+        BalancesResponse {
+            balances: amounts
+                .iter()
+                .map(|amount| MiniAssetHolding {
+                    address: "47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU"
+                        .to_owned(),
+                    amount: *amount,
+                    deleted: Some(false),
+                    is_frozen: false,
+                    opted_in_at_round: Some(Round(1)),
+                    opted_out_at_round: None,
+                })
+                .collect(),
+            current_round: 10,
+            next_token: next_token.map(|t| t.to_owned()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mock_indexer_responds_by_arguments() {
+        let indexer = MockIndexer::new();
+        let first_page = QueryBalances::default();
+        let second_page = QueryBalances {
+            next: Some("page2".to_owned()),
+            ..QueryBalances::default()
+        };
+        indexer.respond_to(
+            "asset_balances",
+            (1, &first_page),
+            Ok(balances(&[1, 2], Some("page2"))),
+        );
+        indexer.respond_to(
+            "asset_balances",
+            (1, &second_page),
+            Ok(balances(&[3], None)),
+        );
+
+        assert_eq!(total_held(&indexer, 1).await, Ok(6));
+        assert_eq!(
+            indexer.calls(),
+            vec![
+                MockCall {
+                    method: "asset_balances",
+                    args: format!("{:?}", (1, &first_page)),
+                },
+                MockCall {
+                    method: "asset_balances",
+                    args: format!("{:?}", (1, &second_page)),
+                },
+            ]
+        );
+
+        // not set up for this asset
+        assert!(matches!(
+            total_held(&indexer, 2).await,
+            Err(AlgonautError::Internal(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_mock_indexer_through_pointers() {
+        let indexer = MockIndexer::new();
+        indexer.respond("asset_balances", Ok(balances(&[5], None)));
+        let error = AlgonautError::IncompleteResponse { url: None };
+        indexer.respond_to(
+            "asset_balances",
+            (2, QueryBalances::default()),
+            Err::<BalancesResponse, _>(error.clone()),
+        );
+
+        let shared: Arc<dyn IndexerService> = Arc::new(indexer);
+        assert_eq!(total_held(&shared, 1).await, Ok(5));
+        assert_eq!(total_held(&&shared, 2).await, Err(error));
+    }
+
+    #[tokio::test]
+    async fn test_mock_algod() {
+        let algod = MockAlgod::new();
+        let supply = Supply {
+            current_round: Round(10),
+            online_money: 1,
+            total_money: 2,
+        };
+        algod.respond("ledger_supply", Ok(supply.clone()));
+        // response of the wrong type
+        algod.respond("versions", Ok(supply.clone()));
+
+        let service: &dyn AlgodService = &algod;
+        assert_eq!(
+            service.ledger_supply().await.map(|s| s.total_money),
+            Ok(supply.total_money)
+        );
+        assert!(matches!(
+            service.versions().await,
+            Err(AlgonautError::Internal(_))
+        ));
+        assert!(matches!(
+            service.status().await,
+            Err(AlgonautError::Internal(_))
+        ));
+        assert_eq!(
+            algod
+                .calls()
+                .iter()
+                .map(|c| (c.method, c.args.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("ledger_supply", "()"),
+                ("versions", "()"),
+                ("status", "()")
+            ]
+        );
+    }
+
+    /// An algod answering the sending of transactions: `pending` is the state of the transactions.
+    fn sending_algod(pending: serde_json::Value) -> MockAlgod {
+        let algod = MockAlgod::new();
+        algod.respond(
+            "suggested_transaction_params",
+            Ok(SuggestedTransactionParams {
+                genesis_id: "testnet-v1.0".to_owned(),
+                genesis_hash: HashDigest([0; 32]),
+                consensus_version: "v1".to_owned(),
+                fee_per_byte: MicroAlgos(0),
+                min_fee: MicroAlgos(1000),
+                first_valid: Round(100),
+                last_valid: Round(1100),
+            }),
+        );
+        algod.respond(
+            "broadcast_signed_transaction",
+            Ok(AlgodTransactionResponse {
+                tx_id: "TX".to_owned(),
+            }),
+        );
+        let pending: PendingTransaction = serde_json::from_value(pending).unwrap();
+        algod.respond("pending_transaction_with_id", Ok(pending));
+        algod
+    }
+
+    #[tokio::test]
+    async fn test_fund() {
+        let dispenser = Account::generate();
+        let receiver = Account::generate().address();
+        let algod = sending_algod(serde_json::json!({
+            "confirmed-round": 101,
+            "pool-error": "",
+            "txn": {},
+        }));
+
+        let confirmed = fund(&algod, &dispenser, &receiver, MicroAlgos(1_000_000)).await;
+
+        assert_eq!(confirmed.map(|t| t.confirmed_round), Ok(Some(101)));
+        let calls = algod.calls();
+        let broadcast = calls
+            .iter()
+            .find(|c| c.method == "broadcast_signed_transaction")
+            .unwrap();
+        assert!(broadcast.args.contains(&format!("{:?}", receiver)));
+        assert!(broadcast.args.contains("MicroAlgos(1000000)"));
+    }
+
+    #[tokio::test]
+    async fn test_send_fails_on_rejection() {
+        let dispenser = Account::generate();
+        let pool_error = "transaction NQKSCMZPPYRCAP3FHN6J4FKXKDJ7ZKCZW5FMUG6NHLOPJSOWO4TQ: overspend (account 47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU, data {_struct:{} Status:Offline MicroAlgos:{Raw:100000}}, tried to spend {1001000})";
+        let algod = sending_algod(serde_json::json!({
+            "pool-error": pool_error,
+            "txn": {},
+        }));
+
+        let res = fund(&algod, &dispenser, &dispenser.address(), MicroAlgos(1)).await;
+
+        match res {
+            Err(LocalnetError::Rejected { error, message, .. }) => {
+                assert_eq!(
+                    AlgodSemanticError::Overspend {
+                        needed: MicroAlgos(1_001_000),
+                        available: MicroAlgos(100_000)
+                    },
+                    error
+                );
+                assert_eq!(pool_error, message);
+            }
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_fails_past_the_validity_window() {
+        let dispenser = Account::generate();
+        let algod = sending_algod(serde_json::json!({
+            "pool-error": "",
+            "txn": {},
+        }));
+        let status: NodeStatus = serde_json::from_value(serde_json::json!({
+            "catchup-time": 0,
+            "last-round": 2000,
+            "last-version": "v1",
+            "next-version": "v1",
+            "next-version-round": 2001,
+            "next-version-supported": true,
+            "stopped-at-unsupported-round": false,
+            "time-since-last-round": 0,
+        }))
+        .unwrap();
+        algod.respond("status_after_round", Ok(status));
+
+        let res = fund(&algod, &dispenser, &dispenser.address(), MicroAlgos(1)).await;
+
+        assert!(matches!(res, Err(LocalnetError::NotConfirmed { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_richest_funded_address() {
+        let algod = MockAlgod::new();
+        let addresses = [
+            Account::generate().address(),
+            Account::generate().address(),
+            Account::generate().address(),
+        ];
+        for (address, amount) in addresses.iter().zip([5, 9, 0]) {
+            let account: AlgodAccount = serde_json::from_value(serde_json::json!({
+                "address": address.to_string(),
+                "amount": amount,
+                "amount-without-pending-rewards": amount,
+                "pending-rewards": 0,
+                "rewards": 0,
+                "round": 100,
+                "status": "Offline",
+            }))
+            .unwrap();
+            algod.respond_to("account_information", address, Ok(account));
+        }
+
+        assert_eq!(richest(&algod, &addresses).await, Ok(Some(addresses[1])));
+        // only unfunded accounts
+        assert_eq!(richest(&algod, &addresses[2..]).await, Ok(None));
+    }
+
+    #[cfg(all(not(target_arch = "wasm32"), feature = "tokio"))]
+    #[tokio::test]
+    async fn test_wait_for_indexer() {
+        let indexer = MockIndexer::new();
+        let health: HealthCheck<serde_json::Value> = serde_json::from_value(serde_json::json!({
+            "data": null,
+            "db-available": true,
+            "is-migrating": false,
+            "message": "",
+            "round": 5,
+            "version": "2.6.4",
+        }))
+        .unwrap();
+        indexer.respond("health_check", Ok(health));
+
+        assert_eq!(
+            wait_for_indexer(&indexer, Round(5), Duration::ZERO).await,
+            Ok(())
+        );
+        assert_eq!(
+            wait_for_indexer(&indexer, Round(6), Duration::from_millis(150)).await,
+            Err(LocalnetError::IndexerBehind {
+                round: Round(6),
+                indexer_round: Round(5),
+            })
+        );
+        // polled until the deadline
+        assert!(indexer.calls().len() >= 3);
+    }
+
+    #[tokio::test]
+    async fn test_localnet_dispenser_releases_the_handle_on_failures() {
+        let kmd_server = TestServer::serve(vec![
+            TestResponse::json(
+                serde_json::json!({
+                    "wallets": [{
+                        "driver_name": "sqlite",
+                        "driver_version": 1,
+                        "id": "wallet-id",
+                        "mnemonic_ux": false,
+                        "name": LOCALNET_WALLET,
+                        "supported_txs": [],
+                    }]
+                })
+                .to_string(),
+            ),
+            TestResponse::json(r#"{"wallet_handle_token":"handle"}"#),
+            TestResponse::json(
+                r#"{"addresses":["47YPQTIGQEO7T4Y4RWDYWEKV6RTR2UNBQXBABEEGM72ESWDQNCQ52OPASU"]}"#,
+            ),
+            TestResponse::json("{}"),
+        ]);
+        let kmd = Kmd::new(kmd_server.url(), &"a".repeat(64)).unwrap();
+        // no account information: `richest` fails
+        let algod = MockAlgod::new();
+
+        assert!(matches!(
+            localnet_dispenser(&kmd, &algod).await,
+            Err(LocalnetError::Algonaut(AlgonautError::Internal(_)))
+        ));
+        let requests = (0..4)
+            .map(|_| kmd_server.request_line())
+            .collect::<Vec<_>>();
+        assert_eq!(requests[3], "POST /v1/wallet/release HTTP/1.1");
+    }
+
+    #[test]
+    fn test_unique_name() {
+        let names = (0..1000)
+            .map(|_| unique_name("ASSET", 8))
+            .collect::<std::collections::HashSet<_>>();
+
+        assert_eq!(names.len(), 1000);
+        assert!(names.iter().all(|n| n.len() == 8 && n.starts_with("ASSE")));
+        assert!(unique_name("app", 64).starts_with("app"));
+        assert!(unique_name("app", 64).len() > 3);
+    }
+}
@@ -0,0 +1,3 @@
+//! [ARC-26](https://github.com/algorandfoundation/ARCs/blob/main/ARCs/arc-0026.md) payment URIs.
+
+pub use algonaut_transaction::url::{Note, PaymentUri, PaymentUriBuilder};
@@ -0,0 +1,310 @@
+//! Conformance of the indexer models with a live indexer: each endpoint is requested with a
+//! sample of the real data of the indexer (the latest block, the first accounts, assets,
+//! applications and transactions) and the test fails if a response can't be decoded. Each
+//! decoding of the models is covered: the MessagePack blocks, the block headers, the borrowed
+//! transactions, and the lookups, streams and scans built on the endpoints.
+//!
+//! These aren't unit tests: they depend on the data of the indexer and are ignored by default.
+//! Run them against the indexer at `ALGONAUT_IT_INDEXER_URL` with:
+//!
+//! ```sh
+//! ALGONAUT_IT_INDEXER_URL=https://mainnet-idx.algonode.cloud cargo test --test indexer_conformance -- --ignored
+//! ```
+//!
+//! Without `ALGONAUT_IT_INDEXER_URL` the tests are skipped (and pass), printing why.
+
+use algonaut::indexer::v2::{BucketSize, Indexer};
+use algonaut::ResponseFormat;
+use algonaut_core::Round;
+use algonaut_crypto::HashDigest;
+use algonaut_model::indexer::v2::{
+    MiniAssetHolding, QueryAccount, QueryAccountInfo, QueryAccountTransaction,
+    QueryApplicationInfo, QueryApplications, QueryAssetTransaction, QueryAssets, QueryAssetsInfo,
+    QueryBalances, QueryTransaction, TransactionType,
+};
+use data_encoding::BASE64;
+use futures_util::{StreamExt, TryStreamExt};
+use std::convert::TryInto;
+use std::env;
+use std::error::Error;
+
+/// The variable with the url of the indexer to check.
+const INDEXER_URL_VAR: &str = "ALGONAUT_IT_INDEXER_URL";
+
+/// Number of resources of each kind requested by the searches and looked up one by one.
+const SAMPLE: u64 = 5;
+
+/// The indexer at `ALGONAUT_IT_INDEXER_URL`, `None` (printing that the test is skipped) if the
+/// variable isn't set.
+fn conformance_indexer(test: &str) -> Result<Option<Indexer>, Box<dyn Error>> {
+    match env::var(INDEXER_URL_VAR) {
+        Ok(url) => Ok(Some(Indexer::new(&url)?)),
+        Err(env::VarError::NotPresent) => {
+            eprintln!("{}: skipped, {} is not set", test, INDEXER_URL_VAR);
+            Ok(None)
+        }
+        Err(e) => Err(format!("{}: {}", INDEXER_URL_VAR, e).into()),
+    }
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_health_and_blocks() -> Result<(), Box<dyn Error>> {
+    let indexer = match conformance_indexer("test_health_and_blocks")? {
+        Some(indexer) => indexer,
+        None => return Ok(()),
+    };
+
+    indexer.health().await?;
+    let round = indexer.health_check().await?.round;
+    indexer.network().await?;
+    let block = indexer.block(round).await?;
+    indexer.block(Round(0)).await?;
+    // the header of a block not fetched yet
+    indexer.round_time(Round(round.0 - 1)).await?;
+    indexer.suggested_params().await?;
+
+    // the MessagePack encoding of the block decodes to the same block
+    let msgpack_block = indexer
+        .block_with_format(round, ResponseFormat::MessagePack)
+        .await?;
+    assert_eq!(block.genesis_hash, msgpack_block.genesis_hash);
+    assert_eq!(block.previous_block_hash, msgpack_block.previous_block_hash);
+    assert_eq!(block.timestamp, msgpack_block.timestamp);
+    assert_eq!(
+        block.transactions.iter().map(|t| &t.id).collect::<Vec<_>>(),
+        msgpack_block
+            .transactions
+            .iter()
+            .map(|t| &t.id)
+            .collect::<Vec<_>>()
+    );
+    indexer
+        .block_with_format(Round(0), ResponseFormat::MessagePack)
+        .await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_accounts() -> Result<(), Box<dyn Error>> {
+    let indexer = match conformance_indexer("test_accounts")? {
+        Some(indexer) => indexer,
+        None => return Ok(()),
+    };
+
+    let query = QueryAccount {
+        limit: Some(SAMPLE),
+        ..QueryAccount::default()
+    };
+    let accounts = indexer.accounts(&query).await?.accounts;
+    assert!(!accounts.is_empty(), "no accounts");
+
+    for account in accounts {
+        indexer
+            .account_info(&account.address, &QueryAccountInfo::default())
+            .await?;
+        let query = QueryAccountTransaction {
+            limit: Some(SAMPLE),
+            ..QueryAccountTransaction::default()
+        };
+        indexer
+            .account_transactions(&account.address, &query)
+            .await?;
+        let round = indexer.health_check().await?.round;
+        for (_, account_info) in indexer
+            .account_info_at_rounds(&account.address, &[round], 1)
+            .await
+        {
+            account_info?;
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_assets() -> Result<(), Box<dyn Error>> {
+    let indexer = match conformance_indexer("test_assets")? {
+        Some(indexer) => indexer,
+        None => return Ok(()),
+    };
+
+    let assets = indexer
+        .assets(&QueryAssets::default().with_limit(SAMPLE))
+        .await?
+        .assets;
+    assert!(!assets.is_empty(), "no assets");
+
+    for asset in assets {
+        indexer
+            .assets_info(asset.index, &QueryAssetsInfo::default())
+            .await?;
+        let query = QueryBalances {
+            limit: Some(SAMPLE),
+            ..QueryBalances::default()
+        };
+        indexer.asset_balances(asset.index, &query).await?;
+        let query = QueryAssetTransaction {
+            limit: Some(SAMPLE),
+            ..QueryAssetTransaction::default()
+        };
+        indexer.asset_transactions(asset.index, &query).await?;
+        let holders: Vec<MiniAssetHolding> = indexer
+            .asset_holders_above(asset.index, 0, true)
+            .take(SAMPLE as usize)
+            .try_collect()
+            .await?;
+        indexer.is_asset_distributed(asset.index).await?;
+        if let Some(holder) = holders.first() {
+            let _: Vec<_> = indexer
+                .asset_transactions_for_account(asset.index, &holder.address.parse()?, &query)
+                .take(SAMPLE as usize)
+                .try_collect()
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_applications() -> Result<(), Box<dyn Error>> {
+    let indexer = match conformance_indexer("test_applications")? {
+        Some(indexer) => indexer,
+        None => return Ok(()),
+    };
+
+    let query = QueryApplications {
+        limit: Some(SAMPLE),
+        ..QueryApplications::default()
+    };
+    let applications = indexer.applications(&query).await?.applications;
+    assert!(!applications.is_empty(), "no applications");
+
+    for application in applications {
+        indexer
+            .application_info(application.id, &QueryApplicationInfo::default())
+            .await?;
+        indexer
+            .application_params_only(application.id, &QueryApplicationInfo::default())
+            .await?;
+        if let Some(creator) = application.params.creator {
+            let applications: Vec<_> = indexer
+                .applications_by_creator(creator)
+                .take(SAMPLE as usize)
+                .try_collect()
+                .await?;
+            assert!(!applications.is_empty(), "no applications of {}", creator);
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_transactions() -> Result<(), Box<dyn Error>> {
+    let indexer = match conformance_indexer("test_transactions")? {
+        Some(indexer) => indexer,
+        None => return Ok(()),
+    };
+
+    // recent transactions, more likely to have the fields of the recent protocol versions
+    let round = indexer.health_check().await?.round;
+    let query = QueryTransaction {
+        limit: Some(SAMPLE),
+        min_round: Some(Round(round.0.saturating_sub(1000))),
+        ..QueryTransaction::default()
+    };
+    let transactions = indexer.transactions(&query).await?.transactions;
+    assert!(!transactions.is_empty(), "no transactions");
+
+    for transaction in &transactions {
+        indexer.transaction_info(&transaction.id).await?;
+    }
+    let ids: Vec<&str> = transactions.iter().map(|t| t.id.as_str()).collect();
+    for transaction_info in indexer.transactions_by_ids(&ids, 2).await? {
+        transaction_info?;
+    }
+    if let Some(transaction) = transactions.iter().find(|t| t.group.is_some()) {
+        let group: [u8; 32] = BASE64
+            .decode(transaction.group.as_deref().unwrap_or_default().as_bytes())?
+            .as_slice()
+            .try_into()?;
+        let group = indexer
+            .group_transactions(&HashDigest(group), transaction.confirmed_round)
+            .await?;
+        assert!(group.iter().any(|t| t.id == transaction.id));
+    }
+
+    // the borrowed decoding of the same page
+    let mut buf = String::new();
+    let page = indexer.transactions_borrowed(&query, &mut buf).await?;
+    assert_eq!(
+        ids,
+        page.transactions
+            .iter()
+            .map(|t| t.id.as_ref())
+            .collect::<Vec<_>>()
+    );
+
+    let recent: Vec<_> = indexer
+        .transactions_recent(&QueryTransaction::default())
+        .take(SAMPLE as usize)
+        .try_collect()
+        .await?;
+    assert!(!recent.is_empty(), "no recent transactions");
+    let from = Round(round.0.saturating_sub(10));
+    indexer
+        .transaction_volume(&QueryTransaction::default(), BucketSize::Hour, from, round)
+        .await?;
+    let mut ndjson = vec![];
+    let query = QueryTransaction {
+        limit: Some(SAMPLE),
+        round: Some(round.0 - 1),
+        ..QueryTransaction::default()
+    };
+    indexer
+        .export_transactions_ndjson(&query, &mut ndjson)
+        .await?;
+
+    // and the oldest ones
+    let query = QueryTransaction {
+        limit: Some(SAMPLE),
+        ..QueryTransaction::default()
+    };
+    indexer.transactions(&query).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_state_proof_and_heartbeat_transactions() -> Result<(), Box<dyn Error>> {
+    let indexer = match conformance_indexer("test_state_proof_and_heartbeat_transactions")? {
+        Some(indexer) => indexer,
+        None => return Ok(()),
+    };
+
+    // The unit tests of these transactions use fixtures built for the tests: check the real ones
+    for tx_type in [TransactionType::StateProof, TransactionType::Heartbeat] {
+        let query = QueryTransaction {
+            limit: Some(SAMPLE),
+            tx_type: Some(tx_type.clone()),
+            ..QueryTransaction::default()
+        };
+        for transaction in indexer.transactions(&query).await?.transactions {
+            let has_fields = match tx_type {
+                TransactionType::StateProof => transaction.state_proof_transaction.is_some(),
+                _ => transaction.heartbeat_transaction.is_some(),
+            };
+            assert!(has_fields, "{} has no {:?} fields", transaction.id, tx_type);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,751 @@
+//! End-to-end tests against a localnet (algokit or sandbox), exercising the lifecycle of the main
+//! kinds of transactions and checking the results with algod and the indexer.
+//!
+//! Requires the `localnet` feature and the environment of [EnvConfig::from_env], e.g.:
+//!
+//! ```sh
+//! algokit localnet start
+//! ALGOD_ENV=localnet cargo test --features localnet --test localnet
+//! ```
+//!
+//! The accounts are funded from the localnet wallet, and the names of the assets are unique, so
+//! the tests can run repeatedly against the same localnet.
+
+use algonaut::algod::v2::Algod;
+use algonaut::config::EnvConfig;
+use algonaut::core::{MicroAlgos, MultisigAddress};
+use algonaut::deploy::{
+    app_address, AppDeployMetadata, AppDeployer, AppSpec, DeployAction, DeployError, OnSchemaBreak,
+    OnUpdate,
+};
+use algonaut::indexer::v2::Indexer;
+use algonaut::model::indexer::v2::{QueryAccountInfo, QueryApplicationInfo, QueryAssetsInfo};
+use algonaut::testing::{
+    fund, funded_account, localnet_dispenser, send, unique_name, wait_for_indexer,
+};
+use algonaut::transaction::account::Account;
+use algonaut::transaction::builder::{
+    CallApplication, ClawbackAsset, DeleteApplication, FreezeAsset, UpdateApplication,
+};
+use algonaut::transaction::contract_account::ContractAccount;
+use algonaut::transaction::transaction::{BoxReference, StateSchema, TransactionSignature};
+use algonaut::transaction::tx_group::TxGroup;
+use algonaut::transaction::{
+    AcceptAsset, CreateApplication, CreateAsset, DestroyAsset, Pay, SignedTransaction,
+    TransferAsset, TxnBuilder,
+};
+use algonaut_core::Round;
+use std::error::Error;
+use std::time::Duration;
+
+/// How long the indexer may take to catch up with algod.
+const INDEXER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Algos of the accounts of the tests.
+const FUNDING: MicroAlgos = MicroAlgos(10_000_000);
+
+struct Localnet {
+    algod: Algod,
+    indexer: Indexer,
+    dispenser: Account,
+}
+
+async fn localnet() -> Result<Localnet, Box<dyn Error>> {
+    let config = EnvConfig::from_env()?;
+    let algod = config.create_algod()?;
+    let kmd = config.create_kmd()?;
+    let dispenser = localnet_dispenser(&kmd, &algod).await?;
+    Ok(Localnet {
+        algod,
+        indexer: config.create_indexer()?,
+        dispenser,
+    })
+}
+
+/// Waits for the indexer to have the round of a confirmed transaction.
+async fn wait_for_round(
+    indexer: &Indexer,
+    confirmed_round: Option<u64>,
+) -> Result<(), Box<dyn Error>> {
+    let round = Round(confirmed_round.ok_or("transaction not confirmed")?);
+    Ok(wait_for_indexer(indexer, round, INDEXER_TIMEOUT).await?)
+}
+
+#[tokio::test]
+async fn test_asset_lifecycle() -> Result<(), Box<dyn Error>> {
+    let Localnet {
+        algod,
+        indexer,
+        dispenser,
+    } = localnet().await?;
+    let creator = funded_account(&algod, &dispenser, FUNDING).await?;
+    let holder = funded_account(&algod, &dispenser, FUNDING).await?;
+    let unit_name = unique_name("T", 8);
+
+    let params = algod.suggested_transaction_params().await?;
+    let create = TxnBuilder::with(
+        &params,
+        CreateAsset::new(creator.address(), 1_000, 0, false)
+            .unit_name(unit_name.clone())
+            .asset_name(unique_name("Test asset ", 32))
+            .manager(creator.address())
+            .build(),
+    )
+    .build()?;
+    let created = send(&algod, &[creator.sign_transaction(create)?]).await?;
+    let asset_id = created[0].asset_index.ok_or("no asset created")?;
+
+    let opt_in = TxnBuilder::with(
+        &params,
+        AcceptAsset::new(holder.address(), asset_id).build(),
+    )
+    .build()?;
+    let transfer = TxnBuilder::with(
+        &params,
+        TransferAsset::new(creator.address(), asset_id, 400, holder.address()).build(),
+    )
+    .build()?;
+    send(&algod, &[holder.sign_transaction(opt_in)?]).await?;
+    let transferred = send(&algod, &[creator.sign_transaction(transfer)?]).await?;
+
+    let holding = algod
+        .account_information(&holder.address())
+        .await?
+        .assets
+        .into_iter()
+        .find(|holding| holding.asset_id == asset_id)
+        .ok_or("no holding")?;
+    assert_eq!(holding.amount, 400);
+
+    wait_for_round(&indexer, transferred[0].confirmed_round).await?;
+    let asset = indexer
+        .assets_info(asset_id, &QueryAssetsInfo::default())
+        .await?
+        .asset;
+    assert_eq!(asset.params.unit_name, Some(unit_name));
+    assert_eq!(asset.params.creator, creator.address());
+
+    // the asset can only be destroyed once its units are back to the creator
+    let close = TxnBuilder::with(
+        &params,
+        TransferAsset::new(holder.address(), asset_id, 400, creator.address()).build(),
+    )
+    .build()?;
+    let destroy = TxnBuilder::with(
+        &params,
+        DestroyAsset::new(creator.address(), asset_id).build(),
+    )
+    .build()?;
+    send(&algod, &[holder.sign_transaction(close)?]).await?;
+    send(&algod, &[creator.sign_transaction(destroy)?]).await?;
+    assert!(algod.asset_information(asset_id).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_asset_freeze_and_clawback() -> Result<(), Box<dyn Error>> {
+    let Localnet {
+        algod,
+        indexer,
+        dispenser,
+    } = localnet().await?;
+    let creator = funded_account(&algod, &dispenser, FUNDING).await?;
+    let holder = funded_account(&algod, &dispenser, FUNDING).await?;
+
+    let params = algod.suggested_transaction_params().await?;
+    let create = TxnBuilder::with(
+        &params,
+        CreateAsset::new(creator.address(), 1_000, 0, false)
+            .unit_name(unique_name("F", 8))
+            .freeze(creator.address())
+            .clawback(creator.address())
+            .build(),
+    )
+    .build()?;
+    let created = send(&algod, &[creator.sign_transaction(create)?]).await?;
+    let asset_id = created[0].asset_index.ok_or("no asset created")?;
+    let opt_in = TxnBuilder::with(
+        &params,
+        AcceptAsset::new(holder.address(), asset_id).build(),
+    )
+    .build()?;
+    let transfer = TxnBuilder::with(
+        &params,
+        TransferAsset::new(creator.address(), asset_id, 400, holder.address()).build(),
+    )
+    .build()?;
+    send(&algod, &[holder.sign_transaction(opt_in)?]).await?;
+    send(&algod, &[creator.sign_transaction(transfer)?]).await?;
+
+    let freeze = TxnBuilder::with(
+        &params,
+        FreezeAsset::new(creator.address(), holder.address(), asset_id, true).build(),
+    )
+    .build()?;
+    let frozen = send(&algod, &[creator.sign_transaction(freeze)?]).await?;
+    let holding = |account: algonaut::model::algod::v2::Account| {
+        account
+            .assets
+            .into_iter()
+            .find(|holding| holding.asset_id == asset_id)
+            .ok_or("no holding")
+    };
+    assert!(holding(algod.account_information(&holder.address()).await?)?.is_frozen);
+
+    // a frozen holder can't transfer its units
+    let transfer_back = TxnBuilder::with(
+        &params,
+        TransferAsset::new(holder.address(), asset_id, 100, creator.address()).build(),
+    )
+    .build()?;
+    assert!(send(&algod, &[holder.sign_transaction(transfer_back)?])
+        .await
+        .is_err());
+
+    wait_for_round(&indexer, frozen[0].confirmed_round).await?;
+    let indexed = indexer
+        .account_info(&holder.address(), &QueryAccountInfo::default())
+        .await?
+        .account
+        .assets
+        .into_iter()
+        .find(|holding| holding.asset_id == asset_id)
+        .ok_or("holding not indexed")?;
+    assert!(indexed.is_frozen);
+
+    // but the clawback can take them back
+    let clawback = TxnBuilder::with(
+        &params,
+        ClawbackAsset::new(
+            creator.address(),
+            asset_id,
+            400,
+            holder.address(),
+            creator.address(),
+        )
+        .build(),
+    )
+    .build()?;
+    let clawed_back = send(&algod, &[creator.sign_transaction(clawback)?]).await?;
+    assert_eq!(
+        holding(algod.account_information(&holder.address()).await?)?.amount,
+        0
+    );
+
+    wait_for_round(&indexer, clawed_back[0].confirmed_round).await?;
+    let asset = indexer
+        .assets_info(asset_id, &QueryAssetsInfo::default())
+        .await?
+        .asset;
+    assert_eq!(asset.params.freeze, Some(creator.address()));
+    assert_eq!(asset.params.clawback, Some(creator.address()));
+    let indexed = indexer
+        .account_info(&holder.address(), &QueryAccountInfo::default())
+        .await?
+        .account
+        .assets
+        .into_iter()
+        .find(|holding| holding.asset_id == asset_id)
+        .ok_or("holding not indexed")?;
+    assert_eq!(indexed.amount, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_application_lifecycle() -> Result<(), Box<dyn Error>> {
+    let Localnet {
+        algod,
+        indexer,
+        dispenser,
+    } = localnet().await?;
+    let creator = funded_account(&algod, &dispenser, FUNDING).await?;
+
+    // counts the calls in the global "calls"
+    let approval = algod
+        .compile_teal(
+            br#"
+#pragma version 6
+txn ApplicationID
+bz create
+txn OnCompletion
+int DeleteApplication
+==
+bnz done
+byte "calls"
+byte "calls"
+app_global_get
+int 1
++
+app_global_put
+b done
+create:
+byte "calls"
+int 0
+app_global_put
+done:
+int 1
+"#,
+        )
+        .await?;
+    let clear = algod.compile_teal(b"#pragma version 6\nint 1").await?;
+
+    let params = algod.suggested_transaction_params().await?;
+    let create = TxnBuilder::with(
+        &params,
+        CreateApplication::new(
+            creator.address(),
+            approval,
+            clear,
+            StateSchema {
+                number_ints: 1,
+                number_byteslices: 0,
+            },
+            StateSchema {
+                number_ints: 0,
+                number_byteslices: 0,
+            },
+        )
+        .build(),
+    )
+    .build()?;
+    let created = send(&algod, &[creator.sign_transaction(create)?]).await?;
+    let app_id = created[0]
+        .application_index
+        .ok_or("no application created")?;
+
+    let call = TxnBuilder::with(
+        &params,
+        CallApplication::new(creator.address(), app_id).build(),
+    )
+    .build()?;
+    let called = send(&algod, &[creator.sign_transaction(call)?]).await?;
+
+    let global_state = algod
+        .application_information(app_id)
+        .await?
+        .params
+        .global_state;
+    assert_eq!(global_state.len(), 1);
+    assert_eq!(global_state[0].value.uint, 1);
+
+    wait_for_round(&indexer, called[0].confirmed_round).await?;
+    let app = indexer
+        .application_info(app_id, &QueryApplicationInfo::default())
+        .await?
+        .application
+        .ok_or("application not indexed")?;
+    assert_eq!(app.id, app_id);
+
+    let delete = TxnBuilder::with(
+        &params,
+        DeleteApplication::new(creator.address(), app_id).build(),
+    )
+    .build()?;
+    send(&algod, &[creator.sign_transaction(delete)?]).await?;
+    assert!(algod.application_information(app_id).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_multisig_payment() -> Result<(), Box<dyn Error>> {
+    let Localnet {
+        algod,
+        indexer,
+        dispenser,
+    } = localnet().await?;
+    let signers = [
+        Account::generate(),
+        Account::generate(),
+        Account::generate(),
+    ];
+    let multisig = MultisigAddress::new(
+        1,
+        2,
+        &signers.iter().map(|s| s.address()).collect::<Vec<_>>(),
+    )?;
+    fund(&algod, &dispenser, &multisig.address(), FUNDING).await?;
+    let receiver = Account::generate().address();
+
+    let params = algod.suggested_transaction_params().await?;
+    let txn = TxnBuilder::with(
+        &params,
+        Pay::new(multisig.address(), receiver, MicroAlgos(1_000_000)).build(),
+    )
+    .build()?;
+    let msig = signers[0].init_transaction_msig(&txn, &multisig)?;
+    let msig = signers[2].append_to_transaction_msig(&txn, msig)?;
+    let signed = SignedTransaction {
+        transaction_id: txn.id()?,
+        transaction: txn,
+        sig: TransactionSignature::Multi(msig),
+    };
+    let sent = send(&algod, std::slice::from_ref(&signed)).await?;
+
+    assert_eq!(
+        algod.account_information(&receiver).await?.amount,
+        MicroAlgos(1_000_000)
+    );
+    wait_for_round(&indexer, sent[0].confirmed_round).await?;
+    let indexed = indexer.transaction_info(&signed.transaction_id).await?;
+    assert_eq!(indexed.transaction.sender, multisig.address().to_string());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_logic_signature_payment() -> Result<(), Box<dyn Error>> {
+    let Localnet {
+        algod,
+        indexer: _,
+        dispenser,
+    } = localnet().await?;
+    // approves the transactions with the argument 42
+    let program = algod
+        .compile_teal(b"#pragma version 5\narg 0\nbtoi\nint 42\n==")
+        .await?;
+    let contract = ContractAccount::new(program);
+    fund(&algod, &dispenser, contract.address(), FUNDING).await?;
+    let receiver = Account::generate().address();
+
+    let params = algod.suggested_transaction_params().await?;
+    let txn = TxnBuilder::with(
+        &params,
+        Pay::new(*contract.address(), receiver, MicroAlgos(1_000_000)).build(),
+    )
+    .build()?;
+
+    let rejected = contract.sign(&txn, vec![41u64.to_be_bytes().to_vec()])?;
+    assert!(send(&algod, &[rejected]).await.is_err());
+
+    let approved = contract.sign(&txn, vec![42u64.to_be_bytes().to_vec()])?;
+    send(&algod, &[approved]).await?;
+    assert_eq!(
+        algod.account_information(&receiver).await?.amount,
+        MicroAlgos(1_000_000)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_group() -> Result<(), Box<dyn Error>> {
+    let Localnet {
+        algod,
+        indexer,
+        dispenser,
+    } = localnet().await?;
+    let first = funded_account(&algod, &dispenser, FUNDING).await?;
+    let second = funded_account(&algod, &dispenser, FUNDING).await?;
+
+    let params = algod.suggested_transaction_params().await?;
+    let mut t1 = TxnBuilder::with(
+        &params,
+        Pay::new(first.address(), second.address(), MicroAlgos(1_000)).build(),
+    )
+    .build()?;
+    let mut t2 = TxnBuilder::with(
+        &params,
+        Pay::new(second.address(), first.address(), MicroAlgos(3_000)).build(),
+    )
+    .build()?;
+    TxGroup::assign_group_id(&mut [&mut t1, &mut t2])?;
+    let signed = [first.sign_transaction(t1)?, second.sign_transaction(t2)?];
+    let sent = send(&algod, &signed).await?;
+
+    // a group is confirmed in a single round
+    assert_eq!(sent[0].confirmed_round, sent[1].confirmed_round);
+    wait_for_round(&indexer, sent[0].confirmed_round).await?;
+    let groups = [
+        indexer.transaction_info(&signed[0].transaction_id).await?,
+        indexer.transaction_info(&signed[1].transaction_id).await?,
+    ]
+    .map(|info| info.transaction.group);
+    assert!(groups[0].is_some());
+    assert_eq!(groups[0], groups[1]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_application_update_and_boxes() -> Result<(), Box<dyn Error>> {
+    let Localnet {
+        algod,
+        indexer,
+        dispenser,
+    } = localnet().await?;
+    let creator = funded_account(&algod, &dispenser, FUNDING).await?;
+
+    // sets the global "version" to 1 on creation, creates the box "box" on the calls and deletes
+    // it with the application
+    let approval = algod
+        .compile_teal(
+            br#"
+#pragma version 8
+txn ApplicationID
+bz create
+txn OnCompletion
+int DeleteApplication
+==
+bnz delete
+txn OnCompletion
+int UpdateApplication
+==
+bnz done
+byte "box"
+int 8
+box_create
+pop
+b done
+create:
+byte "version"
+int 1
+app_global_put
+b done
+delete:
+byte "box"
+box_del
+pop
+done:
+int 1
+"#,
+        )
+        .await?;
+    // sets the global "version" to 2 on the calls instead
+    let updated_approval = algod
+        .compile_teal(
+            br#"
+#pragma version 8
+txn OnCompletion
+int DeleteApplication
+==
+bnz delete
+txn OnCompletion
+int NoOp
+==
+bz done
+byte "version"
+int 2
+app_global_put
+b done
+delete:
+byte "box"
+box_del
+pop
+done:
+int 1
+"#,
+        )
+        .await?;
+    let clear = algod.compile_teal(b"#pragma version 8\nint 1").await?;
+    let the_box = || {
+        vec![BoxReference {
+            app_index: 0,
+            name: b"box".to_vec(),
+        }]
+    };
+
+    let params = algod.suggested_transaction_params().await?;
+    let create = TxnBuilder::with(
+        &params,
+        CreateApplication::new(
+            creator.address(),
+            approval,
+            clear.clone(),
+            StateSchema {
+                number_ints: 1,
+                number_byteslices: 0,
+            },
+            StateSchema {
+                number_ints: 0,
+                number_byteslices: 0,
+            },
+        )
+        .build(),
+    )
+    .build()?;
+    let created = send(&algod, &[creator.sign_transaction(create)?]).await?;
+    let app_id = created[0]
+        .application_index
+        .ok_or("no application created")?;
+    // the minimum balance of the account of the application, and of its box
+    fund(&algod, &dispenser, &app_address(app_id), FUNDING).await?;
+
+    let call = TxnBuilder::with(
+        &params,
+        CallApplication::new(creator.address(), app_id)
+            .boxes(the_box())
+            .build(),
+    )
+    .build()?;
+    let called = send(&algod, &[creator.sign_transaction(call)?]).await?;
+    let app_account = algod.account_information(&app_address(app_id)).await?;
+    assert_eq!(app_account.total_boxes, Some(1));
+    assert_eq!(app_account.total_box_bytes, Some(11));
+
+    wait_for_round(&indexer, called[0].confirmed_round).await?;
+    let indexed = indexer
+        .account_info(&app_address(app_id), &QueryAccountInfo::default())
+        .await?
+        .account;
+    assert_eq!(indexed.total_boxes, Some(1));
+
+    let update = TxnBuilder::with(
+        &params,
+        UpdateApplication::new(creator.address(), app_id, updated_approval.clone(), clear).build(),
+    )
+    .build()?;
+    send(&algod, &[creator.sign_transaction(update)?]).await?;
+    let call = TxnBuilder::with(
+        &params,
+        CallApplication::new(creator.address(), app_id).build(),
+    )
+    .build()?;
+    let called = send(&algod, &[creator.sign_transaction(call)?]).await?;
+
+    let global_state = algod
+        .application_information(app_id)
+        .await?
+        .params
+        .global_state;
+    assert_eq!(global_state.len(), 1);
+    assert_eq!(global_state[0].value.uint, 2);
+
+    wait_for_round(&indexer, called[0].confirmed_round).await?;
+    let app = indexer
+        .application_info(app_id, &QueryApplicationInfo::default())
+        .await?
+        .application
+        .ok_or("application not indexed")?;
+    assert_eq!(app.params.approval_program, updated_approval.0);
+
+    let delete = TxnBuilder::with(
+        &params,
+        DeleteApplication::new(creator.address(), app_id)
+            .boxes(the_box())
+            .build(),
+    )
+    .build()?;
+    let deleted = send(&algod, &[creator.sign_transaction(delete)?]).await?;
+    assert!(algod.application_information(app_id).await.is_err());
+    let app_account = algod.account_information(&app_address(app_id)).await?;
+    assert_eq!(app_account.total_boxes.unwrap_or_default(), 0);
+
+    wait_for_round(&indexer, deleted[0].confirmed_round).await?;
+    let indexed = indexer
+        .account_info(&app_address(app_id), &QueryAccountInfo::default())
+        .await?
+        .account;
+    assert_eq!(indexed.total_boxes.unwrap_or_default(), 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_app_deployer() -> Result<(), Box<dyn Error>> {
+    let Localnet {
+        algod,
+        indexer,
+        dispenser,
+    } = localnet().await?;
+    let creator = funded_account(&algod, &dispenser, FUNDING).await?;
+    // waits for the indexer to have the deployment transactions, to find the deployment
+    let wait_for_deployment = || async {
+        let round = Round(algod.status().await?.last_round);
+        wait_for_indexer(&indexer, round, INDEXER_TIMEOUT).await?;
+        Ok::<_, Box<dyn Error>>(())
+    };
+
+    let app = AppSpec {
+        metadata: AppDeployMetadata {
+            name: unique_name("app", 32),
+            version: "1.0".to_owned(),
+            deletable: Some(true),
+            updatable: Some(true),
+        },
+        approval_program: algod.compile_teal(b"#pragma version 6\nint 1").await?,
+        clear_state_program: algod.compile_teal(b"#pragma version 6\nint 1").await?,
+        global_state_schema: StateSchema {
+            number_ints: 0,
+            number_byteslices: 0,
+        },
+        local_state_schema: StateSchema {
+            number_ints: 0,
+            number_byteslices: 0,
+        },
+        extra_pages: 0,
+    };
+    let deployer = AppDeployer::new(&algod, &indexer, &creator)
+        .on_update(OnUpdate::UpdateApp)
+        .on_schema_break(OnSchemaBreak::ReplaceApp);
+
+    let created = deployer.deploy(&app).await?;
+    assert_eq!(DeployAction::Created, created.action);
+    wait_for_deployment().await?;
+    let unchanged = deployer.deploy(&app).await?;
+    assert_eq!(DeployAction::Unchanged, unchanged.action);
+    assert_eq!(created.app_id, unchanged.app_id);
+
+    let app = AppSpec {
+        metadata: AppDeployMetadata {
+            version: "2.0".to_owned(),
+            ..app.metadata
+        },
+        approval_program: algod
+            .compile_teal(b"#pragma version 6\nint 1\nint 1\n&&")
+            .await?,
+        ..app
+    };
+    let updated = deployer.deploy(&app).await?;
+    assert_eq!(DeployAction::Updated, updated.action);
+    assert_eq!(created.app_id, updated.app_id);
+    assert_eq!(
+        app.approval_program.0,
+        algod
+            .application_information(created.app_id)
+            .await?
+            .params
+            .approval_program
+    );
+
+    // the global schema grows
+    wait_for_deployment().await?;
+    let app = AppSpec {
+        global_state_schema: StateSchema {
+            number_ints: 1,
+            number_byteslices: 0,
+        },
+        ..app
+    };
+    let replaced = deployer.deploy(&app).await?;
+    assert_eq!(
+        DeployAction::Replaced {
+            previous_app_id: created.app_id
+        },
+        replaced.action
+    );
+    assert_ne!(created.app_id, replaced.app_id);
+    assert!(algod.application_information(created.app_id).await.is_err());
+
+    // the Algos of the replaced app would be stranded
+    fund(&algod, &dispenser, &app_address(replaced.app_id), FUNDING).await?;
+    wait_for_deployment().await?;
+    let app = AppSpec {
+        global_state_schema: StateSchema {
+            number_ints: 2,
+            number_byteslices: 0,
+        },
+        ..app
+    };
+    assert_eq!(
+        Err(DeployError::AppFunded {
+            app_id: replaced.app_id,
+            amount: FUNDING
+        }),
+        deployer.deploy(&app).await
+    );
+
+    Ok(())
+}
@@ -5,14 +5,14 @@ use std::{
     time::{Duration, Instant},
 };
 
-use algonaut::{algod::v2::Algod, error::AlgonautError};
-use algonaut_core::Address;
+use algonaut::{algod::v2::AlgodService, error::AlgonautError};
+use algonaut_core::{Address, AddressParseError};
 use algonaut_model::{algod::v2::PendingTransaction, kmd::v1::ExportKeyResponse};
 use algonaut_transaction::account::Account;
 
 /// Utility function to wait on a transaction to be confirmed
 pub async fn wait_for_pending_transaction(
-    algod: &Algod,
+    algod: &impl AlgodService,
     txid: &str,
 ) -> Result<Option<PendingTransaction>, AlgonautError> {
     let timeout = Duration::from_secs(10);
@@ -40,7 +40,10 @@ pub fn split_addresses(args_str: String) -> Result<Vec<Address>, String> {
     if args_str.is_empty() {
         return Ok(vec![]);
     }
-    args_str.split(",").map(|a| a.parse()).collect()
+    args_str
+        .split(",")
+        .map(|a| a.parse().map_err(|e: AddressParseError| e.to_string()))
+        .collect()
 }
 
 pub fn parse_app_args(args_str: String) -> Result<Vec<Vec<u8>>, Box<dyn Error>> {
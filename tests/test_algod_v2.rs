@@ -1,17 +1,27 @@
-use algonaut::algod::v2::Algod;
-use algonaut_core::Round;
+use algonaut::algod::v2::{Algod, AlgodService};
+use algonaut::teal::{Template, TemplateValue};
+use algonaut_core::{Address, Round};
 use algonaut_model::algod::v2::KeyRegistration;
 use dotenv::dotenv;
+use std::collections::HashMap;
 use std::env;
 use std::error::Error;
 use tokio::test;
 
+/// Algod, used only through [AlgodService].
+fn algod() -> Result<impl AlgodService, Box<dyn Error>> {
+    Ok(Algod::new(
+        &env::var("ALGOD_URL")?,
+        &env::var("ALGOD_TOKEN")?,
+    )?)
+}
+
 #[test]
 async fn test_genesis_endpoint() -> Result<(), Box<dyn Error>> {
     // load variables in .env
     dotenv().ok();
 
-    let algod = Algod::new(&env::var("ALGOD_URL")?, &env::var("ALGOD_TOKEN")?)?;
+    let algod = algod()?;
 
     let res = algod.genesis().await;
 
@@ -26,7 +36,7 @@ async fn test_health_endpoint() -> Result<(), Box<dyn Error>> {
     // load variables in .env
     dotenv().ok();
 
-    let algod = Algod::new(&env::var("ALGOD_URL")?, &env::var("ALGOD_TOKEN")?)?;
+    let algod = algod()?;
 
     let res = algod.health().await;
 
@@ -41,7 +51,7 @@ async fn test_metrics_endpoint() -> Result<(), Box<dyn Error>> {
     // load variables in .env
     dotenv().ok();
 
-    let algod = Algod::new(&env::var("ALGOD_URL")?, &env::var("ALGOD_TOKEN")?)?;
+    let algod = algod()?;
 
     let res = algod.metrics().await;
 
@@ -56,7 +66,7 @@ async fn test_account_information_endpoint() -> Result<(), Box<dyn Error>> {
     // load variables in .env
     dotenv().ok();
 
-    let algod = Algod::new(&env::var("ALGOD_URL")?, &env::var("ALGOD_TOKEN")?)?;
+    let algod = algod()?;
 
     let res = algod
         .account_information(&"4MYUHDWHWXAKA5KA7U5PEN646VYUANBFXVJNONBK3TIMHEMWMD4UBOJBI4".parse()?)
@@ -73,7 +83,7 @@ async fn test_pending_transactions_for_endpoint() -> Result<(), Box<dyn Error>>
     // load variables in .env
     dotenv().ok();
 
-    let algod = Algod::new(&env::var("ALGOD_URL")?, &env::var("ALGOD_TOKEN")?)?;
+    let algod = algod()?;
 
     let res = algod
         .pending_transactions_for(
@@ -94,7 +104,7 @@ async fn test_application_information_endpoint() -> Result<(), Box<dyn Error>> {
     // load variables in .env
     dotenv().ok();
 
-    let algod = Algod::new(&env::var("ALGOD_URL")?, &env::var("ALGOD_TOKEN")?)?;
+    let algod = algod()?;
 
     let res = algod.application_information(0).await;
 
@@ -110,7 +120,7 @@ async fn test_asset_information_endpoint() -> Result<(), Box<dyn Error>> {
     // load variables in .env
     dotenv().ok();
 
-    let algod = Algod::new(&env::var("ALGOD_URL")?, &env::var("ALGOD_TOKEN")?)?;
+    let algod = algod()?;
 
     let res = algod.asset_information(0).await;
 
@@ -126,7 +136,7 @@ async fn test_block_endpoint() -> Result<(), Box<dyn Error>> {
     // load variables in .env
     dotenv().ok();
 
-    let algod = Algod::new(&env::var("ALGOD_URL")?, &env::var("ALGOD_TOKEN")?)?;
+    let algod = algod()?;
 
     let last_round = algod.status().await?.last_round;
     let res = algod.block(Round(last_round)).await;
@@ -143,7 +153,7 @@ async fn test_start_catchup_endpoint() -> Result<(), Box<dyn Error>> {
     // load variables in .env
     dotenv().ok();
 
-    let algod = Algod::new(&env::var("ALGOD_URL")?, &env::var("ALGOD_TOKEN")?)?;
+    let algod = algod()?;
 
     let res = algod
         .start_catchup("4420000#Q7T2RRTDIRTYESIXKAAFJYFQWG4A3WRA3JIUZVCJ3F4AQ2G2HZRA")
@@ -161,7 +171,7 @@ async fn test_abort_catchup_endpoint() -> Result<(), Box<dyn Error>> {
     // load variables in .env
     dotenv().ok();
 
-    let algod = Algod::new(&env::var("ALGOD_URL")?, &env::var("ALGOD_TOKEN")?)?;
+    let algod = algod()?;
 
     let res = algod
         .abort_catchup("4420000#Q7T2RRTDIRTYESIXKAAFJYFQWG4A3WRA3JIUZVCJ3F4AQ2G2HZRA")
@@ -178,7 +188,7 @@ async fn test_ledger_supply_endpoint() -> Result<(), Box<dyn Error>> {
     // load variables in .env
     dotenv().ok();
 
-    let algod = Algod::new(&env::var("ALGOD_URL")?, &env::var("ALGOD_TOKEN")?)?;
+    let algod = algod()?;
 
     let res = algod.ledger_supply().await;
 
@@ -194,7 +204,7 @@ async fn test_register_participation_keys_endpoint() -> Result<(), Box<dyn Error
     // load variables in .env
     dotenv().ok();
 
-    let algod = Algod::new(&env::var("ALGOD_URL")?, &env::var("ALGOD_TOKEN")?)?;
+    let algod = algod()?;
 
     let params = KeyRegistration {
         fee: None,
@@ -219,7 +229,7 @@ async fn test_shutdown_endpoint() -> Result<(), Box<dyn Error>> {
     // load variables in .env
     dotenv().ok();
 
-    let algod = Algod::new(&env::var("ALGOD_URL")?, &env::var("ALGOD_TOKEN")?)?;
+    let algod = algod()?;
 
     let res = algod.shutdown(0).await;
 
@@ -234,7 +244,7 @@ async fn test_status_endpoint() -> Result<(), Box<dyn Error>> {
     // load variables in .env
     dotenv().ok();
 
-    let algod = Algod::new(&env::var("ALGOD_URL")?, &env::var("ALGOD_TOKEN")?)?;
+    let algod = algod()?;
 
     let res = algod.status().await;
 
@@ -249,7 +259,7 @@ async fn test_status_after_round_endpoint() -> Result<(), Box<dyn Error>> {
     // load variables in .env
     dotenv().ok();
 
-    let algod = Algod::new(&env::var("ALGOD_URL")?, &env::var("ALGOD_TOKEN")?)?;
+    let algod = algod()?;
 
     let node_status = algod.status().await?;
 
@@ -268,7 +278,7 @@ async fn test_compile_teal_endpoint() -> Result<(), Box<dyn Error>> {
     // load variables in .env
     dotenv().ok();
 
-    let algod = Algod::new(&env::var("ALGOD_URL")?, &env::var("ALGOD_TOKEN")?)?;
+    let algod = algod()?;
 
     let res = algod
         .compile_teal(
@@ -294,7 +304,7 @@ async fn test_failure_compiling_teal() -> Result<(), Box<dyn Error>> {
     // load variables in .env
     dotenv().ok();
 
-    let algod = Algod::new(&env::var("ALGOD_URL")?, &env::var("ALGOD_TOKEN")?)?;
+    let algod = algod()?;
 
     let res = algod.compile_teal("not-a-teal-program".as_bytes()).await;
 
@@ -304,6 +314,85 @@ async fn test_failure_compiling_teal() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+async fn test_compile_teal_template() -> Result<(), Box<dyn Error>> {
+    // load variables in .env
+    dotenv().ok();
+
+    let algod = algod()?;
+
+    // the hash time lock contract template of go-algorand
+    // (tools/teal/templates/htlc.teal.tmpl), also shipped by the SDKs
+    let template = Template::new(
+        r#"// Implements a hash time lock contract
+// Parameters:
+//  - TMPL_RCV: the address to send funds to when the preimage is supplied
+//  - TMPL_HASHFN: the specific hash function (sha256 or keccak256) to use
+//  - TMPL_HASHIMG: the image of the hash function
+//  - TMPL_TIMEOUT: the round at which the account expires
+//  - TMPL_OWN: the address to refund funds to on timeout
+//  - TMPL_FEE: maximum fee used by any transaction spending from this account
+txn Fee
+int TMPL_FEE
+<=
+txn TypeEnum
+int 1
+==
+&&
+txn Receiver
+global ZeroAddress
+==
+&&
+txn Amount
+int 0
+==
+&&
+txn CloseRemainderTo
+addr TMPL_RCV
+==
+arg_0
+TMPL_HASHFN
+byte base64 TMPL_HASHIMG
+==
+&&
+txn CloseRemainderTo
+addr TMPL_OWN
+==
+txn FirstValid
+int TMPL_TIMEOUT
+>
+&&
+||
+&&
+"#,
+    );
+    let values = |receiver: u8| -> HashMap<&str, TemplateValue> {
+        vec![
+            ("TMPL_FEE", TemplateValue::from(1_000)),
+            ("TMPL_RCV", Address::new([receiver; 32]).into()),
+            ("TMPL_OWN", Address::new([9; 32]).into()),
+            ("TMPL_HASHFN", TemplateValue::Verbatim("sha256".to_owned())),
+            ("TMPL_HASHIMG", vec![0xab; 32].into()),
+            ("TMPL_TIMEOUT", 3_000.into()),
+        ]
+        .into_iter()
+        .collect()
+    };
+
+    let res = template.compile(&algod).await;
+    println!("{:#?}", res);
+    assert!(res.is_err());
+
+    let first = template.substitute(&values(1)).compile(&algod).await?;
+    let again = template.substitute(&values(1)).compile(&algod).await?;
+    let other = template.substitute(&values(2)).compile(&algod).await?;
+
+    assert_eq!(first.hash(), again.hash());
+    assert_ne!(first.hash(), other.hash());
+
+    Ok(())
+}
+
 #[test]
 #[ignore = "TODO"]
 async fn test_dryrun_teal_endpoint() -> Result<(), Box<dyn Error>> {
@@ -319,7 +408,7 @@ async fn test_broadcast_raw_transaction_endpoint() -> Result<(), Box<dyn Error>>
     // load variables in .env
     dotenv().ok();
 
-    let algod = Algod::new(&env::var("ALGOD_URL")?, &env::var("ALGOD_TOKEN")?)?;
+    let algod = algod()?;
 
     let res = algod.broadcast_raw_transaction(&[0; 32]).await;
 
@@ -334,7 +423,7 @@ async fn test_transaction_params_endpoint() -> Result<(), Box<dyn Error>> {
     // load variables in .env
     dotenv().ok();
 
-    let algod = Algod::new(&env::var("ALGOD_URL")?, &env::var("ALGOD_TOKEN")?)?;
+    let algod = algod()?;
 
     let res = algod.transaction_params().await;
 
@@ -349,7 +438,7 @@ async fn test_pending_transactions_endpoint() -> Result<(), Box<dyn Error>> {
     // load variables in .env
     dotenv().ok();
 
-    let algod = Algod::new(&env::var("ALGOD_URL")?, &env::var("ALGOD_TOKEN")?)?;
+    let algod = algod()?;
 
     let res = algod.pending_transactions(0).await;
 
@@ -365,7 +454,7 @@ async fn test_pending_transaction_with_id_endpoint() -> Result<(), Box<dyn Error
     // load variables in .env
     dotenv().ok();
 
-    let algod = Algod::new(&env::var("ALGOD_URL")?, &env::var("ALGOD_TOKEN")?)?;
+    let algod = algod()?;
 
     let res = algod.pending_transaction_with_id("").await;
 
@@ -380,7 +469,7 @@ async fn test_versions_endpoint() -> Result<(), Box<dyn Error>> {
     // load variables in .env
     dotenv().ok();
 
-    let algod = Algod::new(&env::var("ALGOD_URL")?, &env::var("ALGOD_TOKEN")?)?;
+    let algod = algod()?;
 
     let res = algod.versions().await;
 
@@ -1,4 +1,4 @@
-use algonaut::indexer::v2::Indexer;
+use algonaut::indexer::v2::{Indexer, IndexerService};
 use algonaut_core::Round;
 use algonaut_model::indexer::v2::{
     QueryAccount, QueryAccountInfo, QueryAccountTransaction, QueryApplicationInfo,
@@ -10,12 +10,17 @@ use std::env;
 use std::error::Error;
 use tokio::test;
 
+/// The indexer, used only through [IndexerService].
+fn indexer() -> Result<impl IndexerService, Box<dyn Error>> {
+    Ok(Indexer::new(&env::var("INDEXER_URL")?)?)
+}
+
 #[test]
 async fn test_health_endpoint() -> Result<(), Box<dyn Error>> {
     // load variables in .env
     dotenv().ok();
 
-    let indexer = Indexer::new(&env::var("INDEXER_URL")?)?;
+    let indexer = indexer()?;
 
     let res = indexer.health().await;
 
@@ -25,12 +30,27 @@ async fn test_health_endpoint() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+async fn test_network() -> Result<(), Box<dyn Error>> {
+    // load variables in .env
+    dotenv().ok();
+
+    let indexer = indexer()?;
+
+    let res = indexer.network().await;
+
+    println!("{:#?}", res);
+    assert!(res.is_ok());
+
+    Ok(())
+}
+
 #[test]
 async fn test_accounts_endpoint() -> Result<(), Box<dyn Error>> {
     // load variables in .env
     dotenv().ok();
 
-    let indexer = Indexer::new(&env::var("INDEXER_URL")?)?;
+    let indexer = indexer()?;
 
     let query = QueryAccount {
         application_id: None,
@@ -38,6 +58,7 @@ async fn test_accounts_endpoint() -> Result<(), Box<dyn Error>> {
         auth_addr: None,
         currency_greater_than: None,
         currency_less_than: None,
+        exclude: None,
         limit: Some(2),
         next: None,
         round: None,
@@ -56,7 +77,7 @@ async fn test_account_info_endpoint() -> Result<(), Box<dyn Error>> {
     // load variables in .env
     dotenv().ok();
 
-    let indexer = Indexer::new(&env::var("INDEXER_URL")?)?;
+    let indexer = indexer()?;
 
     let address = env::var("ACCOUNT")?.parse()?;
 
@@ -78,7 +99,7 @@ async fn test_account_transactions_endpoint() -> Result<(), Box<dyn Error>> {
     // load variables in .env
     dotenv().ok();
 
-    let indexer = Indexer::new(&env::var("INDEXER_URL")?)?;
+    let indexer = indexer()?;
 
     let query = QueryAccountTransaction {
         after_time: None,
@@ -113,10 +134,11 @@ async fn test_applications_endpoint() -> Result<(), Box<dyn Error>> {
     // load variables in .env
     dotenv().ok();
 
-    let indexer = Indexer::new(&env::var("INDEXER_URL")?)?;
+    let indexer = indexer()?;
 
     let query = QueryApplications {
         application_id: None,
+        creator: None,
         limit: None,
         next: None,
     };
@@ -135,7 +157,7 @@ async fn test_applications_info_endpoint() -> Result<(), Box<dyn Error>> {
     // load variables in .env
     dotenv().ok();
 
-    let indexer = Indexer::new(&env::var("INDEXER_URL")?)?;
+    let indexer = indexer()?;
 
     let query = QueryApplicationInfo { include_all: None };
 
@@ -152,11 +174,12 @@ async fn test_assets_endpoint() -> Result<(), Box<dyn Error>> {
     // load variables in .env
     dotenv().ok();
 
-    let indexer = Indexer::new(&env::var("INDEXER_URL")?)?;
+    let indexer = indexer()?;
 
     let query = QueryAssets {
         asset_id: None,
         creator: None,
+        include_all: None,
         limit: None,
         name: None,
         next: None,
@@ -177,7 +200,7 @@ async fn test_assets_info_endpoint() -> Result<(), Box<dyn Error>> {
     // load variables in .env
     dotenv().ok();
 
-    let indexer = Indexer::new(&env::var("INDEXER_URL")?)?;
+    let indexer = indexer()?;
 
     let query = QueryAssetsInfo { include_all: None };
 
@@ -194,7 +217,7 @@ async fn test_asset_balances_endpoint() -> Result<(), Box<dyn Error>> {
     // load variables in .env
     dotenv().ok();
 
-    let indexer = Indexer::new(&env::var("INDEXER_URL")?)?;
+    let indexer = indexer()?;
 
     let query = QueryBalances {
         currency_greater_than: None,
@@ -217,7 +240,7 @@ async fn test_asset_transactions_endpoint() -> Result<(), Box<dyn Error>> {
     // load variables in .env
     dotenv().ok();
 
-    let indexer = Indexer::new(&env::var("INDEXER_URL")?)?;
+    let indexer = indexer()?;
 
     let query = QueryAssetTransaction {
         address: None,
@@ -252,7 +275,7 @@ async fn test_block_endpoint() -> Result<(), Box<dyn Error>> {
     // load variables in .env
     dotenv().ok();
 
-    let indexer = Indexer::new(&env::var("INDEXER_URL")?)?;
+    let indexer = indexer()?;
 
     let res = indexer.block(Round(0)).await;
 
@@ -268,7 +291,7 @@ async fn test_transactions_endpoint() -> Result<(), Box<dyn Error>> {
     // load variables in .env
     dotenv().ok();
 
-    let indexer = Indexer::new(&env::var("INDEXER_URL")?)?;
+    let indexer = indexer()?;
 
     let query = QueryTransaction {
         address: None,
@@ -280,6 +303,7 @@ async fn test_transactions_endpoint() -> Result<(), Box<dyn Error>> {
         currency_greater_than: None,
         currency_less_than: None,
         exclude_close_to: None,
+        group_id: None,
         limit: None,
         max_round: None,
         min_round: None,
@@ -306,7 +330,7 @@ async fn test_transaction_info_endpoint() -> Result<(), Box<dyn Error>> {
     // load variables in .env
     dotenv().ok();
 
-    let indexer = Indexer::new(&env::var("INDEXER_URL")?)?;
+    let indexer = indexer()?;
 
     let res = indexer.transaction_info("123").await;
 
@@ -1,4 +1,5 @@
 use algonaut::kmd::v1::Kmd;
+use algonaut_core::Address;
 use algonaut_crypto::{Ed25519PublicKey, MasterDerivationKey};
 use dotenv::dotenv;
 use rand::{distributions::Alphanumeric, Rng};
@@ -693,3 +694,80 @@ async fn test_delete_multisig_endpoint() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[test]
+async fn test_list_keys_typed_and_has_key() -> Result<(), Box<dyn Error>> {
+    // load variables in .env
+    dotenv().ok();
+
+    let kmd = Kmd::new(&env::var("KMD_URL")?, &env::var("KMD_TOKEN")?)?;
+
+    let wallet_name: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(7)
+        .map(char::from)
+        .collect();
+
+    let wallet = kmd
+        .create_wallet(
+            wallet_name.as_ref(),
+            "testpassword",
+            "sqlite",
+            MasterDerivationKey([0; 32]),
+        )
+        .await?;
+    let handle = kmd
+        .init_wallet_handle(wallet.wallet.id.as_ref(), "testpassword")
+        .await?;
+    let handle = handle.wallet_handle_token.as_str();
+
+    let key = kmd.generate_key(handle).await?;
+    let address: Address = key.address.parse()?;
+
+    let keys = kmd.list_keys_typed(handle).await?;
+    println!("{:#?}", keys);
+    assert_eq!(vec![address], keys);
+    assert!(kmd.has_key(handle, &address).await?);
+    assert!(!kmd.has_key(handle, &Address::new([0; 32])).await?);
+
+    Ok(())
+}
+
+#[test]
+async fn test_list_multisig_typed() -> Result<(), Box<dyn Error>> {
+    // load variables in .env
+    dotenv().ok();
+
+    let kmd = Kmd::new(&env::var("KMD_URL")?, &env::var("KMD_TOKEN")?)?;
+
+    let pks = [Ed25519PublicKey([0; 32]), Ed25519PublicKey([1; 32])];
+
+    let wallet_name: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(7)
+        .map(char::from)
+        .collect();
+
+    let wallet = kmd
+        .create_wallet(
+            wallet_name.as_ref(),
+            "testpassword",
+            "sqlite",
+            MasterDerivationKey([0; 32]),
+        )
+        .await?;
+    let handle = kmd
+        .init_wallet_handle(wallet.wallet.id.as_ref(), "testpassword")
+        .await?;
+    let handle = handle.wallet_handle_token.as_str();
+
+    let imported = kmd.import_multisig(handle, 1, 1, &pks).await?;
+
+    let multisigs = kmd.list_multisig_typed(handle).await?;
+    println!("{:#?}", multisigs);
+    assert_eq!(1, multisigs.len());
+    assert_eq!(imported.address, multisigs[0].address().to_string());
+    assert_eq!(pks.to_vec(), multisigs[0].public_keys);
+
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use algonaut::algod::v2::Algod;
+use algonaut::algod::v2::{Algod, AlgodService};
 use algonaut_core::Address;
 use algonaut_transaction::account::Account;
 use algonaut_transaction::transaction::StateSchema;
@@ -9,12 +9,20 @@ use std::env;
 use std::error::Error;
 use tokio::test;
 
+/// Algod, used only through [AlgodService].
+fn algod() -> Result<impl AlgodService, Box<dyn Error>> {
+    Ok(Algod::new(
+        &env::var("ALGOD_URL")?,
+        &env::var("ALGOD_TOKEN")?,
+    )?)
+}
+
 #[test]
 async fn test_pending_transactions_endpoint() -> Result<(), Box<dyn Error>> {
     // load variables in .env
     dotenv().ok();
 
-    let algod = Algod::new(&env::var("ALGOD_URL")?, &env::var("ALGOD_TOKEN")?)?;
+    let algod = algod()?;
 
     println!("{:?}", algod.pending_transactions(0).await);
     assert!(algod.pending_transactions(0).await.is_ok());
@@ -27,7 +35,7 @@ async fn test_transaction_information_endpoint() -> Result<(), Box<dyn Error>> {
     // load variables in .env
     dotenv().ok();
 
-    let algod = Algod::new(&env::var("ALGOD_URL")?, &env::var("ALGOD_TOKEN")?)?;
+    let algod = algod()?;
 
     println!("{:?}", algod.transaction_params().await);
     assert!(algod.transaction_params().await.is_ok());
@@ -41,7 +49,7 @@ async fn test_app_call_parameters() -> Result<(), Box<dyn Error>> {
     // load variables in .env
     dotenv().ok();
 
-    let algod = Algod::new(&env::var("ALGOD_URL")?, &env::var("ALGOD_TOKEN")?)?;
+    let algod = algod()?;
 
     let sender = Account::from_mnemonic("auction inquiry lava second expand liberty glass involve ginger illness length room item discover ahead table doctor term tackle cement bonus profit right above catch")?;
     dbg!(algod.account_information(&sender.address()).await?);